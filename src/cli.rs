@@ -0,0 +1,409 @@
+//! # Command-line mode
+//!
+//! Headless actions for scripts and the Task Scheduler:
+//! `--clean-ram`, `--clean-disk [--temp --browser --thumbnails --recycle-bin]`,
+//! `--limit <exe> <mbps>`, `--unlimit-all`, `--status`, `--defender-enable`
+//! (run by the scheduled task ending a timed Defender pause) and
+//! `--uninstall-cleanup [--dry-run] [--yes]`, each accepting `--json`.
+//! When one of them is present the GUI is not started. Without `--json`, `--clean-ram`,
+//! `--clean-disk`, `--limit` and `--unlimit-all` are handed to the window already open, if
+//! any (see `ipc`), which owns the QoS policies.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::disk::{DiskCleaningOptions, DiskCleaningResults};
+use crate::memory::{CleaningResults, SystemMemoryInfo};
+use crate::network::{LimitSummary, NetworkLimiter, QosPolicyInfo};
+
+#[derive(Debug, Clone)]
+pub enum CliAction {
+    CleanRam,
+    CleanDisk(DiskCleaningOptions),
+    Limit { exe_name: String, limit_mbps: f64 },
+    UnlimitAll,
+    Status,
+    DefenderEnable,
+    /// `dry_run`: list only; `confirmed`: no question before removing (required with `--json`)
+    UninstallCleanup { dry_run: bool, confirmed: bool },
+}
+
+#[derive(Debug, Clone)]
+pub struct CliInvocation {
+    pub action: CliAction,
+    pub json: bool,
+}
+
+const USAGE: &str = "Usage: gamebooster [--clean-ram | --clean-disk [--temp] [--browser] [--thumbnails] [--recycle-bin] \
+| --limit <exe> <mbps> | --unlimit-all | --status | --defender-enable | --uninstall-cleanup [--dry-run] [--yes]] [--json]";
+
+/// Parse the command line. Returns `Ok(None)` when no action flag is present (GUI mode).
+pub fn parse_args(args: &[String]) -> Result<Option<CliInvocation>> {
+    let mut action: Option<CliAction> = None;
+    let mut json = false;
+    let mut disk_flags: Vec<&str> = Vec::new();
+    let mut cleanup_flags: Vec<&str> = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let next_action = match arg.as_str() {
+            "--json" => {
+                json = true;
+                None
+            }
+            "--temp" | "--browser" | "--thumbnails" | "--recycle-bin" => {
+                disk_flags.push(arg.as_str());
+                None
+            }
+            "--dry-run" | "--yes" => {
+                cleanup_flags.push(arg.as_str());
+                None
+            }
+            "--clean-ram" => Some(CliAction::CleanRam),
+            "--clean-disk" => Some(CliAction::CleanDisk(DiskCleaningOptions::default())),
+            "--unlimit-all" => Some(CliAction::UnlimitAll),
+            "--status" => Some(CliAction::Status),
+            "--defender-enable" => Some(CliAction::DefenderEnable),
+            "--uninstall-cleanup" => Some(CliAction::UninstallCleanup { dry_run: false, confirmed: false }),
+            "--limit" => {
+                let exe_name = iter
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("--limit attend <exe> <mbps>\n{}", USAGE))?
+                    .clone();
+                let limit = iter
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("--limit attend <exe> <mbps>\n{}", USAGE))?;
+                let limit_mbps = crate::network::parse_speed_limit_mbps(limit)?;
+                Some(CliAction::Limit { exe_name, limit_mbps })
+            }
+            // Arguments de l'interface graphique (ex. --tab=network)
+            _ => None,
+        };
+
+        if let Some(next_action) = next_action {
+            if action.is_some() {
+                return Err(anyhow::anyhow!("Une seule action à la fois\n{}", USAGE));
+            }
+            action = Some(next_action);
+        }
+    }
+
+    let Some(mut action) = action else {
+        if json || !disk_flags.is_empty() || !cleanup_flags.is_empty() {
+            return Err(anyhow::anyhow!("Aucune action indiquée\n{}", USAGE));
+        }
+        return Ok(None);
+    };
+
+    // Sans option explicite, --clean-disk garde les options par défaut
+    if let CliAction::CleanDisk(options) = &mut action {
+        if !disk_flags.is_empty() {
+            *options = DiskCleaningOptions {
+                clean_temp_files: disk_flags.contains(&"--temp"),
+                clean_browser_cache: disk_flags.contains(&"--browser"),
+                clean_thumbnails: disk_flags.contains(&"--thumbnails"),
+                clean_recycle_bin: disk_flags.contains(&"--recycle-bin"),
+                ..DiskCleaningOptions::default()
+            };
+        }
+    } else if !disk_flags.is_empty() {
+        return Err(anyhow::anyhow!("{} n'est valable qu'avec --clean-disk", disk_flags.join(", ")));
+    }
+
+    if let CliAction::UninstallCleanup { dry_run, confirmed } = &mut action {
+        *dry_run = cleanup_flags.contains(&"--dry-run");
+        *confirmed = cleanup_flags.contains(&"--yes");
+    } else if !cleanup_flags.is_empty() {
+        return Err(anyhow::anyhow!("{} n'est valable qu'avec --uninstall-cleanup", cleanup_flags.join(", ")));
+    }
+
+    Ok(Some(CliInvocation { action, json }))
+}
+
+#[derive(Debug, Serialize)]
+struct LimitReport {
+    exe_name: String,
+    limit_kbps: u32,
+    limited_pids: Vec<u32>,
+    /// Already at this limit, policy left untouched
+    unchanged_pids: Vec<u32>,
+    errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct StatusReport {
+    memory: SystemMemoryInfo,
+    ram_used_percent: f32,
+    qos_policies: Vec<QosPolicyInfo>,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorReport {
+    error: String,
+}
+
+#[derive(Debug, Serialize)]
+struct DefenderReport {
+    real_time_protection: bool,
+    results: Vec<String>,
+}
+
+/// Run the action and return the process exit code
+pub fn run(invocation: CliInvocation) -> i32 {
+    let json = invocation.json;
+    let outcome = match invocation.action {
+        CliAction::CleanRam => clean_ram(json),
+        CliAction::CleanDisk(options) => clean_disk(options, json),
+        CliAction::Limit { exe_name, limit_mbps } => limit(&exe_name, limit_mbps, json),
+        CliAction::UnlimitAll => unlimit_all(json),
+        CliAction::Status => status(json),
+        CliAction::DefenderEnable => defender_enable(json),
+        CliAction::UninstallCleanup { dry_run, confirmed } => uninstall_cleanup(dry_run, confirmed, json),
+    };
+
+    match outcome {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(e) => {
+            tracing::error!("❌ Commande échouée: {}", e);
+            if json {
+                print_json(&ErrorReport { error: e.to_string() });
+            } else {
+                eprintln!("❌ {}", e);
+            }
+            1
+        }
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(output) => println!("{}", output),
+        Err(e) => eprintln!("❌ Sérialisation JSON impossible: {}", e),
+    }
+}
+
+fn clean_ram(json: bool) -> Result<bool> {
+    let results: CleaningResults = crate::memory::clean_memory()?;
+    if json {
+        print_json(&results);
+    } else if results.has_error {
+        println!("❌ {}", results.error_message);
+    } else {
+        println!(
+            "✅ RAM nettoyée : {:.2} MB libérés sur {} processus",
+            results.total_freed() as f64 / 1024.0 / 1024.0,
+            results.processes.len()
+        );
+        if let Some(gained) = results.available_gained() {
+            println!("   Mémoire disponible : {:+.2} MB", gained as f64 / 1024.0 / 1024.0);
+        }
+    }
+    Ok(!results.has_error)
+}
+
+fn clean_disk(options: DiskCleaningOptions, json: bool) -> Result<bool> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    let results: DiskCleaningResults = runtime.block_on(crate::disk::clean_disk_with_options(options, false))?;
+    if json {
+        print_json(&results);
+    } else {
+        println!(
+            "✅ Disque nettoyé : {:.2} MB libérés, {} fichiers traités",
+            results.total_space_freed as f64 / 1024.0 / 1024.0,
+            results.files_processed
+        );
+        if results.pending_reboot_bytes > 0 {
+            println!("⏳ {:.2} MB supprimés au prochain redémarrage", results.pending_reboot_bytes as f64 / 1024.0 / 1024.0);
+        }
+        for browser in &results.skipped_browsers {
+            println!("ℹ️ {}", crate::disk::browser_cache::skipped_message(browser));
+        }
+        for error in &results.errors {
+            println!("⚠️ {}", error);
+        }
+    }
+    Ok(results.errors.is_empty())
+}
+
+fn limit(exe_name: &str, limit_mbps: f64, json: bool) -> Result<bool> {
+    let mut limiter = NetworkLimiter::new()?;
+    limiter.scan_network_processes()?;
+
+    let target = exe_name.to_lowercase();
+    let target_stem = target.trim_end_matches(".exe");
+    let limit_kbps = (limit_mbps * 1024.0) as u32;
+    let pids: Vec<u32> = limiter
+        .get_processes()
+        .iter()
+        .filter(|p| p.name.to_lowercase().trim_end_matches(".exe") == target_stem)
+        .map(|p| p.pid)
+        .collect();
+
+    if pids.is_empty() {
+        return Err(anyhow::anyhow!("Aucun processus réseau actif nommé {}", exe_name));
+    }
+
+    let limits: Vec<(u32, u32)> = pids.iter().map(|&pid| (pid, limit_kbps)).collect();
+    let outcomes = limiter.set_speed_limits_bulk(&limits);
+    let counts = LimitSummary::from_outcomes(&outcomes);
+
+    let report = LimitReport {
+        exe_name: exe_name.to_string(),
+        limit_kbps,
+        limited_pids: outcomes.iter().filter(|(_, r)| r.is_ok()).map(|(pid, _)| *pid).collect(),
+        unchanged_pids: outcomes
+            .iter()
+            .filter(|(_, r)| r.as_ref().is_ok_and(|change| change.is_unchanged()))
+            .map(|(pid, _)| *pid)
+            .collect(),
+        errors: outcomes
+            .iter()
+            .filter_map(|(pid, r)| r.as_ref().err().map(|e| format!("PID {}: {}", pid, e)))
+            .collect(),
+    };
+
+    if json {
+        print_json(&report);
+    } else {
+        println!("✅ {} limité à {:.2} MB/s ({})", exe_name, limit_mbps, counts.describe());
+        for error in &report.errors {
+            println!("❌ {}", error);
+        }
+    }
+    Ok(report.errors.is_empty())
+}
+
+fn unlimit_all(json: bool) -> Result<bool> {
+    let mut limiter = NetworkLimiter::new()?;
+    limiter.clear_all_limits()?;
+    if json {
+        print_json(&serde_json::json!({ "cleared": true }));
+    } else {
+        println!("✅ Toutes les limitations réseau ont été supprimées");
+    }
+    Ok(true)
+}
+
+/// Inventory first; the removal only runs after `--yes` or a "o" answer
+fn uninstall_cleanup(dry_run: bool, confirmed: bool, json: bool) -> Result<bool> {
+    let preview = crate::maintenance::full_cleanup(None, true);
+    if dry_run || preview.items.is_empty() {
+        if json {
+            print_json(&preview);
+        } else {
+            print_cleanup_report(&preview);
+        }
+        return Ok(preview.failures() == 0);
+    }
+
+    if !confirmed {
+        if json {
+            return Err(anyhow::anyhow!("--uninstall-cleanup --json exige --yes (ou --dry-run)"));
+        }
+        print_cleanup_report(&preview);
+        print!("Supprimer ces éléments ? [o/N] ");
+        std::io::Write::flush(&mut std::io::stdout())?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "o" | "oui" | "y" | "yes") {
+            println!("↩️ Nettoyage annulé");
+            return Ok(false);
+        }
+    }
+
+    let report = crate::maintenance::full_cleanup(None, false);
+    if json {
+        print_json(&report);
+    } else {
+        print_cleanup_report(&report);
+    }
+    Ok(report.failures() == 0 && report.needs_elevation() == 0)
+}
+
+fn print_cleanup_report(report: &crate::maintenance::CleanupReport) {
+    use crate::maintenance::CleanupStatus;
+
+    if report.items.is_empty() {
+        println!("✅ Aucune trace de GameBooster à supprimer");
+        return;
+    }
+    println!("{}", if report.dry_run { "🧹 Voici ce qui sera supprimé :" } else { "🧹 Résultat du nettoyage :" });
+    let mut category = None;
+    for item in &report.items {
+        if category != Some(item.category) {
+            category = Some(item.category);
+            println!("{}", item.category.label());
+        }
+        match &item.status {
+            CleanupStatus::Planned => println!("  • {}", item.description),
+            CleanupStatus::Removed => println!("  ✅ {}", item.description),
+            CleanupStatus::Failed(error) => println!("  ❌ {} : {}", item.description, error),
+            CleanupStatus::NeedsElevation => println!("  🔒 {} : droits administrateur requis", item.description),
+        }
+    }
+}
+
+fn status(json: bool) -> Result<bool> {
+    let memory = crate::memory::get_detailed_system_memory_info();
+    let limiter = NetworkLimiter::new()?;
+    let report = StatusReport {
+        memory,
+        ram_used_percent: memory.used_physical_percent(),
+        qos_policies: limiter.verify_qos_policies(false)?,
+    };
+
+    if json {
+        print_json(&report);
+    } else {
+        println!(
+            "🧠 RAM : {:.1} % ({:.2} / {:.2} GB)",
+            report.ram_used_percent,
+            memory.used_physical() as f64 / 1024.0 / 1024.0 / 1024.0,
+            memory.total_physical as f64 / 1024.0 / 1024.0 / 1024.0
+        );
+        if report.qos_policies.is_empty() {
+            println!("🔍 Aucune politique QoS active");
+        }
+        for policy in &report.qos_policies {
+            println!(
+                "🎯 {} : {} ({:.2} MB/s)",
+                policy.name,
+                policy.app_name,
+                policy.throttle_bits as f64 / (1024.0 * 1024.0 * 8.0)
+            );
+        }
+    }
+    Ok(true)
+}
+
+fn defender_enable(json: bool) -> Result<bool> {
+    let status = crate::services::defender::DefenderService::enable_immediately()?;
+    if json {
+        print_json(&DefenderReport {
+            real_time_protection: status.real_time_protection,
+            results: status.last_operation_results,
+        });
+    } else {
+        println!("{}", status.status_text);
+        for result in &status.last_operation_results {
+            println!("  {}", result);
+        }
+    }
+    Ok(status.real_time_protection)
+}
+
+/// Make stdout/stderr visible: release builds use the "windows" subsystem and start detached
+#[cfg(windows)]
+pub fn attach_console() {
+    use windows_sys::Win32::System::Console::{AllocConsole, AttachConsole, ATTACH_PARENT_PROCESS};
+
+    unsafe {
+        if AttachConsole(ATTACH_PARENT_PROCESS) == 0 {
+            AllocConsole();
+        }
+    }
+}
+
+#[cfg(not(windows))]
+pub fn attach_console() {}
@@ -0,0 +1,328 @@
+//! # Application settings
+//!
+//! User preferences persisted to `config/settings.toml` between sessions.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::disk::DiskCleaningOptions;
+use crate::gaming::hotkey::HotkeyConfig;
+use crate::gaming::launcher_watcher::LauncherWatcherConfig;
+use crate::logs::LogLevel;
+use crate::memory::alert::MemoryAlertConfig;
+use crate::network::{ExitedLimitAction, SpeedUnit};
+use crate::optimization::hosts_blocker::BlockProfile;
+use crate::profiles::{AppliedProfile, BoostProfile};
+use crate::services::ServiceMode;
+
+const CONFIG_DIR: &str = "config";
+const CONFIG_FILE: &str = "settings.toml";
+
+/// Automatic RAM cleaning when usage crosses a threshold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoCleanConfig {
+    pub enabled: bool,
+    pub ram_threshold_percent: u8,
+}
+
+impl Default for AutoCleanConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ram_threshold_percent: 85,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowConfig {
+    /// Inner size in points, of the restored window when it was maximized
+    pub width: f32,
+    pub height: f32,
+    /// Top-left corner of the outer window on the virtual desktop, in points; centered on the
+    /// primary monitor when absent
+    pub position: Option<[f32; 2]>,
+    /// Pixels per point of the monitor the window was on, to locate `position` in pixels
+    pub pixels_per_point: f32,
+    pub maximized: bool,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            width: 1200.0,
+            height: 700.0,
+            position: None,
+            pixels_per_point: 1.0,
+            maximized: false,
+        }
+    }
+}
+
+impl WindowConfig {
+    /// `position` if the window would still be on a connected monitor; `None` once the monitor
+    /// it was on has been unplugged, so that the window opens centered instead of off-screen
+    pub fn restorable_position(&self) -> Option<[f32; 2]> {
+        let [x, y] = self.position?;
+        let scale = self.pixels_per_point.max(0.5);
+        let on_monitor = crate::utils::rect_on_monitor(
+            (x * scale) as i32,
+            (y * scale) as i32,
+            (self.width * scale) as i32,
+            (self.height * scale) as i32,
+        );
+        if !on_monitor {
+            tracing::info!("🖥️ Position de fenêtre enregistrée hors des écrans connectés, fenêtre centrée");
+        }
+        on_monitor.then_some([x, y])
+    }
+}
+
+/// Named speed limit offered next to the limit input of the network tab
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LimitPreset {
+    pub name: String,
+    pub kbps: u32,
+}
+
+/// Speed limits remembered per application, and the named presets
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LimitPreferences {
+    /// Lowercase executable name -> last limit applied to it, in KB/s. Kept when the limit
+    /// is removed, so that it can be applied again in one click.
+    pub per_exe: BTreeMap<String, u32>,
+    pub presets: Vec<LimitPreset>,
+}
+
+impl Default for LimitPreferences {
+    fn default() -> Self {
+        Self {
+            per_exe: BTreeMap::new(),
+            presets: vec![
+                LimitPreset { name: "Streaming en arrière-plan".to_string(), kbps: 1024 },
+                LimitPreset { name: "Mises à jour".to_string(), kbps: 200 },
+            ],
+        }
+    }
+}
+
+impl LimitPreferences {
+    /// Last limit applied to `exe_name`, in KB/s
+    pub fn remembered(&self, exe_name: &str) -> Option<u32> {
+        self.per_exe.get(&exe_name.to_lowercase()).copied()
+    }
+
+    pub fn remember(&mut self, exe_name: &str, kbps: u32) {
+        self.per_exe.insert(exe_name.to_lowercase(), kbps);
+    }
+
+    pub fn forget(&mut self, exe_name: &str) {
+        self.per_exe.remove(&exe_name.to_lowercase());
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    /// `Theme::name` of the selected theme
+    pub theme: String,
+    /// Custom accent color (RGB), theme default when absent
+    pub accent_color: Option<[u8; 3]>,
+    /// `Tab::as_arg` of the last active tab
+    pub last_tab: String,
+    pub disk_options: DiskCleaningOptions,
+    /// Domains written to the hosts file by the hosts blocker
+    pub hosts_profile: BlockProfile,
+    /// Service name -> selected in the services tab
+    pub selected_services: BTreeMap<String, bool>,
+    /// Service name -> how it is stopped, `ServiceMode::Session` when absent
+    pub service_modes: BTreeMap<String, ServiceMode>,
+    /// Stop the running dependents of a gaming service before it instead of skipping it
+    pub manage_service_dependencies: bool,
+    /// Default value of the speed limit input, in `default_speed_limit_unit`
+    pub default_speed_limit: String,
+    pub default_speed_limit_unit: SpeedUnit,
+    pub limit_preferences: LimitPreferences,
+    /// What happens to the policy of a limited process once it exits
+    pub exited_limit_action: ExitedLimitAction,
+    /// Delay before `ExitedLimitAction::Remove` deletes the policy, in seconds
+    pub exited_limit_grace_secs: u64,
+    /// Per-process traffic from kernel network events (ETW) when running as administrator
+    pub high_precision_network: bool,
+    /// Measure the outbound rate after a limit is applied from the network tab
+    pub verify_network_limits: bool,
+    /// List the processes with open sockets but no traffic in the network tab
+    pub show_idle_network_processes: bool,
+    /// Host pinged by the latency monitor of the network tab
+    pub ping_host: String,
+    /// Run the latency monitor on a metered connection too
+    pub ping_on_metered: bool,
+    /// Automatic network scan interval while the network tab is shown, 0 = manual only
+    pub network_refresh_secs: u64,
+    /// Folders searched by the duplicate finder
+    pub duplicate_scan_paths: Vec<PathBuf>,
+    /// Disk cleaning and service optimization only report what they would do
+    pub simulation_mode: bool,
+    /// `PendingAction::key` of the confirmations answered with "Ne plus demander"
+    pub skipped_confirmations: BTreeSet<String>,
+    pub auto_clean: AutoCleanConfig,
+    /// Windows notification when memory runs low
+    pub memory_alert: MemoryAlertConfig,
+    pub window: WindowConfig,
+    /// End of a timed Defender pause, cleared once Defender is re-enabled
+    pub defender_reenable_at: Option<DateTime<Local>>,
+    /// GUID of the power plan activated by the gaming mode, High Performance when absent
+    pub gaming_power_plan: Option<String>,
+    /// GUID of the plan active before the gaming mode, cleared once it is restored
+    pub power_plan_to_restore: Option<String>,
+    /// The gaming mode turns the audio enhancements of every playback device off
+    pub gaming_audio_enhancements_off: bool,
+    /// The gaming mode raises the priorities of the MMCSS "Games" task
+    pub gaming_mmcss_priority: bool,
+    /// Services stopped by the last optimization, cleared once they are restarted
+    pub services_to_restart: Vec<String>,
+    /// Service name -> start type replaced by the durable mode, cleared once it is restored
+    pub services_start_types: BTreeMap<String, String>,
+    /// Boost profiles created by the user, next to `profiles::built_in`
+    pub boost_profiles: Vec<BoostProfile>,
+    /// Profile applied and not reverted yet
+    pub applied_profile: Option<AppliedProfile>,
+    /// Memory cleaning before a game started by Steam, Epic Games or Battle.net
+    pub launcher_watcher: LauncherWatcherConfig,
+    /// System-wide combination that cleans the RAM, even in game
+    pub hotkey: HotkeyConfig,
+    /// Least severe level written to the log files
+    pub log_level: LogLevel,
+    /// Days of log files kept, 0 = never delete them
+    pub log_retention_days: u32,
+    /// Look for a newer GitHub release at startup
+    pub check_updates: bool,
+    /// Also look for it when the connection is metered
+    pub check_updates_on_metered: bool,
+    /// Release tag the user chose to skip, not notified again
+    pub ignored_update_version: Option<String>,
+    /// Name of the profile behind the "Boost" button of the tab bar
+    pub default_profile: Option<String>,
+    /// The first-run assistant was completed or skipped. A settings file written before the
+    /// assistant existed counts as completed: only new installs see it.
+    #[serde(default = "settings_predate_onboarding")]
+    pub onboarding_completed: bool,
+}
+
+fn settings_predate_onboarding() -> bool {
+    true
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            theme: crate::theme::initial_theme().name.to_string(),
+            accent_color: None,
+            last_tab: "memory".to_string(),
+            disk_options: DiskCleaningOptions::default(),
+            hosts_profile: BlockProfile::default(),
+            selected_services: BTreeMap::new(),
+            service_modes: BTreeMap::new(),
+            manage_service_dependencies: true,
+            default_speed_limit: "1.0".to_string(),
+            default_speed_limit_unit: SpeedUnit::default(),
+            limit_preferences: LimitPreferences::default(),
+            exited_limit_action: ExitedLimitAction::default(),
+            exited_limit_grace_secs: crate::network::DEFAULT_EXITED_LIMIT_GRACE_SECS,
+            high_precision_network: true,
+            verify_network_limits: true,
+            show_idle_network_processes: false,
+            ping_host: crate::network::ping_monitor::DEFAULT_HOST.to_string(),
+            ping_on_metered: false,
+            network_refresh_secs: 0,
+            duplicate_scan_paths: crate::disk::duplicates::default_paths(),
+            simulation_mode: false,
+            skipped_confirmations: BTreeSet::new(),
+            auto_clean: AutoCleanConfig::default(),
+            memory_alert: MemoryAlertConfig::default(),
+            window: WindowConfig::default(),
+            defender_reenable_at: None,
+            gaming_power_plan: None,
+            power_plan_to_restore: None,
+            gaming_audio_enhancements_off: false,
+            gaming_mmcss_priority: false,
+            services_to_restart: Vec::new(),
+            services_start_types: BTreeMap::new(),
+            boost_profiles: Vec::new(),
+            applied_profile: None,
+            launcher_watcher: LauncherWatcherConfig::default(),
+            hotkey: HotkeyConfig::default(),
+            log_level: LogLevel::default(),
+            log_retention_days: 7,
+            check_updates: true,
+            check_updates_on_metered: false,
+            ignored_update_version: None,
+            default_profile: None,
+            onboarding_completed: false,
+        }
+    }
+}
+
+impl AppConfig {
+    pub fn path() -> PathBuf {
+        Path::new(CONFIG_DIR).join(CONFIG_FILE)
+    }
+
+    /// Level saved in the settings file, read before logging starts: nothing is logged and an
+    /// invalid file is left to `load`
+    pub fn saved_log_level() -> LogLevel {
+        #[derive(Default, Deserialize)]
+        #[serde(default)]
+        struct LoggingSettings {
+            log_level: LogLevel,
+        }
+
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|content| toml::from_str::<LoggingSettings>(&content).ok())
+            .unwrap_or_default()
+            .log_level
+    }
+
+    /// Load the settings file, falling back to defaults when it is missing or invalid.
+    /// An unreadable file is renamed to `settings.toml.bak` so that it is not overwritten.
+    pub fn load() -> Self {
+        let path = Self::path();
+        if !path.exists() {
+            tracing::info!("⚙️ Aucun fichier de paramètres, valeurs par défaut utilisées");
+            return Self::default();
+        }
+
+        match fs::read_to_string(&path).map_err(anyhow::Error::from).and_then(|content| {
+            toml::from_str::<AppConfig>(&content).map_err(anyhow::Error::from)
+        }) {
+            Ok(config) => {
+                tracing::info!("⚙️ Paramètres chargés depuis {}", path.display());
+                config
+            }
+            Err(e) => {
+                let backup = path.with_extension("toml.bak");
+                tracing::warn!("⚠️ Paramètres invalides ({}), sauvegardés dans {}: {}", path.display(), backup.display(), e);
+                if let Err(e) = fs::rename(&path, &backup) {
+                    tracing::error!("❌ Impossible de renommer {}: {}", path.display(), e);
+                }
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        fs::create_dir_all(CONFIG_DIR)?;
+        let content = toml::to_string_pretty(self)?;
+        fs::write(Self::path(), content)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+//! # Panic handler
+//!
+//! Last-resort cleanup when the interface panics while the system is modified. The hook logs
+//! the panic with its backtrace, then undoes what the persisted state says is still applied,
+//! never relying on the in-memory state of the app:
+//! - GameBooster QoS policies (WMI and registry only, no PowerShell)
+//! - the power plan replaced by the gaming mode (`power_plan_to_restore`)
+//! - the services stopped by the last optimization (`services_to_restart`)
+//! - the audio settings changed by the gaming mode (OS tweaks journal)
+//! - Defender, when a timed pause is pending (`defender_reenable_at`)
+//!
+//! Each step runs on its own thread and is abandoned after `STEP_TIMEOUT`, so a hung call
+//! cannot keep the process alive. A native message box then lists what was restored.
+
+use std::backtrace::Backtrace;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+
+use crate::config::AppConfig;
+
+const STEP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Set by the first panic: a panic raised by the cleanup itself only gets the default hook
+static HANDLING_PANIC: AtomicBool = AtomicBool::new(false);
+
+/// Install the hook; the default one still prints the panic to stderr first
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+        if HANDLING_PANIC.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        tracing::error!("💥 Panique: {}\n{}", info, Backtrace::force_capture());
+        let report = restore_system_state();
+        show_report(&report);
+    }));
+}
+
+/// One line per restored (✅) or failed (❌) item; steps with nothing to undo are omitted
+fn restore_system_state() -> Vec<String> {
+    tracing::info!("🧯 Restauration de l'état du système après la panique...");
+    let config = AppConfig::load();
+    let mut report = Vec::new();
+
+    #[cfg(target_os = "windows")]
+    run_step(&mut report, "Politiques QoS", || {
+        let removed = crate::network::remove_all_policies_now()?;
+        Ok((removed > 0).then(|| format!("{} politique(s) QoS GameBooster supprimée(s)", removed)))
+    });
+
+    if let Some(guid) = config.power_plan_to_restore {
+        run_step(&mut report, "Plan d'alimentation", move || {
+            crate::power::set_active_plan(&guid)?;
+            update_config(|config| config.power_plan_to_restore = None)?;
+            Ok(Some("Plan d'alimentation d'origine réactivé".to_string()))
+        });
+    }
+
+    if !config.services_to_restart.is_empty() {
+        let services = config.services_to_restart;
+        run_step(&mut report, "Services", move || {
+            let results = crate::services::gaming_services::restart_services(&services);
+            let failed = results.operations.iter().filter(|op| !op.success).count();
+            if failed > 0 {
+                return Err(anyhow!("{} service(s) sur {} non redémarré(s)", failed, services.len()));
+            }
+            update_config(|config| config.services_to_restart.clear())?;
+            Ok(Some(format!("{} service(s) redémarré(s)", services.len())))
+        });
+    }
+
+    if crate::optimization::audio::has_gaming_mode_changes() {
+        run_step(&mut report, "Audio", || {
+            let results = crate::optimization::audio::exit_gaming_mode();
+            let failed = results.outcomes.iter().filter(|outcome| !outcome.success).count();
+            if failed > 0 {
+                return Err(anyhow!("{} réglage(s) audio sur {} non rétabli(s)", failed, results.outcomes.len()));
+            }
+            Ok(Some("Réglages audio d'origine rétablis".to_string()))
+        });
+    }
+
+    // Pause en cours : l'échéance est effacée par `enable_immediately`
+    if config.defender_reenable_at.is_some() {
+        run_step(&mut report, "Windows Defender", || {
+            crate::services::defender::DefenderService::enable_immediately()?;
+            Ok(Some("Windows Defender réactivé".to_string()))
+        });
+    }
+
+    report
+}
+
+/// Run `step` on its own thread, giving up after `STEP_TIMEOUT`
+fn run_step<F>(report: &mut Vec<String>, name: &str, step: F)
+where
+    F: FnOnce() -> Result<Option<String>> + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+    let spawned = std::thread::Builder::new()
+        .name("panic_cleanup".to_string())
+        .spawn(move || {
+            let _ = sender.send(step());
+        });
+    if let Err(e) = spawned {
+        report.push(format!("❌ {} : {}", name, e));
+        return;
+    }
+
+    match receiver.recv_timeout(STEP_TIMEOUT) {
+        Ok(Ok(Some(done))) => {
+            tracing::info!("✅ {}", done);
+            report.push(format!("✅ {}", done));
+        }
+        Ok(Ok(None)) => {}
+        Ok(Err(e)) => {
+            tracing::error!("❌ {} non restauré: {}", name, e);
+            report.push(format!("❌ {} : {}", name, e));
+        }
+        Err(RecvTimeoutError::Timeout) => {
+            tracing::error!("⏱️ {} : abandon après {} s", name, STEP_TIMEOUT.as_secs());
+            report.push(format!("❌ {} : aucune réponse après {} s", name, STEP_TIMEOUT.as_secs()));
+        }
+        Err(RecvTimeoutError::Disconnected) => {
+            report.push(format!("❌ {} : échec inattendu", name));
+        }
+    }
+}
+
+fn update_config(change: impl FnOnce(&mut AppConfig)) -> Result<()> {
+    let mut config = AppConfig::load();
+    change(&mut config);
+    config.save()
+}
+
+fn show_report(report: &[String]) {
+    let mut description = String::from("GameBooster s'est arrêté à la suite d'une erreur inattendue.\n\n");
+    if report.is_empty() {
+        description.push_str("Aucune modification du système n'était à annuler.");
+    } else {
+        description.push_str("État du système restauré :\n");
+        description.push_str(&report.join("\n"));
+    }
+    description.push_str("\n\nDétails dans le dossier logs.");
+
+    rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Error)
+        .set_title("GameBooster - Erreur inattendue")
+        .set_description(description)
+        .set_buttons(rfd::MessageButtons::Ok)
+        .show();
+}
@@ -0,0 +1,500 @@
+//! # Recent crashes
+//!
+//! Crashes Windows recorded over the last `LOOKBACK_DAYS` days, read from the event logs
+//! (`EvtQuery`, newest first):
+//! - Application, `Application Error` 1000: faulting application and module, exception code
+//! - Application, `Windows Error Reporting` 1001: the crash report (APPCRASH, BEX); the report
+//!   of an application error already listed is the same crash and is dropped
+//! - System, `Kernel-Power` 41: the machine restarted without shutting down cleanly (freeze,
+//!   power cut, blue screen), logged at the next boot
+//!
+//! Each crash is tagged with the gaming session (see `crate::sessions`) it happened in. At most
+//! `MAX_EVENTS` events are read. The Security log, which needs the administrator rights, holds
+//! nothing about crashes and is never queried; a log the user may not read is listed in
+//! `CrashQuery::skipped_logs` instead of failing the query.
+//!
+//! Blocking: run on a worker thread.
+
+use std::cmp::Ordering;
+
+use chrono::{DateTime, Duration, Local};
+
+use crate::sessions::SessionSummary;
+
+pub const LOOKBACK_DAYS: i64 = 7;
+/// Events read over all the logs
+pub const MAX_EVENTS: usize = 500;
+/// A crash report filed this long after an application error is about the same crash
+const REPORT_MERGE_SECS: i64 = 120;
+/// The session marker is written every minute: a crash just after its last write was in it
+const SESSION_SLACK_SECS: i64 = 120;
+/// Kernel-Power 41 is logged at the next boot, some minutes after the session was cut
+const REBOOT_SLACK_SECS: i64 = 30 * 60;
+
+/// Unnamed `Data` values of `Application Error` 1000, in order (older Windows versions)
+const APPLICATION_ERROR_FIELDS: &[&str] = &[
+    "AppName", "AppVersion", "AppTimeStamp", "ModuleName", "ModuleVersion", "ModuleTimeStamp", "ExceptionCode",
+    "FaultingOffset", "ProcessId", "ProcessCreationTime", "AppPath", "ModulePath", "IntegratorReportId",
+    "PackageFullName", "PackageRelativeAppId",
+];
+/// Unnamed `Data` values of `Windows Error Reporting` 1001, in order
+const ERROR_REPORT_FIELDS: &[&str] = &[
+    "Bucket", "BucketType", "EventName", "Response", "CabId", "P1", "P2", "P3", "P4", "P5", "P6", "P7", "P8", "P9",
+    "P10", "AttachedFiles", "StorePath", "AnalysisSymbol", "Rechecking", "ReportId", "ReportStatus", "HashedBucket",
+    "CabGuid",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CrashKind {
+    ApplicationError,
+    ErrorReport,
+    UnexpectedShutdown,
+}
+
+impl CrashKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            CrashKind::ApplicationError => "Plantage d'application",
+            CrashKind::ErrorReport => "Rapport d'erreur",
+            CrashKind::UnexpectedShutdown => "Arrêt inattendu",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CrashEvent {
+    pub kind: CrashKind,
+    pub time: DateTime<Local>,
+    /// "Application", "System"
+    pub log: &'static str,
+    pub provider: String,
+    pub event_id: u32,
+    /// Faulting executable, `None` for an unexpected shutdown
+    pub application: Option<String>,
+    pub faulting_module: Option<String>,
+    /// "0xc0000005"; the bug check code of an unexpected shutdown after a blue screen
+    pub exception_code: Option<String>,
+    /// Every value of the event, named after its position when Windows leaves it unnamed
+    pub data: Vec<(String, String)>,
+    /// Gaming session running when the crash happened
+    pub session: Option<String>,
+}
+
+impl CrashEvent {
+    /// Text copied by "Copier les détails"
+    pub fn details(&self) -> String {
+        let mut lines = vec![
+            format!("{} — {}", self.kind.label(), self.time.format("%d/%m/%Y %H:%M:%S")),
+            format!("Journal : {}, source : {}, événement {}", self.log, self.provider, self.event_id),
+        ];
+        if let Some(application) = &self.application {
+            lines.push(format!("Application : {}", application));
+        }
+        if let Some(module) = &self.faulting_module {
+            lines.push(format!("Module en faute : {}", module));
+        }
+        if let Some(code) = &self.exception_code {
+            lines.push(format!("Code : {}", code));
+        }
+        if let Some(session) = &self.session {
+            lines.push(format!("Pendant la session : {}", session));
+        }
+        lines.push(String::new());
+        lines.extend(self.data.iter().map(|(name, value)| format!("{} : {}", name, value)));
+        lines.join("\n")
+    }
+}
+
+/// Column used to sort the crash table
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum CrashSort {
+    Time,
+    Kind,
+    Application,
+    Module,
+}
+
+impl CrashSort {
+    pub fn compare(self, a: &CrashEvent, b: &CrashEvent) -> Ordering {
+        let text = |value: &Option<String>| value.as_deref().unwrap_or_default().to_lowercase();
+        match self {
+            CrashSort::Time => a.time.cmp(&b.time),
+            CrashSort::Kind => a.kind.cmp(&b.kind).then(a.time.cmp(&b.time)),
+            CrashSort::Application => text(&a.application).cmp(&text(&b.application)).then(a.time.cmp(&b.time)),
+            CrashSort::Module => text(&a.faulting_module).cmp(&text(&b.faulting_module)).then(a.time.cmp(&b.time)),
+        }
+    }
+}
+
+/// A gaming session, for `recent_crashes` to tag the crashes that happened during play
+#[derive(Debug, Clone)]
+pub struct SessionSpan {
+    pub name: String,
+    pub started_at: DateTime<Local>,
+    pub ended_at: DateTime<Local>,
+    /// Cut by a crash or a forced shutdown
+    pub interrupted: bool,
+}
+
+impl From<&SessionSummary> for SessionSpan {
+    fn from(summary: &SessionSummary) -> Self {
+        Self {
+            name: summary.name.clone(),
+            started_at: summary.started_at,
+            ended_at: summary.ended_at,
+            interrupted: summary.interrupted,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CrashQuery {
+    /// Newest first
+    pub crashes: Vec<CrashEvent>,
+    /// Logs that could not be read: "System : accès refusé"
+    pub skipped_logs: Vec<String>,
+    /// `MAX_EVENTS` reached, older events were not read
+    pub truncated: bool,
+}
+
+/// Query the Application and System logs and tag the crashes with `sessions`
+pub fn recent_crashes(sessions: &[SessionSpan]) -> CrashQuery {
+    let started = std::time::Instant::now();
+    let window_ms = LOOKBACK_DAYS * 24 * 3600 * 1000;
+    let queries = [
+        (
+            "Application",
+            format!(
+                "*[System[Provider[@Name='Application Error' or @Name='Windows Error Reporting'] and (EventID=1000 or EventID=1001) and TimeCreated[timediff(@SystemTime) <= {}]]]",
+                window_ms
+            ),
+        ),
+        (
+            "System",
+            format!(
+                "*[System[Provider[@Name='Microsoft-Windows-Kernel-Power'] and EventID=41 and TimeCreated[timediff(@SystemTime) <= {}]]]",
+                window_ms
+            ),
+        ),
+    ];
+
+    let mut result = CrashQuery::default();
+    let mut read = 0;
+    for &(log, ref xpath) in &queries {
+        let limit = MAX_EVENTS - read;
+        if limit == 0 {
+            result.truncated = true;
+            break;
+        }
+        match evt::read_events(log, xpath, limit) {
+            Ok(events) => {
+                read += events.len();
+                result.truncated |= events.len() >= limit;
+                result.crashes.extend(events.iter().map(String::as_str).filter_map(parse_event).filter_map(|raw| crash_from(raw, log)));
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                tracing::info!("🔒 Journal {} non lu: accès refusé", log);
+                result.skipped_logs.push(format!("{} : accès refusé", log));
+            }
+            Err(e) => {
+                tracing::warn!("⚠️ Journal {} illisible: {}", log, e);
+                result.skipped_logs.push(format!("{} : {}", log, e));
+            }
+        }
+    }
+
+    drop_duplicate_reports(&mut result.crashes);
+    for crash in &mut result.crashes {
+        crash.session = session_of(crash, sessions);
+    }
+    result.crashes.sort_by(|a, b| b.time.cmp(&a.time));
+    tracing::info!(
+        "💥 {} plantage(s) sur {} jours ({} événements lus, ⏱️ {} ms)",
+        result.crashes.len(),
+        LOOKBACK_DAYS,
+        read,
+        started.elapsed().as_millis()
+    );
+    result
+}
+
+/// Drop the crash reports filed for an application error already listed
+fn drop_duplicate_reports(crashes: &mut Vec<CrashEvent>) {
+    let errors: Vec<(DateTime<Local>, String)> = crashes
+        .iter()
+        .filter(|crash| crash.kind == CrashKind::ApplicationError)
+        .filter_map(|crash| Some((crash.time, crash.application.as_ref()?.to_lowercase())))
+        .collect();
+    crashes.retain(|crash| {
+        if crash.kind != CrashKind::ErrorReport {
+            return true;
+        }
+        let Some(application) = crash.application.as_ref().map(|name| name.to_lowercase()) else {
+            return true;
+        };
+        !errors.iter().any(|(time, name)| {
+            *name == application && crash.time >= *time && crash.time - *time <= Duration::seconds(REPORT_MERGE_SECS)
+        })
+    });
+}
+
+fn session_of(crash: &CrashEvent, sessions: &[SessionSpan]) -> Option<String> {
+    sessions
+        .iter()
+        .find(|session| match crash.kind {
+            CrashKind::UnexpectedShutdown => {
+                session.interrupted
+                    && session.started_at <= crash.time
+                    && crash.time <= session.ended_at + Duration::seconds(REBOOT_SLACK_SECS)
+            }
+            _ => session.started_at <= crash.time && crash.time <= session.ended_at + Duration::seconds(SESSION_SLACK_SECS),
+        })
+        .map(|session| session.name.clone())
+}
+
+/// Fields of an event rendered as XML (`EvtRenderEventXml`)
+struct RawEvent {
+    provider: String,
+    event_id: u32,
+    time: DateTime<Local>,
+    /// `Data` values with their `Name` attribute, when present
+    data: Vec<(Option<String>, String)>,
+}
+
+fn crash_from(raw: RawEvent, log: &'static str) -> Option<CrashEvent> {
+    let fields = match (raw.event_id, raw.provider.as_str()) {
+        (1000, "Application Error") => APPLICATION_ERROR_FIELDS,
+        (1001, "Windows Error Reporting") => ERROR_REPORT_FIELDS,
+        _ => &[],
+    };
+    let data: Vec<(String, String)> = raw
+        .data
+        .into_iter()
+        .enumerate()
+        .map(|(index, (name, value))| {
+            let name = name.unwrap_or_else(|| fields.get(index).map(|field| field.to_string()).unwrap_or_else(|| format!("Valeur {}", index + 1)));
+            (name, value)
+        })
+        .collect();
+    let value = |name: &str| {
+        data.iter()
+            .find(|(field, value)| field.eq_ignore_ascii_case(name) && !value.is_empty())
+            .map(|(_, value)| value.clone())
+    };
+
+    let (kind, application, faulting_module, exception_code) = match raw.event_id {
+        1000 if !fields.is_empty() => (
+            CrashKind::ApplicationError,
+            value("AppName"),
+            value("ModuleName"),
+            value("ExceptionCode").map(|code| hex_code(&code)),
+        ),
+        1001 if !fields.is_empty() => {
+            // Les autres rapports (blocages, pilotes graphiques...) ne sont pas des plantages
+            let event_name = value("EventName").unwrap_or_default();
+            let code_field = match event_name.as_str() {
+                "APPCRASH" | "MoAppCrash" => "P7",
+                "BEX" | "BEX64" => "P8",
+                _ => return None,
+            };
+            (CrashKind::ErrorReport, value("P1"), value("P4"), value(code_field).map(|code| hex_code(&code)))
+        }
+        41 if raw.provider == "Microsoft-Windows-Kernel-Power" => {
+            let bugcheck = value("BugcheckCode").and_then(|code| code.parse::<u64>().ok()).filter(|code| *code != 0);
+            (CrashKind::UnexpectedShutdown, None, None, bugcheck.map(|code| format!("0x{:X}", code)))
+        }
+        _ => return None,
+    };
+
+    Some(CrashEvent {
+        kind,
+        time: raw.time,
+        log,
+        provider: raw.provider,
+        event_id: raw.event_id,
+        application,
+        faulting_module,
+        exception_code,
+        data,
+        session: None,
+    })
+}
+
+/// "c0000005" → "0xc0000005"
+fn hex_code(code: &str) -> String {
+    let code = code.trim();
+    if code.starts_with("0x") || code.starts_with("0X") {
+        code.to_string()
+    } else {
+        format!("0x{}", code)
+    }
+}
+
+fn parse_event(xml: &str) -> Option<RawEvent> {
+    let provider = elements(xml, "Provider").first().and_then(|element| attribute(element.attributes, "Name"))?;
+    let event_id = elements(xml, "EventID").first()?.text.trim().parse().ok()?;
+    let time = elements(xml, "TimeCreated")
+        .first()
+        .and_then(|element| attribute(element.attributes, "SystemTime"))
+        .and_then(|time| DateTime::parse_from_rfc3339(&time).ok())?
+        .with_timezone(&Local);
+    let data = elements(xml, "Data")
+        .iter()
+        .map(|element| (attribute(element.attributes, "Name"), unescape(element.text.trim())))
+        .collect();
+    Some(RawEvent { provider, event_id, time, data })
+}
+
+/// An element of the rendered XML: its attributes and its text (empty when self-closing)
+struct Element<'a> {
+    attributes: &'a str,
+    text: &'a str,
+}
+
+/// Every `<name ...>` element, in order. The rendered events are flat enough for this: no
+/// CDATA, no comments, no nested element of the same name.
+fn elements<'a>(xml: &'a str, name: &str) -> Vec<Element<'a>> {
+    let open = format!("<{}", name);
+    let close = format!("</{}>", name);
+    let mut found = Vec::new();
+    let mut rest = xml;
+    while let Some(index) = rest.find(&open) {
+        rest = &rest[index + open.len()..];
+        // <DataItem> n'est pas <Data>
+        if !rest.starts_with(|c: char| c.is_whitespace() || c == '/' || c == '>') {
+            continue;
+        }
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+        if let Some(attributes) = tag.strip_suffix('/') {
+            found.push(Element { attributes, text: "" });
+            continue;
+        }
+        let text_end = rest.find(&close).unwrap_or(rest.len());
+        found.push(Element { attributes: tag, text: &rest[..text_end] });
+        rest = &rest[text_end..];
+    }
+    found
+}
+
+fn attribute(attributes: &str, name: &str) -> Option<String> {
+    let key = format!("{}=", name);
+    let mut rest = attributes;
+    loop {
+        let index = rest.find(&key)?;
+        let standalone = rest[..index].ends_with(char::is_whitespace);
+        rest = &rest[index + key.len()..];
+        let Some(quote) = rest.chars().next().filter(|c| *c == '\'' || *c == '"') else {
+            continue;
+        };
+        if standalone {
+            let value = &rest[1..];
+            return value.find(quote).map(|end| unescape(&value[..end]));
+        }
+    }
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(target_os = "windows")]
+mod evt {
+    use std::io;
+
+    use windows_sys::Win32::Foundation::{GetLastError, ERROR_INSUFFICIENT_BUFFER, ERROR_NO_MORE_ITEMS};
+    use windows_sys::Win32::System::EventLog::{
+        EvtClose, EvtNext, EvtQuery, EvtQueryChannelPath, EvtQueryReverseDirection, EvtRender, EvtRenderEventXml,
+        EVT_HANDLE,
+    };
+
+    /// Events fetched per `EvtNext` call
+    const BATCH: usize = 32;
+    const NEXT_TIMEOUT_MS: u32 = 5_000;
+
+    struct EvtHandle(EVT_HANDLE);
+
+    impl Drop for EvtHandle {
+        fn drop(&mut self) {
+            unsafe { EvtClose(self.0) };
+        }
+    }
+
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    fn last_error() -> io::Error {
+        io::Error::from_raw_os_error(unsafe { GetLastError() } as i32)
+    }
+
+    /// Up to `limit` events of `log` matching `xpath`, newest first, rendered as XML. Access
+    /// denied comes back as `ErrorKind::PermissionDenied`.
+    pub fn read_events(log: &str, xpath: &str, limit: usize) -> io::Result<Vec<String>> {
+        let log = wide(log);
+        let xpath = wide(xpath);
+        let query = unsafe { EvtQuery(0, log.as_ptr(), xpath.as_ptr(), EvtQueryChannelPath | EvtQueryReverseDirection) };
+        if query == 0 {
+            return Err(last_error());
+        }
+        let query = EvtHandle(query);
+
+        let mut events = Vec::new();
+        let mut buffer: Vec<u16> = vec![0; 4096];
+        while events.len() < limit {
+            let mut handles = [0 as EVT_HANDLE; BATCH];
+            let mut returned = 0u32;
+            let wanted = BATCH.min(limit - events.len()) as u32;
+            if unsafe { EvtNext(query.0, wanted, handles.as_mut_ptr(), NEXT_TIMEOUT_MS, 0, &mut returned) } == 0 {
+                if unsafe { GetLastError() } == ERROR_NO_MORE_ITEMS {
+                    break;
+                }
+                return Err(last_error());
+            }
+            let handles: Vec<EvtHandle> = handles[..returned as usize].iter().map(|&handle| EvtHandle(handle)).collect();
+            for event in &handles {
+                match render_xml(event.0, &mut buffer) {
+                    Ok(xml) => events.push(xml),
+                    Err(e) => tracing::debug!("⏭️ Événement illisible ignoré: {}", e),
+                }
+            }
+        }
+        Ok(events)
+    }
+
+    fn render_xml(event: EVT_HANDLE, buffer: &mut Vec<u16>) -> io::Result<String> {
+        let mut used = 0u32;
+        let mut properties = 0u32;
+        let mut render = |buffer: &mut Vec<u16>, used: &mut u32| unsafe {
+            EvtRender(0, event, EvtRenderEventXml, (buffer.len() * 2) as u32, buffer.as_mut_ptr().cast(), used, &mut properties)
+        };
+        if render(buffer, &mut used) == 0 {
+            if unsafe { GetLastError() } != ERROR_INSUFFICIENT_BUFFER {
+                return Err(last_error());
+            }
+            buffer.resize((used as usize).div_ceil(2), 0);
+            if render(buffer, &mut used) == 0 {
+                return Err(last_error());
+            }
+        }
+        let rendered = &buffer[..(used as usize / 2).min(buffer.len())];
+        let end = rendered.iter().position(|&c| c == 0).unwrap_or(rendered.len());
+        Ok(String::from_utf16_lossy(&rendered[..end]))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod evt {
+    use std::io;
+
+    pub fn read_events(_log: &str, _xpath: &str, _limit: usize) -> io::Result<Vec<String>> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "journal d'événements Windows uniquement"))
+    }
+}
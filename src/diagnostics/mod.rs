@@ -0,0 +1,185 @@
+//! # Diagnostic report
+//!
+//! Facts needed to answer a support request ("la limite ne marche pas"), gathered into a
+//! single zip in `reports/`:
+//! - OS details and capabilities, elevation
+//! - the QoS prerequisites: `QosRequirements` (elevation, WMI provider probe)
+//! - the QoS policies found on the system, foreign ones included, and the policies GameBooster
+//!   recorded (persisted intent)
+//! - the last `LOG_LINES` log lines
+//! - the settings, every path replaced by a hash (user names, game folders)
+//! - versions and build features
+//!
+//! Each collector runs on its own: a failure writes an error stub in place of its file and
+//! never aborts the bundle. Read-only and purely local: nothing is changed on the system and
+//! nothing is sent anywhere.
+//!
+//! Blocking (PowerShell, WMI): run on a worker thread.
+
+pub mod event_log;
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde_json::Value;
+
+use crate::config::AppConfig;
+use crate::network::{self, NetworkLimiter, QosBackend, QosRequirements};
+
+pub const REPORTS_DIR: &str = "reports";
+const LOG_LINES: usize = 500;
+
+/// One file of the bundle
+#[derive(Debug, Clone)]
+pub struct DiagnosticSection {
+    pub file_name: &'static str,
+    pub content: String,
+    /// Set when the collector failed; `content` is then the error stub
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiagnosticBundle {
+    pub created_at: DateTime<Local>,
+    pub sections: Vec<DiagnosticSection>,
+}
+
+/// Zip written by `DiagnosticBundle::write_zip`
+#[derive(Debug, Clone)]
+pub struct DiagnosticReport {
+    pub path: PathBuf,
+    pub sections: usize,
+    /// "logs.txt : accès refusé"
+    pub failures: Vec<String>,
+}
+
+/// Run every collector
+pub fn collect() -> DiagnosticBundle {
+    tracing::info!("🩺 Collecte du rapport de diagnostic...");
+    let sections = vec![
+        section("systeme.txt", collect_os),
+        section("elevation.txt", || Ok(format!("Administrateur : {}\n", crate::utils::is_elevated()))),
+        section("prerequis_qos.json", collect_requirements),
+        section("politiques_qos.json", || {
+            let policies = NetworkLimiter::list_qos_policies(QosBackend::from_env(), true)?;
+            Ok(serde_json::to_string_pretty(&policies)?)
+        }),
+        section("limites_enregistrees.json", || Ok(serde_json::to_string_pretty(&network::persisted_policies())?)),
+        section("logs.txt", || Ok(crate::logs::last_lines(LOG_LINES)?.join("\n"))),
+        section("config.json", collect_config),
+        section("versions.txt", collect_versions),
+    ];
+    DiagnosticBundle { created_at: Local::now(), sections }
+}
+
+/// Run a collector, turning its error into a stub
+fn section(file_name: &'static str, collector: impl FnOnce() -> Result<String>) -> DiagnosticSection {
+    match collector() {
+        Ok(content) => DiagnosticSection { file_name, content, error: None },
+        Err(e) => {
+            tracing::warn!("⚠️ Diagnostic : {} non collecté: {:#}", file_name, e);
+            DiagnosticSection {
+                file_name,
+                content: format!("Collecte impossible : {:#}\n", e),
+                error: Some(format!("{:#}", e)),
+            }
+        }
+    }
+}
+
+fn collect_os() -> Result<String> {
+    let details = crate::os_info::get_os_details();
+    Ok(format!(
+        "{}\n\n{:#?}\n\n{:#?}\n",
+        details.description(),
+        details,
+        crate::os_info::capabilities()
+    ))
+}
+
+/// The checks gating the limits: elevation and the WMI QoS provider, read-only
+fn collect_requirements() -> Result<String> {
+    let requirements = serde_json::to_value(QosRequirements::check())?;
+    Ok(serde_json::to_string_pretty(&serde_json::json!({ "limitation": requirements }))?)
+}
+
+fn collect_config() -> Result<String> {
+    let mut config = serde_json::to_value(AppConfig::load())?;
+    hash_paths(&mut config);
+    Ok(serde_json::to_string_pretty(&config)?)
+}
+
+/// Replace every string that looks like a path by a short hash: the same path keeps the same
+/// hash across the file, without revealing user or folder names
+fn hash_paths(value: &mut Value) {
+    match value {
+        Value::String(text) if text.contains('\\') || text.contains('/') => {
+            let hash = blake3::hash(text.as_bytes()).to_hex();
+            *text = format!("<chemin {}>", &hash.as_str()[..12]);
+        }
+        Value::Array(items) => items.iter_mut().for_each(hash_paths),
+        Value::Object(fields) => fields.values_mut().for_each(hash_paths),
+        _ => {}
+    }
+}
+
+fn collect_versions() -> Result<String> {
+    Ok(format!(
+        "GameBooster {}\nCible : {} {}\nProfil : {}\nETW : {}\nBackend QoS : {:?}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        if cfg!(debug_assertions) { "debug" } else { "release" },
+        if cfg!(feature = "etw") { "activé" } else { "désactivé" },
+        QosBackend::from_env(),
+    ))
+}
+
+impl DiagnosticBundle {
+    /// Index of the bundle, first file of the zip
+    fn summary(&self) -> String {
+        let mut text = format!("Rapport de diagnostic GameBooster du {}\n\n", self.created_at.format("%d/%m/%Y %H:%M:%S"));
+        for section in &self.sections {
+            match &section.error {
+                None => text.push_str(&format!("✅ {}\n", section.file_name)),
+                Some(error) => text.push_str(&format!("❌ {} : {}\n", section.file_name, error)),
+            }
+        }
+        text
+    }
+
+    /// Write `reports/diagnostic-<date>.zip`
+    pub fn write_zip(&self) -> Result<DiagnosticReport> {
+        fs::create_dir_all(REPORTS_DIR)?;
+        let path = Path::new(REPORTS_DIR).join(format!("diagnostic-{}.zip", self.created_at.format("%Y%m%d-%H%M%S")));
+        let mut zip = zip::ZipWriter::new(File::create(&path)?);
+        let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+        zip.start_file("LISEZMOI.txt", options)?;
+        zip.write_all(self.summary().as_bytes())?;
+        for section in &self.sections {
+            zip.start_file(section.file_name, options)?;
+            zip.write_all(section.content.as_bytes())?;
+        }
+        zip.finish()?;
+
+        let report = DiagnosticReport {
+            path: std::env::current_dir().map(|dir| dir.join(&path)).unwrap_or(path),
+            sections: self.sections.len(),
+            failures: self
+                .sections
+                .iter()
+                .filter_map(|section| Some(format!("{} : {}", section.file_name, section.error.as_ref()?)))
+                .collect(),
+        };
+        tracing::info!(
+            "🩺 Rapport de diagnostic écrit : {} ({} échec(s))",
+            report.path.display(),
+            report.failures.len()
+        );
+        Ok(report)
+    }
+}
@@ -1,229 +1,476 @@
 // Browser cache cleaning
+//
+// A running browser keeps its cache files open: their deletion fails file by file and the
+// profile ends up half cleaned. The cache of a running browser is skipped, unless
+// `force_close` asks to close the browser first (the same way as its close button).
+//
+// Browsers are detected by their profile folders. Every profile is found by its layout rather
+// than its name: a Chromium profile is any folder of the user data holding a cache folder
+// ("Default", "Profile 1", "Guest Profile"...), a Firefox profile any folder of "Profiles".
 
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
 use std::fs;
-use std::path::{Path};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sysinfo::System;
 use walkdir::WalkDir;
 
-pub async fn clean_browser_cache() -> Result<u64> {
-    let mut total_cleaned = 0u64;
+use super::largest_files::{LargestFiles, ScanDetail};
+use super::{CategoryResult, DiskCategory};
 
-    #[cfg(target_os = "windows")]
-    {
-        if let Ok(user_profile) = std::env::var("USERPROFILE") {
-            // Chrome cache
-            let chrome_cache = format!("{}\\AppData\\Local\\Google\\Chrome\\User Data\\Default\\Cache", user_profile);
-            if Path::new(&chrome_cache).exists() {
-                total_cleaned += clean_directory(&Path::new(&chrome_cache)).await?;
-            }
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
 
-            // Firefox cache
-            let firefox_cache_base = format!("{}\\AppData\\Local\\Mozilla\\Firefox\\Profiles", user_profile);
-            if Path::new(&firefox_cache_base).exists() {
-                total_cleaned += clean_firefox_profiles_windows(&Path::new(&firefox_cache_base)).await?;
-            }
+/// Time left to a browser to close after the request
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(10);
+const CLOSE_POLL_INTERVAL: Duration = Duration::from_millis(500);
 
-            // Edge cache
-            let edge_cache = format!("{}\\AppData\\Local\\Microsoft\\Edge\\User Data\\Default\\Cache", user_profile);
-            if Path::new(&edge_cache).exists() {
-                total_cleaned += clean_directory(&Path::new(&edge_cache)).await?;
-            }
+/// Cache folders of a Chromium profile
+const CHROMIUM_CACHE_DIRS: &[&str] = &["Cache", "Code Cache", "GPUCache"];
+/// Cache folders of a Firefox profile
+const FIREFOX_CACHE_DIRS: &[&str] = &["cache2", "startupCache"];
 
-            // Opera cache
-            let opera_cache = format!("{}\\AppData\\Local\\Opera Software\\Opera Stable\\Cache", user_profile);
-            if Path::new(&opera_cache).exists() {
-                total_cleaned += clean_directory(&Path::new(&opera_cache)).await?;
-            }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Browser {
+    Chrome,
+    Chromium,
+    Edge,
+    Brave,
+    Opera,
+    Firefox,
+}
+
+impl Browser {
+    pub const ALL: [Browser; 6] = [
+        Browser::Chrome,
+        Browser::Chromium,
+        Browser::Edge,
+        Browser::Brave,
+        Browser::Opera,
+        Browser::Firefox,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Browser::Chrome => "Chrome",
+            Browser::Chromium => "Chromium",
+            Browser::Edge => "Edge",
+            Browser::Brave => "Brave",
+            Browser::Opera => "Opera",
+            Browser::Firefox => "Firefox",
         }
     }
 
-    #[cfg(target_os = "linux")]
-    {
-        if let Some(home_dir) = dirs::home_dir() {
-            // Chrome cache
-            let chrome_cache = home_dir.join(".cache/google-chrome/Default/Cache");
-            if chrome_cache.exists() {
-                total_cleaned += clean_directory(&chrome_cache).await?;
-            }
-            // Chromium cache
-            let chromium_cache = home_dir.join(".cache/chromium/Default/Cache");
-            if chromium_cache.exists() {
-                total_cleaned += clean_directory(&chromium_cache).await?;
-            }
+    /// Executables that lock the cache, lowercase
+    fn process_names(self) -> &'static [&'static str] {
+        #[cfg(target_os = "windows")]
+        match self {
+            Browser::Chrome | Browser::Chromium => &["chrome.exe"],
+            Browser::Edge => &["msedge.exe"],
+            Browser::Brave => &["brave.exe"],
+            Browser::Opera => &["opera.exe"],
+            Browser::Firefox => &["firefox.exe"],
+        }
+        #[cfg(not(target_os = "windows"))]
+        match self {
+            Browser::Chrome => &["chrome"],
+            Browser::Chromium => &["chromium", "chromium-browser"],
+            Browser::Edge => &["msedge", "microsoft-edge", "microsoft-edge-dev"],
+            Browser::Brave => &["brave", "brave-browser"],
+            Browser::Opera => &["opera"],
+            Browser::Firefox => &["firefox", "firefox-bin", "firefox-esr"],
+        }
+    }
 
-            // Firefox cache
-            let firefox_cache_base = home_dir.join(".mozilla/firefox");
-            if firefox_cache_base.exists() {
-                total_cleaned += clean_firefox_profiles_linux(&firefox_cache_base).await?;
-            }
-             // Edge cache (snap)
-            let edge_snap_cache = home_dir.join("snap/microsoft-edge-dev/current/.cache/microsoft-edge-dev/Default/Cache");
-             if edge_snap_cache.exists() {
-                total_cleaned += clean_directory(&edge_snap_cache).await?;
+    fn is_firefox(self) -> bool {
+        self == Browser::Firefox
+    }
+
+    /// Folders holding the profiles (Chromium user data, Firefox "Profiles")
+    fn profile_roots(self) -> Vec<PathBuf> {
+        #[cfg(target_os = "windows")]
+        {
+            let Ok(user_profile) = std::env::var("USERPROFILE") else {
+                return Vec::new();
+            };
+            let local = Path::new(&user_profile).join("AppData").join("Local");
+            match self {
+                Browser::Chrome => vec![local.join("Google\\Chrome\\User Data")],
+                Browser::Chromium => vec![local.join("Chromium\\User Data")],
+                Browser::Edge => vec![local.join("Microsoft\\Edge\\User Data")],
+                Browser::Brave => vec![local.join("BraveSoftware\\Brave-Browser\\User Data")],
+                // Opera range son cache directement dans le dossier du profil
+                Browser::Opera => vec![local.join("Opera Software\\Opera Stable"), local.join("Opera Software\\Opera GX Stable")],
+                Browser::Firefox => vec![local.join("Mozilla\\Firefox\\Profiles")],
             }
-            // Edge cache (flatpak)
-            let edge_flatpak_cache = home_dir.join(".var/app/com.microsoft.Edge/cache/Microsoft/Edge/Default/Cache");
-            if edge_flatpak_cache.exists() {
-                total_cleaned += clean_directory(&edge_flatpak_cache).await?;
+        }
+        #[cfg(not(target_os = "windows"))]
+        {
+            let Some(home_dir) = dirs::home_dir() else {
+                return Vec::new();
+            };
+            match self {
+                Browser::Chrome => vec![home_dir.join(".cache/google-chrome")],
+                Browser::Chromium => vec![home_dir.join(".cache/chromium"), home_dir.join("snap/chromium/common/.cache/chromium")],
+                Browser::Edge => vec![
+                    home_dir.join(".cache/microsoft-edge"),
+                    // snap
+                    home_dir.join("snap/microsoft-edge-dev/current/.cache/microsoft-edge-dev"),
+                    // flatpak
+                    home_dir.join(".var/app/com.microsoft.Edge/cache/Microsoft/Edge"),
+                ],
+                Browser::Brave => vec![home_dir.join(".cache/BraveSoftware/Brave-Browser")],
+                Browser::Opera => vec![home_dir.join(".cache/opera")],
+                // cache2 sous ~/.cache, startupCache dans le profil lui-même
+                Browser::Firefox => vec![
+                    home_dir.join(".cache/mozilla/firefox"),
+                    home_dir.join(".mozilla/firefox"),
+                    home_dir.join("snap/firefox/common/.cache/mozilla/firefox"),
+                ],
             }
         }
     }
+}
 
-    Ok(total_cleaned)
+/// Browsers whose cache is cleaned
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum BrowserSelection {
+    /// Every detected browser, including those installed later
+    #[default]
+    All,
+    Only(BTreeSet<Browser>),
 }
 
-async fn clean_directory(dir: &Path) -> Result<u64> {
-    let mut total_size = 0u64;
-    
-    for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
-        if entry.file_type().is_file() {
-            if let Ok(metadata) = entry.metadata() {
-                let file_size = metadata.len();
-                
-                // Try to delete the file
-                if fs::remove_file(entry.path()).is_ok() {
-                    total_size += file_size;
-                }
-            }
+impl BrowserSelection {
+    pub fn includes(&self, browser: Browser) -> bool {
+        match self {
+            BrowserSelection::All => true,
+            BrowserSelection::Only(browsers) => browsers.contains(&browser),
         }
     }
-    
-    Ok(total_size)
-}
 
-#[cfg(target_os = "windows")]
-async fn clean_firefox_profiles_windows(profiles_dir: &Path) -> Result<u64> {
-    let mut total_cleaned = 0u64;
-    for entry in fs::read_dir(profiles_dir)? {
-        let entry = entry?;
-        if entry.file_type()?.is_dir() {
-            let cache_dir = entry.path().join("cache2"); // Windows specific sub-path
-            if cache_dir.exists() {
-                total_cleaned += clean_directory(&cache_dir).await?;
-            }
+    /// Check or uncheck `browser`; back to `All` once every installed browser is checked
+    pub fn set(&mut self, browser: Browser, included: bool, installed: &[Browser]) {
+        let mut browsers: BTreeSet<Browser> = match self {
+            BrowserSelection::All => installed.iter().copied().collect(),
+            BrowserSelection::Only(browsers) => browsers.clone(),
+        };
+        if included {
+            browsers.insert(browser);
+        } else {
+            browsers.remove(&browser);
         }
+        *self = if installed.iter().all(|browser| browsers.contains(browser)) {
+            BrowserSelection::All
+        } else {
+            BrowserSelection::Only(browsers)
+        };
     }
-    Ok(total_cleaned)
-}
-
-#[cfg(target_os = "linux")]
-async fn clean_firefox_profiles_linux(profiles_dir: &Path) -> Result<u64> {
-    let mut total_cleaned = 0u64;
-    for entry in fs::read_dir(profiles_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_dir() && entry.file_name().to_string_lossy().ends_with(".default-release") {
-             // Common pattern for default profile, cache might be directly inside or in a subfolder
-            let cache_dir_variant1 = path.join("cache2"); // Check for cache2
-            if cache_dir_variant1.exists() {
-                total_cleaned += clean_directory(&cache_dir_variant1).await?;
+}
+
+/// Cache of one browser profile, see `get_browser_cache_sizes`
+#[derive(Debug, Clone)]
+pub struct BrowserCache {
+    pub browser: Browser,
+    /// Folder name of the profile ("Default", "Profile 1", "abcd1234.default-release")
+    pub profile: String,
+    pub size: u64,
+    pub files: u32,
+    /// The browser is running: its cache is in use
+    pub locked: bool,
+    /// Detailed preview: biggest files of the cache, largest first
+    pub largest_files: Vec<ScanDetail>,
+}
+
+/// Outcome of a browser for a cleaning (freed) or a preview (size that can be freed)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserCleanResult {
+    pub browser: Browser,
+    pub profiles: Vec<String>,
+    pub freed: u64,
+    /// Files deleted, or left in place when `skipped`
+    #[serde(default)]
+    pub files: u32,
+    /// Running and not closed: left untouched, not counted in `freed`
+    pub skipped: bool,
+    /// Files that could not be deleted
+    #[serde(default)]
+    pub errors: Vec<String>,
+    /// Detailed preview: biggest files of `freed`, largest first
+    #[serde(default)]
+    pub largest_files: Vec<ScanDetail>,
+}
+
+/// Message recorded for a browser skipped because it is running
+pub fn skipped_message(browser: &str) -> String {
+    format!("{} en cours d'exécution — cache ignoré", browser)
+}
+
+/// A profile and its existing cache folders
+struct BrowserProfile {
+    browser: Browser,
+    name: String,
+    cache_dirs: Vec<PathBuf>,
+}
+
+/// Existing cache folders of the profile `dir`, empty when it is not a profile
+fn profile_cache_dirs(browser: Browser, dir: &Path) -> Vec<PathBuf> {
+    let names = if browser.is_firefox() { FIREFOX_CACHE_DIRS } else { CHROMIUM_CACHE_DIRS };
+    names.iter().map(|name| dir.join(name)).filter(|path| path.is_dir()).collect()
+}
+
+/// Profiles of `browser` that have a cache, in every profile root
+fn find_profiles(browser: Browser) -> Vec<BrowserProfile> {
+    let mut profiles: Vec<BrowserProfile> = Vec::new();
+    for root in browser.profile_roots() {
+        let Ok(entries) = fs::read_dir(&root) else {
+            continue;
+        };
+        let mut candidates: Vec<(String, PathBuf)> = entries
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+            .map(|entry| (entry.file_name().to_string_lossy().to_string(), entry.path()))
+            .collect();
+        // Opera : la racine est elle-même le profil
+        if !browser.is_firefox() {
+            candidates.push(("Default".to_string(), root.clone()));
+        }
+
+        for (name, dir) in candidates {
+            let cache_dirs = profile_cache_dirs(browser, &dir);
+            if cache_dirs.is_empty() {
+                continue;
             }
-            let cache_dir_variant2 = path.join("startupCache"); // Check for startupCache (less common for bulk data)
-             if cache_dir_variant2.exists() {
-                total_cleaned += clean_directory(&cache_dir_variant2).await?;
+            // Firefox : cache2 et startupCache d'un même profil sont dans deux racines
+            match profiles.iter_mut().find(|profile| profile.name == name) {
+                Some(profile) => profile.cache_dirs.extend(cache_dirs),
+                None => profiles.push(BrowserProfile { browser, name, cache_dirs }),
             }
         }
     }
-    Ok(total_cleaned)
+    profiles
+}
+
+fn all_profiles() -> Vec<BrowserProfile> {
+    Browser::ALL.into_iter().flat_map(find_profiles).collect()
 }
 
+/// Browsers with at least one profile on this machine
+pub fn installed_browsers() -> Vec<Browser> {
+    Browser::ALL.into_iter().filter(|browser| !find_profiles(*browser).is_empty()).collect()
+}
 
-pub fn get_browser_cache_size() -> Result<u64> {
-    let mut total_size = 0u64;
+/// Lowercased names of the running processes
+fn running_process_names(system: &mut System) -> HashSet<String> {
+    system.refresh_processes();
+    system.processes().values().map(|process| process.name().to_lowercase()).collect()
+}
 
+fn is_running(browser: Browser, running: &HashSet<String>) -> bool {
+    browser.process_names().iter().any(|name| running.contains(*name))
+}
+
+/// Ask the browser to close and wait for it. Returns false when it is still running.
+async fn close_browser(browser: Browser, system: &mut System) -> bool {
+    tracing::info!("🌐 Fermeture de {} avant le nettoyage du cache", browser.name());
+
+    // taskkill sans /F : fermeture normale (WM_CLOSE), les onglets sont restaurés au redémarrage
     #[cfg(target_os = "windows")]
+    for name in browser.process_names() {
+        let _ = std::process::Command::new("taskkill")
+            .args(["/IM", name])
+            .creation_flags(0x08000000) // CREATE_NO_WINDOW
+            .output();
+    }
+    #[cfg(not(target_os = "windows"))]
     {
-        if let Ok(user_profile) = std::env::var("USERPROFILE") {
-            let cache_dirs = vec![
-                format!("{}\\AppData\\Local\\Google\\Chrome\\User Data\\Default\\Cache", user_profile),
-                format!("{}\\AppData\\Local\\Microsoft\\Edge\\User Data\\Default\\Cache", user_profile),
-                format!("{}\\AppData\\Local\\Opera Software\\Opera Stable\\Cache", user_profile),
-            ];
-
-            for cache_dir_str in cache_dirs {
-                let cache_dir = Path::new(&cache_dir_str);
-                if cache_dir.exists() {
-                    total_size += calculate_directory_size(&cache_dir)?;
-                }
+        system.refresh_processes();
+        for process in system.processes().values() {
+            if browser.process_names().contains(&process.name().to_lowercase().as_str()) {
+                process.kill_with(sysinfo::Signal::Term);
             }
+        }
+    }
 
-            // Firefox profiles
-            let firefox_profiles = format!("{}\\AppData\\Local\\Mozilla\\Firefox\\Profiles", user_profile);
-            if Path::new(&firefox_profiles).exists() {
-                total_size += calculate_firefox_cache_size_windows(&Path::new(&firefox_profiles))?;
-            }
+    let mut waited = Duration::ZERO;
+    while waited < CLOSE_TIMEOUT {
+        tokio::time::sleep(CLOSE_POLL_INTERVAL).await;
+        waited += CLOSE_POLL_INTERVAL;
+        if !is_running(browser, &running_process_names(system)) {
+            return true;
         }
     }
-    
-    #[cfg(target_os = "linux")]
-    {
-        if let Some(home_dir) = dirs::home_dir() {
-            let cache_paths = vec![
-                home_dir.join(".cache/google-chrome/Default/Cache"),
-                home_dir.join(".cache/chromium/Default/Cache"),
-                home_dir.join("snap/microsoft-edge-dev/current/.cache/microsoft-edge-dev/Default/Cache"),
-                home_dir.join(".var/app/com.microsoft.Edge/cache/Microsoft/Edge/Default/Cache"),
-            ];
-            for path in cache_paths {
-                if path.exists() {
-                    total_size += calculate_directory_size(&path)?;
-                }
+    tracing::warn!("⚠️ {} ne s'est pas fermé en {}s", browser.name(), CLOSE_TIMEOUT.as_secs());
+    false
+}
+
+/// Clean the cache of every selected browser that is not running (closed first with
+/// `force_close`). One result per detected browser of the selection.
+pub async fn clean_browsers(selection: &BrowserSelection, force_close: bool) -> Result<Vec<BrowserCleanResult>> {
+    let mut results = Vec::new();
+    let mut system = System::new();
+    let running = running_process_names(&mut system);
+    let profiles = all_profiles();
+
+    for browser in Browser::ALL.into_iter().filter(|browser| selection.includes(*browser)) {
+        let browser_profiles: Vec<&BrowserProfile> = profiles.iter().filter(|profile| profile.browser == browser).collect();
+        if browser_profiles.is_empty() {
+            continue;
+        }
+        let mut result = BrowserCleanResult {
+            browser,
+            profiles: browser_profiles.iter().map(|profile| profile.name.clone()).collect(),
+            freed: 0,
+            files: 0,
+            skipped: false,
+            errors: Vec::new(),
+            largest_files: Vec::new(),
+        };
+        let cache_dirs = browser_profiles.iter().flat_map(|profile| &profile.cache_dirs);
+        if is_running(browser, &running) && !(force_close && close_browser(browser, &mut system).await) {
+            tracing::info!("🌐 {}", skipped_message(browser.name()));
+            result.skipped = true;
+            for dir in cache_dirs {
+                result.files += calculate_directory_size(dir)?.1;
             }
-            let firefox_base = home_dir.join(".mozilla/firefox");
-            if firefox_base.exists() {
-                total_size += calculate_firefox_cache_size_linux(&firefox_base)?;
+        } else {
+            let mut cleaned = CategoryResult::default();
+            for dir in cache_dirs {
+                clean_directory(dir, &mut cleaned).await;
             }
+            result.freed = cleaned.bytes_freed;
+            result.files = cleaned.files_deleted;
+            result.errors = cleaned.errors;
+            if cleaned.errors_omitted > 0 {
+                result.errors.push(format!("{} autre(s) fichier(s) non supprimé(s)", cleaned.errors_omitted));
+            }
+            tracing::debug!(
+                "🌐 Cache {} nettoyé ({} profil(s)): {} fichier(s), {} octets",
+                browser.name(),
+                result.profiles.len(),
+                result.files,
+                result.freed
+            );
         }
+        results.push(result);
     }
 
-    Ok(total_size)
+    Ok(results)
 }
 
-fn calculate_directory_size(dir: &Path) -> Result<u64> {
-    let mut total_size = 0u64;
-    
+async fn clean_directory(dir: &Path, result: &mut CategoryResult) {
     for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
         if entry.file_type().is_file() {
             if let Ok(metadata) = entry.metadata() {
-                total_size += metadata.len();
+                match fs::remove_file(entry.path()) {
+                    Ok(()) => {
+                        result.bytes_freed += metadata.len();
+                        result.files_deleted += 1;
+                    }
+                    Err(e) => result.record_error(format!("{} : {}", entry.path().display(), e)),
+                }
             }
         }
     }
-    
-    Ok(total_size)
 }
 
-#[cfg(target_os = "windows")]
-fn calculate_firefox_cache_size_windows(profiles_dir: &Path) -> Result<u64> {
-    let mut total_size = 0u64;
-    for entry in fs::read_dir(profiles_dir)? {
-        let entry = entry?;
-        if entry.file_type()?.is_dir() {
-            let cache_dir = entry.path().join("cache2");
-            if cache_dir.exists() {
-                total_size += calculate_directory_size(&cache_dir)?;
-            }
+/// Cache size of every profile of every detected browser
+pub fn get_browser_cache_sizes() -> Result<Vec<BrowserCache>> {
+    get_browser_cache_sizes_with(|dir| measure_directory(dir, false))
+}
+
+/// `get_browser_cache_sizes`, measuring each cache folder with `measure` (size in
+/// `bytes_freed`, files in `files_deleted`) so that the preview cache can reuse the folders
+/// that did not change
+pub(super) fn get_browser_cache_sizes_with(
+    mut measure: impl FnMut(&Path) -> Result<CategoryResult>,
+) -> Result<Vec<BrowserCache>> {
+    let running = running_process_names(&mut System::new());
+    let mut caches = Vec::new();
+
+    for profile in all_profiles() {
+        let mut measured = CategoryResult::default();
+        for dir in &profile.cache_dirs {
+            measured.add(measure(dir)?);
         }
+        caches.push(BrowserCache {
+            browser: profile.browser,
+            locked: is_running(profile.browser, &running),
+            profile: profile.name,
+            size: measured.bytes_freed,
+            files: measured.files_deleted,
+            largest_files: measured.largest_files,
+        });
     }
-    Ok(total_size)
-}
-
-#[cfg(target_os = "linux")]
-fn calculate_firefox_cache_size_linux(profiles_dir: &Path) -> Result<u64> {
-    let mut total_size = 0u64;
-     for entry in fs::read_dir(profiles_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_dir() && entry.file_name().to_string_lossy().ends_with(".default-release") {
-            let cache_dir_variant1 = path.join("cache2");
-            if cache_dir_variant1.exists() {
-                total_size += calculate_directory_size(&cache_dir_variant1)?;
+
+    Ok(caches)
+}
+
+/// What a cleaning of `selection` can free, per browser. The cache of a running browser is
+/// left out, unless `force_close` will close it first.
+pub fn preview_browsers(selection: &BrowserSelection, force_close: bool) -> Result<Vec<BrowserCleanResult>> {
+    Ok(summarize_caches(get_browser_cache_sizes()?, selection, force_close))
+}
+
+/// Per-browser preview of already measured `caches`, without touching the disk
+pub(super) fn summarize_caches(
+    caches: Vec<BrowserCache>,
+    selection: &BrowserSelection,
+    force_close: bool,
+) -> Vec<BrowserCleanResult> {
+    let mut results: Vec<BrowserCleanResult> = Vec::new();
+    for cache in caches.into_iter().filter(|cache| selection.includes(cache.browser)) {
+        let skipped = cache.locked && !force_close;
+        let index = match results.iter().position(|result| result.browser == cache.browser) {
+            Some(index) => index,
+            None => {
+                results.push(BrowserCleanResult {
+                    browser: cache.browser,
+                    profiles: Vec::new(),
+                    freed: 0,
+                    files: 0,
+                    skipped,
+                    errors: Vec::new(),
+                    largest_files: Vec::new(),
+                });
+                results.len() - 1
             }
-            let cache_dir_variant2 = path.join("startupCache");
-             if cache_dir_variant2.exists() {
-                total_size += calculate_directory_size(&cache_dir_variant2)?;
+        };
+        let result = &mut results[index];
+        result.profiles.push(cache.profile);
+        result.files += cache.files;
+        if !skipped {
+            result.freed += cache.size;
+            super::largest_files::merge(&mut result.largest_files, cache.largest_files);
+        }
+    }
+    results
+}
+
+/// Size and number of the files under `dir`
+pub(super) fn calculate_directory_size(dir: &Path) -> Result<(u64, u32)> {
+    let measured = measure_directory(dir, false)?;
+    Ok((measured.bytes_freed, measured.files_deleted))
+}
+
+/// Size (`bytes_freed`) and number (`files_deleted`) of the files under `dir`, with the
+/// largest ones when `detailed`
+pub(super) fn measure_directory(dir: &Path, detailed: bool) -> Result<CategoryResult> {
+    let mut result = CategoryResult::default();
+    let mut largest = LargestFiles::new(DiskCategory::BrowserCache, detailed);
+
+    for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+        if entry.file_type().is_file() {
+            if let Ok(metadata) = entry.metadata() {
+                result.bytes_freed += metadata.len();
+                result.files_deleted += 1;
+                largest.push(entry.path(), &metadata);
             }
         }
     }
-    Ok(total_size)
+
+    result.largest_files = largest.into_details();
+    Ok(result)
 }
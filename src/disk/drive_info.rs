@@ -0,0 +1,314 @@
+//! # Drives
+//!
+//! Fixed and removable drives with their media type, read from the storage driver with
+//! `IOCTL_STORAGE_QUERY_PROPERTY`: a drive without seek penalty is an SSD, the TRIM support
+//! deciding when the driver does not report the seek penalty. A fixed drive on the USB bus is
+//! treated as removable.
+//!
+//! `optimize` runs `defrag` (administrator rights): a retrim on SSDs, an analysis only on HDDs
+//! (the defragmentation itself is long and left to the Windows schedule), nothing on removable
+//! drives.
+
+use anyhow::{anyhow, Result};
+
+/// Fragmentation from which a defragmentation is recommended
+const FRAGMENTATION_THRESHOLD: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaType {
+    Ssd,
+    Hdd,
+    Removable,
+    Unknown,
+}
+
+impl MediaType {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MediaType::Ssd => "SSD",
+            MediaType::Hdd => "HDD",
+            MediaType::Removable => "Amovible",
+            MediaType::Unknown => "Inconnu",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptimizeMode {
+    /// `defrag /L`
+    Retrim,
+    /// `defrag /A`, nothing is moved
+    Analyze,
+}
+
+#[derive(Debug, Clone)]
+pub struct DriveInfo {
+    /// "C:"
+    pub letter: String,
+    pub media_type: MediaType,
+    /// TRIM support reported by the driver, `None` when not reported
+    pub trim_enabled: Option<bool>,
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+    /// Drive Windows is installed on
+    pub is_system: bool,
+}
+
+impl DriveInfo {
+    pub fn used_fraction(&self) -> f32 {
+        if self.total_bytes == 0 {
+            return 0.0;
+        }
+        1.0 - self.free_bytes as f32 / self.total_bytes as f32
+    }
+
+    /// `None` when the drive must not be optimized (removable or unknown media)
+    pub fn optimize_mode(&self) -> Option<OptimizeMode> {
+        match self.media_type {
+            MediaType::Ssd => Some(OptimizeMode::Retrim),
+            MediaType::Hdd => Some(OptimizeMode::Analyze),
+            MediaType::Removable | MediaType::Unknown => None,
+        }
+    }
+
+    /// Advice shown under the drive
+    pub fn health_hints(&self) -> Vec<String> {
+        let mut hints = Vec::new();
+        if self.total_bytes > 0 && self.free_bytes * 10 < self.total_bytes {
+            hints.push("Moins de 10 % d'espace libre : les écritures et les mises à jour ralentissent".to_string());
+        }
+        if self.media_type == MediaType::Ssd && self.trim_enabled == Some(false) {
+            hints.push("TRIM désactivé : les performances du SSD baissent avec le temps".to_string());
+        }
+        if self.media_type == MediaType::Hdd && self.is_system {
+            hints.push("Windows est installé sur un disque dur : un SSD réduit nettement les temps de chargement".to_string());
+        }
+        hints
+    }
+}
+
+/// Outcome of `optimize`, parsed from the `defrag` output
+#[derive(Debug, Clone)]
+pub struct OptimizeReport {
+    pub letter: String,
+    pub mode: OptimizeMode,
+    /// Analysis only
+    pub fragmented_percent: Option<u32>,
+    pub recommendation: Option<String>,
+    /// "key = value" lines of the output (sizes, fragmentation...), localized by Windows
+    pub details: Vec<String>,
+}
+
+/// Whether `optimize` needs administrator rights (`defrag` refuses to run otherwise)
+pub fn requires_elevation() -> bool {
+    cfg!(target_os = "windows")
+}
+
+#[cfg(target_os = "windows")]
+pub fn list_drives() -> Result<Vec<DriveInfo>> {
+    use windows_sys::Win32::Storage::FileSystem::{GetDiskFreeSpaceExW, GetDriveTypeW, GetLogicalDrives};
+
+    // Valeurs de GetDriveTypeW
+    const DRIVE_REMOVABLE: u32 = 2;
+    const DRIVE_FIXED: u32 = 3;
+
+    let mask = unsafe { GetLogicalDrives() };
+    if mask == 0 {
+        return Err(anyhow!("Liste des lecteurs illisible: {}", std::io::Error::last_os_error()));
+    }
+    let system_drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+
+    let mut drives = Vec::new();
+    for index in 0..26u8 {
+        if mask & (1 << index) == 0 {
+            continue;
+        }
+        let letter = format!("{}:", (b'A' + index) as char);
+        let root: Vec<u16> = format!("{}\\", letter).encode_utf16().chain(std::iter::once(0)).collect();
+        let (media_type, trim_enabled) = match unsafe { GetDriveTypeW(root.as_ptr()) } {
+            DRIVE_FIXED => query_media_type(&letter),
+            DRIVE_REMOVABLE => (MediaType::Removable, None),
+            // Lecteurs réseau, optiques et disques RAM
+            _ => continue,
+        };
+
+        let (mut free_bytes, mut total_bytes) = (0u64, 0u64);
+        let ok = unsafe { GetDiskFreeSpaceExW(root.as_ptr(), &mut free_bytes, &mut total_bytes, std::ptr::null_mut()) };
+        // Lecteur de cartes sans carte
+        if ok == 0 {
+            continue;
+        }
+
+        drives.push(DriveInfo {
+            is_system: letter.eq_ignore_ascii_case(&system_drive),
+            letter,
+            media_type,
+            trim_enabled,
+            total_bytes,
+            free_bytes,
+        });
+    }
+    Ok(drives)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn list_drives() -> Result<Vec<DriveInfo>> {
+    Err(anyhow!("Détection des lecteurs disponible uniquement sous Windows"))
+}
+
+/// Media type and TRIM support of a fixed drive
+#[cfg(target_os = "windows")]
+fn query_media_type(letter: &str) -> (MediaType, Option<bool>) {
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::Storage::FileSystem::{CreateFileW, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING};
+    use windows_sys::Win32::System::Ioctl::{
+        StorageDeviceProperty, StorageDeviceSeekPenaltyProperty, StorageDeviceTrimProperty, DEVICE_SEEK_PENALTY_DESCRIPTOR,
+        DEVICE_TRIM_DESCRIPTOR, STORAGE_DEVICE_DESCRIPTOR,
+    };
+
+    // STORAGE_BUS_TYPE::BusTypeUsb
+    const BUS_TYPE_USB: i32 = 7;
+
+    let path: Vec<u16> = format!("\\\\.\\{}", letter).encode_utf16().chain(std::iter::once(0)).collect();
+    unsafe {
+        // Aucun droit d'accès demandé : suffisant pour les requêtes de propriétés, sans être administrateur
+        let device = CreateFileW(
+            path.as_ptr(),
+            0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE,
+            std::ptr::null(),
+            OPEN_EXISTING,
+            0,
+            std::ptr::null_mut(),
+        );
+        if device == INVALID_HANDLE_VALUE {
+            tracing::warn!("⚠️ Lecteur {} inaccessible: {}", letter, std::io::Error::last_os_error());
+            return (MediaType::Unknown, None);
+        }
+
+        let descriptor = query_property::<STORAGE_DEVICE_DESCRIPTOR>(device, StorageDeviceProperty);
+        let seek_penalty = query_property::<DEVICE_SEEK_PENALTY_DESCRIPTOR>(device, StorageDeviceSeekPenaltyProperty);
+        let trim = query_property::<DEVICE_TRIM_DESCRIPTOR>(device, StorageDeviceTrimProperty);
+        CloseHandle(device);
+
+        let trim_enabled = trim.map(|trim| trim.TrimEnabled != 0);
+        let is_usb = descriptor.is_some_and(|descriptor| descriptor.BusType as i32 == BUS_TYPE_USB);
+        let media_type = if is_usb {
+            MediaType::Removable
+        } else {
+            match (seek_penalty.map(|penalty| penalty.IncursSeekPenalty != 0), trim_enabled) {
+                (Some(false), _) => MediaType::Ssd,
+                (Some(true), _) => MediaType::Hdd,
+                (None, Some(true)) => MediaType::Ssd,
+                (None, _) => MediaType::Unknown,
+            }
+        };
+        (media_type, trim_enabled)
+    }
+}
+
+/// `IOCTL_STORAGE_QUERY_PROPERTY` standard query; `None` when the driver does not report it
+#[cfg(target_os = "windows")]
+unsafe fn query_property<T: Copy>(
+    device: windows_sys::Win32::Foundation::HANDLE,
+    property: windows_sys::Win32::System::Ioctl::STORAGE_PROPERTY_ID,
+) -> Option<T> {
+    use windows_sys::Win32::System::Ioctl::{PropertyStandardQuery, IOCTL_STORAGE_QUERY_PROPERTY, STORAGE_PROPERTY_QUERY};
+    use windows_sys::Win32::System::IO::DeviceIoControl;
+
+    let query = STORAGE_PROPERTY_QUERY {
+        PropertyId: property,
+        QueryType: PropertyStandardQuery,
+        AdditionalParameters: [0],
+    };
+    // Assez grand pour les données variables qui suivent STORAGE_DEVICE_DESCRIPTOR
+    let mut buffer = [0u64; 128];
+    let mut returned = 0u32;
+    let ok = DeviceIoControl(
+        device,
+        IOCTL_STORAGE_QUERY_PROPERTY,
+        &query as *const STORAGE_PROPERTY_QUERY as *const std::ffi::c_void,
+        std::mem::size_of::<STORAGE_PROPERTY_QUERY>() as u32,
+        buffer.as_mut_ptr() as *mut std::ffi::c_void,
+        std::mem::size_of_val(&buffer) as u32,
+        &mut returned,
+        std::ptr::null_mut(),
+    );
+    (ok != 0 && returned as usize >= std::mem::size_of::<T>()).then(|| std::ptr::read_unaligned(buffer.as_ptr() as *const T))
+}
+
+/// Retrim an SSD or analyze an HDD with `defrag`; removable drives are refused
+pub fn optimize(drive: &DriveInfo) -> Result<OptimizeReport> {
+    let Some(mode) = drive.optimize_mode() else {
+        return Err(anyhow!("{} : optimisation non disponible pour un lecteur {}", drive.letter, drive.media_type.label().to_lowercase()));
+    };
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+
+        let flag = match mode {
+            OptimizeMode::Retrim => "/L",
+            OptimizeMode::Analyze => "/A",
+        };
+        tracing::info!("💽 defrag {} {}", drive.letter, flag);
+        let output = std::process::Command::new("defrag")
+            .args([drive.letter.as_str(), flag, "/V"])
+            .creation_flags(0x08000000) // CREATE_NO_WINDOW
+            .output()?;
+        // defrag écrit ses erreurs sur stdout
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        if !output.status.success() {
+            let message = stdout.lines().rev().find(|line| !line.trim().is_empty()).unwrap_or("").trim().to_string();
+            return Err(anyhow!("defrag {} {} a échoué: {}", drive.letter, flag, message));
+        }
+        Ok(parse_defrag_output(&drive.letter, mode, &stdout))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = mode;
+        Err(anyhow!("Optimisation des lecteurs disponible uniquement sous Windows"))
+    }
+}
+
+/// The labels are localized: only the "label = value" layout and the percentages are relied on
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn parse_defrag_output(letter: &str, mode: OptimizeMode, output: &str) -> OptimizeReport {
+    let details: Vec<String> = output
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.contains('='))
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect();
+
+    // "Total fragmented space = 3%" / "Espace fragmenté total = 3%"
+    let fragmented_percent = match mode {
+        OptimizeMode::Analyze => details
+            .iter()
+            .filter(|line| line.to_lowercase().contains("fragment"))
+            .find_map(|line| {
+                let value = line.split_once('=')?.1.trim().strip_suffix('%')?;
+                value.trim().parse().ok()
+            }),
+        OptimizeMode::Retrim => None,
+    };
+
+    let recommendation = match (mode, fragmented_percent) {
+        (OptimizeMode::Retrim, _) => Some("Retrim terminé : rien d'autre à faire sur un SSD".to_string()),
+        (OptimizeMode::Analyze, Some(percent)) if percent >= FRAGMENTATION_THRESHOLD => Some(format!(
+            "{} % fragmenté : lancez une défragmentation depuis « Optimiser les lecteurs » en dehors des sessions de jeu",
+            percent
+        )),
+        (OptimizeMode::Analyze, Some(percent)) => Some(format!("{} % fragmenté : défragmentation inutile", percent)),
+        (OptimizeMode::Analyze, None) => None,
+    };
+
+    OptimizeReport {
+        letter: letter.to_string(),
+        mode,
+        fragmented_percent,
+        recommendation,
+        details,
+    }
+}
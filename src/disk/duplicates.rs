@@ -0,0 +1,205 @@
+// Duplicate file finder
+//
+// Files are grouped by size first; only files sharing a size are hashed (BLAKE3, read in
+// chunks so that large captures never sit in memory), in parallel on the rayon pool.
+// Deletion always keeps at least one file of each group, whatever the selection says.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use poll_promise::Promise;
+use rayon::prelude::*;
+use walkdir::WalkDir;
+
+/// Files smaller than this are ignored by default (1 MB)
+pub const DEFAULT_MIN_SIZE: u64 = 1024 * 1024;
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct DuplicateGroup {
+    /// BLAKE3 hash of the content, hexadecimal
+    pub hash: String,
+    /// Size of each file
+    pub size: u64,
+    pub files: Vec<PathBuf>,
+}
+
+impl DuplicateGroup {
+    /// Bytes freed by keeping a single copy
+    pub fn reclaimable(&self) -> u64 {
+        self.size * self.files.len().saturating_sub(1) as u64
+    }
+}
+
+/// Counters updated by the scan thread, read by the UI
+#[derive(Debug, Default)]
+pub struct ScanProgress {
+    pub files_found: AtomicU64,
+    /// Files sharing their size with another one, hence hashed
+    pub files_to_hash: AtomicU64,
+    pub files_hashed: AtomicU64,
+    pub bytes_to_hash: AtomicU64,
+    pub bytes_hashed: AtomicU64,
+}
+
+impl ScanProgress {
+    /// Hashed share of the candidate bytes, 0.0 while the folders are being listed
+    pub fn fraction(&self) -> f32 {
+        let total = self.bytes_to_hash.load(Ordering::Relaxed);
+        if total == 0 {
+            return 0.0;
+        }
+        self.bytes_hashed.load(Ordering::Relaxed) as f32 / total as f32
+    }
+}
+
+pub struct DuplicateScan {
+    pub promise: Promise<Vec<DuplicateGroup>>,
+    pub progress: Arc<ScanProgress>,
+}
+
+/// Downloads and the Windows game captures folder (Videos\Captures), when they exist
+pub fn default_paths() -> Vec<PathBuf> {
+    [dirs::download_dir(), dirs::video_dir().map(|videos| videos.join("Captures"))]
+        .into_iter()
+        .flatten()
+        .filter(|path| path.is_dir())
+        .collect()
+}
+
+/// Find the files of `paths` (recursively) with identical content, biggest savings first
+pub fn scan_duplicates(paths: Vec<PathBuf>, min_size: u64) -> DuplicateScan {
+    let progress = Arc::new(ScanProgress::default());
+    let thread_progress = Arc::clone(&progress);
+    let promise = Promise::spawn_thread("duplicate_scan", move || find_duplicates(&paths, min_size, &thread_progress));
+    DuplicateScan { promise, progress }
+}
+
+fn find_duplicates(paths: &[PathBuf], min_size: u64, progress: &ScanProgress) -> Vec<DuplicateGroup> {
+    tracing::info!("🔍 Recherche de doublons dans {:?} (≥ {} octets)", paths, min_size);
+
+    // Un dossier inclus dans un autre ne doit pas faire compter ses fichiers deux fois
+    let mut seen = HashSet::new();
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    for root in paths {
+        for entry in WalkDir::new(root).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.len() < min_size.max(1) || !seen.insert(entry.path().to_path_buf()) {
+                continue;
+            }
+            progress.files_found.fetch_add(1, Ordering::Relaxed);
+            by_size.entry(metadata.len()).or_default().push(entry.into_path());
+        }
+    }
+
+    let candidates: Vec<(u64, PathBuf)> = by_size
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .flat_map(|(size, files)| files.into_iter().map(move |path| (size, path)))
+        .collect();
+    progress.files_to_hash.store(candidates.len() as u64, Ordering::Relaxed);
+    progress.bytes_to_hash.store(candidates.iter().map(|(size, _)| size).sum(), Ordering::Relaxed);
+
+    let hashed: Vec<(u64, String, PathBuf)> = candidates
+        .into_par_iter()
+        .filter_map(|(size, path)| {
+            let hash = hash_file(&path, progress);
+            progress.files_hashed.fetch_add(1, Ordering::Relaxed);
+            match hash {
+                Ok(hash) => Some((size, hash, path)),
+                Err(e) => {
+                    tracing::debug!("Hachage de {} impossible: {}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect();
+
+    let mut by_hash: HashMap<(u64, String), Vec<PathBuf>> = HashMap::new();
+    for (size, hash, path) in hashed {
+        by_hash.entry((size, hash)).or_default().push(path);
+    }
+    let mut groups: Vec<DuplicateGroup> = by_hash
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .map(|((size, hash), mut files)| {
+            files.sort();
+            DuplicateGroup { hash, size, files }
+        })
+        .collect();
+    groups.sort_by(|a, b| b.reclaimable().cmp(&a.reclaimable()).then_with(|| a.files.cmp(&b.files)));
+
+    tracing::info!(
+        "✅ {} groupes de doublons, {:.1} MB récupérables",
+        groups.len(),
+        groups.iter().map(DuplicateGroup::reclaimable).sum::<u64>() as f64 / 1024.0 / 1024.0
+    );
+    groups
+}
+
+fn hash_file(path: &Path, progress: &ScanProgress) -> std::io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = blake3::Hasher::new();
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        progress.bytes_hashed.fetch_add(read as u64, Ordering::Relaxed);
+    }
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+/// Delete the `selected` files of `groups` and return the bytes freed.
+///
+/// In each group, a file is kept even when all of them are selected, and a group is left
+/// alone when none of its kept files is still there with the scanned size.
+pub fn delete_duplicates(groups: &[DuplicateGroup], selected: &HashSet<PathBuf>) -> u64 {
+    let mut freed = 0u64;
+    for group in groups {
+        let mut to_delete: Vec<&PathBuf> = group.files.iter().filter(|path| selected.contains(*path)).collect();
+        if to_delete.is_empty() {
+            continue;
+        }
+        if to_delete.len() == group.files.len() {
+            tracing::warn!("⚠️ Tous les fichiers du groupe {} sélectionnés : {} conservé", group.hash, group.files[0].display());
+            to_delete.retain(|path| **path != group.files[0]);
+        }
+
+        let kept_intact = group
+            .files
+            .iter()
+            .filter(|path| !to_delete.contains(path))
+            .any(|path| fs::metadata(path).is_ok_and(|metadata| metadata.len() == group.size));
+        if !kept_intact {
+            tracing::warn!("⚠️ Copie conservée du groupe {} modifiée ou absente : groupe ignoré", group.hash);
+            continue;
+        }
+
+        for path in to_delete {
+            // Un fichier modifié depuis l'analyse n'est plus forcément un doublon
+            if !fs::metadata(path).is_ok_and(|metadata| metadata.len() == group.size) {
+                tracing::warn!("⚠️ {} modifié depuis l'analyse : conservé", path.display());
+                continue;
+            }
+            match fs::remove_file(path) {
+                Ok(()) => freed += group.size,
+                Err(e) => tracing::warn!("⚠️ Suppression de {} impossible: {}", path.display(), e),
+            }
+        }
+    }
+
+    tracing::info!("🗑️ Doublons supprimés: {:.1} MB libérés", freed as f64 / 1024.0 / 1024.0);
+    freed
+}
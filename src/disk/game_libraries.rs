@@ -0,0 +1,325 @@
+// Installed games of the Steam, Epic Games and GOG libraries
+//
+// The launchers record the size of each game: Steam in `appmanifest_<appid>.acf`, Epic in its
+// `*.item` manifests. Mods, shader caches and saves written in the install folder after that
+// make the recorded value stale: when the folder changed well after the manifest (see
+// `STALE_TOLERANCE`), the size is measured again. GOG records no size, its folders are always
+// measured. A manifest that cannot be parsed is skipped with a warning.
+
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::utils::game_libraries;
+
+/// Days without play after which a game is suggested for uninstallation
+pub const RECLAIM_AFTER_DAYS: i64 = 90;
+
+/// Gap between the install folder and the manifest above which the recorded size is measured
+/// again: the launcher itself touches both within the same update
+const STALE_TOLERANCE: Duration = Duration::from_secs(24 * 3600);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum GamePlatform {
+    Steam,
+    Epic,
+    Gog,
+}
+
+impl GamePlatform {
+    pub fn label(self) -> &'static str {
+        match self {
+            GamePlatform::Steam => "Steam",
+            GamePlatform::Epic => "Epic Games",
+            GamePlatform::Gog => "GOG",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstalledGame {
+    pub name: String,
+    pub platform: GamePlatform,
+    pub install_dir: PathBuf,
+    pub size_on_disk: u64,
+    /// Only Steam records it
+    pub last_played: Option<DateTime<Local>>,
+}
+
+impl InstalledGame {
+    /// Not played for `RECLAIM_AFTER_DAYS`; unknown when the launcher does not record it
+    pub fn is_reclaim_candidate(&self) -> bool {
+        self.last_played
+            .is_some_and(|played| (Local::now() - played).num_days() >= RECLAIM_AFTER_DAYS)
+    }
+
+    /// "C:" for a Windows path, "/" elsewhere
+    pub fn drive(&self) -> String {
+        match self.install_dir.components().next() {
+            Some(Component::Prefix(prefix)) => prefix.as_os_str().to_string_lossy().to_uppercase(),
+            _ => "/".to_string(),
+        }
+    }
+}
+
+/// Column used to sort the games
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum GameSort {
+    Name,
+    Platform,
+    Size,
+    LastPlayed,
+}
+
+impl GameSort {
+    pub fn compare(self, a: &InstalledGame, b: &InstalledGame) -> Ordering {
+        let name = |game: &InstalledGame| game.name.to_lowercase();
+        match self {
+            GameSort::Name => name(a).cmp(&name(b)),
+            GameSort::Platform => a.platform.cmp(&b.platform).then(name(a).cmp(&name(b))),
+            GameSort::Size => a.size_on_disk.cmp(&b.size_on_disk),
+            GameSort::LastPlayed => a.last_played.cmp(&b.last_played).then(name(a).cmp(&name(b))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GameLibraryScan {
+    /// Largest first
+    pub games: Vec<InstalledGame>,
+    /// Manifests skipped because they could not be read or parsed
+    pub skipped_manifests: Vec<PathBuf>,
+    /// Games whose size was measured instead of read from the manifest
+    pub measured: usize,
+}
+
+impl GameLibraryScan {
+    /// Total size per drive, largest first
+    pub fn totals_by_drive(&self) -> Vec<(String, u64)> {
+        let mut totals: Vec<(String, u64)> = Vec::new();
+        for game in &self.games {
+            let drive = game.drive();
+            match totals.iter_mut().find(|(known, _)| *known == drive) {
+                Some((_, total)) => *total += game.size_on_disk,
+                None => totals.push((drive, game.size_on_disk)),
+            }
+        }
+        totals.sort_by(|a, b| b.1.cmp(&a.1));
+        totals
+    }
+}
+
+/// Games of every library found on this machine. Slow when sizes have to be measured: run it
+/// in a background thread.
+pub fn scan() -> GameLibraryScan {
+    let mut scan = GameLibraryScan::default();
+    scan_steam(&mut scan);
+    scan_epic(&mut scan);
+    scan_gog(&mut scan);
+
+    // Une bibliothèque Steam listée deux fois ne compte pas ses jeux en double
+    let mut unique: Vec<InstalledGame> = Vec::new();
+    for game in std::mem::take(&mut scan.games) {
+        if !unique.iter().any(|known| game_libraries::same_path(&known.install_dir, &game.install_dir)) {
+            unique.push(game);
+        }
+    }
+    unique.sort_by(|a, b| b.size_on_disk.cmp(&a.size_on_disk));
+    scan.games = unique;
+
+    tracing::info!(
+        "🎮 {} jeu(x) installé(s), {} taille(s) recalculée(s), {} manifeste(s) ignoré(s)",
+        scan.games.len(),
+        scan.measured,
+        scan.skipped_manifests.len()
+    );
+    scan
+}
+
+fn scan_steam(scan: &mut GameLibraryScan) {
+    for library in game_libraries::steam_libraries() {
+        let steamapps = library.join("steamapps");
+        let Ok(entries) = fs::read_dir(&steamapps) else {
+            continue;
+        };
+        for manifest in entries.flatten().map(|entry| entry.path()) {
+            let is_app_manifest = manifest.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("acf"))
+                && manifest.file_name().is_some_and(|name| name.to_string_lossy().starts_with("appmanifest_"));
+            if !is_app_manifest {
+                continue;
+            }
+            let Some(app) = fs::read_to_string(&manifest).ok().and_then(|content| parse_app_manifest(&content)) else {
+                tracing::warn!("⚠️ Manifeste Steam illisible ignoré: {}", manifest.display());
+                scan.skipped_manifests.push(manifest);
+                continue;
+            };
+            let install_dir = steamapps.join("common").join(&app.install_dir);
+            // Manifeste d'un jeu en cours de désinstallation ou déplacé
+            if !install_dir.is_dir() {
+                continue;
+            }
+            let size_on_disk = size_from_manifest(scan, &manifest, &install_dir, app.size_on_disk);
+            scan.games.push(InstalledGame {
+                name: app.name,
+                platform: GamePlatform::Steam,
+                install_dir,
+                size_on_disk,
+                last_played: app.last_played.filter(|&secs| secs > 0).and_then(|secs| Local.timestamp_opt(secs, 0).single()),
+            });
+        }
+    }
+}
+
+fn scan_epic(scan: &mut GameLibraryScan) {
+    let Ok(entries) = fs::read_dir(game_libraries::epic_manifests_dir()) else {
+        return;
+    };
+    for manifest in entries.flatten().map(|entry| entry.path()) {
+        if !manifest.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("item")) {
+            continue;
+        }
+        let parsed = fs::read_to_string(&manifest)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .and_then(|item| {
+                let name = item.get("DisplayName")?.as_str()?.to_string();
+                let install_dir = PathBuf::from(item.get("InstallLocation")?.as_str()?);
+                let size = item.get("InstallSize").and_then(serde_json::Value::as_u64);
+                Some((name, install_dir, size))
+            });
+        let Some((name, install_dir, size)) = parsed else {
+            tracing::warn!("⚠️ Manifeste Epic illisible ignoré: {}", manifest.display());
+            scan.skipped_manifests.push(manifest);
+            continue;
+        };
+        if !install_dir.is_dir() {
+            continue;
+        }
+        let size_on_disk = size_from_manifest(scan, &manifest, &install_dir, size);
+        scan.games.push(InstalledGame { name, platform: GamePlatform::Epic, install_dir, size_on_disk, last_played: None });
+    }
+}
+
+/// GOG Galaxy and the offline installers register each game under
+/// `HKLM\SOFTWARE\WOW6432Node\GOG.com\Games\<id>` (`gameName`, `path`)
+#[cfg(target_os = "windows")]
+fn scan_gog(scan: &mut GameLibraryScan) {
+    use crate::utils::registry::{self, HKEY_LOCAL_MACHINE};
+
+    const GOG_GAMES: &str = "SOFTWARE\\WOW6432Node\\GOG.com\\Games";
+    let ids = match registry::list_subkey_names(HKEY_LOCAL_MACHINE, GOG_GAMES) {
+        Ok(ids) => ids,
+        Err(e) => {
+            tracing::warn!("⚠️ Jeux GOG illisibles: {}", e);
+            return;
+        }
+    };
+    for id in ids {
+        let key = format!("{}\\{}", GOG_GAMES, id);
+        let name = registry::read_string(HKEY_LOCAL_MACHINE, &key, "gameName").ok().flatten();
+        let path = registry::read_string(HKEY_LOCAL_MACHINE, &key, "path").ok().flatten();
+        let (Some(name), Some(path)) = (name, path) else {
+            tracing::warn!("⚠️ Entrée GOG incomplète ignorée: {}", key);
+            continue;
+        };
+        let install_dir = PathBuf::from(path);
+        if !install_dir.is_dir() {
+            continue;
+        }
+        scan.measured += 1;
+        scan.games.push(InstalledGame {
+            name,
+            platform: GamePlatform::Gog,
+            size_on_disk: directory_size(&install_dir),
+            install_dir,
+            last_played: None,
+        });
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn scan_gog(_scan: &mut GameLibraryScan) {}
+
+/// Size recorded by the launcher, measured again when missing or stale
+fn size_from_manifest(scan: &mut GameLibraryScan, manifest: &Path, install_dir: &Path, recorded: Option<u64>) -> u64 {
+    match recorded {
+        Some(size) if size > 0 && !is_stale(manifest, install_dir) => size,
+        _ => {
+            scan.measured += 1;
+            directory_size(install_dir)
+        }
+    }
+}
+
+/// The install folder changed more than `STALE_TOLERANCE` after the manifest was written
+fn is_stale(manifest: &Path, install_dir: &Path) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+    let (Some(manifest_time), Some(folder_time)) = (modified(manifest), modified(install_dir)) else {
+        return false;
+    };
+    folder_time
+        .duration_since(manifest_time)
+        .is_ok_and(|gap| gap > STALE_TOLERANCE)
+}
+
+fn directory_size(dir: &Path) -> u64 {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Fields of `appmanifest_<appid>.acf` used here
+struct AppManifest {
+    name: String,
+    install_dir: String,
+    size_on_disk: Option<u64>,
+    /// Unix seconds, 0 when never played
+    last_played: Option<i64>,
+}
+
+/// Top-level fields of the `"AppState" { ... }` block (KeyValues text format). `None` when
+/// the braces do not balance or `name` / `installdir` is missing.
+fn parse_app_manifest(content: &str) -> Option<AppManifest> {
+    let mut depth = 0usize;
+    let mut seen_app_state = false;
+    let (mut name, mut install_dir, mut size_on_disk, mut last_played) = (None, None, None, None);
+
+    for line in content.lines() {
+        let line = line.trim();
+        match line {
+            "{" => depth += 1,
+            "}" => depth = depth.checked_sub(1)?,
+            _ => {
+                let tokens: Vec<&str> = line.split('"').map(str::trim).filter(|t| !t.is_empty()).collect();
+                match (depth, tokens.as_slice()) {
+                    (0, [key]) if key.eq_ignore_ascii_case("AppState") => seen_app_state = true,
+                    (1, [key, value]) => {
+                        let value = value.replace("\\\\", "\\");
+                        match key.to_ascii_lowercase().as_str() {
+                            "name" => name = Some(value),
+                            "installdir" => install_dir = Some(value),
+                            "sizeondisk" => size_on_disk = value.parse().ok(),
+                            "lastplayed" => last_played = value.parse().ok(),
+                            _ => {}
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    if !seen_app_state || depth != 0 {
+        return None;
+    }
+    Some(AppManifest { name: name?, install_dir: install_dir?, size_on_disk, last_played })
+}
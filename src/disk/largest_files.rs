@@ -0,0 +1,100 @@
+// Largest files of a preview
+//
+// "3.4 Go de fichiers temporaires" can be a single stuck installer log. A detailed preview
+// keeps the `LARGEST_FILES` biggest files of each category; a bounded min-heap drops the
+// smaller ones as the walk goes, so the memory stays the same whatever the number of files.
+// The fast path (cleaning, command line) collects nothing.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use super::DiskCategory;
+
+/// Files kept per category
+pub const LARGEST_FILES: usize = 20;
+
+/// A file a cleaning would remove
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanDetail {
+    pub category: DiskCategory,
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: Option<DateTime<Local>>,
+}
+
+/// Biggest files seen by a walk, nothing when created with `detailed` off
+pub(super) struct LargestFiles {
+    category: DiskCategory,
+    limit: usize,
+    heap: BinaryHeap<Reverse<(u64, PathBuf, Option<SystemTime>)>>,
+}
+
+impl LargestFiles {
+    pub(super) fn new(category: DiskCategory, detailed: bool) -> Self {
+        Self { category, limit: if detailed { LARGEST_FILES } else { 0 }, heap: BinaryHeap::new() }
+    }
+
+    pub(super) fn push(&mut self, path: &Path, metadata: &Metadata) {
+        let size = metadata.len();
+        // Plus petit que le plus petit gardé : pas de copie du chemin
+        if self.limit == 0 || (self.heap.len() >= self.limit && self.heap.peek().is_some_and(|smallest| size <= smallest.0 .0)) {
+            return;
+        }
+        if self.heap.len() >= self.limit {
+            self.heap.pop();
+        }
+        self.heap.push(Reverse((size, path.to_path_buf(), metadata.modified().ok())));
+    }
+
+    /// Largest first
+    pub(super) fn into_details(self) -> Vec<ScanDetail> {
+        let category = self.category;
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((size, path, modified))| ScanDetail { category, path, size, modified: modified.map(DateTime::from) })
+            .collect()
+    }
+}
+
+/// Add `other` to `details`, keeping the `LARGEST_FILES` biggest, largest first
+pub(super) fn merge(details: &mut Vec<ScanDetail>, other: Vec<ScanDetail>) {
+    if other.is_empty() {
+        return;
+    }
+    for detail in other {
+        if !details.iter().any(|known| known.path == detail.path) {
+            details.push(detail);
+        }
+    }
+    details.sort_by(|a, b| b.size.cmp(&a.size));
+    details.truncate(LARGEST_FILES);
+}
+
+/// `path` with the user folders replaced by their variable: "%TEMP%\chrome_installer.log"
+pub fn display_path(path: &Path) -> String {
+    #[cfg(target_os = "windows")]
+    let prefixes: Vec<(&str, Option<PathBuf>)> = vec![
+        ("%TEMP%", Some(std::env::temp_dir())),
+        ("%LOCALAPPDATA%", std::env::var_os("LOCALAPPDATA").map(PathBuf::from)),
+        ("%USERPROFILE%", std::env::var_os("USERPROFILE").map(PathBuf::from)),
+    ];
+    #[cfg(not(target_os = "windows"))]
+    let prefixes: Vec<(&str, Option<PathBuf>)> = vec![("~", dirs::home_dir())];
+
+    for (name, prefix) in prefixes {
+        let Some(prefix) = prefix else {
+            continue;
+        };
+        if let Ok(rest) = path.strip_prefix(&prefix) {
+            return Path::new(name).join(rest).display().to_string();
+        }
+    }
+    path.display().to_string()
+}
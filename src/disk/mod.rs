@@ -2,12 +2,26 @@
 pub mod temp_files;
 pub mod browser_cache;
 pub mod thumbnails;
+pub mod os_tweaks;
+pub mod duplicates;
+pub mod space_analyzer;
+pub mod drive_info;
+pub mod preview_cache;
+pub mod largest_files;
+pub mod game_libraries;
 
 use anyhow::Result;
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
+use tracing::Instrument;
 
-#[derive(Debug, Clone)]
+use browser_cache::{BrowserCleanResult, BrowserSelection};
+use largest_files::ScanDetail;
+use temp_files::TempCleanPolicy;
+use thumbnails::LockedThumbnails;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DiskCleaningOptions {
     pub clean_temp_files: bool,
     pub clean_browser_cache: bool,
@@ -16,6 +30,16 @@ pub struct DiskCleaningOptions {
     pub clean_system_cache: bool,
     pub win10_optimizations: bool,
     pub win11_optimizations: bool,
+    /// Close running browsers before cleaning their cache instead of skipping them
+    pub force_close_browsers: bool,
+    /// Browsers whose cache `clean_browser_cache` covers
+    pub browsers: BrowserSelection,
+    /// Age, extensions and paths of the temporary files that are kept
+    pub temp_policy: TempCleanPolicy,
+    /// Thumbnail cache files held open by Explorer
+    pub locked_thumbnails: LockedThumbnails,
+    /// Age under which a preview is reused without touching the disk
+    pub preview_ttl_secs: u64,
 }
 
 impl Default for DiskCleaningOptions {
@@ -28,6 +52,100 @@ impl Default for DiskCleaningOptions {
             clean_system_cache: false,
             win10_optimizations: false,
             win11_optimizations: false,
+            force_close_browsers: false,
+            browsers: BrowserSelection::All,
+            temp_policy: TempCleanPolicy::default(),
+            locked_thumbnails: LockedThumbnails::default(),
+            preview_ttl_secs: 300,
+        }
+    }
+}
+
+/// Errors kept per category; a locked folder can otherwise fail on thousands of files
+const MAX_CATEGORY_ERRORS: usize = 50;
+
+/// Outcome of one cleaning category, or what it would touch for a preview
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CategoryResult {
+    pub bytes_freed: u64,
+    pub files_deleted: u32,
+    /// Left in place on purpose (open by a program, browser running)
+    pub files_skipped: u32,
+    /// First `MAX_CATEGORY_ERRORS` failures
+    pub errors: Vec<String>,
+    /// Failures beyond `errors`
+    pub errors_omitted: u32,
+    /// Preview: part of `bytes_freed` held open by a program, only freed by a reboot or by
+    /// closing it
+    pub locked_bytes: u64,
+    /// Cleaning: files left for Windows to delete at the next reboot, not in `bytes_freed`
+    pub pending_reboot_bytes: u64,
+    /// Detailed preview: biggest files of `bytes_freed`, largest first
+    pub largest_files: Vec<ScanDetail>,
+}
+
+impl CategoryResult {
+    /// Result of a category that failed as a whole
+    fn failed(error: anyhow::Error) -> Self {
+        let mut result = Self::default();
+        result.record_error(format!("{:#}", error));
+        result
+    }
+
+    pub fn record_error(&mut self, message: String) {
+        if self.errors.len() < MAX_CATEGORY_ERRORS {
+            self.errors.push(message);
+        } else {
+            self.errors_omitted += 1;
+        }
+    }
+
+    pub fn error_count(&self) -> u32 {
+        self.errors.len() as u32 + self.errors_omitted
+    }
+
+    pub fn add(&mut self, other: CategoryResult) {
+        self.bytes_freed += other.bytes_freed;
+        self.files_deleted += other.files_deleted;
+        self.files_skipped += other.files_skipped;
+        self.errors_omitted += other.errors_omitted;
+        self.locked_bytes += other.locked_bytes;
+        self.pending_reboot_bytes += other.pending_reboot_bytes;
+        for error in other.errors {
+            self.record_error(error);
+        }
+        largest_files::merge(&mut self.largest_files, other.largest_files);
+    }
+
+    /// Take a listed file deleted on its own out of the counts; `false` when not listed
+    pub fn forget_file(&mut self, path: &std::path::Path, size: u64) -> bool {
+        let Some(index) = self.largest_files.iter().position(|detail| detail.path == path) else {
+            return false;
+        };
+        self.largest_files.remove(index);
+        self.bytes_freed = self.bytes_freed.saturating_sub(size);
+        self.files_deleted = self.files_deleted.saturating_sub(1);
+        true
+    }
+}
+
+/// Cleaning categories reported separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiskCategory {
+    TempFiles,
+    BrowserCache,
+    Thumbnails,
+}
+
+impl DiskCategory {
+    pub const ALL: [DiskCategory; 3] = [DiskCategory::TempFiles, DiskCategory::BrowserCache, DiskCategory::Thumbnails];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DiskCategory::TempFiles => "Fichiers temporaires",
+            DiskCategory::BrowserCache => "Cache des navigateurs",
+            DiskCategory::Thumbnails => "Miniatures",
         }
     }
 }
@@ -36,12 +154,48 @@ impl Default for DiskCleaningOptions {
 pub struct DiskCleaningResults {
     pub start_time: DateTime<Local>,
     pub end_time: Option<DateTime<Local>>,
+    /// Derived: categories plus `duplicates_cleaned`
     pub total_space_freed: u64,
+    /// Derived from `temp_files`
     pub temp_files_cleaned: u64,
+    /// Derived from `browser_cache`
     pub cache_cleaned: u64,
+    /// Derived from `thumbnails`
     pub thumbnails_cleaned: u64,
+    /// Duplicate files deleted from the Optimization tab
+    #[serde(default)]
+    pub duplicates_cleaned: u64,
+    /// Derived: files deleted by every category (or duplicates deleted)
     pub files_processed: u32,
+    /// Derived from `temp_files`: temporary files kept because another program has them open
+    #[serde(default)]
+    pub skipped_in_use: u32,
+    /// Derived: deleted at the next reboot, by every category
+    #[serde(default)]
+    pub pending_reboot_bytes: u64,
+    /// Derived: errors of every category prefixed with its label, then the general ones
     pub errors: Vec<String>,
+    #[serde(default)]
+    pub temp_files: CategoryResult,
+    #[serde(default)]
+    pub browser_cache: CategoryResult,
+    #[serde(default)]
+    pub thumbnails: CategoryResult,
+    /// Running browsers whose cache was skipped (cleaning) or is not counted (preview)
+    #[serde(default)]
+    pub skipped_browsers: Vec<String>,
+    /// Detail of `cache_cleaned`, one entry per detected browser of the selection
+    #[serde(default)]
+    pub browsers: Vec<BrowserCleanResult>,
+    /// Derived: biggest files of each category for a detailed preview, largest first
+    #[serde(default)]
+    pub largest_files: Vec<ScanDetail>,
+    /// Dry run: the sizes are estimates and nothing was deleted
+    #[serde(default)]
+    pub simulated: bool,
+    /// Preview: time of the oldest folder scan the sizes come from, see `preview_cache`
+    #[serde(default)]
+    pub estimated_at: Option<DateTime<Local>>,
     pub is_completed: bool,
     pub duration: Option<std::time::Duration>,
 }
@@ -55,13 +209,104 @@ impl DiskCleaningResults {
             temp_files_cleaned: 0,
             cache_cleaned: 0,
             thumbnails_cleaned: 0,
+            duplicates_cleaned: 0,
             files_processed: 0,
+            skipped_in_use: 0,
+            pending_reboot_bytes: 0,
             errors: Vec::new(),
+            temp_files: CategoryResult::default(),
+            browser_cache: CategoryResult::default(),
+            thumbnails: CategoryResult::default(),
+            skipped_browsers: Vec::new(),
+            browsers: Vec::new(),
+            largest_files: Vec::new(),
+            simulated: false,
+            estimated_at: None,
             is_completed: false,
             duration: None,
         }
     }
 
+    pub fn category(&self, category: DiskCategory) -> &CategoryResult {
+        match category {
+            DiskCategory::TempFiles => &self.temp_files,
+            DiskCategory::BrowserCache => &self.browser_cache,
+            DiskCategory::Thumbnails => &self.thumbnails,
+        }
+    }
+
+    fn category_mut(&mut self, category: DiskCategory) -> &mut CategoryResult {
+        match category {
+            DiskCategory::TempFiles => &mut self.temp_files,
+            DiskCategory::BrowserCache => &mut self.browser_cache,
+            DiskCategory::Thumbnails => &mut self.thumbnails,
+        }
+    }
+
+    /// Update the totals after `detail` was deleted with `delete_scanned_file`
+    pub fn forget_file(&mut self, detail: &ScanDetail) {
+        self.category_mut(detail.category).forget_file(&detail.path, detail.size);
+        if let Some(browser) = self.browsers.iter_mut().find(|browser| browser.largest_files.contains(detail)) {
+            browser.largest_files.retain(|known| known != detail);
+            browser.freed = browser.freed.saturating_sub(detail.size);
+            browser.files = browser.files.saturating_sub(1);
+        }
+        self.derive_totals();
+    }
+
+    /// Record the per-browser outcome and the browser category derived from it
+    fn set_browsers(&mut self, browsers: Vec<BrowserCleanResult>) {
+        self.browser_cache = CategoryResult::default();
+        for browser in &browsers {
+            let mut result = CategoryResult {
+                bytes_freed: browser.freed,
+                largest_files: browser.largest_files.clone(),
+                errors: browser.errors.iter().map(|error| format!("{} : {}", browser.browser.name(), error)).collect(),
+                ..Default::default()
+            };
+            if browser.skipped {
+                result.files_skipped = browser.files;
+            } else {
+                result.files_deleted = browser.files;
+            }
+            self.browser_cache.add(result);
+        }
+        self.skipped_browsers = browsers
+            .iter()
+            .filter(|result| result.skipped)
+            .map(|result| result.browser.name().to_string())
+            .collect();
+        self.browsers = browsers;
+    }
+
+    /// Fill the fields kept for compatibility (history, command line, older reports) from
+    /// the categories
+    fn derive_totals(&mut self) {
+        self.temp_files_cleaned = self.temp_files.bytes_freed;
+        self.cache_cleaned = self.browser_cache.bytes_freed;
+        self.thumbnails_cleaned = self.thumbnails.bytes_freed;
+        self.skipped_in_use = self.temp_files.files_skipped;
+        self.total_space_freed = self.temp_files_cleaned + self.cache_cleaned + self.thumbnails_cleaned + self.duplicates_cleaned;
+        self.files_processed = DiskCategory::ALL.iter().map(|category| self.category(*category).files_deleted).sum();
+        self.pending_reboot_bytes = DiskCategory::ALL.iter().map(|category| self.category(*category).pending_reboot_bytes).sum();
+
+        self.largest_files = DiskCategory::ALL
+            .iter()
+            .flat_map(|category| self.category(*category).largest_files.iter().cloned())
+            .collect();
+        self.largest_files.sort_by(|a, b| b.size.cmp(&a.size));
+
+        let mut errors = Vec::new();
+        for category in DiskCategory::ALL {
+            let result = self.category(category);
+            errors.extend(result.errors.iter().map(|error| format!("{} : {}", category.label(), error)));
+            if result.errors_omitted > 0 {
+                errors.push(format!("{} : {} autre(s) erreur(s)", category.label(), result.errors_omitted));
+            }
+        }
+        self.errors = errors;
+    }
+
     pub fn complete(&mut self) {
         self.end_time = Some(Local::now());
         self.is_completed = true;
@@ -73,103 +318,141 @@ impl DiskCleaningResults {
     }
 }
 
-pub async fn clean_disk_with_options(options: DiskCleaningOptions) -> Result<DiskCleaningResults> {
+/// Whether disk cleaning needs administrator rights (user temp/cache folders do not)
+pub fn requires_elevation() -> bool {
+    false
+}
+
+/// Clean what `options` selects. With `dry_run`, only the preview scan runs and its
+/// results come back flagged `simulated`.
+pub async fn clean_disk_with_options(options: DiskCleaningOptions, dry_run: bool) -> Result<DiskCleaningResults> {
+    if dry_run {
+        tracing::info!("🧪 Simulation du nettoyage de disque : aucun fichier supprimé");
+        let mut results = scan_disk_with_options(options, false, false)?;
+        results.simulated = true;
+        return Ok(results);
+    }
+
+    // Span attaché au futur : il suit la tâche d'un point d'attente à l'autre
+    let started = std::time::Instant::now();
+    let span = tracing::info_span!("disk_clean", freed_bytes = tracing::field::Empty, duration_ms = tracing::field::Empty);
+    let results = clean_selected_categories(options).instrument(span.clone()).await;
+    let duration_ms = started.elapsed().as_millis() as u64;
+    span.record("freed_bytes", results.total_space_freed);
+    span.record("duration_ms", duration_ms);
+    span.in_scope(|| {
+        tracing::info!("🧹 Nettoyage de disque terminé : {} octets libérés ({} ms)", results.total_space_freed, duration_ms)
+    });
+    Ok(results)
+}
+
+async fn clean_selected_categories(options: DiskCleaningOptions) -> DiskCleaningResults {
     let mut results = DiskCleaningResults::new();
 
     // Clean temporary files if selected
     if options.clean_temp_files {
-        match temp_files::clean_temp_files().await {
-            Ok(cleaned) => {
-                results.temp_files_cleaned = cleaned;
-                results.total_space_freed += cleaned;
-                println!("Fichiers temporaires nettoyés: {} bytes", cleaned);
-            }
-            Err(e) => {
-                results.errors.push(format!("Erreur nettoyage fichiers temporaires: {}", e));
-                println!("Erreur lors du nettoyage des fichiers temporaires: {}", e);
-            }
-        }
+        results.temp_files = temp_files::clean_temp_files(&options.temp_policy).await.unwrap_or_else(CategoryResult::failed);
     }
 
     // Clean browser cache if selected
     if options.clean_browser_cache {
-        match browser_cache::clean_browser_cache().await {
-            Ok(cleaned) => {
-                results.cache_cleaned = cleaned;
-                results.total_space_freed += cleaned;
-                println!("Cache navigateur nettoyé: {} bytes", cleaned);
-            }
-            Err(e) => {
-                results.errors.push(format!("Erreur nettoyage cache navigateur: {}", e));
-                println!("Erreur lors du nettoyage du cache navigateur: {}", e);
-            }
+        match browser_cache::clean_browsers(&options.browsers, options.force_close_browsers).await {
+            Ok(browsers) => results.set_browsers(browsers),
+            Err(e) => results.browser_cache = CategoryResult::failed(e),
         }
     }
 
     // Clean thumbnails if selected
     if options.clean_thumbnails {
-        match thumbnails::clean_thumbnails().await {
-            Ok(cleaned) => {
-                results.thumbnails_cleaned = cleaned;
-                results.total_space_freed += cleaned;
-                println!("Miniatures nettoyées: {} bytes", cleaned);
-            }
-            Err(e) => {
-                results.errors.push(format!("Erreur nettoyage miniatures: {}", e));
-                println!("Erreur lors du nettoyage des miniatures: {}", e);
-            }
-        }
+        results.thumbnails = thumbnails::clean_thumbnails(options.locked_thumbnails).await.unwrap_or_else(CategoryResult::failed);
     }
 
     // TODO: Ajouter support pour recycle_bin et system_cache quand options sélectionnées
     if options.clean_recycle_bin {
-        println!("Nettoyage de la corbeille (non implémenté)");
+        tracing::debug!("🗑️ Nettoyage de la corbeille non implémenté");
     }
     
     if options.clean_system_cache {
-        println!("Nettoyage du cache système (non implémenté)");
+        tracing::debug!("🗑️ Nettoyage du cache système non implémenté");
     }
 
+    // Les tailles en cache ne correspondent plus au disque
+    preview_cache::invalidate();
+
+    results.derive_totals();
     results.complete();
-    println!("Nettoyage de disque terminé. Total libéré: {} bytes", results.total_space_freed);
-    Ok(results)
+    results
 }
 
 pub async fn clean_disk() -> Result<DiskCleaningResults> {
-    clean_disk_with_options(DiskCleaningOptions::default()).await
+    clean_disk_with_options(DiskCleaningOptions::default(), false).await
 }
 
 // Get disk cleaning preview without actually cleaning
 pub fn get_disk_cleaning_preview() -> Result<DiskCleaningResults> {
-    scan_disk_with_options(DiskCleaningOptions::default())
+    scan_disk_with_options(DiskCleaningOptions::default(), false, false)
 }
 
-// Scan disk to get cleaning preview with options without actually cleaning
-pub fn scan_disk_with_options(options: DiskCleaningOptions) -> Result<DiskCleaningResults> {
-    let mut results = DiskCleaningResults::new();
-    
-    // Get size estimates without cleaning based on options
-    if options.clean_temp_files {
-        if let Ok(temp_size) = temp_files::get_temp_file_size() {
-            results.temp_files_cleaned = temp_size;
-            results.total_space_freed += temp_size;
-        }
-    }
-    
-    if options.clean_browser_cache {
-        if let Ok(cache_size) = browser_cache::get_browser_cache_size() {
-            results.cache_cleaned = cache_size;
-            results.total_space_freed += cache_size;
-        }
-    }
-    
-    if options.clean_thumbnails {
-        if let Ok(thumbnails_size) = thumbnails::get_thumbnails_size() {
-            results.thumbnails_cleaned = thumbnails_size;
-            results.total_space_freed += thumbnails_size;
-        }
+// Scan disk to get cleaning preview with options without actually cleaning. Recent scans are
+// reused and only the changed folders are walked again, unless `force` (see `preview_cache`).
+// `detailed` also lists the largest files of each category (see `largest_files`).
+pub fn scan_disk_with_options(options: DiskCleaningOptions, force: bool, detailed: bool) -> Result<DiskCleaningResults> {
+    Ok(preview_cache::scan(&options, force, detailed))
+}
+
+/// Delete one file listed by a detailed preview, outside of a cleaning
+pub fn delete_scanned_file(detail: &ScanDetail) -> Result<()> {
+    std::fs::remove_file(&detail.path)
+        .map_err(|e| anyhow::anyhow!("Suppression de {} impossible: {}", detail.path.display(), e))?;
+    preview_cache::forget_file(detail);
+    tracing::info!("🗑️ {} supprimé ({} octets)", detail.path.display(), detail.size);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn dry_run_reports_the_bytes_but_deletes_nothing() {
+        let dir = TestDir::new("disk_dry_run");
+        // Plus vieux que la limite d'âge : seuls ces fichiers (et rien de récent ailleurs dans
+        // les dossiers temporaires) sont concernés si la simulation supprimait quand même
+        let ancient = SystemTime::UNIX_EPOCH + Duration::from_secs(24 * 3600);
+        let files: Vec<PathBuf> = ["old.tmp", "cache/nested/old.bin", "logs/old.log"]
+            .iter()
+            .map(|relative| {
+                let path = dir.0.join(relative);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(&path, vec![0u8; 4096]).unwrap();
+                fs::File::options().write(true).open(&path).unwrap().set_modified(ancient).unwrap();
+                path
+            })
+            .collect();
+
+        let options = DiskCleaningOptions {
+            clean_temp_files: true,
+            clean_browser_cache: false,
+            clean_thumbnails: false,
+            temp_policy: TempCleanPolicy {
+                min_age_hours: 40 * 365 * 24,
+                skip_extensions: vec!["dry_run_test".to_string()],
+                skip_patterns: Vec::new(),
+            },
+            ..Default::default()
+        };
+        let results = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(clean_disk_with_options(options, true))
+            .unwrap();
+
+        assert!(results.simulated);
+        assert!(results.temp_files.bytes_freed >= 3 * 4096, "{} octets annoncés", results.temp_files.bytes_freed);
+        assert!(results.total_space_freed >= results.temp_files.bytes_freed);
+        assert!(files.iter().all(|file| file.exists()), "la simulation a supprimé des fichiers");
     }
-    
-    results.complete();
-    Ok(results)
 }
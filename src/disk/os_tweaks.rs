@@ -0,0 +1,442 @@
+//! # Windows 10/11 gaming tweaks
+//!
+//! Reversible system tweaks behind the `win10_optimizations` / `win11_optimizations` options,
+//! applied only on the matching Windows version: Game Mode, GameDVR capture off, hardware
+//! accelerated GPU scheduling and the High Performance power plan. On Windows 11 the VBS/HVCI
+//! state is reported (never changed). Every previous value is written to
+//! `config/os_tweaks_journal.json` so that `restore` can put it back.
+//!
+//! `optimization::audio` and `optimization::windows_gaming` journal their registry changes here
+//! too, tagged with their `TweakSource` so that the gaming mode can undo only its own.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::DiskCleaningOptions;
+
+const JOURNAL_DIR: &str = "config";
+const JOURNAL_FILE: &str = "os_tweaks_journal.json";
+/// `SCHEME_MIN`: built-in High Performance plan
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const HIGH_PERFORMANCE_SCHEME: &str = "8c5e7fda-e8bf-4a96-9a85-a6e23a8c635c";
+
+/// The journal is loaded, changed and saved from the UI, gaming mode and profile threads
+static JOURNAL_LOCK: Mutex<()> = Mutex::new(());
+
+/// Held for the whole load / change / save of the journal
+pub(crate) fn lock_journal() -> MutexGuard<'static, ()> {
+    JOURNAL_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RegistryRoot {
+    CurrentUser,
+    LocalMachine,
+}
+
+/// DWORD value set by a tweak
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+struct RegistryTweak {
+    label: &'static str,
+    root: RegistryRoot,
+    path: &'static str,
+    name: &'static str,
+    value: u32,
+    /// Oldest build supporting the setting
+    min_build: u32,
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const REGISTRY_TWEAKS: &[RegistryTweak] = &[
+    RegistryTweak {
+        label: "Mode Jeu",
+        root: RegistryRoot::CurrentUser,
+        path: "Software\\Microsoft\\GameBar",
+        name: "AutoGameModeEnabled",
+        value: 1,
+        min_build: 0,
+    },
+    RegistryTweak {
+        label: "Capture GameDVR désactivée",
+        root: RegistryRoot::CurrentUser,
+        path: "System\\GameConfigStore",
+        name: "GameDVR_Enabled",
+        value: 0,
+        min_build: 0,
+    },
+    RegistryTweak {
+        label: "Capture Game Bar désactivée",
+        root: RegistryRoot::CurrentUser,
+        path: "Software\\Microsoft\\Windows\\CurrentVersion\\GameDVR",
+        name: "AppCaptureEnabled",
+        value: 0,
+        min_build: 0,
+    },
+    RegistryTweak {
+        label: "Planification GPU accélérée (redémarrage requis)",
+        root: RegistryRoot::LocalMachine,
+        path: "SYSTEM\\CurrentControlSet\\Control\\GraphicsDrivers",
+        name: "HwSchMode",
+        value: 2,
+        min_build: 19041,
+    },
+];
+
+/// Value replaced by a tweak, as found before its first application
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PreviousValue {
+    /// `None` when the value did not exist (it is deleted on restore)
+    Registry { root: RegistryRoot, path: String, name: String, value: Option<u32> },
+    PowerScheme(String),
+}
+
+/// What made a journaled change
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TweakSource {
+    /// "Appliquer les optimisations"
+    #[default]
+    Windows,
+    /// Audio enhancements switched off by hand in the device list
+    Audio,
+    /// Changed for the time of the gaming mode, undone when it ends
+    GamingMode,
+    /// Game Mode or GameDVR switched from the Optimization tab or by a boost profile
+    WindowsGaming,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub label: String,
+    pub previous: PreviousValue,
+    #[serde(default)]
+    pub source: TweakSource,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TweakJournal {
+    pub entries: Vec<JournalEntry>,
+}
+
+impl TweakJournal {
+    pub fn path() -> PathBuf {
+        Path::new(JOURNAL_DIR).join(JOURNAL_FILE)
+    }
+
+    pub fn load() -> Self {
+        match fs::read_to_string(Self::path()) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                tracing::warn!("⚠️ Journal des optimisations invalide, ignoré: {}", e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        fs::create_dir_all(JOURNAL_DIR)?;
+        fs::write(Self::path(), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Keep the value found before the first application: re-applying must not overwrite it
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    fn record(&mut self, label: &str, previous: PreviousValue, source: TweakSource) {
+        let same_target = |entry: &JournalEntry| match (&entry.previous, &previous) {
+            (PreviousValue::Registry { root, path, name, .. }, PreviousValue::Registry { root: r, path: p, name: n, .. }) => {
+                root == r && path == p && name == n
+            }
+            (PreviousValue::PowerScheme(_), PreviousValue::PowerScheme(_)) => true,
+            _ => false,
+        };
+        if !self.entries.iter().any(same_target) {
+            self.entries.push(JournalEntry {
+                label: label.to_string(),
+                previous,
+                source,
+            });
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TweakOutcome {
+    pub label: String,
+    pub success: bool,
+    pub detail: String,
+}
+
+/// Outcome of an application or a restoration, shown in the Optimization tab
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OsTweaksResults {
+    pub outcomes: Vec<TweakOutcome>,
+    /// Read-only findings (VBS/HVCI...) the user may want to act on
+    pub warnings: Vec<String>,
+}
+
+impl OsTweaksResults {
+    /// Details joined when every outcome succeeded, the failures joined otherwise; the
+    /// warnings when nothing was attempted
+    pub fn into_result(self) -> Result<String> {
+        if self.outcomes.is_empty() && !self.warnings.is_empty() {
+            return Err(anyhow::anyhow!(self.warnings.join(", ")));
+        }
+        let (succeeded, failed): (Vec<TweakOutcome>, Vec<TweakOutcome>) = self.outcomes.into_iter().partition(|outcome| outcome.success);
+        let join = |outcomes: Vec<TweakOutcome>| {
+            outcomes.into_iter().map(|outcome| format!("{} : {}", outcome.label, outcome.detail)).collect::<Vec<_>>().join(", ")
+        };
+        if !failed.is_empty() {
+            return Err(anyhow::anyhow!(join(failed)));
+        }
+        Ok(join(succeeded))
+    }
+
+    pub(crate) fn push(&mut self, label: &str, result: Result<String>) {
+        let (success, detail) = match result {
+            Ok(detail) => (true, detail),
+            Err(e) => (false, e.to_string()),
+        };
+        self.outcomes.push(TweakOutcome {
+            label: label.to_string(),
+            success,
+            detail,
+        });
+    }
+}
+
+/// Whether the tweaks are wanted for the running Windows version
+pub fn is_enabled_for_this_os(options: &DiskCleaningOptions) -> bool {
+    let capabilities = crate::os_info::capabilities();
+    (capabilities.win11_tweaks && options.win11_optimizations)
+        || (capabilities.win10_tweaks && options.win10_optimizations)
+}
+
+/// Apply the tweaks of the detected Windows version, journaling the previous values
+pub fn apply(options: &DiskCleaningOptions) -> OsTweaksResults {
+    let mut results = OsTweaksResults::default();
+    if !is_enabled_for_this_os(options) {
+        results.warnings.push(format!(
+            "Aucune optimisation cochée pour {}",
+            crate::os_info::get_windows_version_string()
+        ));
+        return results;
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let (_, _, build) = crate::os_info::get_windows_version_numbers();
+        let _guard = lock_journal();
+        let mut journal = TweakJournal::load();
+
+        for tweak in REGISTRY_TWEAKS.iter().filter(|t| build >= t.min_build) {
+            results.push(tweak.label, apply_registry_tweak(&mut journal, tweak));
+        }
+        results.push("Plan d'alimentation Performances élevées", apply_power_plan(&mut journal));
+
+        if build >= crate::os_info::WIN11_BUILD {
+            results.warnings.extend(vbs_report());
+        }
+
+        if let Err(e) = journal.save() {
+            tracing::error!("❌ Journal des optimisations non enregistré: {}", e);
+            results.warnings.push(format!("Journal non enregistré, restauration impossible : {}", e));
+        }
+        tracing::info!("⚡ {} optimisation(s) Windows traitée(s)", results.outcomes.len());
+    }
+    results
+}
+
+/// Put back every journaled value. Entries that could not be restored stay in the journal.
+pub fn restore() -> OsTweaksResults {
+    let _guard = lock_journal();
+    restore_matching(|_| true)
+}
+
+/// Put back the journaled values `selected` accepts, the others stay in the journal. The
+/// caller holds `lock_journal`.
+pub fn restore_matching(selected: impl Fn(&JournalEntry) -> bool) -> OsTweaksResults {
+    let mut results = OsTweaksResults::default();
+    let journal = TweakJournal::load();
+    if !journal.entries.iter().any(&selected) {
+        results.warnings.push("Aucune optimisation à restaurer".to_string());
+        return results;
+    }
+
+    let mut remaining = TweakJournal::default();
+    for entry in journal.entries {
+        if !selected(&entry) {
+            remaining.entries.push(entry);
+            continue;
+        }
+        let result = restore_entry(&entry.previous);
+        if result.is_err() {
+            remaining.entries.push(entry.clone());
+        }
+        results.push(&entry.label, result);
+    }
+
+    if let Err(e) = remaining.save() {
+        tracing::error!("❌ Journal des optimisations non enregistré: {}", e);
+    }
+    results
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn hkey(root: RegistryRoot) -> windows_sys::Win32::System::Registry::HKEY {
+    match root {
+        RegistryRoot::CurrentUser => crate::utils::registry::HKEY_CURRENT_USER,
+        RegistryRoot::LocalMachine => crate::utils::registry::HKEY_LOCAL_MACHINE,
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn apply_registry_tweak(journal: &mut TweakJournal, tweak: &RegistryTweak) -> Result<String> {
+    set_journaled_dword(journal, TweakSource::Windows, tweak.label, tweak.root, tweak.path, tweak.name, tweak.value)
+}
+
+/// Write a DWORD after journaling the value it replaces
+#[cfg(target_os = "windows")]
+pub(crate) fn set_journaled_dword(
+    journal: &mut TweakJournal,
+    source: TweakSource,
+    label: &str,
+    root: RegistryRoot,
+    path: &str,
+    name: &str,
+    value: u32,
+) -> Result<String> {
+    use crate::utils::registry;
+
+    let previous = registry::read_dword(hkey(root), path, name)?;
+    if previous == Some(value) {
+        return Ok("déjà appliqué".to_string());
+    }
+    journal.record(
+        label,
+        PreviousValue::Registry {
+            root,
+            path: path.to_string(),
+            name: name.to_string(),
+            value: previous,
+        },
+        source,
+    );
+    registry::write_dword(hkey(root), path, name, value)?;
+    Ok(format!(
+        "{} : {} → {}",
+        name,
+        previous.map(|v| v.to_string()).unwrap_or_else(|| "absent".to_string()),
+        value
+    ))
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn apply_power_plan(journal: &mut TweakJournal) -> Result<String> {
+    let active = active_power_scheme()?;
+    if active.eq_ignore_ascii_case(HIGH_PERFORMANCE_SCHEME) {
+        return Ok("déjà actif".to_string());
+    }
+    journal.record(
+        "Plan d'alimentation Performances élevées",
+        PreviousValue::PowerScheme(active.clone()),
+        TweakSource::Windows,
+    );
+    run_powercfg(&["/setactive", HIGH_PERFORMANCE_SCHEME])?;
+    Ok(format!("{} → {}", active, HIGH_PERFORMANCE_SCHEME))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn apply_power_plan(_journal: &mut TweakJournal) -> Result<String> {
+    Err(anyhow::anyhow!("Plans d'alimentation Windows uniquement"))
+}
+
+/// Read-only: disabling VBS/HVCI lowers the security of the machine, so it is only reported
+#[cfg(target_os = "windows")]
+fn vbs_report() -> Vec<String> {
+    use crate::utils::registry;
+
+    let device_guard = "SYSTEM\\CurrentControlSet\\Control\\DeviceGuard";
+    let vbs = registry::read_dword(registry::HKEY_LOCAL_MACHINE, device_guard, "EnableVirtualizationBasedSecurity")
+        .ok()
+        .flatten();
+    let hvci = registry::read_dword(
+        registry::HKEY_LOCAL_MACHINE,
+        &format!("{}\\Scenarios\\HypervisorEnforcedCodeIntegrity", device_guard),
+        "Enabled",
+    )
+    .ok()
+    .flatten();
+
+    let mut warnings = Vec::new();
+    if vbs == Some(1) {
+        warnings.push("VBS (sécurité basée sur la virtualisation) activée : peut coûter quelques FPS, non modifiée".to_string());
+    }
+    if hvci == Some(1) {
+        warnings.push("Intégrité de la mémoire (HVCI) activée : à désactiver manuellement dans Sécurité Windows si souhaité".to_string());
+    }
+    warnings
+}
+
+fn restore_entry(previous: &PreviousValue) -> Result<String> {
+    #[cfg(target_os = "windows")]
+    {
+        use crate::utils::registry;
+
+        match previous {
+            // Clé disparue (périphérique désinstallé...) : ne pas la recréer pour une seule valeur
+            PreviousValue::Registry { root, path, name, .. } if !registry::key_exists(hkey(*root), path)? => {
+                Ok(format!("{} : clé absente, ignoré", name))
+            }
+            PreviousValue::Registry { root, path, name, value: Some(value) } => {
+                registry::write_dword(hkey(*root), path, name, *value)?;
+                Ok(format!("{} = {}", name, value))
+            }
+            PreviousValue::Registry { root, path, name, value: None } => {
+                registry::delete_value(hkey(*root), path, name)?;
+                Ok(format!("{} supprimé", name))
+            }
+            PreviousValue::PowerScheme(guid) => {
+                run_powercfg(&["/setactive", guid])?;
+                Ok(format!("plan {} réactivé", guid))
+            }
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = previous;
+        Err(anyhow::anyhow!("Restauration disponible uniquement sous Windows"))
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn run_powercfg(args: &[&str]) -> Result<String> {
+    use std::os::windows::process::CommandExt;
+
+    let output = std::process::Command::new("powercfg")
+        .args(args)
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .output()?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(anyhow::anyhow!(
+            "powercfg {} a échoué: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stdout).trim()
+        ))
+    }
+}
+
+/// GUID of the active plan; the surrounding text of `powercfg /getactivescheme` is localized
+#[cfg(target_os = "windows")]
+fn active_power_scheme() -> Result<String> {
+    let output = run_powercfg(&["/getactivescheme"])?;
+    output
+        .split_whitespace()
+        .find(|token| token.len() == 36 && token.matches('-').count() == 4)
+        .map(str::to_string)
+        .ok_or_else(|| anyhow::anyhow!("Plan d'alimentation actif introuvable: {}", output.trim()))
+}
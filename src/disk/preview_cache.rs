@@ -0,0 +1,331 @@
+//! # Disk preview cache
+//!
+//! Walking the temporary, browser cache and thumbnail folders takes tens of seconds on a big
+//! profile. Each category scan is kept here with the time it ran and, per folder, the result
+//! and the modification date of the folder itself:
+//! - younger than the TTL (`DiskCleaningOptions::preview_ttl_secs`): reused as is
+//! - older: incremental, only the folders whose own mtime changed are walked again
+//! - `force`: every folder is walked again
+//!
+//! A folder mtime only changes when an entry is added to or removed from the folder itself, so
+//! the incremental mode can miss changes deeper down; the refresh button forces a full scan.
+//! `cached_preview` builds an estimate from the cache alone, without any disk access, so that
+//! toggling a category or a browser updates the total at once. A cleaning empties the cache.
+//!
+//! A detailed scan (largest files listed) can serve a plain request, not the other way round:
+//! a plain scan is walked again when the details are asked for.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Local};
+
+use super::browser_cache::{self, BrowserCache};
+use super::largest_files::ScanDetail;
+use super::{temp_files, thumbnails, CategoryResult, DiskCleaningOptions, DiskCleaningResults};
+
+static CACHE: Mutex<PreviewCache> = Mutex::new(PreviewCache { temp_files: None, browsers: None, thumbnails: None });
+
+/// Result of one folder and the folder mtime it was computed for
+struct FolderScan {
+    modified: Option<SystemTime>,
+    result: CategoryResult,
+}
+
+struct CategoryScan {
+    scanned_at: DateTime<Local>,
+    /// Settings the scan depends on (temporary files policy), rescanned when they change
+    key: String,
+    /// The folder results list their largest files
+    detailed: bool,
+    folders: HashMap<PathBuf, FolderScan>,
+    total: CategoryResult,
+}
+
+struct PreviewCache {
+    temp_files: Option<CategoryScan>,
+    /// Per-profile sizes, filtered by the browser selection in memory
+    browsers: Option<(CategoryScan, Vec<BrowserCache>)>,
+    thumbnails: Option<CategoryScan>,
+}
+
+fn lock() -> MutexGuard<'static, PreviewCache> {
+    CACHE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn modified(folder: &Path) -> Option<SystemTime> {
+    std::fs::metadata(folder).and_then(|metadata| metadata.modified()).ok()
+}
+
+fn age(scan: &CategoryScan) -> Duration {
+    (Local::now() - scan.scanned_at).to_std().unwrap_or_default()
+}
+
+/// Whether `scan`, made for `key`, answers a request for `key` with or without details
+fn matches(scan: &CategoryScan, key: &str, detailed: bool) -> bool {
+    scan.key == key && (scan.detailed || !detailed)
+}
+
+/// Scan of `folders` reusing `previous` as described in the module documentation
+fn scan_folders(
+    previous: Option<&CategoryScan>,
+    key: &str,
+    detailed: bool,
+    folders: Vec<PathBuf>,
+    mut walk: impl FnMut(&Path) -> CategoryResult,
+) -> CategoryScan {
+    let previous = previous.filter(|scan| matches(scan, key, detailed));
+    let mut scan = CategoryScan {
+        scanned_at: Local::now(),
+        key: key.to_string(),
+        detailed,
+        folders: HashMap::new(),
+        total: CategoryResult::default(),
+    };
+    let (mut walked, mut reused) = (0, 0);
+    for folder in folders {
+        let modified = modified(&folder);
+        let unchanged = previous
+            .and_then(|previous| previous.folders.get(&folder))
+            .filter(|cached| modified.is_some() && cached.modified == modified);
+        let result = match unchanged {
+            // Un scan détaillé repris pour une demande simple garde ses détails
+            Some(cached) => {
+                reused += 1;
+                cached.result.clone()
+            }
+            None => {
+                walked += 1;
+                walk(&folder)
+            }
+        };
+        scan.total.add(result.clone());
+        scan.folders.insert(folder, FolderScan { modified, result });
+    }
+    tracing::debug!("🔍 Aperçu disque : {} dossier(s) parcouru(s), {} repris du cache", walked, reused);
+    scan
+}
+
+fn policy_key(options: &DiskCleaningOptions) -> String {
+    serde_json::to_string(&options.temp_policy).unwrap_or_default()
+}
+
+/// Whether `cached` can be returned without touching the disk
+fn is_fresh(cached: Option<&CategoryScan>, key: &str, detailed: bool, ttl: Duration, force: bool) -> bool {
+    !force && cached.is_some_and(|scan| matches(scan, key, detailed) && age(scan) < ttl)
+}
+
+/// Preview of the selected categories, from the cache when it is recent enough
+pub(super) fn scan(options: &DiskCleaningOptions, force: bool, detailed: bool) -> DiskCleaningResults {
+    let ttl = Duration::from_secs(options.preview_ttl_secs);
+    let mut cache = lock();
+    if force {
+        tracing::info!("🔍 Aperçu disque complet demandé");
+    }
+
+    if options.clean_temp_files {
+        let key = policy_key(options);
+        if !is_fresh(cache.temp_files.as_ref(), &key, detailed, ttl, force) {
+            let previous = cache.temp_files.take().filter(|_| !force);
+            let policy = options.temp_policy.clone();
+            cache.temp_files = Some(scan_folders(previous.as_ref(), &key, detailed, temp_files::temp_dirs(), |folder| {
+                temp_files::preview_dir(folder, &policy, detailed)
+            }));
+        }
+    }
+
+    let mut browser_error = None;
+    if options.clean_browser_cache && !is_fresh(cache.browsers.as_ref().map(|(scan, _)| scan), "", detailed, ttl, force) {
+        let previous = cache
+            .browsers
+            .take()
+            .filter(|_| !force)
+            .map(|(scan, _)| scan)
+            .filter(|scan| matches(scan, "", detailed));
+        let mut folders = HashMap::new();
+        let caches = browser_cache::get_browser_cache_sizes_with(|folder| {
+            let modified = modified(folder);
+            let unchanged = previous
+                .as_ref()
+                .and_then(|previous| previous.folders.get(folder))
+                .filter(|cached| modified.is_some() && cached.modified == modified);
+            let result = match unchanged {
+                Some(cached) => cached.result.clone(),
+                None => browser_cache::measure_directory(folder, detailed)?,
+            };
+            folders.insert(folder.to_path_buf(), FolderScan { modified, result: result.clone() });
+            Ok(result)
+        });
+        match caches {
+            Ok(caches) => {
+                let scan = CategoryScan {
+                    scanned_at: Local::now(),
+                    key: String::new(),
+                    detailed,
+                    folders,
+                    total: CategoryResult::default(),
+                };
+                cache.browsers = Some((scan, caches));
+            }
+            Err(e) => browser_error = Some(e),
+        }
+    }
+
+    if options.clean_thumbnails && !is_fresh(cache.thumbnails.as_ref(), "", detailed, ttl, force) {
+        let previous = cache.thumbnails.take().filter(|_| !force);
+        cache.thumbnails = Some(scan_folders(previous.as_ref(), "", detailed, thumbnails::thumbnails_dirs(), |folder| {
+            thumbnails::preview_dir(folder, detailed)
+        }));
+    }
+
+    // Cache navigateur en échec : rien de mis en cache, la catégorie porte l'erreur
+    let browsers_scanned = options.clean_browser_cache && browser_error.is_none();
+    let built = build(&cache, &DiskCleaningOptions { clean_browser_cache: browsers_scanned, ..options.clone() });
+    let mut results = built.expect("catégories sélectionnées analysées");
+    if let Some(e) = browser_error {
+        results.browser_cache = CategoryResult::failed(e);
+        results.derive_totals();
+    }
+    results
+}
+
+/// Estimate of `options` from the cached scans alone, whatever their age; `None` when a
+/// selected category was never scanned (or with another temporary files policy)
+pub fn cached_preview(options: &DiskCleaningOptions) -> Option<DiskCleaningResults> {
+    build(&lock(), options)
+}
+
+fn build(cache: &PreviewCache, options: &DiskCleaningOptions) -> Option<DiskCleaningResults> {
+    let mut results = DiskCleaningResults::new();
+    let mut oldest: Option<DateTime<Local>> = None;
+    let mut note = |scanned_at: DateTime<Local>| {
+        oldest = Some(oldest.map_or(scanned_at, |oldest| oldest.min(scanned_at)));
+    };
+
+    if options.clean_temp_files {
+        let key = policy_key(options);
+        let scan = cache.temp_files.as_ref().filter(|scan| scan.key == key)?;
+        results.temp_files = scan.total.clone();
+        note(scan.scanned_at);
+    }
+    if options.clean_browser_cache {
+        let (scan, caches) = cache.browsers.as_ref()?;
+        results.set_browsers(browser_cache::summarize_caches(caches.clone(), &options.browsers, options.force_close_browsers));
+        note(scan.scanned_at);
+    }
+    if options.clean_thumbnails {
+        let scan = cache.thumbnails.as_ref()?;
+        results.thumbnails = scan.total.clone();
+        note(scan.scanned_at);
+    }
+
+    results.estimated_at = oldest;
+    results.derive_totals();
+    results.complete();
+    Some(results)
+}
+
+/// Take a file deleted on its own out of the cached scans, so that the next estimate does not
+/// count it without walking its folder again
+pub(super) fn forget_file(detail: &ScanDetail) {
+    let mut cache = lock();
+    let scan = match detail.category {
+        super::DiskCategory::TempFiles => cache.temp_files.as_mut(),
+        super::DiskCategory::Thumbnails => cache.thumbnails.as_mut(),
+        super::DiskCategory::BrowserCache => {
+            let Some((scan, caches)) = cache.browsers.as_mut() else {
+                return;
+            };
+            if let Some(browser) = caches.iter_mut().find(|browser| browser.largest_files.contains(detail)) {
+                browser.largest_files.retain(|known| known != detail);
+                browser.size = browser.size.saturating_sub(detail.size);
+                browser.files = browser.files.saturating_sub(1);
+            }
+            Some(scan)
+        }
+    };
+    let Some(scan) = scan else {
+        return;
+    };
+    for folder in scan.folders.values_mut() {
+        folder.result.forget_file(&detail.path, detail.size);
+    }
+    scan.total.forget_file(&detail.path, detail.size);
+}
+
+/// Forget every scan, after a cleaning changed the folders
+pub(super) fn invalidate() {
+    let mut cache = lock();
+    cache.temp_files = None;
+    cache.browsers = None;
+    cache.thumbnails = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    fn category_scan(age_secs: i64, key: &str, detailed: bool) -> CategoryScan {
+        CategoryScan {
+            scanned_at: Local::now() - chrono::Duration::seconds(age_secs),
+            key: key.to_string(),
+            detailed,
+            folders: HashMap::new(),
+            total: CategoryResult::default(),
+        }
+    }
+
+    /// Scan of `folders` counting the walks, each folder worth 100 bytes
+    fn scan_counting(previous: Option<&CategoryScan>, key: &str, folders: &[PathBuf]) -> (CategoryScan, Vec<PathBuf>) {
+        let mut walked = Vec::new();
+        let scan = scan_folders(previous, key, false, folders.to_vec(), |folder| {
+            walked.push(folder.to_path_buf());
+            CategoryResult { bytes_freed: 100, files_deleted: 1, ..Default::default() }
+        });
+        (scan, walked)
+    }
+
+    #[test]
+    fn fresh_within_ttl_unless_forced() {
+        let ttl = Duration::from_secs(300);
+        let recent = category_scan(10, "policy", false);
+        assert!(is_fresh(Some(&recent), "policy", false, ttl, false));
+        assert!(!is_fresh(Some(&recent), "policy", false, ttl, true));
+        assert!(!is_fresh(Some(&category_scan(600, "policy", false)), "policy", false, ttl, false));
+        assert!(!is_fresh(None, "policy", false, ttl, false));
+        // Autre politique, ou détails demandés à un scan simple
+        assert!(!is_fresh(Some(&recent), "other", false, ttl, false));
+        assert!(!is_fresh(Some(&recent), "policy", true, ttl, false));
+        assert!(is_fresh(Some(&category_scan(10, "policy", true)), "policy", false, ttl, false));
+    }
+
+    #[test]
+    fn only_changed_folders_are_walked_again() {
+        let dir = TestDir::new("preview_cache");
+        let (a, b) = (dir.0.join("a"), dir.0.join("b"));
+        std::fs::create_dir_all(&a).unwrap();
+        std::fs::create_dir_all(&b).unwrap();
+        let missing = dir.0.join("missing");
+        let folders = vec![a.clone(), b.clone(), missing.clone()];
+
+        let (first, walked) = scan_counting(None, "", &folders);
+        assert_eq!(walked, folders);
+        assert_eq!(first.total.bytes_freed, 300);
+
+        // Dossiers inchangés repris ; un dossier sans date est toujours parcouru
+        let (mut second, walked) = scan_counting(Some(&first), "", &folders);
+        assert_eq!(walked, vec![missing.clone()]);
+        assert_eq!(second.total.bytes_freed, 300);
+
+        second.folders.get_mut(&a).unwrap().modified = Some(SystemTime::UNIX_EPOCH);
+        let (_, walked) = scan_counting(Some(&second), "", &folders);
+        assert_eq!(walked, vec![a.clone(), missing.clone()]);
+
+        // Politique changée : tout est parcouru
+        let (_, walked) = scan_counting(Some(&first), "other", &folders);
+        assert_eq!(walked, folders);
+    }
+}
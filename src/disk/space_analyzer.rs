@@ -0,0 +1,230 @@
+// Disk space analyzer
+//
+// Size of every directory under a root, with the largest files and directories as flat
+// lists. Reparse points (junctions, symbolic links) are not followed, which avoids counting
+// a folder twice and looping forever; on Linux the walk stays on the root's file system.
+// Unreadable directories are skipped and counted instead of failing the scan.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::{self, Metadata};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use poll_promise::Promise;
+
+pub const LARGEST_FILES: usize = 50;
+pub const LARGEST_DIRS: usize = 20;
+/// Depth of the tree kept for the treemap; deeper directories are only summed
+pub const DEFAULT_MAX_DEPTH: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct DirNode {
+    pub path: PathBuf,
+    /// Bytes of every file below, at any depth
+    pub size: u64,
+    /// Largest first, empty beyond `max_depth`
+    pub children: Vec<DirNode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SizedPath {
+    pub size: u64,
+    pub path: PathBuf,
+}
+
+/// Column used to sort the analysis results
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum SpaceSort {
+    Size,
+    Path,
+}
+
+#[derive(Debug, Clone)]
+pub struct SpaceAnalysis {
+    pub root: DirNode,
+    /// Largest first
+    pub largest_files: Vec<SizedPath>,
+    /// Largest first, the root excluded
+    pub largest_dirs: Vec<SizedPath>,
+    /// Directories that could not be read (access denied...)
+    pub skipped_dirs: u64,
+    pub cancelled: bool,
+}
+
+/// Counters updated by the analysis thread, and its stop request
+#[derive(Debug, Default)]
+pub struct AnalysisProgress {
+    pub dirs_visited: AtomicU64,
+    pub bytes_accounted: AtomicU64,
+    cancel: AtomicBool,
+}
+
+impl AnalysisProgress {
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+}
+
+pub struct SpaceAnalysisJob {
+    pub root: PathBuf,
+    pub promise: Promise<SpaceAnalysis>,
+    pub progress: Arc<AnalysisProgress>,
+}
+
+/// Mount points of the local disks (drive letters on Windows)
+pub fn drives() -> Vec<PathBuf> {
+    let mut drives: Vec<PathBuf> = sysinfo::Disks::new_with_refreshed_list()
+        .iter()
+        .map(|disk| disk.mount_point().to_path_buf())
+        .collect();
+    drives.sort();
+    drives.dedup();
+    drives
+}
+
+/// Run `analyze` on a background thread
+pub fn start_analysis(root: PathBuf, max_depth: usize) -> SpaceAnalysisJob {
+    let progress = Arc::new(AnalysisProgress::default());
+    let thread_progress = Arc::clone(&progress);
+    let thread_root = root.clone();
+    let promise = Promise::spawn_thread("space_analysis", move || analyze(&thread_root, max_depth, &thread_progress));
+    SpaceAnalysisJob { root, promise, progress }
+}
+
+/// Walk `root`; a cancelled analysis returns what was measured so far
+pub fn analyze(root: &Path, max_depth: usize, progress: &AnalysisProgress) -> SpaceAnalysis {
+    tracing::info!("🔍 Analyse de l'espace disque de {}", root.display());
+    let mut walker = Walker {
+        progress,
+        max_depth,
+        root_device: fs::metadata(root).ok().as_ref().and_then(device_id),
+        largest_files: BinaryHeap::new(),
+        largest_dirs: BinaryHeap::new(),
+        skipped_dirs: 0,
+    };
+    let root_node = walker.visit(root, 0);
+
+    let analysis = SpaceAnalysis {
+        root: root_node,
+        largest_files: into_sorted(walker.largest_files),
+        largest_dirs: into_sorted(walker.largest_dirs),
+        skipped_dirs: walker.skipped_dirs,
+        cancelled: progress.is_cancelled(),
+    };
+    tracing::info!(
+        "✅ Analyse de {} {}: {:.1} GB, {} dossiers illisibles",
+        root.display(),
+        if analysis.cancelled { "interrompue" } else { "terminée" },
+        analysis.root.size as f64 / 1024.0 / 1024.0 / 1024.0,
+        analysis.skipped_dirs
+    );
+    analysis
+}
+
+struct Walker<'a> {
+    progress: &'a AnalysisProgress,
+    max_depth: usize,
+    root_device: Option<u64>,
+    /// Min-heaps bounded to the list sizes
+    largest_files: BinaryHeap<Reverse<SizedPath>>,
+    largest_dirs: BinaryHeap<Reverse<SizedPath>>,
+    skipped_dirs: u64,
+}
+
+impl Walker<'_> {
+    fn visit(&mut self, dir: &Path, depth: usize) -> DirNode {
+        let mut node = DirNode {
+            path: dir.to_path_buf(),
+            size: 0,
+            children: Vec::new(),
+        };
+        if self.progress.is_cancelled() {
+            return node;
+        }
+        self.progress.dirs_visited.fetch_add(1, Ordering::Relaxed);
+
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::debug!("Dossier ignoré {}: {}", dir.display(), e);
+                self.skipped_dirs += 1;
+                return node;
+            }
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            // symlink_metadata : les liens et jonctions ne sont pas suivis
+            let Ok(metadata) = path.symlink_metadata() else {
+                continue;
+            };
+            if is_link(&metadata) {
+                continue;
+            }
+            if metadata.is_dir() {
+                if self.root_device.is_some() && device_id(&metadata) != self.root_device {
+                    continue;
+                }
+                let child = self.visit(&path, depth + 1);
+                node.size += child.size;
+                push_bounded(&mut self.largest_dirs, SizedPath { size: child.size, path }, LARGEST_DIRS);
+                if depth < self.max_depth {
+                    node.children.push(child);
+                }
+            } else if metadata.is_file() {
+                let size = metadata.len();
+                node.size += size;
+                self.progress.bytes_accounted.fetch_add(size, Ordering::Relaxed);
+                push_bounded(&mut self.largest_files, SizedPath { size, path }, LARGEST_FILES);
+            }
+        }
+
+        node.children.sort_by(|a, b| b.size.cmp(&a.size));
+        node
+    }
+}
+
+fn push_bounded(heap: &mut BinaryHeap<Reverse<SizedPath>>, item: SizedPath, limit: usize) {
+    if heap.len() < limit {
+        heap.push(Reverse(item));
+    } else if heap.peek().is_some_and(|smallest| item > smallest.0) {
+        heap.pop();
+        heap.push(Reverse(item));
+    }
+}
+
+fn into_sorted(heap: BinaryHeap<Reverse<SizedPath>>) -> Vec<SizedPath> {
+    // Ordre croissant des Reverse = ordre décroissant des tailles
+    heap.into_sorted_vec().into_iter().map(|Reverse(item)| item).collect()
+}
+
+#[cfg(target_os = "windows")]
+fn is_link(metadata: &Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x400;
+    metadata.file_attributes() & FILE_ATTRIBUTE_REPARSE_POINT != 0
+}
+
+#[cfg(not(target_os = "windows"))]
+fn is_link(metadata: &Metadata) -> bool {
+    metadata.file_type().is_symlink()
+}
+
+/// File system of an entry, to stay on the root's one (/proc, network mounts)
+#[cfg(unix)]
+fn device_id(metadata: &Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.dev())
+}
+
+/// Reparse points already cover mounted volumes on Windows
+#[cfg(not(unix))]
+fn device_id(_metadata: &Metadata) -> Option<u64> {
+    None
+}
@@ -1,148 +1,294 @@
 // Temporary files cleaning
+//
+// Only files matching the `TempCleanPolicy` are removed: old enough, without a protected
+// extension or path. A file still open by another program (an installer waiting for its next
+// launch, a running application) cannot be deleted; it is counted in `files_skipped` rather
+// than reported as an error. The preview walks the same folders with the same policy, so that
+// its estimate matches what the cleaning removes.
 
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::largest_files::LargestFiles;
+use super::{CategoryResult, DiskCategory};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 use walkdir::WalkDir;
 
-pub async fn clean_temp_files() -> Result<u64> {
-    let mut total_cleaned = 0u64;
-
-    // System temp directories
-    let mut temp_dirs: Vec<PathBuf> = vec![std::env::temp_dir()];
+/// Which temporary files may be removed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TempCleanPolicy {
+    /// Files modified more recently are kept, 0 = no age limit
+    pub min_age_hours: u32,
+    /// Extensions kept, without the dot, case-insensitive
+    pub skip_extensions: Vec<String>,
+    /// Paths kept, `*` and `?` wildcards, case-insensitive (`*\Setup*\*`)
+    pub skip_patterns: Vec<String>,
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        temp_dirs.push(Path::new("C:\\Windows\\Temp").to_path_buf());
-        temp_dirs.push(Path::new("C:\\Windows\\Prefetch").to_path_buf());
+impl Default for TempCleanPolicy {
+    fn default() -> Self {
+        Self {
+            min_age_hours: 24,
+            skip_extensions: vec!["lock".to_string()],
+            skip_patterns: Vec::new(),
+        }
     }
-    #[cfg(target_os = "linux")]
-    {
-        temp_dirs.push(PathBuf::from("/tmp"));
-        temp_dirs.push(PathBuf::from("/var/tmp"));
-        // Prefetch n'a pas d'équivalent direct universel sur Linux qui soit sûr à nettoyer de cette manière.
+}
+
+impl TempCleanPolicy {
+    /// Whether `path`, last modified `age` ago, may be removed
+    fn allows(&self, path: &Path, age: Duration) -> bool {
+        if age < Duration::from_secs(self.min_age_hours as u64 * 3600) {
+            return false;
+        }
+        let extension = path.extension().map(|ext| ext.to_string_lossy().to_lowercase());
+        if let Some(extension) = extension {
+            let skipped = self
+                .skip_extensions
+                .iter()
+                .any(|skip| skip.trim().trim_start_matches('.').eq_ignore_ascii_case(&extension));
+            if skipped {
+                return false;
+            }
+        }
+        let path = normalize(&path.to_string_lossy());
+        !self.skip_patterns.iter().any(|pattern| wildcard_match(&normalize(pattern), &path))
     }
+}
+
+/// Lowercase with `\` separators, so that a pattern matches on every platform
+fn normalize(text: &str) -> String {
+    text.trim().to_lowercase().replace('/', "\\")
+}
 
-    for temp_dir in &temp_dirs {
-        if temp_dir.exists() {
-            total_cleaned += clean_directory(temp_dir).await?;
+/// `*` matches any run of characters (separators included), `?` a single one
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Dernière étoile rencontrée et position du texte où elle a commencé à consommer
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
         }
     }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Folders cleaned by `clean_temp_files` and measured by `get_temp_file_size`
+pub(super) fn temp_dirs() -> Vec<PathBuf> {
+    #[allow(unused_mut)]
+    let mut temp_dirs: Vec<PathBuf> = vec![std::env::temp_dir()];
 
-    // User-specific temp directories
     #[cfg(target_os = "windows")]
     {
+        temp_dirs.push(PathBuf::from("C:\\Windows\\Temp"));
+        temp_dirs.push(PathBuf::from("C:\\Windows\\Prefetch"));
         if let Ok(user_profile) = std::env::var("USERPROFILE") {
-            let user_temp_dirs_str = vec![
-                format!("{}\\AppData\\Local\\Temp", user_profile),
-                format!("{}\\AppData\\Local\\Microsoft\\Windows\\Temporary Internet Files", user_profile),
-            ];
-            for temp_dir_str in user_temp_dirs_str {
-                let path = Path::new(&temp_dir_str);
-                if path.exists() {
-                    total_cleaned += clean_directory(path).await?;
-                }
-            }
+            temp_dirs.push(Path::new(&user_profile).join("AppData\\Local\\Temp"));
+            temp_dirs.push(Path::new(&user_profile).join("AppData\\Local\\Microsoft\\Windows\\Temporary Internet Files"));
         }
     }
     #[cfg(target_os = "linux")]
     {
+        temp_dirs.push(PathBuf::from("/tmp"));
+        temp_dirs.push(PathBuf::from("/var/tmp"));
+        // Prefetch n'a pas d'équivalent direct universel sur Linux qui soit sûr à nettoyer de cette manière.
         if let Some(home_dir) = dirs::home_dir() {
-            let user_temp_dirs_path = vec![
-                home_dir.join(".cache"), // Un bon candidat général pour le cache utilisateur
-            ];
-            for path in user_temp_dirs_path {
-                if path.exists() {
-                    // Nettoyer le contenu de .cache peut être agressif,
-                    // il faudrait être plus sélectif ou permettre à l'utilisateur de configurer.
-                    // Pour l'instant, nous allons le parcourir.
-                    total_cleaned += clean_directory(&path).await?;
-                }
-            }
+            // Nettoyer le contenu de .cache peut être agressif : la politique d'âge limite la casse
+            temp_dirs.push(home_dir.join(".cache"));
+        }
+    }
+
+    // %TEMP% est souvent aussi AppData\Local\Temp : ne pas le parcourir deux fois
+    let mut unique: Vec<PathBuf> = Vec::new();
+    for dir in temp_dirs {
+        let key = normalize(&dir.to_string_lossy());
+        if dir.exists() && !unique.iter().any(|known| normalize(&known.to_string_lossy()) == key) {
+            unique.push(dir);
         }
     }
+    unique
+}
+
+/// A file operation refused because another program holds the file open
+pub(super) fn is_sharing_violation(error: &std::io::Error) -> bool {
+    const ERROR_SHARING_VIOLATION: i32 = 32;
+    const ERROR_LOCK_VIOLATION: i32 = 33;
+    cfg!(target_os = "windows") && matches!(error.raw_os_error(), Some(ERROR_SHARING_VIOLATION | ERROR_LOCK_VIOLATION))
+}
 
+/// Whether another program holds `path` open without allowing its deletion
+#[cfg(target_os = "windows")]
+pub(super) fn is_in_use(path: &Path) -> bool {
+    use std::os::windows::fs::OpenOptionsExt;
+    const DELETE: u32 = 0x0001_0000;
+    const FILE_SHARE_ALL: u32 = 0x1 | 0x2 | 0x4; // READ | WRITE | DELETE
 
-    Ok(total_cleaned)
+    match fs::OpenOptions::new().access_mode(DELETE).share_mode(FILE_SHARE_ALL).open(path) {
+        Ok(_) => false,
+        Err(e) => is_sharing_violation(&e),
+    }
+}
+
+/// Files can be deleted while open on Linux
+#[cfg(not(target_os = "windows"))]
+pub(super) fn is_in_use(_path: &Path) -> bool {
+    false
 }
 
-async fn clean_directory(dir: &Path) -> Result<u64> {
-    let mut total_size = 0u64;
-    
+/// Walk `dir` and call `action` on each file the policy allows and no program holds open.
+/// Files open by another program are counted as skipped: keeping them is expected.
+fn for_each_removable(
+    dir: &Path,
+    policy: &TempCleanPolicy,
+    result: &mut CategoryResult,
+    mut action: impl FnMut(&Path, &fs::Metadata) -> std::io::Result<()>,
+) {
+    let now = SystemTime::now();
     for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
-        if entry.file_type().is_file() {
-            if let Ok(metadata) = entry.metadata() {
-                let file_size = metadata.len();
-                
-                // Try to delete the file
-                if fs::remove_file(entry.path()).is_ok() {
-                    total_size += file_size;
-                }
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        // Date inconnue ou dans le futur : considérée comme récente
+        let age = metadata.modified().ok().and_then(|modified| now.duration_since(modified).ok()).unwrap_or_default();
+        if !policy.allows(entry.path(), age) {
+            continue;
+        }
+        if is_in_use(entry.path()) {
+            result.files_skipped += 1;
+            continue;
+        }
+        match action(entry.path(), &metadata) {
+            Ok(()) => {
+                result.bytes_freed += metadata.len();
+                result.files_deleted += 1;
             }
+            Err(e) => result.record_error(format!("{} : {}", entry.path().display(), e)),
         }
     }
-    
-    Ok(total_size)
 }
 
-pub fn get_temp_file_size() -> Result<u64> {
-    let mut total_size = 0u64;
-    
-    let mut temp_dirs_path: Vec<PathBuf> = vec![std::env::temp_dir()];
-    #[cfg(target_os = "windows")]
-    {
-        temp_dirs_path.push(Path::new("C:\\Windows\\Temp").to_path_buf());
+pub async fn clean_temp_files(policy: &TempCleanPolicy) -> Result<CategoryResult> {
+    let mut result = CategoryResult::default();
+    for dir in temp_dirs() {
+        for_each_removable(&dir, policy, &mut result, |path, _| fs::remove_file(path));
     }
-    #[cfg(target_os = "linux")]
-    {
-        temp_dirs_path.push(PathBuf::from("/tmp"));
-        temp_dirs_path.push(PathBuf::from("/var/tmp"));
+    tracing::debug!(
+        "🗃️ Fichiers temporaires: {} supprimé(s), {} octets, {} en cours d'utilisation, {} erreur(s)",
+        result.files_deleted,
+        result.bytes_freed,
+        result.files_skipped,
+        result.error_count()
+    );
+    Ok(result)
+}
+
+/// What `clean_temp_files` would remove with `policy`
+pub fn get_temp_file_size(policy: &TempCleanPolicy) -> Result<CategoryResult> {
+    let mut result = CategoryResult::default();
+    for dir in temp_dirs() {
+        result.add(preview_dir(&dir, policy, false));
     }
+    Ok(result)
+}
 
+/// What `clean_temp_files` would remove from `dir`, one of `temp_dirs`, with its largest files
+/// when `detailed`
+pub(super) fn preview_dir(dir: &Path, policy: &TempCleanPolicy, detailed: bool) -> CategoryResult {
+    let mut result = CategoryResult::default();
+    let mut largest = LargestFiles::new(DiskCategory::TempFiles, detailed);
+    for_each_removable(dir, policy, &mut result, |path, metadata| {
+        largest.push(path, metadata);
+        Ok(())
+    });
+    result.largest_files = largest.into_details();
+    result
+}
 
-    for temp_dir in temp_dirs_path {
-        if temp_dir.exists() {
-            total_size += calculate_directory_size(&temp_dir)?;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    const DAY: Duration = Duration::from_secs(24 * 3600);
+
+    /// `size` bytes at `relative`, last modified `age` ago
+    fn file(root: &Path, relative: &str, size: usize, age: Duration) -> PathBuf {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, vec![0u8; size]).unwrap();
+        fs::File::options().write(true).open(&path).unwrap().set_modified(SystemTime::now() - age).unwrap();
+        path
     }
-    
-    #[cfg(target_os = "windows")]
-    {
-        if let Ok(user_profile) = std::env::var("USERPROFILE") {
-            let user_temp_dirs_str = vec![
-                format!("{}\\AppData\\Local\\Temp", user_profile),
-            ];
-            for temp_dir_str in user_temp_dirs_str {
-                let path = Path::new(&temp_dir_str);
-                if path.exists() {
-                    total_size += calculate_directory_size(path)?;
-                }
-            }
-        }
+
+    #[test]
+    fn wildcards() {
+        assert!(wildcard_match("*\\setup*\\*", "c:\\temp\\setup_1234\\data.bin"));
+        assert!(wildcard_match("c:\\temp\\?.log", "c:\\temp\\a.log"));
+        assert!(!wildcard_match("c:\\temp\\?.log", "c:\\temp\\ab.log"));
+        assert!(wildcard_match("*", ""));
+        assert!(!wildcard_match("*.tmp", "c:\\temp\\file.tmp.bak"));
+        assert_eq!(normalize(" C:/Temp/Setup "), "c:\\temp\\setup");
     }
-    #[cfg(target_os = "linux")]
-    {
-        if let Some(home_dir) = dirs::home_dir() {
-            let user_temp_dir = home_dir.join(".cache");
-            if user_temp_dir.exists() {
-                total_size += calculate_directory_size(&user_temp_dir)?;
-            }
-        }
+
+    #[test]
+    fn policy_checks_age_extension_and_path() {
+        let policy = TempCleanPolicy {
+            min_age_hours: 24,
+            skip_extensions: vec![".LOCK".to_string(), "dmp".to_string()],
+            skip_patterns: vec!["*/Setup*/*".to_string()],
+        };
+        assert!(policy.allows(Path::new("/tmp/old.tmp"), 2 * DAY));
+        assert!(!policy.allows(Path::new("/tmp/new.tmp"), DAY / 2));
+        assert!(!policy.allows(Path::new("/tmp/app.lock"), 2 * DAY));
+        assert!(!policy.allows(Path::new("/tmp/crash.DMP"), 2 * DAY));
+        assert!(!policy.allows(Path::new("/tmp/setup_42/payload.msi"), 2 * DAY));
+        let no_age_limit = TempCleanPolicy { min_age_hours: 0, ..Default::default() };
+        assert!(no_age_limit.allows(Path::new("/tmp/new.tmp"), Duration::ZERO));
     }
 
-    Ok(total_size)
-}
+    #[test]
+    fn preview_and_cleaning_of_a_fixture_agree() {
+        let dir = TestDir::new("temp_files");
+        let old = file(&dir.0, "old.tmp", 1000, 2 * DAY);
+        let nested = file(&dir.0, "cache/nested/old.bin", 500, 3 * DAY);
+        let recent = file(&dir.0, "recent.tmp", 300, Duration::from_secs(60));
+        let locked = file(&dir.0, "app.lock", 200, 2 * DAY);
+        let setup = file(&dir.0, "Setup_1/installer.msi", 700, 2 * DAY);
+        let policy = TempCleanPolicy {
+            skip_patterns: vec!["*/setup*/*".to_string()],
+            ..Default::default()
+        };
 
-fn calculate_directory_size(dir: &Path) -> Result<u64> {
-    let mut total_size = 0u64;
-    
-    for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
-        if entry.file_type().is_file() {
-            if let Ok(metadata) = entry.metadata() {
-                total_size += metadata.len();
-            }
-        }
+        let preview = preview_dir(&dir.0, &policy, true);
+        assert_eq!(preview.bytes_freed, 1500);
+        assert_eq!(preview.files_deleted, 2);
+        assert_eq!(preview.largest_files.len(), 2);
+        assert!(old.exists() && nested.exists());
+
+        let mut cleaning = CategoryResult::default();
+        for_each_removable(&dir.0, &policy, &mut cleaning, |path, _| fs::remove_file(path));
+        assert_eq!((cleaning.bytes_freed, cleaning.files_deleted), (preview.bytes_freed, preview.files_deleted));
+        assert!(cleaning.errors.is_empty());
+        assert!(!old.exists() && !nested.exists());
+        assert!(recent.exists() && locked.exists() && setup.exists());
     }
-    
-    Ok(total_size)
 }
@@ -1,75 +1,211 @@
 // Thumbnails cleaning
+//
+// Explorer keeps its thumbcache_*.db files open for the whole session: deleting them fails
+// with a sharing violation. `LockedThumbnails` decides what happens to those files.
 
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::{Path};
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
-pub async fn clean_thumbnails() -> Result<u64> {
-    let mut total_cleaned = 0u64;
+use super::temp_files::{is_in_use, is_sharing_violation};
+use super::largest_files::LargestFiles;
+use super::{CategoryResult, DiskCategory};
+
+/// Longest wait for Explorer to exit once killed
+#[cfg(target_os = "windows")]
+const EXPLORER_EXIT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+#[cfg(target_os = "windows")]
+const EXPLORER_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// What the cleaning does with the thumbnail files held open by Explorer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LockedThumbnails {
+    /// Left in place, counted as skipped
+    #[default]
+    Skip,
+    /// Deleted by Windows at the next reboot (administrator rights required)
+    DeleteAtReboot,
+    /// Explorer is stopped for the deletion, then started again
+    RestartExplorer,
+}
+
+impl LockedThumbnails {
+    pub const ALL: [LockedThumbnails; 3] =
+        [LockedThumbnails::Skip, LockedThumbnails::DeleteAtReboot, LockedThumbnails::RestartExplorer];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LockedThumbnails::Skip => "Ignorer",
+            LockedThumbnails::DeleteAtReboot => "Supprimer au redémarrage",
+            LockedThumbnails::RestartExplorer => "Redémarrer l'Explorateur",
+        }
+    }
+}
+
+/// Existing thumbnail cache folders
+pub(super) fn thumbnails_dirs() -> Vec<PathBuf> {
+    #[allow(unused_mut)]
+    let mut folders: Vec<PathBuf> = Vec::new();
 
     #[cfg(target_os = "windows")]
     {
         if let Ok(user_profile) = std::env::var("USERPROFILE") {
-            let thumbnails_dirs_str = vec![
-                format!("{}\\AppData\\Local\\Microsoft\\Windows\\Explorer", user_profile),
-                format!("{}\\AppData\\Local\\Packages\\Microsoft.Windows.Photos_8wekyb3d8bbwe\\LocalState\\PhotosAppCache", user_profile),
-            ];
-
-            for thumb_dir_str in thumbnails_dirs_str {
-                let path = Path::new(&thumb_dir_str);
-                if path.exists() {
-                    total_cleaned += clean_thumbnails_directory(path).await?;
-                }
-            }
+            let local = Path::new(&user_profile).join("AppData\\Local");
+            folders.push(local.join("Microsoft\\Windows\\Explorer"));
+            folders.push(local.join("Packages\\Microsoft.Windows.Photos_8wekyb3d8bbwe\\LocalState\\PhotosAppCache"));
         }
     }
     #[cfg(target_os = "linux")]
     {
         if let Some(home_dir) = dirs::home_dir() {
-            let thumbnails_dirs_path = vec![
-                home_dir.join(".cache/thumbnails"),
-                home_dir.join(".thumbnails"), // Ancien emplacement, parfois encore utilisé
-            ];
-            for path in thumbnails_dirs_path {
-                if path.exists() {
-                    total_cleaned += clean_thumbnails_directory(&path).await?;
+            folders.push(home_dir.join(".cache/thumbnails"));
+            folders.push(home_dir.join(".thumbnails")); // Ancien emplacement, parfois encore utilisé
+        }
+    }
+
+    folders.into_iter().filter(|dir| dir.exists()).collect()
+}
+
+/// Thumbnail files of `dir`
+fn thumbnail_files(dir: &Path) -> impl Iterator<Item = walkdir::DirEntry> {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|entry| entry.file_type().is_file() && is_thumbnail_file(entry.path()))
+}
+
+pub async fn clean_thumbnails(locked: LockedThumbnails) -> Result<CategoryResult> {
+    let mut result = CategoryResult::default();
+    let mut in_use = Vec::new();
+    for dir in thumbnails_dirs() {
+        for entry in thumbnail_files(&dir) {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            match fs::remove_file(entry.path()) {
+                Ok(()) => {
+                    result.bytes_freed += metadata.len();
+                    result.files_deleted += 1;
                 }
+                Err(e) if is_sharing_violation(&e) => in_use.push((entry.into_path(), metadata.len())),
+                Err(e) => result.record_error(format!("{} : {}", entry.path().display(), e)),
             }
         }
     }
 
+    if !in_use.is_empty() {
+        match locked {
+            LockedThumbnails::Skip => result.files_skipped += in_use.len() as u32,
+            LockedThumbnails::DeleteAtReboot => schedule_at_reboot(&in_use, &mut result),
+            LockedThumbnails::RestartExplorer => delete_with_explorer_stopped(&in_use, &mut result).await,
+        }
+    }
+    tracing::debug!(
+        "🖼️ Miniatures: {} supprimée(s), {} octets, {} en cours d'utilisation, {} octets au redémarrage",
+        result.files_deleted,
+        result.bytes_freed,
+        result.files_skipped,
+        result.pending_reboot_bytes
+    );
+    Ok(result)
+}
+
+/// Ask Windows to delete `files` at the next reboot, before anything opens them
+#[cfg(target_os = "windows")]
+fn schedule_at_reboot(files: &[(PathBuf, u64)], result: &mut CategoryResult) {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::{MoveFileExW, MOVEFILE_DELAY_UNTIL_REBOOT};
+
+    // PendingFileRenameOperations est sous HKLM
+    if !crate::utils::is_elevated() {
+        result.files_skipped += files.len() as u32;
+        result.record_error("suppression au redémarrage : droits administrateur requis".to_string());
+        return;
+    }
+    for (path, size) in files {
+        let path_w: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+        if unsafe { MoveFileExW(path_w.as_ptr(), std::ptr::null(), MOVEFILE_DELAY_UNTIL_REBOOT) } != 0 {
+            result.pending_reboot_bytes += size;
+        } else {
+            result.files_skipped += 1;
+            result.record_error(format!("{} : {}", path.display(), std::io::Error::last_os_error()));
+        }
+    }
+}
 
-    Ok(total_cleaned)
+#[cfg(not(target_os = "windows"))]
+fn schedule_at_reboot(files: &[(PathBuf, u64)], result: &mut CategoryResult) {
+    result.files_skipped += files.len() as u32;
 }
 
-async fn clean_thumbnails_directory(dir: &Path) -> Result<u64> {
-    let mut total_size = 0u64;
-    
-    for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
-        if entry.file_type().is_file() {
-            let path = entry.path();
-            
-            // Pour Linux, les fichiers de miniatures sont souvent des .png ou .jpeg directement.
-            // La fonction is_thumbnail_file est très spécifique à Windows.
-            // Nous allons simplement supprimer les fichiers dans les répertoires de miniatures pour Linux pour l'instant.
-            // Une approche plus sûre serait de vérifier les types MIME ou les extensions courantes d'images.
-            #[cfg(target_os = "windows")]
-            let should_delete = is_thumbnail_file(path);
-            #[cfg(not(target_os = "windows"))]
-            let should_delete = true; // Simplification pour Linux : nettoie tout dans les dossiers de miniatures.
-
-            if should_delete {
-                if let Ok(metadata) = entry.metadata() {
-                    let file_size = metadata.len();
-                    if fs::remove_file(path).is_ok() {
-                        total_size += file_size;
-                    }
-                }
+/// Starts Explorer again when dropped, whatever happened in between
+#[cfg(target_os = "windows")]
+struct ExplorerRestart;
+
+#[cfg(target_os = "windows")]
+impl Drop for ExplorerRestart {
+    fn drop(&mut self) {
+        // Winlogon relance parfois l'Explorateur de lui-même : pas de seconde instance
+        if !explorer_pids().is_empty() {
+            tracing::info!("🖼️ Explorateur déjà relancé");
+            return;
+        }
+        match std::process::Command::new("explorer.exe").spawn() {
+            Ok(_) => tracing::info!("🖼️ Explorateur relancé"),
+            Err(e) => tracing::error!("❌ Relance de l'Explorateur impossible, à lancer depuis le Gestionnaire des tâches: {}", e),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn explorer_pids() -> Vec<sysinfo::Pid> {
+    let mut system = sysinfo::System::new();
+    system.refresh_processes();
+    system
+        .processes()
+        .iter()
+        .filter(|(_, process)| process.name().eq_ignore_ascii_case("explorer.exe"))
+        .map(|(pid, _)| *pid)
+        .collect()
+}
+
+/// Stop Explorer, delete `files` and start it again; what was actually deleted is counted
+#[cfg(target_os = "windows")]
+async fn delete_with_explorer_stopped(files: &[(PathBuf, u64)], result: &mut CategoryResult) {
+    tracing::info!("🖼️ Arrêt de l'Explorateur pour supprimer {} miniature(s) verrouillée(s)", files.len());
+    let stopped = explorer_pids();
+    let _restart = ExplorerRestart;
+    let killed = crate::system::default_runner().run("taskkill", &["/F", "/IM", "explorer.exe"]);
+    if let Err(e) = killed {
+        result.files_skipped += files.len() as u32;
+        result.record_error(format!("arrêt de l'Explorateur impossible : {:#}", e));
+        return;
+    }
+
+    // Les fichiers sont libérés à la fin du processus, pas à la réception du signal
+    let mut waited = std::time::Duration::ZERO;
+    while waited < EXPLORER_EXIT_TIMEOUT && explorer_pids().iter().any(|pid| stopped.contains(pid)) {
+        tokio::time::sleep(EXPLORER_POLL_INTERVAL).await;
+        waited += EXPLORER_POLL_INTERVAL;
+    }
+
+    for (path, size) in files {
+        match fs::remove_file(path) {
+            Ok(()) => {
+                result.bytes_freed += size;
+                result.files_deleted += 1;
             }
+            Err(e) if is_sharing_violation(&e) => result.files_skipped += 1,
+            Err(e) => result.record_error(format!("{} : {}", path.display(), e)),
         }
     }
-    Ok(total_size)
+}
+
+#[cfg(not(target_os = "windows"))]
+async fn delete_with_explorer_stopped(files: &[(PathBuf, u64)], result: &mut CategoryResult) {
+    result.files_skipped += files.len() as u32;
 }
 
 #[cfg(target_os = "windows")]
@@ -89,56 +225,37 @@ fn is_thumbnail_file(path: &Path) -> bool {
     false
 }
 
-pub fn get_thumbnails_size() -> Result<u64> {
-    let mut total_size = 0u64;
+// Pour Linux, les fichiers de miniatures sont souvent des .png ou .jpeg directement.
+// Simplification : tout le contenu des dossiers de miniatures est nettoyé.
+#[cfg(not(target_os = "windows"))]
+fn is_thumbnail_file(_path: &Path) -> bool {
+    true
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        if let Ok(user_profile) = std::env::var("USERPROFILE") {
-            let thumbnails_dirs_str = vec![
-                format!("{}\\AppData\\Local\\Microsoft\\Windows\\Explorer", user_profile),
-                format!("{}\\AppData\\Local\\Packages\\Microsoft.Windows.Photos_8wekyb3d8bbwe\\LocalState\\PhotosAppCache", user_profile),
-            ];
-
-            for thumb_dir_str in thumbnails_dirs_str {
-                let path = Path::new(&thumb_dir_str);
-                if path.exists() {
-                    total_size += calculate_thumbnails_size_os(path)?;
-                }
-            }
-        }
-    }
-    #[cfg(target_os = "linux")]
-    {
-        if let Some(home_dir) = dirs::home_dir() {
-            let thumbnails_dirs_path = vec![
-                home_dir.join(".cache/thumbnails"),
-                home_dir.join(".thumbnails"),
-            ];
-            for path in thumbnails_dirs_path {
-                if path.exists() {
-                    total_size += calculate_thumbnails_size_os(&path)?;
-                }
-            }
-        }
+/// What `clean_thumbnails` would remove
+pub fn get_thumbnails_size() -> Result<CategoryResult> {
+    let mut result = CategoryResult::default();
+    for dir in thumbnails_dirs() {
+        result.add(preview_dir(&dir, false));
     }
-    Ok(total_size)
+    Ok(result)
 }
 
-// Renommée pour éviter la collision avec la version spécifique à Windows
-fn calculate_thumbnails_size_os(dir: &Path) -> Result<u64> {
-    let mut total_size = 0u64;
-    for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
-        #[cfg(target_os = "windows")]
-        let is_thumb = entry.file_type().is_file() && is_thumbnail_file(entry.path());
-        #[cfg(not(target_os = "windows"))]
-        let is_thumb = entry.file_type().is_file(); // Simplification pour Linux
-
-        if is_thumb {
-            if let Ok(metadata) = entry.metadata() {
-                total_size += metadata.len();
+/// What `clean_thumbnails` would remove from `dir`, one of `thumbnails_dirs`, with its largest
+/// files when `detailed`
+pub(super) fn preview_dir(dir: &Path, detailed: bool) -> CategoryResult {
+    let mut result = CategoryResult::default();
+    let mut largest = LargestFiles::new(DiskCategory::Thumbnails, detailed);
+    for entry in thumbnail_files(dir) {
+        if let Ok(metadata) = entry.metadata() {
+            result.bytes_freed += metadata.len();
+            result.files_deleted += 1;
+            if is_in_use(entry.path()) {
+                result.locked_bytes += metadata.len();
             }
+            largest.push(entry.path(), &metadata);
         }
     }
-    Ok(total_size)
+    result.largest_files = largest.into_details();
+    result
 }
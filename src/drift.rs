@@ -0,0 +1,141 @@
+//! # Drift check
+//!
+//! Windows feature updates and Group Policy refreshes (`gpupdate`) silently remove QoS
+//! policies, and put Defender and service settings back, while GameBooster still shows them
+//! as applied. The check compares what GameBooster recorded with the system:
+//! - QoS policies of the policy store against `NetworkLimiter::list_qos_policies`
+//! - a Defender pause still running against `DefenderManager::check_defender_status`
+//! - services disabled by the durable mode against their current start type
+//!
+//! Blocking: runs on a worker thread, every `CHECK_INTERVAL` and on demand from the settings.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+
+use crate::network::{NetworkLimiter, PersistedPolicy, QosBackend};
+use crate::services::gaming_services;
+use crate::services::winapi_defender::DefenderManager;
+
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// What GameBooster believes is applied, snapshotted on the UI thread
+#[derive(Debug, Clone)]
+pub struct DriftIntent {
+    pub policies: Vec<PersistedPolicy>,
+    pub qos_backend: QosBackend,
+    /// End of a Defender pause still running
+    pub defender_paused_until: Option<DateTime<Local>>,
+    /// Services left disabled by the durable mode
+    pub disabled_services: Vec<String>,
+}
+
+impl DriftIntent {
+    pub fn is_empty(&self) -> bool {
+        self.policies.is_empty() && self.defender_paused_until.is_none() && self.disabled_services.is_empty()
+    }
+}
+
+/// A service disabled by the durable mode whose start type changed since
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceDrift {
+    pub name: String,
+    /// Start type found, as accepted by `sc config` (`auto`, `demand`...)
+    pub start_type: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DriftReport {
+    /// Recorded in the policy store but no longer on the system
+    pub missing_policies: Vec<PersistedPolicy>,
+    /// Defender paused by GameBooster but its real-time protection is back on
+    pub unexpected_defender_state: bool,
+    pub changed_services: Vec<ServiceDrift>,
+    pub checked_at: DateTime<Local>,
+}
+
+impl DriftReport {
+    pub fn is_empty(&self) -> bool {
+        self.missing_policies.is_empty() && !self.unexpected_defender_state && self.changed_services.is_empty()
+    }
+
+    /// Same drifts, whenever they were found
+    pub fn same_drifts(&self, other: &DriftReport) -> bool {
+        self.missing_policies == other.missing_policies
+            && self.unexpected_defender_state == other.unexpected_defender_state
+            && self.changed_services == other.changed_services
+    }
+
+    pub fn count(&self) -> usize {
+        self.missing_policies.len() + usize::from(self.unexpected_defender_state) + self.changed_services.len()
+    }
+
+    /// One line per drift, for the details of the notification
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .missing_policies
+            .iter()
+            .map(|policy| {
+                let target = policy.port_rule.map(|rule| rule.describe()).unwrap_or_else(|| policy.app_name.clone());
+                format!("Politique QoS {} absente ({})", policy.name, target)
+            })
+            .collect();
+        if self.unexpected_defender_state {
+            lines.push("Defender : protection en temps réel réactivée pendant la pause".to_string());
+        }
+        lines.extend(
+            self.changed_services
+                .iter()
+                .map(|service| format!("Service {} : démarrage {} au lieu de disabled", service.name, service.start_type)),
+        );
+        lines
+    }
+}
+
+/// Compare `intent` with the system. A source that cannot be read reports no drift.
+pub fn check(intent: &DriftIntent) -> DriftReport {
+    let mut report = DriftReport {
+        missing_policies: Vec::new(),
+        unexpected_defender_state: false,
+        changed_services: Vec::new(),
+        checked_at: Local::now(),
+    };
+
+    if !intent.policies.is_empty() {
+        match NetworkLimiter::list_qos_policies(intent.qos_backend, false) {
+            Ok(found) => {
+                report.missing_policies = intent
+                    .policies
+                    .iter()
+                    .filter(|known| !found.iter().any(|policy| policy.name == known.name))
+                    .cloned()
+                    .collect();
+            }
+            Err(e) => tracing::warn!("⚠️ Cohérence : politiques QoS illisibles: {}", e),
+        }
+    }
+
+    if intent.defender_paused_until.is_some_and(|deadline| deadline > Local::now()) {
+        match DefenderManager::check_defender_status() {
+            Ok(status) => report.unexpected_defender_state = status.real_time_protection,
+            Err(e) => tracing::warn!("⚠️ Cohérence : état de Defender illisible: {}", e),
+        }
+    }
+
+    for name in &intent.disabled_services {
+        match gaming_services::query_start_type(name) {
+            Ok(start_type) if start_type != "disabled" => {
+                report.changed_services.push(ServiceDrift { name: name.clone(), start_type });
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("⚠️ Cohérence : service {} illisible: {}", name, e),
+        }
+    }
+
+    if report.is_empty() {
+        tracing::debug!("🧭 Cohérence vérifiée : état système conforme");
+    } else {
+        tracing::warn!("🧭 {} écart(s) avec l'état appliqué : {}", report.count(), report.lines().join(" ; "));
+    }
+    report
+}
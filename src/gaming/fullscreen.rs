@@ -0,0 +1,23 @@
+//! # Fullscreen detection
+//!
+//! Whether a fullscreen game or presentation is in the foreground, as Windows itself decides
+//! it before showing notifications (`SHQueryUserNotificationState`).
+
+/// A Direct3D fullscreen game, a borderless fullscreen window or presentation mode
+#[cfg(target_os = "windows")]
+pub fn is_fullscreen_foreground() -> bool {
+    use windows_sys::Win32::UI::Shell::{
+        SHQueryUserNotificationState, QUNS_BUSY, QUNS_PRESENTATION_MODE, QUNS_RUNNING_D3D_FULL_SCREEN,
+    };
+
+    let mut state = 0;
+    if unsafe { SHQueryUserNotificationState(&mut state) } < 0 {
+        return false;
+    }
+    matches!(state, QUNS_BUSY | QUNS_RUNNING_D3D_FULL_SCREEN | QUNS_PRESENTATION_MODE)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn is_fullscreen_foreground() -> bool {
+    false
+}
@@ -0,0 +1,263 @@
+//! # Global hotkey
+//!
+//! "Nettoyer la RAM" without leaving the game: a system-wide combination (Ctrl+Alt+F9 by
+//! default) registered with `RegisterHotKey` on a dedicated thread running a message loop.
+//! A press sends `IpcCommand::HotkeyCleanRam` over the command channel of the window, which
+//! runs the cleaning even minimized and confirms it with a Windows notification.
+//!
+//! The combination is registered again when the settings change. `RegisterHotKey` fails when
+//! another program already owns it: the error is shown in the settings. Dropping the
+//! `GlobalHotkey` unregisters the combination and stops its thread.
+
+use std::fmt;
+use std::sync::mpsc::Sender;
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::ipc::IpcCommand;
+use crate::memory::CleaningResults;
+
+pub const DEFAULT_COMBINATION: &str = "Ctrl+Alt+F9";
+
+/// Settings of the global hotkey
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HotkeyConfig {
+    pub enabled: bool,
+    /// "Ctrl+Alt+F9", see `Hotkey::parse`
+    pub combination: String,
+    /// Also empty the standby list after the cleaning (administrator only)
+    pub purge_standby: bool,
+}
+
+impl Default for HotkeyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            combination: DEFAULT_COMBINATION.to_string(),
+            purge_standby: false,
+        }
+    }
+}
+
+/// Modifiers and key of a combination
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hotkey {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub win: bool,
+    pub key: HotkeyKey,
+}
+
+/// Keys accepted after the modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyKey {
+    /// F1 to F24
+    Function(u8),
+    /// A to Z, 0 to 9
+    Character(char),
+}
+
+impl Hotkey {
+    /// Read "Ctrl+Alt+F9", "maj+win+c"... At least one modifier is required: a lone key
+    /// would be taken from every other program.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut hotkey = Hotkey { ctrl: false, alt: false, shift: false, win: false, key: HotkeyKey::Function(0) };
+        let mut key = None;
+        for part in text.split('+').map(str::trim) {
+            let flag = match part.to_lowercase().as_str() {
+                "ctrl" | "control" | "ctl" => &mut hotkey.ctrl,
+                "alt" => &mut hotkey.alt,
+                "shift" | "maj" => &mut hotkey.shift,
+                "win" | "windows" => &mut hotkey.win,
+                _ => {
+                    if key.is_some() {
+                        bail!("« {} » : une seule touche après les modificateurs", text.trim());
+                    }
+                    key = Some(HotkeyKey::parse(part)?);
+                    continue;
+                }
+            };
+            *flag = true;
+        }
+        hotkey.key = key.ok_or_else(|| anyhow!("« {} » : touche manquante (F1-F24, A-Z ou 0-9)", text.trim()))?;
+        if !(hotkey.ctrl || hotkey.alt || hotkey.shift || hotkey.win) {
+            bail!("« {} » : au moins un modificateur requis (Ctrl, Alt, Maj ou Win)", text.trim());
+        }
+        Ok(hotkey)
+    }
+
+    #[cfg(windows)]
+    fn modifiers(&self) -> u32 {
+        use windows_sys::Win32::UI::Input::KeyboardAndMouse::{MOD_ALT, MOD_CONTROL, MOD_NOREPEAT, MOD_SHIFT, MOD_WIN};
+        // MOD_NOREPEAT : une touche maintenue ne relance pas le nettoyage
+        let mut modifiers = MOD_NOREPEAT;
+        for (enabled, flag) in [(self.ctrl, MOD_CONTROL), (self.alt, MOD_ALT), (self.shift, MOD_SHIFT), (self.win, MOD_WIN)] {
+            if enabled {
+                modifiers |= flag;
+            }
+        }
+        modifiers
+    }
+
+    /// Virtual-key code: VK_F1 (0x70) onwards, the ASCII code for letters and digits
+    #[cfg(windows)]
+    fn virtual_key(&self) -> u32 {
+        match self.key {
+            HotkeyKey::Function(number) => 0x70 + u32::from(number) - 1,
+            HotkeyKey::Character(character) => character as u32,
+        }
+    }
+}
+
+impl HotkeyKey {
+    fn parse(text: &str) -> Result<Self> {
+        let upper = text.to_uppercase();
+        if let Some(number) = upper.strip_prefix('F').and_then(|n| n.parse::<u8>().ok()) {
+            if (1..=24).contains(&number) {
+                return Ok(HotkeyKey::Function(number));
+            }
+        }
+        let mut characters = upper.chars();
+        match (characters.next(), characters.next()) {
+            (Some(character), None) if character.is_ascii_uppercase() || character.is_ascii_digit() => {
+                Ok(HotkeyKey::Character(character))
+            }
+            _ => bail!("Touche « {} » non prise en charge (F1-F24, A-Z ou 0-9)", text),
+        }
+    }
+}
+
+impl fmt::Display for Hotkey {
+    /// Canonical form saved in the settings: "Ctrl+Alt+F9"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (enabled, name) in [(self.ctrl, "Ctrl"), (self.alt, "Alt"), (self.shift, "Maj"), (self.win, "Win")] {
+            if enabled {
+                write!(f, "{}+", name)?;
+            }
+        }
+        match self.key {
+            HotkeyKey::Function(number) => write!(f, "F{}", number),
+            HotkeyKey::Character(character) => write!(f, "{}", character),
+        }
+    }
+}
+
+/// Cleaning started by the hotkey
+#[derive(Debug)]
+pub struct HotkeyCleaning {
+    pub cleaning: Result<CleaningResults, String>,
+    /// `None` when the purge is disabled
+    pub standby_purge: Option<Result<(), String>>,
+}
+
+impl HotkeyCleaning {
+    /// Blocking: run on a worker thread
+    pub fn run(purge_standby: bool) -> Self {
+        tracing::info!("⌨️ Nettoyage de la RAM demandé par le raccourci global");
+        let cleaning = crate::memory::clean_memory().map_err(|e| e.to_string());
+        // Les pages retirées des working sets passent par la liste standby : la purger ensuite
+        let standby_purge = purge_standby.then(|| crate::memory::purge_standby_list().map_err(|e| e.to_string()));
+        if let Some(Err(e)) = &standby_purge {
+            tracing::warn!("⚠️ Purge de la liste standby impossible: {}", e);
+        }
+        Self { cleaning, standby_purge }
+    }
+
+    /// Text of the confirmation: "812 Mo libérés, liste standby purgée"
+    pub fn summary(&self) -> String {
+        let mut text = match &self.cleaning {
+            Ok(results) => format!("{:.0} Mo libérés", results.total_freed() as f64 / 1024.0 / 1024.0),
+            Err(e) => format!("Nettoyage impossible : {}", e),
+        };
+        match &self.standby_purge {
+            Some(Ok(())) => text.push_str(", liste standby purgée"),
+            Some(Err(_)) => text.push_str(", liste standby non purgée"),
+            None => {}
+        }
+        text
+    }
+}
+
+/// Combination registered on its own message-loop thread, unregistered when dropped
+pub struct GlobalHotkey {
+    #[cfg(windows)]
+    hotkey: Hotkey,
+    #[cfg(windows)]
+    thread_id: u32,
+    #[cfg(windows)]
+    handle: Option<std::thread::JoinHandle<()>>,
+}
+
+#[cfg(windows)]
+const HOTKEY_ID: i32 = 1;
+
+impl GlobalHotkey {
+    /// Register `hotkey`; each press sends `IpcCommand::HotkeyCleanRam` to `commands` then
+    /// calls `wake`. Fails when another program owns the combination.
+    #[cfg(windows)]
+    pub fn register(hotkey: Hotkey, commands: Sender<IpcCommand>, wake: impl Fn() + Send + 'static) -> Result<Self> {
+        use windows_sys::Win32::System::Threading::GetCurrentThreadId;
+        use windows_sys::Win32::UI::Input::KeyboardAndMouse::{RegisterHotKey, UnregisterHotKey};
+        use windows_sys::Win32::UI::WindowsAndMessaging::{GetMessageW, PeekMessageW, MSG, PM_NOREMOVE, WM_HOTKEY, WM_USER};
+
+        let (registered, registration) = std::sync::mpsc::channel();
+        let handle = std::thread::Builder::new().name("global-hotkey".to_string()).spawn(move || unsafe {
+            let mut message: MSG = std::mem::zeroed();
+            // Crée la file de messages du thread avant que PostThreadMessageW ne la vise
+            PeekMessageW(&mut message, std::ptr::null_mut(), WM_USER, WM_USER, PM_NOREMOVE);
+            if RegisterHotKey(std::ptr::null_mut(), HOTKEY_ID, hotkey.modifiers(), hotkey.virtual_key()) == 0 {
+                let _ = registered.send(Err(std::io::Error::last_os_error()));
+                return;
+            }
+            let _ = registered.send(Ok(GetCurrentThreadId()));
+
+            // 0 : WM_QUIT envoyé par Drop, -1 : erreur
+            while GetMessageW(&mut message, std::ptr::null_mut(), 0, 0) > 0 {
+                if message.message == WM_HOTKEY && message.wParam == HOTKEY_ID as usize {
+                    tracing::info!("⌨️ Raccourci global {} pressé", hotkey);
+                    if commands.send(IpcCommand::HotkeyCleanRam).is_err() {
+                        break;
+                    }
+                    wake();
+                }
+            }
+            UnregisterHotKey(std::ptr::null_mut(), HOTKEY_ID);
+        })?;
+
+        match registration.recv() {
+            Ok(Ok(thread_id)) => {
+                tracing::info!("⌨️ Raccourci global {} enregistré", hotkey);
+                Ok(Self { hotkey, thread_id, handle: Some(handle) })
+            }
+            Ok(Err(e)) => {
+                let _ = handle.join();
+                Err(anyhow!("{} déjà utilisé par une autre application ({})", hotkey, e))
+            }
+            Err(_) => Err(anyhow!("Thread du raccourci global arrêté avant l'enregistrement")),
+        }
+    }
+
+    #[cfg(not(windows))]
+    pub fn register(_hotkey: Hotkey, _commands: Sender<IpcCommand>, _wake: impl Fn() + Send + 'static) -> Result<Self> {
+        bail!("Le raccourci global n'est disponible que sous Windows")
+    }
+}
+
+#[cfg(windows)]
+impl Drop for GlobalHotkey {
+    fn drop(&mut self) {
+        use windows_sys::Win32::UI::WindowsAndMessaging::{PostThreadMessageW, WM_QUIT};
+        // Le thread désenregistre le raccourci en sortant de sa boucle
+        if unsafe { PostThreadMessageW(self.thread_id, WM_QUIT, 0, 0) } == 0 {
+            tracing::warn!("⚠️ Arrêt du raccourci global impossible: {}", std::io::Error::last_os_error());
+            return;
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+        tracing::info!("⌨️ Raccourci global {} désenregistré", self.hotkey);
+    }
+}
@@ -0,0 +1,338 @@
+//! # Launcher watcher
+//!
+//! Frees RAM before a game loads. A worker thread polls the processes every `POLL_INTERVAL`
+//! and, for each enabled `LauncherRule`, triggers a cleaning when the launcher:
+//!
+//! - starts a child process that is not one of its helpers (the game), or
+//! - writes to disk or the machine downloads above the thresholds of its `ActivityTrigger`
+//!   for `sustained_secs` (a download or an update, usually followed by a launch).
+//!
+//! The cleaning is `memory::clean_memory`, followed by `memory::purge_standby_list` when
+//! enabled. A launcher does not trigger again before `cooldown_minutes`, so a long download
+//! does not clean every few seconds. Outcomes are sent back as `LauncherEvent`s.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Networks, Pid, System};
+
+use crate::memory::CleaningResults;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Sustained activity that reveals a download or an update
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActivityTrigger {
+    /// Disk writes of the launcher and its helpers, MB/s
+    pub disk_write_mb_per_sec: f64,
+    /// Download of the whole machine while the launcher runs, MB/s
+    pub download_mb_per_sec: f64,
+    /// Time spent above one of the thresholds before triggering
+    pub sustained_secs: u64,
+}
+
+impl Default for ActivityTrigger {
+    fn default() -> Self {
+        Self {
+            disk_write_mb_per_sec: 20.0,
+            download_mb_per_sec: 5.0,
+            sustained_secs: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LauncherRule {
+    pub name: String,
+    /// Executable of the launcher, case-insensitive
+    pub exe_name: String,
+    pub enabled: bool,
+    /// Trigger when the launcher starts a process that is not in `helper_processes`
+    pub child_trigger: bool,
+    /// Processes the launcher runs for itself (web views, overlay, updater), never taken for a game
+    pub helper_processes: Vec<String>,
+    /// `None`: downloads do not trigger
+    pub activity_trigger: Option<ActivityTrigger>,
+}
+
+impl Default for LauncherRule {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            exe_name: String::new(),
+            enabled: true,
+            child_trigger: true,
+            helper_processes: Vec::new(),
+            activity_trigger: Some(ActivityTrigger::default()),
+        }
+    }
+}
+
+/// Steam, Epic Games and Battle.net
+pub fn built_in_launchers() -> Vec<LauncherRule> {
+    let rule = |name: &str, exe_name: &str, helpers: &[&str]| LauncherRule {
+        name: name.to_string(),
+        exe_name: exe_name.to_string(),
+        helper_processes: helpers.iter().map(|helper| helper.to_string()).collect(),
+        ..LauncherRule::default()
+    };
+    vec![
+        rule(
+            "Steam",
+            "steam.exe",
+            &["steamwebhelper.exe", "steamservice.exe", "steamerrorreporter.exe", "steamerrorreporter64.exe", "gameoverlayui.exe"],
+        ),
+        rule(
+            "Epic Games",
+            "EpicGamesLauncher.exe",
+            &["EpicWebHelper.exe", "EpicOnlineServicesUserHelper.exe", "UnrealCEFSubProcess.exe", "CrashReportClient.exe"],
+        ),
+        rule(
+            "Battle.net",
+            "Battle.net.exe",
+            &["Battle.net Helper.exe", "Agent.exe", "BlizzardError.exe", "BlizzardBrowser.exe"],
+        ),
+    ]
+}
+
+/// Settings of the watcher, off by default
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LauncherWatcherConfig {
+    pub enabled: bool,
+    /// Also empty the standby list after the cleaning (administrator only)
+    pub purge_standby: bool,
+    /// Minimum time between two cleanings started by the same launcher
+    pub cooldown_minutes: u64,
+    pub launchers: Vec<LauncherRule>,
+}
+
+impl Default for LauncherWatcherConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            purge_standby: false,
+            cooldown_minutes: 30,
+            launchers: built_in_launchers(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LauncherTrigger {
+    /// Name of the game started by the launcher
+    GameLaunch(String),
+    /// Sustained disk or network activity
+    Download,
+}
+
+/// Cleaning started by a launcher
+#[derive(Debug)]
+pub struct LauncherEvent {
+    pub launcher: String,
+    pub trigger: LauncherTrigger,
+    pub cleaning: Result<CleaningResults, String>,
+    /// `None` when the purge is disabled
+    pub standby_purge: Option<Result<(), String>>,
+}
+
+impl LauncherEvent {
+    /// "Nettoyage préventif avant lancement de Elden Ring : 2.1 Go libérés"
+    pub fn summary(&self) -> String {
+        let context = match &self.trigger {
+            LauncherTrigger::GameLaunch(game) => format!("Nettoyage préventif avant lancement de {}", game),
+            LauncherTrigger::Download => format!("Nettoyage préventif pendant un téléchargement {}", self.launcher),
+        };
+        match &self.cleaning {
+            Ok(results) => format!("{} : {} libérés", context, format_freed(results.total_freed() as u64)),
+            Err(e) => format!("{} impossible : {}", context, e),
+        }
+    }
+}
+
+fn format_freed(bytes: u64) -> String {
+    let gb = bytes as f64 / 1024.0 / 1024.0 / 1024.0;
+    if gb >= 1.0 {
+        format!("{:.1} Go", gb)
+    } else {
+        format!("{:.0} Mo", bytes as f64 / 1024.0 / 1024.0)
+    }
+}
+
+/// Folder of the game in a Steam or Epic library ("ELDEN RING"), else the executable name
+fn game_display_name(process_name: &str, exe_path: Option<&Path>) -> String {
+    if let Some(path) = exe_path {
+        let components: Vec<String> = path.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+        for (i, component) in components.iter().enumerate() {
+            let steam_library = component.eq_ignore_ascii_case("common")
+                && i > 0
+                && components[i - 1].eq_ignore_ascii_case("steamapps");
+            let epic_library = component.eq_ignore_ascii_case("Epic Games");
+            // Le dossier du jeu, pas l'exécutable lui-même
+            if (steam_library || epic_library) && i + 2 < components.len() {
+                return components[i + 1].clone();
+            }
+        }
+    }
+    Path::new(process_name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| process_name.to_string())
+}
+
+/// What the worker remembers about one launcher between two polls
+#[derive(Default)]
+struct LauncherState {
+    /// Launcher seen at the previous poll: its children then are not new
+    running: bool,
+    children: HashSet<Pid>,
+    /// Activity above the thresholds since then
+    active_since: Option<Instant>,
+    last_cleaning: Option<Instant>,
+}
+
+fn run_cleaning(rule: &LauncherRule, trigger: LauncherTrigger, purge_standby: bool) -> LauncherEvent {
+    tracing::info!("🎮 {} : nettoyage préventif ({:?})", rule.name, trigger);
+    let cleaning = crate::memory::clean_memory().map_err(|e| e.to_string());
+    // Les pages retirées des working sets passent par la liste standby : la purger ensuite
+    let standby_purge = purge_standby.then(|| crate::memory::purge_standby_list().map_err(|e| e.to_string()));
+    if let Some(Err(e)) = &standby_purge {
+        tracing::warn!("⚠️ Purge de la liste standby impossible: {}", e);
+    }
+    LauncherEvent { launcher: rule.name.clone(), trigger, cleaning, standby_purge }
+}
+
+fn watch(config: LauncherWatcherConfig, events: Sender<LauncherEvent>, stop: Receiver<()>) {
+    let mut system = System::new();
+    let mut networks = Networks::new_with_refreshed_list();
+    let mut states: HashMap<String, LauncherState> = HashMap::new();
+    let mut last_poll = Instant::now();
+    let cooldown = Duration::from_secs(config.cooldown_minutes * 60);
+
+    loop {
+        system.refresh_processes();
+        networks.refresh();
+        let elapsed = last_poll.elapsed().as_secs_f64().max(0.1);
+        last_poll = Instant::now();
+        let downloaded: u64 = networks.iter().map(|(_, data)| data.received()).sum();
+
+        for rule in config.launchers.iter().filter(|rule| rule.enabled) {
+            let state = states.entry(rule.exe_name.to_lowercase()).or_default();
+            let launcher_pids: HashSet<Pid> = system
+                .processes()
+                .iter()
+                .filter(|(_, process)| process.name().eq_ignore_ascii_case(&rule.exe_name))
+                .map(|(pid, _)| *pid)
+                .collect();
+            if launcher_pids.is_empty() {
+                *state = LauncherState { last_cleaning: state.last_cleaning, ..Default::default() };
+                continue;
+            }
+
+            let is_helper = |name: &str| rule.helper_processes.iter().any(|helper| helper.eq_ignore_ascii_case(name));
+            let mut family_written = 0u64;
+            let mut games = Vec::new();
+            for (pid, process) in system.processes() {
+                let is_child = process.parent().is_some_and(|parent| launcher_pids.contains(&parent));
+                if launcher_pids.contains(pid) || (is_child && is_helper(process.name())) {
+                    family_written += process.disk_usage().written_bytes;
+                } else if is_child && !process.name().eq_ignore_ascii_case(&rule.exe_name) {
+                    games.push((*pid, game_display_name(process.name(), process.exe())));
+                }
+            }
+
+            let children: HashSet<Pid> = games.iter().map(|(pid, _)| *pid).collect();
+            let new_game = games
+                .iter()
+                .find(|(pid, _)| state.running && !state.children.contains(pid))
+                .map(|(_, name)| name.clone());
+            state.running = true;
+            state.children = children;
+
+            let mut trigger = new_game.filter(|_| rule.child_trigger).map(LauncherTrigger::GameLaunch);
+            match &rule.activity_trigger {
+                Some(activity) => {
+                    let disk_mb_s = family_written as f64 / elapsed / 1024.0 / 1024.0;
+                    let download_mb_s = downloaded as f64 / elapsed / 1024.0 / 1024.0;
+                    if disk_mb_s >= activity.disk_write_mb_per_sec || download_mb_s >= activity.download_mb_per_sec {
+                        let since = *state.active_since.get_or_insert_with(Instant::now);
+                        if trigger.is_none() && since.elapsed() >= Duration::from_secs(activity.sustained_secs) {
+                            trigger = Some(LauncherTrigger::Download);
+                        }
+                    } else {
+                        state.active_since = None;
+                    }
+                }
+                None => state.active_since = None,
+            }
+
+            let Some(trigger) = trigger else {
+                continue;
+            };
+            if state.last_cleaning.is_some_and(|last| last.elapsed() < cooldown) {
+                tracing::debug!("⏳ {} : nettoyage préventif ignoré ({:?}), délai minimal non écoulé", rule.name, trigger);
+                continue;
+            }
+            state.last_cleaning = Some(Instant::now());
+            state.active_since = None;
+            if events.send(run_cleaning(rule, trigger, config.purge_standby)).is_err() {
+                return;
+            }
+        }
+
+        match stop.recv_timeout(POLL_INTERVAL) {
+            Err(RecvTimeoutError::Timeout) => continue,
+            Ok(()) | Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
+
+/// Worker watching the launchers of `config`, stopped and joined when dropped
+pub struct LauncherWatcher {
+    config: LauncherWatcherConfig,
+    events: Receiver<LauncherEvent>,
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl LauncherWatcher {
+    pub fn start(config: LauncherWatcherConfig) -> Self {
+        let (sender, events) = mpsc::channel();
+        let (stop, stop_receiver) = mpsc::channel();
+        let watched = config.clone();
+        let handle = std::thread::Builder::new()
+            .name("launcher-watcher".to_string())
+            .spawn(move || watch(watched, sender, stop_receiver))
+            .map_err(|e| tracing::warn!("⚠️ Surveillance des launchers non démarrée: {}", e))
+            .ok();
+        tracing::info!("🎮 Surveillance des launchers démarrée");
+        Self { config, events, stop: Some(stop), handle }
+    }
+
+    /// Settings the worker runs with, to restart it when they change
+    pub fn config(&self) -> &LauncherWatcherConfig {
+        &self.config
+    }
+
+    /// Cleanings finished since the last call
+    pub fn poll_events(&self) -> Vec<LauncherEvent> {
+        self.events.try_iter().collect()
+    }
+}
+
+impl Drop for LauncherWatcher {
+    fn drop(&mut self) {
+        // Fermer le canal réveille le thread ; un nettoyage en cours se termine avant le join
+        self.stop.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+        tracing::info!("🎮 Surveillance des launchers arrêtée");
+    }
+}
@@ -0,0 +1,7 @@
+//! # Gaming automations
+//!
+//! Actions started on their own around game sessions.
+
+pub mod fullscreen;
+pub mod hotkey;
+pub mod launcher_watcher;
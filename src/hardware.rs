@@ -0,0 +1,217 @@
+//! # Hardware monitor
+//!
+//! CPU and GPU load and temperatures, sampled every 2 seconds on a background thread for the
+//! strip shown at the top of the tabs. Only userland sources are used, each one optional:
+//! - CPU load: sysinfo, total and per core
+//! - CPU temperature: sysinfo components (hwmon on Linux; on Windows the ACPI thermal zones of
+//!   `MSAcpi_ThermalZoneTemperature`, readable as administrator only)
+//! - GPU: `nvidia-smi`, and the amdgpu sysfs files on Linux
+//!
+//! A sensor that cannot be read stays `None` and is shown as "n/a".
+
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use sysinfo::{Components, System};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+const INTERVAL: Duration = Duration::from_secs(2);
+/// Temperatures from which the badges turn orange, then red (°C)
+pub const TEMP_WARN: f32 = 75.0;
+pub const TEMP_CRITICAL: f32 = 90.0;
+
+/// Labels of the sysinfo components measuring the CPU package, most relevant first
+const CPU_SENSOR_LABELS: &[&str] = &["package", "tctl", "tdie", "cpu", "coretemp", "k10temp"];
+
+#[derive(Debug, Clone, Default)]
+pub struct HardwareSnapshot {
+    /// Percent, all cores
+    pub cpu_usage: f32,
+    pub cpu_temp: Option<f32>,
+    /// Sensor the CPU temperature comes from
+    pub cpu_temp_source: Option<String>,
+    pub gpu_usage: Option<f32>,
+    pub gpu_temp: Option<f32>,
+    pub gpu_name: Option<String>,
+    /// Percent per logical core
+    pub per_core: Vec<f32>,
+}
+
+/// Samples in the background until dropped
+pub struct HardwareMonitor {
+    stop: Arc<AtomicBool>,
+    shared: Arc<Mutex<Option<HardwareSnapshot>>>,
+}
+
+impl HardwareMonitor {
+    pub fn start() -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let shared = Arc::new(Mutex::new(None));
+
+        let thread_stop = Arc::clone(&stop);
+        let thread_shared = Arc::clone(&shared);
+        let spawned = std::thread::Builder::new()
+            .name("hardware_monitor".to_string())
+            .spawn(move || run(&thread_stop, &thread_shared));
+        if let Err(e) = spawned {
+            tracing::error!("❌ Démarrage du moniteur matériel impossible: {}", e);
+        }
+
+        Self { stop, shared }
+    }
+
+    /// Last sample, `None` until the first one (about 2 seconds after `start`)
+    pub fn snapshot(&self) -> Option<HardwareSnapshot> {
+        self.shared.lock().ok().and_then(|shared| shared.clone())
+    }
+}
+
+impl Drop for HardwareMonitor {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn run(stop: &AtomicBool, shared: &Mutex<Option<HardwareSnapshot>>) {
+    let mut system = System::new();
+    let mut components = Components::new_with_refreshed_list();
+    let mut gpu = GpuSources::default();
+    // Première mesure de référence : l'utilisation CPU est un écart entre deux relevés
+    system.refresh_cpu_usage();
+
+    while !stop.load(Ordering::Relaxed) {
+        std::thread::sleep(INTERVAL);
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+
+        system.refresh_cpu_usage();
+        components.refresh();
+        let (cpu_temp_source, cpu_temp) = cpu_temperature(&components).unzip();
+        let mut snapshot = HardwareSnapshot {
+            cpu_usage: system.global_cpu_info().cpu_usage(),
+            per_core: system.cpus().iter().map(|cpu| cpu.cpu_usage()).collect(),
+            cpu_temp,
+            cpu_temp_source,
+            ..Default::default()
+        };
+        gpu.sample(&mut snapshot);
+
+        if let Ok(mut shared) = shared.lock() {
+            *shared = Some(snapshot);
+        }
+    }
+}
+
+/// (sensor label, °C) of the component that best matches the CPU package
+fn cpu_temperature(components: &Components) -> Option<(String, f32)> {
+    let readings: Vec<(String, f32)> = components
+        .iter()
+        .map(|component| (component.label().to_string(), component.temperature()))
+        .filter(|(_, temp)| temp.is_finite() && *temp > 0.0)
+        .collect();
+
+    for pattern in CPU_SENSOR_LABELS {
+        if let Some(reading) = readings.iter().find(|(label, _)| label.to_lowercase().contains(pattern)) {
+            return Some(reading.clone());
+        }
+    }
+    // Sous Windows, seules les zones thermiques ACPI sont exposées : la plus chaude
+    #[cfg(target_os = "windows")]
+    {
+        readings.into_iter().max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        None
+    }
+}
+
+/// GPU sources, each one disabled after its first failure to avoid retrying every 2 seconds
+struct GpuSources {
+    nvidia_smi: bool,
+    #[cfg(target_os = "linux")]
+    amdgpu: bool,
+}
+
+impl Default for GpuSources {
+    fn default() -> Self {
+        Self {
+            nvidia_smi: true,
+            #[cfg(target_os = "linux")]
+            amdgpu: true,
+        }
+    }
+}
+
+impl GpuSources {
+    fn sample(&mut self, snapshot: &mut HardwareSnapshot) {
+        if self.nvidia_smi {
+            match query_nvidia_smi() {
+                Some((name, usage, temp)) => {
+                    snapshot.gpu_name = Some(name);
+                    snapshot.gpu_usage = usage;
+                    snapshot.gpu_temp = temp;
+                    return;
+                }
+                None => {
+                    tracing::debug!("nvidia-smi indisponible, source GPU désactivée");
+                    self.nvidia_smi = false;
+                }
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        if self.amdgpu {
+            match read_amdgpu() {
+                Some((usage, temp)) => {
+                    snapshot.gpu_name = Some("AMD (amdgpu)".to_string());
+                    snapshot.gpu_usage = usage;
+                    snapshot.gpu_temp = temp;
+                }
+                None => self.amdgpu = false,
+            }
+        }
+    }
+}
+
+/// (name, usage %, temperature °C) of the first NVIDIA GPU
+fn query_nvidia_smi() -> Option<(String, Option<f32>, Option<f32>)> {
+    let mut command = Command::new("nvidia-smi");
+    command.args(["--query-gpu=name,utilization.gpu,temperature.gpu", "--format=csv,noheader,nounits"]);
+    #[cfg(target_os = "windows")]
+    command.creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+    let output = command.output().ok().filter(|output| output.status.success())?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut fields = stdout.lines().next()?.split(',').map(str::trim);
+    let name = fields.next()?.to_string();
+    // "[N/A]" sur les cartes qui n'exposent pas la valeur
+    let usage = fields.next().and_then(|value| value.parse().ok());
+    let temp = fields.next().and_then(|value| value.parse().ok());
+    Some((name, usage, temp))
+}
+
+/// (usage %, temperature °C) of the first amdgpu card
+#[cfg(target_os = "linux")]
+fn read_amdgpu() -> Option<(Option<f32>, Option<f32>)> {
+    let read_number = |path: &std::path::Path| -> Option<f32> { std::fs::read_to_string(path).ok()?.trim().parse().ok() };
+
+    for card in std::fs::read_dir("/sys/class/drm").ok()?.flatten() {
+        let device = card.path().join("device");
+        let Some(usage) = read_number(&device.join("gpu_busy_percent")) else {
+            continue;
+        };
+        // temp1_input : millidegrés, capteur "edge"
+        let temp = std::fs::read_dir(device.join("hwmon"))
+            .ok()
+            .and_then(|hwmons| hwmons.flatten().find_map(|hwmon| read_number(&hwmon.path().join("temp1_input"))))
+            .map(|millidegrees| millidegrees / 1000.0);
+        return Some((Some(usage), temp));
+    }
+    None
+}
@@ -0,0 +1,129 @@
+//! # Cleaning history
+//!
+//! Summaries of completed RAM/disk cleanings appended to `history/history.jsonl`
+//! (one JSON object per line, last `MAX_ENTRIES` kept).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::disk::DiskCleaningResults;
+use crate::memory::CleaningResults;
+
+pub const HISTORY_DIR: &str = "history";
+const HISTORY_FILE: &str = "history.jsonl";
+pub const MAX_ENTRIES: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HistoryKind {
+    Ram,
+    Disk,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub timestamp: DateTime<Local>,
+    pub kind: HistoryKind,
+    pub bytes_freed: u64,
+    /// Processes trimmed (RAM) or files processed (disk)
+    pub items: u32,
+    pub errors: u32,
+}
+
+impl HistoryEntry {
+    pub fn from_ram(results: &CleaningResults) -> Self {
+        Self {
+            timestamp: results.end_time.unwrap_or(results.start_time),
+            kind: HistoryKind::Ram,
+            bytes_freed: results.total_freed() as u64,
+            items: results.processes.len() as u32,
+            errors: results.has_error as u32,
+        }
+    }
+
+    pub fn from_disk(results: &DiskCleaningResults) -> Self {
+        Self {
+            timestamp: results.end_time.unwrap_or(results.start_time),
+            kind: HistoryKind::Disk,
+            bytes_freed: results.total_space_freed,
+            items: results.files_processed,
+            errors: results.errors.len() as u32,
+        }
+    }
+}
+
+pub fn path() -> PathBuf {
+    Path::new(HISTORY_DIR).join(HISTORY_FILE)
+}
+
+/// Append an entry, dropping the oldest ones beyond `MAX_ENTRIES`
+pub fn append(entry: &HistoryEntry) -> Result<()> {
+    fs::create_dir_all(HISTORY_DIR)?;
+
+    let mut lines: Vec<String> = match fs::read_to_string(path()) {
+        Ok(content) => content.lines().filter(|l| !l.trim().is_empty()).map(str::to_string).collect(),
+        Err(_) => Vec::new(),
+    };
+    lines.push(serde_json::to_string(entry)?);
+
+    let start = lines.len().saturating_sub(MAX_ENTRIES);
+    let mut content = lines[start..].join("\n");
+    content.push('\n');
+    fs::write(path(), content)?;
+    Ok(())
+}
+
+/// The `n` most recent entries, oldest first. Corrupt lines are skipped.
+pub fn load_recent(n: usize) -> Vec<HistoryEntry> {
+    let content = match fs::read_to_string(path()) {
+        Ok(content) => content,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut entries: Vec<HistoryEntry> = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(index, line)| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                tracing::warn!("⚠️ Ligne {} de l'historique ignorée (corrompue): {}", index + 1, e);
+                None
+            }
+        })
+        .collect();
+
+    let start = entries.len().saturating_sub(n);
+    entries.drain(..start);
+    entries
+}
+
+/// Bytes freed by entries of the last `days` days
+pub fn total_freed_in(entries: &[HistoryEntry], days: i64) -> u64 {
+    let since = Local::now() - Duration::days(days);
+    entries
+        .iter()
+        .filter(|e| e.timestamp >= since)
+        .map(|e| e.bytes_freed)
+        .sum()
+}
+
+/// Bytes freed per day for the last `days` days, oldest first (today included)
+pub fn daily_totals(entries: &[HistoryEntry], days: i64) -> Vec<(NaiveDate, u64)> {
+    let today = Local::now().date_naive();
+    (0..days)
+        .rev()
+        .map(|offset| {
+            let day = today - Duration::days(offset);
+            let total = entries
+                .iter()
+                .filter(|e| e.timestamp.date_naive() == day)
+                .map(|e| e.bytes_freed)
+                .sum();
+            (day, total)
+        })
+        .collect()
+}
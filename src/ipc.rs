@@ -0,0 +1,341 @@
+//! # Single instance
+//!
+//! The first GameBooster started owns the lock — the `Local\GameBooster_SingleInstance` mutex
+//! on Windows, `instance.lock` in the per-user data folder elsewhere — and listens on
+//! `127.0.0.1:IPC_PORT`. A second instance sends its request there — show the window, or run
+//! a command-line action — as one JSON line, waits for the `ok` reply and exits, so that two
+//! windows never fight over the QoS policies and the settings file.
+//!
+//! The loopback port is open to every local process: each request carries the token the
+//! running instance wrote to `ipc.token` in the per-user data folder, which other accounts
+//! cannot read. A request without the right token is dropped unanswered.
+//!
+//! The system releases the lock of a crashed instance: the next start takes it over, a stale
+//! lock never blocks startup. An instance relaunched as administrator (`RELAUNCH_ARG`) waits
+//! for the one it replaces to exit instead of handing the request back to it.
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::cli::{CliAction, CliInvocation};
+use crate::disk::DiskCleaningOptions;
+
+/// Loopback port of the running instance
+const IPC_PORT: u16 = 47_631;
+/// Delay for the running instance to accept and acknowledge a command
+const IPC_TIMEOUT: Duration = Duration::from_secs(3);
+/// Acknowledgement, also telling a GameBooster instance apart from another program on the port
+const ACK: &str = "ok";
+/// Token of the running instance, in `ipc_dir`
+const TOKEN_FILE: &str = "ipc.token";
+/// Argument of an instance started by `relaunch_as_admin`
+pub const RELAUNCH_ARG: &str = "--relaunched";
+/// Delay for the instance being replaced to close its window and release the lock
+pub const RELAUNCH_WAIT: Duration = Duration::from_secs(15);
+
+/// Request sent by a second instance to the running one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum IpcCommand {
+    /// Bring the window to the front, on `tab` (`Tab::as_arg`) when given
+    Show { tab: Option<String> },
+    CleanRam,
+    CleanDisk { options: DiskCleaningOptions },
+    Limit { exe_name: String, limit_mbps: f64 },
+    UnlimitAll,
+    /// Sent by `gaming::hotkey` on the same channel, never over the socket
+    HotkeyCleanRam,
+}
+
+impl IpcCommand {
+    /// Command-line action the running instance can run in its place. `--json` invocations,
+    /// `--status` and `--uninstall-cleanup` stay headless: their output goes to the caller.
+    /// `--defender-enable` too: the scheduled task runs it elevated, the window may not be.
+    pub fn from_cli(invocation: &CliInvocation) -> Option<Self> {
+        if invocation.json {
+            return None;
+        }
+        match &invocation.action {
+            CliAction::CleanRam => Some(IpcCommand::CleanRam),
+            CliAction::CleanDisk(options) => Some(IpcCommand::CleanDisk { options: options.clone() }),
+            CliAction::Limit { exe_name, limit_mbps } => Some(IpcCommand::Limit {
+                exe_name: exe_name.clone(),
+                limit_mbps: *limit_mbps,
+            }),
+            CliAction::UnlimitAll => Some(IpcCommand::UnlimitAll),
+            CliAction::Status | CliAction::DefenderEnable | CliAction::UninstallCleanup { .. } => None,
+        }
+    }
+}
+
+/// One line of the socket: the command and the token proving the sender runs as this user
+#[derive(Serialize, Deserialize)]
+struct IpcRequest {
+    token: String,
+    request: IpcCommand,
+}
+
+/// One line of JSON, as sent over the socket
+fn encode_request(command: &IpcCommand, token: &str) -> Result<String> {
+    Ok(serde_json::to_string(&IpcRequest { token: token.to_string(), request: command.clone() })?)
+}
+
+/// The command of `line`, refused when its token is not `token`
+fn decode_request(line: &str, token: &str) -> Result<IpcCommand> {
+    let request: IpcRequest = serde_json::from_str(line.trim())?;
+    if request.token != token {
+        return Err(anyhow::anyhow!("jeton IPC invalide"));
+    }
+    Ok(request.request)
+}
+
+fn ipc_address() -> SocketAddr {
+    SocketAddr::from((Ipv4Addr::LOCALHOST, IPC_PORT))
+}
+
+/// Per-user data folder: `%LOCALAPPDATA%\GameBooster`, `~/.local/share/GameBooster`
+fn ipc_dir() -> Result<PathBuf> {
+    let dir = dirs::data_local_dir()
+        .ok_or_else(|| anyhow::anyhow!("Dossier de données de l'utilisateur introuvable"))?
+        .join("GameBooster");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// 64 hex digits drawn from the random keys of `RandomState`, seeded by the system
+fn new_token() -> String {
+    use std::collections::hash_map::RandomState;
+    use std::hash::BuildHasher;
+
+    let mut hasher = blake3::Hasher::new();
+    for round in 0u8..4 {
+        hasher.update(&RandomState::new().hash_one(round).to_le_bytes());
+    }
+    hasher.update(&std::process::id().to_le_bytes());
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+    hasher.update(&now.as_nanos().to_le_bytes());
+    hasher.finalize().to_hex().to_string()
+}
+
+/// Write a new token, readable by this user only
+fn write_token() -> Result<String> {
+    let token = new_token();
+    let path = ipc_dir()?.join(TOKEN_FILE);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(&path)?.write_all(token.as_bytes())?;
+    Ok(token)
+}
+
+fn read_token() -> Result<String> {
+    let path = ipc_dir()?.join(TOKEN_FILE);
+    let token = fs::read_to_string(&path)
+        .map_err(|e| anyhow::anyhow!("Jeton de l'instance ouverte illisible ({}): {}", path.display(), e))?;
+    Ok(token.trim().to_string())
+}
+
+/// Outcome of `acquire`
+pub enum Instance {
+    /// No other instance runs: keep the guard alive as long as the application
+    Primary(InstanceGuard),
+    /// Another instance holds the lock, send it the request with `send`
+    Secondary,
+}
+
+/// Lock of the running instance and its command socket
+pub struct InstanceGuard {
+    #[cfg(target_os = "windows")]
+    mutex: windows_sys::Win32::Foundation::HANDLE,
+    /// Locked for the lifetime of the instance, released by the system on exit
+    #[cfg(not(target_os = "windows"))]
+    _lock: fs::File,
+    /// `None` when the port is taken by another program or the token could not be written:
+    /// the instance runs without IPC
+    listener: Option<(TcpListener, String)>,
+}
+
+impl InstanceGuard {
+    /// Forward the commands of later instances to `sender` from a background thread; `wake` is
+    /// called after each one so that an idle window processes it at once
+    pub fn listen(&mut self, sender: Sender<IpcCommand>, wake: impl Fn() + Send + 'static) {
+        let Some((listener, token)) = self.listener.take() else {
+            return;
+        };
+        let spawned = std::thread::Builder::new().name("ipc_listener".to_string()).spawn(move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        tracing::warn!("⚠️ Connexion IPC refusée: {}", e);
+                        continue;
+                    }
+                };
+                match read_command(stream, &token) {
+                    Ok(command) => {
+                        tracing::info!("📨 Commande reçue d'une autre instance: {:?}", command);
+                        if sender.send(command).is_err() {
+                            // Application fermée
+                            return;
+                        }
+                        wake();
+                    }
+                    Err(e) => tracing::warn!("⚠️ Commande IPC invalide ignorée: {}", e),
+                }
+            }
+        });
+        if let Err(e) = spawned {
+            tracing::error!("❌ Thread IPC impossible à démarrer: {}", e);
+        }
+    }
+}
+
+impl Drop for InstanceGuard {
+    fn drop(&mut self) {
+        // Jeton supprimé avant de relâcher le verrou : l'instance suivante écrit le sien ensuite
+        if let Ok(dir) = ipc_dir() {
+            let _ = fs::remove_file(dir.join(TOKEN_FILE));
+        }
+        #[cfg(target_os = "windows")]
+        {
+            use windows_sys::Win32::Foundation::CloseHandle;
+            use windows_sys::Win32::System::Threading::ReleaseMutex;
+            unsafe {
+                ReleaseMutex(self.mutex);
+                CloseHandle(self.mutex);
+            }
+        }
+    }
+}
+
+/// Read one command and acknowledge it
+fn read_command(stream: TcpStream, token: &str) -> Result<IpcCommand> {
+    stream.set_read_timeout(Some(IPC_TIMEOUT))?;
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line)?;
+    let command = decode_request(&line, token)?;
+    (&stream).write_all(format!("{}\n", ACK).as_bytes())?;
+    Ok(command)
+}
+
+/// Command socket of the instance that just took the lock
+fn open_listener() -> Option<(TcpListener, String)> {
+    let listener = TcpListener::bind(ipc_address())
+        .map_err(|e| tracing::warn!("⚠️ Port IPC {} indisponible, instance sans transfert de commandes: {}", IPC_PORT, e))
+        .ok()?;
+    let token = write_token()
+        .map_err(|e| tracing::warn!("⚠️ Jeton IPC non écrit, instance sans transfert de commandes: {:#}", e))
+        .ok()?;
+    Some((listener, token))
+}
+
+/// Become the running instance, or find out that another one is. A lock held by another
+/// instance is waited for up to `wait`.
+#[cfg(target_os = "windows")]
+pub fn acquire(wait: Duration) -> Result<Instance> {
+    use windows_sys::Win32::Foundation::{CloseHandle, GetLastError, ERROR_ALREADY_EXISTS, WAIT_ABANDONED, WAIT_OBJECT_0};
+    use windows_sys::Win32::System::Threading::{CreateMutexW, WaitForSingleObject};
+
+    let name: Vec<u16> = "Local\\GameBooster_SingleInstance".encode_utf16().chain(std::iter::once(0)).collect();
+    let mutex = unsafe { CreateMutexW(std::ptr::null(), 1, name.as_ptr()) };
+    if mutex.is_null() {
+        return Err(anyhow::anyhow!("CreateMutexW: {}", std::io::Error::last_os_error()));
+    }
+    if unsafe { GetLastError() } == ERROR_ALREADY_EXISTS {
+        // Le mutex existait : il n'est à nous que s'il est libre ou abandonné (instance plantée)
+        let wait_ms = u32::try_from(wait.as_millis()).unwrap_or(u32::MAX);
+        match unsafe { WaitForSingleObject(mutex, wait_ms) } {
+            WAIT_OBJECT_0 => {}
+            WAIT_ABANDONED => tracing::warn!("⚠️ L'instance précédente s'est terminée anormalement, reprise du verrou"),
+            _ => {
+                unsafe { CloseHandle(mutex) };
+                return Ok(Instance::Secondary);
+            }
+        }
+    }
+    Ok(Instance::Primary(InstanceGuard { mutex, listener: open_listener() }))
+}
+
+/// Become the running instance, or find out that another one is. A lock held by another
+/// instance is waited for up to `wait`; the system releases it with a crashed instance.
+#[cfg(not(target_os = "windows"))]
+pub fn acquire(wait: Duration) -> Result<Instance> {
+    use std::fs::TryLockError;
+    use std::time::Instant;
+
+    let lock = fs::OpenOptions::new().create(true).truncate(false).write(true).open(ipc_dir()?.join("instance.lock"))?;
+    let deadline = Instant::now() + wait;
+    loop {
+        match lock.try_lock() {
+            Ok(()) => break,
+            Err(TryLockError::WouldBlock) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(200)),
+            Err(TryLockError::WouldBlock) => return Ok(Instance::Secondary),
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+    }
+    Ok(Instance::Primary(InstanceGuard { _lock: lock, listener: open_listener() }))
+}
+
+/// Deliver `command` to the running instance and wait for its acknowledgement
+pub fn send(command: &IpcCommand) -> Result<()> {
+    let stream = TcpStream::connect_timeout(&ipc_address(), IPC_TIMEOUT)?;
+    stream.set_read_timeout(Some(IPC_TIMEOUT))?;
+    (&stream).write_all(format!("{}\n", encode_request(command, &read_token()?)?).as_bytes())?;
+
+    let mut reply = String::new();
+    BufReader::new(&stream).read_line(&mut reply)?;
+    if reply.trim() != ACK {
+        return Err(anyhow::anyhow!("Réponse inattendue sur le port {} : {:?}", IPC_PORT, reply.trim()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_round_trip() {
+        let commands = [
+            IpcCommand::Show { tab: Some("network".to_string()) },
+            IpcCommand::Show { tab: None },
+            IpcCommand::CleanRam,
+            IpcCommand::CleanDisk { options: DiskCleaningOptions::default() },
+            IpcCommand::Limit { exe_name: "steam.exe".to_string(), limit_mbps: 2.5 },
+            IpcCommand::UnlimitAll,
+        ];
+        for command in commands {
+            let line = encode_request(&command, "secret").unwrap();
+            assert!(!line.contains('\n'), "une requête tient sur une ligne : {}", line);
+            let decoded = decode_request(&format!("{}\n", line), "secret").unwrap();
+            assert_eq!(format!("{:?}", decoded), format!("{:?}", command));
+        }
+    }
+
+    #[test]
+    fn request_with_wrong_token_is_refused() {
+        let line = encode_request(&IpcCommand::UnlimitAll, "secret").unwrap();
+        assert!(decode_request(&line, "other").is_err());
+        // Ancien format, sans jeton
+        assert!(decode_request(r#"{"command":"unlimit_all"}"#, "secret").is_err());
+        assert!(decode_request("pas du json", "secret").is_err());
+    }
+
+    #[test]
+    fn tokens_differ() {
+        let (a, b) = (new_token(), new_token());
+        assert_eq!(a.len(), 64);
+        assert_ne!(a, b);
+    }
+}
@@ -0,0 +1,538 @@
+//! # Log viewer backend
+//!
+//! `LogTail` follows the daily files written by `tracing_appender` (`logs/gamebooster.log.*`)
+//! on a worker thread: it loads the end of the newest file, then polls it for appends and
+//! switches to the next file when the appender rotates. The last `MAX_LINES` entries are kept
+//! in a ring buffer shared with the UI.
+//!
+//! Lines not starting with a timestamp and a level (panic backtraces, multi-line messages)
+//! are attached to the previous entry; invalid UTF-8 is replaced rather than rejected. The
+//! file is opened for reading with every share flag, so the appender keeps writing to it.
+//!
+//! The level written to the files is set by `AppConfig::log_level` through a reload handle,
+//! and `enforce_retention` deletes the files older than `AppConfig::log_retention_days`.
+//!
+//! Recurring operations (network scans, QoS listings, cleanings) run in `info_span!`s carrying
+//! their pid / exe / kbps / duration_ms fields, log their routine steps at debug, and write
+//! their summary through `log_every!`: one line per interval at info, the others at debug.
+//! Errors and warnings are never throttled.
+
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+pub const LOG_DIR: &str = "logs";
+pub const LOG_FILE_PREFIX: &str = "gamebooster.log";
+/// Entries kept in memory
+pub const MAX_LINES: usize = 2000;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Tail of the file read when it is opened, enough for `MAX_LINES` ordinary lines
+const INITIAL_READ_BYTES: u64 = 512 * 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    #[default]
+    Info,
+    Warn,
+    Error,
+}
+
+/// Changes the level of the running subscriber, returned by `setup_logging`
+pub type LevelHandle = reload::Handle<EnvFilter, Registry>;
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 5] = [LogLevel::Error, LogLevel::Warn, LogLevel::Info, LogLevel::Debug, LogLevel::Trace];
+
+    /// `EnvFilter` directive, also the value stored in the settings
+    pub fn directive(self) -> &'static str {
+        match self {
+            LogLevel::Trace => "trace",
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+        }
+    }
+
+    pub fn filter(self) -> EnvFilter {
+        EnvFilter::new(self.directive())
+    }
+
+    fn parse(token: &str) -> Option<Self> {
+        match token {
+            "TRACE" => Some(LogLevel::Trace),
+            "DEBUG" => Some(LogLevel::Debug),
+            "INFO" => Some(LogLevel::Info),
+            "WARN" => Some(LogLevel::Warn),
+            "ERROR" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    /// `None` for lines read before the first header of the file
+    pub level: Option<LogLevel>,
+    /// Header line followed by its continuation lines
+    pub text: String,
+}
+
+/// Level of a line written by the fmt layer ("2024-05-01T18:00:00.123456Z  INFO message"),
+/// `None` for a continuation line
+fn header_level(line: &str) -> Option<LogLevel> {
+    let mut tokens = line.split_whitespace();
+    let timestamp = tokens.next()?;
+    let is_timestamp = timestamp.len() >= 10
+        && timestamp.as_bytes()[..4].iter().all(u8::is_ascii_digit)
+        && timestamp.as_bytes()[4] == b'-';
+    if !is_timestamp {
+        return None;
+    }
+    LogLevel::parse(tokens.next()?)
+}
+
+/// `gamebooster.log.*` files of `dir`
+fn log_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(LOG_FILE_PREFIX))
+        .map(|entry| entry.path())
+        .collect()
+}
+
+/// Newest `gamebooster.log.*` of `dir` (the date suffix sorts chronologically)
+fn current_log_file(dir: &Path) -> Option<PathBuf> {
+    log_files(dir).into_iter().max()
+}
+
+/// Day of a `gamebooster.log.2024-05-01` file, from its modification date when the name has none
+fn log_file_day(path: &Path) -> Option<NaiveDate> {
+    let name = path.file_name()?.to_string_lossy();
+    let suffix = name.strip_prefix(LOG_FILE_PREFIX)?.trim_start_matches('.');
+    NaiveDate::parse_from_str(suffix, "%Y-%m-%d").ok().or_else(|| {
+        let modified: chrono::DateTime<Local> = fs::metadata(path).and_then(|metadata| metadata.modified()).ok()?.into();
+        Some(modified.date_naive())
+    })
+}
+
+/// Last `count` lines of the log files, oldest first, reading older days until enough
+pub fn last_lines(count: usize) -> std::io::Result<Vec<String>> {
+    let mut files = log_files(Path::new(LOG_DIR));
+    files.sort();
+    let mut lines: VecDeque<String> = VecDeque::new();
+    for path in files.iter().rev() {
+        let mut content = Vec::new();
+        open_shared(path)?.read_to_end(&mut content)?;
+        let text = String::from_utf8_lossy(&content);
+        for line in text.lines().rev() {
+            if lines.len() == count {
+                return Ok(lines.into());
+            }
+            lines.push_front(line.trim_end_matches('\r').to_string());
+        }
+    }
+    Ok(lines.into())
+}
+
+/// Total size of the log files, in bytes
+pub fn directory_size() -> u64 {
+    log_files(Path::new(LOG_DIR))
+        .iter()
+        .filter_map(|path| fs::metadata(path).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Delete the log files of the days before the last `keep_days` (today included), 0 = keep
+/// everything. Returns the number of files deleted and the bytes reclaimed.
+pub fn enforce_retention(keep_days: u32) -> (usize, u64) {
+    if keep_days == 0 {
+        return (0, 0);
+    }
+    let oldest_kept = Local::now().date_naive() - chrono::Duration::days(keep_days as i64 - 1);
+    let dir = PathBuf::from(LOG_DIR);
+    let current = current_log_file(&dir);
+    let (mut deleted, mut reclaimed) = (0, 0);
+    for path in log_files(&dir) {
+        let expired = log_file_day(&path).is_some_and(|day| day < oldest_kept);
+        if !expired || Some(&path) == current.as_ref() {
+            continue;
+        }
+        let size = fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or(0);
+        match fs::remove_file(&path) {
+            Ok(()) => {
+                deleted += 1;
+                reclaimed += size;
+            }
+            Err(e) => tracing::warn!("⚠️ Journal {} non supprimé: {}", path.display(), e),
+        }
+    }
+    if deleted > 0 {
+        tracing::info!(
+            "🗑️ Rétention des journaux ({} jour(s)) : {} fichier(s) supprimé(s), {} octets libérés",
+            keep_days,
+            deleted,
+            reclaimed
+        );
+    }
+    (deleted, reclaimed)
+}
+
+/// Delete every log file and empty the one the appender is writing to, which stays open.
+/// Returns the bytes reclaimed.
+pub fn clear() -> std::io::Result<u64> {
+    let dir = PathBuf::from(LOG_DIR);
+    let current = current_log_file(&dir);
+    let mut reclaimed = 0;
+    let mut first_error = None;
+    for path in log_files(&dir) {
+        let size = fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or(0);
+        let result = if Some(&path) == current.as_ref() {
+            // Ouvert en ajout par l'appender : il continue d'écrire au début du fichier vidé
+            OpenOptions::new().write(true).open(&path).and_then(|file| file.set_len(0))
+        } else {
+            fs::remove_file(&path)
+        };
+        match result {
+            Ok(()) => reclaimed += size,
+            Err(e) => {
+                tracing::warn!("⚠️ Journal {} non vidé: {}", path.display(), e);
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    match first_error {
+        Some(e) => Err(e),
+        None => {
+            tracing::info!("🗑️ Journaux vidés : {} octets libérés", reclaimed);
+            Ok(reclaimed)
+        }
+    }
+}
+
+/// Read-only handle that never prevents the appender from writing, renaming or deleting
+fn open_shared(path: &Path) -> std::io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.read(true);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        use windows_sys::Win32::Storage::FileSystem::{FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE};
+        options.share_mode(FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE);
+    }
+    options.open(path)
+}
+
+/// Splits the bytes read into entries, keeping an incomplete last line for the next read
+#[derive(Default)]
+struct LineAssembler {
+    pending: Vec<u8>,
+}
+
+impl LineAssembler {
+    fn push(&mut self, bytes: &[u8], entries: &Mutex<VecDeque<LogEntry>>) {
+        self.pending.extend_from_slice(bytes);
+        let Some(last_newline) = self.pending.iter().rposition(|byte| *byte == b'\n') else {
+            return;
+        };
+        let complete: Vec<u8> = self.pending.drain(..=last_newline).collect();
+
+        let Ok(mut entries) = entries.lock() else {
+            return;
+        };
+        for raw in complete.split(|byte| *byte == b'\n') {
+            let line = String::from_utf8_lossy(raw);
+            let line = line.trim_end_matches('\r');
+            if line.is_empty() {
+                continue;
+            }
+            match (header_level(line), entries.back_mut()) {
+                (None, Some(previous)) => {
+                    previous.text.push('\n');
+                    previous.text.push_str(line);
+                }
+                (level, _) => entries.push_back(LogEntry { level, text: line.to_string() }),
+            }
+        }
+        let excess = entries.len().saturating_sub(MAX_LINES);
+        entries.drain(..excess);
+    }
+}
+
+struct FollowedFile {
+    path: PathBuf,
+    file: File,
+    position: u64,
+}
+
+impl FollowedFile {
+    /// Open `path` at its last `INITIAL_READ_BYTES`, or at its start after a rotation
+    fn open(path: PathBuf, from_start: bool) -> std::io::Result<Self> {
+        let file = open_shared(&path)?;
+        let length = file.metadata()?.len();
+        let position = if from_start { 0 } else { length.saturating_sub(INITIAL_READ_BYTES) };
+        Ok(Self { path, file, position })
+    }
+
+    /// Bytes appended since the last read. A file shorter than the position was truncated
+    /// and is read again from its start.
+    fn read_new(&mut self) -> std::io::Result<Vec<u8>> {
+        let length = self.file.metadata()?.len();
+        if length < self.position {
+            self.position = 0;
+        }
+        let mut bytes = Vec::new();
+        if length > self.position {
+            self.file.seek(SeekFrom::Start(self.position))?;
+            (&mut self.file).take(length - self.position).read_to_end(&mut bytes)?;
+            self.position += bytes.len() as u64;
+        }
+        Ok(bytes)
+    }
+}
+
+fn follow(dir: PathBuf, entries: Arc<Mutex<VecDeque<LogEntry>>>, stop: mpsc::Receiver<()>) {
+    let mut assembler = LineAssembler::default();
+    let mut followed: Option<FollowedFile> = None;
+    let mut skip_partial_line = false;
+
+    loop {
+        // Rotation quotidienne : finir l'ancien fichier puis suivre le nouveau depuis son début
+        if let Some(newest) = current_log_file(&dir) {
+            if followed.as_ref().map_or(true, |current| current.path != newest) {
+                if let Some(current) = followed.as_mut() {
+                    if let Ok(bytes) = current.read_new() {
+                        assembler.push(&bytes, &entries);
+                    }
+                }
+                let from_start = followed.is_some();
+                match FollowedFile::open(newest.clone(), from_start) {
+                    Ok(file) => {
+                        skip_partial_line = file.position > 0;
+                        assembler = LineAssembler::default();
+                        followed = Some(file);
+                    }
+                    Err(e) => tracing::debug!("⚠️ Journal {} illisible: {}", newest.display(), e),
+                }
+            }
+        }
+
+        if let Some(current) = followed.as_mut() {
+            match current.read_new() {
+                Ok(mut bytes) => {
+                    // Lecture commencée en milieu de fichier : la première ligne est tronquée
+                    if skip_partial_line {
+                        match bytes.iter().position(|byte| *byte == b'\n') {
+                            Some(newline) => {
+                                bytes.drain(..=newline);
+                                skip_partial_line = false;
+                            }
+                            None => bytes.clear(),
+                        }
+                    }
+                    assembler.push(&bytes, &entries);
+                }
+                Err(e) => {
+                    tracing::debug!("⚠️ Lecture du journal {} impossible: {}", current.path.display(), e);
+                    followed = None;
+                }
+            }
+        }
+
+        match stop.recv_timeout(POLL_INTERVAL) {
+            Err(RecvTimeoutError::Timeout) => continue,
+            Ok(()) | Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
+
+/// Rate limit of one `log_every!` call site
+pub struct LogThrottle {
+    last: Mutex<Option<Instant>>,
+    suppressed: AtomicU64,
+}
+
+impl LogThrottle {
+    pub const fn new() -> Self {
+        Self { last: Mutex::new(None), suppressed: AtomicU64::new(0) }
+    }
+
+    /// `Some(lines demoted since the previous one)` when `interval` has passed, `None` otherwise
+    pub fn acquire(&self, interval: Duration) -> Option<u64> {
+        let Ok(mut last) = self.last.lock() else {
+            return Some(0);
+        };
+        if last.is_some_and(|at| at.elapsed() < interval) {
+            self.suppressed.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+        *last = Some(Instant::now());
+        Some(self.suppressed.swap(0, Ordering::Relaxed))
+    }
+}
+
+/// `tracing::info!` at most once per interval for this call site, `tracing::debug!` otherwise:
+/// `log_every!(Duration::from_secs(60), "✅ Scan réseau terminé : {} processus", count)`.
+/// The info line carries a `suppressed` field with the lines demoted since the previous one.
+macro_rules! log_every {
+    ($interval:expr, $($arg:tt)+) => {{
+        static THROTTLE: $crate::logs::LogThrottle = $crate::logs::LogThrottle::new();
+        match THROTTLE.acquire($interval) {
+            Some(0) => ::tracing::info!($($arg)+),
+            Some(suppressed) => ::tracing::info!(suppressed, $($arg)+),
+            None => ::tracing::debug!($($arg)+),
+        }
+    }};
+}
+pub(crate) use log_every;
+
+/// Interval of the per-scan summaries
+pub const SUMMARY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Worker following the log files, stopped and joined when dropped
+pub struct LogTail {
+    entries: Arc<Mutex<VecDeque<LogEntry>>>,
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl LogTail {
+    pub fn start() -> Self {
+        let entries = Arc::new(Mutex::new(VecDeque::new()));
+        let (stop, stop_receiver) = mpsc::channel();
+        let shared = entries.clone();
+        let handle = std::thread::Builder::new()
+            .name("log-tail".to_string())
+            .spawn(move || follow(PathBuf::from(LOG_DIR), shared, stop_receiver))
+            .map_err(|e| tracing::warn!("⚠️ Lecteur du journal non démarré: {}", e))
+            .ok();
+        Self { entries, stop: Some(stop), handle }
+    }
+
+    /// Copy of the entries at least as severe as `min_level` and containing `search`
+    /// (case-insensitive), oldest first
+    pub fn entries(&self, min_level: LogLevel, search: &str) -> Vec<LogEntry> {
+        let search = search.to_lowercase();
+        let Ok(entries) = self.entries.lock() else {
+            return Vec::new();
+        };
+        entries
+            .iter()
+            .filter(|entry| entry.level.map_or(true, |level| level >= min_level))
+            .filter(|entry| search.is_empty() || entry.text.to_lowercase().contains(&search))
+            .cloned()
+            .collect()
+    }
+
+    /// Absolute folder of the log files, for the file manager
+    pub fn directory() -> PathBuf {
+        std::env::current_dir().map(|dir| dir.join(LOG_DIR)).unwrap_or_else(|_| PathBuf::from(LOG_DIR))
+    }
+}
+
+impl Drop for LogTail {
+    fn drop(&mut self) {
+        // Fermer le canal réveille le thread, qui rend le fichier avant le join
+        self.stop.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Event capture for the tests checking what a module logs
+#[cfg(test)]
+pub mod testing {
+    use std::fmt::Write as _;
+    use std::sync::{Arc, Mutex};
+
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id};
+    use tracing::{Event, Level, Subscriber};
+    use tracing_subscriber::layer::{Context, Layer};
+    use tracing_subscriber::registry::LookupSpan;
+
+    /// `name=value` pairs of a span or an event
+    struct Fields(String);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            let _ = write!(self.0, "{}={:?} ", field.name(), value);
+        }
+    }
+
+    /// An event: its level, its fields, and the name and fields of its enclosing span
+    pub type Captured = (Level, String, Option<(String, String)>);
+
+    /// Layer recording every event
+    #[derive(Default, Clone)]
+    pub struct Capture(Arc<Mutex<Vec<Captured>>>);
+
+    impl Capture {
+        pub fn events(&self) -> Vec<Captured> {
+            self.0.lock().unwrap().clone()
+        }
+    }
+
+    impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Capture {
+        fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+            let mut fields = Fields(String::new());
+            attrs.record(&mut fields);
+            if let Some(span) = ctx.span(id) {
+                span.extensions_mut().insert(fields);
+            }
+        }
+
+        fn on_record(&self, id: &Id, values: &tracing::span::Record<'_>, ctx: Context<'_, S>) {
+            if let Some(span) = ctx.span(id) {
+                if let Some(fields) = span.extensions_mut().get_mut::<Fields>() {
+                    values.record(fields);
+                }
+            }
+        }
+
+        fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+            let mut fields = Fields(String::new());
+            event.record(&mut fields);
+            let span = ctx.event_span(event).map(|span| {
+                let span_fields = span.extensions().get::<Fields>().map(|f| f.0.clone()).unwrap_or_default();
+                (span.name().to_string(), span_fields)
+            });
+            self.0.lock().unwrap().push((*event.metadata().level(), fields.0, span));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throttle_counts_suppressed_lines() {
+        let throttle = LogThrottle::new();
+        let interval = Duration::from_millis(50);
+        assert_eq!(throttle.acquire(interval), Some(0));
+        assert_eq!(throttle.acquire(interval), None);
+        assert_eq!(throttle.acquire(interval), None);
+        std::thread::sleep(Duration::from_millis(80));
+        assert_eq!(throttle.acquire(interval), Some(2));
+        assert_eq!(throttle.acquire(interval), None);
+    }
+}
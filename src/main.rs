@@ -1,45 +1,146 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod cli;
+mod config;
+mod crash;
+mod diagnostics;
 mod disk;
+mod drift;
+mod gaming;
+mod hardware;
+mod history;
+mod ipc;
+mod logs;
+mod maintenance;
 mod memory;
 mod network;
+mod optimization;
 mod os_info;
+mod power;
+mod profiles;
+mod safety;
 mod scheduler;
 mod services;
+mod sessions;
+mod system;
 mod theme;
 mod ui;
+mod updater;
 mod utils; // Added utils module
 
+use std::sync::{Arc, OnceLock};
+
 use ui::app::CleanRamApp;
 use tracing::{info, warn};
-use tracing_subscriber::{layer::SubscriberExt, EnvFilter};
+use tracing_subscriber::{layer::SubscriberExt, reload};
 
 fn main() {
-    let _guard = setup_logging();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match cli::parse_args(&args) {
+        Ok(Some(invocation)) => {
+            cli::attach_console();
+            // Logs uniquement dans le fichier : stdout est réservé au résultat (JSON)
+            let _guard = setup_logging(false, config::AppConfig::saved_log_level());
+            // L'instance ouverte possède les politiques QoS : l'action lui est confiée
+            if let Some(command) = ipc::IpcCommand::from_cli(&invocation) {
+                if ipc::send(&command).is_ok() {
+                    info!("📨 Action transmise à l'instance déjà ouverte: {:?}", command);
+                    println!("Action transmise à la fenêtre GameBooster déjà ouverte");
+                    std::process::exit(0);
+                }
+            }
+            std::process::exit(cli::run(invocation));
+        }
+        Ok(None) => {}
+        Err(e) => {
+            cli::attach_console();
+            eprintln!("❌ {}", e);
+            std::process::exit(2);
+        }
+    }
+
+    let logging = setup_logging(true, config::AppConfig::saved_log_level());
+
+    // Relancée en administrateur : l'instance d'origine se ferme, son verrou est attendu
+    let wait = if args.iter().any(|arg| arg == ipc::RELAUNCH_ARG) { ipc::RELAUNCH_WAIT } else { std::time::Duration::ZERO };
+    let mut instance = match ipc::acquire(wait) {
+        Ok(ipc::Instance::Primary(guard)) => Some(guard),
+        Ok(ipc::Instance::Secondary) => {
+            let tab = ui::app::Tab::from_cli_args().map(|tab| tab.as_arg().to_string());
+            match ipc::send(&ipc::IpcCommand::Show { tab }) {
+                Ok(()) => {
+                    info!("🪟 GameBooster est déjà ouvert : fenêtre existante affichée, fin de cette instance");
+                    println!("GameBooster est déjà ouvert : la fenêtre existante a été affichée");
+                    std::process::exit(0);
+                }
+                Err(e) => {
+                    warn!("⚠️ GameBooster est déjà ouvert mais ne répond pas: {:#}", e);
+                    eprintln!("GameBooster est déjà ouvert mais ne répond pas : {:#}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Err(e) => {
+            // Sans verrou, mieux vaut une instance de trop que pas d'instance du tout
+            warn!("⚠️ Vérification d'instance unique impossible: {:#}", e);
+            None
+        }
+    };
+    crash::install_panic_hook();
 
     info!("🚀 Initializing GameBooster application...");
 
-    // Test QoS automatique au démarrage (mode release uniquement)
-    #[cfg(not(debug_assertions))]
-    test_qos_system();
+    let config = config::AppConfig::load();
+    logs::enforce_retention(config.log_retention_days);
+
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_inner_size([config.window.width, config.window.height])
+        .with_min_inner_size([900.0, 500.0])
+        .with_title("GameBooster - Network QoS Ready")
+        .with_icon(ui::icons::app_icon().clone())
+        .with_resizable(true)
+        .with_maximized(config.window.maximized);
+    let position = config.window.restorable_position();
+    if let Some(position) = position {
+        viewport = viewport.with_position(position);
+    }
 
     let native_options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([1200.0, 700.0])
-            .with_min_inner_size([900.0, 500.0])
-            .with_title("GameBooster - Network QoS Ready")
-            .with_resizable(true),
-        centered: true,
+        viewport,
+        centered: position.is_none(),
+        // La géométrie est conservée dans settings.toml, pas dans le stockage d'eframe
+        persist_window: false,
         ..Default::default()
     };
 
+    // Les commandes reçues avant la première image attendent dans le canal
+    let repaint: Arc<OnceLock<egui::Context>> = Arc::default();
+    let log_level_handle = logging.as_ref().map(|(_, handle)| handle.clone());
+    // Canal partagé par les autres instances et le raccourci global
+    let (command_sender, ipc_commands) = std::sync::mpsc::channel();
+    if let Some(guard) = instance.as_mut() {
+        let repaint = repaint.clone();
+        guard.listen(command_sender.clone(), move || {
+            if let Some(ctx) = repaint.get() {
+                ctx.request_repaint();
+            }
+        });
+    }
+
     info!("Starting eframe::run_native...");
     
     if let Err(e) = eframe::run_native(
         "GameBooster",
         native_options,
-        Box::new(|cc| {
-            let app = CleanRamApp::new(cc);
+        Box::new(move |cc| {
+            let mut app = CleanRamApp::new(cc, config);
+            if let Some(tab) = ui::app::Tab::from_cli_args() {
+                app.active_tab = tab;
+            }
+            let _ = repaint.set(cc.egui_ctx.clone());
+            app.ipc_commands = Some(ipc_commands);
+            app.command_sender = Some(command_sender);
+            app.log_level_handle = log_level_handle;
             Box::new(app)
         }),
     ) {
@@ -47,89 +148,42 @@ fn main() {
     }
 }
 
-/// Test automatique du système QoS au démarrage
-#[cfg(not(debug_assertions))]
-fn test_qos_system() {
-    use crate::network::NetworkLimiter;
-    
-    info!("🧪 Test système QoS automatique...");
-    
-    match NetworkLimiter::new() {
-        Ok(mut limiter) => {
-            info!("✅ NetworkLimiter créé");
-            
-            match limiter.scan_network_processes() {
-                Ok(()) => {
-                    let processes = limiter.get_processes();
-                    info!("✅ Scan réseau: {} processus détectés", processes.len());
-                    
-                    // Afficher quelques processus détectés
-                    for (i, process) in processes.iter().take(3).enumerate() {
-                        info!("  {}. {} (PID: {}) - {}↓ {}↑", 
-                            i + 1, 
-                            process.name, 
-                            process.pid,
-                            crate::network::format_speed(process.current_download_speed),
-                            crate::network::format_speed(process.current_upload_speed)
-                        );
-                    }
-                    
-                    // Test vérification politiques existantes
-                    match limiter.verify_qos_policies() {
-                        Ok(policies) => {
-                            if policies.is_empty() {
-                                info!("📋 Aucune politique QoS GameBooster active");
-                            } else {
-                                info!("📋 {} politiques QoS GameBooster actives", policies.len());
-                            }
-                        }
-                        Err(e) => {
-                            warn!("⚠️ Impossible de vérifier les politiques QoS: {}", e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    warn!("⚠️ Erreur scan réseau: {}", e);
-                }
-            }
-        }
-        Err(e) => {
-            warn!("⚠️ Impossible de créer NetworkLimiter: {}", e);
-        }
-    }
-    
-    info!("🎯 Système QoS prêt pour utilisation");
-}
-
-fn setup_logging() -> Option<tracing_appender::non_blocking::WorkerGuard> {
+/// Returns the guard flushing the file writer and the handle changing the level at runtime
+fn setup_logging(
+    console: bool,
+    level: logs::LogLevel,
+) -> Option<(tracing_appender::non_blocking::WorkerGuard, logs::LevelHandle)> {
     // Create logs directory if it doesn't exist
-    if let Err(e) = std::fs::create_dir_all("logs") {
+    if let Err(e) = std::fs::create_dir_all(logs::LOG_DIR) {
         eprintln!("Failed to create logs directory: {}", e);
         return None;
     }
 
     // File appender for logs
-    let file_appender = tracing_appender::rolling::daily("logs", "gamebooster.log");
+    let file_appender = tracing_appender::rolling::daily(logs::LOG_DIR, logs::LOG_FILE_PREFIX);
     let (non_blocking_file, guard) = tracing_appender::non_blocking(file_appender);
 
     // Console writer
     let (non_blocking_stdout, _guard_stdout) = tracing_appender::non_blocking(std::io::stdout());
 
+    // Le filtre est la première couche : son handle ne dépend pas des sorties
+    let (filter, level_handle) = reload::Layer::new(level.filter());
+
     // Build subscriber with both file and console outputs
     let subscriber = tracing_subscriber::registry()
+        .with(filter)
         .with(
             tracing_subscriber::fmt::layer()
                 .with_writer(non_blocking_file)
                 .with_ansi(false)
                 .with_target(false)
         )
-        .with(
+        .with(console.then(|| {
             tracing_subscriber::fmt::layer()
                 .with_writer(non_blocking_stdout)
                 .with_ansi(true)
                 .with_target(false)
-        )
-        .with(EnvFilter::new("info"));
+        }));
 
     // Set the subscriber as the global default
     if let Err(e) = tracing::subscriber::set_global_default(subscriber) {
@@ -137,5 +191,5 @@ fn setup_logging() -> Option<tracing_appender::non_blocking::WorkerGuard> {
         return None;
     }
 
-    Some(guard)
+    Some((guard, level_handle))
 }
\ No newline at end of file
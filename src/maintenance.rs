@@ -0,0 +1,379 @@
+//! # Cleanup before uninstallation
+//!
+//! `full_cleanup` removes every trace GameBooster left on the system, category by category,
+//! through the removal paths the app already uses (`NetworkLimiter::clear_all_limits`,
+//! `DefenderService::enable_immediately`, `os_tweaks::restore`...). Each category first lists
+//! what is actually present, so a second run finds nothing left to do; a dry run stops after
+//! this listing ("voici ce qui sera supprimé"). Items needing administrator rights are
+//! reported and left alone when the process is not elevated.
+//!
+//! The data folders (settings, history, logs) go last: the settings are read at the start
+//! and updated as items are restored, in case the cleanup is interrupted.
+
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+use crate::config::AppConfig;
+use crate::network::NetworkLimiter;
+
+/// Folders created next to the executable, removed by `CleanupCategory::AppData`
+const DATA_DIRS: &[&str] = &["config", "history", crate::logs::LOG_DIR, crate::diagnostics::REPORTS_DIR];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum CleanupCategory {
+    QosPolicies,
+    FirewallRules,
+    Defender,
+    Services,
+    PowerPlan,
+    OsTweaks,
+    HostsFile,
+    ScheduledTasks,
+    Autostart,
+    Notifications,
+    AppData,
+}
+
+impl CleanupCategory {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CleanupCategory::QosPolicies => "Politiques QoS",
+            CleanupCategory::FirewallRules => "Règles pare-feu",
+            CleanupCategory::Defender => "Windows Defender",
+            CleanupCategory::Services => "Services",
+            CleanupCategory::PowerPlan => "Plan d'alimentation",
+            CleanupCategory::OsTweaks => "Optimisations Windows",
+            CleanupCategory::HostsFile => "Fichier hosts",
+            CleanupCategory::ScheduledTasks => "Tâches planifiées",
+            CleanupCategory::Autostart => "Lancement au démarrage",
+            CleanupCategory::Notifications => "Notifications Windows",
+            CleanupCategory::AppData => "Données de GameBooster",
+        }
+    }
+
+    pub fn requires_elevation(&self) -> bool {
+        match self {
+            CleanupCategory::QosPolicies
+            | CleanupCategory::FirewallRules
+            | CleanupCategory::Defender
+            | CleanupCategory::Services
+            | CleanupCategory::OsTweaks
+            | CleanupCategory::HostsFile => true,
+            CleanupCategory::PowerPlan
+            | CleanupCategory::ScheduledTasks
+            | CleanupCategory::Autostart
+            | CleanupCategory::Notifications
+            | CleanupCategory::AppData => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum CleanupStatus {
+    /// Found by a dry run
+    Planned,
+    Removed,
+    Failed(String),
+    /// Left alone: the process is not elevated
+    NeedsElevation,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanupItem {
+    pub category: CleanupCategory,
+    pub description: String,
+    pub status: CleanupStatus,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CleanupReport {
+    pub dry_run: bool,
+    pub items: Vec<CleanupItem>,
+}
+
+impl CleanupReport {
+    pub fn failures(&self) -> usize {
+        self.items.iter().filter(|item| matches!(item.status, CleanupStatus::Failed(_))).count()
+    }
+
+    pub fn needs_elevation(&self) -> usize {
+        self.items.iter().filter(|item| item.status == CleanupStatus::NeedsElevation).count()
+    }
+
+    /// Whether `category` has an item that could not be removed
+    fn has_leftovers(&self, category: CleanupCategory) -> bool {
+        self.items
+            .iter()
+            .any(|item| item.category == category && !matches!(item.status, CleanupStatus::Removed))
+    }
+
+    /// Record `descriptions` and, unless dry run or not elevated, remove them with `remove`,
+    /// whose outcome applies to all of them. Returns true when `remove` succeeded.
+    fn run(
+        &mut self,
+        category: CleanupCategory,
+        descriptions: Vec<String>,
+        elevated: bool,
+        remove: impl FnOnce() -> Result<()>,
+    ) -> bool {
+        if descriptions.is_empty() {
+            return false;
+        }
+        let (status, removed) = if self.dry_run {
+            (CleanupStatus::Planned, false)
+        } else if category.requires_elevation() && !elevated {
+            (CleanupStatus::NeedsElevation, false)
+        } else {
+            match remove() {
+                Ok(()) => (CleanupStatus::Removed, true),
+                Err(e) => {
+                    tracing::error!("❌ Nettoyage {} : {:#}", category.label(), e);
+                    (CleanupStatus::Failed(format!("{:#}", e)), false)
+                }
+            }
+        };
+        for description in descriptions {
+            self.items.push(CleanupItem { category, description, status: status.clone() });
+        }
+        removed
+    }
+
+    /// The inventory of `category` itself failed
+    fn inventory_failed(&mut self, category: CleanupCategory, error: &anyhow::Error) {
+        tracing::warn!("⚠️ Inventaire {} impossible: {:#}", category.label(), error);
+        self.items.push(CleanupItem {
+            category,
+            description: "Inventaire impossible".to_string(),
+            status: CleanupStatus::Failed(format!("{:#}", error)),
+        });
+    }
+}
+
+/// Absolute path of a data folder, for the report
+fn data_dir(name: &str) -> PathBuf {
+    std::env::current_dir().map(|dir| dir.join(name)).unwrap_or_else(|_| PathBuf::from(name))
+}
+
+/// List (`dry_run`) or remove every GameBooster artifact. `limiter` is the one of the app when
+/// it runs, so that its state follows; a new one is created otherwise.
+pub fn full_cleanup(limiter: Option<&mut NetworkLimiter>, dry_run: bool) -> CleanupReport {
+    tracing::info!("🧹 Nettoyage avant désinstallation{}", if dry_run { " (inventaire)" } else { "" });
+    let elevated = crate::utils::is_elevated();
+    let mut report = CleanupReport { dry_run, items: Vec::new() };
+    let mut config = AppConfig::load();
+
+    let mut own_limiter = None;
+    let limiter = match limiter {
+        Some(limiter) => Ok(limiter),
+        None => match NetworkLimiter::new() {
+            Ok(created) => Ok(own_limiter.insert(created)),
+            Err(e) => Err(e),
+        },
+    };
+    match limiter {
+        Ok(limiter) => cleanup_network(&mut report, limiter, elevated),
+        Err(e) => {
+            report.inventory_failed(CleanupCategory::QosPolicies, &e);
+            report.inventory_failed(CleanupCategory::FirewallRules, &e);
+        }
+    }
+
+    cleanup_defender(&mut report, &mut config, elevated);
+    cleanup_services(&mut report, &mut config, elevated);
+
+    if let Some(guid) = config.power_plan_to_restore.clone() {
+        let restored = report.run(CleanupCategory::PowerPlan, vec![format!("Plan d'origine {} à réactiver", guid)], elevated, || {
+            crate::power::set_active_plan(&guid)
+        });
+        if restored {
+            config.power_plan_to_restore = None;
+        }
+    }
+
+    cleanup_os_tweaks(&mut report, elevated);
+
+    match crate::optimization::hosts_blocker::status() {
+        Ok(status) if status.section_present => {
+            let description = format!("Section GameBooster du fichier hosts ({} domaine(s))", status.entry_count());
+            report.run(CleanupCategory::HostsFile, vec![description], elevated, || {
+                crate::optimization::hosts_blocker::remove_profile().map(|_| ())
+            });
+        }
+        Ok(_) => {}
+        Err(e) => report.inventory_failed(CleanupCategory::HostsFile, &e),
+    }
+
+    cleanup_scheduled_tasks(&mut report, elevated);
+
+    if crate::utils::is_run_at_startup_enabled() {
+        report.run(CleanupCategory::Autostart, vec!["Entrée Run du registre (HKCU)".to_string()], elevated, || {
+            crate::utils::set_run_at_startup(false)
+        });
+    }
+
+    if crate::ui::notifications::system_toast::is_registered() {
+        let description = format!("Identifiant {} des notifications (HKCU)", crate::ui::notifications::system_toast::APP_ID);
+        report.run(CleanupCategory::Notifications, vec![description], elevated, crate::ui::notifications::system_toast::unregister);
+    }
+
+    // Tant qu'il reste quelque chose à restaurer, les données qui le décrivent sont gardées
+    let leftovers = [CleanupCategory::Defender, CleanupCategory::Services, CleanupCategory::PowerPlan, CleanupCategory::OsTweaks]
+        .into_iter()
+        .filter(|category| report.has_leftovers(*category))
+        .map(|category| category.label())
+        .collect::<Vec<_>>();
+    if !dry_run {
+        if let Err(e) = config.save() {
+            tracing::error!("❌ Paramètres non mis à jour après le nettoyage: {}", e);
+        }
+    }
+    cleanup_data_dirs(&mut report, elevated, &leftovers);
+
+    tracing::info!(
+        "🧹 Nettoyage {}: {} élément(s), {} échec(s), {} nécessitant les droits administrateur",
+        if dry_run { "inventorié" } else { "terminé" },
+        report.items.len(),
+        report.failures(),
+        report.needs_elevation()
+    );
+    report
+}
+
+fn cleanup_network(report: &mut CleanupReport, limiter: &mut NetworkLimiter, elevated: bool) {
+    match limiter.verify_qos_policies(false) {
+        Ok(policies) => {
+            let descriptions = policies.iter().map(|policy| format!("{} ({})", policy.name, policy.app_name)).collect();
+            report.run(CleanupCategory::QosPolicies, descriptions, elevated, || limiter.clear_all_limits());
+        }
+        Err(e) => report.inventory_failed(CleanupCategory::QosPolicies, &e),
+    }
+
+    match limiter.list_block_rules() {
+        Ok(rules) => {
+            report.run(CleanupCategory::FirewallRules, rules, elevated, || limiter.clear_all_blocks());
+        }
+        Err(e) => report.inventory_failed(CleanupCategory::FirewallRules, &e),
+    }
+}
+
+fn cleanup_defender(report: &mut CleanupReport, config: &mut AppConfig, elevated: bool) {
+    use crate::services::defender::DefenderService;
+
+    // Pause en cours, ou désactivation sans échéance depuis l'onglet Services
+    let description = match config.defender_reenable_at {
+        Some(deadline) => format!("Pause jusqu'à {} à lever", deadline.format("%d/%m %H:%M")),
+        None if !DefenderService::is_active() => "Protection en temps réel désactivée".to_string(),
+        None => return,
+    };
+    let enabled = report.run(CleanupCategory::Defender, vec![description], elevated, || {
+        let status = DefenderService::enable_immediately()?;
+        if !status.real_time_protection {
+            return Err(anyhow!("protection en temps réel toujours inactive ({})", status.status_text));
+        }
+        Ok(())
+    });
+    if enabled {
+        // `enable_immediately` efface aussi l'échéance sur disque
+        config.defender_reenable_at = None;
+    }
+}
+
+fn cleanup_services(report: &mut CleanupReport, config: &mut AppConfig, elevated: bool) {
+    use crate::services::gaming_services;
+
+    // Types de démarrage d'abord : un service désactivé ne redémarre pas
+    for (service, start_type) in config.services_start_types.clone() {
+        let description = format!("{} : type de démarrage « {} » à rétablir", service, start_type);
+        if report.run(CleanupCategory::Services, vec![description], elevated, || {
+            gaming_services::set_start_type(&service, &start_type)
+        }) {
+            config.services_start_types.remove(&service);
+        }
+    }
+
+    for service in config.services_to_restart.clone() {
+        let description = format!("{} : arrêté, à redémarrer", service);
+        if report.run(CleanupCategory::Services, vec![description], elevated, || {
+            let results = gaming_services::restart_services(std::slice::from_ref(&service));
+            match results.operations.iter().find(|op| !op.success) {
+                Some(op) => Err(anyhow!("{}", op.error_message.as_deref().unwrap_or("redémarrage impossible"))),
+                None => Ok(()),
+            }
+        }) {
+            config.services_to_restart.retain(|name| *name != service);
+        }
+    }
+}
+
+fn cleanup_os_tweaks(report: &mut CleanupReport, elevated: bool) {
+    use crate::disk::os_tweaks::{self, TweakJournal};
+
+    let journal = TweakJournal::load();
+    if journal.entries.is_empty() {
+        return;
+    }
+    let labels: Vec<String> = journal.entries.iter().map(|entry| entry.label.clone()).collect();
+    if report.dry_run || !elevated {
+        report.run(CleanupCategory::OsTweaks, labels, elevated, || Ok(()));
+        return;
+    }
+
+    // Le journal garde les entrées non restaurées : une relance reprend là où on s'est arrêté
+    let results = os_tweaks::restore();
+    for outcome in results.outcomes {
+        let detail = outcome.detail.clone();
+        report.run(CleanupCategory::OsTweaks, vec![outcome.label], elevated, || {
+            if outcome.success {
+                Ok(())
+            } else {
+                Err(anyhow!("{}", detail))
+            }
+        });
+    }
+}
+
+fn cleanup_scheduled_tasks(report: &mut CleanupReport, elevated: bool) {
+    use crate::scheduler::config::SchedulerConfig;
+    use crate::scheduler::windows_tasks;
+    use crate::services::defender::REENABLE_TASK_NAME;
+    use crate::utils::scheduled_task;
+
+    for task in SchedulerConfig::load().tasks {
+        if windows_tasks::exists(&task.id) {
+            let description = format!("{} ({})", task.task_type.label(), task.schedule.describe());
+            report.run(CleanupCategory::ScheduledTasks, vec![description], elevated, || windows_tasks::unregister(&task.id));
+        }
+    }
+    if scheduled_task::task_exists(REENABLE_TASK_NAME) {
+        report.run(CleanupCategory::ScheduledTasks, vec![REENABLE_TASK_NAME.to_string()], elevated, || {
+            scheduled_task::delete_task(REENABLE_TASK_NAME)
+        });
+    }
+}
+
+/// `leftovers`: categories still to restore, whose state lives in the settings folder
+fn cleanup_data_dirs(report: &mut CleanupReport, elevated: bool, leftovers: &[&str]) {
+    for name in DATA_DIRS {
+        let path = data_dir(name);
+        if !path.exists() {
+            continue;
+        }
+        let description = path.display().to_string();
+        if !report.dry_run && *name == "config" && !leftovers.is_empty() {
+            report.items.push(CleanupItem {
+                category: CleanupCategory::AppData,
+                description,
+                status: CleanupStatus::Failed(format!("conservé, reste à restaurer : {}", leftovers.join(", "))),
+            });
+            continue;
+        }
+        report.run(CleanupCategory::AppData, vec![description], elevated, || {
+            std::fs::remove_dir_all(&path).map_err(|e| {
+                // Le journal du jour reste ouvert par l'application jusqu'à sa fermeture
+                anyhow!("{} (fichier en cours d'utilisation ? relancez après avoir fermé GameBooster)", e)
+            })
+        });
+    }
+}
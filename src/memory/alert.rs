@@ -0,0 +1,83 @@
+//! # Low memory alert
+//!
+//! Passive counterpart of the cleaning: a warning when the available physical memory falls
+//! below a floor or the pagefile fills up past a percentage. Each threshold fires once when
+//! crossed and re-arms only after memory went back past it by a margin, so that a value
+//! hovering around the threshold does not repeat the alert.
+
+use serde::{Deserialize, Serialize};
+
+use super::SystemMemoryInfo;
+
+const MB: u64 = 1024 * 1024;
+/// Available memory above the floor by this much re-arms the alert
+const AVAILABLE_RECOVERY_MB: u64 = 256;
+/// Pagefile usage below the threshold by this much re-arms the alert
+const PAGEFILE_RECOVERY_PERCENT: f32 = 5.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemoryAlertConfig {
+    pub enabled: bool,
+    /// Alert when the available physical memory falls below this, in MB
+    pub min_available_mb: u64,
+    pub pagefile_alert: bool,
+    /// Alert when the pagefile is used past this percentage
+    pub max_pagefile_percent: u8,
+    /// No Windows notification while a fullscreen game is in the foreground; the alert is
+    /// still listed behind the bell
+    pub quiet_when_fullscreen: bool,
+}
+
+impl Default for MemoryAlertConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_available_mb: 1536,
+            pagefile_alert: false,
+            max_pagefile_percent: 90,
+            quiet_when_fullscreen: true,
+        }
+    }
+}
+
+/// Which thresholds are currently crossed
+#[derive(Debug, Default)]
+pub struct MemoryAlert {
+    low_available: bool,
+    high_pagefile: bool,
+}
+
+impl MemoryAlert {
+    /// Message when a threshold was just crossed, `None` while nothing new happened
+    pub fn check(&mut self, config: &MemoryAlertConfig, info: &SystemMemoryInfo) -> Option<String> {
+        if !config.enabled || info.total_physical == 0 {
+            *self = Self::default();
+            return None;
+        }
+        let mut messages = Vec::new();
+
+        let floor = config.min_available_mb * MB;
+        if !self.low_available && info.avail_physical < floor {
+            self.low_available = true;
+            messages.push(format!("mémoire faible — {:.1} Go disponibles", info.avail_physical as f64 / (1024 * MB) as f64));
+        } else if self.low_available && info.avail_physical >= floor + AVAILABLE_RECOVERY_MB * MB {
+            self.low_available = false;
+        }
+
+        let pagefile = info.pagefile_used_percent();
+        let threshold = config.max_pagefile_percent as f32;
+        if !config.pagefile_alert {
+            self.high_pagefile = false;
+        } else if !self.high_pagefile && pagefile > threshold {
+            self.high_pagefile = true;
+            messages.push(format!("fichier d'échange utilisé à {:.0} %", pagefile));
+        } else if self.high_pagefile && pagefile < threshold - PAGEFILE_RECOVERY_PERCENT {
+            self.high_pagefile = false;
+        }
+
+        let message = messages.join(", ");
+        let mut chars = message.chars();
+        chars.next().map(|first| first.to_uppercase().chain(chars).collect())
+    }
+}
@@ -1,12 +1,13 @@
 use anyhow::{Result};
+use std::collections::BTreeMap;
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 #[cfg(windows)]
-use windows_sys::Win32::Foundation::{CloseHandle, BOOL, MAX_PATH};
+use windows_sys::Win32::Foundation::{CloseHandle, BOOL, HANDLE, MAX_PATH};
 #[cfg(windows)]
 use windows_sys::Win32::System::ProcessStatus::{
     EmptyWorkingSet, EnumProcesses, GetModuleBaseNameW, K32GetProcessMemoryInfo,
-    PROCESS_MEMORY_COUNTERS,
+    PROCESS_MEMORY_COUNTERS, PROCESS_MEMORY_COUNTERS_EX,
 };
 #[cfg(windows)]
 use windows_sys::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
@@ -17,13 +18,50 @@ use windows_sys::Win32::System::Threading::{
 
 // Import from local utils module
 use crate::utils;
+use crate::logs::{log_every, SUMMARY_INTERVAL};
+use crate::safety::{self, ProtectionLevel};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+pub mod alert;
+pub mod wsl;
+
+/// A trimmed process. The counters other than `memory_freed` are 0 in reports saved before
+/// they were recorded.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ProcessCleaned {
     pub name: String,
+    #[serde(default)]
+    pub pid: u32,
+    #[serde(default)]
+    pub working_set_before: usize,
+    #[serde(default)]
+    pub working_set_after: usize,
+    /// Memory committed by the process alone, left unchanged by the trim
+    #[serde(default)]
+    pub private_bytes: usize,
+    /// `working_set_before - working_set_after`
     pub memory_freed: usize,
 }
 
+/// Settings of a memory cleaning started by a boost profile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemoryCleanOptions {
+    /// Process names whose working set is left untouched (the game itself), case-insensitive.
+    /// Ignored on Linux, where the system caches are dropped as a whole.
+    pub excluded_processes: Vec<String>,
+    /// Processes with a smaller working set are not trimmed: there is nothing to gain
+    pub min_working_set_mb: usize,
+}
+
+impl Default for MemoryCleanOptions {
+    fn default() -> Self {
+        Self {
+            excluded_processes: Vec::new(),
+            min_working_set_mb: 4,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CleaningResults {
     pub start_time: DateTime<Local>,
@@ -31,6 +69,11 @@ pub struct CleaningResults {
     pub processes: Vec<ProcessCleaned>,
     pub total_memory_before: usize,
     pub total_memory_after: usize,
+    /// Available physical memory of the system before and after, 0 in older reports
+    #[serde(default)]
+    pub available_before: u64,
+    #[serde(default)]
+    pub available_after: u64,
     pub has_error: bool,
     pub error_message: String,
     pub is_completed: bool,
@@ -42,6 +85,8 @@ impl CleaningResults {
             processes: Vec::new(),
             total_memory_before: 0,
             total_memory_after: 0,
+            available_before: 0,
+            available_after: 0,
             has_error: false,
             error_message: String::new(),
             is_completed: false,
@@ -57,9 +102,118 @@ impl CleaningResults {
             0
         }
     }
+
+    /// Working set freed by the listed processes
+    pub fn working_set_freed(&self) -> usize {
+        self.processes.iter().map(|process| process.memory_freed).sum()
+    }
+
+    /// Change of the system available memory across the cleaning, `None` when it was not
+    /// measured. Usually well below `total_freed`: trimmed pages stay in the standby or
+    /// modified lists, and processes fault part of them back in at once.
+    pub fn available_gained(&self) -> Option<i64> {
+        if self.available_before == 0 || self.available_after == 0 {
+            return None;
+        }
+        Some(self.available_after as i64 - self.available_before as i64)
+    }
+
+    /// Add a trimmed process to the totals; the entry to list is returned when it freed something
+    #[cfg_attr(not(windows), allow(dead_code))]
+    fn record_trim(&mut self, pid: u32, name: String, before: usize, after: usize, private_bytes: usize) -> Option<ProcessCleaned> {
+        self.total_memory_before += before;
+        self.total_memory_after += after;
+        let memory_freed = before.saturating_sub(after);
+        (memory_freed > 0).then(|| ProcessCleaned {
+            name,
+            pid,
+            working_set_before: before,
+            working_set_after: after,
+            private_bytes,
+            memory_freed,
+        })
+    }
+
+    /// List the processes recorded by `record_trim` with their position in the enumeration:
+    /// most freed first, ties in enumeration order, as a serial run would give
+    #[cfg_attr(not(windows), allow(dead_code))]
+    fn set_processes(&mut self, mut cleaned: Vec<(usize, ProcessCleaned)>) {
+        cleaned.sort_by_key(|(index, _)| *index);
+        self.processes = cleaned.into_iter().map(|(_, process)| process).collect();
+        self.processes.sort_by(|a, b| b.memory_freed.cmp(&a.memory_freed));
+    }
+
+    /// Compare with the run before. Processes are matched by name, case-insensitively, and the
+    /// instances sharing a name (one per tab, worker...) are summed.
+    pub fn diff(&self, previous: &CleaningResults) -> CleaningDiff {
+        let current_by_name = freed_by_name(&self.processes);
+        let previous_by_name = freed_by_name(&previous.processes);
+
+        let mut diff = CleaningDiff {
+            freed_delta: self.total_freed() as i64 - previous.total_freed() as i64,
+            ..Default::default()
+        };
+        for (key, (name, freed)) in &current_by_name {
+            match previous_by_name.get(key) {
+                Some((_, previous_freed)) => diff.changed.push(ProcessDiff {
+                    name: name.clone(),
+                    previous_freed: *previous_freed,
+                    freed: *freed,
+                }),
+                None => diff.appeared.push(name.clone()),
+            }
+        }
+        diff.disappeared = previous_by_name
+            .iter()
+            .filter(|(key, _)| !current_by_name.contains_key(*key))
+            .map(|(_, (name, _))| name.clone())
+            .collect();
+        // Plus grands écarts en premier
+        diff.changed.sort_by_key(|process| std::cmp::Reverse(process.delta().unsigned_abs()));
+        diff
+    }
+}
+
+/// Lowercase name -> (name as first seen, bytes freed by all its instances)
+fn freed_by_name(processes: &[ProcessCleaned]) -> BTreeMap<String, (String, usize)> {
+    let mut by_name: BTreeMap<String, (String, usize)> = BTreeMap::new();
+    for process in processes {
+        by_name
+            .entry(process.name.to_lowercase())
+            .or_insert_with(|| (process.name.clone(), 0))
+            .1 += process.memory_freed;
+    }
+    by_name
+}
+
+/// Difference between two memory cleanings, see `CleaningResults::diff`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CleaningDiff {
+    /// Total freed by this run minus the total freed by the previous one, in bytes
+    pub freed_delta: i64,
+    /// Cleaned by this run only
+    pub appeared: Vec<String>,
+    /// Cleaned by the previous run only
+    pub disappeared: Vec<String>,
+    /// Cleaned by both runs, largest change first
+    pub changed: Vec<ProcessDiff>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessDiff {
+    pub name: String,
+    pub previous_freed: usize,
+    pub freed: usize,
+}
+
+impl ProcessDiff {
+    /// Bytes freed in this run minus the previous one
+    pub fn delta(&self) -> i64 {
+        self.freed as i64 - self.previous_freed as i64
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub struct SystemMemoryInfo {
     pub total_physical: u64,
     pub avail_physical: u64,
@@ -79,12 +233,224 @@ impl SystemMemoryInfo {
             (self.used_physical() as f32 / self.total_physical as f32) * 100.0
         }
     }
+
+    pub fn pagefile_used_percent(&self) -> f32 {
+        if self.total_pagefile == 0 {
+            0.0
+        } else {
+            (self.total_pagefile - self.avail_pagefile) as f32 / self.total_pagefile as f32 * 100.0
+        }
+    }
+}
+
+/// Memory usage of a single process, as shown in the memory tab table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessMemoryInfo {
+    pub pid: u32,
+    pub name: String,
+    pub working_set: usize,
+    pub private_bytes: usize,
+    /// See `safety`: critical processes cannot be trimmed
+    #[serde(skip)]
+    pub protection: ProtectionLevel,
+    /// Executable, for its icon; `None` when access is denied
+    #[serde(default)]
+    pub exe_path: Option<std::path::PathBuf>,
+    /// `vmmem` of WSL2 / Hyper-V, see `wsl`: never trimmed
+    #[serde(default)]
+    pub is_vm_host: bool,
+}
+
+/// Whether memory cleaning needs administrator/root rights to do anything useful.
+/// On Windows EmptyWorkingSet still works on the user's own processes.
+pub fn requires_elevation() -> bool {
+    cfg!(not(windows))
 }
 
-// Fonction principale pour nettoyer la mémoire
-#[cfg(windows)]
 pub fn clean_memory() -> Result<CleaningResults> {
+    clean_memory_with_options(&MemoryCleanOptions::default())
+}
+
+/// Trim the working sets (drop the caches on Linux) inside a `memory_clean` span. Cleanings
+/// triggered by the alert or a launcher can be frequent: the summary reaches the info level
+/// once per `SUMMARY_INTERVAL`.
+pub fn clean_memory_with_options(options: &MemoryCleanOptions) -> Result<CleaningResults> {
+    let started = std::time::Instant::now();
+    let span = tracing::info_span!(
+        "memory_clean",
+        processes = tracing::field::Empty,
+        freed_mb = tracing::field::Empty,
+        duration_ms = tracing::field::Empty
+    );
+    let _entered = span.enter();
+    let results = clean_memory_platform(options)?;
+
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let freed_mb = results.total_freed() / 1024 / 1024;
+    span.record("processes", results.processes.len());
+    span.record("freed_mb", freed_mb);
+    span.record("duration_ms", duration_ms);
+    log_every!(
+        SUMMARY_INTERVAL,
+        "🧹 Nettoyage mémoire terminé : {} processus, {} Mo libérés ({} ms)",
+        results.processes.len(),
+        freed_mb,
+        duration_ms
+    );
+    Ok(results)
+}
+
+/// Processes trimmed at the same time by `clean_memory_with_options`; more only adds handle pressure
+#[cfg(windows)]
+const CLEAN_WORKERS: usize = 8;
+/// A process still being trimmed after this is abandoned (hung `OpenProcess` / `K32GetProcessMemoryInfo`)
+#[cfg(windows)]
+const PROCESS_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+#[cfg(windows)]
+enum CleanMessage {
+    /// `index`: position in the enumeration, to keep the order of a serial run
+    Trimmed { index: usize, pid: u32, trimmed: TrimmedProcess },
+    /// No process left for this worker
+    Finished(usize),
+}
+
+// Fonction principale pour nettoyer la mémoire : les PID sont répartis entre `CLEAN_WORKERS` threads,
+// un processus qui ne répond pas est abandonné après `PROCESS_TIMEOUT` sans bloquer les autres
+#[cfg(windows)]
+fn clean_memory_platform(options: &MemoryCleanOptions) -> Result<CleaningResults> {
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{mpsc, Arc, Mutex};
+    use std::time::Instant;
+
     let mut results = CleaningResults::new();
+    results.available_before = get_detailed_system_memory_info().avail_physical;
+    let pids: Arc<Vec<u32>> = Arc::new(enumerate_pids()?.into_iter().filter(|pid| *pid != 0).collect());
+
+    let current_process_handle = unsafe { GetCurrentProcess() };
+    unsafe { EmptyWorkingSet(current_process_handle) };
+
+    let excluded: Arc<Vec<String>> = Arc::new(options.excluded_processes.iter().map(|name| name.to_lowercase()).collect());
+    let floor = options.min_working_set_mb * 1024 * 1024;
+    let next = Arc::new(AtomicUsize::new(0));
+    let cancelled = Arc::new(AtomicBool::new(false));
+    // Processus en cours par worker, avec l'heure de début
+    let in_progress: Arc<Mutex<Vec<Option<(u32, Instant)>>>> = Arc::new(Mutex::new(vec![None; CLEAN_WORKERS]));
+    let (sender, receiver) = mpsc::channel();
+
+    for worker in 0..CLEAN_WORKERS {
+        let (pids, excluded, next, cancelled, in_progress, worker_sender) =
+            (pids.clone(), excluded.clone(), next.clone(), cancelled.clone(), in_progress.clone(), sender.clone());
+        // Threads détachés : un appel bloqué ne doit pas retenir le nettoyage
+        let spawned = std::thread::Builder::new().name(format!("memory_clean_{}", worker)).spawn(move || {
+            while !cancelled.load(Ordering::Relaxed) {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(&pid) = pids.get(index) else {
+                    break;
+                };
+                in_progress.lock().unwrap()[worker] = Some((pid, Instant::now()));
+                let trimmed = trim_process_for_cleaning(pid, &excluded, floor);
+                in_progress.lock().unwrap()[worker] = None;
+                if let Some(trimmed) = trimmed {
+                    if worker_sender.send(CleanMessage::Trimmed { index, pid, trimmed }).is_err() {
+                        return;
+                    }
+                }
+            }
+            let _ = worker_sender.send(CleanMessage::Finished(worker));
+        });
+        if let Err(e) = spawned {
+            tracing::warn!("⚠️ Thread de nettoyage mémoire {} non démarré: {}", worker, e);
+            // Compté comme terminé pour ne pas l'attendre
+            let _ = sender.send(CleanMessage::Finished(worker));
+        }
+    }
+    drop(sender);
+
+    let mut cleaned: Vec<(usize, ProcessCleaned)> = Vec::new();
+    let mut finished: HashSet<usize> = HashSet::new();
+    let mut hung: HashSet<usize> = HashSet::new();
+    let mut abandoned: HashSet<u32> = HashSet::new();
+    while (0..CLEAN_WORKERS).any(|worker| !finished.contains(&worker) && !hung.contains(&worker)) {
+        match receiver.recv_timeout(std::time::Duration::from_millis(100)) {
+            Ok(CleanMessage::Trimmed { index, pid, trimmed }) => {
+                if abandoned.contains(&pid) {
+                    continue;
+                }
+                let TrimmedProcess { name, before, after, private_bytes } = trimmed;
+                if let Some(process) = results.record_trim(pid, name, before, after, private_bytes) {
+                    cleaned.push((index, process));
+                }
+            }
+            Ok(CleanMessage::Finished(worker)) => {
+                finished.insert(worker);
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+
+        for (worker, slot) in in_progress.lock().unwrap().iter().enumerate() {
+            if let Some((pid, since)) = slot {
+                if since.elapsed() > PROCESS_TIMEOUT && !hung.contains(&worker) {
+                    tracing::warn!("⏱️ Processus {} sans réponse après {} s, ignoré", pid, PROCESS_TIMEOUT.as_secs());
+                    hung.insert(worker);
+                    abandoned.insert(*pid);
+                }
+            }
+        }
+    }
+    cancelled.store(true, Ordering::Relaxed);
+
+    results.set_processes(cleaned);
+
+    results.available_after = get_detailed_system_memory_info().avail_physical;
+    results.is_completed = true;
+    results.end_time = Some(Local::now());
+    Ok(results)
+}
+
+/// Counters of a process trimmed by `clean_memory_with_options`
+#[cfg(windows)]
+struct TrimmedProcess {
+    name: String,
+    before: usize,
+    after: usize,
+    private_bytes: usize,
+}
+
+/// A trimmed process; `None` when it cannot be opened, is excluded (`excluded` is lowercase)
+/// or is under `floor` bytes
+#[cfg(windows)]
+fn trim_process_for_cleaning(pid: u32, excluded: &[String], floor: usize) -> Option<TrimmedProcess> {
+    let handle = unsafe {
+        OpenProcess(
+            PROCESS_QUERY_INFORMATION | PROCESS_VM_READ | PROCESS_SET_QUOTA,
+            BOOL::from(false),
+            pid,
+        )
+    };
+    if handle.is_null() {
+        return None;
+    }
+
+    let process_name = process_name_from_handle(handle, pid);
+    // Pas de confirmation possible pendant un nettoyage global : processus protégés épargnés
+    let trimmed = if excluded.contains(&process_name.to_lowercase())
+        || wsl::is_vm_host(&process_name)
+        || safety::is_protected_process(pid, &process_name, None).is_protected()
+    {
+        None
+    } else {
+        trim_working_set_above(handle, floor)
+    };
+    unsafe { CloseHandle(handle) };
+    trimmed.map(|(before, after, private_bytes)| TrimmedProcess { name: process_name, before, after, private_bytes })
+}
+
+// Liste des PID actifs via EnumProcesses
+#[cfg(windows)]
+fn enumerate_pids() -> Result<Vec<u32>> {
     let mut pids = [0u32; 2048];
     let mut bytes_returned = 0;
 
@@ -99,107 +465,150 @@ pub fn clean_memory() -> Result<CleaningResults> {
         return Err(anyhow::anyhow!("Failed to enumerate processes."));
     }
 
-    let current_process_handle = unsafe { GetCurrentProcess() };
-    unsafe { EmptyWorkingSet(current_process_handle) };
+    Ok(pids[..bytes_returned as usize / std::mem::size_of::<u32>()].to_vec())
+}
+
+#[cfg(windows)]
+fn process_name_from_handle(handle: HANDLE, pid: u32) -> String {
+    let mut name_buffer = [0u16; MAX_PATH as usize];
+    let name_len = unsafe {
+        GetModuleBaseNameW(
+            handle,
+            std::ptr::null_mut(),
+            name_buffer.as_mut_ptr(),
+            MAX_PATH,
+        )
+    };
+
+    if name_len > 0 {
+        String::from_utf16_lossy(&name_buffer[..name_len as usize])
+    } else {
+        format!("PID: {}", pid)
+    }
+}
+
+// Retourne (working set, private bytes) pour un handle ouvert
+#[cfg(windows)]
+fn query_process_memory(handle: HANDLE) -> Option<(usize, usize)> {
+    let mut mem_counters: PROCESS_MEMORY_COUNTERS_EX = unsafe { std::mem::zeroed() };
+    mem_counters.cb = std::mem::size_of::<PROCESS_MEMORY_COUNTERS_EX>() as u32;
+
+    if unsafe {
+        K32GetProcessMemoryInfo(
+            handle,
+            &mut mem_counters as *mut PROCESS_MEMORY_COUNTERS_EX as *mut PROCESS_MEMORY_COUNTERS,
+            mem_counters.cb,
+        )
+    } != 0
+    {
+        Some((mem_counters.WorkingSetSize, mem_counters.PrivateUsage))
+    } else {
+        None
+    }
+}
+
+// Vide le working set d'un processus et retourne (avant, après, private bytes)
+#[cfg(windows)]
+fn trim_working_set(handle: HANDLE) -> Option<(usize, usize, usize)> {
+    trim_working_set_above(handle, 0)
+}
+
+// Idem, sans toucher aux working sets plus petits que `floor` octets
+#[cfg(windows)]
+fn trim_working_set_above(handle: HANDLE, floor: usize) -> Option<(usize, usize, usize)> {
+    let (before_memory, _) = query_process_memory(handle)?;
+    if before_memory < floor {
+        return None;
+    }
+
+    if unsafe { EmptyWorkingSet(handle) } == 0 {
+        return None;
+    }
+
+    let (after_memory, private_bytes) = query_process_memory(handle)?;
+    Some((before_memory, after_memory, private_bytes))
+}
 
-    for &pid in &pids[..bytes_returned as usize / std::mem::size_of::<u32>()] {
+/// List every accessible process with its working set and private bytes
+#[cfg(windows)]
+pub fn list_processes_memory() -> Result<Vec<ProcessMemoryInfo>> {
+    let mut processes = Vec::new();
+
+    for pid in enumerate_pids()? {
         if pid == 0 {
             continue;
         }
 
         let handle = unsafe {
             OpenProcess(
-                PROCESS_QUERY_INFORMATION | PROCESS_VM_READ | PROCESS_SET_QUOTA,
+                PROCESS_QUERY_INFORMATION | PROCESS_VM_READ,
                 BOOL::from(false),
                 pid,
             )
         };
-        if handle != std::ptr::null_mut() {
-            // Essayer d'obtenir le nom du processus
-            let mut name_buffer = [0u16; MAX_PATH as usize];
-            let name_len = unsafe {
-                GetModuleBaseNameW(
-                    handle,
-                    std::ptr::null_mut(),
-                    name_buffer.as_mut_ptr(),
-                    MAX_PATH,
-                )
-            };
-
-            let process_name = if name_len > 0 {
-                String::from_utf16_lossy(&name_buffer[..name_len as usize])
-            } else {
-                format!("PID: {}", pid)
-            };
-
-            // Obtenir la mémoire avant le nettoyage
-            let mut mem_counters = PROCESS_MEMORY_COUNTERS {
-                cb: std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
-                PageFaultCount: 0,
-                PeakWorkingSetSize: 0,
-                WorkingSetSize: 0,
-                QuotaPeakPagedPoolUsage: 0,
-                QuotaPagedPoolUsage: 0,
-                QuotaPeakNonPagedPoolUsage: 0,
-                QuotaNonPagedPoolUsage: 0,
-                PagefileUsage: 0,
-                PeakPagefileUsage: 0,
-            };
-
-            if unsafe {
-                K32GetProcessMemoryInfo(
-                    handle,
-                    &mut mem_counters,
-                    std::mem::size_of_val(&mem_counters) as u32,
-                )
-            } != 0
-            {
-                let before_memory = mem_counters.WorkingSetSize;
-                results.total_memory_before += before_memory;
-
-                if unsafe { EmptyWorkingSet(handle) } != 0 {
-                    if unsafe {
-                        K32GetProcessMemoryInfo(
-                            handle,
-                            &mut mem_counters,
-                            std::mem::size_of_val(&mem_counters) as u32,
-                        )
-                    } != 0
-                    {
-                        let after_memory = mem_counters.WorkingSetSize;
-                        results.total_memory_after += after_memory;
-
-                        // Calculer la mémoire libérée
-                        let freed_memory = if before_memory > after_memory {
-                            before_memory - after_memory
-                        } else {
-                            0
-                        };
-
-                        if freed_memory > 0 {
-                            results.processes.push(ProcessCleaned {
-                                name: process_name,
-                                memory_freed: freed_memory,
-                            });
-                        }
-                    }
-                }
-            }
+        if handle.is_null() {
+            continue;
+        }
 
-            unsafe { CloseHandle(handle) };
+        if let Some((working_set, private_bytes)) = query_process_memory(handle) {
+            let name = process_name_from_handle(handle, pid);
+            processes.push(ProcessMemoryInfo {
+                pid,
+                protection: safety::is_protected_process(pid, &name, None),
+                is_vm_host: wsl::is_vm_host(&name),
+                name,
+                working_set,
+                private_bytes,
+                exe_path: crate::network::query_full_image_path(pid),
+            });
         }
+
+        unsafe { CloseHandle(handle) };
     }
 
-    // Sort processes by memory freed in descending order
-    results.processes.sort_by(|a, b| b.memory_freed.cmp(&a.memory_freed));
+    processes.sort_by(|a, b| b.working_set.cmp(&a.working_set));
+    Ok(processes)
+}
 
-    results.is_completed = true;
-    results.end_time = Some(Local::now());
-    Ok(results)
+/// Trim the working set of a single process, returning the number of bytes released.
+/// Critical processes (see `safety`) are refused; the UI confirms the cautious ones.
+#[cfg(windows)]
+pub fn trim_process(pid: u32) -> Result<usize> {
+    let handle = unsafe {
+        OpenProcess(
+            PROCESS_QUERY_INFORMATION | PROCESS_VM_READ | PROCESS_SET_QUOTA,
+            BOOL::from(false),
+            pid,
+        )
+    };
+    if handle.is_null() {
+        return Err(anyhow::anyhow!("Impossible d'ouvrir le processus {}", pid));
+    }
+
+    let name = process_name_from_handle(handle, pid);
+    if let Err(e) = safety::ensure_not_critical(pid, &name, None, "vider la mémoire de") {
+        unsafe { CloseHandle(handle) };
+        return Err(e);
+    }
+    if wsl::is_vm_host(&name) {
+        unsafe { CloseHandle(handle) };
+        return Err(anyhow::anyhow!(
+            "{} contient la mémoire d'une machine virtuelle WSL2 / Hyper-V : utilisez « Compacter la mémoire WSL »",
+            name
+        ));
+    }
+
+    let trimmed = trim_working_set(handle);
+    unsafe { CloseHandle(handle) };
+
+    match trimmed {
+        Some((before, after, _)) => Ok(before.saturating_sub(after)),
+        None => Err(anyhow::anyhow!("Échec de EmptyWorkingSet pour le processus {}", pid)),
+    }
 }
 
 #[cfg(not(windows))]
-pub fn clean_memory() -> Result<CleaningResults> {
+fn clean_memory_platform(_options: &MemoryCleanOptions) -> Result<CleaningResults> {
     use std::process::Command;
     use sysinfo::{System};
 
@@ -207,6 +616,7 @@ pub fn clean_memory() -> Result<CleaningResults> {
     let mut sys = System::new_all();
     sys.refresh_memory();
     results.total_memory_before = (sys.total_memory() - sys.available_memory()) as usize;
+    results.available_before = sys.available_memory();
 
     if utils::is_elevated() {
         // Synchroniser les données sur le disque pour éviter la perte de données
@@ -238,6 +648,7 @@ pub fn clean_memory() -> Result<CleaningResults> {
 
     sys.refresh_memory(); // Re-vérifier après l'opération
     results.total_memory_after = (sys.total_memory() - sys.available_memory()) as usize;
+    results.available_after = sys.available_memory();
     results.is_completed = true;
     results.end_time = Some(Local::now());
 
@@ -256,6 +667,116 @@ pub fn clean_memory() -> Result<CleaningResults> {
     Ok(results)
 }
 
+#[cfg(not(windows))]
+pub fn list_processes_memory() -> Result<Vec<ProcessMemoryInfo>> {
+    use sysinfo::System;
+
+    let mut sys = System::new();
+    sys.refresh_processes();
+
+    let mut processes: Vec<ProcessMemoryInfo> = sys
+        .processes()
+        .iter()
+        .map(|(pid, process)| {
+            let working_set = process.memory() as usize;
+            ProcessMemoryInfo {
+                pid: pid.as_u32(),
+                name: process.name().to_string(),
+                working_set,
+                private_bytes: private_bytes_from_statm(pid.as_u32()).unwrap_or(working_set),
+                protection: safety::is_protected_process(pid.as_u32(), process.name(), process.exe()),
+                exe_path: process.exe().map(std::path::Path::to_path_buf),
+                is_vm_host: false,
+            }
+        })
+        .collect();
+
+    processes.sort_by(|a, b| b.working_set.cmp(&a.working_set));
+    Ok(processes)
+}
+
+// Approximation des private bytes sous Linux : resident - shared (/proc/<pid>/statm)
+#[cfg(not(windows))]
+fn private_bytes_from_statm(pid: u32) -> Option<usize> {
+    let statm = std::fs::read_to_string(format!("/proc/{}/statm", pid)).ok()?;
+    let mut fields = statm.split_whitespace().skip(1);
+    let resident: usize = fields.next()?.parse().ok()?;
+    let shared: usize = fields.next()?.parse().ok()?;
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    Some(resident.saturating_sub(shared) * page_size)
+}
+
+#[cfg(not(windows))]
+pub fn trim_process(pid: u32) -> Result<usize> {
+    Err(anyhow::anyhow!(
+        "Le vidage du working set du processus {} n'est disponible que sous Windows",
+        pid
+    ))
+}
+
+/// Release the standby list (cached file pages) to the free list. Needs administrator rights
+/// (SeProfileSingleProcessPrivilege).
+#[cfg(windows)]
+pub fn purge_standby_list() -> Result<()> {
+    use windows_sys::Wdk::System::SystemInformation::SYSTEM_INFORMATION_CLASS;
+    use windows_sys::Win32::Security::{
+        AdjustTokenPrivileges, LookupPrivilegeValueW, LUID_AND_ATTRIBUTES, SE_PRIVILEGE_ENABLED,
+        TOKEN_ADJUST_PRIVILEGES, TOKEN_PRIVILEGES, TOKEN_QUERY,
+    };
+    use windows_sys::Win32::System::Threading::OpenProcessToken;
+
+    // SystemMemoryListInformation / MemoryPurgeStandbyList (non documentés, stables depuis Vista)
+    const SYSTEM_MEMORY_LIST_INFORMATION: SYSTEM_INFORMATION_CLASS = 80;
+    const MEMORY_PURGE_STANDBY_LIST: i32 = 4;
+
+    // Absente de windows-sys : déclarée ici, exportée par ntdll
+    #[link(name = "ntdll")]
+    extern "system" {
+        fn NtSetSystemInformation(
+            system_information_class: SYSTEM_INFORMATION_CLASS,
+            system_information: *const std::ffi::c_void,
+            system_information_length: u32,
+        ) -> i32;
+    }
+
+    let privilege: Vec<u16> = "SeProfileSingleProcessPrivilege".encode_utf16().chain(Some(0)).collect();
+    unsafe {
+        let mut token = std::ptr::null_mut();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_ADJUST_PRIVILEGES | TOKEN_QUERY, &mut token) == 0 {
+            return Err(anyhow::anyhow!("Jeton du processus inaccessible: {}", std::io::Error::last_os_error()));
+        }
+        let mut privileges = TOKEN_PRIVILEGES {
+            PrivilegeCount: 1,
+            Privileges: [LUID_AND_ATTRIBUTES { Luid: std::mem::zeroed(), Attributes: SE_PRIVILEGE_ENABLED }],
+        };
+        let adjusted = LookupPrivilegeValueW(std::ptr::null(), privilege.as_ptr(), &mut privileges.Privileges[0].Luid) != 0
+            && AdjustTokenPrivileges(token, 0, &privileges, 0, std::ptr::null_mut(), std::ptr::null_mut()) != 0
+            // AdjustTokenPrivileges réussit aussi quand le privilège n'est pas attribué
+            && std::io::Error::last_os_error().raw_os_error() == Some(0);
+        CloseHandle(token);
+        if !adjusted {
+            return Err(anyhow::anyhow!("Privilège SeProfileSingleProcessPrivilege indisponible (droits administrateur requis)"));
+        }
+
+        let command = MEMORY_PURGE_STANDBY_LIST;
+        let status = NtSetSystemInformation(
+            SYSTEM_MEMORY_LIST_INFORMATION,
+            &command as *const i32 as *const std::ffi::c_void,
+            std::mem::size_of::<i32>() as u32,
+        );
+        if status < 0 {
+            return Err(anyhow::anyhow!("Purge de la liste standby refusée (NTSTATUS 0x{:08X})", status as u32));
+        }
+    }
+    tracing::info!("🧽 Liste standby purgée");
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn purge_standby_list() -> Result<()> {
+    Err(anyhow::anyhow!("La purge de la liste standby n'est disponible que sous Windows"))
+}
+
 // Fonction pour obtenir les informations sur la mémoire système
 #[cfg(windows)]
 pub fn get_system_memory_info() -> (u64, u64) {
@@ -311,4 +832,127 @@ pub fn get_detailed_system_memory_info() -> SystemMemoryInfo {
         total_pagefile: sys.total_swap(),
         avail_pagefile: sys.free_swap(), // sys.available_swap() n'existe pas, free_swap est le plus proche
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(processes: &[(&str, usize)], total_freed: usize) -> CleaningResults {
+        let mut results = CleaningResults::new();
+        results.processes = processes
+            .iter()
+            .map(|(name, freed)| ProcessCleaned { name: name.to_string(), memory_freed: *freed, ..Default::default() })
+            .collect();
+        results.total_memory_before = total_freed;
+        results
+    }
+
+    #[test]
+    fn diff_matches_processes_by_name() {
+        let previous = run(&[("chrome.exe", 100), ("Discord.exe", 50), ("steam.exe", 30)], 180);
+        let current = run(&[("chrome.exe", 40), ("chrome.exe", 20), ("discord.exe", 80), ("obs64.exe", 10)], 150);
+
+        let diff = current.diff(&previous);
+        assert_eq!(diff.freed_delta, -30);
+        assert_eq!(diff.appeared, vec!["obs64.exe".to_string()]);
+        assert_eq!(diff.disappeared, vec!["steam.exe".to_string()]);
+        // Instances additionnées, plus grand écart en premier
+        assert_eq!(
+            diff.changed,
+            vec![
+                ProcessDiff { name: "chrome.exe".to_string(), previous_freed: 100, freed: 60 },
+                ProcessDiff { name: "discord.exe".to_string(), previous_freed: 50, freed: 80 },
+            ]
+        );
+        assert_eq!(diff.changed[0].delta(), -40);
+        assert_eq!(diff.changed[1].delta(), 30);
+    }
+
+    #[test]
+    fn totals_add_up_the_trimmed_processes() {
+        const MB: usize = 1024 * 1024;
+        let mut results = CleaningResults::new();
+        let cleaned: Vec<(usize, ProcessCleaned)> = [
+            (10, "a.exe", 100 * MB, 40 * MB, 80 * MB),
+            (20, "b.exe", 50 * MB, 50 * MB, 30 * MB),
+            // Le working set a grossi pendant le trim : rien de libéré, totaux quand même comptés
+            (30, "c.exe", 10 * MB, 12 * MB, 5 * MB),
+            (40, "d.exe", 80 * MB, 20 * MB, 60 * MB),
+            (50, "e.exe", 20 * MB, 10 * MB, 15 * MB),
+        ]
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, (pid, name, before, after, private))| {
+            results.record_trim(pid, name.to_string(), before, after, private).map(|process| (index, process))
+        })
+        .rev()
+        .collect();
+        results.set_processes(cleaned);
+
+        assert_eq!(results.total_memory_before, 260 * MB);
+        assert_eq!(results.total_memory_after, 132 * MB);
+        assert_eq!(results.total_freed(), 128 * MB);
+        assert_eq!(results.working_set_freed(), 130 * MB);
+        // a et d ex aequo : ordre d'énumération
+        let order: Vec<u32> = results.processes.iter().map(|process| process.pid).collect();
+        assert_eq!(order, vec![10, 40, 50]);
+        assert_eq!(results.processes[0].working_set_after, 40 * MB);
+        assert_eq!(results.processes[0].private_bytes, 80 * MB);
+    }
+
+    #[test]
+    fn available_memory_change_needs_both_measures() {
+        let mut results = CleaningResults::new();
+        assert_eq!(results.available_gained(), None);
+        results.available_before = 4_000;
+        assert_eq!(results.available_gained(), None);
+        results.available_after = 3_500;
+        assert_eq!(results.available_gained(), Some(-500));
+        results.total_memory_after = 10;
+        assert_eq!(results.total_freed(), 0);
+    }
+
+    #[test]
+    fn diff_with_itself_changes_nothing() {
+        let results = run(&[("chrome.exe", 100)], 100);
+        let diff = results.diff(&results);
+        assert_eq!(diff.freed_delta, 0);
+        assert!(diff.appeared.is_empty() && diff.disappeared.is_empty());
+        assert_eq!(diff.changed[0].delta(), 0);
+        assert_eq!(CleaningResults::new().diff(&CleaningResults::new()), CleaningDiff::default());
+    }
+
+    /// Worker pool against a serial pass over the same PIDs. Trims the working sets of the
+    /// machine: `cargo test --release -- --ignored --nocapture clean_memory_benchmark`
+    #[cfg(windows)]
+    #[test]
+    #[ignore = "benchmark, trims the working sets of the machine"]
+    fn clean_memory_benchmark() {
+        let options = MemoryCleanOptions::default();
+        let floor = options.min_working_set_mb * 1024 * 1024;
+
+        let started = std::time::Instant::now();
+        let serial = enumerate_pids()
+            .unwrap()
+            .into_iter()
+            .filter(|pid| *pid != 0)
+            .filter_map(|pid| trim_process_for_cleaning(pid, &[], floor))
+            .count();
+        let serial_elapsed = started.elapsed();
+
+        // Working sets déjà vidés par la passe en série : mêmes processus ouverts, moins de pages à rendre
+        let started = std::time::Instant::now();
+        let results = clean_memory_with_options(&options).unwrap();
+        let pool_elapsed = started.elapsed();
+
+        println!(
+            "série : {} processus en {:?}, {} workers : {} processus en {:?}",
+            serial,
+            serial_elapsed,
+            CLEAN_WORKERS,
+            results.processes.len(),
+            pool_elapsed
+        );
+        assert!(results.is_completed);
+    }
+}
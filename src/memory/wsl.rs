@@ -0,0 +1,213 @@
+//! # WSL2 and Hyper-V virtual machines
+//!
+//! `vmmem` (Hyper-V, Docker Desktop) and `vmmemWSL` (WSL2) stand for the memory and the
+//! network traffic of a virtual machine. Neither tool of GameBooster works on them:
+//! - `EmptyWorkingSet` only pushes guest memory to the page file, the guest kernel touches it
+//!   again at once: `clean_memory` leaves them alone
+//! - the guest traffic leaves through the virtual switch, not under the image path of
+//!   `vmmem`: an executable-matched QoS policy never applies, a port rule does
+//!
+//! What frees their memory happens inside the VM: `wsl --shutdown` stops every distribution,
+//! and `autoMemoryReclaim` (WSL 1.3.10 and later, `%UserProfile%\.wslconfig`) lets the VM
+//! give its cache back on its own.
+
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+
+use crate::system::CommandRunner;
+
+/// Executables standing for a virtual machine, lowercase without `.exe`
+const VM_HOST_NAMES: &[&str] = &["vmmem", "vmmemwsl"];
+
+/// First WSL release reading `autoMemoryReclaim`
+const AUTO_MEMORY_RECLAIM_VERSION: [u32; 3] = [1, 3, 10];
+
+/// Badge tooltip of the memory and network rows
+pub const VM_HOST_HINT: &str = "Machine virtuelle WSL2 / Hyper-V (Docker Desktop) : sa mémoire appartient aux distributions \
+    et n'est pas vidée par le nettoyage, son trafic passe par le commutateur virtuel et ne se limite que par port";
+
+/// Refusal of the network limiter
+pub const VM_HOST_LIMIT_MESSAGE: &str = "héberge la machine virtuelle WSL2 / Hyper-V : une politique par exécutable ne \
+    verrait pas son trafic, utilisez une règle par port (Réseau → Règles par port)";
+
+pub fn is_vm_host(name: &str) -> bool {
+    let name = name.to_lowercase();
+    let stem = name.strip_suffix(".exe").unwrap_or(&name);
+    VM_HOST_NAMES.contains(&stem)
+}
+
+/// What the memory tab shows next to the "Compacter la mémoire WSL" action
+#[derive(Debug, Clone, Default)]
+pub struct WslStatus {
+    /// `wsl.exe` answered
+    pub installed: bool,
+    /// "2.0.9.0"; `None` for the WSL shipped with Windows, which has no `--version`
+    pub version: Option<String>,
+    pub running_distros: Vec<String>,
+    /// Value of `autoMemoryReclaim` in `.wslconfig`
+    pub auto_memory_reclaim: Option<String>,
+}
+
+impl WslStatus {
+    pub fn supports_auto_memory_reclaim(&self) -> bool {
+        self.version.as_deref().is_some_and(|version| {
+            let parts: Vec<u32> = version.split('.').filter_map(|part| part.parse().ok()).collect();
+            parts.as_slice() >= AUTO_MEMORY_RECLAIM_VERSION.as_slice()
+        })
+    }
+
+    pub fn auto_memory_reclaim_enabled(&self) -> bool {
+        self.auto_memory_reclaim
+            .as_deref()
+            .is_some_and(|value| !value.eq_ignore_ascii_case("disabled"))
+    }
+}
+
+/// Operations of the memory tab, run on a worker thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WslAction {
+    Shutdown,
+    EnableAutoMemoryReclaim,
+}
+
+impl WslAction {
+    /// Run the action, returning the message shown once done
+    pub fn run(self, runner: &dyn CommandRunner) -> Result<String> {
+        match self {
+            WslAction::Shutdown => {
+                shutdown(runner)?;
+                Ok("Distributions WSL arrêtées, mémoire de la machine virtuelle rendue à Windows".to_string())
+            }
+            WslAction::EnableAutoMemoryReclaim => {
+                let path = enable_auto_memory_reclaim()?;
+                Ok(format!("autoMemoryReclaim activé dans {} (pris en compte au prochain démarrage de WSL)", path.display()))
+            }
+        }
+    }
+}
+
+/// `wsl.exe` writes UTF-16: decoded as UTF-8, its ASCII text comes with a NUL after each
+/// character and a byte order mark
+fn clean_wsl_output(text: &str) -> String {
+    text.chars().filter(|c| !matches!(c, '\0' | '\u{FEFF}' | '\u{FFFD}')).collect()
+}
+
+/// Blocking: run on a worker thread
+pub fn query_status(runner: &dyn CommandRunner) -> WslStatus {
+    let mut status = WslStatus::default();
+    if !cfg!(windows) {
+        return status;
+    }
+    status.auto_memory_reclaim = wslconfig_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| ini_value(&content, "experimental", "autoMemoryReclaim"));
+
+    match runner.run("wsl.exe", &["--list", "--running", "--quiet"]) {
+        Ok(output) => {
+            status.installed = true;
+            // Code non nul quand aucune distribution ne tourne
+            if output.success() {
+                status.running_distros = clean_wsl_output(&output.stdout)
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(str::to_string)
+                    .collect();
+            }
+        }
+        Err(e) => {
+            tracing::debug!("🐧 WSL absent: {}", e);
+            return status;
+        }
+    }
+    if let Ok(output) = runner.run("wsl.exe", &["--version"]) {
+        // "WSL version: 2.0.9.0", "Version WSL : 2.0.9.0" selon la langue
+        status.version = output.success().then(|| clean_wsl_output(&output.stdout)).and_then(|text| {
+            text.lines().next()?.split_whitespace().find(|token| {
+                token.contains('.') && token.chars().all(|c| c.is_ascii_digit() || c == '.')
+            }).map(str::to_string)
+        });
+    }
+    status
+}
+
+/// Stop every distribution and the WSL2 virtual machine
+pub fn shutdown(runner: &dyn CommandRunner) -> Result<()> {
+    let output = runner.run("wsl.exe", &["--shutdown"])?;
+    if !output.success() {
+        let message = clean_wsl_output(&output.stderr);
+        let message = if message.trim().is_empty() { clean_wsl_output(&output.stdout) } else { message };
+        return Err(anyhow!("wsl --shutdown a échoué (code {:?}) : {}", output.code, message.trim()));
+    }
+    tracing::info!("🐧 WSL arrêté (wsl --shutdown)");
+    Ok(())
+}
+
+fn wslconfig_path() -> Option<PathBuf> {
+    std::env::var_os("USERPROFILE").map(|home| PathBuf::from(home).join(".wslconfig"))
+}
+
+/// Write `autoMemoryReclaim=gradual` in `.wslconfig`, keeping the other settings
+pub fn enable_auto_memory_reclaim() -> Result<PathBuf> {
+    let path = wslconfig_path().ok_or_else(|| anyhow!("Dossier utilisateur introuvable (USERPROFILE)"))?;
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(anyhow!("Lecture de {} impossible: {}", path.display(), e)),
+    };
+    let updated = set_ini_value(&content, "experimental", "autoMemoryReclaim", "gradual");
+    std::fs::write(&path, updated).map_err(|e| anyhow!("Écriture de {} impossible: {}", path.display(), e))?;
+    tracing::info!("🐧 autoMemoryReclaim=gradual écrit dans {}", path.display());
+    Ok(path)
+}
+
+fn is_section(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with('[') && line.ends_with(']')
+}
+
+fn is_named_section(line: &str, section: &str) -> bool {
+    is_section(line) && line.trim()[1..line.trim().len() - 1].trim().eq_ignore_ascii_case(section)
+}
+
+/// `key` of the line, when it is a `key=value` line
+fn line_key(line: &str) -> Option<&str> {
+    let (key, _) = line.split_once('=')?;
+    Some(key.trim())
+}
+
+fn ini_value(content: &str, section: &str, key: &str) -> Option<String> {
+    let mut in_section = false;
+    for line in content.lines() {
+        if is_section(line) {
+            in_section = is_named_section(line, section);
+        } else if in_section && line_key(line).is_some_and(|name| name.eq_ignore_ascii_case(key)) {
+            return line.split_once('=').map(|(_, value)| value.trim().to_string());
+        }
+    }
+    None
+}
+
+/// Replace `key` in `section`, or add it (and the section) when missing
+fn set_ini_value(content: &str, section: &str, key: &str, value: &str) -> String {
+    let setting = format!("{}={}", key, value);
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let Some(header) = lines.iter().position(|line| is_named_section(line, section)) else {
+        if !lines.last().map_or(true, |line| line.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.push(format!("[{}]", section));
+        lines.push(setting);
+        return lines.join("\n") + "\n";
+    };
+    let end = lines[header + 1..]
+        .iter()
+        .position(|line| is_section(line))
+        .map_or(lines.len(), |offset| header + 1 + offset);
+    match (header + 1..end).find(|&index| line_key(&lines[index]).is_some_and(|name| name.eq_ignore_ascii_case(key))) {
+        Some(index) => lines[index] = setting,
+        None => lines.insert(header + 1, setting),
+    }
+    lines.join("\n") + "\n"
+}
@@ -0,0 +1,207 @@
+//! # Network adapters
+//!
+//! Adapters of the machine and the one carrying the default route, used to show the active
+//! connection and to scope QoS policies to one adapter. Windows reads `GetAdaptersAddresses`;
+//! Linux reads `/sys/class/net` and the default route of `/proc/net/route`.
+
+use std::net::Ipv4Addr;
+
+use anyhow::Result;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdapterInfo {
+    /// Interface alias ("Wi-Fi", "Ethernet 2", "wlan0")
+    pub name: String,
+    pub description: String,
+    pub interface_index: u32,
+    pub is_up: bool,
+    pub is_wifi: bool,
+    /// Bits per second, 0 when unknown
+    pub link_speed: u64,
+    pub ipv4: Vec<Ipv4Addr>,
+    /// Carries a default route
+    pub has_gateway: bool,
+    /// Route metric, the lowest one wins between several connected adapters
+    pub metric: u32,
+}
+
+impl AdapterInfo {
+    /// "1 Gb/s", "866 Mb/s"
+    pub fn link_speed_text(&self) -> String {
+        match self.link_speed {
+            0 => "débit inconnu".to_string(),
+            speed if speed >= 1_000_000_000 => format!("{:.1} Gb/s", speed as f64 / 1e9),
+            speed => format!("{} Mb/s", speed / 1_000_000),
+        }
+    }
+}
+
+/// Adapter used for outgoing traffic: connected, with a default route, lowest metric
+pub fn active_adapter(adapters: &[AdapterInfo]) -> Option<&AdapterInfo> {
+    adapters
+        .iter()
+        .filter(|adapter| adapter.is_up && adapter.has_gateway)
+        .min_by_key(|adapter| adapter.metric)
+}
+
+/// Physical and virtual adapters, loopback excluded
+pub fn list_adapters() -> Result<Vec<AdapterInfo>> {
+    #[cfg(target_os = "windows")]
+    {
+        windows::list_adapters()
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::list_adapters()
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        Err(anyhow::anyhow!("Liste des cartes réseau non disponible sur cette plateforme"))
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::net::Ipv4Addr;
+
+    use anyhow::{anyhow, Result};
+    use windows_sys::Win32::Foundation::{ERROR_BUFFER_OVERFLOW, ERROR_SUCCESS};
+    use windows_sys::Win32::NetworkManagement::IpHelper::{
+        GetAdaptersAddresses, GAA_FLAG_INCLUDE_GATEWAYS, GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_DNS_SERVER,
+        GAA_FLAG_SKIP_MULTICAST, IF_TYPE_IEEE80211, IF_TYPE_SOFTWARE_LOOPBACK, IP_ADAPTER_ADDRESSES_LH,
+    };
+    use windows_sys::Win32::NetworkManagement::Ndis::IfOperStatusUp;
+    use windows_sys::Win32::Networking::WinSock::{AF_INET, AF_UNSPEC, SOCKADDR_IN};
+
+    use super::AdapterInfo;
+
+    fn wide_to_string(ptr: *const u16) -> String {
+        if ptr.is_null() {
+            return String::new();
+        }
+        let len = (0..).take_while(|&i| unsafe { *ptr.add(i) } != 0).count();
+        String::from_utf16_lossy(unsafe { std::slice::from_raw_parts(ptr, len) })
+    }
+
+    pub fn list_adapters() -> Result<Vec<AdapterInfo>> {
+        let flags = GAA_FLAG_INCLUDE_GATEWAYS | GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST | GAA_FLAG_SKIP_DNS_SERVER;
+        // Taille recommandée par la documentation, agrandie si nécessaire
+        let mut size: u32 = 15 * 1024;
+        let mut buffer: Vec<u64>;
+        loop {
+            buffer = vec![0u64; size as usize / 8 + 1];
+            let result = unsafe {
+                GetAdaptersAddresses(
+                    AF_UNSPEC as u32,
+                    flags,
+                    std::ptr::null(),
+                    buffer.as_mut_ptr() as *mut IP_ADAPTER_ADDRESSES_LH,
+                    &mut size,
+                )
+            };
+            match result {
+                ERROR_SUCCESS => break,
+                ERROR_BUFFER_OVERFLOW => continue,
+                error => return Err(anyhow!("GetAdaptersAddresses: erreur {}", error)),
+            }
+        }
+
+        let mut adapters = Vec::new();
+        let mut current = buffer.as_ptr() as *const IP_ADAPTER_ADDRESSES_LH;
+        while !current.is_null() {
+            let adapter = unsafe { &*current };
+            current = adapter.Next;
+            if adapter.IfType == IF_TYPE_SOFTWARE_LOOPBACK {
+                continue;
+            }
+
+            let mut ipv4 = Vec::new();
+            let mut unicast = adapter.FirstUnicastAddress;
+            while !unicast.is_null() {
+                let address = unsafe { &*unicast };
+                unicast = address.Next;
+                let sockaddr = address.Address.lpSockaddr;
+                if !sockaddr.is_null() && unsafe { (*sockaddr).sa_family } == AF_INET {
+                    let sockaddr_in = unsafe { &*(sockaddr as *const SOCKADDR_IN) };
+                    let octets = unsafe { sockaddr_in.sin_addr.S_un.S_addr }.to_ne_bytes();
+                    ipv4.push(Ipv4Addr::from(octets));
+                }
+            }
+
+            adapters.push(AdapterInfo {
+                name: wide_to_string(adapter.FriendlyName),
+                description: wide_to_string(adapter.Description),
+                interface_index: unsafe { adapter.Anonymous1.Anonymous.IfIndex },
+                is_up: adapter.OperStatus == IfOperStatusUp,
+                is_wifi: adapter.IfType == IF_TYPE_IEEE80211,
+                // Débit inconnu : u64::MAX
+                link_speed: if adapter.TransmitLinkSpeed == u64::MAX { 0 } else { adapter.TransmitLinkSpeed },
+                ipv4,
+                has_gateway: !adapter.FirstGatewayAddress.is_null(),
+                metric: adapter.Ipv4Metric,
+            });
+        }
+        Ok(adapters)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::fs;
+
+    use anyhow::{anyhow, Result};
+
+    use super::AdapterInfo;
+
+    /// Interface -> metric of its default route (`Destination` 00000000)
+    fn default_routes() -> Vec<(String, u32)> {
+        let Ok(content) = fs::read_to_string("/proc/net/route") else {
+            return Vec::new();
+        };
+        content
+            .lines()
+            .skip(1)
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                match fields.as_slice() {
+                    [iface, "00000000", _, _, _, _, metric, ..] => Some((iface.to_string(), metric.parse().unwrap_or(0))),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    pub fn list_adapters() -> Result<Vec<AdapterInfo>> {
+        let routes = default_routes();
+        let entries = fs::read_dir("/sys/class/net").map_err(|e| anyhow!("/sys/class/net illisible: {}", e))?;
+
+        let mut adapters = Vec::new();
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name == "lo" {
+                continue;
+            }
+            let dir = entry.path();
+            let read = |file: &str| fs::read_to_string(dir.join(file)).map(|v| v.trim().to_string()).unwrap_or_default();
+            let route = routes.iter().find(|(iface, _)| *iface == name);
+
+            adapters.push(AdapterInfo {
+                description: read("device/uevent")
+                    .lines()
+                    .find_map(|line| line.strip_prefix("DRIVER="))
+                    .unwrap_or_default()
+                    .to_string(),
+                interface_index: read("ifindex").parse().unwrap_or(0),
+                is_up: read("operstate") == "up",
+                is_wifi: dir.join("wireless").exists(),
+                // En Mb/s dans sysfs, -1 ou illisible quand inconnu
+                link_speed: read("speed").parse::<u64>().map(|mbps| mbps * 1_000_000).unwrap_or(0),
+                ipv4: Vec::new(),
+                has_gateway: route.is_some(),
+                metric: route.map(|(_, metric)| *metric).unwrap_or(u32::MAX),
+                name,
+            });
+        }
+        Ok(adapters)
+    }
+}
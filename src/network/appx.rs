@@ -0,0 +1,65 @@
+//! # Store applications (UWP / MSIX)
+//!
+//! Packaged applications (Xbox app, Game Pass games, Store versions of Discord...) run from
+//! `C:\Program Files\WindowsApps\<PackageFullName>\`. A QoS policy matching their bare file
+//! name is not reliably applied to them, so they are limited through the full path of their
+//! executable. When that path cannot be read, limiting them is not supported.
+
+use std::path::{Component, Path};
+
+/// Error shown instead of pretending that a Store application was limited
+pub const UNSUPPORTED_MESSAGE: &str = "limitation non supportée pour cette application Store";
+
+/// Whether `path` is inside a `WindowsApps` folder
+pub fn is_windows_apps_path(path: &Path) -> bool {
+    path.components().any(|component| match component {
+        Component::Normal(name) => name.to_string_lossy().eq_ignore_ascii_case("WindowsApps"),
+        _ => false,
+    })
+}
+
+/// Package family name (`Name_PublisherId`) read from the `<PackageFullName>` folder of a
+/// path under `WindowsApps` (`Name_Version_Architecture_ResourceId_PublisherId`)
+pub fn family_name_from_path(path: &str) -> Option<String> {
+    let mut components = path.split(['\\', '/']);
+    components.find(|component| component.eq_ignore_ascii_case("WindowsApps"))?;
+    let full_name = components.next()?;
+    let parts: Vec<&str> = full_name.split('_').collect();
+    match parts.as_slice() {
+        [name, _version, _architecture, _resource_id, publisher_id] => Some(format!("{}_{}", name, publisher_id)),
+        _ => None,
+    }
+}
+
+/// Package family name of a running process, `None` for unpackaged processes
+#[cfg(target_os = "windows")]
+pub fn package_family_name(pid: u32) -> Option<String> {
+    use windows_sys::Win32::Foundation::{CloseHandle, ERROR_SUCCESS};
+    use windows_sys::Win32::Storage::Packaging::Appx::GetPackageFamilyName;
+    use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION};
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return None;
+        }
+
+        // PACKAGE_FAMILY_NAME_MAX_LENGTH + 1
+        let mut buffer = [0u16; 65];
+        let mut len = buffer.len() as u32;
+        let status = GetPackageFamilyName(handle, &mut len, buffer.as_mut_ptr());
+        CloseHandle(handle);
+
+        // APPMODEL_ERROR_NO_PACKAGE pour un processus non empaqueté
+        if status != ERROR_SUCCESS || len == 0 {
+            return None;
+        }
+        // `len` compte le zéro final
+        Some(String::from_utf16_lossy(&buffer[..len as usize - 1]))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn package_family_name(_pid: u32) -> Option<String> {
+    None
+}
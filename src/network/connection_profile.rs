@@ -0,0 +1,106 @@
+//! # Connection profile
+//!
+//! Cost of the active connection: a phone hotspot or a capped mobile plan is "metered", and
+//! background traffic (update check, latency monitor, browser caches downloaded again after
+//! a cleaning) costs real money there. Windows reports it with `GetNetworkConnectivityHint`
+//! (Windows 10 2004 and later, resolved at runtime so older systems still start); elsewhere
+//! the cost is unknown and the connection is treated as unmetered.
+
+use anyhow::Result;
+
+/// Cost hint of the active connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub enum ConnectionCost {
+    #[default]
+    Unknown,
+    /// No data limit (Ethernet, most Wi-Fi)
+    Unrestricted,
+    /// Data cap, free until it is reached
+    Fixed,
+    /// Paid per byte
+    Variable,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ConnectionProfile {
+    pub cost: ConnectionCost,
+    pub roaming: bool,
+    pub approaching_data_limit: bool,
+    pub over_data_limit: bool,
+}
+
+impl ConnectionProfile {
+    /// Traffic is limited or billed: capped or per-byte plan, or roaming
+    pub fn is_metered(&self) -> bool {
+        matches!(self.cost, ConnectionCost::Fixed | ConnectionCost::Variable) || self.roaming
+    }
+
+    /// "connexion limitée (itinérance)", for the notifications and the tooltips
+    pub fn describe(&self) -> String {
+        let mut text = if self.is_metered() { "connexion limitée" } else { "connexion illimitée" }.to_string();
+        let details: Vec<&str> = [
+            (self.roaming, "itinérance"),
+            (self.over_data_limit, "forfait dépassé"),
+            (self.approaching_data_limit && !self.over_data_limit, "forfait presque atteint"),
+        ]
+        .into_iter()
+        .filter_map(|(active, label)| active.then_some(label))
+        .collect();
+        if !details.is_empty() {
+            text.push_str(&format!(" ({})", details.join(", ")));
+        }
+        text
+    }
+}
+
+/// Cost of the active connection
+#[cfg(target_os = "windows")]
+pub fn current() -> Result<ConnectionProfile> {
+    use anyhow::anyhow;
+    use windows_sys::Win32::Networking::WinSock::{
+        NetworkConnectivityCostHintFixed, NetworkConnectivityCostHintUnrestricted, NetworkConnectivityCostHintVariable,
+        NL_NETWORK_CONNECTIVITY_HINT,
+    };
+    use windows_sys::Win32::System::LibraryLoader::{GetProcAddress, LoadLibraryW};
+
+    type GetNetworkConnectivityHint = unsafe extern "system" fn(*mut NL_NETWORK_CONNECTIVITY_HINT) -> u32;
+
+    let library: Vec<u16> = "iphlpapi.dll".encode_utf16().chain(std::iter::once(0)).collect();
+    // SAFETY : iphlpapi.dll reste chargée pour tout le processus (tables TCP), le pointeur
+    // de fonction a la signature documentée
+    let hint = unsafe {
+        let module = LoadLibraryW(library.as_ptr());
+        if module.is_null() {
+            return Err(anyhow!("iphlpapi.dll introuvable"));
+        }
+        let Some(function) = GetProcAddress(module, b"GetNetworkConnectivityHint\0".as_ptr()) else {
+            return Err(anyhow!("GetNetworkConnectivityHint indisponible (Windows 10 2004 requis)"));
+        };
+        let function: GetNetworkConnectivityHint = std::mem::transmute(function);
+        let mut hint: NL_NETWORK_CONNECTIVITY_HINT = std::mem::zeroed();
+        let status = function(&mut hint);
+        if status != 0 {
+            return Err(anyhow!("GetNetworkConnectivityHint a échoué (code {})", status));
+        }
+        hint
+    };
+
+    let cost = match hint.ConnectivityCost {
+        NetworkConnectivityCostHintUnrestricted => ConnectionCost::Unrestricted,
+        NetworkConnectivityCostHintFixed => ConnectionCost::Fixed,
+        NetworkConnectivityCostHintVariable => ConnectionCost::Variable,
+        _ => ConnectionCost::Unknown,
+    };
+    Ok(ConnectionProfile {
+        cost,
+        roaming: hint.Roaming != 0,
+        approaching_data_limit: hint.ApproachingDataLimit != 0,
+        over_data_limit: hint.OverDataLimit != 0,
+    })
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn current() -> Result<ConnectionProfile> {
+    Ok(ConnectionProfile::default())
+}
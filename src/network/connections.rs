@@ -0,0 +1,683 @@
+//! # Per-process connections
+//!
+//! Open TCP and UDP sockets of one process, IPv4 and IPv6. Windows reads the owner-PID tables
+//! of `GetExtendedTcpTable`/`GetExtendedUdpTable`; Linux matches `/proc/net/{tcp,udp}{,6}`
+//! against the socket inodes of `/proc/<pid>/fd` (current user's processes only).
+//! `count_by_pid` aggregates the TCP states of every process for the scan of the table, and
+//! `udp_count_by_pid` its UDP sockets.
+//!
+//! Remote addresses are resolved by `ReverseDnsCache` on a worker thread, so the UI never
+//! waits on a DNS server.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl Protocol {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Protocol::Tcp => "TCP",
+            Protocol::Udp => "UDP",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionInfo {
+    pub protocol: Protocol,
+    pub local_addr: SocketAddr,
+    /// `None` for listening TCP sockets and unconnected UDP sockets
+    pub remote_addr: Option<IpAddr>,
+    pub remote_port: u16,
+    /// TCP state ("ESTABLISHED", "LISTEN"...), `None` for UDP
+    pub state: Option<&'static str>,
+    pub owning_pid: u32,
+}
+
+/// Open sockets of `pid`
+pub(super) fn list(pid: u32) -> Result<Vec<ConnectionInfo>> {
+    #[cfg(target_os = "windows")]
+    {
+        windows::list(pid)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::list(pid)
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        let _ = pid;
+        Err(anyhow::anyhow!("Liste des connexions non disponible sur cette plateforme"))
+    }
+}
+
+/// Name of a TCP state, numbered as in `MIB_TCP_STATE`
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn tcp_state_name(state: u32) -> &'static str {
+    match state {
+        1 => "CLOSED",
+        2 => "LISTEN",
+        3 => "SYN_SENT",
+        4 => "SYN_RCVD",
+        5 => "ESTABLISHED",
+        6 => "FIN_WAIT1",
+        7 => "FIN_WAIT2",
+        8 => "CLOSE_WAIT",
+        9 => "CLOSING",
+        10 => "LAST_ACK",
+        11 => "TIME_WAIT",
+        12 => "DELETE_TCB",
+        _ => "?",
+    }
+}
+
+/// State of an open TCP socket, as counted per process by `count_by_pid`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum TcpState {
+    Listen,
+    SynSent,
+    SynReceived,
+    Established,
+    FinWait1,
+    FinWait2,
+    CloseWait,
+    Closing,
+    LastAck,
+    TimeWait,
+}
+
+impl TcpState {
+    /// `MIB_TCP_STATE` value, `None` for CLOSED and DELETE_TCB (socket already gone)
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    fn from_mib(state: u32) -> Option<Self> {
+        match state {
+            2 => Some(TcpState::Listen),
+            3 => Some(TcpState::SynSent),
+            4 => Some(TcpState::SynReceived),
+            5 => Some(TcpState::Established),
+            6 => Some(TcpState::FinWait1),
+            7 => Some(TcpState::FinWait2),
+            8 => Some(TcpState::CloseWait),
+            9 => Some(TcpState::Closing),
+            10 => Some(TcpState::LastAck),
+            11 => Some(TcpState::TimeWait),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TcpState::Listen => "listen",
+            TcpState::SynSent => "syn_sent",
+            TcpState::SynReceived => "syn_rcvd",
+            TcpState::Established => "established",
+            TcpState::FinWait1 => "fin_wait1",
+            TcpState::FinWait2 => "fin_wait2",
+            TcpState::CloseWait => "close_wait",
+            TcpState::Closing => "closing",
+            TcpState::LastAck => "last_ack",
+            TcpState::TimeWait => "time_wait",
+        }
+    }
+}
+
+/// Owning PID and state of every TCP socket of the machine
+pub(super) trait SocketTable {
+    fn tcp_sockets(&self) -> Result<Vec<(u32, TcpState)>>;
+
+    /// Owning PID of every UDP socket
+    fn udp_sockets(&self) -> Result<Vec<u32>>;
+}
+
+/// Owner-PID tables of the running system
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+pub(super) struct SystemSocketTable;
+
+impl SocketTable for SystemSocketTable {
+    fn tcp_sockets(&self) -> Result<Vec<(u32, TcpState)>> {
+        #[cfg(target_os = "windows")]
+        {
+            windows::tcp_sockets()
+        }
+        #[cfg(not(target_os = "windows"))]
+        {
+            Err(anyhow::anyhow!("Table des sockets TCP non disponible sur cette plateforme"))
+        }
+    }
+
+    fn udp_sockets(&self) -> Result<Vec<u32>> {
+        #[cfg(target_os = "windows")]
+        {
+            windows::udp_sockets()
+        }
+        #[cfg(not(target_os = "windows"))]
+        {
+            Err(anyhow::anyhow!("Table des sockets UDP non disponible sur cette plateforme"))
+        }
+    }
+}
+
+/// Open TCP sockets of every process, by state. Built from a single read of the tables, so a
+/// process that exited since the previous call simply has no entry.
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+pub(super) fn count_by_pid(table: &impl SocketTable) -> Result<HashMap<u32, HashMap<TcpState, u32>>> {
+    let mut counts: HashMap<u32, HashMap<TcpState, u32>> = HashMap::new();
+    for (pid, state) in table.tcp_sockets()? {
+        *counts.entry(pid).or_default().entry(state).or_insert(0) += 1;
+    }
+    Ok(counts)
+}
+
+/// Open UDP sockets of every process: a game waiting in a lobby often has nothing else
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+pub(super) fn udp_count_by_pid(table: &impl SocketTable) -> Result<HashMap<u32, u32>> {
+    let mut counts: HashMap<u32, u32> = HashMap::new();
+    for pid in table.udp_sockets()? {
+        *counts.entry(pid).or_insert(0) += 1;
+    }
+    Ok(counts)
+}
+
+/// Remote part of a socket, `None` when not connected (0.0.0.0:0, [::]:0)
+fn remote(address: IpAddr, port: u16) -> Option<IpAddr> {
+    (!address.is_unspecified() || port != 0).then_some(address)
+}
+
+#[cfg(target_os = "windows")]
+pub(super) mod windows {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+    use anyhow::{anyhow, Result};
+    use windows_sys::Win32::Foundation::{ERROR_INSUFFICIENT_BUFFER, NO_ERROR};
+    use windows_sys::Win32::NetworkManagement::IpHelper::{
+        GetExtendedTcpTable, GetExtendedUdpTable, MIB_TCP6ROW_OWNER_PID, MIB_TCPROW_OWNER_PID, MIB_UDP6ROW_OWNER_PID,
+        MIB_UDPROW_OWNER_PID, TCP_TABLE_OWNER_PID_ALL, TCP_TABLE_OWNER_PID_CONNECTIONS, UDP_TABLE_OWNER_PID,
+    };
+
+    use super::{remote, tcp_state_name, ConnectionInfo, Protocol, TcpState};
+
+    const AF_INET: u32 = 2;
+    const AF_INET6: u32 = 23;
+
+    /// Ports are stored in network byte order in the low 16 bits
+    fn port(value: u32) -> u16 {
+        u16::from_be(value as u16)
+    }
+
+    fn ipv4(value: u32) -> Ipv4Addr {
+        Ipv4Addr::from(value.to_ne_bytes())
+    }
+
+    /// Rows of an owner-PID table. The buffer holds `dwNumEntries` followed by the rows, which
+    /// only contain u32 and byte arrays, so a u32 buffer keeps them aligned.
+    fn read_table<T: Copy>(query: impl Fn(*mut core::ffi::c_void, *mut u32) -> u32) -> Result<Vec<T>> {
+        let mut size = 0u32;
+        let result = query(std::ptr::null_mut(), &mut size);
+        if result != ERROR_INSUFFICIENT_BUFFER {
+            return Err(anyhow!("lecture de la table des connexions: erreur {}", result));
+        }
+
+        // La table peut grossir entre les deux appels
+        loop {
+            let mut buffer = vec![0u32; size as usize / 4 + 1];
+            match query(buffer.as_mut_ptr().cast(), &mut size) {
+                NO_ERROR => {
+                    let count = buffer[0] as usize;
+                    let rows = unsafe { std::slice::from_raw_parts(buffer[1..].as_ptr() as *const T, count) };
+                    return Ok(rows.to_vec());
+                }
+                ERROR_INSUFFICIENT_BUFFER => continue,
+                error => return Err(anyhow!("lecture de la table des connexions: erreur {}", error)),
+            }
+        }
+    }
+
+    /// Established IPv4 TCP connections of every process, as used by the TCP statistics
+    pub(in crate::network) fn tcp4_connections() -> Result<Vec<MIB_TCPROW_OWNER_PID>> {
+        read_table(|buffer, size| unsafe {
+            GetExtendedTcpTable(buffer, size, 0, AF_INET, TCP_TABLE_OWNER_PID_CONNECTIONS, 0)
+        })
+    }
+
+    /// Owning PID and state of every IPv4 and IPv6 TCP socket, for `count_by_pid`
+    pub(in crate::network) fn tcp_sockets() -> Result<Vec<(u32, TcpState)>> {
+        let tcp4: Vec<MIB_TCPROW_OWNER_PID> =
+            read_table(|buffer, size| unsafe { GetExtendedTcpTable(buffer, size, 0, AF_INET, TCP_TABLE_OWNER_PID_ALL, 0) })?;
+        let tcp6: Vec<MIB_TCP6ROW_OWNER_PID> =
+            read_table(|buffer, size| unsafe { GetExtendedTcpTable(buffer, size, 0, AF_INET6, TCP_TABLE_OWNER_PID_ALL, 0) })
+                .unwrap_or_default();
+
+        let v4 = tcp4.iter().map(|row| (row.dwOwningPid, row.dwState));
+        let v6 = tcp6.iter().map(|row| (row.dwOwningPid, row.dwState));
+        Ok(v4
+            .chain(v6)
+            .filter_map(|(pid, state)| TcpState::from_mib(state).map(|state| (pid, state)))
+            .collect())
+    }
+
+    /// Owning PID of every IPv4 and IPv6 UDP socket, for `udp_count_by_pid`
+    pub(in crate::network) fn udp_sockets() -> Result<Vec<u32>> {
+        let udp4: Vec<MIB_UDPROW_OWNER_PID> =
+            read_table(|buffer, size| unsafe { GetExtendedUdpTable(buffer, size, 0, AF_INET, UDP_TABLE_OWNER_PID, 0) })?;
+        let udp6: Vec<MIB_UDP6ROW_OWNER_PID> =
+            read_table(|buffer, size| unsafe { GetExtendedUdpTable(buffer, size, 0, AF_INET6, UDP_TABLE_OWNER_PID, 0) })
+                .unwrap_or_default();
+        Ok(udp4.iter().map(|row| row.dwOwningPid).chain(udp6.iter().map(|row| row.dwOwningPid)).collect())
+    }
+
+    pub fn list(pid: u32) -> Result<Vec<ConnectionInfo>> {
+        let mut connections = Vec::new();
+
+        let tcp4: Vec<MIB_TCPROW_OWNER_PID> =
+            read_table(|buffer, size| unsafe { GetExtendedTcpTable(buffer, size, 0, AF_INET, TCP_TABLE_OWNER_PID_ALL, 0) })?;
+        for row in tcp4.iter().filter(|row| row.dwOwningPid == pid) {
+            let remote_port = port(row.dwRemotePort);
+            connections.push(ConnectionInfo {
+                protocol: Protocol::Tcp,
+                local_addr: SocketAddr::new(IpAddr::V4(ipv4(row.dwLocalAddr)), port(row.dwLocalPort)),
+                remote_addr: remote(IpAddr::V4(ipv4(row.dwRemoteAddr)), remote_port),
+                remote_port,
+                state: Some(tcp_state_name(row.dwState)),
+                owning_pid: pid,
+            });
+        }
+
+        // IPv6 absent quand la pile est désactivée : pas une erreur
+        let tcp6: Vec<MIB_TCP6ROW_OWNER_PID> =
+            read_table(|buffer, size| unsafe { GetExtendedTcpTable(buffer, size, 0, AF_INET6, TCP_TABLE_OWNER_PID_ALL, 0) })
+                .unwrap_or_default();
+        for row in tcp6.iter().filter(|row| row.dwOwningPid == pid) {
+            let remote_port = port(row.dwRemotePort);
+            connections.push(ConnectionInfo {
+                protocol: Protocol::Tcp,
+                local_addr: SocketAddr::new(IpAddr::V6(Ipv6Addr::from(row.ucLocalAddr)), port(row.dwLocalPort)),
+                remote_addr: remote(IpAddr::V6(Ipv6Addr::from(row.ucRemoteAddr)), remote_port),
+                remote_port,
+                state: Some(tcp_state_name(row.dwState)),
+                owning_pid: pid,
+            });
+        }
+
+        let udp4: Vec<MIB_UDPROW_OWNER_PID> =
+            read_table(|buffer, size| unsafe { GetExtendedUdpTable(buffer, size, 0, AF_INET, UDP_TABLE_OWNER_PID, 0) })?;
+        for row in udp4.iter().filter(|row| row.dwOwningPid == pid) {
+            connections.push(ConnectionInfo {
+                protocol: Protocol::Udp,
+                local_addr: SocketAddr::new(IpAddr::V4(ipv4(row.dwLocalAddr)), port(row.dwLocalPort)),
+                remote_addr: None,
+                remote_port: 0,
+                state: None,
+                owning_pid: pid,
+            });
+        }
+
+        let udp6: Vec<MIB_UDP6ROW_OWNER_PID> =
+            read_table(|buffer, size| unsafe { GetExtendedUdpTable(buffer, size, 0, AF_INET6, UDP_TABLE_OWNER_PID, 0) })
+                .unwrap_or_default();
+        for row in udp6.iter().filter(|row| row.dwOwningPid == pid) {
+            connections.push(ConnectionInfo {
+                protocol: Protocol::Udp,
+                local_addr: SocketAddr::new(IpAddr::V6(Ipv6Addr::from(row.ucLocalAddr)), port(row.dwLocalPort)),
+                remote_addr: None,
+                remote_port: 0,
+                state: None,
+                owning_pid: pid,
+            });
+        }
+
+        Ok(connections)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::fs;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+    use anyhow::Result;
+
+    use super::super::linux_monitor;
+    use super::{remote, ConnectionInfo, Protocol};
+
+    /// `st` column of /proc/net/tcp, same numbering as `MIB_TCP_STATE` shifted by one
+    fn tcp_state_name(hex: &str) -> &'static str {
+        match hex {
+            "01" => "ESTABLISHED",
+            "02" => "SYN_SENT",
+            "03" => "SYN_RECV",
+            "04" => "FIN_WAIT1",
+            "05" => "FIN_WAIT2",
+            "06" => "TIME_WAIT",
+            "07" => "CLOSE",
+            "08" => "CLOSE_WAIT",
+            "09" => "LAST_ACK",
+            "0A" => "LISTEN",
+            "0B" => "CLOSING",
+            _ => "?",
+        }
+    }
+
+    /// "0100007F:0035" -> 127.0.0.1:53. Addresses are printed as native-endian u32 words.
+    fn parse_address(field: &str) -> Option<(IpAddr, u16)> {
+        let (address, port) = field.split_once(':')?;
+        let port = u16::from_str_radix(port, 16).ok()?;
+        let mut bytes = Vec::with_capacity(16);
+        for chunk in address.as_bytes().chunks(8) {
+            let word = u32::from_str_radix(std::str::from_utf8(chunk).ok()?, 16).ok()?;
+            bytes.extend_from_slice(&word.to_ne_bytes());
+        }
+        let address = match bytes.len() {
+            4 => IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(bytes).ok()?)),
+            16 => IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(bytes).ok()?)),
+            _ => return None,
+        };
+        Some((address, port))
+    }
+
+    pub fn list(pid: u32) -> Result<Vec<ConnectionInfo>> {
+        let inodes = linux_monitor::process_socket_inodes(pid);
+        let mut connections = Vec::new();
+        if inodes.is_empty() {
+            return Ok(connections);
+        }
+
+        for table in linux_monitor::SOCKET_TABLES {
+            // tcp6/udp6 absents quand IPv6 est désactivé
+            let Ok(content) = fs::read_to_string(table) else {
+                continue;
+            };
+            let protocol = if table.contains("tcp") { Protocol::Tcp } else { Protocol::Udp };
+
+            for line in content.lines().skip(1) {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let (Some(local), Some(remote_field), Some(state), Some(inode)) =
+                    (fields.get(1), fields.get(2), fields.get(3), fields.get(9))
+                else {
+                    continue;
+                };
+                if !inode.parse::<u64>().is_ok_and(|inode| inodes.contains(&inode)) {
+                    continue;
+                }
+                let (Some((local_ip, local_port)), Some((remote_ip, remote_port))) =
+                    (parse_address(local), parse_address(remote_field))
+                else {
+                    continue;
+                };
+
+                connections.push(ConnectionInfo {
+                    protocol,
+                    local_addr: SocketAddr::new(local_ip, local_port),
+                    remote_addr: remote(remote_ip, remote_port),
+                    remote_port,
+                    state: (protocol == Protocol::Tcp).then(|| tcp_state_name(state)),
+                    owning_pid: pid,
+                });
+            }
+        }
+        Ok(connections)
+    }
+}
+
+/// Lookups kept before the cache is emptied
+const DNS_CACHE_CAPACITY: usize = 512;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReverseDns {
+    Pending,
+    Found(String),
+    NotFound,
+}
+
+/// Reverse DNS names resolved one at a time on a worker thread, started on the first lookup
+#[derive(Default)]
+pub struct ReverseDnsCache {
+    names: Arc<Mutex<HashMap<IpAddr, ReverseDns>>>,
+    sender: Option<Sender<IpAddr>>,
+}
+
+impl ReverseDnsCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cached name of `address`, queuing its resolution the first time
+    pub fn lookup(&mut self, address: IpAddr) -> ReverseDns {
+        let Ok(mut names) = self.names.lock() else {
+            return ReverseDns::NotFound;
+        };
+        if let Some(name) = names.get(&address) {
+            return name.clone();
+        }
+        if address.is_unspecified() || address.is_loopback() {
+            names.insert(address, ReverseDns::NotFound);
+            return ReverseDns::NotFound;
+        }
+
+        if names.len() >= DNS_CACHE_CAPACITY {
+            names.retain(|_, name| *name == ReverseDns::Pending);
+        }
+        names.insert(address, ReverseDns::Pending);
+        drop(names);
+
+        let queued = self.sender().map(|sender| sender.send(address).is_ok()).unwrap_or(false);
+        if !queued {
+            self.sender = None;
+            if let Ok(mut names) = self.names.lock() {
+                names.insert(address, ReverseDns::NotFound);
+            }
+            return ReverseDns::NotFound;
+        }
+        ReverseDns::Pending
+    }
+
+    fn sender(&mut self) -> Option<&Sender<IpAddr>> {
+        if self.sender.is_none() {
+            let (sender, receiver) = mpsc::channel::<IpAddr>();
+            let names = Arc::clone(&self.names);
+            let spawned = std::thread::Builder::new().name("reverse_dns".to_string()).spawn(move || {
+                for address in receiver {
+                    let name = match resolve(address) {
+                        Some(name) => ReverseDns::Found(name),
+                        None => ReverseDns::NotFound,
+                    };
+                    if let Ok(mut names) = names.lock() {
+                        names.insert(address, name);
+                    }
+                }
+            });
+            match spawned {
+                Ok(_) => self.sender = Some(sender),
+                Err(e) => tracing::warn!("⚠️ Résolution DNS inverse indisponible: {}", e),
+            }
+        }
+        self.sender.as_ref()
+    }
+}
+
+/// Blocking PTR lookup through `getnameinfo`, `None` when the address has no name
+#[cfg(target_os = "windows")]
+fn resolve(address: IpAddr) -> Option<String> {
+    use windows_sys::Win32::Networking::WinSock::{
+        getnameinfo, WSAStartup, AF_INET, AF_INET6, NI_NAMEREQD, SOCKADDR, SOCKADDR_IN, SOCKADDR_IN6, WSADATA,
+    };
+
+    // Winsock doit être initialisé dans le processus avant getnameinfo
+    static WINSOCK: std::sync::Once = std::sync::Once::new();
+    WINSOCK.call_once(|| {
+        let mut data: WSADATA = unsafe { std::mem::zeroed() };
+        unsafe { WSAStartup(0x0202, &mut data) };
+    });
+
+    let mut host = [0u8; 1025];
+    let result = match address {
+        IpAddr::V4(v4) => {
+            let mut sockaddr: SOCKADDR_IN = unsafe { std::mem::zeroed() };
+            sockaddr.sin_family = AF_INET;
+            sockaddr.sin_addr.S_un.S_addr = u32::from_ne_bytes(v4.octets());
+            unsafe {
+                getnameinfo(
+                    &sockaddr as *const SOCKADDR_IN as *const SOCKADDR,
+                    std::mem::size_of::<SOCKADDR_IN>() as i32,
+                    host.as_mut_ptr(),
+                    host.len() as u32,
+                    std::ptr::null_mut(),
+                    0,
+                    NI_NAMEREQD as i32,
+                )
+            }
+        }
+        IpAddr::V6(v6) => {
+            let mut sockaddr: SOCKADDR_IN6 = unsafe { std::mem::zeroed() };
+            sockaddr.sin6_family = AF_INET6;
+            sockaddr.sin6_addr.u.Byte = v6.octets();
+            unsafe {
+                getnameinfo(
+                    &sockaddr as *const SOCKADDR_IN6 as *const SOCKADDR,
+                    std::mem::size_of::<SOCKADDR_IN6>() as i32,
+                    host.as_mut_ptr(),
+                    host.len() as u32,
+                    std::ptr::null_mut(),
+                    0,
+                    NI_NAMEREQD as i32,
+                )
+            }
+        }
+    };
+    host_name(result, &host)
+}
+
+/// Blocking PTR lookup through `getnameinfo`, `None` when the address has no name
+#[cfg(unix)]
+fn resolve(address: IpAddr) -> Option<String> {
+    let mut host = [0u8; 1025];
+    let result = match address {
+        IpAddr::V4(v4) => {
+            let mut sockaddr: libc::sockaddr_in = unsafe { std::mem::zeroed() };
+            sockaddr.sin_family = libc::AF_INET as libc::sa_family_t;
+            sockaddr.sin_addr.s_addr = u32::from_ne_bytes(v4.octets());
+            unsafe {
+                libc::getnameinfo(
+                    &sockaddr as *const libc::sockaddr_in as *const libc::sockaddr,
+                    std::mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+                    host.as_mut_ptr().cast(),
+                    host.len() as libc::socklen_t,
+                    std::ptr::null_mut(),
+                    0,
+                    libc::NI_NAMEREQD,
+                )
+            }
+        }
+        IpAddr::V6(v6) => {
+            let mut sockaddr: libc::sockaddr_in6 = unsafe { std::mem::zeroed() };
+            sockaddr.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+            sockaddr.sin6_addr.s6_addr = v6.octets();
+            unsafe {
+                libc::getnameinfo(
+                    &sockaddr as *const libc::sockaddr_in6 as *const libc::sockaddr,
+                    std::mem::size_of::<libc::sockaddr_in6>() as libc::socklen_t,
+                    host.as_mut_ptr().cast(),
+                    host.len() as libc::socklen_t,
+                    std::ptr::null_mut(),
+                    0,
+                    libc::NI_NAMEREQD,
+                )
+            }
+        }
+    };
+    host_name(result, &host)
+}
+
+#[cfg(not(any(target_os = "windows", unix)))]
+fn resolve(_address: IpAddr) -> Option<String> {
+    None
+}
+
+#[cfg_attr(not(any(target_os = "windows", unix)), allow(dead_code))]
+fn host_name(result: i32, host: &[u8]) -> Option<String> {
+    if result != 0 {
+        return None;
+    }
+    let len = host.iter().position(|&b| b == 0).unwrap_or(host.len());
+    let name = String::from_utf8_lossy(&host[..len]).to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    /// Socket tables given by the test, `None` for a table that cannot be read
+    struct FakeTable {
+        tcp: Option<Vec<(u32, TcpState)>>,
+        udp: Option<Vec<u32>>,
+    }
+
+    impl SocketTable for FakeTable {
+        fn tcp_sockets(&self) -> Result<Vec<(u32, TcpState)>> {
+            self.tcp.clone().ok_or_else(|| anyhow::anyhow!("table TCP illisible"))
+        }
+
+        fn udp_sockets(&self) -> Result<Vec<u32>> {
+            self.udp.clone().ok_or_else(|| anyhow::anyhow!("table UDP illisible"))
+        }
+    }
+
+    #[test]
+    fn sockets_are_counted_per_process_and_state() {
+        let table = FakeTable {
+            tcp: Some(vec![
+                (100, TcpState::Established),
+                (100, TcpState::Established),
+                (100, TcpState::TimeWait),
+                (200, TcpState::Listen),
+            ]),
+            udp: Some(vec![100, 300, 300]),
+        };
+
+        let tcp = count_by_pid(&table).unwrap();
+        assert_eq!(tcp.len(), 2);
+        assert_eq!(tcp[&100][&TcpState::Established], 2);
+        assert_eq!(tcp[&100][&TcpState::TimeWait], 1);
+        assert_eq!(tcp[&200], HashMap::from([(TcpState::Listen, 1)]));
+
+        let udp = udp_count_by_pid(&table).unwrap();
+        assert_eq!(udp, HashMap::from([(100, 1), (300, 2)]));
+    }
+
+    #[test]
+    fn empty_and_unreadable_tables() {
+        let empty = FakeTable { tcp: Some(Vec::new()), udp: Some(Vec::new()) };
+        assert!(count_by_pid(&empty).unwrap().is_empty());
+        assert!(udp_count_by_pid(&empty).unwrap().is_empty());
+
+        let unreadable = FakeTable { tcp: None, udp: None };
+        assert!(count_by_pid(&unreadable).is_err());
+        assert!(udp_count_by_pid(&unreadable).is_err());
+    }
+
+    #[test]
+    fn closed_sockets_are_not_counted() {
+        assert_eq!(TcpState::from_mib(5), Some(TcpState::Established));
+        assert_eq!(TcpState::from_mib(1), None);
+        assert_eq!(TcpState::from_mib(12), None);
+        assert_eq!(tcp_state_name(2), "LISTEN");
+    }
+
+    #[test]
+    fn unconnected_sockets_have_no_remote() {
+        assert_eq!(remote(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0), None);
+        let server = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7));
+        assert_eq!(remote(server, 443), Some(server));
+    }
+}
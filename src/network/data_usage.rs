@@ -0,0 +1,187 @@
+//! # Data usage
+//!
+//! Bytes downloaded and uploaded by each application today, added up from the byte counters
+//! of each scan. The totals are keyed by executable (full path when known, so that two
+//! `game.exe` in different folders stay apart): a process that restarts under a new PID keeps
+//! adding to the same line.
+//!
+//! - the first scan only records the counters, the traffic before GameBooster started is not
+//!   today's usage of this session; processes started afterwards count from their first byte
+//! - a counter lower than at the previous scan (ETW session restarted, PID reused) adds
+//!   nothing instead of a negative amount
+//! - the totals are written to `history/data_usage.json` every `SAVE_INTERVAL` and on exit,
+//!   so that a restart continues the day; they start over at local midnight
+//!
+//! With the estimated source (no administrator rights) the counters are guesses: so are the
+//! totals.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use super::NetworkProcessInfo;
+use crate::history::HISTORY_DIR;
+
+const USAGE_FILE: &str = "data_usage.json";
+const SAVE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Traffic of one application since midnight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTraffic {
+    /// Executable name shown in the list
+    pub name: String,
+    pub exe_path: Option<PathBuf>,
+    pub bytes_down: u64,
+    pub bytes_up: u64,
+    pub first_seen: DateTime<Local>,
+    pub last_seen: DateTime<Local>,
+}
+
+impl SessionTraffic {
+    pub fn total(&self) -> u64 {
+        self.bytes_down + self.bytes_up
+    }
+}
+
+/// Content of `data_usage.json`
+#[derive(Serialize, Deserialize)]
+struct SavedUsage {
+    day: NaiveDate,
+    totals: Vec<SessionTraffic>,
+}
+
+pub struct SessionTotals {
+    day: NaiveDate,
+    /// Usage key (see `usage_key`) -> traffic
+    totals: HashMap<String, SessionTraffic>,
+    /// PID -> usage key and byte counters (sent, received) at the previous scan
+    counters: HashMap<u32, (String, u64, u64)>,
+    /// The first scan only sets `counters`
+    baseline_taken: bool,
+    saved_at: Instant,
+    changed: bool,
+}
+
+/// Full path when known, lowercase: Windows paths are case-insensitive
+fn usage_key(name: &str, exe_path: Option<&Path>) -> String {
+    match exe_path {
+        Some(path) => path.to_string_lossy().to_lowercase(),
+        None => name.to_lowercase(),
+    }
+}
+
+fn path() -> PathBuf {
+    Path::new(HISTORY_DIR).join(USAGE_FILE)
+}
+
+impl SessionTotals {
+    /// Today's totals saved by a previous run, empty after midnight or without a file
+    pub fn load() -> Self {
+        let today = Local::now().date_naive();
+        let mut totals = Self {
+            day: today,
+            totals: HashMap::new(),
+            counters: HashMap::new(),
+            baseline_taken: false,
+            saved_at: Instant::now(),
+            changed: false,
+        };
+        let saved = match std::fs::read_to_string(path()) {
+            Ok(content) => serde_json::from_str::<SavedUsage>(&content)
+                .map_err(|e| tracing::warn!("⚠️ {} invalide, consommation remise à zéro: {}", path().display(), e))
+                .ok(),
+            Err(_) => None,
+        };
+        if let Some(saved) = saved.filter(|saved| saved.day == today) {
+            totals.totals = saved
+                .totals
+                .into_iter()
+                .map(|traffic| (usage_key(&traffic.name, traffic.exe_path.as_deref()), traffic))
+                .collect();
+            tracing::info!("📶 Consommation du jour reprise ({} application(s))", totals.totals.len());
+        }
+        totals
+    }
+
+    /// Add the traffic of each process since the previous scan
+    pub fn record(&mut self, processes: &HashMap<u32, NetworkProcessInfo>) {
+        let now = Local::now();
+        if now.date_naive() != self.day {
+            tracing::info!("📶 Nouveau jour : consommation du {} remise à zéro", self.day);
+            self.day = now.date_naive();
+            self.totals.clear();
+            self.changed = true;
+        }
+
+        let mut counters = HashMap::with_capacity(processes.len());
+        for (pid, process) in processes {
+            let key = usage_key(&process.name, process.exe_path.as_deref());
+            let (sent, received) = match self.counters.get(pid) {
+                // Compteur revenu en arrière : rien n'est compté plutôt qu'un écart négatif
+                Some((previous_key, sent, received)) if *previous_key == key => (
+                    process.bytes_sent.saturating_sub(*sent),
+                    process.bytes_received.saturating_sub(*received),
+                ),
+                // PID apparu depuis le scan précédent : tout son trafic est nouveau
+                _ if self.baseline_taken => (process.bytes_sent, process.bytes_received),
+                _ => (0, 0),
+            };
+            counters.insert(*pid, (key.clone(), process.bytes_sent, process.bytes_received));
+            if sent == 0 && received == 0 {
+                continue;
+            }
+            let traffic = self.totals.entry(key).or_insert_with(|| SessionTraffic {
+                name: process.name.clone(),
+                exe_path: process.exe_path.clone(),
+                bytes_down: 0,
+                bytes_up: 0,
+                first_seen: now,
+                last_seen: now,
+            });
+            traffic.bytes_up += sent;
+            traffic.bytes_down += received;
+            traffic.last_seen = now;
+            self.changed = true;
+        }
+        self.counters = counters;
+        self.baseline_taken = true;
+
+        if self.saved_at.elapsed() >= SAVE_INTERVAL {
+            self.save();
+        }
+    }
+
+    /// Applications by data used, most first
+    pub fn sorted(&self) -> Vec<SessionTraffic> {
+        let mut totals: Vec<SessionTraffic> = self.totals.values().cloned().collect();
+        totals.sort_by(|a, b| b.total().cmp(&a.total()).then_with(|| a.name.cmp(&b.name)));
+        totals
+    }
+
+    /// Start counting from now; the counters are kept as the new baseline
+    pub fn reset(&mut self) {
+        self.totals.clear();
+        self.changed = true;
+        self.save();
+        tracing::info!("📶 Consommation réseau remise à zéro");
+    }
+
+    /// Write the totals when they changed since the last write
+    pub fn save(&mut self) {
+        self.saved_at = Instant::now();
+        if !self.changed {
+            return;
+        }
+        let saved = SavedUsage { day: self.day, totals: self.sorted() };
+        let written = serde_json::to_string(&saved)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| crate::sessions::write_atomic(&path(), &content));
+        match written {
+            Ok(()) => self.changed = false,
+            Err(e) => tracing::warn!("⚠️ Consommation réseau non enregistrée: {}", e),
+        }
+    }
+}
@@ -0,0 +1,290 @@
+//! # ETW network monitor
+//!
+//! Real per-process counters from a real-time ETW session on the
+//! Microsoft-Windows-Kernel-Network provider: every TCP and UDP send and receive, IPv4 and
+//! IPv6, including short flows that never show in a TCP table poll.
+//! - a dedicated thread runs `ProcessTrace` and adds each event to a per-PID map
+//! - `refresh` turns the map into speeds, from the difference with the previous refresh
+//! - the session name is fixed: one left open by a crash is stopped before a new one starts
+//!
+//! Needs administrator rights. Built with the `etw` feature only.
+
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use windows_sys::core::GUID;
+use windows_sys::Win32::Foundation::{ERROR_ALREADY_EXISTS, ERROR_CANCELLED, ERROR_SUCCESS};
+use windows_sys::Win32::System::Diagnostics::Etw::{
+    CloseTrace, ControlTraceW, EnableTraceEx2, OpenTraceW, ProcessTrace, StartTraceW, CONTROLTRACE_HANDLE,
+    EVENT_CONTROL_CODE_ENABLE_PROVIDER, EVENT_RECORD, EVENT_TRACE_CONTROL_STOP, EVENT_TRACE_LOGFILEW,
+    EVENT_TRACE_PROPERTIES, EVENT_TRACE_REAL_TIME_MODE, PROCESSTRACE_HANDLE, PROCESS_TRACE_MODE_EVENT_RECORD,
+    PROCESS_TRACE_MODE_REAL_TIME, TRACE_LEVEL_INFORMATION, WNODE_FLAG_TRACED_GUID,
+};
+
+use super::stats_source::{NetworkStatsSource, ProcessTraffic};
+
+const SESSION_NAME: &str = "GameBooster-Network";
+/// Microsoft-Windows-Kernel-Network
+const KERNEL_NETWORK_PROVIDER: GUID = GUID::from_u128(0x7dd42a49_5329_4832_8dfd_43d979153a88);
+/// KERNEL_NETWORK_KEYWORD_IPV4 | KERNEL_NETWORK_KEYWORD_IPV6
+const KERNEL_NETWORK_KEYWORDS: u64 = 0x10 | 0x20;
+/// Returned by `OpenTraceW` on failure
+const INVALID_PROCESSTRACE_HANDLE: u64 = u64::MAX;
+/// A PID without any traffic for this long is dropped from the counters
+const IDLE_PID: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Counters {
+    sent: u64,
+    received: u64,
+}
+
+type SharedCounters = Mutex<HashMap<u32, Counters>>;
+
+#[derive(Clone, Copy)]
+enum Direction {
+    Sent,
+    Received,
+}
+
+/// TCP and UDP send/receive events, IPv4 and IPv6; the others (connect, retransmit...) carry
+/// no new bytes
+fn direction(event_id: u16) -> Option<Direction> {
+    match event_id {
+        10 | 26 | 42 | 58 => Some(Direction::Sent),
+        11 | 27 | 43 | 59 => Some(Direction::Received),
+        _ => None,
+    }
+}
+
+fn wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// Session properties followed by room for its name, as `StartTraceW` and `ControlTraceW` expect
+#[repr(C)]
+struct SessionProperties {
+    properties: EVENT_TRACE_PROPERTIES,
+    name: [u16; 64],
+}
+
+impl SessionProperties {
+    fn new() -> Box<Self> {
+        // SAFETY: structure C sans pointeur valide requis, entièrement remplie de zéros
+        let mut session: Box<Self> = Box::new(unsafe { std::mem::zeroed() });
+        session.properties.Wnode.BufferSize = std::mem::size_of::<Self>() as u32;
+        session.properties.Wnode.Flags = WNODE_FLAG_TRACED_GUID;
+        session.properties.Wnode.ClientContext = 1; // horloge QueryPerformanceCounter
+        session.properties.LogFileMode = EVENT_TRACE_REAL_TIME_MODE;
+        session.properties.FlushTimer = 1;
+        session.properties.LoggerNameOffset = std::mem::size_of::<EVENT_TRACE_PROPERTIES>() as u32;
+        session
+    }
+}
+
+/// Stop the session named `name`, running or left by a previous process
+fn stop_session(name: &[u16]) -> u32 {
+    let mut session = SessionProperties::new();
+    unsafe {
+        ControlTraceW(
+            CONTROLTRACE_HANDLE { Value: 0 },
+            name.as_ptr(),
+            &mut session.properties,
+            EVENT_TRACE_CONTROL_STOP,
+        )
+    }
+}
+
+unsafe extern "system" fn on_event(record: *mut EVENT_RECORD) {
+    let Some(record) = record.as_ref() else {
+        return;
+    };
+    let Some(direction) = direction(record.EventHeader.EventDescriptor.Id) else {
+        return;
+    };
+    if record.UserDataLength < 8 || record.UserData.is_null() || record.UserContext.is_null() {
+        return;
+    }
+
+    // Charge utile : PID puis taille ; le ProcessId de l'en-tête est souvent celui du système
+    let data = record.UserData as *const u8;
+    let pid = std::ptr::read_unaligned(data as *const u32);
+    let size = std::ptr::read_unaligned(data.add(4) as *const u32) as u64;
+
+    let counters = &*(record.UserContext as *const SharedCounters);
+    if let Ok(mut counters) = counters.lock() {
+        let entry = counters.entry(pid).or_default();
+        match direction {
+            Direction::Sent => entry.sent += size,
+            Direction::Received => entry.received += size,
+        }
+    }
+}
+
+/// Counters of a PID at the last refresh and the speeds derived from them
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    counters: Counters,
+    upload_speed: u64,
+    download_speed: u64,
+    last_active: Instant,
+}
+
+pub struct EtwStatsSource {
+    counters: Arc<SharedCounters>,
+    thread: Option<JoinHandle<()>>,
+    samples: HashMap<u32, Sample>,
+    last_refresh: Option<Instant>,
+}
+
+impl EtwStatsSource {
+    /// Start the session and its consumer thread
+    pub fn start() -> Result<Self> {
+        let mut name = wide(SESSION_NAME);
+
+        // Session restée ouverte par un plantage précédent : arrêtée avant d'en créer une
+        if stop_session(&name) == ERROR_SUCCESS {
+            tracing::warn!("⚠️ Session ETW {} orpheline arrêtée", SESSION_NAME);
+        }
+        let mut handle = CONTROLTRACE_HANDLE { Value: 0 };
+        let mut session = SessionProperties::new();
+        let mut status = unsafe { StartTraceW(&mut handle, name.as_ptr(), &mut session.properties) };
+        if status == ERROR_ALREADY_EXISTS {
+            stop_session(&name);
+            session = SessionProperties::new();
+            status = unsafe { StartTraceW(&mut handle, name.as_ptr(), &mut session.properties) };
+        }
+        if status != ERROR_SUCCESS {
+            return Err(anyhow!("StartTraceW {}: erreur {}", SESSION_NAME, status));
+        }
+
+        let status = unsafe {
+            EnableTraceEx2(
+                handle,
+                &KERNEL_NETWORK_PROVIDER,
+                EVENT_CONTROL_CODE_ENABLE_PROVIDER,
+                TRACE_LEVEL_INFORMATION as u8,
+                KERNEL_NETWORK_KEYWORDS,
+                0,
+                0,
+                std::ptr::null(),
+            )
+        };
+        if status != ERROR_SUCCESS {
+            stop_session(&name);
+            return Err(anyhow!("EnableTraceEx2 Kernel-Network: erreur {}", status));
+        }
+
+        let counters: Arc<SharedCounters> = Arc::default();
+        // Référence tenue par le callback, rendue une fois ProcessTrace terminé
+        let context = Arc::into_raw(counters.clone()) as *mut c_void;
+        // SAFETY: structure C remplie de zéros, les champs utiles sont renseignés ci-dessous
+        let mut logfile: EVENT_TRACE_LOGFILEW = unsafe { std::mem::zeroed() };
+        logfile.LoggerName = name.as_mut_ptr();
+        logfile.Anonymous1.ProcessTraceMode = PROCESS_TRACE_MODE_REAL_TIME | PROCESS_TRACE_MODE_EVENT_RECORD;
+        logfile.Anonymous2.EventRecordCallback = Some(on_event);
+        logfile.Context = context;
+
+        let trace = unsafe { OpenTraceW(&mut logfile) };
+        if trace.Value == INVALID_PROCESSTRACE_HANDLE {
+            let error = std::io::Error::last_os_error();
+            stop_session(&name);
+            unsafe { drop(Arc::from_raw(context as *const SharedCounters)) };
+            return Err(anyhow!("OpenTraceW {}: {}", SESSION_NAME, error));
+        }
+
+        let context = context as usize;
+        let thread = std::thread::Builder::new()
+            .name("etw-network".to_string())
+            .spawn(move || {
+                let trace = PROCESSTRACE_HANDLE { Value: trace.Value };
+                // Bloque jusqu'à l'arrêt de la session
+                let status = unsafe { ProcessTrace(&trace, 1, std::ptr::null(), std::ptr::null()) };
+                if status != ERROR_SUCCESS && status != ERROR_CANCELLED {
+                    tracing::warn!("⚠️ Lecture de la session ETW {} interrompue: erreur {}", SESSION_NAME, status);
+                }
+                unsafe {
+                    CloseTrace(trace);
+                    drop(Arc::from_raw(context as *const SharedCounters));
+                }
+            })
+            .map_err(|e| {
+                stop_session(&name);
+                unsafe { CloseTrace(trace) };
+                anyhow!("Thread ETW impossible à lancer: {}", e)
+            })?;
+
+        tracing::info!("📊 Surveillance réseau haute précision : session ETW {} démarrée", SESSION_NAME);
+        Ok(Self { counters, thread: Some(thread), samples: HashMap::new(), last_refresh: None })
+    }
+}
+
+impl NetworkStatsSource for EtwStatsSource {
+    fn label(&self) -> &'static str {
+        "ETW (haute précision)"
+    }
+
+    fn refresh(&mut self) -> Result<()> {
+        if self.thread.as_ref().map_or(true, |thread| thread.is_finished()) {
+            return Err(anyhow!("session ETW {} arrêtée", SESSION_NAME));
+        }
+
+        let now = Instant::now();
+        let elapsed = self.last_refresh.map(|at| now.duration_since(at).as_secs_f64()).unwrap_or(0.0);
+        self.last_refresh = Some(now);
+        let Ok(mut counters) = self.counters.lock() else {
+            return Err(anyhow!("compteurs ETW inaccessibles"));
+        };
+        // PID inactifs depuis longtemps : terminés, ou réutilisés par un autre processus
+        counters.retain(|pid, _| self.samples.get(pid).map_or(true, |sample| now.duration_since(sample.last_active) < IDLE_PID));
+
+        let mut samples = HashMap::with_capacity(counters.len());
+        for (pid, current) in counters.iter() {
+            let previous = self.samples.get(pid);
+            let (sent, received) = match previous {
+                Some(previous) => (
+                    current.sent.saturating_sub(previous.counters.sent),
+                    current.received.saturating_sub(previous.counters.received),
+                ),
+                None => (0, 0),
+            };
+            let speed = |bytes: u64| if elapsed > 0.0 { (bytes as f64 / elapsed) as u64 } else { 0 };
+            let last_active = match previous {
+                Some(previous) if sent == 0 && received == 0 => previous.last_active,
+                _ => now,
+            };
+            samples.insert(*pid, Sample {
+                counters: *current,
+                upload_speed: speed(sent),
+                download_speed: speed(received),
+                last_active,
+            });
+        }
+        self.samples = samples;
+        Ok(())
+    }
+
+    fn traffic(&self, pid: u32, _process: &sysinfo::Process) -> ProcessTraffic {
+        self.samples.get(&pid).map_or_else(ProcessTraffic::default, |sample| ProcessTraffic {
+            bytes_sent: sample.counters.sent,
+            bytes_received: sample.counters.received,
+            upload_speed: sample.upload_speed,
+            download_speed: sample.download_speed,
+        })
+    }
+}
+
+impl Drop for EtwStatsSource {
+    fn drop(&mut self) {
+        // L'arrêt de la session fait sortir ProcessTrace
+        stop_session(&wide(SESSION_NAME));
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        tracing::info!("📊 Session ETW {} arrêtée", SESSION_NAME);
+    }
+}
@@ -0,0 +1,96 @@
+//! # Blocage réseau par pare-feu
+//!
+//! Windows Firewall rules (inbound + outbound) created through `netsh advfirewall`
+//! to cut network access of a given executable entirely.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Result;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+pub const BLOCK_RULE_PREFIX: &str = "GameBooster_Block_";
+
+/// Rule name used for an executable path (`GameBooster_Block_<exe>`)
+pub fn block_rule_name(exe_path: &str) -> String {
+    let exe_name = Path::new(exe_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| exe_path.to_string());
+    format!("{}{}", BLOCK_RULE_PREFIX, exe_name)
+}
+
+fn run_netsh(args: &[&str]) -> Result<String> {
+    let mut command = Command::new("netsh");
+    command.args(args);
+
+    #[cfg(target_os = "windows")]
+    command.creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+    let output = command
+        .output()
+        .map_err(|e| anyhow::anyhow!("Impossible d'exécuter netsh: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if output.status.success() {
+        Ok(stdout)
+    } else {
+        Err(anyhow::anyhow!("netsh a échoué: {}", stdout.trim()))
+    }
+}
+
+/// Add inbound and outbound block rules for `exe_path`
+#[cfg(target_os = "windows")]
+pub fn add_block_rules(exe_path: &str) -> Result<()> {
+    let rule_name = block_rule_name(exe_path);
+    let name_arg = format!("name={}", rule_name);
+    let program_arg = format!("program={}", exe_path);
+
+    // Éviter les doublons si la règle existe déjà
+    let _ = run_netsh(&["advfirewall", "firewall", "delete", "rule", &name_arg]);
+
+    for direction in ["dir=in", "dir=out"] {
+        run_netsh(&[
+            "advfirewall", "firewall", "add", "rule",
+            &name_arg, direction, "action=block", &program_arg, "enable=yes",
+        ])?;
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn add_block_rules(_exe_path: &str) -> Result<()> {
+    Err(anyhow::anyhow!("Blocage pare-feu disponible uniquement sous Windows"))
+}
+
+/// Delete every rule (both directions) named `rule_name`
+#[cfg(target_os = "windows")]
+pub fn delete_rule(rule_name: &str) -> Result<()> {
+    run_netsh(&["advfirewall", "firewall", "delete", "rule", &format!("name={}", rule_name)])?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn delete_rule(_rule_name: &str) -> Result<()> {
+    Err(anyhow::anyhow!("Blocage pare-feu disponible uniquement sous Windows"))
+}
+
+/// Names of the existing GameBooster block rules
+#[cfg(target_os = "windows")]
+pub fn list_block_rules() -> Result<HashSet<String>> {
+    let output = run_netsh(&["advfirewall", "firewall", "show", "rule", "name=all"])?;
+
+    // Le libellé "Rule Name:" est traduit selon la langue de Windows : on ne garde que le nom
+    Ok(output
+        .lines()
+        .filter_map(|line| line.find(BLOCK_RULE_PREFIX).map(|start| line[start..].trim().to_string()))
+        .collect())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn list_block_rules() -> Result<HashSet<String>> {
+    Ok(HashSet::new())
+}
@@ -0,0 +1,220 @@
+//! # Limit verification
+//!
+//! Measures the outbound rate of a process for a few seconds after its limit is applied,
+//! and compares it with the configured limit. QoS policies only shape outbound traffic, so
+//! the received bytes are not taken into account.
+//!
+//! Windows reads the extended TCP statistics (`GetPerTcpConnectionEStats`) of the IPv4
+//! connections owned by the process, which requires the same elevation as the limit itself;
+//! UDP and IPv6 traffic are not counted. Linux uses the byte counters of `linux_monitor`,
+//! only available for processes running in their own network namespace.
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+/// Measured rate above the limit still considered effective (20 %)
+pub const LIMIT_TOLERANCE: f64 = 0.2;
+/// Measurement length used after a limit is applied from the network tab
+pub const DEFAULT_VERIFICATION_SECS: u64 = 10;
+
+/// Outcome of `NetworkLimiter::verify_limit_effectiveness`
+#[derive(Debug, Clone)]
+pub struct LimitVerification {
+    pub pid: u32,
+    /// Average outbound rate over the measurement, in KB/s
+    pub measured_avg_kbps: f64,
+    /// Configured limit, in KB/s
+    pub configured_kbps: u32,
+    pub effective: bool,
+    /// Nothing was sent during the measurement: `effective` proves nothing
+    pub idle: bool,
+}
+
+/// Sample the bytes sent by `pid` once per second for `duration_secs` seconds
+pub(super) fn measure(pid: u32, configured_kbps: u32, duration_secs: u64) -> Result<LimitVerification> {
+    let mut sampler = OutboundSampler::new(pid)?;
+    let started = Instant::now();
+    let mut total_sent = 0u64;
+    for _ in 0..duration_secs.max(1) {
+        std::thread::sleep(Duration::from_secs(1));
+        total_sent += sampler.sent_since_last_sample()?;
+    }
+
+    let measured_avg_kbps = total_sent as f64 / 1024.0 / started.elapsed().as_secs_f64();
+    let verification = LimitVerification {
+        pid,
+        measured_avg_kbps,
+        configured_kbps,
+        effective: measured_avg_kbps <= configured_kbps as f64 * (1.0 + LIMIT_TOLERANCE),
+        idle: total_sent == 0,
+    };
+
+    if verification.idle {
+        tracing::warn!(
+            "⚠️ Vérification limite PID {}: aucun envoi mesuré en {}s, résultat non concluant (limite {} KB/s)",
+            pid, duration_secs, configured_kbps
+        );
+    } else if verification.effective {
+        tracing::info!(
+            "✅ Vérification limite PID {}: {:.1} KB/s mesurés pour {} KB/s configurés → efficace",
+            pid, measured_avg_kbps, configured_kbps
+        );
+    } else {
+        tracing::warn!(
+            "❌ Vérification limite PID {}: {:.1} KB/s mesurés pour {} KB/s configurés → non efficace",
+            pid, measured_avg_kbps, configured_kbps
+        );
+    }
+    Ok(verification)
+}
+
+/// Bytes sent by the IPv4 TCP connections of one process, per connection
+#[cfg(target_os = "windows")]
+struct OutboundSampler {
+    pid: u32,
+    /// (local addr, local port, remote addr, remote port) -> DataBytesOut at the previous sample
+    previous: std::collections::HashMap<(u32, u32, u32, u32), u64>,
+}
+
+#[cfg(target_os = "windows")]
+impl OutboundSampler {
+    fn new(pid: u32) -> Result<Self> {
+        let mut sampler = Self {
+            pid,
+            previous: std::collections::HashMap::new(),
+        };
+        sampler.sent_since_last_sample()?;
+        Ok(sampler)
+    }
+
+    /// Connections seen for the first time only set their baseline
+    fn sent_since_last_sample(&mut self) -> Result<u64> {
+        let mut current = std::collections::HashMap::new();
+        let mut sent = 0u64;
+        for row in tcp_estats::process_connections(self.pid)? {
+            let key = (row.dwLocalAddr, row.dwLocalPort, row.dwRemoteAddr, row.dwRemotePort);
+            let Some(bytes_out) = tcp_estats::bytes_out(&row) else {
+                continue;
+            };
+            if let Some(previous) = self.previous.get(&key) {
+                sent += bytes_out.saturating_sub(*previous);
+            }
+            current.insert(key, bytes_out);
+        }
+        self.previous = current;
+        Ok(sent)
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod tcp_estats {
+    use anyhow::Result;
+    use windows_sys::Win32::Foundation::NO_ERROR;
+    use windows_sys::Win32::NetworkManagement::IpHelper::{
+        GetPerTcpConnectionEStats, SetPerTcpConnectionEStats, TcpConnectionEstatsData, MIB_TCPROW_LH,
+        MIB_TCPROW_LH_0, TCP_ESTATS_DATA_ROD_v0, TCP_ESTATS_DATA_RW_v0,
+    };
+
+    /// Established IPv4 TCP connections owned by `pid`
+    pub fn process_connections(pid: u32) -> Result<Vec<MIB_TCPROW_LH>> {
+        let rows = super::super::connections::windows::tcp4_connections()?;
+        Ok(rows
+            .iter()
+            .filter(|row| row.dwOwningPid == pid)
+            .map(|row| MIB_TCPROW_LH {
+                Anonymous: MIB_TCPROW_LH_0 { dwState: row.dwState },
+                dwLocalAddr: row.dwLocalAddr,
+                dwLocalPort: row.dwLocalPort,
+                dwRemoteAddr: row.dwRemoteAddr,
+                dwRemotePort: row.dwRemotePort,
+            })
+            .collect())
+    }
+
+    /// Bytes sent on the connection since its statistics were enabled (enabled on first call)
+    pub fn bytes_out(row: &MIB_TCPROW_LH) -> Option<u64> {
+        let rw = TCP_ESTATS_DATA_RW_v0 { EnableCollection: 1 };
+        let result = unsafe {
+            SetPerTcpConnectionEStats(
+                row,
+                TcpConnectionEstatsData,
+                &rw as *const TCP_ESTATS_DATA_RW_v0 as *const u8,
+                0,
+                std::mem::size_of::<TCP_ESTATS_DATA_RW_v0>() as u32,
+                0,
+            )
+        };
+        if result != NO_ERROR {
+            tracing::debug!("SetPerTcpConnectionEStats: erreur {}", result);
+            return None;
+        }
+
+        let mut rod: TCP_ESTATS_DATA_ROD_v0 = unsafe { std::mem::zeroed() };
+        let result = unsafe {
+            GetPerTcpConnectionEStats(
+                row,
+                TcpConnectionEstatsData,
+                std::ptr::null_mut(),
+                0,
+                0,
+                std::ptr::null_mut(),
+                0,
+                0,
+                &mut rod as *mut TCP_ESTATS_DATA_ROD_v0 as *mut u8,
+                0,
+                std::mem::size_of::<TCP_ESTATS_DATA_ROD_v0>() as u32,
+            )
+        };
+        if result != NO_ERROR {
+            tracing::debug!("GetPerTcpConnectionEStats: erreur {}", result);
+            return None;
+        }
+        Some(rod.DataBytesOut)
+    }
+}
+
+/// Namespace byte counters read by `linux_monitor`
+#[cfg(target_os = "linux")]
+struct OutboundSampler {
+    pid: u32,
+    monitor: super::linux_monitor::LinuxNetMonitor,
+    previous: Option<u64>,
+}
+
+#[cfg(target_os = "linux")]
+impl OutboundSampler {
+    fn new(pid: u32) -> Result<Self> {
+        let mut sampler = Self {
+            pid,
+            monitor: super::linux_monitor::LinuxNetMonitor::new()?,
+            previous: None,
+        };
+        sampler.sent_since_last_sample()?;
+        Ok(sampler)
+    }
+
+    fn sent_since_last_sample(&mut self) -> Result<u64> {
+        let current = self.monitor.sample()?.get(&self.pid).map(|activity| activity.bytes_sent);
+        let sent = match (self.previous, current) {
+            (Some(previous), Some(current)) => current.saturating_sub(previous),
+            _ => 0,
+        };
+        self.previous = current;
+        Ok(sent)
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+struct OutboundSampler;
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+impl OutboundSampler {
+    fn new(_pid: u32) -> Result<Self> {
+        Err(anyhow::anyhow!("Mesure du débit par processus non disponible sur cette plateforme"))
+    }
+
+    fn sent_since_last_sample(&mut self) -> Result<u64> {
+        Ok(0)
+    }
+}
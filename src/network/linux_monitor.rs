@@ -0,0 +1,158 @@
+//! # Linux network monitor
+//!
+//! Per-process network activity without root privileges. Sockets listed in
+//! `/proc/net/{tcp,tcp6,udp,udp6}` are attributed to processes through the
+//! `socket:[inode]` links of `/proc/<pid>/fd` (readable for the current user's processes only).
+//! Byte counters come from `/proc/<pid>/net/dev` when the process lives in its own network
+//! namespace (containers, sandboxes); otherwise only the connection count is known.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+
+pub(super) const SOCKET_TABLES: &[&str] = &["/proc/net/tcp", "/proc/net/tcp6", "/proc/net/udp", "/proc/net/udp6"];
+/// `st` column value of a listening TCP socket
+const TCP_LISTEN: &str = "0A";
+
+/// Network activity attributed to one process during a sample
+#[derive(Debug, Clone, Default)]
+pub struct ProcessNetActivity {
+    pub connections: u32,
+    /// Cumulative counters of the process's own namespace, 0 when it shares ours
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+}
+
+pub struct LinuxNetMonitor {
+    own_netns: Option<PathBuf>,
+}
+
+impl LinuxNetMonitor {
+    pub fn new() -> Result<Self> {
+        fs::metadata(SOCKET_TABLES[0]).map_err(|e| anyhow!("{} illisible: {}", SOCKET_TABLES[0], e))?;
+        Ok(Self {
+            own_netns: fs::read_link("/proc/self/ns/net").ok(),
+        })
+    }
+
+    /// Processes with at least one open connection or their own network counters
+    pub fn sample(&self) -> Result<HashMap<u32, ProcessNetActivity>> {
+        let connected = connected_socket_inodes()?;
+        let mut activity = HashMap::new();
+
+        let entries = fs::read_dir("/proc").map_err(|e| anyhow!("/proc illisible: {}", e))?;
+        for entry in entries.flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+                continue;
+            };
+
+            let connections = count_process_sockets(pid, &connected);
+            let counters = self.namespace_counters(pid);
+            if connections == 0 && counters.is_none() {
+                continue;
+            }
+
+            let mut process = ProcessNetActivity {
+                connections,
+                ..Default::default()
+            };
+            if let Some((sent, received)) = counters {
+                process.bytes_sent = sent;
+                process.bytes_received = received;
+            }
+            activity.insert(pid, process);
+        }
+        Ok(activity)
+    }
+
+    /// (sent, received) of the process's network namespace, only when it differs from ours:
+    /// in the shared namespace the counters are the whole machine's
+    fn namespace_counters(&self, pid: u32) -> Option<(u64, u64)> {
+        let netns = fs::read_link(format!("/proc/{}/ns/net", pid)).ok()?;
+        if self.own_netns.as_ref() == Some(&netns) {
+            return None;
+        }
+        let content = fs::read_to_string(format!("/proc/{}/net/dev", pid)).ok()?;
+        Some(parse_net_dev(&content))
+    }
+}
+
+/// Inodes of established/connected sockets (listening TCP sockets excluded)
+fn connected_socket_inodes() -> Result<HashSet<u64>> {
+    let mut inodes = HashSet::new();
+    for table in SOCKET_TABLES {
+        let content = match fs::read_to_string(table) {
+            Ok(content) => content,
+            // tcp6/udp6 absents quand IPv6 est désactivé
+            Err(e) if table.ends_with('6') => {
+                tracing::debug!("🔍 {} ignoré: {}", table, e);
+                continue;
+            }
+            Err(e) => return Err(anyhow!("{} illisible: {}", table, e)),
+        };
+        let is_tcp = table.contains("tcp");
+
+        for line in content.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (Some(state), Some(inode)) = (fields.get(3), fields.get(9)) else {
+                continue;
+            };
+            if is_tcp && *state == TCP_LISTEN {
+                continue;
+            }
+            match inode.parse::<u64>() {
+                Ok(inode) if inode != 0 => {
+                    inodes.insert(inode);
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(inodes)
+}
+
+/// Sockets of `pid` found in `connected`. 0 when its fds are not readable (other user).
+fn count_process_sockets(pid: u32, connected: &HashSet<u64>) -> u32 {
+    process_socket_inodes(pid).intersection(connected).count() as u32
+}
+
+/// Inodes of the sockets opened by `pid`, empty when its fds are not readable (other user)
+pub(super) fn process_socket_inodes(pid: u32) -> HashSet<u64> {
+    let Ok(fds) = fs::read_dir(format!("/proc/{}/fd", pid)) else {
+        return HashSet::new();
+    };
+
+    fds.flatten()
+        .filter_map(|fd| fs::read_link(fd.path()).ok())
+        .filter_map(|target| {
+            target
+                .to_str()?
+                .strip_prefix("socket:[")?
+                .strip_suffix(']')?
+                .parse::<u64>()
+                .ok()
+        })
+        .collect()
+}
+
+/// Total (transmitted, received) bytes of `/proc/.../net/dev`, loopback excluded
+fn parse_net_dev(content: &str) -> (u64, u64) {
+    let mut sent = 0u64;
+    let mut received = 0u64;
+    for line in content.lines().skip(2) {
+        let Some((interface, counters)) = line.split_once(':') else {
+            continue;
+        };
+        if interface.trim() == "lo" {
+            continue;
+        }
+        let fields: Vec<u64> = counters.split_whitespace().filter_map(|f| f.parse().ok()).collect();
+        if fields.len() >= 9 {
+            received += fields[0];
+            sent += fields[8];
+        }
+    }
+    (sent, received)
+}
@@ -0,0 +1,395 @@
+//! # Linux traffic shaping
+//!
+//! Per-process upload limits with cgroup v1 `net_cls` and `tc`: each limited PID is moved
+//! into a `gamebooster_<minor>` cgroup whose classid points to an HTB class capping its rate
+//! on the default interface, selected by a `cgroup` filter. Only egress traffic is shaped
+//! (download shaping would need an IFB device). Requires root, `tc` and a mounted `net_cls`.
+//! `tc` runs through a `CommandRunner`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use thiserror::Error;
+
+use crate::system::{self, CommandRunner};
+
+const NET_CLS_ROOT: &str = "/sys/fs/cgroup/net_cls";
+const CGROUP_PREFIX: &str = "gamebooster_";
+/// HTB qdisc handle (major) on the interface root
+const HTB_MAJOR: u32 = 1;
+/// First class minor used for limited processes
+const FIRST_MINOR: u16 = 0x10;
+
+/// Missing piece of the system preventing traffic shaping
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum Prerequisite {
+    #[error("droits root")]
+    NotRoot,
+    #[error("commande tc (iproute2)")]
+    TcMissing,
+    #[error("cgroup net_cls monté sur /sys/fs/cgroup/net_cls")]
+    NetClsMissing,
+}
+
+#[derive(Debug, Error)]
+pub enum ShapingError {
+    #[error("Prérequis manquants pour la limitation Linux : {}", join_prerequisites(.0))]
+    MissingPrerequisites(Vec<Prerequisite>),
+    #[error("Aucune interface réseau par défaut (pas de route 0.0.0.0 dans /proc/net/route)")]
+    NoDefaultInterface,
+    #[error("`{command}` a échoué : {stderr}")]
+    CommandFailed { command: String, stderr: String },
+    #[error("Erreur d'entrée/sortie sur {path} : {source}")]
+    Io { path: String, source: std::io::Error },
+    #[error("Plus de classe tc disponible")]
+    NoFreeClass,
+}
+
+fn join_prerequisites(missing: &[Prerequisite]) -> String {
+    missing.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+}
+
+fn io_error(path: &Path, source: std::io::Error) -> ShapingError {
+    ShapingError::Io {
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+/// Check root, `tc` and the `net_cls` mount, reporting everything that is missing at once
+pub fn check_requirements() -> Result<(), ShapingError> {
+    let mut missing = Vec::new();
+    if !crate::utils::is_elevated() {
+        missing.push(Prerequisite::NotRoot);
+    }
+    if find_tc().is_none() {
+        missing.push(Prerequisite::TcMissing);
+    }
+    if !Path::new(NET_CLS_ROOT).join("cgroup.procs").exists() {
+        missing.push(Prerequisite::NetClsMissing);
+    }
+
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(ShapingError::MissingPrerequisites(missing))
+    }
+}
+
+fn find_tc() -> Option<PathBuf> {
+    let path_dirs = std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).collect::<Vec<_>>())
+        .unwrap_or_default();
+    path_dirs
+        .into_iter()
+        .chain(["/sbin", "/usr/sbin"].map(PathBuf::from))
+        .map(|dir| dir.join("tc"))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Interface of the default route (`Destination` 00000000 in `/proc/net/route`)
+pub fn default_interface() -> Result<String, ShapingError> {
+    let path = Path::new("/proc/net/route");
+    let content = fs::read_to_string(path).map_err(|e| io_error(path, e))?;
+    content
+        .lines()
+        .skip(1)
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .find(|fields| fields.get(1) == Some(&"00000000"))
+        .and_then(|fields| fields.first().map(|iface| iface.to_string()))
+        .ok_or(ShapingError::NoDefaultInterface)
+}
+
+/// `tc` arguments installing the HTB root qdisc; unclassified traffic stays unshaped
+pub fn root_qdisc_args(interface: &str) -> Vec<String> {
+    to_args(&["qdisc", "replace", "dev", interface, "root", "handle", &format!("{}:", HTB_MAJOR), "htb", "default", "0"])
+}
+
+/// `tc` arguments of the filter mapping the `net_cls` classid of a packet to its HTB class
+pub fn cgroup_filter_args(interface: &str) -> Vec<String> {
+    to_args(&[
+        "filter", "add", "dev", interface, "parent", &format!("{}:", HTB_MAJOR),
+        "protocol", "all", "prio", "10", "handle", "1:", "cgroup",
+    ])
+}
+
+/// `tc` arguments creating/updating the class capping a process at `limit_kbps` (KB/s)
+pub fn class_args(interface: &str, minor: u16, limit_kbps: u32) -> Vec<String> {
+    let rate = format!("{}kbit", limit_kbps as u64 * 8);
+    to_args(&[
+        "class", "replace", "dev", interface, "parent", &format!("{}:", HTB_MAJOR),
+        "classid", &class_id(minor), "htb", "rate", &rate, "ceil", &rate,
+    ])
+}
+
+pub fn class_delete_args(interface: &str, minor: u16) -> Vec<String> {
+    to_args(&["class", "del", "dev", interface, "classid", &class_id(minor)])
+}
+
+pub fn root_qdisc_delete_args(interface: &str) -> Vec<String> {
+    to_args(&["qdisc", "del", "dev", interface, "root"])
+}
+
+fn class_id(minor: u16) -> String {
+    format!("{}:{:x}", HTB_MAJOR, minor)
+}
+
+/// Value written to `net_cls.classid` (0xAAAABBBB = major:minor)
+pub fn net_cls_classid(minor: u16) -> String {
+    format!("0x{:04x}{:04x}", HTB_MAJOR, minor)
+}
+
+fn to_args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+fn run_tc(runner: &dyn CommandRunner, args: &[String]) -> Result<(), ShapingError> {
+    let command = format!("tc {}", args.join(" "));
+    tracing::debug!("🐧 {}", command);
+
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = runner.run("tc", &args).map_err(|e| ShapingError::CommandFailed {
+        command: command.clone(),
+        stderr: e.to_string(),
+    })?;
+
+    if output.success() {
+        Ok(())
+    } else {
+        Err(ShapingError::CommandFailed {
+            command,
+            stderr: output.stderr.trim().to_string(),
+        })
+    }
+}
+
+fn write_file(path: &Path, value: &str) -> Result<(), ShapingError> {
+    fs::write(path, value).map_err(|e| io_error(path, e))
+}
+
+/// Move every task of a GameBooster cgroup back to the root one, then remove it. A cgroup
+/// already removed is not an error.
+fn remove_cgroup(root: &Path, dir: &Path) -> Result<(), ShapingError> {
+    let procs_path = dir.join("cgroup.procs");
+    let procs = fs::read_to_string(&procs_path).unwrap_or_default();
+    let root_procs = root.join("cgroup.procs");
+    for pid in procs.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        // Le processus a pu se terminer entre-temps
+        if let Err(e) = write_file(&root_procs, pid) {
+            tracing::debug!("🐧 PID {} non déplacé: {}", pid, e);
+        }
+    }
+    match fs::remove_dir(dir) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(io_error(dir, e)),
+        _ => Ok(()),
+    }
+}
+
+/// Limits applied on this machine, keyed by PID
+pub struct LinuxShaper {
+    runner: Arc<dyn CommandRunner>,
+    /// `NET_CLS_ROOT`, a plain folder in tests
+    net_cls_root: PathBuf,
+    interface: String,
+    root_ready: bool,
+    /// PID -> HTB class minor (also the minor of its net_cls classid)
+    classes: HashMap<u32, u16>,
+}
+
+impl LinuxShaper {
+    pub fn new() -> Result<Self, ShapingError> {
+        check_requirements()?;
+        let interface = default_interface()?;
+        tracing::info!("🐧 Limitation tc/net_cls prête sur {}", interface);
+        Ok(Self::with_runner(system::default_runner(), PathBuf::from(NET_CLS_ROOT), interface))
+    }
+
+    /// Shaper of `interface` without the prerequisite checks, `tc` running through `runner`
+    /// and the cgroups created under `net_cls_root`
+    pub fn with_runner(runner: Arc<dyn CommandRunner>, net_cls_root: PathBuf, interface: String) -> Self {
+        Self {
+            runner,
+            net_cls_root,
+            interface,
+            root_ready: false,
+            classes: HashMap::new(),
+        }
+    }
+
+    fn run_tc(&self, args: &[String]) -> Result<(), ShapingError> {
+        run_tc(self.runner.as_ref(), args)
+    }
+
+    fn cgroup_dir(&self, minor: u16) -> PathBuf {
+        self.net_cls_root.join(format!("{}{}", CGROUP_PREFIX, minor))
+    }
+
+    fn ensure_root(&mut self) -> Result<(), ShapingError> {
+        if !self.root_ready {
+            // Remplacer la qdisc racine supprime aussi les anciens filtres
+            self.run_tc(&root_qdisc_args(&self.interface))?;
+            self.run_tc(&cgroup_filter_args(&self.interface))?;
+            self.root_ready = true;
+        }
+        Ok(())
+    }
+
+    fn allocate_minor(&self) -> Result<u16, ShapingError> {
+        (FIRST_MINOR..u16::MAX)
+            .find(|minor| !self.classes.values().any(|used| used == minor))
+            .ok_or(ShapingError::NoFreeClass)
+    }
+
+    /// Cap the upload rate of `pid` (and of the children it starts afterwards)
+    pub fn limit_pid(&mut self, pid: u32, limit_kbps: u32) -> Result<(), ShapingError> {
+        self.ensure_root()?;
+
+        let minor = match self.classes.get(&pid) {
+            Some(minor) => *minor,
+            None => self.allocate_minor()?,
+        };
+        self.run_tc(&class_args(&self.interface, minor, limit_kbps))?;
+
+        let dir = self.cgroup_dir(minor);
+        fs::create_dir_all(&dir).map_err(|e| io_error(&dir, e))?;
+        write_file(&dir.join("net_cls.classid"), &net_cls_classid(minor))?;
+        write_file(&dir.join("cgroup.procs"), &pid.to_string())?;
+
+        self.classes.insert(pid, minor);
+        tracing::info!("🐧 PID {} limité à {} KB/s (classe {})", pid, limit_kbps, class_id(minor));
+        Ok(())
+    }
+
+    /// `pid` has a class and its cgroup still exists (a cgroup removed behind our back frees
+    /// the process)
+    pub fn has_class(&self, pid: u32) -> bool {
+        self.classes.get(&pid).is_some_and(|minor| self.cgroup_dir(*minor).is_dir())
+    }
+
+    /// Remove the limit of `pid`. Succeeds when it was not limited.
+    pub fn remove_pid(&mut self, pid: u32) -> Result<(), ShapingError> {
+        let Some(minor) = self.classes.remove(&pid) else {
+            return Ok(());
+        };
+
+        remove_cgroup(&self.net_cls_root, &self.cgroup_dir(minor))?;
+        self.run_tc(&class_delete_args(&self.interface, minor))?;
+
+        // Plus aucune limite : ne pas laisser la qdisc HTB sur l'interface
+        if self.classes.is_empty() {
+            self.run_tc(&root_qdisc_delete_args(&self.interface))?;
+            self.root_ready = false;
+        }
+        Ok(())
+    }
+
+    /// Tear down every GameBooster cgroup and the root qdisc, including leftovers of a previous run
+    pub fn clear_all(&mut self) -> Result<(), ShapingError> {
+        let root = &self.net_cls_root;
+        let entries = fs::read_dir(root).map_err(|e| io_error(root, e))?;
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with(CGROUP_PREFIX) {
+                remove_cgroup(root, &entry.path())?;
+            }
+        }
+
+        if let Err(e) = self.run_tc(&root_qdisc_delete_args(&self.interface)) {
+            // Pas de qdisc racine : rien à supprimer
+            tracing::debug!("🐧 {}", e);
+        }
+        self.classes.clear();
+        self.root_ready = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system::testing::{output, FakeRunner};
+    use crate::utils::TestDir;
+
+    /// The control files of a real cgroup vanish with it: leave the folder empty, so that
+    /// `remove_dir` succeeds as on cgroupfs
+    fn empty_control_files(dir: &Path) {
+        for file in ["net_cls.classid", "cgroup.procs"] {
+            let _ = fs::remove_file(dir.join(file));
+        }
+    }
+
+    #[test]
+    fn tc_arguments() {
+        assert_eq!(root_qdisc_args("eth0").join(" "), "qdisc replace dev eth0 root handle 1: htb default 0");
+        assert_eq!(
+            cgroup_filter_args("eth0").join(" "),
+            "filter add dev eth0 parent 1: protocol all prio 10 handle 1: cgroup"
+        );
+        assert_eq!(
+            class_args("eth0", 0x10, 1024).join(" "),
+            "class replace dev eth0 parent 1: classid 1:10 htb rate 8192kbit ceil 8192kbit"
+        );
+        assert_eq!(class_delete_args("wlan0", 0x1f).join(" "), "class del dev wlan0 classid 1:1f");
+        assert_eq!(net_cls_classid(0x10), "0x00010010");
+    }
+
+    #[test]
+    fn limit_and_remove_run_tc_and_fill_the_cgroups() {
+        let root = TestDir::new("linux_shaping");
+        let runner = Arc::new(FakeRunner::printing(""));
+        let mut shaper = LinuxShaper::with_runner(runner.clone(), root.0.clone(), "eth0".to_string());
+
+        shaper.limit_pid(4242, 512).unwrap();
+        shaper.limit_pid(4343, 256).unwrap();
+        // Nouvelle limite : même classe, qdisc déjà en place
+        shaper.limit_pid(4242, 1024).unwrap();
+        assert_eq!(
+            runner.calls(),
+            vec![
+                "tc qdisc replace dev eth0 root handle 1: htb default 0",
+                "tc filter add dev eth0 parent 1: protocol all prio 10 handle 1: cgroup",
+                "tc class replace dev eth0 parent 1: classid 1:10 htb rate 4096kbit ceil 4096kbit",
+                "tc class replace dev eth0 parent 1: classid 1:11 htb rate 2048kbit ceil 2048kbit",
+                "tc class replace dev eth0 parent 1: classid 1:10 htb rate 8192kbit ceil 8192kbit",
+            ]
+        );
+        let first = root.0.join("gamebooster_16");
+        assert_eq!(fs::read_to_string(first.join("net_cls.classid")).unwrap(), "0x00010010");
+        assert_eq!(fs::read_to_string(first.join("cgroup.procs")).unwrap(), "4242");
+        assert!(shaper.has_class(4242) && shaper.has_class(4343));
+
+        empty_control_files(&first);
+        shaper.remove_pid(4242).unwrap();
+        assert!(!first.exists() && !shaper.has_class(4242));
+        empty_control_files(&root.0.join("gamebooster_17"));
+        shaper.remove_pid(4343).unwrap();
+        // Un PID non limité n'exécute rien
+        shaper.remove_pid(4343).unwrap();
+        assert_eq!(
+            runner.calls()[5..],
+            ["tc class del dev eth0 classid 1:10", "tc class del dev eth0 classid 1:11", "tc qdisc del dev eth0 root"]
+        );
+    }
+
+    #[test]
+    fn failed_tc_reports_the_command_and_keeps_the_process_unlimited() {
+        let root = TestDir::new("linux_shaping_failure");
+        let runner = Arc::new(FakeRunner::new(|_, args| {
+            let mut result = output(if args[0] == "class" { 2 } else { 0 }, "");
+            result.stderr = "RTNETLINK answers: Operation not permitted\n".to_string();
+            Ok(result)
+        }));
+        let mut shaper = LinuxShaper::with_runner(runner, root.0.clone(), "eth0".to_string());
+
+        match shaper.limit_pid(4242, 512) {
+            Err(ShapingError::CommandFailed { command, stderr }) => {
+                assert!(command.starts_with("tc class replace dev eth0"));
+                assert_eq!(stderr, "RTNETLINK answers: Operation not permitted");
+            }
+            other => panic!("échec attendu, obtenu {:?}", other.map(|_| ())),
+        }
+        assert!(!shaper.has_class(4242));
+        assert!(!root.0.join("gamebooster_16").exists());
+    }
+}
@@ -1,21 +1,50 @@
 //! # Network Module - Real-time Network Monitoring with QoS
 //!
-//! This module provides real network process monitoring and uses Windows netsh for QoS.
-//! Uses silent netsh commands (no visible windows) for actual bandwidth limiting.
+//! This module provides real network process monitoring and uses Windows QoS policies.
+//! Policies are managed through the WMI NetQosPolicy provider, with silent PowerShell
+//! commands (no visible windows) kept as a fallback.
 
 pub mod process_monitor;
+pub mod adapters;
+pub mod appx;
+mod connections;
+pub mod connection_profile;
+pub mod data_usage;
+#[cfg(all(target_os = "windows", feature = "etw"))]
+mod etw_monitor;
+mod firewall;
+mod limit_verification;
+pub mod ping_monitor;
+pub mod process_filter;
+#[cfg(target_os = "linux")]
+mod linux_monitor;
+#[cfg(target_os = "linux")]
+mod linux_shaping;
+mod policy_store;
+#[cfg(target_os = "windows")]
+mod qos_wmi;
+pub mod stats_source;
 
 use std::sync::{Arc, Mutex};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::net::Ipv4Addr;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use sysinfo::{System};
-use std::process::Command;
-use std::time::Instant;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-/// Conditional import for Windows-specific features
-#[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
+use adapters::AdapterInfo;
+use data_usage::{SessionTotals, SessionTraffic};
+use chrono::{DateTime, Local};
+use crate::logs::{log_every, SUMMARY_INTERVAL};
+use crate::memory::wsl;
+use crate::safety::{self, ProtectionLevel};
+use crate::system::{self, CommandRunner};
+pub use policy_store::PersistedPolicy;
+
+pub use connections::{ConnectionInfo, ReverseDns, ReverseDnsCache, TcpState};
+pub use limit_verification::{LimitVerification, DEFAULT_VERIFICATION_SECS};
 
 /// Information about a network process with real-time data
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,8 +58,103 @@ pub struct NetworkProcessInfo {
     pub is_limited: bool,
     pub speed_limit: Option<u32>, // KB/s
     pub connections: u32,
+    /// Open TCP sockets by state, empty when the platform does not report states
+    #[serde(default)]
+    pub connections_by_state: HashMap<TcpState, u32>,
     pub current_upload_speed: u64,   // bytes/s current
     pub current_download_speed: u64, // bytes/s current
+    pub is_blocked: bool, // firewall rule GameBooster_Block_<exe> present
+    pub exe_path: Option<PathBuf>, // full image path, None when access is denied
+    pub parent_pid: Option<u32>,
+    /// Store application (package identity or executable under WindowsApps), see `appx`
+    #[serde(default)]
+    pub is_uwp: bool,
+    #[serde(default)]
+    pub package_family_name: Option<String>,
+    /// See `safety`: critical processes cannot be limited or blocked
+    #[serde(skip)]
+    pub protection: ProtectionLevel,
+    /// `vmmem` of WSL2 / Hyper-V, see `memory::wsl`: only port rules reach its traffic
+    #[serde(default)]
+    pub is_vm_host: bool,
+    /// Owns at least one TCP or UDP socket; a process may be listed without one while it is
+    /// limited or blocked
+    #[serde(default)]
+    pub has_active_sockets: bool,
+}
+
+impl NetworkProcessInfo {
+    /// Listed only for its open sockets (a game idling in a lobby): no traffic, neither limited
+    /// nor blocked. Hidden unless "Afficher les processus inactifs" is on. Linux lists the
+    /// processes by their sockets and rarely knows their counters: none is idle there.
+    pub fn is_idle(&self) -> bool {
+        !cfg!(target_os = "linux")
+            && self.has_active_sockets
+            && self.bytes_sent == 0
+            && self.bytes_received == 0
+            && !self.is_limited
+            && !self.is_blocked
+    }
+}
+
+/// Processes of one application (e.g. a browser and its renderers) shown as a single row
+#[derive(Debug, Clone)]
+pub struct ProcessGroup {
+    pub exe_name: String,
+    /// Topmost ancestor running the same executable, not necessarily among `members`
+    pub root_pid: u32,
+    pub total_download_speed: u64,
+    pub total_upload_speed: u64,
+    /// Sorted by download speed, fastest first
+    pub members: Vec<NetworkProcessInfo>,
+}
+
+impl ProcessGroup {
+    pub fn is_limited(&self) -> bool {
+        self.members.iter().any(|p| p.is_limited)
+    }
+
+    pub fn is_blocked(&self) -> bool {
+        self.members.iter().any(|p| p.is_blocked)
+    }
+
+    /// Every member is idle, see `NetworkProcessInfo::is_idle`
+    pub fn is_idle(&self) -> bool {
+        self.members.iter().all(|p| p.is_idle())
+    }
+
+    pub fn total_connections(&self) -> u32 {
+        self.members.iter().map(|p| p.connections).sum()
+    }
+
+    pub fn connections_by_state(&self) -> HashMap<TcpState, u32> {
+        let mut states = HashMap::new();
+        for (state, count) in self.members.iter().flat_map(|p| &p.connections_by_state) {
+            *states.entry(*state).or_insert(0) += count;
+        }
+        states
+    }
+
+    /// Limit shared by the members (one QoS policy per executable), KB/s
+    pub fn speed_limit(&self) -> Option<u32> {
+        self.members.iter().find_map(|p| p.speed_limit)
+    }
+
+    /// Strictest protection among the members
+    pub fn protection(&self) -> ProtectionLevel {
+        self.members.iter().map(|p| p.protection).max().unwrap_or_default()
+    }
+}
+
+/// Column used to sort the network process table
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum NetworkProcessSort {
+    Name,
+    Pid,
+    Download,
+    Upload,
+    Connections,
+    Limit,
 }
 
 /// Structure pour représenter une politique QoS active (via JSON)
@@ -44,979 +168,3606 @@ pub struct QosPolicyInfo {
     pub throttle_bits: u64,
     #[serde(rename = "IsRegistryOnly")]
     pub is_registry_only: bool,
+    #[serde(rename = "DSCP", default)]
+    pub dscp: Option<u8>,
+    /// Package family name when the policy targets a Store application
+    #[serde(rename = "PackageFamilyName", default)]
+    pub package_family_name: Option<String>,
+    /// IPProtocolMatchCondition of a port rule, `None` when the policy matches any protocol
+    #[serde(rename = "Protocol", default)]
+    pub protocol: Option<PortProtocol>,
+    /// Port range of a port rule and the side of the connection it matches
+    #[serde(rename = "PortStart", default)]
+    pub port_start: Option<u16>,
+    #[serde(rename = "PortEnd", default)]
+    pub port_end: Option<u16>,
+    #[serde(rename = "PortDirection", default)]
+    pub port_direction: Option<PortDirection>,
 }
 
-/// Real network bandwidth limiter using sysinfo monitoring + netsh QoS
-pub struct NetworkLimiter {
-    system: System,
-    processes: HashMap<u32, NetworkProcessInfo>,
-    limited_processes: Arc<Mutex<HashMap<u32, u32>>>, // PID -> limit in KB/s
-    last_update: Instant,
+/// Expedited Forwarding: latency-sensitive traffic (jeux, VoIP)
+pub const DSCP_EF: u8 = 46;
+/// Class Selector 4: interactive real-time traffic
+pub const DSCP_CS4: u8 = 32;
+/// Highest valid DSCP value (6 bits)
+pub const DSCP_MAX: u8 = 63;
+/// DSCP presets offered in the UI
+pub const DSCP_PRESETS: &[(&str, u8)] = &[("EF (46)", DSCP_EF), ("CS4 (32)", DSCP_CS4)];
 
+const DSCP_POLICY_PREFIX: &str = "GameBooster_DSCP_";
+
+/// Start of the name of every policy created by GameBooster; the others are foreign
+const POLICY_NAME_PREFIX: &str = "GameBooster";
+
+/// Prefix shared by the catch-all policy and its exemptions
+const GLOBAL_POLICY_PREFIX: &str = "GameBooster_Global_";
+const GLOBAL_LIMIT_POLICY_NAME: &str = "GameBooster_Global_Limit";
+
+const PORT_POLICY_PREFIX: &str = "GameBooster_Port_";
+
+/// IPProtocolMatchCondition of a port rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PortProtocol {
+    #[serde(rename = "TCP")]
+    Tcp,
+    #[serde(rename = "UDP")]
+    Udp,
+    Both,
 }
 
-impl NetworkLimiter {
-    /// Create a new NetworkLimiter with enhanced error checking
-    pub fn new() -> Result<Self> {
-        tracing::info!("🚀 Initialisation NetworkLimiter avec vérifications système");
-        
-        // Vérifier les prérequis système
-        Self::check_system_requirements()?;
-        
-        let limiter = NetworkLimiter {
-            system: System::new_all(),
-            processes: HashMap::new(),
-            limited_processes: Arc::new(Mutex::new(HashMap::new())),
-            last_update: Instant::now(),
-        };
-        
-        tracing::info!("✅ NetworkLimiter initialisé avec succès");
-        Ok(limiter)
+impl PortProtocol {
+    pub const ALL: [PortProtocol; 3] = [PortProtocol::Tcp, PortProtocol::Udp, PortProtocol::Both];
+
+    /// Value of the NetQos cmdlets and of the policy names
+    pub fn label(self) -> &'static str {
+        match self {
+            PortProtocol::Tcp => "TCP",
+            PortProtocol::Udp => "UDP",
+            PortProtocol::Both => "Both",
+        }
     }
 
-    /// Check system requirements for QoS functionality
-    fn check_system_requirements() -> Result<()> {
-        tracing::info!("🔍 Vérification des prérequis système QoS...");
-        
-        let check_script = r#"
-$ErrorActionPreference = "Continue"
-$OutputEncoding = [System.Text.Encoding]::UTF8
-$requirements = @()
-
-Write-Host "🔍 Vérification des prérequis système QoS..."
-
-# 1. Vérifier les permissions administrateur
-try {
-    $currentUser = [Security.Principal.WindowsIdentity]::GetCurrent()
-    $principal = New-Object Security.Principal.WindowsPrincipal($currentUser)
-    $isAdmin = $principal.IsInRole([Security.Principal.WindowsBuiltInRole]::Administrator)
-    
-    if ($isAdmin) {
-        Write-Host "✅ Permissions administrateur: OK"
-        $requirements += "ADMIN_OK"
-    } else {
-        Write-Host "❌ Permissions administrateur: MANQUANTES"
-        $requirements += "ADMIN_MISSING"
+    /// Value of `MSFT_NetQosPolicySettingData.IPProtocolMatchCondition` (0 = any protocol)
+    #[cfg(target_os = "windows")]
+    pub(crate) fn wmi_value(self) -> u16 {
+        match self {
+            PortProtocol::Tcp => 1,
+            PortProtocol::Udp => 2,
+            PortProtocol::Both => 3,
+        }
     }
-} catch {
-    Write-Host "⚠️ Impossible de vérifier les permissions: $($_.Exception.Message)"
-    $requirements += "ADMIN_ERROR"
-}
 
-# 2. Vérifier le module NetQoS
-try {
-    $netQosModule = Get-Module -ListAvailable -Name NetQoS -ErrorAction SilentlyContinue
-    if ($netQosModule) {
-        Write-Host "✅ Module NetQoS: Disponible (version $($netQosModule.Version))"
-        $requirements += "NETQOS_OK"
-    } else {
-        Write-Host "❌ Module NetQoS: NON DISPONIBLE"
-        $requirements += "NETQOS_MISSING"
+    #[cfg(target_os = "windows")]
+    pub(crate) fn from_wmi_value(value: u16) -> Option<Self> {
+        PortProtocol::ALL.into_iter().find(|protocol| protocol.wmi_value() == value)
     }
-} catch {
-    Write-Host "⚠️ Erreur vérification NetQoS: $($_.Exception.Message)"
-    $requirements += "NETQOS_ERROR"
-}
 
-# 3. Vérifier PowerShell version
-try {
-    $psVersion = $PSVersionTable.PSVersion
-    if ($psVersion.Major -ge 5) {
-        Write-Host "✅ PowerShell version: $psVersion (OK)"
-        $requirements += "POWERSHELL_OK"
-    } else {
-        Write-Host "❌ PowerShell version: $psVersion (INSUFFISANTE - requis: 5.0+)"
-        $requirements += "POWERSHELL_OLD"
-    }
-} catch {
-    Write-Host "⚠️ Erreur vérification PowerShell: $($_.Exception.Message)"
-    $requirements += "POWERSHELL_ERROR"
-}
-
-# 4. Vérifier la politique d'exécution
-try {
-    $execPolicy = Get-ExecutionPolicy -Scope CurrentUser
-    Write-Host "✅ Politique d'exécution: $execPolicy"
-    $requirements += "EXECPOLICY_$execPolicy"
-} catch {
-    Write-Host "⚠️ Erreur vérification politique: $($_.Exception.Message)"
-    $requirements += "EXECPOLICY_ERROR"
-}
-
-# 5. Tester la création d'une politique QoS test
-try {
-    $testPolicyName = "GameBooster_Test_$((Get-Date).Ticks)"
-    $testPolicy = New-NetQosPolicy -Name $testPolicyName -Default -ThrottleRateActionBitsPerSecond 1000000 -Confirm:$false -ErrorAction Stop
-    
-    if ($testPolicy) {
-        Write-Host "✅ Test création politique QoS: SUCCÈS"
-        $requirements += "QOS_CREATE_OK"
-        
-        # Nettoyer la politique de test
-        Remove-NetQosPolicy -Name $testPolicyName -Confirm:$false -ErrorAction SilentlyContinue
-        Write-Host "🧹 Politique de test supprimée"
+    fn overlaps(self, other: PortProtocol) -> bool {
+        self == other || self == PortProtocol::Both || other == PortProtocol::Both
     }
-} catch {
-    Write-Host "❌ Test création politique QoS: ÉCHEC - $($_.Exception.Message)"
-    $requirements += "QOS_CREATE_FAILED"
 }
 
-# Sortie des résultats
-Write-Host "📊 Résumé des vérifications:"
-foreach ($req in $requirements) {
-    Write-Output $req
+/// Port matched by a port rule. QoS only throttles outbound traffic: `Destination` is the
+/// remote port of the connection (a server), `Source` the local one (a listening client,
+/// BitTorrent seeding)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PortDirection {
+    Destination,
+    Source,
 }
-        "#;
-
-        let mut command = Command::new("powershell.exe");
-        command.args(["-NoProfile", "-WindowStyle", "Hidden", "-ExecutionPolicy", "Bypass", "-Command", check_script]);
-        
-        #[cfg(target_os = "windows")]
-        command.creation_flags(0x08000000); // CREATE_NO_WINDOW
-
-        let output = command.output();
 
-        match output {
-            Ok(result) => {
-                let stdout = String::from_utf8_lossy(&result.stdout);
-                let stderr = String::from_utf8_lossy(&result.stderr);
-                
-                if !stderr.is_empty() {
-                    tracing::warn!("⚠️ Avertissements vérification système: {}", stderr.trim());
-                }
-                
-                let requirements: Vec<String> = stdout
-                    .lines()
-                    .map(|line| line.trim().to_string())
-                    .filter(|line| !line.is_empty() && 
-                            !line.starts_with("🔍") && 
-                            !line.starts_with("✅") && 
-                            !line.starts_with("❌") && 
-                            !line.starts_with("⚠️") && 
-                            !line.starts_with("📊") &&
-                            !line.starts_with("🧹"))
-                    .collect();
-                
-                tracing::info!("📋 Prérequis système vérifiés: {} éléments", requirements.len());
-                
-                let mut warnings = Vec::new();
-                let mut errors = Vec::new();
-                
-                for req in &requirements {
-                    match req.as_str() {
-                        "ADMIN_MISSING" => errors.push("Permissions administrateur manquantes"),
-                        "NETQOS_MISSING" => errors.push("Module NetQoS non disponible"),
-                        "POWERSHELL_OLD" => errors.push("Version PowerShell insuffisante"),
-                        "QOS_CREATE_FAILED" => errors.push("Impossible de créer des politiques QoS"),
-                        r if r.starts_with("ADMIN_ERROR") || r.starts_with("NETQOS_ERROR") || r.starts_with("POWERSHELL_ERROR") => {
-                            warnings.push(format!("Erreur vérification: {}", req));
-                        }
-                        "ADMIN_OK" => tracing::info!("  ✅ Permissions administrateur"),
-                        "NETQOS_OK" => tracing::info!("  ✅ Module NetQoS disponible"),
-                        "POWERSHELL_OK" => tracing::info!("  ✅ Version PowerShell OK"),
-                        "QOS_CREATE_OK" => tracing::info!("  ✅ Test création QoS réussi"),
-                        _ => tracing::debug!("  📋 {}", req),
-                    }
-                }
-                
-                if !errors.is_empty() {
-                    let error_msg = format!("Prérequis système manquants: {}", errors.join(", "));
-                    tracing::error!("❌ {}", error_msg);
-                    return Err(anyhow::anyhow!(error_msg));
-                }
-                
-                if !warnings.is_empty() {
-                    for warning in warnings {
-                        tracing::warn!("⚠️ {}", warning);
-                    }
-                }
-                
-                tracing::info!("✅ Tous les prérequis système sont satisfaits");
-                Ok(())
-            }
-            Err(e) => {
-                let error_msg = format!("Impossible de vérifier les prérequis système: {}", e);
-                tracing::error!("❌ {}", error_msg);
-                Err(anyhow::anyhow!(error_msg))
-            }
+impl PortDirection {
+    pub fn label(self) -> &'static str {
+        match self {
+            PortDirection::Destination => "port distant",
+            PortDirection::Source => "port local",
         }
     }
 
-    /// Scan ALL processes using REAL system data from sysinfo
-    pub fn scan_network_processes(&mut self) -> Result<()> {
-        // Refresh system data
-        self.system.refresh_all();
-        
-        self.processes.clear();
-        
-        // Get processes with their real network activity
-        for (pid, process) in self.system.processes() {
-            let pid_u32 = pid.as_u32();
-            
-            // Skip system processes
-            if pid_u32 <= 4 { continue; }
-            
-            let name = process.name().to_string();
-            
-            // Get network statistics for this process (estimated based on CPU/memory usage)
-            let (estimated_sent, estimated_received, estimated_speed_up, estimated_speed_down) = 
-                self.estimate_process_network_activity(process);
-            
-            if estimated_sent > 0 || estimated_received > 0 || self.is_process_limited(pid_u32) {
-                let connections = self.estimate_connections_for_process(&name);
-                
-                let process_info = NetworkProcessInfo {
-                    pid: pid_u32,
-                    name: name.clone(),
-                    bytes_sent: estimated_sent,
-                    bytes_received: estimated_received,
-                    packets_sent: estimated_sent / 1024, // Rough estimate
-                    packets_received: estimated_received / 1024,
-                    is_limited: self.is_process_limited(pid_u32),
-                    speed_limit: self.get_process_limit(pid_u32),
-                    connections,
-                    current_upload_speed: estimated_speed_up,
-                    current_download_speed: estimated_speed_down,
-                };
-                
-                self.processes.insert(pid_u32, process_info);
-            }
+    /// Start of the NetQos port conditions ("IPDstPortStartMatchCondition")
+    pub(crate) fn condition_prefix(self) -> &'static str {
+        match self {
+            PortDirection::Destination => "IPDstPort",
+            PortDirection::Source => "IPSrcPort",
         }
-        
-        self.last_update = Instant::now();
-        
-        Ok(())
     }
+}
 
-    /// Estimate network activity for a process based on CPU/memory and process type
-    fn estimate_process_network_activity(
-        &self, 
-        process: &sysinfo::Process,
-    ) -> (u64, u64, u64, u64) {
-        let name = process.name().to_lowercase();
-        let cpu_usage = process.cpu_usage() as f64; // Convert to f64
-        let memory_usage = process.memory();
-        
-        // Base estimation multiplier based on process type
-        let (base_sent, base_received, speed_multiplier) = match name.as_str() {
-            name if name.contains("chrome") => (2_048_000, 1_024_000, 3.0),
-            name if name.contains("firefox") => (1_536_000, 768_000, 2.5),
-            name if name.contains("discord") => (512_000, 256_000, 1.5),
-            name if name.contains("steam") => (4_096_000, 2_048_000, 4.0),
-            name if name.contains("teams") => (800_000, 400_000, 2.0),
-            name if name.contains("zoom") => (1_200_000, 600_000, 2.5),
-            name if name.contains("spotify") => (600_000, 300_000, 1.8),
-            name if name.contains("vlc") => (300_000, 150_000, 1.2),
-            name if name.contains("edge") => (1_800_000, 900_000, 2.8),
-            name if name.contains("skype") => (400_000, 200_000, 1.6),
-            _ => {
-                // For unknown processes, use CPU and memory as indicators
-                if cpu_usage > 5.0 || memory_usage > 100_000_000 { // >100MB
-                    (200_000, 100_000, 1.0)
-                } else {
-                    (0, 0, 0.0)
-                }
-            }
-        };
-        
-        // Modulate based on actual CPU usage (more CPU = more network activity likely)
-        let cpu_factor = (cpu_usage / 100.0).max(0.1).min(3.0);
-        let memory_factor = ((memory_usage as f64) / 100_000_000.0).max(0.1).min(2.0); // Normalize to 100MB
-        
-        let final_sent = (base_sent as f64 * cpu_factor * memory_factor) as u64;
-        let final_received = (base_received as f64 * cpu_factor * memory_factor) as u64;
-        
-        // Current speeds (simulated based on activity)
-        let current_up = (final_sent as f64 * speed_multiplier * cpu_factor / 8.0) as u64; // /8 for current speed
-        let current_down = (final_received as f64 * speed_multiplier * cpu_factor / 8.0) as u64;
-        
-        (final_sent, final_received, current_up, current_down)
+/// Limit on a protocol and port range rather than an executable, for the traffic that no
+/// single process owns (virtual machines, WSL, uTP)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortRule {
+    pub protocol: PortProtocol,
+    /// Inclusive
+    pub port_range: (u16, u16),
+    pub direction: PortDirection,
+    pub limit_kbps: u32,
+}
+
+impl Default for PortRule {
+    fn default() -> Self {
+        // Plage BitTorrent classique
+        Self { protocol: PortProtocol::Both, port_range: (6881, 6889), direction: PortDirection::Source, limit_kbps: 512 }
     }
+}
 
-    /// Estimate connections for a process based on its type
-    fn estimate_connections_for_process(&self, name: &str) -> u32 {
-        let name_lower = name.to_lowercase();
-        match name_lower.as_str() {
-            name if name.contains("chrome") => 8,
-            name if name.contains("firefox") => 6,
-            name if name.contains("discord") => 3,
-            name if name.contains("steam") => 12,
-            name if name.contains("teams") => 5,
-            name if name.contains("zoom") => 4,
-            name if name.contains("spotify") => 2,
-            name if name.contains("vlc") => 1,
-            name if name.contains("edge") => 7,
-            _ => 1,
-        }
+impl PortRule {
+    /// "GameBooster_Port_TCP_6881-6889"
+    pub fn policy_name(&self) -> String {
+        format!("{}{}_{}-{}", PORT_POLICY_PREFIX, self.protocol.label(), self.port_range.0, self.port_range.1)
     }
 
-    /// Apply QoS limitation using Windows Group Policy (consistent approach)
-    fn apply_netsh_qos_limit(&self, pid: u32, limit_kbps: u32) -> Result<()> {
-        tracing::info!("🔧 Début limitation bande passante QoS GROUP POLICY pour PID {}", pid);
+    /// "TCP 6881-6889 (port local)"
+    pub fn describe(&self) -> String {
+        let (start, end) = self.port_range;
+        let ports = if start == end { start.to_string() } else { format!("{}-{}", start, end) };
+        format!("{} {} ({})", self.protocol.label(), ports, self.direction.label())
+    }
 
-        // Si la limite est 0, il faut supprimer la politique, pas en créer une nouvelle
-        if limit_kbps == 0 {
-            tracing::info!("🚫 Limite de 0 KB/s détectée. Suppression de la politique pour le PID {}", pid);
-            return self.remove_netsh_qos_limit(pid);
+    pub fn validate(&self) -> Result<()> {
+        let (start, end) = self.port_range;
+        if start == 0 {
+            return Err(anyhow::anyhow!("Le port 0 n'est pas valide (1-65535)"));
         }
-        
-        // Get process name for filtering
-        let process_name = if let Some(process) = self.processes.get(&pid) {
-            let exe_name = if process.name.contains(".exe") {
-                process.name.clone()
-            } else {
-                format!("{}.exe", process.name)
-            };
-            tracing::info!("📂 Nom processus trouvé: {} → {}", process.name, exe_name);
-            exe_name
-        } else {
-            let fallback = format!("Process_{}.exe", pid);
-            tracing::warn!("⚠️ Processus PID {} non trouvé, utilisation du nom générique: {}", pid, fallback);
-            fallback
-        };
+        if start > end {
+            return Err(anyhow::anyhow!("Plage de ports inversée : {} > {}", start, end));
+        }
+        parse_speed_limit(self.limit_kbps as f64, SpeedUnit::KiloBytesPerSec)?;
+        Ok(())
+    }
 
-        let policy_name = format!("GameBooster_Limit_{}", pid);
-        let throttle_bits_per_second = (limit_kbps * 1024 * 8) as u64; // Convert KB/s to bits/s
-        
-        tracing::info!("🔢 Limitation QoS: {} KB/s → {} bits/s pour {}", 
-            limit_kbps, throttle_bits_per_second, process_name);
-        tracing::info!("🎯 Politique: {} | Processus: {} | PID: {}", policy_name, process_name, pid);
+    /// Same traffic matched by both rules: the protocols and the port ranges intersect
+    pub fn overlaps(&self, other: &PortRule) -> bool {
+        self.protocol.overlaps(other.protocol)
+            && self.port_range.0 <= other.port_range.1
+            && other.port_range.0 <= self.port_range.1
+    }
+}
 
-        // Méthode PowerShell avec sortie JSON pour une fiabilité maximale
-        let powershell_script = format!(
-            r#"
-$ErrorActionPreference = "Stop"
-$OutputEncoding = [System.Text.Encoding]::UTF8
-[System.Threading.Thread]::CurrentThread.CurrentCulture = 'en-US'
+/// What a GameBooster QoS policy is used for, derived from its name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QosPolicyKind {
+    ProcessLimit,
+    GlobalLimit,
+    PortLimit,
+    GlobalExemption,
+    Dscp,
+    Other,
+}
 
-$policyName = "{0}"
-$processName = "{1}"
-$throttleBits = [long]{2}
+impl QosPolicyKind {
+    pub fn from_name(name: &str) -> Self {
+        if name == GLOBAL_LIMIT_POLICY_NAME {
+            QosPolicyKind::GlobalLimit
+        } else if name.starts_with(GLOBAL_POLICY_PREFIX) {
+            QosPolicyKind::GlobalExemption
+        } else if name.starts_with(DSCP_POLICY_PREFIX) {
+            QosPolicyKind::Dscp
+        } else if name.starts_with("GameBooster_Limit_") {
+            QosPolicyKind::ProcessLimit
+        } else if name.starts_with(PORT_POLICY_PREFIX) {
+            QosPolicyKind::PortLimit
+        } else {
+            QosPolicyKind::Other
+        }
+    }
+}
 
-$result = @{{
-    Success = $false
-    PolicyName = $policyName
-    AppName = $processName
-    ThrottleBits = $throttleBits
-    Message = ""
-}}
+impl QosPolicyInfo {
+    pub fn kind(&self) -> QosPolicyKind {
+        QosPolicyKind::from_name(&self.name)
+    }
 
-try {{
-    Remove-NetQosPolicy -Name $policyName -Confirm:$false -ErrorAction SilentlyContinue
+    /// Created by another tool, by hand or by a Group Policy (GPO)
+    pub fn is_foreign(&self) -> bool {
+        !self.name.starts_with(POLICY_NAME_PREFIX)
+    }
 
-    $policy = New-NetQosPolicy -Name $policyName -AppPathNameMatchCondition $processName -ThrottleRateActionBitsPerSecond $throttleBits -Confirm:$false
+    /// Rule of a policy matching a protocol and a port range
+    pub fn port_rule(&self) -> Option<PortRule> {
+        Some(PortRule {
+            protocol: self.protocol?,
+            port_range: (self.port_start?, self.port_end?),
+            direction: self.port_direction.unwrap_or(PortDirection::Destination),
+            limit_kbps: (self.throttle_bits / 8 / 1024) as u32,
+        })
+    }
 
-    $verification = Get-NetQosPolicy -Name $policyName
-    if ($verification -and $verification.ThrottleRateActionBitsPerSecond -eq $throttleBits) {{
-        $result.Success = $true
-        $result.Message = "Policy created and verified successfully."
-    }} else {{
-        $result.Message = "Policy created but verification failed. Expected {2} bits, got $($verification.ThrottleRateActionBitsPerSecond)."
-    }}
-}} catch {{
-    $result.Message = "PowerShell Error: $($_.Exception.Message)"
-}}
+    /// What the policy matches: the executable, or the protocol and ports of a port rule
+    pub fn target_label(&self) -> String {
+        match self.port_rule() {
+            Some(rule) => format!("🔌 {}", rule.describe()),
+            None => self.app_name.clone(),
+        }
+    }
 
-$result | ConvertTo-Json -Compress
-            "#,
-            policy_name,
-            process_name,
-            throttle_bits_per_second
-        );
+    /// Rate of a throttling policy, `None` for a marking-only (DSCP) policy
+    pub fn throttle_rate(&self) -> Option<u64> {
+        (self.throttle_bits > 0).then_some(self.throttle_bits)
+    }
 
-        tracing::info!("🔧 Lancement script QoS avec sortie JSON");
-        
-        let mut command = Command::new("powershell.exe");
-        command.args([
-                "-NoProfile", 
-                "-WindowStyle", "Hidden", 
-                "-ExecutionPolicy", "Bypass", 
-                "-Command", &powershell_script
-            ]);
-        
-        #[cfg(target_os = "windows")]
-        command.creation_flags(0x08000000); // CREATE_NO_WINDOW
-        
-        let output = command.output();
+    /// Lowercase file name of the targeted executable, `None` when the policy matches on
+    /// something else (URL, port, every application)
+    fn target_exe(&self) -> Option<String> {
+        let app_name = self.app_name.trim();
+        if app_name.is_empty() || app_name == "N/A" || app_name.contains('*') {
+            return None;
+        }
+        let file_name = Path::new(app_name).file_name()?.to_string_lossy().to_lowercase();
+        Some(file_name)
+    }
 
-        match output {
-            Ok(result) => {
-                let stdout = String::from_utf8(result.stdout)
-                    .map_err(|e| anyhow::anyhow!("Erreur de décodage UTF-8 (stdout): {}", e))?;
-                let stderr = String::from_utf8(result.stderr)
-                    .map_err(|e| anyhow::anyhow!("Erreur de décodage UTF-8 (stderr): {}", e))?;
+    /// Whether the executable targeted by the policy is known to be gone: only a full
+    /// path can be checked, a bare file name may match any install.
+    pub fn target_missing(&self) -> bool {
+        let path = Path::new(&self.app_name);
+        path.is_absolute() && !path.exists()
+    }
+}
 
-                if !stderr.is_empty() {
-                    tracing::warn!("⚠️ Avertissements (stderr) QoS: {}", stderr.trim());
-                }
+/// Where a policy found by `verify_qos_policies` stands against the ones GameBooster tracks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QosPolicyState {
+    /// On the system and recorded by GameBooster
+    Tracked,
+    /// On the system but unknown to this session (orphan, created by hand or by another tool)
+    Untracked,
+    /// Recorded by GameBooster but no longer on the system
+    Missing,
+}
 
-                #[derive(Deserialize)]
-                struct JsonResult {
-                    Success: bool,
-                    Message: String,
-                }
+/// Two throttling policies targeting the same executable: Windows applies only one of them,
+/// so the effective limit may not be the one set in GameBooster
+#[derive(Debug, Clone)]
+pub struct PolicyConflict {
+    /// Lowercase file name of the executable
+    pub exe: String,
+    pub ours: QosPolicyInfo,
+    /// Foreign policy, or a second GameBooster policy with a different rate
+    pub theirs: QosPolicyInfo,
+}
 
-                if let Ok(json_result) = serde_json::from_str::<JsonResult>(stdout.trim()) {
-                    if json_result.Success {
-                        tracing::info!("✅ Politique QoS GROUP POLICY créée avec succès: {}", policy_name);
-                        let _ = self.apply_netsh_qos_limit_realtime(pid, limit_kbps);
-                        Ok(())
-                    } else {
-                        let error_msg = format!("Échec création politique QoS (JSON): {}", json_result.Message);
-                        tracing::error!("❌ {}", error_msg);
-                        Err(anyhow::anyhow!(error_msg))
-                    }
-                } else {
-                    let error_msg = format!("Réponse JSON invalide du script QoS: {}. Stderr: {}", stdout.trim(), stderr.trim());
-                    tracing::error!("❌ {}", error_msg);
-                    Err(anyhow::anyhow!(error_msg))
-                }
-            }
-            Err(e) => {
-                let error_msg = format!("Impossible d'exécuter PowerShell QoS: {}", e);
-                tracing::error!("❌ {}", error_msg);
-                Err(anyhow::anyhow!(error_msg))
-            }
-        }
+impl PolicyConflict {
+    pub fn is_foreign(&self) -> bool {
+        self.theirs.is_foreign()
     }
+}
 
-    /// Méthode de limitation temps réel en parallèle (backup)
-    fn apply_netsh_qos_limit_realtime(&self, pid: u32, limit_kbps: u32) -> Result<()> {
-        tracing::info!("🔧 Début limitation bande passante TEMPS RÉEL pour PID {}", pid);
-        
-        // Get process name for filtering
-        let process_name = if let Some(process) = self.processes.get(&pid) {
-            let exe_name = if process.name.contains(".exe") {
-                process.name.clone()
-            } else {
-                format!("{}.exe", process.name)
-            };
-            tracing::info!("📂 Nom processus trouvé: {} → {}", process.name, exe_name);
-            exe_name
-        } else {
-            let fallback = format!("Process_{}.exe", pid);
-            tracing::warn!("⚠️ Processus PID {} non trouvé, utilisation du nom générique: {}", pid, fallback);
-            fallback
-        };
-
-        // Calculer la limitation en bytes/seconde
-        let limit_bytes_per_second = limit_kbps * 1024;
-        let delay_ms = self.calculate_packet_delay(limit_bytes_per_second);
-        
-        tracing::info!("🔢 Limitation TEMPS RÉEL: {} KB/s → {} bytes/s → délai {}ms par paquet", 
-            limit_kbps, limit_bytes_per_second, delay_ms);
-        tracing::info!("🎯 Application: {} | PID: {} | Limitation: {} KB/s", process_name, pid, limit_kbps);
-
-        // Démarrer l'interception WinDivert en arrière-plan
-        self.start_windivert_limiter(pid, process_name, delay_ms, limit_bytes_per_second)?;
-        
-        tracing::info!("✅ Limitation TEMPS RÉEL appliquée: PID {} → {} KB/s (actif immédiatement)", pid, limit_kbps);
-        Ok(())
+/// Executables throttled by a GameBooster policy and a foreign one, or by two GameBooster
+/// policies with different rates (a limit set per PID next to one set per name). Policies
+/// without an executable condition or without a rate are ignored.
+pub fn find_conflicts(policies: &[QosPolicyInfo]) -> Vec<PolicyConflict> {
+    let mut by_exe: HashMap<String, Vec<&QosPolicyInfo>> = HashMap::new();
+    for policy in policies {
+        if policy.throttle_rate().is_none() {
+            continue;
+        }
+        if let Some(exe) = policy.target_exe() {
+            by_exe.entry(exe).or_default().push(policy);
+        }
     }
 
-    /// Calculate packet delay based on bandwidth limit
-    fn calculate_packet_delay(&self, limit_bytes_per_second: u32) -> u64 {
-        // Assumer une taille moyenne de paquet de 1500 bytes (MTU Ethernet standard)
-        let avg_packet_size = 1500;
-        
-        // Calculer combien de paquets par seconde on peut envoyer
-        let packets_per_second = limit_bytes_per_second / avg_packet_size;
-        
-        if packets_per_second == 0 {
-            return 1000; // 1 seconde de délai minimum
+    let mut conflicts = Vec::new();
+    for (exe, targeting) in by_exe {
+        let (ours, theirs): (Vec<&QosPolicyInfo>, Vec<&QosPolicyInfo>) =
+            targeting.into_iter().partition(|policy| !policy.is_foreign());
+        let Some((first, others)) = ours.split_first() else {
+            continue;
+        };
+        let duplicates = others.iter().filter(|other| other.throttle_bits != first.throttle_bits);
+        for other in theirs.iter().chain(duplicates) {
+            conflicts.push(PolicyConflict { exe: exe.clone(), ours: (*first).clone(), theirs: (*other).clone() });
         }
-        
-        // Calculer le délai entre les paquets en millisecondes
-        let delay_ms = 1000 / packets_per_second;
-        
-        // Minimum 1ms, maximum 1000ms
-        delay_ms.max(1).min(1000) as u64
     }
+    conflicts.sort_by(|a, b| a.exe.cmp(&b.exe).then_with(|| a.theirs.name.cmp(&b.theirs.name)));
+    conflicts
+}
 
-    /// Start WinDivert-based bandwidth limiter (runs in background thread)
-    fn start_windivert_limiter(&self, pid: u32, process_name: String, delay_ms: u64, limit_bytes_per_second: u32) -> Result<()> {
-        tracing::info!("🚀 Démarrage limiteur WinDivert pour PID {} ({})", pid, process_name);
-        
-        let limit_kbps = limit_bytes_per_second / 1024; // Convert back to KB/s for script
+/// One line of the reconciliation between the system policies and the tracked ones
+#[derive(Debug, Clone)]
+pub struct QosPolicyRow {
+    pub policy: QosPolicyInfo,
+    pub state: QosPolicyState,
+    /// "name (PID)" of the scanned processes limited through this policy
+    pub limited_processes: Vec<String>,
+}
+
+/// Description of a QoS policy to create, shared by the WMI and PowerShell backends
+#[derive(Debug, Clone)]
+pub struct QosPolicySpec<'a> {
+    pub name: &'a str,
+    /// AppPathNameMatchCondition; `None` together with `default_match` for a catch-all policy
+    pub app_name: Option<&'a str>,
+    pub default_match: bool,
+    pub throttle_bits_per_second: Option<u64>,
+    pub dscp: Option<u8>,
+    /// IPSrcPrefixMatchCondition ("192.168.1.20/32"). NetQosPolicy has no interface
+    /// condition, so a policy is tied to an adapter through the source address of its traffic
+    pub src_prefix: Option<&'a str>,
+    /// IPProtocolMatchCondition and the destination or source port range
+    pub port_rule: Option<&'a PortRule>,
+}
+
+/// State of the "everything except my game" mode
+#[derive(Debug, Clone)]
+pub struct GlobalLimit {
+    pub limit_kbps: u32,
+    pub exempt_exe_names: Vec<String>,
+}
+
+/// Delay before trying again to remove an expired limit
+const EXPIRY_RETRY_SECS: i64 = 60;
+
+/// End of a time-boxed limit, see `set_process_speed_limit_for`
+#[derive(Debug, Clone, Copy)]
+pub struct LimitExpiry {
+    pub expires_at: DateTime<Local>,
+    /// Next attempt after a failed removal, `None` until the limit has expired
+    pub retry_at: Option<DateTime<Local>>,
+}
+
+impl LimitExpiry {
+    /// Expired but still in place, its removal having failed
+    pub fn removal_failed(&self) -> bool {
+        self.retry_at.is_some()
+    }
+}
+
+/// What `set_process_speed_limit` did to the limit of a PID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitChange {
+    /// Limit in place before the call, in KB/s
+    pub previous: Option<u32>,
+    /// Limit in place after the call, in KB/s; 0 when the bulk path removed it
+    pub new: u32,
+    /// The same limit was requested again but its policy had disappeared: it was created again
+    pub policy_recreated: bool,
+}
+
+impl LimitChange {
+    /// The same limit was requested again and its policy was left untouched
+    pub fn is_unchanged(&self) -> bool {
+        self.previous == Some(self.new) && !self.policy_recreated
+    }
+
+    /// "limite modifiée 1.0 → 0.5 MB/s", for the notifications
+    pub fn describe(&self) -> String {
+        let mbps = |kbps: u32| kbps as f64 / 1024.0;
+        match self.previous {
+            _ if self.new == 0 => "limite supprimée".to_string(),
+            Some(previous) if previous == self.new && self.policy_recreated => {
+                format!("politique disparue recréée ({:.1} MB/s)", mbps(self.new))
+            }
+            Some(previous) if previous == self.new => format!("limite inchangée ({:.1} MB/s)", mbps(self.new)),
+            Some(previous) => format!("limite modifiée {:.1} → {:.1} MB/s", mbps(previous), mbps(self.new)),
+            None => format!("limité à {:.1} MB/s", mbps(self.new)),
+        }
+    }
+}
+
+/// Outcomes of a bulk limit, counted by kind of change
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LimitSummary {
+    pub added: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    /// Same limit, policy found missing and created again
+    pub recreated: usize,
+    pub removed: usize,
+    pub failed: usize,
+}
+
+impl LimitSummary {
+    pub fn from_outcomes(outcomes: &[(u32, Result<LimitChange>)]) -> Self {
+        let mut summary = Self::default();
+        for (_, outcome) in outcomes {
+            match outcome {
+                Err(_) => summary.failed += 1,
+                Ok(change) if change.new == 0 => summary.removed += 1,
+                Ok(change) if change.policy_recreated => summary.recreated += 1,
+                Ok(change) if change.is_unchanged() => summary.unchanged += 1,
+                Ok(change) if change.previous.is_some() => summary.updated += 1,
+                Ok(_) => summary.added += 1,
+            }
+        }
+        summary
+    }
+
+    pub fn succeeded(&self) -> usize {
+        self.added + self.updated + self.unchanged + self.recreated + self.removed
+    }
+
+    /// "2 nouvelle(s), 1 modifiée(s), 3 inchangée(s)", the empty counts left out
+    pub fn describe(&self) -> String {
+        let parts: Vec<String> = [
+            (self.added, "nouvelle(s)"),
+            (self.updated, "modifiée(s)"),
+            (self.unchanged, "inchangée(s)"),
+            (self.recreated, "recréée(s)"),
+            (self.removed, "supprimée(s)"),
+            (self.failed, "échec(s)"),
+        ]
+        .iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, label)| format!("{} {}", count, label))
+        .collect();
+        if parts.is_empty() {
+            "aucune limite".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// A time-boxed limit that reached its end, reported by `tick_expirations`
+#[derive(Debug, Clone)]
+pub struct ExpiredLimit {
+    /// Process or executable the limit applied to
+    pub target: String,
+    /// Removal failed: the limit stays in place and is tried again
+    pub error: Option<String>,
+}
+
+/// What becomes of the policy of a limited process once the process exits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExitedLimitAction {
+    /// Removed once the process has been gone for the grace period
+    #[default]
+    Remove,
+    /// Kept, and attached to the next process of the same executable
+    Keep,
+}
+
+impl ExitedLimitAction {
+    pub fn label(self) -> &'static str {
+        match self {
+            ExitedLimitAction::Remove => "Supprimer la limite",
+            ExitedLimitAction::Keep => "Conserver pour le prochain lancement",
+        }
+    }
+}
+
+/// Default delay before the limit of an exited process is removed
+pub const DEFAULT_EXITED_LIMIT_GRACE_SECS: u64 = 120;
+
+/// Limit whose process exited while its policy, matching the executable name, stays in place
+#[derive(Debug, Clone)]
+pub struct ExitedLimit {
+    /// Executable the policy matches
+    pub exe: String,
+    pub kbps: u32,
+    pub exited_at: DateTime<Local>,
+    pub policy_name: String,
+    /// Automatic removal failed, it is left to the user
+    pub removal_error: Option<String>,
+}
+
+/// How long a limit set from the quick limit bar lasts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LimitDuration {
+    #[default]
+    Permanent,
+    HalfHour,
+    OneHour,
+    TwoHours,
+    Custom,
+}
+
+impl LimitDuration {
+    pub const ALL: [LimitDuration; 5] = [
+        LimitDuration::Permanent,
+        LimitDuration::HalfHour,
+        LimitDuration::OneHour,
+        LimitDuration::TwoHours,
+        LimitDuration::Custom,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LimitDuration::Permanent => "Permanente",
+            LimitDuration::HalfHour => "30 min",
+            LimitDuration::OneHour => "1 h",
+            LimitDuration::TwoHours => "2 h",
+            LimitDuration::Custom => "Personnalisée",
+        }
+    }
+
+    /// `None` for a permanent limit, `custom_minutes` for `Custom`
+    pub fn duration(self, custom_minutes: u32) -> Option<Duration> {
+        let minutes = match self {
+            LimitDuration::Permanent => return None,
+            LimitDuration::HalfHour => 30,
+            LimitDuration::OneHour => 60,
+            LimitDuration::TwoHours => 120,
+            LimitDuration::Custom => custom_minutes.max(1),
+        };
+        Some(Duration::from_secs(minutes as u64 * 60))
+    }
+}
+
+/// DSCP value applied to exempted executables
+const EXEMPTION_DSCP: u8 = DSCP_EF;
+
+/// System processes never limited by `limit_all_except`, whatever their traffic
+pub const BULK_LIMIT_DENYLIST: &[&str] = &[
+    "system",
+    "registry",
+    "smss.exe",
+    "csrss.exe",
+    "wininit.exe",
+    "winlogon.exe",
+    "services.exe",
+    "lsass.exe",
+    "svchost.exe",
+    "dwm.exe",
+];
+
+/// Below this (upload + download, bytes/s) a process is left alone by `limit_all_except`
+pub const BULK_LIMIT_MIN_ACTIVITY: u64 = 1024;
+
+/// Scanned data older than this is shown as outdated
+pub const STALE_SCAN_SECS: u64 = 10;
+/// A PID seen this long ago may have exited: limiting it logs a warning
+const STALE_PID_SECS: u64 = 30;
+/// `bytes_sent`/`bytes_received` are cumulative counters (Linux namespaces), turned into speeds
+/// between two scans. On Windows the stats source gives the speeds: measured from the
+/// difference between two refreshes (ETW), or estimated, see `speeds_estimated`.
+const REAL_BYTE_COUNTERS: bool = cfg!(target_os = "linux");
+
+fn is_bulk_protected(name: &str) -> bool {
+    let name = name.to_lowercase();
+    BULK_LIMIT_DENYLIST.iter().any(|denied| name == *denied || name.trim_end_matches(".exe") == *denied)
+}
+
+/// Processes `limit_all_except` would limit, shown before applying
+#[derive(Debug, Clone, Default)]
+pub struct BulkLimitPreview {
+    /// PID and executable (QoS match condition) of each process to limit, sorted by executable
+    pub targets: Vec<(u32, String)>,
+    /// Active processes spared by `BULK_LIMIT_DENYLIST`
+    pub protected: Vec<String>,
+    /// Executables of the selection, never limited even through another PID
+    pub selected_exe_names: Vec<String>,
+}
+
+impl BulkLimitPreview {
+    /// Distinct executables among the targets (one policy each)
+    pub fn exe_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.targets.iter().map(|(_, name)| name.as_str()).collect();
+        names.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        names
+    }
+}
+
+/// Policies created by one `limit_all_except`, removed together by `undo_limit_batch`
+#[derive(Debug, Clone)]
+pub struct LimitBatch {
+    pub id: u64,
+    /// PID -> limit policy created for it
+    pub policies: HashMap<u32, String>,
+    /// Executables of the selection marked with `EXEMPTION_DSCP`
+    pub exempted_exe_names: Vec<String>,
+}
+
+/// Backend used to create, remove and list QoS policies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QosBackend {
+    /// `ROOT\StandardCimv2` `MSFT_NetQosPolicySettingData` via COM (default)
+    Wmi,
+    /// NetQos PowerShell cmdlets (legacy path, also used as fallback)
+    PowerShell,
+}
+
+impl QosBackend {
+    /// Backend selected by `GAMEBOOSTER_QOS_BACKEND=powershell|wmi` (WMI by default)
+    pub fn from_env() -> Self {
+        match std::env::var("GAMEBOOSTER_QOS_BACKEND") {
+            Ok(value) if value.eq_ignore_ascii_case("powershell") => QosBackend::PowerShell,
+            _ => QosBackend::Wmi,
+        }
+    }
+}
+
+/// A cached `QosRequirements` is checked again after this long
+const QOS_REQUIREMENTS_TTL_SECS: i64 = 10 * 60;
+
+/// Why limits cannot be applied; monitoring is unaffected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QosUnavailableReason {
+    NotElevated,
+    /// No NetQoS provider (Windows 7, stripped editions); the check error when it failed
+    NetQosMissing(Option<String>),
+}
+
+/// Error of the functions applying limits when the prerequisites are missing, see
+/// `QosRequirements`. The UI turns it into a banner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QosUnavailable(pub QosUnavailableReason);
+
+impl std::fmt::Display for QosUnavailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            QosUnavailableReason::NotElevated => write!(f, "Limitation indisponible: droits administrateur requis"),
+            QosUnavailableReason::NetQosMissing(None) => write!(f, "Limitation indisponible: module NetQoS absent"),
+            QosUnavailableReason::NetQosMissing(Some(error)) => {
+                write!(f, "Limitation indisponible: module NetQoS introuvable ({})", error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for QosUnavailable {}
+
+/// Prerequisites of the limits, checked without PowerShell: token elevation and the
+/// presence of the NetQoS WMI class (tc needs root on Linux, nothing else)
+#[derive(Debug, Clone, Serialize)]
+pub struct QosRequirements {
+    pub elevated: bool,
+    pub netqos_available: bool,
+    /// Why the NetQoS check could not run (namespace missing, COM error)
+    pub check_error: Option<String>,
+    pub checked_at: DateTime<Local>,
+}
+
+impl QosRequirements {
+    /// Blocking (WMI), cheap enough for the first limit; also run on a worker at startup
+    pub fn check() -> Self {
+        let elevated = crate::utils::is_elevated();
+        #[cfg(target_os = "windows")]
+        let (netqos_available, check_error) = match qos_wmi::provider_available() {
+            Ok(available) => (available, None),
+            Err(e) => (false, Some(e.to_string())),
+        };
+        #[cfg(not(target_os = "windows"))]
+        let (netqos_available, check_error) = (true, None);
+
+        let requirements = Self { elevated, netqos_available, check_error, checked_at: Local::now() };
+        match requirements.unavailable() {
+            None => tracing::info!("✅ Prérequis de limitation satisfaits"),
+            Some(reason) => tracing::warn!("⚠️ {} (surveillance seule)", reason),
+        }
+        requirements
+    }
+
+    pub fn unavailable(&self) -> Option<QosUnavailable> {
+        if !self.netqos_available {
+            Some(QosUnavailable(QosUnavailableReason::NetQosMissing(self.check_error.clone())))
+        } else if !self.elevated {
+            Some(QosUnavailable(QosUnavailableReason::NotElevated))
+        } else {
+            None
+        }
+    }
+
+    fn is_stale(&self) -> bool {
+        (Local::now() - self.checked_at).num_seconds() > QOS_REQUIREMENTS_TTL_SECS
+    }
+}
+
+/// Policies recorded in the policy store, without a limiter (diagnostic report)
+pub fn persisted_policies() -> Vec<PersistedPolicy> {
+    policy_store::load()
+}
+
+/// Whether creating/removing QoS policies needs administrator rights
+pub fn requires_elevation() -> bool {
+    true
+}
+
+/// Remove every GameBooster QoS policy without a `NetworkLimiter`, through WMI and the
+/// registry only: used by the panic hook, where a PowerShell call could hang.
+/// Returns the number of policies removed from the QoS provider.
+#[cfg(target_os = "windows")]
+pub fn remove_all_policies_now() -> Result<usize> {
+    let removed = qos_wmi::remove_policies_with_prefix("GameBooster_")?;
+    qos_wmi::remove_registry_policies("GameBooster_");
+    policy_store::save(&[])?;
+    Ok(removed)
+}
+
+/// Real network bandwidth limiter using sysinfo monitoring + netsh QoS
+pub struct NetworkLimiter {
+    /// Runs the PowerShell scripts, replaced by recorded outputs in tests
+    runner: Arc<dyn CommandRunner>,
+    system: System,
+    processes: HashMap<u32, NetworkProcessInfo>,
+    limited_processes: Arc<Mutex<HashMap<u32, u32>>>, // PID -> limit in KB/s
+    /// End of the last successful scan, `None` before the first one
+    last_update: Option<Instant>,
+    qos_backend: QosBackend,
+    policy_names: HashMap<u32, String>, // PID -> QoS policy name (shared when several PIDs run the same exe)
+    global_limit: Option<GlobalLimit>,
+    blocked_rules: HashSet<String>, // existing GameBooster_Block_* firewall rules
+    #[cfg(target_os = "linux")]
+    linux_monitor: linux_monitor::LinuxNetMonitor,
+    /// Bytes and speeds of each process, see `set_high_precision`
+    #[cfg(not(target_os = "linux"))]
+    stats_source: Box<dyn stats_source::NetworkStatsSource>,
+    /// tc/net_cls backend, created on the first limit (checks root, tc and net_cls)
+    #[cfg(target_os = "linux")]
+    linux_shaper: Option<linux_shaping::LinuxShaper>,
+    /// Policies created by GameBooster, mirrored in `config/qos_policies.json`
+    managed_policies: Vec<PersistedPolicy>,
+    /// Process limits of a previous session, attached to matching PIDs during scans
+    adopted_limits: Vec<PersistedPolicy>,
+    /// GameBooster policies found at startup that no session recorded
+    orphaned_policies: Vec<QosPolicyInfo>,
+    /// Adapters seen at the last scan
+    adapters: Vec<AdapterInfo>,
+    /// Name of the adapter carrying the default route at the last scan
+    active_adapter: Option<String>,
+    /// PID -> adapter and address its policy is scoped to
+    policy_scopes: HashMap<u32, (String, Ipv4Addr)>,
+    /// Operations of `limit_all_except` that can still be undone (this session only)
+    limit_batches: Vec<LimitBatch>,
+    next_batch_id: u64,
+    /// Policy name -> end of its time-boxed limit
+    limit_expiries: HashMap<String, LimitExpiry>,
+    /// Limits of the processes that exited, until removed, kept or attached again
+    recently_exited: Vec<ExitedLimit>,
+    exited_limit_action: ExitedLimitAction,
+    exited_limit_grace: Duration,
+    /// Last prerequisite check, made by the first limit or handed over by a background check
+    qos_requirements: Option<QosRequirements>,
+    /// Bytes used today by each application, see `data_usage`
+    session_totals: SessionTotals,
+    /// `restore_policies` ran: deferred to the first scan or limit, so that starting the app
+    /// does not query QoS before the network tab is used
+    policies_restored: bool,
+    /// The TCP auto-tuning was found at "restricted" when the policies were restored, see
+    /// `reset_legacy_autotuning`
+    legacy_autotuning_restricted: bool,
+}
+
+impl NetworkLimiter {
+    /// Create a new NetworkLimiter with enhanced error checking
+    pub fn new() -> Result<Self> {
+        tracing::info!("🚀 Initialisation NetworkLimiter avec vérifications système");
+        Self::with_runner(system::default_runner())
+    }
+
+    /// `new`, every PowerShell script running through `runner`. The QoS prerequisites are
+    /// not checked here: monitoring works without them, see `qos_requirements`. Neither are
+    /// the policies of a previous session read, see `ensure_policies_restored`.
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Result<Self> {
+        let mut limiter = NetworkLimiter {
+            runner,
+            system: System::new_all(),
+            processes: HashMap::new(),
+            limited_processes: Arc::new(Mutex::new(HashMap::new())),
+            last_update: None,
+            qos_backend: QosBackend::from_env(),
+            policy_names: HashMap::new(),
+            global_limit: None,
+            blocked_rules: HashSet::new(),
+            #[cfg(target_os = "linux")]
+            linux_monitor: linux_monitor::LinuxNetMonitor::new()?,
+            #[cfg(not(target_os = "linux"))]
+            stats_source: Box::new(stats_source::EstimatedStats),
+            #[cfg(target_os = "linux")]
+            linux_shaper: None,
+            managed_policies: Vec::new(),
+            adopted_limits: Vec::new(),
+            orphaned_policies: Vec::new(),
+            adapters: Vec::new(),
+            active_adapter: None,
+            policy_scopes: HashMap::new(),
+            limit_batches: Vec::new(),
+            next_batch_id: 1,
+            limit_expiries: HashMap::new(),
+            recently_exited: Vec::new(),
+            exited_limit_action: ExitedLimitAction::default(),
+            exited_limit_grace: Duration::from_secs(DEFAULT_EXITED_LIMIT_GRACE_SECS),
+            qos_requirements: None,
+            session_totals: SessionTotals::load(),
+            policies_restored: false,
+            legacy_autotuning_restricted: false,
+        };
+        limiter.refresh_adapters();
+        
+        tracing::info!("✅ NetworkLimiter initialisé avec succès (backend QoS: {:?})", limiter.qos_backend);
+        Ok(limiter)
+    }
+
+    /// Run `restore_policies` once, before the first operation that depends on the policies.
+    /// The TCP auto-tuning left restricted by older builds is only looked at: resetting it is
+    /// up to the user, see `reset_legacy_autotuning`.
+    pub fn ensure_policies_restored(&mut self) {
+        if !self.policies_restored {
+            self.policies_restored = true;
+            #[cfg(not(target_os = "linux"))]
+            match Self::autotuning_is_restricted(self.runner.as_ref()) {
+                Ok(restricted) => self.legacy_autotuning_restricted = restricted,
+                Err(e) => tracing::warn!("⚠️ Vérification de l'auto-tuning TCP impossible: {}", e),
+            }
+            self.restore_policies();
+        }
+    }
+
+    /// Whether `netsh` reports the receive window auto-tuning at "restricted", read only
+    #[cfg_attr(target_os = "linux", allow(dead_code))]
+    fn autotuning_is_restricted(runner: &dyn CommandRunner) -> Result<bool> {
+        let output = runner.run("netsh", &["interface", "tcp", "show", "global"])?;
+        if !output.success() {
+            return Err(anyhow::anyhow!("netsh a échoué (code {:?}): {}", output.code, output.stderr.trim()));
+        }
+        let restricted = autotuning_restricted(&output.stdout);
+        if restricted {
+            tracing::warn!("⚠️ Auto-tuning TCP à \"restricted\", probablement laissé par une ancienne version de GameBooster");
+        }
+        Ok(restricted)
+    }
+
+    /// The TCP auto-tuning was found at "restricted", the setting the realtime fallback script
+    /// of older builds applied and never reverted
+    pub fn legacy_autotuning_restricted(&self) -> bool {
+        self.legacy_autotuning_restricted
+    }
+
+    /// Put the machine-wide TCP auto-tuning back to the Windows default ("normal"), on the
+    /// user's request. Returns the change made, for the notification.
+    pub fn reset_legacy_autotuning(&mut self) -> Result<String> {
+        let reset = self.runner.run("netsh", &["interface", "tcp", "set", "global", "autotuninglevel=normal"])?;
+        if !reset.success() {
+            return Err(anyhow::anyhow!("Rétablissement de l'auto-tuning TCP échoué: {}", reset.stdout.trim()));
+        }
+        self.legacy_autotuning_restricted = false;
+        let change = "Auto-tuning TCP : restricted → normal".to_string();
+        tracing::info!("✅ {}", change);
+        Ok(change)
+    }
+
+    /// Sort the GameBooster policies present on the system: the ones recorded in the policy
+    /// store are tracked again, the others are kept aside as orphans for the user to review
+    fn restore_policies(&mut self) {
+        self.managed_policies = policy_store::load();
+        let policies = match self.verify_qos_policies(false) {
+            Ok(policies) => policies,
+            Err(e) => {
+                tracing::warn!("⚠️ Détection des politiques QoS orphelines impossible: {}", e);
+                return;
+            }
+        };
+
+        // Politiques enregistrées mais supprimées depuis (à la main, autre outil...)
+        self.managed_policies.retain(|known| policies.iter().any(|p| p.name == known.name));
+        for policy in policies {
+            match self.managed_policies.iter().find(|known| known.name == policy.name).cloned() {
+                Some(known) => self.track_policy(&known),
+                None => self.orphaned_policies.push(policy),
+            }
+        }
+        self.save_policy_store();
+
+        if !self.orphaned_policies.is_empty() {
+            tracing::warn!("⚠️ {} politique(s) QoS GameBooster orpheline(s) d'une session précédente", self.orphaned_policies.len());
+        }
+    }
+
+    /// Restore the in-memory state matching a policy of a previous session
+    fn track_policy(&mut self, policy: &PersistedPolicy) {
+        let limit_kbps = (policy.throttle_bits / 8 / 1024) as u32;
+        match QosPolicyKind::from_name(&policy.name) {
+            QosPolicyKind::ProcessLimit => {
+                if let Some(expires_at) = policy.expires_at {
+                    self.limit_expiries.insert(policy.name.clone(), LimitExpiry { expires_at, retry_at: None });
+                }
+                self.adopted_limits.push(policy.clone());
+            }
+            QosPolicyKind::GlobalLimit => {
+                self.global_limit
+                    .get_or_insert_with(|| GlobalLimit { limit_kbps, exempt_exe_names: Vec::new() })
+                    .limit_kbps = limit_kbps;
+            }
+            QosPolicyKind::GlobalExemption => {
+                self.global_limit
+                    .get_or_insert_with(|| GlobalLimit { limit_kbps: 0, exempt_exe_names: Vec::new() })
+                    .exempt_exe_names
+                    .push(policy.app_name.clone());
+            }
+            QosPolicyKind::PortLimit | QosPolicyKind::Dscp | QosPolicyKind::Other => {}
+        }
+    }
+
+    /// Mark a PID as limited when its executable matches a limit policy: one adopted from a
+    /// previous session, or one created for another process of the same executable (the policy
+    /// matches the executable, not the PID, so the new process is throttled too)
+    #[cfg(not(target_os = "linux"))]
+    fn attach_adopted_limit(&mut self, pid: u32, exe_path: &Path) {
+        let Some(policy) = self.adopted_policy_for(exe_path) else {
+            return;
+        };
+        let limit_kbps = (policy.throttle_bits / 8 / 1024) as u32;
+        let policy_name = policy.name.clone();
+
+        if let Ok(mut limited) = self.limited_processes.lock() {
+            limited.insert(pid, limit_kbps);
+        }
+        if self.recently_exited.iter().any(|exited| exited.policy_name == policy_name) {
+            tracing::info!("🔁 {} relancé (PID {}) : limite {} KB/s rattachée", policy.app_name, pid, limit_kbps);
+            self.recently_exited.retain(|exited| exited.policy_name != policy_name);
+        }
+        self.policy_names.insert(pid, policy_name);
+    }
+
+    /// Limit policy matching `exe_path`, see `attach_adopted_limit`
+    #[cfg(not(target_os = "linux"))]
+    fn adopted_policy_for(&self, exe_path: &Path) -> Option<&PersistedPolicy> {
+        self.adopted_limits
+            .iter()
+            .chain(self.managed_policies.iter().filter(|p| QosPolicyKind::from_name(&p.name) == QosPolicyKind::ProcessLimit))
+            .find(|p| app_name_matches(&p.app_name, exe_path))
+    }
+
+    /// GameBooster policies found at startup that no session recorded
+    pub fn orphaned_policies(&self) -> &[QosPolicyInfo] {
+        &self.orphaned_policies
+    }
+
+    /// Keep an orphaned policy: it is recorded and shown like the ones created by this session
+    pub fn adopt_orphaned_policy(&mut self, name: &str) -> Result<()> {
+        let index = self
+            .orphaned_policies
+            .iter()
+            .position(|p| p.name == name)
+            .ok_or_else(|| anyhow::anyhow!("Politique orpheline {} introuvable", name))?;
+        let policy = self.orphaned_policies.remove(index);
+
+        let persisted = PersistedPolicy {
+            name: policy.name.clone(),
+            app_name: policy.app_name.clone(),
+            throttle_bits: policy.throttle_bits,
+            expires_at: None,
+            port_rule: policy.port_rule(),
+        };
+        self.track_policy(&persisted);
+        self.remember(persisted);
+        tracing::info!("📥 Politique {} adoptée ({})", policy.name, policy.app_name);
+        Ok(())
+    }
+
+    /// Delete an orphaned policy, from the QoS provider and the Group Policy registry store
+    pub fn remove_orphaned_policy(&mut self, name: &str) -> Result<()> {
+        let index = self
+            .orphaned_policies
+            .iter()
+            .position(|p| p.name == name)
+            .ok_or_else(|| anyhow::anyhow!("Politique orpheline {} introuvable", name))?;
+
+        if !self.orphaned_policies[index].is_registry_only {
+            self.remove_policy_by_name(name)?;
+        }
+        #[cfg(target_os = "windows")]
+        qos_wmi::remove_registry_policy(name);
+
+        self.orphaned_policies.remove(index);
+        tracing::info!("🗑️ Politique orpheline {} supprimée", name);
+        Ok(())
+    }
+
+    /// Compare the policies found on the system with the ones recorded by GameBooster:
+    /// found policies first (in their order), then the recorded ones Windows no longer has.
+    /// Foreign policies are left out, see `find_conflicts`.
+    pub fn reconcile_qos_policies(&self, found: &[QosPolicyInfo]) -> Vec<QosPolicyRow> {
+        let limited_processes = |policy_name: &str| -> Vec<String> {
+            let mut processes: Vec<String> = self
+                .policy_names
+                .iter()
+                .filter(|(_, name)| name.as_str() == policy_name)
+                .map(|(pid, _)| match self.processes.get(pid) {
+                    Some(process) => format!("{} ({})", process.name, pid),
+                    None => format!("PID {}", pid),
+                })
+                .collect();
+            processes.sort();
+            processes
+        };
+
+        let mut rows: Vec<QosPolicyRow> = found
+            .iter()
+            .filter(|policy| !policy.is_foreign())
+            .map(|policy| QosPolicyRow {
+                policy: policy.clone(),
+                state: if self.managed_policies.iter().any(|known| known.name == policy.name) {
+                    QosPolicyState::Tracked
+                } else {
+                    QosPolicyState::Untracked
+                },
+                limited_processes: limited_processes(&policy.name),
+            })
+            .collect();
+
+        for known in &self.managed_policies {
+            if found.iter().any(|policy| policy.name == known.name) {
+                continue;
+            }
+            rows.push(QosPolicyRow {
+                policy: QosPolicyInfo {
+                    name: known.name.clone(),
+                    app_name: known.app_name.clone(),
+                    throttle_bits: known.throttle_bits,
+                    is_registry_only: false,
+                    dscp: None,
+                    package_family_name: appx::family_name_from_path(&known.app_name),
+                    protocol: known.port_rule.map(|rule| rule.protocol),
+                    port_start: known.port_rule.map(|rule| rule.port_range.0),
+                    port_end: known.port_rule.map(|rule| rule.port_range.1),
+                    port_direction: known.port_rule.map(|rule| rule.direction),
+                },
+                state: QosPolicyState::Missing,
+                limited_processes: limited_processes(&known.name),
+            });
+        }
+        rows
+    }
+
+    /// Delete a policy found on the system that GameBooster does not track
+    pub fn remove_untracked_policy(&mut self, policy: &QosPolicyInfo) -> Result<()> {
+        if policy.is_foreign() {
+            return Err(anyhow::anyhow!("La politique {} n'a pas été créée par GameBooster", policy.name));
+        }
+        if self.orphaned_policies.iter().any(|orphan| orphan.name == policy.name) {
+            return self.remove_orphaned_policy(&policy.name);
+        }
+        if self.managed_policies.iter().any(|known| known.name == policy.name) {
+            return Err(anyhow::anyhow!("La politique {} est suivie par GameBooster, libérez-la depuis le tableau", policy.name));
+        }
+
+        if !policy.is_registry_only {
+            self.remove_policy_by_name(&policy.name)?;
+        }
+        #[cfg(target_os = "windows")]
+        qos_wmi::remove_registry_policy(&policy.name);
+        tracing::info!("🗑️ Politique non suivie {} supprimée", policy.name);
+        Ok(())
+    }
+
+    /// Policies created by GameBooster and recorded in the policy store
+    pub fn managed_policies(&self) -> &[PersistedPolicy] {
+        &self.managed_policies
+    }
+
+    /// Stop tracking a recorded policy that Windows no longer has, instead of creating it again
+    pub fn forget_missing_policy(&mut self, name: &str) {
+        self.forget_policies(|policy_name| policy_name == name);
+        tracing::info!("🧹 Politique {} absente de Windows, oubliée", name);
+    }
+
+    /// Create again a tracked policy that Windows no longer has (removed by hand, Group
+    /// Policy refresh...), with its recorded target, rate and adapter scope
+    pub fn reapply_policy(&mut self, name: &str) -> Result<()> {
+        let known = self
+            .managed_policies
+            .iter()
+            .find(|known| known.name == name)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Politique {} non suivie par GameBooster", name))?;
+        self.ensure_qos_available()?;
+
+        match QosPolicyKind::from_name(name) {
+            QosPolicyKind::GlobalLimit | QosPolicyKind::GlobalExemption => {
+                // La limite globale et ses exemptions ne vont pas l'une sans l'autre
+                let global = self
+                    .global_limit
+                    .clone()
+                    .ok_or_else(|| anyhow::anyhow!("Aucune limite globale active pour recréer {}", name))?;
+                self.set_global_limit_except(&global.exempt_exe_names, global.limit_kbps)?;
+            }
+            QosPolicyKind::Dscp => {
+                return Err(anyhow::anyhow!(
+                    "Valeur DSCP de {} non enregistrée : marquez à nouveau {} depuis la sélection",
+                    name,
+                    known.app_name
+                ));
+            }
+            QosPolicyKind::PortLimit => {
+                let rule = known
+                    .port_rule
+                    .ok_or_else(|| anyhow::anyhow!("Plage de ports de {} non enregistrée", name))?;
+                self.create_policy_from_spec(&QosPolicySpec {
+                    name,
+                    app_name: None,
+                    default_match: false,
+                    throttle_bits_per_second: Some(known.throttle_bits),
+                    dscp: None,
+                    src_prefix: None,
+                    port_rule: Some(&rule),
+                })?;
+            }
+            QosPolicyKind::ProcessLimit | QosPolicyKind::Other => {
+                let src_prefix = self
+                    .policy_names
+                    .iter()
+                    .filter(|(_, policy_name)| policy_name.as_str() == name)
+                    .find_map(|(pid, _)| self.policy_scopes.get(pid))
+                    .map(|(_, address)| format!("{}/32", address));
+                self.create_policy_from_spec(&QosPolicySpec {
+                    name,
+                    app_name: Some(&known.app_name),
+                    default_match: false,
+                    throttle_bits_per_second: (known.throttle_bits > 0).then_some(known.throttle_bits),
+                    dscp: None,
+                    src_prefix: src_prefix.as_deref(),
+                    port_rule: None,
+                })?;
+            }
+        }
+        tracing::info!("🔁 Politique {} recréée ({})", name, known.app_name);
+        Ok(())
+    }
+
+    /// Throttle every process of `exe_name`, running or not, with a policy named after the
+    /// executable. Returns the policy name. Not available on Linux, where limits are per PID.
+    pub fn limit_executable(&mut self, exe_name: &str, limit_kbps: u32) -> Result<String> {
+        self.ensure_policies_restored();
+        if cfg!(target_os = "linux") {
+            return Err(anyhow::anyhow!("Limitation par exécutable indisponible sous Linux ({})", exe_name));
+        }
+        if wsl::is_vm_host(exe_name) {
+            return Err(anyhow::anyhow!("{} {}", exe_name, wsl::VM_HOST_LIMIT_MESSAGE));
+        }
+        self.ensure_qos_available()?;
+        let policy_name = format!("GameBooster_Limit_{}", exe_name);
+        let throttle_bits = (limit_kbps as u64) * 1024 * 8;
+        let request = (policy_name.clone(), exe_name.to_string(), throttle_bits);
+        if let Some(Err(message)) = self.create_qos_policies_bulk(&[request]).pop() {
+            return Err(anyhow::anyhow!(message));
+        }
+        self.remember_policy(&policy_name, exe_name, throttle_bits);
+        tracing::info!("🎯 {} limité à {} KB/s ({})", exe_name, limit_kbps, policy_name);
+        Ok(policy_name)
+    }
+
+    /// Delete a policy created by `limit_executable`
+    pub fn remove_policy(&mut self, policy_name: &str) -> Result<()> {
+        self.remove_policy_by_name(policy_name)?;
+        #[cfg(target_os = "windows")]
+        qos_wmi::remove_registry_policy(policy_name);
+        self.forget_policies(|name| name == policy_name);
+        tracing::info!("🗑️ Politique {} supprimée", policy_name);
+        Ok(())
+    }
+
+    /// Record a policy created by GameBooster so that the next session recognizes it
+    fn remember_policy(&mut self, name: &str, app_name: &str, throttle_bits: u64) {
+        self.remember(PersistedPolicy {
+            name: name.to_string(),
+            app_name: app_name.to_string(),
+            throttle_bits,
+            expires_at: None,
+            port_rule: None,
+        });
+    }
+
+    fn remember(&mut self, policy: PersistedPolicy) {
+        self.managed_policies.retain(|p| p.name != policy.name);
+        self.managed_policies.push(policy);
+        self.save_policy_store();
+    }
+
+    /// Drop the removed policies from the store and from the adopted limits
+    fn forget_policies(&mut self, removed: impl Fn(&str) -> bool) {
+        self.managed_policies.retain(|p| !removed(&p.name));
+        self.adopted_limits.retain(|p| !removed(&p.name));
+        self.limit_expiries.retain(|name, _| !removed(name));
+        self.recently_exited.retain(|exited| !removed(&exited.policy_name));
+        self.save_policy_store();
+    }
+
+    fn save_policy_store(&self) {
+        if let Err(e) = policy_store::save(&self.managed_policies) {
+            tracing::warn!("⚠️ Impossible d'enregistrer {}: {}", policy_store::path().display(), e);
+        }
+    }
+
+    /// Refresh the list of processes with network activity
+    pub fn scan_network_processes(&mut self) -> Result<()> {
+        self.ensure_policies_restored();
+        let started = Instant::now();
+        let span = tracing::info_span!("network_scan", processes = tracing::field::Empty, duration_ms = tracing::field::Empty);
+        let _entered = span.enter();
+        self.refresh_adapters();
+
+        // Les deux générations restent disponibles pendant le scan : les débits sont des écarts
+        // entre compteurs. Un scan échoué garde l'ancienne, signalée comme périmée par son âge.
+        let previous = std::mem::take(&mut self.processes);
+        let previous_update = self.last_update;
+        #[cfg(target_os = "linux")]
+        let result = self.scan_linux_processes();
+        #[cfg(not(target_os = "linux"))]
+        let result = self.scan_estimated_processes();
+        if let Err(e) = result {
+            self.processes = previous;
+            return Err(e);
+        }
+        self.track_exited_limits();
+
+        let now = Instant::now();
+        if REAL_BYTE_COUNTERS {
+            let elapsed = previous_update.map(|at| now.duration_since(at).as_secs_f64()).unwrap_or(0.0);
+            self.compute_speeds(&previous, elapsed);
+        }
+        self.last_update = Some(now);
+        self.session_totals.record(&self.processes);
+
+        let duration_ms = started.elapsed().as_millis() as u64;
+        span.record("processes", self.processes.len());
+        span.record("duration_ms", duration_ms);
+        log_every!(SUMMARY_INTERVAL, "✅ Scan réseau terminé : {} processus ({} ms)", self.processes.len(), duration_ms);
+        Ok(())
+    }
+
+    /// Data used today by each application, most first
+    pub fn get_session_totals(&self) -> Vec<SessionTraffic> {
+        self.session_totals.sorted()
+    }
+
+    pub fn reset_session_totals(&mut self) {
+        self.session_totals.reset();
+    }
+
+    /// Write the data usage now, called on exit
+    pub fn save_session_totals(&mut self) {
+        self.session_totals.save();
+    }
+
+    /// Release the limited PIDs missing from the system snapshot. When no running process
+    /// shares its policy, the limit moves to `recently_exited`: the policy still matches the
+    /// executable name and would throttle its next launch with nothing shown in the table.
+    /// The limit is adopted (see `attach_adopted_limit`) so that a relaunch is attached to it
+    /// again, and removed after the grace period with `ExitedLimitAction::Remove`.
+    fn track_exited_limits(&mut self) {
+        let exited: Vec<(u32, u32)> = match self.limited_processes.lock() {
+            Ok(limited) => limited
+                .iter()
+                .filter(|(pid, _)| self.system.process(sysinfo::Pid::from_u32(**pid)).is_none())
+                .map(|(pid, kbps)| (*pid, *kbps))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+
+        for (pid, kbps) in exited {
+            let policy_name = self.policy_name_for(pid);
+            // Sous Linux, la classe tc suit le PID : rien ne reste appliqué après sa fin
+            #[cfg(target_os = "linux")]
+            if let Some(shaper) = self.linux_shaper.as_mut() {
+                if let Err(e) = shaper.remove_pid(pid) {
+                    tracing::warn!("⚠️ Classe tc du PID {} terminé non supprimée: {}", pid, e);
+                }
+            }
+            if let Ok(mut limited) = self.limited_processes.lock() {
+                limited.remove(&pid);
+            }
+            self.policy_names.remove(&pid);
+            self.policy_scopes.remove(&pid);
+
+            if cfg!(target_os = "linux") || self.policy_names.values().any(|name| *name == policy_name) {
+                tracing::debug!("💤 PID {} limité terminé, libéré", pid);
+                continue;
+            }
+            let Some(policy) = self.managed_policies.iter().find(|p| p.name == policy_name).cloned() else {
+                continue;
+            };
+            tracing::info!("💤 {} (PID {}) terminé, sa limite {} KB/s reste en place", policy.app_name, pid, kbps);
+            if !self.recently_exited.iter().any(|exited| exited.policy_name == policy_name) {
+                self.recently_exited.push(ExitedLimit {
+                    exe: policy.app_name.clone(),
+                    kbps,
+                    exited_at: Local::now(),
+                    policy_name: policy_name.clone(),
+                    removal_error: None,
+                });
+            }
+            if !self.adopted_limits.iter().any(|p| p.name == policy_name) {
+                self.adopted_limits.push(policy);
+            }
+        }
+
+        if self.exited_limit_action != ExitedLimitAction::Remove {
+            return;
+        }
+        let grace = chrono::Duration::from_std(self.exited_limit_grace).unwrap_or_default();
+        let due: Vec<String> = self
+            .recently_exited
+            .iter()
+            .filter(|exited| exited.removal_error.is_none() && Local::now() - exited.exited_at >= grace)
+            .map(|exited| exited.policy_name.clone())
+            .collect();
+        for policy_name in due {
+            match self.remove_exited_limit(&policy_name) {
+                Ok(()) => tracing::info!("🗑️ Limite {} supprimée après la fin de son processus", policy_name),
+                Err(e) => tracing::warn!("⚠️ Suppression automatique de la limite {} impossible: {:#}", policy_name, e),
+            }
+        }
+    }
+
+    /// Limits of the processes that exited, see `track_exited_limits`
+    pub fn recently_exited(&self) -> &[ExitedLimit] {
+        &self.recently_exited
+    }
+
+    /// What happens to the limit of a process that exits, removal waiting `grace_secs`
+    pub fn set_exited_limit_handling(&mut self, action: ExitedLimitAction, grace_secs: u64) {
+        self.exited_limit_action = action;
+        self.exited_limit_grace = Duration::from_secs(grace_secs);
+    }
+
+    /// Delete the policy of an exited process ("Supprimer")
+    pub fn remove_exited_limit(&mut self, policy_name: &str) -> Result<()> {
+        if !self.recently_exited.iter().any(|exited| exited.policy_name == policy_name) {
+            return Err(anyhow::anyhow!("Limite {} absente des processus terminés", policy_name));
+        }
+        if let Err(e) = self.remove_policy(policy_name) {
+            if let Some(exited) = self.recently_exited.iter_mut().find(|exited| exited.policy_name == policy_name) {
+                exited.removal_error = Some(format!("{:#}", e));
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Leave the policy of an exited process in place and stop listing it ("Conserver"): it
+    /// stays tracked and is attached to the next process of the executable
+    pub fn keep_exited_limit(&mut self, policy_name: &str) {
+        self.recently_exited.retain(|exited| exited.policy_name != policy_name);
+        tracing::info!("📌 Limite {} conservée pour le prochain lancement", policy_name);
+    }
+
+    /// Speeds of each process from its byte counters at this scan and the previous one, 0 for a
+    /// new process or a PID reused by another executable
+    fn compute_speeds(&mut self, previous: &HashMap<u32, NetworkProcessInfo>, elapsed_secs: f64) {
+        for (pid, process) in self.processes.iter_mut() {
+            let (upload, download) = match previous.get(pid) {
+                Some(before) if elapsed_secs > 0.0 && before.name == process.name => (
+                    (process.bytes_sent.saturating_sub(before.bytes_sent) as f64 / elapsed_secs) as u64,
+                    (process.bytes_received.saturating_sub(before.bytes_received) as f64 / elapsed_secs) as u64,
+                ),
+                _ => (0, 0),
+            };
+            process.current_upload_speed = upload;
+            process.current_download_speed = download;
+        }
+    }
+
+    /// Age of the scanned data, `None` before the first scan
+    pub fn seconds_since_last_scan(&self) -> Option<u64> {
+        self.last_update.map(|at| at.elapsed().as_secs())
+    }
+
+    /// Warn when `pid` comes from a scan old enough for it to have exited since
+    fn warn_if_stale(&self, pid: u32) {
+        if !self.processes.contains_key(&pid) {
+            return;
+        }
+        if let Some(age) = self.seconds_since_last_scan().filter(|age| *age > STALE_PID_SECS) {
+            tracing::warn!("⚠️ PID {} vu pour la dernière fois il y a {} s : il s'est peut-être terminé", pid, age);
+        }
+    }
+
+    /// Open TCP/UDP sockets of `pid`, read at call time
+    pub fn get_process_connections(&self, pid: u32) -> Vec<ConnectionInfo> {
+        connections::list(pid).unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Connexions du PID {} illisibles: {}", pid, e);
+            Vec::new()
+        })
+    }
+
+    /// Adapters seen at the last scan
+    pub fn adapters(&self) -> &[AdapterInfo] {
+        &self.adapters
+    }
+
+    /// Adapter carrying the default route at the last scan
+    pub fn active_adapter(&self) -> Option<&AdapterInfo> {
+        let name = self.active_adapter.as_deref()?;
+        self.adapters.iter().find(|adapter| adapter.name == name)
+    }
+
+    /// Adapter the policy of `pid` is scoped to, `None` for a policy matching every adapter
+    pub fn policy_scope(&self, pid: u32) -> Option<&str> {
+        self.policy_scopes.get(&pid).map(|(adapter, _)| adapter.as_str())
+    }
+
+    /// Limited PIDs whose policy is scoped to an adapter that is down, gone or has lost the
+    /// scoped address: their traffic now leaves through another adapter, unthrottled
+    pub fn stale_scoped_limits(&self) -> HashSet<u32> {
+        self.policy_scopes
+            .iter()
+            .filter(|(_, (name, address))| {
+                !self
+                    .adapters
+                    .iter()
+                    .any(|adapter| adapter.name == *name && adapter.is_up && adapter.ipv4.contains(address))
+            })
+            .map(|(pid, _)| *pid)
+            .collect()
+    }
+
+    /// Re-read the adapters and log a change of the active one
+    fn refresh_adapters(&mut self) {
+        match adapters::list_adapters() {
+            Ok(list) => self.adapters = list,
+            Err(e) => {
+                tracing::debug!("Liste des cartes réseau indisponible: {}", e);
+                return;
+            }
+        }
+
+        let active = adapters::active_adapter(&self.adapters).map(|adapter| adapter.name.clone());
+        if active == self.active_adapter {
+            return;
+        }
+        match (&self.active_adapter, &active) {
+            (Some(previous), Some(current)) => tracing::info!("🔌 Carte réseau active: {} → {}", previous, current),
+            (None, Some(current)) => tracing::info!("🔌 Carte réseau active: {}", current),
+            (Some(previous), None) => tracing::warn!("⚠️ Plus de carte réseau active (précédente: {})", previous),
+            (None, None) => {}
+        }
+        self.active_adapter = active;
+
+        let stale = self.stale_scoped_limits();
+        if !stale.is_empty() {
+            tracing::warn!("⚠️ Limites liées à une carte déconnectée, à réappliquer: PID {:?}", stale);
+        }
+    }
+
+    /// Source prefix matching the traffic of `adapter_name`, for `QosPolicySpec::src_prefix`
+    #[cfg_attr(target_os = "linux", allow(dead_code))]
+    fn adapter_scope(&self, adapter_name: &str) -> Result<(Ipv4Addr, String)> {
+        let adapter = self
+            .adapters
+            .iter()
+            .find(|adapter| adapter.name == adapter_name)
+            .ok_or_else(|| anyhow::anyhow!("Carte réseau {} introuvable", adapter_name))?;
+        let address = *adapter
+            .ipv4
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Carte réseau {} sans adresse IPv4", adapter_name))?;
+        Ok((address, format!("{}/32", address)))
+    }
+
+    /// Linux: sockets and counters read from /proc (see `linux_monitor`), no root needed
+    #[cfg(target_os = "linux")]
+    fn scan_linux_processes(&mut self) -> Result<()> {
+        self.system.refresh_all();
+
+        for (pid, activity) in self.linux_monitor.sample()? {
+            let Some(process) = self.system.process(sysinfo::Pid::from_u32(pid)) else {
+                continue;
+            };
+
+            let exe_path = process.exe().map(Path::to_path_buf).or_else(|| query_full_image_path(pid));
+            let process_info = NetworkProcessInfo {
+                pid,
+                name: process.name().to_string(),
+                bytes_sent: activity.bytes_sent,
+                bytes_received: activity.bytes_received,
+                packets_sent: 0, // non disponible sans root
+                packets_received: 0,
+                is_limited: self.is_process_limited(pid),
+                speed_limit: self.get_process_limit(pid),
+                connections: activity.connections,
+                connections_by_state: HashMap::new(),
+                // Calculés par `compute_speeds` à partir du scan précédent
+                current_upload_speed: 0,
+                current_download_speed: 0,
+                is_blocked: false,
+                protection: safety::is_protected_process(pid, process.name(), exe_path.as_deref()),
+                exe_path,
+                parent_pid: process.parent().map(|parent| parent.as_u32()),
+                is_uwp: false,
+                package_family_name: None,
+                is_vm_host: false,
+                has_active_sockets: activity.connections > 0,
+            };
+            self.processes.insert(pid, process_info);
+        }
+
+        // Un processus limité reste listé sans socket ouverte : sa limite doit rester visible
+        let limited: Vec<(u32, u32)> = self
+            .limited_processes
+            .lock()
+            .map(|limited| limited.iter().map(|(&pid, &limit)| (pid, limit)).collect())
+            .unwrap_or_default();
+        for (pid, limit_kbps) in limited {
+            if self.processes.contains_key(&pid) {
+                continue;
+            }
+            let Some(process) = self.system.process(sysinfo::Pid::from_u32(pid)) else {
+                continue;
+            };
+            let exe_path = process.exe().map(Path::to_path_buf).or_else(|| query_full_image_path(pid));
+            let process_info = NetworkProcessInfo {
+                pid,
+                name: process.name().to_string(),
+                bytes_sent: 0,
+                bytes_received: 0,
+                packets_sent: 0,
+                packets_received: 0,
+                is_limited: true,
+                speed_limit: Some(limit_kbps),
+                connections: 0,
+                connections_by_state: HashMap::new(),
+                current_upload_speed: 0,
+                current_download_speed: 0,
+                is_blocked: false,
+                protection: safety::is_protected_process(pid, process.name(), exe_path.as_deref()),
+                exe_path,
+                parent_pid: process.parent().map(|parent| parent.as_u32()),
+                is_uwp: false,
+                package_family_name: None,
+                is_vm_host: false,
+                has_active_sockets: false,
+            };
+            self.processes.insert(pid, process_info);
+        }
+
+        tracing::debug!("🐧 Scan réseau /proc: {} processus avec connexions", self.processes.len());
+        Ok(())
+    }
+
+    /// Scan ALL processes using REAL system data from sysinfo
+    #[cfg(not(target_os = "linux"))]
+    fn scan_estimated_processes(&mut self) -> Result<()> {
+        // Refresh system data
+        self.system.refresh_all();
+
+        match firewall::list_block_rules() {
+            Ok(rules) => self.blocked_rules = rules,
+            Err(e) => tracing::warn!("⚠️ Lecture des règles de blocage impossible: {}", e),
+        }
+
+        if let Err(e) = self.stats_source.refresh() {
+            tracing::warn!("⚠️ Source {} indisponible, retour à l'estimation: {:#}", self.stats_source.label(), e);
+            self.stats_source = Box::new(stats_source::EstimatedStats);
+        }
+
+        // Relue à chaque scan : un PID terminé entre deux scans ne garde pas d'anciens compteurs
+        let mut sockets = connections::count_by_pid(&connections::SystemSocketTable).unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Lecture de la table des sockets TCP impossible: {}", e);
+            HashMap::new()
+        });
+        // Les jeux gardent souvent une socket UDP ouverte sans trafic (lobby, voix)
+        let mut udp_sockets = connections::udp_count_by_pid(&connections::SystemSocketTable).unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Lecture de la table des sockets UDP impossible: {}", e);
+            HashMap::new()
+        });
+        
+        // PID rattachés à une politique existante, après la boucle qui emprunte `self.system`
+        let mut adopted: Vec<(u32, PathBuf)> = Vec::new();
+
+        // Get processes with their real network activity
+        for (pid, process) in self.system.processes() {
+            let pid_u32 = pid.as_u32();
+            
+            // Skip system processes
+            if pid_u32 <= 4 { continue; }
+            
+            let name = process.name().to_string();
+            
+            let traffic = self.stats_source.traffic(pid_u32, process);
+            
+            let exe_path = process
+                .exe()
+                .map(Path::to_path_buf)
+                .or_else(|| query_full_image_path(pid_u32));
+            let is_blocked = self.blocked_rules.contains(&firewall::block_rule_name(&name));
+
+            let adopted_limit = match exe_path.as_deref() {
+                Some(exe_path) if !self.is_process_limited(pid_u32) => self.adopted_policy_for(exe_path).map(|policy| {
+                    adopted.push((pid_u32, exe_path.to_path_buf()));
+                    (policy.throttle_bits / 8 / 1024) as u32
+                }),
+                _ => None,
+            };
+            let is_limited = adopted_limit.is_some() || self.is_process_limited(pid_u32);
+
+            let connections_by_state = sockets.remove(&pid_u32).unwrap_or_default();
+            let udp = udp_sockets.remove(&pid_u32).unwrap_or(0);
+            let has_active_sockets = udp > 0 || connections_by_state.values().any(|&count| count > 0);
+
+            // Une socket ouverte suffit : la ligne apparaît à 0 B/s, masquée par le filtre des inactifs
+            if traffic.bytes_sent > 0
+                || traffic.bytes_received > 0
+                || has_active_sockets
+                || is_limited
+                || is_blocked
+            {
+                let connections = connections_by_state.values().sum();
+                let package_family_name = appx::package_family_name(pid_u32);
+                let is_uwp = package_family_name.is_some()
+                    || exe_path.as_deref().is_some_and(appx::is_windows_apps_path);
+                let protection = safety::is_protected_process(pid_u32, &name, exe_path.as_deref());
+                
+                let process_info = NetworkProcessInfo {
+                    pid: pid_u32,
+                    name: name.clone(),
+                    bytes_sent: traffic.bytes_sent,
+                    bytes_received: traffic.bytes_received,
+                    packets_sent: traffic.bytes_sent / 1024, // Rough estimate
+                    packets_received: traffic.bytes_received / 1024,
+                    is_limited,
+                    speed_limit: adopted_limit.or_else(|| self.get_process_limit(pid_u32)),
+                    connections,
+                    connections_by_state,
+                    current_upload_speed: traffic.upload_speed,
+                    current_download_speed: traffic.download_speed,
+                    is_blocked,
+                    exe_path,
+                    parent_pid: process.parent().map(|parent| parent.as_u32()),
+                    is_uwp,
+                    package_family_name,
+                    protection,
+                    is_vm_host: wsl::is_vm_host(&name),
+                    has_active_sockets,
+                };
+                
+                self.processes.insert(pid_u32, process_info);
+            }
+        }
+
+        for (pid, exe_path) in adopted {
+            self.attach_adopted_limit(pid, &exe_path);
+        }
+        Ok(())
+    }
+
+    /// Measure the traffic of each process with kernel network events (ETW, administrator
+    /// only) instead of estimates; falls back to estimates when the session cannot start or
+    /// stops later
+    #[cfg(not(target_os = "linux"))]
+    pub fn set_high_precision(&mut self, enabled: bool) {
+        let label = self.stats_source.label();
+        // Session ETW arrêtée avant d'en recréer une du même nom
+        self.stats_source = Box::new(stats_source::EstimatedStats);
+        self.stats_source = stats_source::select(enabled);
+        if self.stats_source.label() != label {
+            tracing::info!("📊 Source des débits réseau : {} → {}", label, self.stats_source.label());
+        }
+    }
+
+    /// Linux counters come from /proc, see `linux_monitor`
+    #[cfg(target_os = "linux")]
+    pub fn set_high_precision(&mut self, _enabled: bool) {}
+
+    /// Where the speeds shown come from
+    #[cfg(not(target_os = "linux"))]
+    pub fn stats_source_label(&self) -> &'static str {
+        self.stats_source.label()
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn stats_source_label(&self) -> &'static str {
+        "/proc"
+    }
+
+    /// The speeds and byte counts shown are estimates, not measurements (Windows without
+    /// the ETW source)
+    #[cfg(not(target_os = "linux"))]
+    pub fn speeds_estimated(&self) -> bool {
+        self.stats_source.is_estimate()
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn speeds_estimated(&self) -> bool {
+        false
+    }
+
+    /// Select the backend used for QoS policy operations
+    pub fn set_qos_backend(&mut self, backend: QosBackend) {
+        tracing::info!("🔧 Backend QoS: {:?} → {:?}", self.qos_backend, backend);
+        self.qos_backend = backend;
+    }
+
+    /// Backend currently used for QoS policy operations
+    pub fn qos_backend(&self) -> QosBackend {
+        self.qos_backend
+    }
+
+    /// Executable name of `pid` for the span fields, empty when it was not scanned
+    fn process_label(&self, pid: u32) -> String {
+        self.processes.get(&pid).map(|process| process.name.clone()).unwrap_or_default()
+    }
+
+    /// Name of the QoS policy that limits a PID
+    pub fn policy_name_for(&self, pid: u32) -> String {
+        self.policy_names
+            .get(&pid)
+            .cloned()
+            .unwrap_or_else(|| format!("GameBooster_Limit_{}", pid))
+    }
+
+    /// Executable used as AppPathNameMatchCondition for a PID: the file name, or the
+    /// full path when another scanned install shares the same file name. Store applications
+    /// are always matched on their full path.
+    pub fn qos_app_name(&self, pid: u32) -> Result<String> {
+        let process = self.processes.get(&pid).ok_or_else(|| {
+            anyhow::anyhow!("Processus PID {} absent du scan réseau, relancez un scan", pid)
+        })?;
+        if process.is_vm_host {
+            return Err(anyhow::anyhow!("{} {}", process.name, wsl::VM_HOST_LIMIT_MESSAGE));
+        }
+        let Some(exe_path) = process.exe_path.as_ref() else {
+            if process.is_uwp {
+                return Err(anyhow::anyhow!(
+                    "{} (PID {}, {}) : {}",
+                    process.name,
+                    pid,
+                    process.package_family_name.as_deref().unwrap_or("package inconnu"),
+                    appx::UNSUPPORTED_MESSAGE
+                ));
+            }
+            return Err(anyhow::anyhow!(
+                "Chemin de l'exécutable introuvable pour {} (PID {}) : accès refusé ou processus terminé",
+                process.name,
+                pid
+            ));
+        };
+        if process.is_uwp {
+            let app_name = exe_path.to_string_lossy().to_string();
+            tracing::info!(
+                "🛍️ Application Store {} ({}) → {}",
+                process.name,
+                process.package_family_name.as_deref().unwrap_or("package inconnu"),
+                app_name
+            );
+            return Ok(app_name);
+        }
+        let file_name = exe_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| anyhow::anyhow!("Chemin d'exécutable invalide: {}", exe_path.display()))?;
+
+        // Deux installations différentes du même exe : seul le chemin complet les distingue
+        let ambiguous = self.processes.values().any(|other| {
+            other.exe_path.as_ref().is_some_and(|other_path| {
+                other_path != exe_path
+                    && other_path
+                        .file_name()
+                        .is_some_and(|name| name.to_string_lossy().eq_ignore_ascii_case(&file_name))
+            })
+        });
+
+        let app_name = if ambiguous {
+            exe_path.to_string_lossy().to_string()
+        } else {
+            file_name
+        };
+        tracing::info!("📂 Exécutable résolu: {} → {}", process.name, app_name);
+        Ok(app_name)
+    }
+
+    /// Apply QoS limitation using Windows Group Policy (consistent approach).
+    /// With `src_prefix`, only the traffic leaving from that address (one adapter) is throttled.
+    #[cfg_attr(target_os = "linux", allow(dead_code))]
+    fn apply_netsh_qos_limit(&self, pid: u32, limit_kbps: u32, src_prefix: Option<&str>) -> Result<()> {
+        tracing::debug!("🔧 Début limitation bande passante QoS GROUP POLICY pour PID {}", pid);
+
+        // Si la limite est 0, il faut supprimer la politique, pas en créer une nouvelle
+        if limit_kbps == 0 {
+            tracing::debug!("🚫 Limite de 0 KB/s détectée. Suppression de la politique pour le PID {}", pid);
+            return self.remove_netsh_qos_limit(pid);
+        }
+        
+        // Get process name for filtering
+        let process_name = self.qos_app_name(pid)?;
+
+        let policy_name = self.policy_name_for(pid);
+        let throttle_bits_per_second = (limit_kbps as u64) * 1024 * 8; // Convert KB/s to bits/s
+        
+        tracing::debug!("🔢 Limitation QoS: {} KB/s → {} bits/s pour {}", 
+            limit_kbps, throttle_bits_per_second, process_name);
+        tracing::debug!("🎯 Politique: {} | Processus: {} | PID: {}", policy_name, process_name, pid);
+        if let Some(prefix) = src_prefix {
+            tracing::debug!("🔌 Politique limitée au trafic émis depuis {}", prefix);
+        }
+
+        #[cfg(target_os = "windows")]
+        if self.qos_backend == QosBackend::Wmi {
+            let started = Instant::now();
+            let spec = QosPolicySpec {
+                name: &policy_name,
+                app_name: Some(&process_name),
+                default_match: false,
+                throttle_bits_per_second: Some(throttle_bits_per_second),
+                dscp: None,
+                src_prefix,
+                port_rule: None,
+            };
+            match qos_wmi::create_policy_from_spec(&spec) {
+                Ok(()) => {
+                    tracing::debug!("✅ Politique QoS créée via WMI: {} (⏱️ {} ms)", policy_name, started.elapsed().as_millis());
+                    return Ok(());
+                }
+                Err(e) => {
+                    tracing::warn!("⚠️ Création QoS WMI échouée après {} ms, fallback PowerShell: {}", started.elapsed().as_millis(), e);
+                }
+            }
+        }
+
+        self.apply_qos_limit_powershell(pid, limit_kbps, &policy_name, &process_name, throttle_bits_per_second, src_prefix)
+    }
+
+    /// Create the QoS policy through New-NetQosPolicy (legacy/fallback path)
+    fn apply_qos_limit_powershell(
+        &self,
+        pid: u32,
+        limit_kbps: u32,
+        policy_name: &str,
+        process_name: &str,
+        throttle_bits_per_second: u64,
+        src_prefix: Option<&str>,
+    ) -> Result<()> {
+        let started = Instant::now();
+        let src_condition = src_prefix
+            .map(|prefix| format!(" -IPSrcPrefixMatchCondition '{}'", prefix.replace('\'', "''")))
+            .unwrap_or_default();
+
+        // Méthode PowerShell avec sortie JSON pour une fiabilité maximale
+        let powershell_script = format!(
+            r#"
+$ErrorActionPreference = "Stop"
+$OutputEncoding = [System.Text.Encoding]::UTF8
+[System.Threading.Thread]::CurrentThread.CurrentCulture = 'en-US'
+
+$policyName = "{0}"
+$processName = "{1}"
+$throttleBits = [long]{2}
+
+$result = @{{
+    Success = $false
+    PolicyName = $policyName
+    AppName = $processName
+    ThrottleBits = $throttleBits
+    Message = ""
+}}
+
+try {{
+    Remove-NetQosPolicy -Name $policyName -Confirm:$false -ErrorAction SilentlyContinue
+
+    $policy = New-NetQosPolicy -Name $policyName -AppPathNameMatchCondition $processName -ThrottleRateActionBitsPerSecond $throttleBits{3} -Confirm:$false
+
+    $verification = Get-NetQosPolicy -Name $policyName
+    if ($verification -and $verification.ThrottleRateActionBitsPerSecond -eq $throttleBits) {{
+        $result.Success = $true
+        $result.Message = "Policy created and verified successfully."
+    }} else {{
+        $result.Message = "Policy created but verification failed. Expected {2} bits, got $($verification.ThrottleRateActionBitsPerSecond)."
+    }}
+}} catch {{
+    $result.Message = "PowerShell Error: $($_.Exception.Message)"
+}}
+
+$result | ConvertTo-Json -Compress
+            "#,
+            policy_name,
+            process_name,
+            throttle_bits_per_second,
+            src_condition
+        );
+
+        tracing::debug!("🔧 Lancement script QoS avec sortie JSON");
+        
+        let output = self.runner.powershell(&powershell_script);
+
+        match output {
+            Ok(result) => {
+                let stdout = result.stdout;
+                let stderr = result.stderr;
+
+                if !stderr.is_empty() {
+                    tracing::warn!("⚠️ Avertissements (stderr) QoS: {}", stderr.trim());
+                }
+
+                #[derive(Deserialize)]
+                struct JsonResult {
+                    Success: bool,
+                    Message: String,
+                }
+
+                if let Ok(json_result) = serde_json::from_str::<JsonResult>(stdout.trim()) {
+                    if json_result.Success {
+                        tracing::debug!("✅ Politique QoS GROUP POLICY créée avec succès: {} (⏱️ PowerShell {} ms)", policy_name, started.elapsed().as_millis());
+                        Ok(())
+                    } else {
+                        let error_msg = format!("Échec création politique QoS (JSON): {}", json_result.Message);
+                        tracing::error!("❌ {}", error_msg);
+                        Err(anyhow::anyhow!(error_msg))
+                    }
+                } else {
+                    let error_msg = format!("Réponse JSON invalide du script QoS: {}. Stderr: {}", stdout.trim(), stderr.trim());
+                    tracing::error!("❌ {}", error_msg);
+                    Err(anyhow::anyhow!(error_msg))
+                }
+            }
+            Err(e) => {
+                let error_msg = format!("Impossible d'exécuter PowerShell QoS: {}", e);
+                tracing::error!("❌ {}", error_msg);
+                Err(anyhow::anyhow!(error_msg))
+            }
+        }
+    }
+
+    /// Remove limitation from a process
+    pub fn remove_process_limit(&mut self, pid: u32) -> Result<()> {
+        let span = tracing::info_span!("qos_remove", pid, exe = %self.process_label(pid));
+        let _entered = span.enter();
+        // Une politique partagée (même exécutable) libère tous les PID qui l'utilisent
+        let policy_name = self.policy_name_for(pid);
+        let mut released_pids: Vec<u32> = self
+            .policy_names
+            .iter()
+            .filter(|(_, name)| **name == policy_name)
+            .map(|(other_pid, _)| *other_pid)
+            .collect();
+        if !released_pids.contains(&pid) {
+            released_pids.push(pid);
+        }
+
+        // Remove QoS policy (tc class on Linux)
+        #[cfg(target_os = "linux")]
+        if let Some(shaper) = self.linux_shaper.as_mut() {
+            shaper.remove_pid(pid)?;
+        }
+        #[cfg(not(target_os = "linux"))]
+        self.remove_netsh_qos_limit(pid)?;
+
+        for released in released_pids {
+            // Remove from limited processes list
+            if let Ok(mut limited) = self.limited_processes.lock() {
+                limited.remove(&released);
+            }
+
+            // Update process info
+            if let Some(process) = self.processes.get_mut(&released) {
+                process.is_limited = false;
+                process.speed_limit = None;
+            }
+
+            self.policy_names.remove(&released);
+            self.policy_scopes.remove(&released);
+        }
+
+        let rt_policy_name = format!("GameBooster_RT_Limit_{}", pid);
+        self.forget_policies(|name| name == policy_name || name == rt_policy_name);
+        
+        tracing::info!("✅ Limitation supprimée: PID {}", pid);
+        Ok(())
+    }
+
+    /// Forget a process GameBooster terminated. Its limit is removed when no other running
+    /// process of the same executable shares it; otherwise only its PID is dropped.
+    pub fn release_terminated_process(&mut self, pid: u32) -> Result<()> {
+        let shared = self
+            .policy_names
+            .get(&pid)
+            .is_some_and(|name| self.policy_names.iter().any(|(other, other_name)| *other != pid && other_name == name));
+        if self.is_process_limited(pid) && !shared {
+            self.remove_process_limit(pid)?;
+        } else {
+            #[cfg(target_os = "linux")]
+            if let Some(shaper) = self.linux_shaper.as_mut() {
+                shaper.remove_pid(pid)?;
+            }
+            if let Ok(mut limited) = self.limited_processes.lock() {
+                limited.remove(&pid);
+            }
+            self.policy_names.remove(&pid);
+            self.policy_scopes.remove(&pid);
+        }
+        self.processes.remove(&pid);
+        Ok(())
+    }
+
+    /// Remove QoS limitation using Windows Group Policy (consistent with creation)
+    #[cfg_attr(target_os = "linux", allow(dead_code))]
+    fn remove_netsh_qos_limit(&self, pid: u32) -> Result<()> {
+        let policy_name = self.policy_name_for(pid);
+        let rt_policy_name = format!("GameBooster_RT_Limit_{}", pid);
+        
+        tracing::debug!("🔧 Suppression politique QoS GROUP POLICY: {}", policy_name);
+
+        #[cfg(target_os = "windows")]
+        if self.qos_backend == QosBackend::Wmi {
+            let started = Instant::now();
+            match qos_wmi::remove_policies(&[policy_name.as_str(), rt_policy_name.as_str()]) {
+                Ok(removed) => {
+                    tracing::debug!("✅ {} politique(s) QoS supprimée(s) via WMI pour PID {} (⏱️ {} ms)", removed, pid, started.elapsed().as_millis());
+                    return Ok(());
+                }
+                Err(e) => {
+                    tracing::warn!("⚠️ Suppression QoS WMI échouée après {} ms, fallback PowerShell: {}", started.elapsed().as_millis(), e);
+                }
+            }
+        }
+
+        let started = Instant::now();
+        
+        // Use PowerShell to remove Group Policy QoS rule
+        let powershell_script = format!(
+            r#"
+            $OutputEncoding = [System.Text.Encoding]::UTF8
+            try {{
+                Remove-NetQosPolicy -Name "{0}" -Confirm:$false -ErrorAction SilentlyContinue
+                Remove-NetQosPolicy -Name "{1}" -Confirm:$false -ErrorAction SilentlyContinue
+                Write-Output "SUCCESS: Policy removed"
+            }} catch {{
+                # Ignorer l'erreur si la politique n'existe pas
+                if ($_.Exception.Message -like "*No matching MSFT_NetQosPolicy*") {{
+                    Write-Output "INFO: Policy did not exist"
+                }} else {{
+                    Write-Error "ERROR: $($_.Exception.Message)"
+                    exit 1
+                }}
+            }}
+            "#,
+            policy_name,
+            rt_policy_name
+        );
+        
+        tracing::debug!("🔧 Script suppression GROUP POLICY QoS");
+        
+        let output = self.runner.powershell(&powershell_script);
+        
+        match output {
+            Ok(result) => {
+                let stdout = &result.stdout;
+                let stderr = &result.stderr;
+                
+                tracing::debug!("📤 Sortie suppression GROUP POLICY: {}", stdout.trim());
+                if !stderr.is_empty() {
+                    tracing::warn!("⚠️ Erreur suppression GROUP POLICY: {}", stderr.trim());
+                }
+                
+                if result.success() || stdout.contains("SUCCESS") || stdout.contains("INFO:") {
+                    tracing::debug!("✅ Politique QoS GROUP POLICY supprimée: {} (⏱️ PowerShell {} ms)", policy_name, started.elapsed().as_millis());
+                    Ok(())
+                } else {
+                    let error_msg = format!("Échec suppression politique GROUP POLICY: {}", stderr.trim());
+                    tracing::warn!("⚠️ {}", error_msg);
+                    // Ne pas retourner d'erreur, juste un warning
+                    Ok(())
+                }
+            }
+            Err(e) => {
+                let error_msg = format!("Impossible d'exécuter suppression PowerShell GROUP POLICY: {}", e);
+                tracing::warn!("⚠️ {}", error_msg);
+                // Ne pas retourner d'erreur, juste un warning
+                Ok(())
+            }
+        }
+    }
+
+    /// Clear all QoS limitations using Windows Group Policy
+    #[cfg_attr(target_os = "linux", allow(dead_code))]
+    fn clear_all_qos_policies(&self) -> Result<()> {
+        tracing::info!("🧹 Suppression globale des politiques QoS GROUP POLICY GameBooster");
+
+        #[cfg(target_os = "windows")]
+        if self.qos_backend == QosBackend::Wmi {
+            let started = Instant::now();
+            match qos_wmi::remove_policies_with_prefix("GameBooster_") {
+                Ok(provider_removed) => {
+                    let registry_removed = qos_wmi::remove_registry_policies("GameBooster_");
+                    tracing::info!("✅ Suppression globale terminée via WMI. Fournisseur: {}, Registre: {} (⏱️ {} ms)",
+                        provider_removed, registry_removed, started.elapsed().as_millis());
+                    return Ok(());
+                }
+                Err(e) => {
+                    tracing::warn!("⚠️ Suppression globale WMI échouée après {} ms, fallback PowerShell: {}", started.elapsed().as_millis(), e);
+                }
+            }
+        }
+
+        let started = Instant::now();
+        
+        // Use PowerShell to remove all GameBooster QoS policies from provider and registry
+        let powershell_script = 
+            r#"
+            $OutputEncoding = [System.Text.Encoding]::UTF8
+            $ErrorActionPreference = "SilentlyContinue"
+            
+            # Supprimer via le provider Get-NetQosPolicy
+            $policies = Get-NetQosPolicy | Where-Object { $_.Name -like 'GameBooster_*' }
+            $providerCount = 0
+            if ($policies) {
+                $providerCount = ($policies | Measure-Object).Count
+                $policies | Remove-NetQosPolicy -Confirm:$false
+            }
+            
+            # Supprimer les politiques orphelines du registre
+            $regPath = "HKLM:\SOFTWARE\Policies\Microsoft\Windows\QoS"
+            $registryCount = 0
+            if (Test-Path $regPath) {
+                $regPolicies = Get-ChildItem -Path $regPath | Where-Object { $_.PSChildName -like 'GameBooster_*' }
+                if ($regPolicies) {
+                    $registryCount = ($regPolicies | Measure-Object).Count
+                    $regPolicies | Remove-Item -Recurse -Force
+                }
+            }
+
+            $result = @{
+                ProviderRemoved = $providerCount
+                RegistryRemoved = $registryCount
+                Message = "Cleanup finished."
+            }
+            $result | ConvertTo-Json -Compress
+            "#;
+        
+        tracing::info!("🔧 Script suppression globale avec sortie JSON");
+        
+        let output = self.runner.powershell(powershell_script);
+        
+        match output {
+            Ok(result) => {
+                let stdout = result.stdout;
+                let stderr = result.stderr;
+                
+                if !stderr.is_empty() {
+                    tracing::warn!("⚠️ Erreur (stderr) suppression globale: {}", stderr.trim());
+                }
+
+                #[derive(Deserialize, Debug)]
+                struct CleanupResult {
+                    ProviderRemoved: usize,
+                    RegistryRemoved: usize,
+                }
+
+                if let Ok(json_result) = serde_json::from_str::<CleanupResult>(stdout.trim()) {
+                    tracing::info!("✅ Suppression globale terminée. Fournisseur: {}, Registre: {} (⏱️ PowerShell {} ms)", 
+                        json_result.ProviderRemoved, json_result.RegistryRemoved, started.elapsed().as_millis());
+                } else {
+                    tracing::warn!("⚠️ Réponse JSON invalide du script de nettoyage: {}. Stderr: {}", stdout.trim(), stderr.trim());
+                }
+                
+                Ok(())
+            }
+            Err(e) => {
+                let error_msg = format!("Impossible d'exécuter suppression globale PowerShell: {}", e);
+                tracing::warn!("⚠️ {}", error_msg);
+                Ok(())
+            }
+        }
+    }
+
+    /// Clear all QoS limitations (public interface)
+    pub fn clear_all_limits(&mut self) -> Result<()> {
+        self.ensure_policies_restored();
+        // Clear internal tracking first
+        let pids_to_clear: Vec<u32> = if let Ok(limited) = self.limited_processes.lock() {
+            limited.keys().copied().collect()
+        } else {
+            Vec::new()
+        };
+        
+        for pid in pids_to_clear {
+            let _ = self.remove_process_limit(pid);
+        }
+        
+        if let Ok(mut limited) = self.limited_processes.lock() {
+            limited.clear();
+        }
+        self.policy_names.clear();
+        self.policy_scopes.clear();
+        self.global_limit = None;
+        
+        // Then clear all QoS policies
+        #[cfg(target_os = "linux")]
+        {
+            match self.linux_shaper.as_mut() {
+                Some(shaper) => shaper.clear_all().map_err(anyhow::Error::from),
+                None => Ok(()),
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            // Toutes les politiques GameBooster_*, orphelines et règles par port comprises
+            self.clear_all_qos_policies()?;
+            self.forget_policies(|_| true);
+            self.orphaned_policies.clear();
+            Ok(())
+        }
+    }
+
+    /// Linux shaping backend, created (and its prerequisites checked) on first use
+    #[cfg(target_os = "linux")]
+    fn linux_shaper_mut(&mut self) -> Result<&mut linux_shaping::LinuxShaper> {
+        let shaper = match self.linux_shaper.take() {
+            Some(shaper) => shaper,
+            None => linux_shaping::LinuxShaper::new()?,
+        };
+        Ok(self.linux_shaper.insert(shaper))
+    }
+
+    /// Last prerequisite check, `None` until the first limit or background check
+    pub fn qos_requirements(&self) -> Option<&QosRequirements> {
+        self.qos_requirements.as_ref()
+    }
+
+    /// Cache the result of a check made on a worker thread
+    pub fn set_qos_requirements(&mut self, requirements: QosRequirements) {
+        self.qos_requirements = Some(requirements);
+    }
+
+    /// Refuse to apply a limit when the prerequisites are missing, checking them first when
+    /// never checked or when the cached check is stale
+    fn ensure_qos_available(&mut self) -> std::result::Result<(), QosUnavailable> {
+        let requirements = match self.qos_requirements.take() {
+            Some(requirements) if !requirements.is_stale() => requirements,
+            _ => QosRequirements::check(),
+        };
+        let unavailable = requirements.unavailable();
+        self.qos_requirements = Some(requirements);
+        match unavailable {
+            Some(reason) => Err(reason),
+            None => Ok(()),
+        }
+    }
+
+    /// Protection level of a scanned process, `Normal` for an unknown PID
+    pub fn process_protection(&self, pid: u32) -> ProtectionLevel {
+        self.processes.get(&pid).map(|process| process.protection).unwrap_or_default()
+    }
+
+    /// Refuse `action` on a critical process (see `safety`)
+    fn ensure_not_critical(&self, pid: u32, action: &str) -> Result<()> {
+        match self.processes.get(&pid) {
+            Some(process) => safety::ensure_not_critical(pid, &process.name, process.exe_path.as_deref(), action)?,
+            None => safety::ensure_not_critical(pid, &format!("PID {}", pid), None, action)?,
+        };
+        Ok(())
+    }
+
+    /// Block all inbound and outbound traffic of an executable with firewall rules
+    pub fn block_process_network(&mut self, exe_path: &str) -> Result<()> {
+        // Les règles visent l'exécutable : chacun de ses processus doit pouvoir être bloqué
+        let pids: Vec<u32> = self
+            .processes
+            .values()
+            .filter(|process| process.exe_path.as_deref().is_some_and(|path| path == Path::new(exe_path)))
+            .map(|process| process.pid)
+            .collect();
+        for pid in pids {
+            self.ensure_not_critical(pid, "bloquer")?;
+        }
+
+        let rule_name = firewall::block_rule_name(exe_path);
+        tracing::info!("⛔ Blocage réseau de {} ({})", exe_path, rule_name);
+
+        firewall::add_block_rules(exe_path)?;
+        self.blocked_rules.insert(rule_name.clone());
+        self.set_blocked_flag(&rule_name, true);
+
+        tracing::info!("✅ Règles pare-feu créées: {}", rule_name);
+        Ok(())
+    }
+
+    /// Remove both firewall rules created by `block_process_network`
+    pub fn unblock_process_network(&mut self, exe_path: &str) -> Result<()> {
+        let rule_name = firewall::block_rule_name(exe_path);
+        tracing::info!("🔓 Déblocage réseau de {}", exe_path);
+
+        firewall::delete_rule(&rule_name)?;
+        self.blocked_rules.remove(&rule_name);
+        self.set_blocked_flag(&rule_name, false);
+        Ok(())
+    }
+
+    /// Existing GameBooster firewall block rules, sorted by name
+    pub fn list_block_rules(&self) -> Result<Vec<String>> {
+        let mut rules: Vec<String> = firewall::list_block_rules()?.into_iter().collect();
+        rules.sort();
+        Ok(rules)
+    }
+
+    /// Remove every GameBooster firewall block rule
+    pub fn clear_all_blocks(&mut self) -> Result<()> {
+        if let Ok(rules) = firewall::list_block_rules() {
+            self.blocked_rules.extend(rules);
+        }
+
+        let mut errors = Vec::new();
+        for rule_name in std::mem::take(&mut self.blocked_rules) {
+            if let Err(e) = firewall::delete_rule(&rule_name) {
+                tracing::error!("❌ Suppression de la règle {} échouée: {}", rule_name, e);
+                errors.push(rule_name);
+            }
+        }
+        for process in self.processes.values_mut() {
+            process.is_blocked = false;
+        }
+
+        if errors.is_empty() {
+            tracing::info!("✅ Toutes les règles de blocage supprimées");
+            Ok(())
+        } else {
+            self.blocked_rules.extend(errors.iter().cloned());
+            Err(anyhow::anyhow!("Règles non supprimées: {}", errors.join(", ")))
+        }
+    }
+
+    /// Executable path of a scanned process
+    pub fn process_exe_path(&self, pid: u32) -> Option<String> {
+        self.processes
+            .get(&pid)
+            .and_then(|p| p.exe_path.as_ref())
+            .map(|path| path.to_string_lossy().to_string())
+    }
+
+    fn set_blocked_flag(&mut self, rule_name: &str, blocked: bool) {
+        for process in self.processes.values_mut() {
+            if firewall::block_rule_name(&process.name) == rule_name {
+                process.is_blocked = blocked;
+            }
+        }
+    }
+
+    /// Get all processes managed by this limiter
+    pub fn get_processes(&self) -> Vec<&NetworkProcessInfo> {
+        self.processes.values().collect()
+    }
+
+    /// Owned snapshot of the scanned processes grouped by application, sorted by `sort`.
+    /// Children running the same executable as their parent chain are merged into the group
+    /// of their topmost such ancestor; members are sorted by download speed, fastest first.
+    pub fn get_processes_grouped(&self, sort: NetworkProcessSort, descending: bool) -> Vec<ProcessGroup> {
+        let mut groups: HashMap<u32, ProcessGroup> = HashMap::new();
+
+        for process in self.processes.values() {
+            let root_pid = self.group_root_pid(process);
+            let group = groups.entry(root_pid).or_insert_with(|| ProcessGroup {
+                exe_name: process.name.clone(),
+                root_pid,
+                total_download_speed: 0,
+                total_upload_speed: 0,
+                members: Vec::new(),
+            });
+            group.total_download_speed += process.current_download_speed;
+            group.total_upload_speed += process.current_upload_speed;
+            group.members.push(process.clone());
+        }
+
+        let mut groups: Vec<ProcessGroup> = groups.into_values().collect();
+        for group in &mut groups {
+            group
+                .members
+                .sort_by(|a, b| b.current_download_speed.cmp(&a.current_download_speed).then(a.pid.cmp(&b.pid)));
+        }
+        groups.sort_by(|a, b| {
+            let ordering = match sort {
+                NetworkProcessSort::Name => a.exe_name.to_lowercase().cmp(&b.exe_name.to_lowercase()),
+                NetworkProcessSort::Pid => a.root_pid.cmp(&b.root_pid),
+                NetworkProcessSort::Download => a.total_download_speed.cmp(&b.total_download_speed),
+                NetworkProcessSort::Upload => a.total_upload_speed.cmp(&b.total_upload_speed),
+                NetworkProcessSort::Connections => a.total_connections().cmp(&b.total_connections()),
+                NetworkProcessSort::Limit => a.speed_limit().cmp(&b.speed_limit()),
+            };
+            // Ordre stable d'une frame à l'autre à valeurs égales
+            let ordering = if descending { ordering.reverse() } else { ordering };
+            ordering.then(a.root_pid.cmp(&b.root_pid))
+        });
+        groups
+    }
+
+    /// Walk up the parent chain (all system processes, not only the scanned ones)
+    /// while the parent runs the same executable
+    fn group_root_pid(&self, process: &NetworkProcessInfo) -> u32 {
+        const MAX_DEPTH: usize = 32; // garde-fou contre les cycles de PID réutilisés
+
+        let same_exe = |candidate: &sysinfo::Process| match (&process.exe_path, candidate.exe()) {
+            (Some(path), Some(candidate_path)) => path.as_path() == candidate_path,
+            _ => candidate.name().eq_ignore_ascii_case(&process.name),
+        };
+
+        let mut root = process.pid;
+        let mut parent = process.parent_pid;
+        for _ in 0..MAX_DEPTH {
+            let Some(parent_pid) = parent else { break };
+            match self.system.process(sysinfo::Pid::from_u32(parent_pid)) {
+                Some(parent_process) if same_exe(parent_process) => {
+                    root = parent_pid;
+                    parent = parent_process.parent().map(|p| p.as_u32());
+                }
+                _ => break,
+            }
+        }
+        root
+    }
+
+    /// Check if a process is currently limited
+    pub fn is_process_limited(&self, pid: u32) -> bool {
+        if let Ok(limited) = self.limited_processes.lock() {
+            limited.contains_key(&pid)
+        } else {
+            false
+        }
+    }
+
+    /// Get the current speed limit for a process
+    pub fn get_process_limit(&self, pid: u32) -> Option<u32> {
+        if let Ok(limited) = self.limited_processes.lock() {
+            limited.get(&pid).copied()
+        } else {
+            None
+        }
+    }
+
+    /// Measure the outbound rate of `pid` for `duration_secs` seconds and compare it with
+    /// `configured_kbps`. Blocking and independent from the limiter state, so that it can
+    /// run on a worker thread while scans go on.
+    pub fn verify_limit_effectiveness(pid: u32, configured_kbps: u32, duration_secs: u64) -> Result<LimitVerification> {
+        tracing::info!("🔬 Vérification de la limite PID {} ({} KB/s) pendant {}s", pid, configured_kbps, duration_secs);
+        limit_verification::measure(pid, configured_kbps, duration_secs)
+    }
+
+    /// Get network statistics
+    pub fn get_network_stats(&self) -> NetworkStats {
+        let total_upload = self.processes.values().map(|p| p.current_upload_speed).sum();
+        let total_download = self.processes.values().map(|p| p.current_download_speed).sum();
+        let limited_count = self.processes.values().filter(|p| p.is_limited).count();
         
-        // Créer un script PowerShell qui lance un limiteur de bande passante personnalisé
-        // En utilisant une approche hybride : filtrage + temporisation des paquets
-        let limiter_script = format!(
-            r#"
-# Script de limitation bande passante TEMPS RÉEL
-# PID: {}, Process: {}, Limit: {} KB/s, Delay: {}ms
+        NetworkStats {
+            total_upload_bytes: total_upload,
+            total_download_bytes: total_download,
+            total_processes: self.processes.len(),
+            limited_processes_count: limited_count,
+        }
+    }
+
+    /// Verify if QoS policies are active using Windows Group Policy (JSON output).
+    /// `include_foreign` also returns the policies GameBooster did not create.
+    pub fn verify_qos_policies(&self, include_foreign: bool) -> Result<Vec<QosPolicyInfo>> {
+        Self::list_qos_policies_with(self.runner.as_ref(), self.qos_backend, include_foreign)
+    }
+
+    /// Policies of the QoS provider and of the registry store, GameBooster ones only unless
+    /// `include_foreign`, without a limiter so that it can run on a worker thread (see
+    /// `qos_backend()`)
+    pub fn list_qos_policies(backend: QosBackend, include_foreign: bool) -> Result<Vec<QosPolicyInfo>> {
+        Self::list_qos_policies_with(system::default_runner().as_ref(), backend, include_foreign)
+    }
+
+    /// `list_qos_policies`, the PowerShell fallback running through `runner`
+    #[cfg(target_os = "windows")]
+    pub fn list_qos_policies_with(runner: &dyn CommandRunner, backend: QosBackend, include_foreign: bool) -> Result<Vec<QosPolicyInfo>> {
+        let span = tracing::info_span!("qos_verify", include_foreign, policies = tracing::field::Empty, duration_ms = tracing::field::Empty);
+        let _entered = span.enter();
+        let name_prefix = if include_foreign { "" } else { POLICY_NAME_PREFIX };
+        if backend == QosBackend::Wmi {
+            let started = Instant::now();
+            match qos_wmi::list_policies(name_prefix) {
+                Ok(mut policies) => {
+                    let orphans = qos_wmi::list_registry_only_policies(name_prefix, &policies);
+                    policies.extend(orphans);
+                    let duration_ms = started.elapsed().as_millis() as u64;
+                    span.record("policies", policies.len());
+                    span.record("duration_ms", duration_ms);
+                    log_every!(SUMMARY_INTERVAL, "📋 {} politiques QoS actives trouvées via WMI (⏱️ {} ms)", policies.len(), duration_ms);
+                    return Ok(policies);
+                }
+                Err(e) => {
+                    tracing::warn!("⚠️ Vérification QoS WMI échouée après {} ms, fallback PowerShell: {}", started.elapsed().as_millis(), e);
+                }
+            }
+        }
 
+        tracing::debug!("📋 Vérification des politiques QoS via JSON...");
+        let started = Instant::now();
+        
+        let powershell_script = r#"
 $ErrorActionPreference = "SilentlyContinue"
+$OutputEncoding = [System.Text.Encoding]::UTF8
+[System.Threading.Thread]::CurrentThread.CurrentCulture = 'en-US'
+
+$policiesFound = @()
+
+# Port unique ou plage, $null sans condition de port
+function Get-PortRange($single, $start, $end) {
+    if ($single -gt 0) { return ,@([int]$single, [int]$single) }
+    if ($start -gt 0 -and $end -gt 0) { return ,@([int]$start, [int]$end) }
+    return $null
+}
+
+# Source de vérité: Get-NetQosPolicy
+$allPolicies = Get-NetQosPolicy | Where-Object { $_.Name -like "{NAME_FILTER}" }
+foreach ($policy in $allPolicies) {
+    $ports = Get-PortRange $policy.IPDstPortMatchCondition $policy.IPDstPortStartMatchCondition $policy.IPDstPortEndMatchCondition
+    $direction = "Destination"
+    if (-not $ports) {
+        $ports = Get-PortRange $policy.IPSrcPortMatchCondition $policy.IPSrcPortStartMatchCondition $policy.IPSrcPortEndMatchCondition
+        $direction = "Source"
+    }
+    $protocol = [string]$policy.IPProtocolMatchCondition
+    $policiesFound += [PSCustomObject]@{
+        Name = $policy.Name
+        AppName = $policy.AppPathNameMatchCondition
+        ThrottleBits = $policy.ThrottleRateActionBitsPerSecond
+        IsRegistryOnly = $false
+        DSCP = $(if ($policy.DSCPAction -ge 0) { [int]$policy.DSCPAction } else { $null })
+        Protocol = $(if ($protocol -in @("TCP", "UDP", "Both")) { $protocol } else { $null })
+        PortStart = $(if ($ports) { $ports[0] } else { $null })
+        PortEnd = $(if ($ports) { $ports[1] } else { $null })
+        PortDirection = $(if ($ports) { $direction } else { $null })
+    }
+}
+
+# Vérifier les politiques orphelines dans le registre
+$regPath = "HKLM:\SOFTWARE\Policies\Microsoft\Windows\QoS"
+if (Test-Path $regPath) {
+    $regPolicies = Get-ChildItem -Path $regPath | Where-Object { $_.PSChildName -like "{NAME_FILTER}" }
+    foreach ($regKey in $regPolicies) {
+        $policyName = $regKey.PSChildName
+        if (-not ($allPolicies | Where-Object { $_.Name -eq $policyName })) {
+            # "Throttle Rate" est une chaîne en Kbps, -1 sans limite (politique de marquage, GPO)
+            $rateKbps = [int64]0
+            [void][int64]::TryParse([string](Get-ItemProperty -Path $regKey.PSPath)."Throttle Rate", [ref]$rateKbps)
+            # Ports "6881:6889" ou "*", protocole "TCP", "UDP" ou "*"
+            $ports = $null
+            $direction = $null
+            foreach ($side in @(@("Remote Port", "Destination"), @("Local Port", "Source"))) {
+                $value = [string](Get-ItemProperty -Path $regKey.PSPath).($side[0])
+                if (-not $ports -and $value -and $value -ne "*") {
+                    $bounds = $value.Split(":")
+                    $ports = @([int]$bounds[0], [int]$bounds[-1])
+                    $direction = $side[1]
+                }
+            }
+            $protocol = [string](Get-ItemProperty -Path $regKey.PSPath)."Protocol"
+            $policiesFound += [PSCustomObject]@{
+                Name = $policyName
+                AppName = (Get-ItemProperty -Path $regKey.PSPath)."Application Name"
+                ThrottleBits = $rateKbps * 1000
+                IsRegistryOnly = $true
+                DSCP = $(if ([int]((Get-ItemProperty -Path $regKey.PSPath)."DSCP Value") -ge 0) { [int]((Get-ItemProperty -Path $regKey.PSPath)."DSCP Value") } else { $null })
+                Protocol = $(if ($protocol -in @("TCP", "UDP")) { $protocol } elseif ($protocol -eq "*" -and $ports) { "Both" } else { $null })
+                PortStart = $(if ($ports) { $ports[0] } else { $null })
+                PortEnd = $(if ($ports) { $ports[1] } else { $null })
+                PortDirection = $direction
+            }
+        }
+    }
+}
+
+$policiesFound | ForEach-Object {
+    if (-not $_.ThrottleBits -or $_.ThrottleBits -lt 0) {
+        $_.ThrottleBits = 0
+    }
+    if (-not $_.AppName) {
+        $_.AppName = "N/A"
+    }
+}
+
+$policiesFound | ConvertTo-Json -Compress
+        "#;
+
+        let name_filter = format!("{}*", name_prefix);
+        let powershell_script = powershell_script.replace("{NAME_FILTER}", &name_filter);
+        let output = runner.powershell(&powershell_script);
+
+        match output {
+            Ok(result) => {
+                let stdout = result.stdout;
+                let stderr = result.stderr;
+                
+                if !stderr.is_empty() {
+                    tracing::warn!("⚠️ Avertissements vérification QoS JSON: {}", stderr.trim());
+                }
+                
+                let policies = parse_qos_policies(&stdout)?;
+
+                let duration_ms = started.elapsed().as_millis() as u64;
+                span.record("policies", policies.len());
+                span.record("duration_ms", duration_ms);
+                log_every!(SUMMARY_INTERVAL, "📋 {} politiques QoS actives trouvées via JSON (⏱️ PowerShell {} ms)", policies.len(), duration_ms);
+
+                for policy in &policies {
+                    let rate_mbps = policy.throttle_bits as f64 / (1024.0 * 1024.0 * 8.0);
+                    let registry_tag = if policy.is_registry_only { "(registre seulement)" } else { "" };
+                    let dscp_tag = policy.dscp.map(|d| format!(", DSCP: {}", d)).unwrap_or_default();
+                    let package_tag = policy.package_family_name.as_deref().map(|p| format!(", Package: {}", p)).unwrap_or_default();
+                    tracing::debug!("  - Nom: {}, App: {}, Limite: {:.2} MB/s{}{} {}", policy.name, policy.app_name, rate_mbps, dscp_tag, package_tag, registry_tag);
+                }
+                
+                Ok(policies)
+            }
+            Err(e) => {
+                tracing::error!("❌ Erreur exécution vérification QoS JSON: {}", e);
+                Err(anyhow::anyhow!("Erreur vérification QoS: {}", e))
+            }
+        }
+    }
+
+    /// Placeholder for Linux QoS verification
+    #[cfg(not(target_os = "windows"))]
+    pub fn list_qos_policies_with(_runner: &dyn CommandRunner, _backend: QosBackend, _include_foreign: bool) -> Result<Vec<QosPolicyInfo>> {
+        tracing::debug!("📋 Vérification des politiques QoS (Linux stub - non implémenté)");
+        // Retourner un vecteur vide ou une erreur appropriée pour Linux
+        Ok(Vec::new())
+    }
+
+    /// Get a summary of active QoS limitations
+    pub fn get_qos_summary(&self) -> String {
+        match self.verify_qos_policies(false) {
+            Ok(policies) => {
+                if policies.is_empty() {
+                    "🔍 Aucune politique QoS active".to_string()
+                } else {
+                    let summary_lines: Vec<String> = policies.iter().map(|p| {
+                        let rate_mbps = p.throttle_bits as f64 / (1024.0 * 1024.0 * 8.0);
+                        match p.kind() {
+                            QosPolicyKind::GlobalLimit => {
+                                format!("- 🌐 Limite globale ({}): {:.2} MB/s pour tout le trafic", p.name, rate_mbps)
+                            }
+                            QosPolicyKind::GlobalExemption => {
+                                format!("- 🎮 Exemption limite globale ({}): {}", p.name, p.app_name)
+                            }
+                            QosPolicyKind::Dscp => {
+                                format!("- 🏷️ {}: DSCP {} pour {}", p.name, p.dscp.map(|d| d.to_string()).unwrap_or_else(|| "?".to_string()), p.app_name)
+                            }
+                            QosPolicyKind::PortLimit => {
+                                format!("- {}: {:.2} MB/s sur {}", p.name, rate_mbps, p.target_label())
+                            }
+                            _ => format!("- {}: {:.2} MB/s pour {}", p.name, rate_mbps, p.app_name),
+                        }
+                    }).collect();
+                    format!("🎯 {} politiques QoS actives:\n{}", policies.len(), summary_lines.join("\n"))
+                }
+            }
+            Err(e) => format!("❌ Impossible de vérifier les politiques QoS: {}", e)
+        }
+    }
+
+    /// PIDs of `pids` whose policy (tc class on Linux) still exists, from a single listing.
+    /// A failed listing counts as missing: creating a policy again is harmless, leaving a
+    /// process unthrottled is not.
+    fn limits_in_place(&self, pids: &[u32]) -> HashSet<u32> {
+        if pids.is_empty() {
+            return HashSet::new();
+        }
+        #[cfg(target_os = "linux")]
+        {
+            pids.iter()
+                .copied()
+                .filter(|pid| self.linux_shaper.as_ref().is_some_and(|shaper| shaper.has_class(*pid)))
+                .collect()
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let policies = match self.verify_qos_policies(false) {
+                Ok(policies) => policies,
+                Err(e) => {
+                    tracing::warn!("⚠️ Politiques QoS illisibles, limites réappliquées: {}", e);
+                    return HashSet::new();
+                }
+            };
+            pids.iter()
+                .copied()
+                .filter(|pid| {
+                    let policy_name = self.policy_name_for(*pid);
+                    policies.iter().any(|policy| policy.name == policy_name)
+                })
+                .collect()
+        }
+    }
+
+    /// Limit in place for `pid`, in KB/s
+    fn current_limit(&self, pid: u32) -> Option<u32> {
+        self.limited_processes.lock().ok().and_then(|limited| limited.get(&pid).copied())
+    }
+
+    /// Limit a process with a QoS policy (tc class on Linux), active immediately.
+    /// `scope_adapter` restricts the policy to the traffic leaving through that adapter
+    /// (see `adapters()`); `None` throttles every adapter.
+    ///
+    /// Asking again for the limit already in place only checks that its policy still exists
+    /// (and creates it again when it does not); another limit replaces the policy. The
+    /// returned `LimitChange` tells which of these happened.
+    pub fn set_process_speed_limit(&mut self, pid: u32, limit_kbps: u32, scope_adapter: Option<&str>) -> Result<LimitChange> {
+        self.ensure_policies_restored();
+        let started = Instant::now();
+        let span = tracing::info_span!(
+            "qos_limit",
+            pid,
+            exe = %self.process_label(pid),
+            kbps = limit_kbps,
+            duration_ms = tracing::field::Empty
+        );
+        let _entered = span.enter();
+        self.ensure_qos_available()?;
+        self.ensure_not_critical(pid, "limiter")?;
+        self.warn_if_stale(pid);
+
+        let previous = self.current_limit(pid);
+        // Supprimer puis recréer la même politique laisserait le processus libre entre les deux
+        let same_limit = previous == Some(limit_kbps) && self.policy_scope(pid) == scope_adapter;
+        if same_limit {
+            if self.limits_in_place(&[pid]).contains(&pid) {
+                tracing::info!("⏭️ PID {} déjà limité à {} KB/s, politique {} conservée", pid, limit_kbps, self.policy_name_for(pid));
+                self.set_limit_expiry(pid, None);
+                return Ok(LimitChange { previous, new: limit_kbps, policy_recreated: false });
+            }
+            tracing::warn!("⚠️ Politique {} disparue pour PID {}, recréation", self.policy_name_for(pid), pid);
+        } else if let Some(previous) = previous {
+            tracing::info!("🔁 Limite PID {} remplacée: {} → {} KB/s", pid, previous, limit_kbps);
+        }
+
+        // Linux : classe HTB + cgroup net_cls, appliquée avant de marquer le processus limité
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(adapter) = scope_adapter {
+                tracing::warn!("⚠️ Portée {} ignorée: la classe tc limite le processus sur toutes les cartes", adapter);
+            }
+            self.linux_shaper_mut()?.limit_pid(pid, limit_kbps)?;
+        }
+
+        // Une politique sur un exécutable non résolu ne correspondrait à rien
+        #[cfg(not(target_os = "linux"))]
+        {
+            let app_name = self.qos_app_name(pid)?;
+            let scope = scope_adapter.map(|adapter| self.adapter_scope(adapter)).transpose()?;
+
+            // Pas de limiteur par paquets (il faudrait le pilote WinDivert) : sans politique
+            // QoS le processus n'est pas limité, on le signale au lieu de le marquer comme tel
+            if let Err(e) = self.apply_netsh_qos_limit(pid, limit_kbps, scope.as_ref().map(|(_, prefix)| prefix.as_str())) {
+                tracing::error!("❌ Limitation QoS échouée pour PID {}, fallback temps réel indisponible: {}", pid, e);
+                return Err(anyhow::anyhow!("{} (fallback temps réel indisponible)", e));
+            }
+            tracing::debug!("✅ Limitation QoS principale appliquée: PID {} → {} KB/s", pid, limit_kbps);
+            let policy_name = self.policy_name_for(pid);
+            self.remember_policy(&policy_name, &app_name, (limit_kbps as u64) * 1024 * 8);
+            match (scope_adapter, scope) {
+                (Some(adapter), Some((address, _))) => {
+                    self.policy_scopes.insert(pid, (adapter.to_string(), address));
+                }
+                _ => {
+                    self.policy_scopes.remove(&pid);
+                }
+            }
+        }
+
+        // Add to limited processes list
+        if let Ok(mut limited) = self.limited_processes.lock() {
+            limited.insert(pid, limit_kbps);
+        }
+        
+        // Update process info
+        if let Some(process) = self.processes.get_mut(&pid) {
+            process.is_limited = true;
+            process.speed_limit = Some(limit_kbps);
+        }
+        
+        // Une limite permanente remplace une éventuelle limite temporaire
+        self.limit_expiries.remove(&self.policy_name_for(pid));
+
+        span.record("duration_ms", started.elapsed().as_millis() as u64);
+        tracing::info!("✅ Limitation COMPLÈTE appliquée: PID {} → {} KB/s (actif immédiatement)", pid, limit_kbps);
+        Ok(LimitChange { previous, new: limit_kbps, policy_recreated: same_limit })
+    }
+
+    /// Like `set_process_speed_limit`, removed by `tick_expirations` once `duration` has passed
+    pub fn set_process_speed_limit_for(
+        &mut self,
+        pid: u32,
+        limit_kbps: u32,
+        duration: Duration,
+        scope_adapter: Option<&str>,
+    ) -> Result<LimitChange> {
+        let change = self.set_process_speed_limit(pid, limit_kbps, scope_adapter)?;
+        self.set_limit_expiry(pid, Some(duration));
+        Ok(change)
+    }
+
+    /// Make the limit of `pid` end after `duration`, `None` making it permanent. The PIDs
+    /// sharing its policy (same executable) share the expiry, which is persisted with the policy
+    /// so that a restart does not turn it into a permanent limit.
+    pub fn set_limit_expiry(&mut self, pid: u32, duration: Option<Duration>) {
+        let policy_name = self.policy_name_for(pid);
+        let expires_at = duration.and_then(|duration| chrono::Duration::from_std(duration).ok()).map(|duration| Local::now() + duration);
+        match expires_at {
+            Some(expires_at) => {
+                tracing::info!("⏳ Limite {} jusqu'à {}", policy_name, expires_at.format("%H:%M"));
+                self.limit_expiries.insert(policy_name.clone(), LimitExpiry { expires_at, retry_at: None });
+            }
+            None => {
+                self.limit_expiries.remove(&policy_name);
+            }
+        }
+        if let Some(policy) = self.managed_policies.iter_mut().find(|p| p.name == policy_name) {
+            policy.expires_at = expires_at;
+            self.save_policy_store();
+        }
+    }
+
+    /// Expiry of the limit of `pid`, `None` for a permanent limit
+    pub fn limit_expiry(&self, pid: u32) -> Option<&LimitExpiry> {
+        self.limit_expiries.get(&self.policy_name_for(pid))
+    }
+
+    /// Soonest expiry, for the caller to wake up in time
+    pub fn next_expiry(&self) -> Option<DateTime<Local>> {
+        self.limit_expiries
+            .values()
+            .map(|expiry| expiry.retry_at.unwrap_or(expiry.expires_at))
+            .min()
+    }
+
+    /// Remove the limits whose time is up, through the normal removal path. A limit whose
+    /// removal fails stays listed, flagged, and is tried again every `EXPIRY_RETRY_SECS`;
+    /// only its first failure is reported.
+    pub fn tick_expirations(&mut self) -> Vec<ExpiredLimit> {
+        let now = Local::now();
+        let due: Vec<String> = self
+            .limit_expiries
+            .iter()
+            .filter(|(_, expiry)| expiry.retry_at.unwrap_or(expiry.expires_at) <= now)
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        let mut expired = Vec::new();
+        for policy_name in due {
+            let limited_pids: Vec<u32> = match self.limited_processes.lock() {
+                Ok(limited) => limited.keys().copied().collect(),
+                Err(_) => Vec::new(),
+            };
+            let pid = limited_pids.into_iter().find(|pid| self.policy_name_for(*pid) == policy_name);
+            let target = match pid {
+                Some(pid) => match self.processes.get(&pid) {
+                    Some(process) => format!("{} ({})", process.name, pid),
+                    None => format!("PID {}", pid),
+                },
+                None => self
+                    .managed_policies
+                    .iter()
+                    .find(|p| p.name == policy_name)
+                    .map(|p| p.app_name.clone())
+                    .unwrap_or_else(|| policy_name.clone()),
+            };
+
+            let result = match pid {
+                Some(pid) => self.remove_process_limit(pid),
+                // Sous Linux, la classe tc disparaît avec le processus
+                None if cfg!(target_os = "linux") => {
+                    self.forget_policies(|name| name == policy_name);
+                    Ok(())
+                }
+                // Limite d'une session précédente dont l'exécutable ne tourne pas
+                None => self.remove_policy(&policy_name),
+            };
+            match result {
+                Ok(()) => {
+                    self.limit_expiries.remove(&policy_name);
+                    tracing::info!("⏳ Limite temporaire {} expirée et supprimée", target);
+                    expired.push(ExpiredLimit { target, error: None });
+                }
+                Err(e) => {
+                    tracing::error!("❌ Suppression de la limite expirée {} impossible: {:#}", target, e);
+                    let Some(expiry) = self.limit_expiries.get_mut(&policy_name) else {
+                        continue;
+                    };
+                    let first_failure = expiry.retry_at.is_none();
+                    expiry.retry_at = Some(now + chrono::Duration::seconds(EXPIRY_RETRY_SECS));
+                    if first_failure {
+                        expired.push(ExpiredLimit { target, error: Some(format!("{:#}", e)) });
+                    }
+                }
+            }
+        }
+        expired
+    }
 
-Write-Host "🚀 Démarrage limiteur temps réel pour {} (PID {})"
+    /// Current "everything except" mode, if active
+    pub fn global_limit(&self) -> Option<&GlobalLimit> {
+        self.global_limit.as_ref()
+    }
 
-# Méthode 1: Limitation TCP Window pour trafic entrant (temporaire)
-try {{
-    # Créer une limitation temporaire via netsh interface
-    $adapterId = (Get-NetAdapter | Where-Object {{$_.Status -eq "Up"}} | Select-Object -First 1).InterfaceIndex
-    if ($adapterId) {{
-        # Limitation de la bande passante via netsh (méthode alternative)
-        netsh interface tcp set global autotuninglevel=restricted 2>$null
-        Write-Host "✅ Limitation TCP temporaire appliquée"
-    }}
-}} catch {{
-    Write-Host "⚠️ Erreur limitation TCP: $($_.Exception.Message)"
-}}
+    /// Throttle all traffic to `limit_kbps` except the given executables, which get
+    /// an unthrottled, high-priority (DSCP EF) policy instead.
+    pub fn set_global_limit_except(&mut self, exempt_exe_names: &[String], limit_kbps: u32) -> Result<()> {
+        self.ensure_policies_restored();
+        if limit_kbps == 0 {
+            return Err(anyhow::anyhow!("La limite globale doit être supérieure à 0 KB/s"));
+        }
+        self.ensure_qos_available()?;
 
-# Méthode 2: Monitoring et alerte
-$startTime = Get-Date
-$processObj = Get-Process -Id {} -ErrorAction SilentlyContinue
-if ($processObj) {{
-    Write-Host "✅ Processus {} surveillé actif (PID {})"
-    
-    # Surveiller pendant 60 secondes puis arrêter automatiquement
-    $timeout = 60
-    $elapsed = 0
-    
-    while ($elapsed -lt $timeout -and (Get-Process -Id {} -ErrorAction SilentlyContinue)) {{
-        Start-Sleep -Seconds 5
-        $elapsed = ((Get-Date) - $startTime).TotalSeconds
-        
-        if ($elapsed % 20 -eq 0) {{
-            Write-Host "📊 Limiteur actif depuis ${{elapsed}}s pour {} (PID {})"
-        }}
-    }}
-    
-    Write-Host "🔄 Limitation temps réel terminée pour {} après ${{elapsed}}s"
-}} else {{
-    Write-Host "⚠️ Processus PID {} non trouvé"
-}}
+        // Remplacer une éventuelle limite globale précédente
+        if self.global_limit.is_some() {
+            self.clear_global_limit()?;
+        }
 
-Write-Host "✅ Script limiteur terminé pour {}"
-            
-            "#,
-            pid, process_name, limit_kbps, delay_ms,       // 1-4: Commentaire en-tête
-            process_name, pid,                              // 5-6: Message démarrage
-            pid,                                            // 7: Get-Process check 1
-            process_name, pid,                              // 8-9: Message processus actif
-            pid,                                            // 10: Get-Process check 2  
-            process_name, pid,                              // 11-12: Message surveillance
-            process_name,                                   // 13: Message terminaison
-            pid,                                            // 14: Message PID non trouvé
-            process_name                                    // 15: Message script terminé
-        );
+        let throttle_bits_per_second = (limit_kbps as u64) * 1024 * 8;
+        tracing::info!("🌐 Limite globale {} KB/s, exemptions: {:?}", limit_kbps, exempt_exe_names);
 
-        // Exécuter le script en arrière-plan
-        tracing::info!("🔧 Lancement script limiteur temps réel");
-        
-        let mut command = Command::new("powershell.exe");
-        command.args(["-NoProfile", "-WindowStyle", "Hidden", "-ExecutionPolicy", "Bypass", "-Command", &limiter_script]);
-        
-        #[cfg(target_os = "windows")]
-        command.creation_flags(0x08000000); // CREATE_NO_WINDOW
-        
-        let output = command.spawn(); // Utiliser spawn() au lieu de output() pour lancer en arrière-plan
+        let mut specs_names: Vec<(String, Option<String>)> = vec![(GLOBAL_LIMIT_POLICY_NAME.to_string(), None)];
+        for exe in exempt_exe_names {
+            specs_names.push((format!("{}Exempt_{}", GLOBAL_POLICY_PREFIX, exe), Some(exe.clone())));
+        }
 
-        match output {
-            Ok(mut child) => {
-                tracing::info!("✅ Limiteur temps réel lancé en arrière-plan pour PID {}", pid);
-                
-                // Optionnel: surveiller le processus en arrière-plan
-                std::thread::spawn(move || {
-                    match child.wait() {
-                        Ok(status) => {
-                            if status.success() {
-                                tracing::info!("✅ Limiteur temps réel terminé avec succès pour PID {}", pid);
-                            } else {
-                                tracing::warn!("⚠️ Limiteur temps réel terminé avec code d'erreur pour PID {}", pid);
-                            }
-                        }
-                        Err(e) => {
-                            tracing::error!("❌ Erreur attente limiteur PID {}: {}", pid, e);
-                        }
-                    }
-                });
-                
-                Ok(())
-            }
-            Err(e) => {
-                let error_msg = format!("Impossible de lancer le limiteur temps réel: {}", e);
-                tracing::error!("❌ {}", error_msg);
-                Err(anyhow::anyhow!(error_msg))
+        for (name, app_name) in &specs_names {
+            let spec = QosPolicySpec {
+                name,
+                app_name: app_name.as_deref(),
+                default_match: app_name.is_none(),
+                throttle_bits_per_second: if app_name.is_none() { Some(throttle_bits_per_second) } else { None },
+                dscp: if app_name.is_some() { Some(EXEMPTION_DSCP) } else { None },
+                src_prefix: None,
+                port_rule: None,
+            };
+            if let Err(e) = self.create_policy_from_spec(&spec) {
+                // Ne pas laisser une limite globale sans ses exemptions
+                let _ = self.remove_policies_with_prefix(GLOBAL_POLICY_PREFIX);
+                return Err(e);
             }
         }
+        for (name, app_name) in &specs_names {
+            let throttle_bits = if app_name.is_none() { throttle_bits_per_second } else { 0 };
+            self.remember_policy(name, app_name.as_deref().unwrap_or_default(), throttle_bits);
+        }
+
+        self.global_limit = Some(GlobalLimit {
+            limit_kbps,
+            exempt_exe_names: exempt_exe_names.to_vec(),
+        });
+        tracing::info!("✅ Limite globale active ({} exemption(s))", exempt_exe_names.len());
+        Ok(())
     }
 
-    /// Remove limitation from a process
-    pub fn remove_process_limit(&mut self, pid: u32) -> Result<()> {
-        // Remove from limited processes list
-        if let Ok(mut limited) = self.limited_processes.lock() {
-            limited.remove(&pid);
+    /// Remove the catch-all policy and its exemptions, leaving per-process limits untouched
+    pub fn clear_global_limit(&mut self) -> Result<()> {
+        let removed = self.remove_policies_with_prefix(GLOBAL_POLICY_PREFIX)?;
+        self.forget_policies(|name| name.starts_with(GLOBAL_POLICY_PREFIX));
+        self.global_limit = None;
+        tracing::info!("✅ Limite globale supprimée ({} politique(s))", removed);
+        Ok(())
+    }
+
+    /// Mark the traffic of `exe_name` with a DSCP value so that routers can prioritize it
+    pub fn set_process_dscp(&mut self, exe_name: &str, dscp: u8) -> Result<()> {
+        self.ensure_policies_restored();
+        if dscp > DSCP_MAX {
+            return Err(anyhow::anyhow!("Valeur DSCP invalide {} (0-{})", dscp, DSCP_MAX));
         }
-        
-        // Update process info
-        if let Some(process) = self.processes.get_mut(&pid) {
-            process.is_limited = false;
-            process.speed_limit = None;
+        self.ensure_qos_available()?;
+
+        // Sans "Do not use NLA", Windows ignore le marquage DSCP hors domaine
+        match Self::ensure_dscp_nla_setting() {
+            Ok(true) => tracing::info!("🔧 Paramètre \"Do not use NLA\" créé (un redémarrage peut être nécessaire)"),
+            Ok(false) => {}
+            Err(e) => tracing::warn!("⚠️ Impossible de vérifier le paramètre \"Do not use NLA\": {}", e),
         }
-        
-        // Remove QoS policy
-        self.remove_netsh_qos_limit(pid)?;
-        
-        tracing::info!("✅ Limitation supprimée: PID {}", pid);
+
+        let policy_name = Self::dscp_policy_name(exe_name);
+        tracing::info!("🏷️ Marquage DSCP {} pour {}", dscp, exe_name);
+        self.create_policy_from_spec(&QosPolicySpec {
+            name: &policy_name,
+            app_name: Some(exe_name),
+            default_match: false,
+            throttle_bits_per_second: None,
+            dscp: Some(dscp),
+            src_prefix: None,
+            port_rule: None,
+        })?;
+        self.remember_policy(&policy_name, exe_name, 0);
         Ok(())
     }
 
-    /// Remove QoS limitation using Windows Group Policy (consistent with creation)
-    fn remove_netsh_qos_limit(&self, pid: u32) -> Result<()> {
-        let policy_name = format!("GameBooster_Limit_{}", pid);
-        let rt_policy_name = format!("GameBooster_RT_Limit_{}", pid);
-        
-        tracing::info!("🔧 Suppression politique QoS GROUP POLICY: {}", policy_name);
-        
-        // Use PowerShell to remove Group Policy QoS rule
-        let powershell_script = format!(
-            r#"
-            $OutputEncoding = [System.Text.Encoding]::UTF8
-            try {{
-                Remove-NetQosPolicy -Name "{0}" -Confirm:$false -ErrorAction SilentlyContinue
-                Remove-NetQosPolicy -Name "{1}" -Confirm:$false -ErrorAction SilentlyContinue
-                Write-Output "SUCCESS: Policy removed"
-            }} catch {{
-                # Ignorer l'erreur si la politique n'existe pas
-                if ($_.Exception.Message -like "*No matching MSFT_NetQosPolicy*") {{
-                    Write-Output "INFO: Policy did not exist"
-                }} else {{
-                    Write-Error "ERROR: $($_.Exception.Message)"
-                    exit 1
-                }}
-            }}
-            "#,
-            policy_name,
-            rt_policy_name
-        );
-        
-        tracing::info!("🔧 Script suppression GROUP POLICY QoS");
-        
-        let mut command = Command::new("powershell.exe");
-        command.args(["-NoProfile", "-WindowStyle", "Hidden", "-ExecutionPolicy", "Bypass", "-Command", &powershell_script]);
+    /// Remove the DSCP marking policy of `exe_name`
+    pub fn remove_process_dscp(&mut self, exe_name: &str) -> Result<()> {
+        let policy_name = Self::dscp_policy_name(exe_name);
+        self.remove_policy_by_name(&policy_name)?;
+        self.forget_policies(|name| name == policy_name);
+        tracing::info!("✅ Marquage DSCP supprimé pour {}", exe_name);
+        Ok(())
+    }
+
+    /// Port rules created by GameBooster, in creation order
+    pub fn port_rules(&self) -> Vec<PortRule> {
+        self.managed_policies.iter().filter_map(|policy| policy.port_rule).collect()
+    }
+
+    /// Existing port rule matching some of the traffic of `rule`, other than its own policy
+    pub fn overlapping_port_rule(&self, rule: &PortRule) -> Option<PortRule> {
+        let name = rule.policy_name();
+        self.managed_policies
+            .iter()
+            .filter(|policy| policy.name != name)
+            .filter_map(|policy| policy.port_rule)
+            .find(|existing| existing.overlaps(rule))
+    }
+
+    /// Throttle the outbound traffic of a protocol and port range, whatever the process
+    /// (virtual machines, WSL, uTP). Replaces the rule on the same range.
+    pub fn set_port_limit(&mut self, rule: PortRule) -> Result<()> {
+        self.ensure_policies_restored();
+        if cfg!(target_os = "linux") {
+            return Err(anyhow::anyhow!("Limitation par port indisponible sous Linux ({})", rule.describe()));
+        }
+        rule.validate()?;
+        self.ensure_qos_available()?;
+        if let Some(existing) = self.overlapping_port_rule(&rule) {
+            return Err(anyhow::anyhow!("{} chevauche la règle existante {}", rule.describe(), existing.describe()));
+        }
+
+        let policy_name = rule.policy_name();
+        let throttle_bits = (rule.limit_kbps as u64) * 1024 * 8;
+        tracing::info!("🔌 Limite {} KB/s sur {}", rule.limit_kbps, rule.describe());
+        self.create_policy_from_spec(&QosPolicySpec {
+            name: &policy_name,
+            app_name: None,
+            default_match: false,
+            throttle_bits_per_second: Some(throttle_bits),
+            dscp: None,
+            src_prefix: None,
+            port_rule: Some(&rule),
+        })?;
+        self.remember(PersistedPolicy {
+            name: policy_name,
+            app_name: String::new(),
+            throttle_bits,
+            expires_at: None,
+            port_rule: Some(rule),
+        });
+        Ok(())
+    }
+
+    /// Remove the policy of a port rule
+    pub fn remove_port_limit(&mut self, rule: &PortRule) -> Result<()> {
+        let policy_name = rule.policy_name();
+        self.remove_policy_by_name(&policy_name)?;
+        self.forget_policies(|name| name == policy_name);
+        tracing::info!("✅ Limite supprimée sur {}", rule.describe());
+        Ok(())
+    }
 
+    /// Remove a single policy with the configured backend
+    fn remove_policy_by_name(&self, policy_name: &str) -> Result<()> {
         #[cfg(target_os = "windows")]
-        command.creation_flags(0x08000000); // CREATE_NO_WINDOW
-        
-        let output = command.output();
-        
-        match output {
-            Ok(result) => {
-                let stdout = String::from_utf8_lossy(&result.stdout);
-                let stderr = String::from_utf8_lossy(&result.stderr);
-                
-                tracing::info!("📤 Sortie suppression GROUP POLICY: {}", stdout.trim());
-                if !stderr.is_empty() {
-                    tracing::warn!("⚠️ Erreur suppression GROUP POLICY: {}", stderr.trim());
-                }
-                
-                if result.status.success() || stdout.contains("SUCCESS") || stdout.contains("INFO:") {
-                    tracing::info!("✅ Politique QoS GROUP POLICY supprimée: {}", policy_name);
-                    Ok(())
-                } else {
-                    let error_msg = format!("Échec suppression politique GROUP POLICY: {}", stderr.trim());
-                    tracing::warn!("⚠️ {}", error_msg);
-                    // Ne pas retourner d'erreur, juste un warning
-                    Ok(())
-                }
-            }
-            Err(e) => {
-                let error_msg = format!("Impossible d'exécuter suppression PowerShell GROUP POLICY: {}", e);
-                tracing::warn!("⚠️ {}", error_msg);
-                // Ne pas retourner d'erreur, juste un warning
-                Ok(())
+        if self.qos_backend == QosBackend::Wmi {
+            match qos_wmi::remove_policies(&[policy_name]) {
+                Ok(_) => return Ok(()),
+                Err(e) => tracing::warn!("⚠️ Suppression WMI de {} échouée, fallback PowerShell: {}", policy_name, e),
             }
         }
+
+        self.run_hidden_powershell(&format!(
+            "Remove-NetQosPolicy -Name '{}' -Confirm:$false -ErrorAction SilentlyContinue",
+            policy_name.replace('\'', "''")
+        ))?;
+        Ok(())
     }
 
-    /// Clear all QoS limitations using Windows Group Policy
-    fn clear_all_qos_policies(&self) -> Result<()> {
-        tracing::info!("🧹 Suppression globale des politiques QoS GROUP POLICY GameBooster");
-        
-        // Use PowerShell to remove all GameBooster QoS policies from provider and registry
-        let powershell_script = 
-            r#"
-            $OutputEncoding = [System.Text.Encoding]::UTF8
-            $ErrorActionPreference = "SilentlyContinue"
-            
-            # Supprimer via le provider Get-NetQosPolicy
-            $policies = Get-NetQosPolicy | Where-Object { $_.Name -like 'GameBooster_*' }
-            $providerCount = 0
-            if ($policies) {
-                $providerCount = ($policies | Measure-Object).Count
-                $policies | Remove-NetQosPolicy -Confirm:$false
-            }
-            
-            # Supprimer les politiques orphelines du registre
-            $regPath = "HKLM:\SOFTWARE\Policies\Microsoft\Windows\QoS"
-            $registryCount = 0
-            if (Test-Path $regPath) {
-                $regPolicies = Get-ChildItem -Path $regPath | Where-Object { $_.PSChildName -like 'GameBooster_*' }
-                if ($regPolicies) {
-                    $registryCount = ($regPolicies | Measure-Object).Count
-                    $regPolicies | Remove-Item -Recurse -Force
-                }
-            }
+    fn dscp_policy_name(exe_name: &str) -> String {
+        format!("{}{}", DSCP_POLICY_PREFIX, exe_name)
+    }
 
-            $result = @{
-                ProviderRemoved = $providerCount
-                RegistryRemoved = $registryCount
-                Message = "Cleanup finished."
-            }
-            $result | ConvertTo-Json -Compress
-            "#;
-        
-        tracing::info!("🔧 Script suppression globale avec sortie JSON");
-        
-        let mut command = Command::new("powershell.exe");
-        command.args(["-NoProfile", "-WindowStyle", "Hidden", "-ExecutionPolicy", "Bypass", "-Command", powershell_script]);
+    /// Create the "Do not use NLA" QoS setting when missing. Returns true if it was created.
+    #[cfg(target_os = "windows")]
+    fn ensure_dscp_nla_setting() -> Result<bool> {
+        qos_wmi::ensure_do_not_use_nla()
+    }
 
-        #[cfg(target_os = "windows")]
-        command.creation_flags(0x08000000); // CREATE_NO_WINDOW
-        
-        let output = command.output();
-        
-        match output {
-            Ok(result) => {
-                let stdout = String::from_utf8(result.stdout)
-                    .map_err(|e| anyhow::anyhow!("Erreur de décodage UTF-8 (stdout): {}", e))?;
-                let stderr = String::from_utf8(result.stderr)
-                    .map_err(|e| anyhow::anyhow!("Erreur de décodage UTF-8 (stderr): {}", e))?;
-                
-                if !stderr.is_empty() {
-                    tracing::warn!("⚠️ Erreur (stderr) suppression globale: {}", stderr.trim());
-                }
+    #[cfg(not(target_os = "windows"))]
+    fn ensure_dscp_nla_setting() -> Result<bool> {
+        Ok(false)
+    }
 
-                #[derive(Deserialize, Debug)]
-                struct CleanupResult {
-                    ProviderRemoved: usize,
-                    RegistryRemoved: usize,
+    /// Create a single policy with the configured backend
+    fn create_policy_from_spec(&self, spec: &QosPolicySpec) -> Result<()> {
+        #[cfg(target_os = "windows")]
+        if self.qos_backend == QosBackend::Wmi {
+            let started = Instant::now();
+            match qos_wmi::create_policy_from_spec(spec) {
+                Ok(()) => {
+                    tracing::debug!("✅ Politique QoS créée via WMI: {} (⏱️ {} ms)", spec.name, started.elapsed().as_millis());
+                    return Ok(());
                 }
-
-                if let Ok(json_result) = serde_json::from_str::<CleanupResult>(stdout.trim()) {
-                    tracing::info!("✅ Suppression globale terminée. Fournisseur: {}, Registre: {}", 
-                        json_result.ProviderRemoved, json_result.RegistryRemoved);
-                } else {
-                    tracing::warn!("⚠️ Réponse JSON invalide du script de nettoyage: {}. Stderr: {}", stdout.trim(), stderr.trim());
+                Err(e) => {
+                    tracing::warn!("⚠️ Création QoS WMI de {} échouée, fallback PowerShell: {}", spec.name, e);
                 }
-                
-                Ok(())
             }
-            Err(e) => {
-                let error_msg = format!("Impossible d'exécuter suppression globale PowerShell: {}", e);
-                tracing::warn!("⚠️ {}", error_msg);
-                Ok(())
+        }
+
+        let mut arguments = format!("-Name '{}'", spec.name.replace('\'', "''"));
+        if let Some(app_name) = spec.app_name {
+            arguments.push_str(&format!(" -AppPathNameMatchCondition '{}'", app_name.replace('\'', "''")));
+        }
+        if spec.default_match {
+            arguments.push_str(" -Default");
+        }
+        if let Some(bits) = spec.throttle_bits_per_second {
+            arguments.push_str(&format!(" -ThrottleRateActionBitsPerSecond {}", bits));
+        }
+        if let Some(dscp) = spec.dscp {
+            arguments.push_str(&format!(" -DSCPAction {}", dscp));
+        }
+        if let Some(prefix) = spec.src_prefix {
+            arguments.push_str(&format!(" -IPSrcPrefixMatchCondition '{}'", prefix.replace('\'', "''")));
+        }
+        if let Some(rule) = spec.port_rule {
+            let condition = rule.direction.condition_prefix();
+            let (start, end) = rule.port_range;
+            arguments.push_str(&format!(" -IPProtocolMatchCondition {}", rule.protocol.label()));
+            if start == end {
+                arguments.push_str(&format!(" -{}MatchCondition {}", condition, start));
+            } else {
+                arguments.push_str(&format!(" -{0}StartMatchCondition {1} -{0}EndMatchCondition {2}", condition, start, end));
             }
         }
-    }
 
-    /// Clear all QoS limitations (public interface)
-    pub fn clear_all_limits(&mut self) -> Result<()> {
-        // Clear internal tracking first
-        let pids_to_clear: Vec<u32> = if let Ok(limited) = self.limited_processes.lock() {
-            limited.keys().copied().collect()
+        let script = format!(
+            r#"
+$ErrorActionPreference = "Stop"
+Remove-NetQosPolicy -Name '{0}' -Confirm:$false -ErrorAction SilentlyContinue
+New-NetQosPolicy {1} -Confirm:$false | Out-Null
+Write-Output "SUCCESS"
+            "#,
+            spec.name.replace('\'', "''"),
+            arguments
+        );
+
+        let stdout = self.run_hidden_powershell(&script)?;
+        if stdout.contains("SUCCESS") {
+            tracing::info!("✅ Politique QoS créée via PowerShell: {}", spec.name);
+            Ok(())
         } else {
-            Vec::new()
-        };
-        
-        for pid in pids_to_clear {
-            let _ = self.remove_process_limit(pid);
+            Err(anyhow::anyhow!("Échec création politique QoS {}: {}", spec.name, stdout.trim()))
         }
-        
-        if let Ok(mut limited) = self.limited_processes.lock() {
-            limited.clear();
-        }
-        
-        // Then clear all QoS policies
-        self.clear_all_qos_policies()
     }
 
-    /// Get all processes managed by this limiter
-    pub fn get_processes(&self) -> Vec<&NetworkProcessInfo> {
-        self.processes.values().collect()
+    /// Remove every policy starting with `prefix` with the configured backend
+    fn remove_policies_with_prefix(&self, prefix: &str) -> Result<usize> {
+        #[cfg(target_os = "windows")]
+        if self.qos_backend == QosBackend::Wmi {
+            match qos_wmi::remove_policies_with_prefix(prefix) {
+                Ok(removed) => return Ok(removed),
+                Err(e) => tracing::warn!("⚠️ Suppression QoS WMI {}* échouée, fallback PowerShell: {}", prefix, e),
+            }
+        }
+
+        let script = format!(
+            r#"
+$policies = @(Get-NetQosPolicy -ErrorAction SilentlyContinue | Where-Object {{ $_.Name -like '{0}*' }})
+$policies | Remove-NetQosPolicy -Confirm:$false -ErrorAction SilentlyContinue
+Write-Output $policies.Count
+            "#,
+            prefix.replace('\'', "''")
+        );
+        let stdout = self.run_hidden_powershell(&script)?;
+        Ok(stdout.trim().lines().last().and_then(|l| l.trim().parse().ok()).unwrap_or(0))
     }
 
-    /// Check if a process is currently limited
-    pub fn is_process_limited(&self, pid: u32) -> bool {
-        if let Ok(limited) = self.limited_processes.lock() {
-            limited.contains_key(&pid)
-        } else {
-            false
+    /// Run a PowerShell script without a visible window and return its stdout
+    fn run_hidden_powershell(&self, script: &str) -> Result<String> {
+        let output = self.runner.powershell(script)?;
+        if !output.stderr.trim().is_empty() {
+            tracing::warn!("⚠️ Avertissements PowerShell: {}", output.stderr.trim());
         }
+        Ok(output.stdout)
     }
 
-    /// Get the current speed limit for a process
-    pub fn get_process_limit(&self, pid: u32) -> Option<u32> {
-        if let Ok(limited) = self.limited_processes.lock() {
-            limited.get(&pid).copied()
-        } else {
-            None
-        }
+    /// Remove the limit of `pid` for the bulk path, which reports a 0 KB/s `LimitChange`
+    fn remove_limit_change(&mut self, pid: u32) -> Result<LimitChange> {
+        let previous = self.current_limit(pid);
+        self.remove_process_limit(pid)?;
+        Ok(LimitChange { previous, new: 0, policy_recreated: false })
     }
 
-    /// Get network statistics
-    pub fn get_network_stats(&self) -> NetworkStats {
-        let total_upload = self.processes.values().map(|p| p.current_upload_speed).sum();
-        let total_download = self.processes.values().map(|p| p.current_download_speed).sum();
-        let limited_count = self.processes.values().filter(|p| p.is_limited).count();
-        
-        NetworkStats {
-            total_upload_bytes: total_upload,
-            total_download_bytes: total_download,
-            total_processes: self.processes.len(),
-            limited_processes_count: limited_count,
+    /// Limit several processes at once (one WMI session or one PowerShell script).
+    /// PIDs sharing the same executable get a single policy; a PID already at the requested
+    /// limit keeps its policy, as with `set_process_speed_limit`. Returns the outcome per PID,
+    /// see `LimitSummary::from_outcomes` for the counts.
+    pub fn set_speed_limits_bulk(&mut self, limits: &[(u32, u32)]) -> Vec<(u32, Result<LimitChange>)> {
+        self.ensure_policies_restored();
+        if let Err(unavailable) = self.ensure_qos_available() {
+            return limits.iter().map(|&(pid, _)| (pid, Err(unavailable.clone().into()))).collect();
+        }
+        #[cfg(target_os = "linux")]
+        {
+            limits
+                .iter()
+                .map(|&(pid, limit_kbps)| {
+                    let result = if limit_kbps == 0 {
+                        self.remove_limit_change(pid)
+                    } else {
+                        self.set_process_speed_limit(pid, limit_kbps, None)
+                    };
+                    (pid, result)
+                })
+                .collect()
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            self.set_speed_limits_bulk_qos(limits)
         }
     }
 
-    /// Verify if QoS policies are active using Windows Group Policy (JSON output)
-    #[cfg(target_os = "windows")]
-    pub fn verify_qos_policies(&self) -> Result<Vec<QosPolicyInfo>> {
-        tracing::info!("📋 Vérification des politiques QoS via JSON...");
-        
-        let powershell_script = r#"
-$ErrorActionPreference = "SilentlyContinue"
-$OutputEncoding = [System.Text.Encoding]::UTF8
-[System.Threading.Thread]::CurrentThread.CurrentCulture = 'en-US'
+    /// Processes of the last scan that `limit_all_except(selected)` would limit: every process
+    /// with some traffic, except the selection (and any other PID of its executables, which
+    /// would share their policy), the already limited ones, `BULK_LIMIT_DENYLIST` and the
+    /// processes `safety` protects
+    pub fn preview_limit_all_except(&self, selected: &HashSet<u32>) -> BulkLimitPreview {
+        let mut preview = BulkLimitPreview {
+            selected_exe_names: selected.iter().filter_map(|&pid| self.qos_app_name(pid).ok()).collect(),
+            ..Default::default()
+        };
+        preview.selected_exe_names.sort_by_key(|name| name.to_lowercase());
+        preview.selected_exe_names.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
 
-$policiesFound = @()
+        let own_pid = std::process::id();
+        for process in self.processes.values() {
+            if selected.contains(&process.pid) || process.pid == own_pid || self.is_process_limited(process.pid) {
+                continue;
+            }
+            if process.current_download_speed + process.current_upload_speed < BULK_LIMIT_MIN_ACTIVITY {
+                continue;
+            }
+            if is_bulk_protected(&process.name) || process.protection.is_protected() {
+                preview.protected.push(process.name.clone());
+                continue;
+            }
+            let app_name = match self.qos_app_name(process.pid) {
+                Ok(app_name) => app_name,
+                Err(e) => {
+                    tracing::debug!("⏭️ {} (PID {}) ignoré: {}", process.name, process.pid, e);
+                    continue;
+                }
+            };
+            if preview.selected_exe_names.iter().any(|name| name.eq_ignore_ascii_case(&app_name)) {
+                continue;
+            }
+            preview.targets.push((process.pid, app_name));
+        }
 
-# Source de vérité: Get-NetQosPolicy
-$allPolicies = Get-NetQosPolicy | Where-Object { $_.Name -like "GameBooster*" }
-foreach ($policy in $allPolicies) {
-    $policiesFound += [PSCustomObject]@{
-        Name = $policy.Name
-        AppName = $policy.AppPathNameMatchCondition
-        ThrottleBits = $policy.ThrottleRateActionBitsPerSecond
-        IsRegistryOnly = $false
+        preview.targets.sort_by(|a, b| a.1.to_lowercase().cmp(&b.1.to_lowercase()).then(a.0.cmp(&b.0)));
+        preview.protected.sort_by_key(|name| name.to_lowercase());
+        preview.protected.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        preview
     }
-}
 
-# Vérifier les politiques orphelines dans le registre
-$regPath = "HKLM:\SOFTWARE\Policies\Microsoft\Windows\QoS"
-if (Test-Path $regPath) {
-    $regPolicies = Get-ChildItem -Path $regPath | Where-Object { $_.PSChildName -like "GameBooster*" }
-    foreach ($regKey in $regPolicies) {
-        $policyName = $regKey.PSChildName
-        if (-not ($allPolicies | Where-Object { $_.Name -eq $policyName })) {
-            $policiesFound += [PSCustomObject]@{
-                Name = $policyName
-                AppName = (Get-ItemProperty -Path $regKey.PSPath)."Application Name"
-                ThrottleBits = (Get-ItemProperty -Path $regKey.PSPath)."Throttle Rate"
-                IsRegistryOnly = $true
+    /// Limit the targets of `preview` to `limit_kbps` as one batch, and mark the selection
+    /// as priority traffic when `exempt_selected`. The batch is kept for `undo_limit_batch`.
+    pub fn limit_all_except(
+        &mut self,
+        preview: &BulkLimitPreview,
+        limit_kbps: u32,
+        exempt_selected: bool,
+    ) -> (LimitBatch, Vec<(u32, Result<LimitChange>)>) {
+        // La liste d'exclusion prime sur un aperçu construit ailleurs
+        let limits: Vec<(u32, u32)> = preview
+            .targets
+            .iter()
+            .filter(|(pid, app_name)| {
+                let name = self.processes.get(pid).map(|process| process.name.as_str()).unwrap_or(app_name);
+                !is_bulk_protected(name) && !self.process_protection(*pid).is_protected()
+            })
+            .map(|(pid, _)| (*pid, limit_kbps))
+            .collect();
+        let outcomes = self.set_speed_limits_bulk(&limits);
+
+        let mut batch = LimitBatch {
+            id: self.next_batch_id,
+            policies: outcomes
+                .iter()
+                .filter(|(_, result)| result.is_ok())
+                .map(|(pid, _)| (*pid, self.policy_name_for(*pid)))
+                .collect(),
+            exempted_exe_names: Vec::new(),
+        };
+        self.next_batch_id += 1;
+
+        if exempt_selected {
+            for exe_name in &preview.selected_exe_names {
+                // Un marquage posé par l'utilisateur n'appartient pas au lot
+                let policy_name = Self::dscp_policy_name(exe_name);
+                if self.managed_policies.iter().any(|policy| policy.name == policy_name) {
+                    continue;
+                }
+                match self.set_process_dscp(exe_name, EXEMPTION_DSCP) {
+                    Ok(()) => batch.exempted_exe_names.push(exe_name.clone()),
+                    Err(e) => tracing::warn!("⚠️ Exemption de {} impossible: {}", exe_name, e),
+                }
             }
         }
-    }
-}
 
-$policiesFound | ForEach-Object {
-    if (-not $_.ThrottleBits) {
-        $_.ThrottleBits = 0
+        tracing::info!(
+            "📦 Lot #{}: {} processus limités à {} KB/s, {} exemption(s)",
+            batch.id,
+            batch.policies.len(),
+            limit_kbps,
+            batch.exempted_exe_names.len()
+        );
+        self.limit_batches.push(batch.clone());
+        (batch, outcomes)
     }
-    if (-not $_.AppName) {
-        $_.AppName = "N/A"
+
+    /// Remove the policies created by the batch `id`. A PID limited again since then (other
+    /// policy name) keeps its new limit. Returns the failures.
+    pub fn undo_limit_batch(&mut self, id: u64) -> Result<Vec<String>> {
+        let position = self
+            .limit_batches
+            .iter()
+            .position(|batch| batch.id == id)
+            .ok_or_else(|| anyhow::anyhow!("Lot #{} inconnu ou déjà annulé", id))?;
+        let batch = self.limit_batches.remove(position);
+
+        let mut errors = Vec::new();
+        let mut pids: Vec<(&u32, &String)> = batch.policies.iter().collect();
+        pids.sort();
+        for (&pid, policy_name) in pids {
+            // Une politique partagée est levée par le premier PID : les suivants sont déjà libres
+            if !self.is_process_limited(pid) || self.policy_name_for(pid) != *policy_name {
+                continue;
+            }
+            if let Err(e) = self.remove_process_limit(pid) {
+                tracing::error!("❌ Annulation du lot #{}: PID {}: {}", id, pid, e);
+                errors.push(format!("PID {} : {:#}", pid, e));
+            }
+        }
+        for exe_name in &batch.exempted_exe_names {
+            if let Err(e) = self.remove_process_dscp(exe_name) {
+                tracing::error!("❌ Annulation du lot #{}: exemption {}: {}", id, exe_name, e);
+                errors.push(format!("{} : {:#}", exe_name, e));
+            }
+        }
+
+        tracing::info!("↩️ Lot #{} annulé ({} échec(s))", id, errors.len());
+        Ok(errors)
     }
-}
 
-$policiesFound | ConvertTo-Json -Compress
-        "#;
+    #[cfg(not(target_os = "linux"))]
+    fn set_speed_limits_bulk_qos(&mut self, limits: &[(u32, u32)]) -> Vec<(u32, Result<LimitChange>)> {
+        let mut outcomes: Vec<(u32, Result<LimitChange>)> = Vec::new();
 
-        let mut command = Command::new("powershell.exe");
-            command.args(["-NoProfile", "-WindowStyle", "Hidden", "-ExecutionPolicy", "Bypass", "-Command", powershell_script]);
-        
-        #[cfg(target_os = "windows")] // This is technically redundant here due to the function's cfg, but good for clarity
-        command.creation_flags(0x08000000); // CREATE_NO_WINDOW
-            
-        let output = command.output();
+        // Regrouper par exécutable : une seule politique par nom d'exe
+        struct PolicyGroup {
+            policy_name: String,
+            app_name: String,
+            limit_kbps: u32,
+            pids: Vec<(u32, Option<u32>)>,
+        }
+        let mut groups: Vec<PolicyGroup> = Vec::new();
 
-        match output {
-            Ok(result) => {
-                let stdout = String::from_utf8(result.stdout)
-                    .map_err(|e| anyhow::anyhow!("Erreur de décodage UTF-8 (stdout): {}", e))?;
-                let stderr = String::from_utf8(result.stderr)
-                    .map_err(|e| anyhow::anyhow!("Erreur de décodage UTF-8 (stderr): {}", e))?;
-                
-                if !stderr.is_empty() {
-                    tracing::warn!("⚠️ Avertissements vérification QoS JSON: {}", stderr.trim());
-                }
-                
-                // Le script retourne "[]" si aucune politique n'est trouvée
-                let policies: Vec<QosPolicyInfo> = serde_json::from_str(stdout.trim())
-                    .map_err(|e| anyhow::anyhow!("Erreur parsing JSON des politiques: {}. Output: '{}'", e, stdout))?;
+        // Une seule liste des politiques pour tous les PID déjà à la limite demandée
+        let same_limit: Vec<u32> = limits
+            .iter()
+            .filter(|&&(pid, limit_kbps)| {
+                limit_kbps != 0 && self.current_limit(pid) == Some(limit_kbps) && self.policy_scope(pid).is_none()
+            })
+            .map(|&(pid, _)| pid)
+            .collect();
+        let in_place = self.limits_in_place(&same_limit);
 
-                tracing::info!("📋 {} politiques QoS actives trouvées via JSON.", policies.len());
+        for &(pid, limit_kbps) in limits {
+            if limit_kbps == 0 {
+                outcomes.push((pid, self.remove_limit_change(pid)));
+                continue;
+            }
+            if let Err(e) = self.ensure_not_critical(pid, "limiter") {
+                outcomes.push((pid, Err(e)));
+                continue;
+            }
+            let previous = self.current_limit(pid);
+            if in_place.contains(&pid) {
+                tracing::info!("⏭️ PID {} déjà limité à {} KB/s, politique conservée", pid, limit_kbps);
+                outcomes.push((pid, Ok(LimitChange { previous, new: limit_kbps, policy_recreated: false })));
+                continue;
+            }
 
-                for policy in &policies {
-                    let rate_mbps = policy.throttle_bits as f64 / (1024.0 * 1024.0 * 8.0);
-                    let registry_tag = if policy.is_registry_only { "(registre seulement)" } else { "" };
-                    tracing::info!("  - Nom: {}, App: {}, Limite: {:.2} MB/s {}", policy.name, policy.app_name, rate_mbps, registry_tag);
+            let app_name = match self.qos_app_name(pid) {
+                Ok(app_name) => app_name,
+                Err(e) => {
+                    outcomes.push((pid, Err(e)));
+                    continue;
                 }
-                
-                Ok(policies)
-            }
-            Err(e) => {
-                tracing::error!("❌ Erreur exécution vérification QoS JSON: {}", e);
-                Err(anyhow::anyhow!("Erreur vérification QoS: {}", e))
+            };
+            if let Some(group) = groups.iter_mut().find(|g| g.app_name.eq_ignore_ascii_case(&app_name)) {
+                if group.limit_kbps != limit_kbps {
+                    tracing::warn!("⚠️ {} (PID {}) partage la politique {} : limite {} KB/s conservée",
+                        app_name, pid, group.policy_name, group.limit_kbps);
+                }
+                group.pids.push((pid, previous));
+            } else {
+                // Le nom déjà utilisé par ce PID : la nouvelle limite remplace l'ancienne politique
+                groups.push(PolicyGroup {
+                    policy_name: self.policy_name_for(pid),
+                    app_name,
+                    limit_kbps,
+                    pids: vec![(pid, previous)],
+                });
             }
         }
-    }
 
-    /// Placeholder for Linux QoS verification
-    #[cfg(not(target_os = "windows"))]
-    pub fn verify_qos_policies(&self) -> Result<Vec<QosPolicyInfo>> {
-        tracing::info!("📋 Vérification des politiques QoS (Linux stub - non implémenté)");
-        // Retourner un vecteur vide ou une erreur appropriée pour Linux
-        Ok(Vec::new())
-    }
+        if groups.is_empty() {
+            return outcomes;
+        }
 
-    /// Get a summary of active QoS limitations
-    pub fn get_qos_summary(&self) -> String {
-        match self.verify_qos_policies() {
-            Ok(policies) => {
-                if policies.is_empty() {
-                    "🔍 Aucune politique QoS active".to_string()
-                } else {
-                    let summary_lines: Vec<String> = policies.iter().map(|p| {
-                        let rate_mbps = p.throttle_bits as f64 / (1024.0 * 1024.0 * 8.0);
-                        format!("- {}: {:.2} MB/s pour {}", p.name, rate_mbps, p.app_name)
-                    }).collect();
-                    format!("🎯 {} politiques QoS actives:\n{}", policies.len(), summary_lines.join("\n"))
+        let requests: Vec<(String, String, u64)> = groups
+            .iter()
+            .map(|g| (g.policy_name.clone(), g.app_name.clone(), (g.limit_kbps as u64) * 1024 * 8))
+            .collect();
+
+        let started = Instant::now();
+        let group_results = self.create_qos_policies_bulk(&requests);
+        tracing::info!("📦 {} politique(s) QoS traitée(s) en lot pour {} processus (⏱️ {} ms)",
+            requests.len(), limits.len(), started.elapsed().as_millis());
+
+        for (group, result) in groups.into_iter().zip(group_results) {
+            if result.is_ok() {
+                self.remember_policy(&group.policy_name, &group.app_name, (group.limit_kbps as u64) * 1024 * 8);
+            }
+            for (pid, previous) in group.pids {
+                match &result {
+                    Ok(()) => {
+                        if let Ok(mut limited) = self.limited_processes.lock() {
+                            limited.insert(pid, group.limit_kbps);
+                        }
+                        if let Some(process) = self.processes.get_mut(&pid) {
+                            process.is_limited = true;
+                            process.speed_limit = Some(group.limit_kbps);
+                        }
+                        self.policy_names.insert(pid, group.policy_name.clone());
+                        // Même limite sans politique trouvée : elle avait disparu
+                        let change = LimitChange {
+                            previous,
+                            new: group.limit_kbps,
+                            policy_recreated: same_limit.contains(&pid),
+                        };
+                        outcomes.push((pid, Ok(change)));
+                    }
+                    Err(message) => {
+                        outcomes.push((pid, Err(anyhow::anyhow!(message.clone()))));
+                    }
                 }
             }
-            Err(e) => format!("❌ Impossible de vérifier les politiques QoS: {}", e)
         }
+
+        outcomes
     }
 
-    /// REAL bandwidth limitation using real-time packet interception (NO REBOOT REQUIRED)
-    pub fn set_process_speed_limit(&mut self, pid: u32, limit_kbps: u32) -> Result<()> {
-        // Add to limited processes list
-        if let Ok(mut limited) = self.limited_processes.lock() {
-            limited.insert(pid, limit_kbps);
+    /// Create several policies, returning one result per (policy, app, bits) request
+    #[cfg_attr(target_os = "linux", allow(dead_code))]
+    fn create_qos_policies_bulk(&self, requests: &[(String, String, u64)]) -> Vec<std::result::Result<(), String>> {
+        #[cfg(target_os = "windows")]
+        if self.qos_backend == QosBackend::Wmi {
+            // La connexion WMI est réutilisée pour toutes les politiques du lot
+            let results: Vec<std::result::Result<(), String>> = requests
+                .iter()
+                .map(|(policy_name, app_name, bits)| {
+                    qos_wmi::create_policy(policy_name, app_name, *bits).map_err(|e| e.to_string())
+                })
+                .collect();
+            if results.iter().any(|r| r.is_ok()) {
+                return results;
+            }
+            tracing::warn!("⚠️ Création QoS WMI en lot entièrement échouée, fallback PowerShell");
         }
-        
-        // Update process info
-        if let Some(process) = self.processes.get_mut(&pid) {
-            process.is_limited = true;
-            process.speed_limit = Some(limit_kbps);
+
+        self.create_qos_policies_bulk_powershell(requests)
+    }
+
+    /// Single PowerShell script creating every policy of the batch
+    #[cfg_attr(target_os = "linux", allow(dead_code))]
+    fn create_qos_policies_bulk_powershell(&self, requests: &[(String, String, u64)]) -> Vec<std::result::Result<(), String>> {
+        let entries: Vec<String> = requests
+            .iter()
+            .map(|(policy_name, app_name, bits)| {
+                format!("@{{ Name = '{}'; App = '{}'; Bits = [long]{} }}",
+                    policy_name.replace('\'', "''"), app_name.replace('\'', "''"), bits)
+            })
+            .collect();
+
+        let powershell_script = format!(
+            r#"
+$ErrorActionPreference = "Stop"
+$OutputEncoding = [System.Text.Encoding]::UTF8
+[System.Threading.Thread]::CurrentThread.CurrentCulture = 'en-US'
+
+$requests = @(
+    {}
+)
+$results = @()
+
+foreach ($req in $requests) {{
+    $result = @{{ PolicyName = $req.Name; Success = $false; Message = "" }}
+    try {{
+        Remove-NetQosPolicy -Name $req.Name -Confirm:$false -ErrorAction SilentlyContinue
+        New-NetQosPolicy -Name $req.Name -AppPathNameMatchCondition $req.App -ThrottleRateActionBitsPerSecond $req.Bits -Confirm:$false | Out-Null
+        $verification = Get-NetQosPolicy -Name $req.Name
+        if ($verification -and $verification.ThrottleRateActionBitsPerSecond -eq $req.Bits) {{
+            $result.Success = $true
+        }} else {{
+            $result.Message = "Verification failed"
+        }}
+    }} catch {{
+        $result.Message = "PowerShell Error: $($_.Exception.Message)"
+    }}
+    $results += [PSCustomObject]$result
+}}
+
+ConvertTo-Json -InputObject @($results) -Compress
+            "#,
+            entries.join("\n    ")
+        );
+
+        let output = self.runner.powershell(&powershell_script);
+
+        #[derive(Deserialize)]
+        #[allow(non_snake_case)]
+        struct BulkResult {
+            PolicyName: String,
+            Success: bool,
+            Message: String,
         }
-        
-        // Apply ENHANCED QoS limitation with fallback
-        match self.apply_netsh_qos_limit(pid, limit_kbps) {
-            Ok(()) => {
-                tracing::info!("✅ Limitation QoS principale appliquée: PID {} → {} KB/s", pid, limit_kbps);
+
+        let parsed: std::result::Result<Vec<BulkResult>, String> = match output {
+            Ok(result) => {
+                let stdout = &result.stdout;
+                parse_json_list::<BulkResult>(stdout)
+                    .map_err(|e| format!("Réponse JSON invalide du script QoS en lot: {}", e))
             }
-            Err(e) => {
-                tracing::warn!("⚠️ Limitation QoS principale échouée, application du fallback temps réel: {}", e);
-                // Utiliser seulement la méthode temps réel si QoS échoue
-                self.apply_netsh_qos_limit_realtime(pid, limit_kbps)?;
+            Err(e) => Err(format!("Impossible d'exécuter PowerShell QoS: {}", e)),
+        };
+
+        match parsed {
+            Ok(results) => requests
+                .iter()
+                .map(|(policy_name, _, _)| match results.iter().find(|r| &r.PolicyName == policy_name) {
+                    Some(r) if r.Success => Ok(()),
+                    Some(r) => Err(format!("Échec création politique QoS: {}", r.Message)),
+                    None => Err(format!("Politique {} absente de la réponse", policy_name)),
+                })
+                .collect(),
+            Err(message) => {
+                tracing::error!("❌ {}", message);
+                requests.iter().map(|_| Err(message.clone())).collect()
             }
         }
-        
-        tracing::info!("✅ Limitation COMPLÈTE appliquée: PID {} → {} KB/s (actif immédiatement)", pid, limit_kbps);
-        Ok(())
     }
 }
 
+/// Whether a policy AppPathNameMatchCondition (file name or full path) targets `exe_path`
+#[cfg(not(target_os = "linux"))]
+fn app_name_matches(app_name: &str, exe_path: &Path) -> bool {
+    if Path::new(app_name).is_absolute() {
+        exe_path.to_string_lossy().eq_ignore_ascii_case(app_name)
+    } else {
+        exe_path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().eq_ignore_ascii_case(app_name))
+    }
+}
+
+/// Resolve the image path of a process when sysinfo could not read it
+#[cfg(target_os = "windows")]
+pub(crate) fn query_full_image_path(pid: u32) -> Option<PathBuf> {
+    use std::os::windows::ffi::OsStringExt;
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return None;
+        }
+
+        let mut buffer = [0u16; 1024];
+        let mut len = buffer.len() as u32;
+        let ok = QueryFullProcessImageNameW(handle, PROCESS_NAME_WIN32, buffer.as_mut_ptr(), &mut len);
+        CloseHandle(handle);
+
+        if ok == 0 {
+            return None;
+        }
+        Some(PathBuf::from(std::ffi::OsString::from_wide(&buffer[..len as usize])))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn query_full_image_path(_pid: u32) -> Option<PathBuf> {
+    None
+}
+
 /// Network statistics
 #[derive(Debug, Clone)]
 pub struct NetworkStats {
@@ -1026,6 +3777,31 @@ pub struct NetworkStats {
     pub limited_processes_count: usize,
 }
 
+/// Parse `ConvertTo-Json` output as a list. PowerShell prints nothing for an empty
+/// collection and a bare object (no array) when it holds a single element.
+fn parse_json_list<T: serde::de::DeserializeOwned>(output: &str) -> serde_json::Result<Vec<T>> {
+    let output = output.trim();
+    if output.is_empty() || output == "null" {
+        Ok(Vec::new())
+    } else if output.starts_with('[') {
+        serde_json::from_str(output)
+    } else {
+        serde_json::from_str(output).map(|item| vec![item])
+    }
+}
+
+/// Policies listed by the PowerShell fallback of `list_qos_policies_with`
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn parse_qos_policies(output: &str) -> Result<Vec<QosPolicyInfo>> {
+    // ConvertTo-Json : rien si aucune politique, un objet seul s'il n'y en a qu'une
+    let mut policies: Vec<QosPolicyInfo> = parse_json_list(output)
+        .map_err(|e| anyhow::anyhow!("Erreur parsing JSON des politiques: {}. Output: '{}'", e, output))?;
+    for policy in &mut policies {
+        policy.package_family_name = appx::family_name_from_path(&policy.app_name);
+    }
+    Ok(policies)
+}
+
 // Fonctions utilitaires pour l'interface utilisateur
 pub fn format_speed(bytes_per_sec: u64) -> String {
     if bytes_per_sec >= 1024 * 1024 {
@@ -1037,17 +3813,369 @@ pub fn format_speed(bytes_per_sec: u64) -> String {
     }
 }
 
-// FONCTION pour parser les Mo/s dans l'interface utilisateur
+/// Unit of the speed limit input. KB and MB are binary (1 KB = 1024 bytes) like the rest of the
+/// limiter; Mbps is decimal, like the speed of a network link.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpeedUnit {
+    KiloBytesPerSec,
+    #[default]
+    MegaBytesPerSec,
+    MegabitsPerSec,
+}
+
+impl SpeedUnit {
+    pub const ALL: [SpeedUnit; 3] = [SpeedUnit::KiloBytesPerSec, SpeedUnit::MegaBytesPerSec, SpeedUnit::MegabitsPerSec];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SpeedUnit::KiloBytesPerSec => "KB/s",
+            SpeedUnit::MegaBytesPerSec => "MB/s",
+            SpeedUnit::MegabitsPerSec => "Mbps",
+        }
+    }
+
+    /// KB/s in one unit of `self`
+    fn kbps_factor(self) -> f64 {
+        match self {
+            SpeedUnit::KiloBytesPerSec => 1.0,
+            SpeedUnit::MegaBytesPerSec => 1024.0,
+            SpeedUnit::MegabitsPerSec => 1_000_000.0 / 8.0 / 1024.0,
+        }
+    }
+}
+
+/// Below this QoS drops almost every packet instead of pacing them
+pub const MIN_SPEED_LIMIT_KBPS: u32 = 16;
+/// 10 Gbit/s: beyond any consumer link, a larger value is a typo
+pub const MAX_SPEED_LIMIT_KBPS: u32 = 1_220_703;
+
+/// Why a speed limit input is refused
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpeedLimitError {
+    /// Not a number, or negative
+    Invalid,
+    /// 0 means "no limit" for the policies: removing it is a separate action
+    Zero,
+    TooLow { kbps: u32 },
+    TooHigh { kbps: u32 },
+}
+
+impl std::fmt::Display for SpeedLimitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpeedLimitError::Invalid => write!(f, "Valeur invalide : un nombre positif est attendu"),
+            SpeedLimitError::Zero => write!(f, "Une limite de 0 ne limite rien : utilisez « Supprimer la limite »"),
+            SpeedLimitError::TooLow { kbps } => {
+                write!(f, "{} KB/s est trop bas, minimum {} KB/s", kbps, MIN_SPEED_LIMIT_KBPS)
+            }
+            SpeedLimitError::TooHigh { kbps } => {
+                write!(f, "{} KB/s dépasse le maximum de {} KB/s (10 Gbps)", kbps, MAX_SPEED_LIMIT_KBPS)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SpeedLimitError {}
+
+/// Convert `value` in `unit` to whole KB/s, within `MIN_SPEED_LIMIT_KBPS..=MAX_SPEED_LIMIT_KBPS`
+pub fn parse_speed_limit(value: f64, unit: SpeedUnit) -> std::result::Result<u32, SpeedLimitError> {
+    if !value.is_finite() || value < 0.0 {
+        return Err(SpeedLimitError::Invalid);
+    }
+    if value == 0.0 {
+        return Err(SpeedLimitError::Zero);
+    }
+    let kbps = (value * unit.kbps_factor()).round();
+    if kbps > MAX_SPEED_LIMIT_KBPS as f64 {
+        return Err(SpeedLimitError::TooHigh { kbps: kbps.min(u32::MAX as f64) as u32 });
+    }
+    if kbps < MIN_SPEED_LIMIT_KBPS as f64 {
+        return Err(SpeedLimitError::TooLow { kbps: kbps as u32 });
+    }
+    Ok(kbps as u32)
+}
+
+/// Whether `netsh interface tcp show global` reports the receive window auto-tuning at
+/// "restricted". The labels are translated but not the values, and no other global TCP
+/// parameter takes this one ("highlyrestricted" is a deliberate choice, left alone).
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+fn autotuning_restricted(netsh_output: &str) -> bool {
+    netsh_output
+        .lines()
+        .filter_map(|line| line.rsplit_once(':'))
+        .any(|(_, value)| value.trim().eq_ignore_ascii_case("restricted"))
+}
+
+/// "1024 KB/s ≈ 8.4 Mbps": the value that will be applied, in both families of units
+pub fn describe_speed_limit(kbps: u32) -> String {
+    let mbits = kbps as f64 * 1024.0 * 8.0 / 1_000_000.0;
+    format!("{} KB/s ≈ {:.1} Mbps", kbps, mbits)
+}
+
+/// Text input in MB/s (global cap, command line), with the bounds of `parse_speed_limit`
 pub fn parse_speed_limit_mbps(input: &str) -> Result<f64> {
-    let input = input.trim();
-    
-    // Parse directement en Mo/s (pas d'unité nécessaire)
-    let mbps: f64 = input.parse().map_err(|_| anyhow::anyhow!("Format invalide"))?;
-    
-    if mbps < 0.0 {
-        return Err(anyhow::anyhow!("La vitesse ne peut pas être négative"));
-    }
-    
+    let mbps: f64 = input.trim().parse().map_err(|_| SpeedLimitError::Invalid)?;
+    parse_speed_limit(mbps, SpeedUnit::MegaBytesPerSec)?;
     Ok(mbps)
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn speed_units_convert_to_kbps() {
+        assert_eq!(parse_speed_limit(512.0, SpeedUnit::KiloBytesPerSec), Ok(512));
+        assert_eq!(parse_speed_limit(1.0, SpeedUnit::MegaBytesPerSec), Ok(1024));
+        assert_eq!(parse_speed_limit(2.5, SpeedUnit::MegaBytesPerSec), Ok(2560));
+        // 1 Mbps = 1 000 000 / 8 / 1024 ≈ 122,07 KB/s
+        assert_eq!(parse_speed_limit(1.0, SpeedUnit::MegabitsPerSec), Ok(122));
+        assert_eq!(parse_speed_limit(100.0, SpeedUnit::MegabitsPerSec), Ok(12_207));
+    }
+
+    #[test]
+    fn speed_limit_bounds() {
+        assert_eq!(parse_speed_limit(MIN_SPEED_LIMIT_KBPS as f64, SpeedUnit::KiloBytesPerSec), Ok(MIN_SPEED_LIMIT_KBPS));
+        assert_eq!(parse_speed_limit(MAX_SPEED_LIMIT_KBPS as f64, SpeedUnit::KiloBytesPerSec), Ok(MAX_SPEED_LIMIT_KBPS));
+        assert_eq!(parse_speed_limit(10_000.0, SpeedUnit::MegabitsPerSec), Ok(MAX_SPEED_LIMIT_KBPS));
+        assert_eq!(parse_speed_limit(15.0, SpeedUnit::KiloBytesPerSec), Err(SpeedLimitError::TooLow { kbps: 15 }));
+        assert_eq!(parse_speed_limit(0.1, SpeedUnit::MegabitsPerSec), Err(SpeedLimitError::TooLow { kbps: 12 }));
+        assert_eq!(
+            parse_speed_limit((MAX_SPEED_LIMIT_KBPS + 1) as f64, SpeedUnit::KiloBytesPerSec),
+            Err(SpeedLimitError::TooHigh { kbps: MAX_SPEED_LIMIT_KBPS + 1 })
+        );
+        assert_eq!(parse_speed_limit(1e12, SpeedUnit::MegaBytesPerSec), Err(SpeedLimitError::TooHigh { kbps: u32::MAX }));
+    }
+
+    #[test]
+    fn speed_limit_rejects_zero_and_invalid_values() {
+        assert_eq!(parse_speed_limit(0.0, SpeedUnit::MegaBytesPerSec), Err(SpeedLimitError::Zero));
+        assert_eq!(parse_speed_limit(-1.0, SpeedUnit::MegaBytesPerSec), Err(SpeedLimitError::Invalid));
+        assert_eq!(parse_speed_limit(f64::NAN, SpeedUnit::KiloBytesPerSec), Err(SpeedLimitError::Invalid));
+        assert_eq!(parse_speed_limit(f64::INFINITY, SpeedUnit::KiloBytesPerSec), Err(SpeedLimitError::Invalid));
+        assert!(parse_speed_limit_mbps("abc").is_err());
+        assert!(parse_speed_limit_mbps("0").is_err());
+        assert_eq!(parse_speed_limit_mbps(" 2 ").unwrap(), 2.0);
+    }
+
+    #[test]
+    fn largest_limit_fits_in_bits_per_second() {
+        // Au-delà de u32 : la conversion en bits/s doit se faire en u64
+        assert_eq!((MAX_SPEED_LIMIT_KBPS as u64) * 1024 * 8, 9_999_998_976);
+    }
+
+    #[test]
+    fn describe_speed_limit_shows_both_units() {
+        assert_eq!(describe_speed_limit(1024), "1024 KB/s ≈ 8.4 Mbps");
+        assert_eq!(describe_speed_limit(MAX_SPEED_LIMIT_KBPS), "1220703 KB/s ≈ 10000.0 Mbps");
+    }
+
+    #[test]
+    fn json_list_accepts_nothing_one_object_or_an_array() {
+        assert_eq!(parse_json_list::<u32>("").unwrap(), Vec::<u32>::new());
+        assert_eq!(parse_json_list::<u32>(" null\r\n").unwrap(), Vec::<u32>::new());
+        assert_eq!(parse_json_list::<u32>("7").unwrap(), vec![7]);
+        assert_eq!(parse_json_list::<u32>("[1,2,3]").unwrap(), vec![1, 2, 3]);
+        assert!(parse_json_list::<u32>("WARNING: module absent").is_err());
+    }
+
+    #[test]
+    fn qos_policies_parse_zero_one_and_many() {
+        assert!(parse_qos_policies("").unwrap().is_empty());
+
+        let one = parse_qos_policies(
+            r#"{"Name":"GameBooster_Limit_game.exe","AppName":"game.exe","ThrottleBits":8388608,"IsRegistryOnly":false,"DSCP":46,"Protocol":null,"PortStart":null,"PortEnd":null,"PortDirection":null}"#,
+        )
+        .unwrap();
+        assert_eq!(one.len(), 1);
+        assert_eq!(one[0].app_name, "game.exe");
+        assert_eq!(one[0].dscp, Some(DSCP_EF));
+        assert_eq!(one[0].protocol, None);
+        assert_eq!(one[0].package_family_name, None);
+
+        let many = parse_qos_policies(
+            r#"[{"Name":"GameBooster_Limit_a.exe","AppName":"a.exe","ThrottleBits":0,"IsRegistryOnly":false},
+                {"Name":"GameBooster_Port_6881","AppName":"N/A","ThrottleBits":1024000,"IsRegistryOnly":false,"Protocol":"TCP","PortStart":6881,"PortEnd":6889,"PortDirection":"Destination"},
+                {"Name":"GameBooster_Limit_Store","AppName":"C:\\Program Files\\WindowsApps\\Microsoft.MinecraftUWP_1.20.0.0_x64__8wekyb3d8bbwe\\Minecraft.Windows.exe","ThrottleBits":0,"IsRegistryOnly":false}]"#,
+        )
+        .unwrap();
+        assert_eq!(many.len(), 3);
+        assert_eq!(many[1].protocol, Some(PortProtocol::Tcp));
+        assert_eq!((many[1].port_start, many[1].port_end), (Some(6881), Some(6889)));
+        assert_eq!(many[1].port_direction, Some(PortDirection::Destination));
+        assert_eq!(many[2].package_family_name.as_deref(), Some("Microsoft.MinecraftUWP_8wekyb3d8bbwe"));
+    }
+
+    #[test]
+    fn registry_only_policies_are_flagged() {
+        let policies = parse_qos_policies(
+            r#"[{"Name":"GameBooster_Limit_old.exe","AppName":"old.exe","ThrottleBits":2048000,"IsRegistryOnly":true,"DSCP":null,"Protocol":"Both","PortStart":27015,"PortEnd":27015,"PortDirection":"Source"}]"#,
+        )
+        .unwrap();
+        assert_eq!(policies.len(), 1);
+        assert!(policies[0].is_registry_only);
+        assert_eq!(policies[0].throttle_bits, 2_048_000);
+        assert_eq!(policies[0].protocol, Some(PortProtocol::Both));
+        assert_eq!(policies[0].port_direction, Some(PortDirection::Source));
+        assert!(parse_qos_policies("{\"Name\":").is_err());
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn powershell_fallback_reads_recorded_policies() {
+        use crate::system::testing::FakeRunner;
+
+        let runner = FakeRunner::printing(
+            r#"{"Name":"GameBooster_Limit_app.exe","AppName":"app.exe","ThrottleBits":8388608,"IsRegistryOnly":false,"DSCP":null}"#,
+        );
+        let policies = NetworkLimiter::list_qos_policies_with(&runner, QosBackend::PowerShell, false).unwrap();
+        assert_eq!(policies.len(), 1);
+        assert_eq!(policies[0].name, "GameBooster_Limit_app.exe");
+        assert_eq!(policies[0].throttle_bits, 8_388_608);
+        let calls = runner.calls();
+        assert_eq!(calls.len(), 1);
+        assert!(calls[0].starts_with("powershell.exe") && calls[0].contains("-like \"GameBooster*\""));
+
+        let failing = FakeRunner::new(|_, _| Err(anyhow::anyhow!("powershell.exe sans réponse")));
+        assert!(NetworkLimiter::list_qos_policies_with(&failing, QosBackend::PowerShell, true).is_err());
+    }
+
+    #[test]
+    fn limit_summary_counts_each_kind_of_change() {
+        let change = |previous, new, policy_recreated| Ok(LimitChange { previous, new, policy_recreated });
+        let outcomes = vec![
+            (1, change(None, 1024, false)),
+            (2, change(None, 512, false)),
+            (3, change(Some(1024), 512, false)),
+            (4, change(Some(512), 512, false)),
+            (5, change(Some(512), 512, true)),
+            (6, change(Some(512), 0, false)),
+            (7, Err(anyhow::anyhow!("politique refusée"))),
+        ];
+        let summary = LimitSummary::from_outcomes(&outcomes);
+        assert_eq!(
+            summary,
+            LimitSummary { added: 2, updated: 1, unchanged: 1, recreated: 1, removed: 1, failed: 1 }
+        );
+        assert_eq!(summary.succeeded(), 6);
+        assert_eq!(
+            summary.describe(),
+            "2 nouvelle(s), 1 modifiée(s), 1 inchangée(s), 1 recréée(s), 1 supprimée(s), 1 échec(s)"
+        );
+        assert_eq!(LimitSummary::default().describe(), "aucune limite");
+    }
+
+    /// The tc backend runs through a fake runner and a temporary net_cls root: asking again
+    /// for the same limit must leave the class alone, unless its cgroup has disappeared
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn same_limit_is_reported_unchanged_without_touching_the_class() {
+        use crate::system::testing::FakeRunner;
+        use crate::utils::TestDir;
+
+        let root = TestDir::new("limit_change");
+        let tc = Arc::new(FakeRunner::printing(""));
+        let mut limiter = NetworkLimiter::with_runner(Arc::new(FakeRunner::printing(""))).unwrap();
+        // Ni magasin de politiques ni vérification des droits : seul le chemin de limitation est testé
+        limiter.policies_restored = true;
+        limiter.qos_requirements =
+            Some(QosRequirements { elevated: true, netqos_available: true, check_error: None, checked_at: Local::now() });
+        limiter.linux_shaper = Some(linux_shaping::LinuxShaper::with_runner(tc.clone(), root.0.clone(), "eth0".to_string()));
+        // PID hors de la table des processus : ni critique, ni scanné
+        let pid = 4_000_000;
+
+        let added = limiter.set_process_speed_limit(pid, 1024, None).unwrap();
+        assert_eq!(added, LimitChange { previous: None, new: 1024, policy_recreated: false });
+        assert_eq!(added.describe(), "limité à 1.0 MB/s");
+        let tc_calls = tc.calls().len();
+
+        let unchanged = limiter.set_process_speed_limit(pid, 1024, None).unwrap();
+        assert!(unchanged.is_unchanged());
+        assert_eq!(unchanged.describe(), "limite inchangée (1.0 MB/s)");
+        assert_eq!(tc.calls().len(), tc_calls);
+
+        let updated = limiter.set_process_speed_limit(pid, 512, None).unwrap();
+        assert!(!updated.is_unchanged());
+        assert_eq!(updated.describe(), "limite modifiée 1.0 → 0.5 MB/s");
+        assert_eq!(tc.calls().len(), tc_calls + 1);
+        assert_eq!(limiter.current_limit(pid), Some(512));
+
+        // Cgroup supprimé par un autre outil : la même limite recrée la classe
+        std::fs::remove_dir_all(root.0.join("gamebooster_16")).unwrap();
+        let recreated = limiter.set_process_speed_limit(pid, 512, None).unwrap();
+        assert!(recreated.policy_recreated && !recreated.is_unchanged());
+        assert_eq!(recreated.describe(), "politique disparue recréée (0.5 MB/s)");
+        assert_eq!(tc.calls().len(), tc_calls + 2);
+        assert!(limiter.limits_in_place(&[pid]).contains(&pid));
+    }
+
+    #[test]
+    fn restricted_autotuning_is_detected_without_being_changed() {
+        use crate::system::testing::{output, FakeRunner};
+
+        let restricted = "Paramètres TCP globaux\r\n----------------------------------------------\r\n\
+            État de mise à l'échelle côté réception    : enabled\r\n\
+            Niveau de réglage automatique de la fenêtre de réception    : restricted\r\n\
+            Fournisseur de contrôle de congestion complémentaire    : default\r\n";
+        let runner = FakeRunner::printing(restricted);
+        assert!(NetworkLimiter::autotuning_is_restricted(&runner).unwrap());
+        assert_eq!(runner.calls(), vec!["netsh interface tcp show global"]);
+
+        for level in ["normal", "highlyrestricted", "disabled"] {
+            let shown = format!("Receive Window Auto-Tuning Level    : {}\r\nPacing Profile    : off\r\n", level);
+            assert!(!NetworkLimiter::autotuning_is_restricted(&FakeRunner::printing(&shown)).unwrap());
+        }
+
+        let failing = FakeRunner::new(|_, _| Ok(output(1, "")));
+        assert!(NetworkLimiter::autotuning_is_restricted(&failing).is_err());
+    }
+
+    #[test]
+    fn autotuning_is_reset_only_on_request() {
+        use crate::system::testing::FakeRunner;
+
+        let runner = Arc::new(FakeRunner::printing("Ok.\r\n"));
+        let mut limiter = NetworkLimiter::with_runner(runner.clone()).unwrap();
+        limiter.legacy_autotuning_restricted = true;
+        assert!(runner.calls().iter().all(|call| !call.contains("autotuninglevel")));
+
+        assert_eq!(limiter.reset_legacy_autotuning().unwrap(), "Auto-tuning TCP : restricted → normal");
+        assert!(!limiter.legacy_autotuning_restricted());
+        assert!(runner.calls().iter().any(|call| call == "netsh interface tcp set global autotuninglevel=normal"));
+    }
+
+    #[test]
+    fn hundred_scans_log_a_single_summary_inside_their_span() {
+        use crate::logs::testing::Capture;
+        use crate::system::testing::FakeRunner;
+        use tracing::Level;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let mut limiter = NetworkLimiter::with_runner(Arc::new(FakeRunner::printing(""))).unwrap();
+        // Le magasin de politiques du dossier courant n'est ni lu ni réécrit
+        limiter.policies_restored = true;
+        let capture = Capture::default();
+        let subscriber = tracing_subscriber::Registry::default().with(capture.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            for _ in 0..100 {
+                limiter.scan_network_processes().unwrap();
+            }
+        });
+
+        let summaries: Vec<_> = capture
+            .events()
+            .into_iter()
+            .filter(|(_, message, _)| message.contains("Scan réseau terminé"))
+            .collect();
+        assert_eq!(summaries.len(), 100);
+        let infos = summaries.iter().filter(|(level, ..)| *level == Level::INFO).count();
+        assert!(infos <= 1, "{} lignes info pour 100 scans", infos);
+        assert!(summaries.iter().all(|(level, ..)| *level == Level::INFO || *level == Level::DEBUG));
+        for (_, _, span) in &summaries {
+            let (name, span_fields) = span.as_ref().expect("résumé hors du span du scan");
+            assert_eq!(name, "network_scan");
+            assert!(span_fields.contains("processes=") && span_fields.contains("duration_ms="), "{}", span_fields);
+        }
+    }
+}
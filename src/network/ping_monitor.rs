@@ -0,0 +1,283 @@
+//! # Ping monitor
+//!
+//! Latency to a game server, measured once per second on a background thread. Windows uses
+//! `IcmpSendEcho`, which needs no raw-socket privileges; other systems run the `ping`
+//! command. Statistics cover the last `WINDOW` samples.
+
+use std::collections::VecDeque;
+use std::net::{Ipv4Addr, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+
+/// Samples kept for the statistics and the sparkline
+pub const WINDOW: usize = 60;
+pub const DEFAULT_HOST: &str = "1.1.1.1";
+/// Above these values the latency is shown as degraded
+pub const JITTER_WARN_MS: f64 = 20.0;
+pub const LOSS_WARN_PERCENT: f64 = 2.0;
+
+const INTERVAL: Duration = Duration::from_secs(1);
+const TIMEOUT_MS: u32 = 1000;
+
+/// (label, host) of the hosts offered in the network tab
+pub const HOST_PRESETS: &[(&str, &str)] = &[
+    ("Cloudflare", DEFAULT_HOST),
+    ("Europe (Gravelines)", "gra.proof.ovh.net"),
+    ("Amérique du Nord Est (Beauharnois)", "bhs.proof.ovh.ca"),
+    ("Amérique du Nord Ouest (Hillsboro)", "hil.proof.ovh.us"),
+    ("Asie (Singapour)", "sgp.proof.ovh.net"),
+    ("Océanie (Sydney)", "syd.proof.ovh.net"),
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct PingStats {
+    pub host: String,
+    /// Oldest first, `None` for a lost echo
+    pub samples: Vec<Option<f64>>,
+    pub last_ms: Option<f64>,
+    pub min_ms: Option<f64>,
+    pub avg_ms: Option<f64>,
+    pub max_ms: Option<f64>,
+    /// Mean difference between consecutive replies
+    pub jitter_ms: Option<f64>,
+    pub loss_percent: f64,
+    /// Host resolution or ICMP failure preventing any measurement
+    pub error: Option<String>,
+}
+
+impl PingStats {
+    pub fn is_degraded(&self) -> bool {
+        self.loss_percent > LOSS_WARN_PERCENT || self.jitter_ms.is_some_and(|jitter| jitter > JITTER_WARN_MS)
+    }
+
+    fn compute(host: &str, samples: &VecDeque<Option<f64>>, error: Option<String>) -> Self {
+        let replies: Vec<f64> = samples.iter().flatten().copied().collect();
+        let lost = samples.len() - replies.len();
+        let jitter = (replies.len() > 1).then(|| {
+            replies.windows(2).map(|pair| (pair[1] - pair[0]).abs()).sum::<f64>() / (replies.len() - 1) as f64
+        });
+        Self {
+            host: host.to_string(),
+            samples: samples.iter().copied().collect(),
+            last_ms: samples.back().copied().flatten(),
+            min_ms: replies.iter().copied().reduce(f64::min),
+            avg_ms: (!replies.is_empty()).then(|| replies.iter().sum::<f64>() / replies.len() as f64),
+            max_ms: replies.iter().copied().reduce(f64::max),
+            jitter_ms: jitter,
+            loss_percent: if samples.is_empty() { 0.0 } else { lost as f64 * 100.0 / samples.len() as f64 },
+            error,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    samples: VecDeque<Option<f64>>,
+    error: Option<String>,
+}
+
+/// Each `start` gets its own stop flag and buffer, so a thread still finishing its last echo
+/// after a host change cannot write into the new series
+#[derive(Default)]
+pub struct PingMonitor {
+    host: String,
+    stop: Option<Arc<AtomicBool>>,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl PingMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.stop.is_some()
+    }
+
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// Ping `host` every second, replacing any previous measurement
+    pub fn start(&mut self, host: &str) {
+        self.stop();
+        let host = host.trim().to_string();
+        let stop = Arc::new(AtomicBool::new(false));
+        let shared = Arc::new(Mutex::new(Shared::default()));
+
+        let thread_stop = Arc::clone(&stop);
+        let thread_shared = Arc::clone(&shared);
+        let thread_host = host.clone();
+        let spawned = std::thread::Builder::new()
+            .name("ping_monitor".to_string())
+            .spawn(move || run(&thread_host, &thread_stop, &thread_shared));
+        if let Err(e) = spawned {
+            tracing::error!("❌ Démarrage de la mesure de latence impossible: {}", e);
+            return;
+        }
+
+        tracing::info!("📶 Mesure de latence vers {}", host);
+        self.host = host;
+        self.stop = Some(stop);
+        self.shared = shared;
+    }
+
+    /// Ask the thread to end; it exits after its current echo (at most one timeout)
+    pub fn stop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            stop.store(true, Ordering::Relaxed);
+            tracing::info!("📶 Mesure de latence vers {} arrêtée", self.host);
+        }
+    }
+
+    pub fn snapshot(&self) -> PingStats {
+        match self.shared.lock() {
+            Ok(shared) => PingStats::compute(&self.host, &shared.samples, shared.error.clone()),
+            Err(_) => PingStats::default(),
+        }
+    }
+}
+
+impl Drop for PingMonitor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn run(host: &str, stop: &AtomicBool, shared: &Mutex<Shared>) {
+    let set_error = |error: String| {
+        if let Ok(mut shared) = shared.lock() {
+            shared.error = Some(error);
+        }
+    };
+
+    let address = match resolve_ipv4(host) {
+        Ok(address) => address,
+        Err(e) => {
+            tracing::warn!("⚠️ Hôte de mesure de latence {} introuvable: {}", host, e);
+            set_error(e.to_string());
+            return;
+        }
+    };
+    let pinger = match Pinger::new() {
+        Ok(pinger) => pinger,
+        Err(e) => {
+            tracing::error!("❌ Envoi ICMP impossible: {}", e);
+            set_error(e.to_string());
+            return;
+        }
+    };
+
+    while !stop.load(Ordering::Relaxed) {
+        let started = Instant::now();
+        let rtt = pinger.echo(address);
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+        if let Ok(mut shared) = shared.lock() {
+            shared.samples.push_back(rtt);
+            while shared.samples.len() > WINDOW {
+                shared.samples.pop_front();
+            }
+        }
+        std::thread::sleep(INTERVAL.saturating_sub(started.elapsed()));
+    }
+}
+
+fn resolve_ipv4(host: &str) -> Result<Ipv4Addr> {
+    if let Ok(address) = host.parse::<Ipv4Addr>() {
+        return Ok(address);
+    }
+    (host, 0)
+        .to_socket_addrs()?
+        .find_map(|address| match address.ip() {
+            std::net::IpAddr::V4(v4) => Some(v4),
+            std::net::IpAddr::V6(_) => None,
+        })
+        .ok_or_else(|| anyhow!("aucune adresse IPv4 pour {}", host))
+}
+
+#[cfg(target_os = "windows")]
+struct Pinger {
+    handle: windows_sys::Win32::Foundation::HANDLE,
+}
+
+#[cfg(target_os = "windows")]
+impl Pinger {
+    fn new() -> Result<Self> {
+        use windows_sys::Win32::Foundation::INVALID_HANDLE_VALUE;
+        use windows_sys::Win32::NetworkManagement::IpHelper::IcmpCreateFile;
+
+        let handle = unsafe { IcmpCreateFile() };
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(anyhow!("IcmpCreateFile: {}", std::io::Error::last_os_error()));
+        }
+        Ok(Self { handle })
+    }
+
+    /// Round trip in ms, `None` when the echo is lost or times out
+    fn echo(&self, address: Ipv4Addr) -> Option<f64> {
+        use windows_sys::Win32::NetworkManagement::IpHelper::{IcmpSendEcho, ICMP_ECHO_REPLY};
+
+        const PAYLOAD: &[u8] = b"GameBoosterPing!";
+        let mut reply = vec![0u8; std::mem::size_of::<ICMP_ECHO_REPLY>() + PAYLOAD.len() + 8];
+        let count = unsafe {
+            IcmpSendEcho(
+                self.handle,
+                u32::from_ne_bytes(address.octets()),
+                PAYLOAD.as_ptr().cast(),
+                PAYLOAD.len() as u16,
+                std::ptr::null(),
+                reply.as_mut_ptr().cast(),
+                reply.len() as u32,
+                TIMEOUT_MS,
+            )
+        };
+        if count == 0 {
+            return None;
+        }
+        let reply = unsafe { std::ptr::read_unaligned(reply.as_ptr() as *const ICMP_ECHO_REPLY) };
+        // IP_SUCCESS
+        (reply.Status == 0).then_some(reply.RoundTripTime as f64)
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for Pinger {
+    fn drop(&mut self) {
+        unsafe { windows_sys::Win32::NetworkManagement::IpHelper::IcmpCloseHandle(self.handle) };
+    }
+}
+
+/// `ping` command: ICMP sockets need root or `net.ipv4.ping_group_range` otherwise
+#[cfg(not(target_os = "windows"))]
+struct Pinger;
+
+#[cfg(not(target_os = "windows"))]
+impl Pinger {
+    fn new() -> Result<Self> {
+        std::process::Command::new("ping")
+            .arg("-V")
+            .output()
+            .map_err(|e| anyhow!("commande ping introuvable: {}", e))?;
+        Ok(Self)
+    }
+
+    fn echo(&self, address: Ipv4Addr) -> Option<f64> {
+        let output = std::process::Command::new("ping")
+            .args(["-n", "-c", "1", "-W", &(TIMEOUT_MS / 1000).to_string(), &address.to_string()])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        // "64 bytes from 1.1.1.1: icmp_seq=1 ttl=57 time=12.3 ms"
+        String::from_utf8_lossy(&output.stdout)
+            .split_whitespace()
+            .find_map(|field| field.strip_prefix("time="))
+            .and_then(|value| value.parse().ok())
+    }
+}
@@ -0,0 +1,54 @@
+//! # QoS policy store
+//!
+//! QoS policies created by GameBooster, persisted to `config/qos_policies.json`. At startup,
+//! a GameBooster policy missing from this file was left behind by a crashed session (or an
+//! older version) and is reported as orphaned instead of silently throttling an application.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use super::PortRule;
+
+const STORE_DIR: &str = "config";
+const STORE_FILE: &str = "qos_policies.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersistedPolicy {
+    pub name: String,
+    /// AppPathNameMatchCondition, empty for the catch-all policy
+    pub app_name: String,
+    /// 0 when the policy only marks traffic (DSCP, exemptions)
+    pub throttle_bits: u64,
+    /// End of a time-boxed limit, removed by the first session running after it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Local>>,
+    /// Protocol and ports of a `GameBooster_Port_*` policy, which has no executable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port_rule: Option<PortRule>,
+}
+
+pub fn path() -> PathBuf {
+    Path::new(STORE_DIR).join(STORE_FILE)
+}
+
+/// Policies recorded by the previous sessions. Empty when the file is missing or invalid.
+pub fn load() -> Vec<PersistedPolicy> {
+    let content = match fs::read_to_string(path()) {
+        Ok(content) => content,
+        Err(_) => return Vec::new(),
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        tracing::warn!("⚠️ {} invalide, ignoré: {}", path().display(), e);
+        Vec::new()
+    })
+}
+
+pub fn save(policies: &[PersistedPolicy]) -> Result<()> {
+    fs::create_dir_all(STORE_DIR)?;
+    fs::write(path(), serde_json::to_string_pretty(policies)?)?;
+    Ok(())
+}
@@ -0,0 +1,159 @@
+//! # Process list filter
+//!
+//! Search box of the network tab. Space-separated terms must all match (case-insensitive
+//! substring of the name or the PID); a `re:` prefix switches to a case-insensitive regex
+//! tested against the same two fields. Limited processes are never filtered out, so that an
+//! active throttle cannot be lost from view.
+
+use regex::{Regex, RegexBuilder};
+
+use super::ProcessGroup;
+
+const REGEX_PREFIX: &str = "re:";
+
+#[derive(Debug, Clone)]
+pub enum ProcessFilter {
+    All,
+    /// Lowercased terms, all required
+    Terms(Vec<String>),
+    Regex(Regex),
+}
+
+impl ProcessFilter {
+    /// Parse the search text; an invalid `re:` pattern is returned as an error to display
+    pub fn parse(text: &str) -> Result<Self, regex::Error> {
+        let text = text.trim();
+        if let Some(pattern) = text.strip_prefix(REGEX_PREFIX) {
+            if pattern.trim().is_empty() {
+                return Ok(ProcessFilter::All);
+            }
+            return RegexBuilder::new(pattern.trim()).case_insensitive(true).build().map(ProcessFilter::Regex);
+        }
+
+        let terms: Vec<String> = text.split_whitespace().map(str::to_lowercase).collect();
+        if terms.is_empty() {
+            Ok(ProcessFilter::All)
+        } else {
+            Ok(ProcessFilter::Terms(terms))
+        }
+    }
+
+    pub fn matches(&self, name: &str, pid: u32) -> bool {
+        let pid = pid.to_string();
+        match self {
+            ProcessFilter::All => true,
+            ProcessFilter::Terms(terms) => {
+                let name = name.to_lowercase();
+                terms.iter().all(|term| name.contains(term.as_str()) || pid.contains(term.as_str()))
+            }
+            ProcessFilter::Regex(regex) => regex.is_match(name) || regex.is_match(&pid),
+        }
+    }
+
+    /// A group matches through its executable name or any of its processes
+    pub fn matches_group(&self, group: &ProcessGroup) -> bool {
+        self.matches(&group.exe_name, group.root_pid)
+            || group.members.iter().any(|process| self.matches(&process.name, process.pid))
+    }
+}
+
+/// Groups to display with whether they match `filter`. Limited groups are always kept, the
+/// ones that do not match being flagged `false`; order is preserved.
+pub fn filter_groups(groups: Vec<ProcessGroup>, filter: &ProcessFilter) -> Vec<(ProcessGroup, bool)> {
+    groups
+        .into_iter()
+        .map(|group| {
+            let matched = filter.matches_group(&group);
+            (group, matched)
+        })
+        .filter(|(group, matched)| *matched || group.is_limited())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::NetworkProcessInfo;
+
+    fn process(pid: u32, name: &str, is_limited: bool) -> NetworkProcessInfo {
+        NetworkProcessInfo {
+            pid,
+            name: name.to_string(),
+            bytes_sent: 0,
+            bytes_received: 0,
+            packets_sent: 0,
+            packets_received: 0,
+            is_limited,
+            speed_limit: is_limited.then_some(512),
+            connections: 1,
+            connections_by_state: Default::default(),
+            current_upload_speed: 0,
+            current_download_speed: 0,
+            is_blocked: false,
+            exe_path: None,
+            parent_pid: None,
+            is_uwp: false,
+            package_family_name: None,
+            protection: Default::default(),
+            is_vm_host: false,
+            has_active_sockets: true,
+        }
+    }
+
+    fn group(exe_name: &str, root_pid: u32, members: Vec<NetworkProcessInfo>) -> ProcessGroup {
+        ProcessGroup {
+            exe_name: exe_name.to_string(),
+            root_pid,
+            total_download_speed: 0,
+            total_upload_speed: 0,
+            members,
+        }
+    }
+
+    #[test]
+    fn empty_text_matches_everything() {
+        for text in ["", "   ", "re:", "re:  "] {
+            assert!(matches!(ProcessFilter::parse(text).unwrap(), ProcessFilter::All), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn terms_are_case_insensitive_and_all_required() {
+        let filter = ProcessFilter::parse("  Steam  WEB ").unwrap();
+        assert!(filter.matches("steamwebhelper.exe", 1200));
+        assert!(!filter.matches("steam.exe", 1200));
+        // Un terme peut viser le PID
+        let filter = ProcessFilter::parse("chrome 42").unwrap();
+        assert!(filter.matches("chrome.exe", 4242));
+        assert!(!filter.matches("chrome.exe", 1000));
+    }
+
+    #[test]
+    fn regex_prefix_matches_name_or_pid() {
+        let filter = ProcessFilter::parse("re:^(steam|epic).*\\.exe$").unwrap();
+        assert!(filter.matches("Steam.exe", 1));
+        assert!(filter.matches("EpicGamesLauncher.exe", 1));
+        assert!(!filter.matches("discord.exe", 1));
+        assert!(ProcessFilter::parse("re:^1\\d{3}$").unwrap().matches("a.exe", 1234));
+        assert!(ProcessFilter::parse("re:(").is_err());
+    }
+
+    #[test]
+    fn groups_match_through_any_member_and_limited_ones_stay() {
+        let groups = vec![
+            group("chrome.exe", 100, vec![process(100, "chrome.exe", false), process(101, "chrome.exe", false)]),
+            group("launcher.exe", 200, vec![process(201, "game.exe", false)]),
+            group("torrent.exe", 300, vec![process(300, "torrent.exe", true)]),
+            group("discord.exe", 400, vec![process(400, "discord.exe", false)]),
+        ];
+
+        let filtered = filter_groups(groups, &ProcessFilter::parse("game").unwrap());
+        let shown: Vec<(&str, bool)> = filtered.iter().map(|(group, matched)| (group.exe_name.as_str(), *matched)).collect();
+        assert_eq!(shown, vec![("launcher.exe", true), ("torrent.exe", false)]);
+
+        let by_member_pid = ProcessFilter::parse("101").unwrap();
+        let chrome = group("chrome.exe", 100, vec![process(100, "chrome.exe", false), process(101, "chrome.exe", false)]);
+        assert!(by_member_pid.matches_group(&chrome));
+        assert_eq!(filter_groups(vec![chrome], &ProcessFilter::All).len(), 1);
+    }
+}
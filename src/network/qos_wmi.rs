@@ -0,0 +1,495 @@
+//! # QoS via WMI
+//!
+//! Direct access to the `ROOT\StandardCimv2` `MSFT_NetQosPolicySettingData` provider,
+//! the same provider used by the NetQos PowerShell cmdlets, without spawning powershell.exe.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde::Deserialize;
+use windows::core::BSTR;
+use windows::Win32::System::Wmi::IWbemContext;
+use wmi::{COMLibrary, Variant, WMIConnection};
+
+use super::{appx, PortDirection, PortProtocol, QosPolicyInfo, QosPolicySpec};
+
+const QOS_NAMESPACE: &str = "ROOT\\StandardCimv2";
+const QOS_CLASS: &str = "MSFT_NetQosPolicySettingData";
+
+thread_local! {
+    // COMLibrary n'est pas Send : une connexion par thread, réutilisée entre les appels
+    static CONNECTION: RefCell<Option<WMIConnection>> = RefCell::new(None);
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename = "MSFT_NetQosPolicySettingData")]
+#[serde(rename_all = "PascalCase")]
+struct NetQosPolicySettingData {
+    #[serde(rename = "__Path")]
+    path: String,
+    name: String,
+    app_path_name_match_condition: Option<String>,
+    throttle_rate_action: Option<u64>,
+    #[serde(rename = "DSCPAction")]
+    dscp_action: Option<i8>,
+    #[serde(rename = "IPProtocolMatchCondition")]
+    ip_protocol_match_condition: Option<u16>,
+    #[serde(rename = "IPDstPortMatchCondition")]
+    ip_dst_port_match_condition: Option<u16>,
+    #[serde(rename = "IPDstPortStartMatchCondition")]
+    ip_dst_port_start_match_condition: Option<u16>,
+    #[serde(rename = "IPDstPortEndMatchCondition")]
+    ip_dst_port_end_match_condition: Option<u16>,
+    #[serde(rename = "IPSrcPortMatchCondition")]
+    ip_src_port_match_condition: Option<u16>,
+    #[serde(rename = "IPSrcPortStartMatchCondition")]
+    ip_src_port_start_match_condition: Option<u16>,
+    #[serde(rename = "IPSrcPortEndMatchCondition")]
+    ip_src_port_end_match_condition: Option<u16>,
+}
+
+impl NetQosPolicySettingData {
+    /// Port range of the policy and the side it matches; 0 means no condition
+    fn port_condition(&self) -> Option<((u16, u16), PortDirection)> {
+        let range = |single: Option<u16>, start: Option<u16>, end: Option<u16>| {
+            match (single.filter(|p| *p > 0), start.filter(|p| *p > 0), end.filter(|p| *p > 0)) {
+                (Some(port), _, _) => Some((port, port)),
+                (None, Some(start), Some(end)) => Some((start, end)),
+                _ => None,
+            }
+        };
+        range(self.ip_dst_port_match_condition, self.ip_dst_port_start_match_condition, self.ip_dst_port_end_match_condition)
+            .map(|ports| (ports, PortDirection::Destination))
+            .or_else(|| {
+                range(self.ip_src_port_match_condition, self.ip_src_port_start_match_condition, self.ip_src_port_end_match_condition)
+                    .map(|ports| (ports, PortDirection::Source))
+            })
+    }
+}
+
+fn with_connection<T>(f: impl FnOnce(&WMIConnection) -> Result<T>) -> Result<T> {
+    CONNECTION.with(|cell| {
+        let mut connection = cell.borrow_mut();
+        if connection.is_none() {
+            let com = COMLibrary::new()
+                .map_err(|e| anyhow::anyhow!("Initialisation COM impossible: {}", e))?;
+            let con = WMIConnection::with_namespace_path(QOS_NAMESPACE, com)
+                .map_err(|e| anyhow::anyhow!("Connexion WMI {} impossible: {}", QOS_NAMESPACE, e))?;
+            *connection = Some(con);
+        }
+        f(connection.as_ref().expect("connexion WMI initialisée"))
+    })
+}
+
+/// WQL `LIKE` pattern matching the names starting with `prefix`: `_`, `%` and `[` are
+/// wildcards there, so "GameBooster_" would also match "GameBoosterX..."
+fn like_prefix_pattern(prefix: &str) -> String {
+    let mut pattern = String::with_capacity(prefix.len() + 1);
+    for c in prefix.chars() {
+        match c {
+            '[' => pattern.push_str("[[]"),
+            '_' => pattern.push_str("[_]"),
+            '%' => pattern.push_str("[%]"),
+            // Pas d'échappement de guillemet en WQL : retiré pour ne pas fermer la chaîne
+            '\'' => {}
+            c => pattern.push(c),
+        }
+    }
+    pattern.push('%');
+    pattern
+}
+
+/// Policies whose name starts with `name_prefix` (case-insensitive, like the WMI provider)
+fn query_policies(con: &WMIConnection, name_prefix: &str) -> Result<Vec<NetQosPolicySettingData>> {
+    let query = format!("SELECT * FROM {} WHERE Name LIKE '{}'", QOS_CLASS, like_prefix_pattern(name_prefix));
+    let mut policies: Vec<NetQosPolicySettingData> = con
+        .raw_query(query)
+        .map_err(|e| anyhow::anyhow!("Requête WMI des politiques QoS échouée: {}", e))?;
+    // Filet de sécurité : ne jamais renvoyer (et donc supprimer) une politique hors du préfixe
+    let prefix = name_prefix.to_lowercase();
+    policies.retain(|policy| policy.name.to_lowercase().starts_with(&prefix));
+    Ok(policies)
+}
+
+fn delete_instance(con: &WMIConnection, path: &str) -> Result<()> {
+    unsafe {
+        con.svc
+            .DeleteInstance(&BSTR::from(path), Default::default(), None::<&IWbemContext>, None)
+            .map_err(|e| anyhow::anyhow!("Suppression WMI de {} échouée: {}", path, e))
+    }
+}
+
+/// Whether the `MSFT_NetQosPolicySettingData` class exists (NetQoS, Windows 8 and later)
+pub fn provider_available() -> Result<bool> {
+    with_connection(|con| {
+        let classes: Vec<HashMap<String, Variant>> = con
+            .raw_query(format!("SELECT * FROM meta_class WHERE __CLASS = '{}'", QOS_CLASS))
+            .map_err(|e| anyhow::anyhow!("Requête WMI sur {} échouée: {}", QOS_CLASS, e))?;
+        Ok(!classes.is_empty())
+    })
+}
+
+/// Create (or replace) a throttling policy for an executable
+pub fn create_policy(policy_name: &str, app_name: &str, throttle_bits_per_second: u64) -> Result<()> {
+    create_policy_from_spec(&QosPolicySpec {
+        name: policy_name,
+        app_name: Some(app_name),
+        default_match: false,
+        throttle_bits_per_second: Some(throttle_bits_per_second),
+        dscp: None,
+        src_prefix: None,
+        port_rule: None,
+    })
+}
+
+/// Create (or replace) a policy described by `spec`
+pub fn create_policy_from_spec(spec: &QosPolicySpec) -> Result<()> {
+    let policy_name = spec.name;
+    with_connection(|con| {
+        // Équivalent de Remove-NetQosPolicy avant création
+        for existing in query_policies(con, policy_name)? {
+            if existing.name == policy_name {
+                delete_instance(con, &existing.path)?;
+            }
+        }
+
+        let mut params: HashMap<String, Variant> = HashMap::new();
+        params.insert("Name".to_string(), Variant::String(policy_name.to_string()));
+        if let Some(app_name) = spec.app_name {
+            params.insert("AppPathNameMatchCondition".to_string(), Variant::String(app_name.to_string()));
+        }
+        if spec.default_match {
+            // Équivalent de New-NetQosPolicy -Default (modèle "Default" = 1)
+            params.insert("TemplateMatchCondition".to_string(), Variant::UI4(1));
+        }
+        if let Some(bits) = spec.throttle_bits_per_second {
+            // Les uint64 CIM transitent sous forme de chaîne dans les VARIANT WMI
+            params.insert("ThrottleRateAction".to_string(), Variant::String(bits.to_string()));
+        }
+        if let Some(dscp) = spec.dscp {
+            params.insert("DSCPAction".to_string(), Variant::UI1(dscp));
+        }
+        if let Some(prefix) = spec.src_prefix {
+            params.insert("IPSrcPrefixMatchCondition".to_string(), Variant::String(prefix.to_string()));
+        }
+        if let Some(rule) = spec.port_rule {
+            let condition = rule.direction.condition_prefix();
+            let (start, end) = rule.port_range;
+            params.insert("IPProtocolMatchCondition".to_string(), Variant::UI2(rule.protocol.wmi_value()));
+            if start == end {
+                params.insert(format!("{}MatchCondition", condition), Variant::UI2(start));
+            } else {
+                params.insert(format!("{}StartMatchCondition", condition), Variant::UI2(start));
+                params.insert(format!("{}EndMatchCondition", condition), Variant::UI2(end));
+            }
+        }
+
+        let output = con
+            .exec_method_native_wrapper(QOS_CLASS, QOS_CLASS, "Create", params)
+            .map_err(|e| anyhow::anyhow!("Appel WMI Create échoué: {}", e))?;
+
+        if let Some(output) = output {
+            match output.get_property("ReturnValue") {
+                Ok(Variant::UI4(0)) | Ok(Variant::I4(0)) | Ok(Variant::Empty) | Ok(Variant::Null) => {}
+                Ok(code) => return Err(anyhow::anyhow!("Create a retourné le code {:?}", code)),
+                Err(e) => tracing::debug!("ReturnValue absent de la réponse WMI Create: {}", e),
+            }
+        }
+
+        // Vérification identique au script PowerShell
+        let created = query_policies(con, policy_name)?
+            .into_iter()
+            .find(|p| p.name == policy_name)
+            .ok_or_else(|| anyhow::anyhow!("Politique {} introuvable après création", policy_name))?;
+
+        if spec.throttle_bits_per_second.is_some() && created.throttle_rate_action != spec.throttle_bits_per_second {
+            return Err(anyhow::anyhow!(
+                "Politique créée mais vérification échouée. Attendu {:?} bits, obtenu {:?}",
+                spec.throttle_bits_per_second,
+                created.throttle_rate_action
+            ));
+        }
+        Ok(())
+    })
+}
+
+/// Remove every policy whose name matches exactly one of `policy_names`.
+/// Returns the number of removed policies.
+pub fn remove_policies(policy_names: &[&str]) -> Result<usize> {
+    with_connection(|con| {
+        let mut removed = 0;
+        for policy in query_policies(con, "GameBooster")? {
+            if policy_names.contains(&policy.name.as_str()) {
+                delete_instance(con, &policy.path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    })
+}
+
+/// Remove every policy whose name starts with `name_prefix`
+pub fn remove_policies_with_prefix(name_prefix: &str) -> Result<usize> {
+    with_connection(|con| {
+        let policies = query_policies(con, name_prefix)?;
+        for policy in &policies {
+            delete_instance(con, &policy.path)?;
+        }
+        Ok(policies.len())
+    })
+}
+
+/// List the policies whose name starts with `name_prefix`
+pub fn list_policies(name_prefix: &str) -> Result<Vec<QosPolicyInfo>> {
+    with_connection(|con| {
+        Ok(query_policies(con, name_prefix)?
+            .into_iter()
+            .map(|p| {
+                let ports = p.port_condition();
+                let app_name = p.app_path_name_match_condition.unwrap_or_else(|| "N/A".to_string());
+                QosPolicyInfo {
+                    name: p.name,
+                    package_family_name: appx::family_name_from_path(&app_name),
+                    app_name,
+                    throttle_bits: p.throttle_rate_action.unwrap_or(0),
+                    is_registry_only: false,
+                    // -1 signifie "pas de marquage DSCP"
+                    dscp: p.dscp_action.filter(|d| *d >= 0).map(|d| d as u8),
+                    protocol: p.ip_protocol_match_condition.and_then(PortProtocol::from_wmi_value),
+                    port_start: ports.map(|((start, _), _)| start),
+                    port_end: ports.map(|((_, end), _)| end),
+                    port_direction: ports.map(|(_, direction)| direction),
+                }
+            })
+            .collect())
+    })
+}
+
+// Les politiques QoS de stratégie de groupe orphelines n'existent que dans le registre
+// et ne sont pas visibles via le provider WMI.
+const QOS_REGISTRY_PATH: &str = "SOFTWARE\\Policies\\Microsoft\\Windows\\QoS";
+
+fn to_wide(value: &str) -> Vec<u16> {
+    value.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+fn registry_policy_names(name_prefix: &str) -> Vec<String> {
+    use windows_sys::Win32::Foundation::ERROR_SUCCESS;
+    use windows_sys::Win32::System::Registry::{
+        RegCloseKey, RegEnumKeyExW, RegOpenKeyExW, HKEY, HKEY_LOCAL_MACHINE, KEY_READ,
+    };
+
+    let mut names = Vec::new();
+    let path = to_wide(QOS_REGISTRY_PATH);
+    let mut key: HKEY = std::ptr::null_mut();
+
+    unsafe {
+        if RegOpenKeyExW(HKEY_LOCAL_MACHINE, path.as_ptr(), 0, KEY_READ, &mut key) != ERROR_SUCCESS {
+            return names;
+        }
+
+        let mut index = 0;
+        loop {
+            let mut buffer = [0u16; 256];
+            let mut len = buffer.len() as u32;
+            let status = RegEnumKeyExW(
+                key,
+                index,
+                buffer.as_mut_ptr(),
+                &mut len,
+                std::ptr::null(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            );
+            if status != ERROR_SUCCESS {
+                break;
+            }
+            let name = String::from_utf16_lossy(&buffer[..len as usize]);
+            if name.starts_with(name_prefix) {
+                names.push(name);
+            }
+            index += 1;
+        }
+
+        RegCloseKey(key);
+    }
+
+    names
+}
+
+fn registry_policy_value(policy_name: &str, value_name: &str) -> Option<String> {
+    use windows_sys::Win32::Foundation::ERROR_SUCCESS;
+    use windows_sys::Win32::System::Registry::{RegGetValueW, HKEY_LOCAL_MACHINE, RRF_RT_REG_SZ};
+
+    let subkey = to_wide(&format!("{}\\{}", QOS_REGISTRY_PATH, policy_name));
+    let value = to_wide(value_name);
+    let mut buffer = [0u16; 1024];
+    let mut size = std::mem::size_of_val(&buffer) as u32;
+
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            subkey.as_ptr(),
+            value.as_ptr(),
+            RRF_RT_REG_SZ,
+            std::ptr::null_mut(),
+            buffer.as_mut_ptr() as *mut _,
+            &mut size,
+        )
+    };
+    if status != ERROR_SUCCESS {
+        return None;
+    }
+
+    let len = (size as usize / 2).saturating_sub(1);
+    Some(String::from_utf16_lossy(&buffer[..len]))
+}
+
+/// "6881:6889" or "27015", `None` for "*"
+fn registry_port_range(value: &str) -> Option<(u16, u16)> {
+    let (start, end) = value.trim().split_once(':').unwrap_or((value.trim(), value.trim()));
+    Some((start.parse().ok()?, end.parse().ok()?))
+}
+
+/// List GameBooster policies that only exist in the Group Policy registry store
+pub fn list_registry_only_policies(name_prefix: &str, known: &[QosPolicyInfo]) -> Vec<QosPolicyInfo> {
+    registry_policy_names(name_prefix)
+        .into_iter()
+        .filter(|name| !known.iter().any(|p| &p.name == name))
+        .map(|name| {
+            // "Throttle Rate" est stocké en Kbps dans le registre
+            let throttle_bits = registry_policy_value(&name, "Throttle Rate")
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .map(|kbps| kbps * 1000)
+                .unwrap_or(0);
+            let dscp = registry_policy_value(&name, "DSCP Value")
+                .and_then(|v| v.trim().parse::<u8>().ok());
+            let app_name = registry_policy_value(&name, "Application Name").unwrap_or_else(|| "N/A".to_string());
+            // Ports "6881:6889" ou "*", protocole "TCP", "UDP" ou "*"
+            let ports = [("Remote Port", PortDirection::Destination), ("Local Port", PortDirection::Source)]
+                .into_iter()
+                .find_map(|(value, direction)| Some((registry_port_range(&registry_policy_value(&name, value)?)?, direction)));
+            let protocol = match registry_policy_value(&name, "Protocol").as_deref().map(str::trim) {
+                Some("TCP") => Some(PortProtocol::Tcp),
+                Some("UDP") => Some(PortProtocol::Udp),
+                Some("*") if ports.is_some() => Some(PortProtocol::Both),
+                _ => None,
+            };
+            QosPolicyInfo {
+                package_family_name: appx::family_name_from_path(&app_name),
+                app_name,
+                name,
+                throttle_bits,
+                is_registry_only: true,
+                dscp,
+                protocol,
+                port_start: ports.map(|((start, _), _)| start),
+                port_end: ports.map(|((_, end), _)| end),
+                port_direction: ports.map(|(_, direction)| direction),
+            }
+        })
+        .collect()
+}
+
+/// Delete GameBooster policies from the Group Policy registry store
+pub fn remove_registry_policies(name_prefix: &str) -> usize {
+    use windows_sys::Win32::Foundation::ERROR_SUCCESS;
+    use windows_sys::Win32::System::Registry::{RegDeleteTreeW, HKEY_LOCAL_MACHINE};
+
+    registry_policy_names(name_prefix)
+        .into_iter()
+        .filter(|name| {
+            let subkey = to_wide(&format!("{}\\{}", QOS_REGISTRY_PATH, name));
+            unsafe { RegDeleteTreeW(HKEY_LOCAL_MACHINE, subkey.as_ptr()) == ERROR_SUCCESS }
+        })
+        .count()
+}
+
+/// Delete a single policy from the Group Policy registry store. True when it was removed.
+pub fn remove_registry_policy(policy_name: &str) -> bool {
+    use windows_sys::Win32::Foundation::ERROR_SUCCESS;
+    use windows_sys::Win32::System::Registry::{RegDeleteTreeW, HKEY_LOCAL_MACHINE};
+
+    let subkey = to_wide(&format!("{}\\{}", QOS_REGISTRY_PATH, policy_name));
+    unsafe { RegDeleteTreeW(HKEY_LOCAL_MACHINE, subkey.as_ptr()) == ERROR_SUCCESS }
+}
+
+const TCPIP_QOS_REGISTRY_PATH: &str = "SYSTEM\\CurrentControlSet\\Services\\Tcpip\\QoS";
+
+/// Create `Do not use NLA = "1"`, required for DSCP marking on non-domain machines.
+/// Returns true when the value had to be created.
+pub fn ensure_do_not_use_nla() -> Result<bool> {
+    use windows_sys::Win32::Foundation::ERROR_SUCCESS;
+    use windows_sys::Win32::System::Registry::{
+        RegCloseKey, RegCreateKeyExW, RegGetValueW, RegSetValueExW, HKEY, HKEY_LOCAL_MACHINE, KEY_WRITE,
+        REG_OPTION_NON_VOLATILE, REG_SZ, RRF_RT_REG_SZ,
+    };
+
+    let subkey = to_wide(TCPIP_QOS_REGISTRY_PATH);
+    let value_name = to_wide("Do not use NLA");
+
+    unsafe {
+        let mut buffer = [0u16; 16];
+        let mut size = std::mem::size_of_val(&buffer) as u32;
+        let status = RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            subkey.as_ptr(),
+            value_name.as_ptr(),
+            RRF_RT_REG_SZ,
+            std::ptr::null_mut(),
+            buffer.as_mut_ptr() as *mut _,
+            &mut size,
+        );
+        if status == ERROR_SUCCESS {
+            return Ok(false);
+        }
+
+        let mut key: HKEY = std::ptr::null_mut();
+        let status = RegCreateKeyExW(
+            HKEY_LOCAL_MACHINE,
+            subkey.as_ptr(),
+            0,
+            std::ptr::null(),
+            REG_OPTION_NON_VOLATILE,
+            KEY_WRITE,
+            std::ptr::null(),
+            &mut key,
+            std::ptr::null_mut(),
+        );
+        if status != ERROR_SUCCESS {
+            return Err(anyhow::anyhow!("Ouverture de HKLM\\{} impossible (code {})", TCPIP_QOS_REGISTRY_PATH, status));
+        }
+
+        let data = to_wide("1");
+        let status = RegSetValueExW(
+            key,
+            value_name.as_ptr(),
+            0,
+            REG_SZ,
+            data.as_ptr() as *const u8,
+            (data.len() * 2) as u32,
+        );
+        RegCloseKey(key);
+
+        if status != ERROR_SUCCESS {
+            return Err(anyhow::anyhow!("Écriture de \"Do not use NLA\" impossible (code {})", status));
+        }
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn like_pattern_escapes_the_wql_wildcards() {
+        assert_eq!(like_prefix_pattern("GameBooster"), "GameBooster%");
+        assert_eq!(like_prefix_pattern("GameBooster_Limit_my_game.exe"), "GameBooster[_]Limit[_]my[_]game.exe%");
+        assert_eq!(like_prefix_pattern("GameBooster_[x64] 100%"), "GameBooster[_][[]x64] 100[%]%");
+        assert_eq!(like_prefix_pattern("GameBooster_O'Brien"), "GameBooster[_]OBrien%");
+    }
+}
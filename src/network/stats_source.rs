@@ -0,0 +1,111 @@
+//! # Per-process traffic sources
+//!
+//! Where the Windows scan gets the bytes and speeds of each process, behind
+//! `NetworkStatsSource` so that the sources can be swapped while the limiter runs:
+//! - `EstimatedStats`: always available, derived from the process type, CPU and memory
+//! - `etw_monitor::EtwStatsSource` (`etw` feature): bytes actually sent and received, TCP and
+//!   UDP, counted from kernel network events; needs administrator rights
+
+use anyhow::Result;
+
+/// Traffic of one process: cumulative bytes and current speeds, in bytes per second
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProcessTraffic {
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub upload_speed: u64,
+    pub download_speed: u64,
+}
+
+pub trait NetworkStatsSource: Send {
+    /// Shown next to the totals of the network tab
+    fn label(&self) -> &'static str;
+
+    /// Bytes and speeds are guesses rather than measurements, to be shown as such
+    fn is_estimate(&self) -> bool {
+        false
+    }
+
+    /// Called once per scan, before `traffic` is asked for each process
+    fn refresh(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn traffic(&self, pid: u32, process: &sysinfo::Process) -> ProcessTraffic;
+}
+
+/// Estimates from the process type and its CPU and memory use, without any measurement
+pub struct EstimatedStats;
+
+impl NetworkStatsSource for EstimatedStats {
+    fn label(&self) -> &'static str {
+        "estimation"
+    }
+
+    fn is_estimate(&self) -> bool {
+        true
+    }
+
+    fn traffic(&self, _pid: u32, process: &sysinfo::Process) -> ProcessTraffic {
+        let name = process.name().to_lowercase();
+        let cpu_usage = process.cpu_usage() as f64; // Convert to f64
+        let memory_usage = process.memory();
+
+        // Base estimation multiplier based on process type
+        let (base_sent, base_received, speed_multiplier) = match name.as_str() {
+            name if name.contains("chrome") => (2_048_000, 1_024_000, 3.0),
+            name if name.contains("firefox") => (1_536_000, 768_000, 2.5),
+            name if name.contains("discord") => (512_000, 256_000, 1.5),
+            name if name.contains("steam") => (4_096_000, 2_048_000, 4.0),
+            name if name.contains("teams") => (800_000, 400_000, 2.0),
+            name if name.contains("zoom") => (1_200_000, 600_000, 2.5),
+            name if name.contains("spotify") => (600_000, 300_000, 1.8),
+            name if name.contains("vlc") => (300_000, 150_000, 1.2),
+            name if name.contains("edge") => (1_800_000, 900_000, 2.8),
+            name if name.contains("skype") => (400_000, 200_000, 1.6),
+            _ => {
+                // For unknown processes, use CPU and memory as indicators
+                if cpu_usage > 5.0 || memory_usage > 100_000_000 { // >100MB
+                    (200_000, 100_000, 1.0)
+                } else {
+                    (0, 0, 0.0)
+                }
+            }
+        };
+
+        // Modulate based on actual CPU usage (more CPU = more network activity likely)
+        let cpu_factor = (cpu_usage / 100.0).max(0.1).min(3.0);
+        let memory_factor = ((memory_usage as f64) / 100_000_000.0).max(0.1).min(2.0); // Normalize to 100MB
+
+        let final_sent = (base_sent as f64 * cpu_factor * memory_factor) as u64;
+        let final_received = (base_received as f64 * cpu_factor * memory_factor) as u64;
+
+        // Current speeds (simulated based on activity)
+        ProcessTraffic {
+            bytes_sent: final_sent,
+            bytes_received: final_received,
+            upload_speed: (final_sent as f64 * speed_multiplier * cpu_factor / 8.0) as u64, // /8 for current speed
+            download_speed: (final_received as f64 * speed_multiplier * cpu_factor / 8.0) as u64,
+        }
+    }
+}
+
+/// Kernel events when `high_precision` is asked for and possible, estimates otherwise
+pub fn select(high_precision: bool) -> Box<dyn NetworkStatsSource> {
+    #[cfg(all(target_os = "windows", feature = "etw"))]
+    if high_precision {
+        if !crate::utils::is_elevated() {
+            tracing::info!("📊 Surveillance réseau haute précision : droits administrateur requis, estimation utilisée");
+        } else {
+            match super::etw_monitor::EtwStatsSource::start() {
+                Ok(source) => return Box::new(source),
+                Err(e) => tracing::warn!("⚠️ Session ETW réseau impossible, estimation utilisée: {:#}", e),
+            }
+        }
+    }
+    #[cfg(not(all(target_os = "windows", feature = "etw")))]
+    if high_precision {
+        tracing::debug!("📊 Surveillance réseau haute précision absente de cette version");
+    }
+    Box::new(EstimatedStats)
+}
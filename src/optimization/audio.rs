@@ -0,0 +1,329 @@
+//! # Audio enhancements
+//!
+//! Sound effects (loudness equalization, virtual surround, vendor APOs) run on the audio
+//! thread and are a common cause of crackling under load. Each active render endpoint, listed
+//! with the Core Audio `IMMDeviceEnumerator`, keeps its switch in
+//! `MMDevices\Audio\Render\{endpoint}\FxProperties` (`PKEY_AudioEndpoint_Disable_SysFx`,
+//! 1 = disabled). The gaming mode can also raise the priorities of the MMCSS "Games" task.
+//!
+//! Changes go through the `os_tweaks` journal: the per-device toggle as `TweakSource::Audio`,
+//! the gaming mode as `TweakSource::GamingMode`. `exit_gaming_mode` restores from the journal
+//! alone, so a headset unplugged in the meantime does not block the other devices.
+
+use anyhow::Result;
+
+use crate::disk::os_tweaks::{self, JournalEntry, OsTweaksResults, PreviousValue, TweakSource};
+use crate::optimization::tweaks::{Applied, Tweak, TweakState, AUDIO_TWEAK_PREFIX};
+
+const RENDER_KEY: &str = "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\MMDevices\\Audio\\Render";
+/// `PKEY_AudioEndpoint_Disable_SysFx`
+const DISABLE_SYSFX: &str = "{1da5d803-d492-4edd-8c23-e0c0ffee7f0e},5";
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const MMCSS_GAMES_KEY: &str = "SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion\\Multimedia\\SystemProfile\\Tasks\\Games";
+/// (value, raised setting, label) of the MMCSS "Games" task
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const MMCSS_VALUES: &[(&str, u32, &str)] = &[
+    ("GPU Priority", 8, "Priorité GPU MMCSS des jeux"),
+    ("Priority", 6, "Priorité CPU MMCSS des jeux"),
+];
+/// Reads FxProperties when a stream opens: restarted so that the change applies at once
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const AUDIO_SERVICE: &str = "Audiosrv";
+
+/// Active playback device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioDevice {
+    /// Endpoint GUID, name of its key under `MMDevices\Audio\Render`
+    pub endpoint: String,
+    pub name: String,
+    pub enhancements_enabled: bool,
+}
+
+fn fx_key(endpoint: &str) -> String {
+    format!("{}\\{}\\FxProperties", RENDER_KEY, endpoint)
+}
+
+/// `{0.0.0.00000000}.{guid}` → `{guid}`
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn endpoint_guid(device_id: &str) -> &str {
+    device_id.rsplit_once('.').map(|(_, guid)| guid).unwrap_or(device_id)
+}
+
+/// Journal entry of the enhancements switch of `endpoint` (every endpoint when `None`)
+fn is_enhancements_entry(entry: &JournalEntry, endpoint: Option<&str>) -> bool {
+    match &entry.previous {
+        PreviousValue::Registry { path, name, .. } => {
+            let device = match endpoint {
+                Some(endpoint) => path.eq_ignore_ascii_case(&fx_key(endpoint)),
+                None => path.starts_with(RENDER_KEY),
+            };
+            name == DISABLE_SYSFX && device
+        }
+        PreviousValue::PowerScheme(_) => false,
+    }
+}
+
+/// Whether the gaming mode left changes to undo (crash, app killed while playing)
+pub fn has_gaming_mode_changes() -> bool {
+    os_tweaks::TweakJournal::load().entries.iter().any(|entry| entry.source == TweakSource::GamingMode)
+}
+
+#[cfg(target_os = "windows")]
+fn enhancements_enabled(endpoint: &str) -> bool {
+    use crate::utils::registry;
+    registry::read_dword(registry::HKEY_LOCAL_MACHINE, &fx_key(endpoint), DISABLE_SYSFX).ok().flatten() != Some(1)
+}
+
+/// Enhancements of one device switched off, as a row of the tweak registry
+pub struct EnhancementsTweak {
+    id: String,
+    device: AudioDevice,
+}
+
+impl EnhancementsTweak {
+    pub fn new(device: AudioDevice) -> Self {
+        Self { id: format!("{}{}", AUDIO_TWEAK_PREFIX, device.endpoint), device }
+    }
+}
+
+impl Tweak for EnhancementsTweak {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn label(&self) -> String {
+        format!("{} : améliorations désactivées", self.device.name)
+    }
+
+    fn requires_elevation(&self) -> bool {
+        true
+    }
+
+    #[cfg(target_os = "windows")]
+    fn current_state(&self) -> Result<TweakState> {
+        Ok(if enhancements_enabled(&self.device.endpoint) { TweakState::NotApplied } else { TweakState::Applied })
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn current_state(&self) -> Result<TweakState> {
+        Err(anyhow::anyhow!("Améliorations audio disponibles uniquement sous Windows"))
+    }
+
+    /// The audio service is restarted, as with the device list
+    fn apply(&self) -> Result<Applied> {
+        Ok(Applied { detail: set_enhancements(&self.device, false).into_result()? })
+    }
+
+    fn revert(&self, _applied: &Applied) -> Result<()> {
+        set_enhancements(&self.device, true).into_result().map(|_| ())
+    }
+}
+
+/// Active playback devices with the state of their enhancements
+#[cfg(target_os = "windows")]
+pub fn list_render_devices() -> Result<Vec<AudioDevice>> {
+    // COM sur un thread dédié : celui de l'interface est déjà initialisé en STA par la fenêtre
+    std::thread::spawn(|| unsafe { enumerate_render_devices() })
+        .join()
+        .map_err(|_| anyhow::anyhow!("Énumération des périphériques audio interrompue"))?
+}
+
+#[cfg(target_os = "windows")]
+unsafe fn enumerate_render_devices() -> Result<Vec<AudioDevice>> {
+    use windows::Win32::Devices::FunctionDiscovery::PKEY_Device_FriendlyName;
+    use windows::Win32::Media::Audio::{eRender, IMMDeviceEnumerator, MMDeviceEnumerator, DEVICE_STATE_ACTIVE};
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoTaskMemFree, CoUninitialize, CLSCTX_ALL, COINIT_MULTITHREADED, STGM_READ,
+    };
+
+    CoInitializeEx(None, COINIT_MULTITHREADED)
+        .ok()
+        .map_err(|e| anyhow::anyhow!("Initialisation COM impossible: {}", e))?;
+    let devices = (|| -> Result<Vec<AudioDevice>> {
+        let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)?;
+        let collection = enumerator.EnumAudioEndpoints(eRender, DEVICE_STATE_ACTIVE)?;
+        let mut devices = Vec::new();
+        for index in 0..collection.GetCount()? {
+            let device = collection.Item(index)?;
+            let raw_id = device.GetId()?;
+            let id = raw_id.to_string();
+            CoTaskMemFree(Some(raw_id.0 as *const _));
+            let endpoint = endpoint_guid(&id?).to_string();
+            let name = device
+                .OpenPropertyStore(STGM_READ)
+                .and_then(|store| store.GetValue(&PKEY_Device_FriendlyName))
+                .map(|value| value.to_string())
+                .unwrap_or_else(|_| endpoint.clone());
+            devices.push(AudioDevice {
+                enhancements_enabled: enhancements_enabled(&endpoint),
+                endpoint,
+                name,
+            });
+        }
+        Ok(devices)
+    })();
+    CoUninitialize();
+    devices
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn list_render_devices() -> Result<Vec<AudioDevice>> {
+    Err(anyhow::anyhow!("Améliorations audio disponibles uniquement sous Windows"))
+}
+
+/// Stop and start the audio service; `sc stop` returns before the service is stopped
+#[cfg(target_os = "windows")]
+fn restart_audio_service() -> Result<String> {
+    use crate::services::gaming_services::{start_service, stop_service};
+
+    stop_service(AUDIO_SERVICE)?;
+    for _ in 0..50 {
+        if !crate::services::is_service_running(AUDIO_SERVICE).unwrap_or(false) {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    start_service(AUDIO_SERVICE)?;
+    Ok("son coupé une seconde le temps du redémarrage".to_string())
+}
+
+/// Switch the enhancements of one device. Switching them back on restores the value journaled
+/// when GameBooster turned them off, if any.
+#[cfg(target_os = "windows")]
+pub fn set_enhancements(device: &AudioDevice, enabled: bool) -> OsTweaksResults {
+    use crate::disk::os_tweaks::{set_journaled_dword, RegistryRoot, TweakJournal};
+    use crate::utils::registry;
+
+    let _guard = os_tweaks::lock_journal();
+    let label = format!("Améliorations audio : {}", device.name);
+    let mut results = OsTweaksResults::default();
+    let journaled = TweakJournal::load()
+        .entries
+        .iter()
+        .any(|entry| is_enhancements_entry(entry, Some(&device.endpoint)));
+
+    let changed = if enabled && journaled {
+        results = os_tweaks::restore_matching(|entry| is_enhancements_entry(entry, Some(&device.endpoint)));
+        results.outcomes.iter().any(|outcome| outcome.success)
+    } else if enabled {
+        let result = registry::write_dword(registry::HKEY_LOCAL_MACHINE, &fx_key(&device.endpoint), DISABLE_SYSFX, 0)
+            .map(|()| format!("{} = 0", DISABLE_SYSFX));
+        let ok = result.is_ok();
+        results.push(&label, result);
+        ok
+    } else {
+        let mut journal = TweakJournal::load();
+        let result = set_journaled_dword(
+            &mut journal,
+            TweakSource::Audio,
+            &label,
+            RegistryRoot::LocalMachine,
+            &fx_key(&device.endpoint),
+            DISABLE_SYSFX,
+            1,
+        );
+        if let Err(e) = journal.save() {
+            results.warnings.push(format!("Journal non enregistré, restauration impossible : {}", e));
+        }
+        let ok = result.is_ok();
+        results.push(&label, result);
+        ok
+    };
+    if changed {
+        results.push("Service audio redémarré", restart_audio_service());
+    }
+    tracing::info!("🔊 Améliorations audio de {} : {}", device.name, if enabled { "activées" } else { "désactivées" });
+    results
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn set_enhancements(_device: &AudioDevice, _enabled: bool) -> OsTweaksResults {
+    OsTweaksResults {
+        warnings: vec!["Améliorations audio disponibles uniquement sous Windows".to_string()],
+        ..Default::default()
+    }
+}
+
+/// For the time of the gaming mode, turn the enhancements of every active device off and/or
+/// raise the priorities of the MMCSS "Games" task
+#[cfg(target_os = "windows")]
+pub fn enter_gaming_mode(enhancements: bool, mmcss: bool) -> OsTweaksResults {
+    use crate::disk::os_tweaks::{set_journaled_dword, RegistryRoot, TweakJournal};
+
+    let _guard = os_tweaks::lock_journal();
+    let mut results = OsTweaksResults::default();
+    let mut journal = TweakJournal::load();
+
+    let mut changed = false;
+    let devices = if enhancements { list_render_devices() } else { Ok(Vec::new()) };
+    match devices {
+        Ok(devices) => {
+            for device in devices.iter().filter(|device| device.enhancements_enabled) {
+                let label = format!("Améliorations audio : {}", device.name);
+                let result = set_journaled_dword(
+                    &mut journal,
+                    TweakSource::GamingMode,
+                    &label,
+                    RegistryRoot::LocalMachine,
+                    &fx_key(&device.endpoint),
+                    DISABLE_SYSFX,
+                    1,
+                );
+                changed |= result.is_ok();
+                results.push(&label, result);
+            }
+        }
+        Err(e) => results.warnings.push(format!("Périphériques audio non listés : {}", e)),
+    }
+    if mmcss {
+        for (name, value, label) in MMCSS_VALUES {
+            let result = set_journaled_dword(
+                &mut journal,
+                TweakSource::GamingMode,
+                label,
+                RegistryRoot::LocalMachine,
+                MMCSS_GAMES_KEY,
+                name,
+                *value,
+            );
+            results.push(label, result);
+        }
+    }
+    if let Err(e) = journal.save() {
+        tracing::error!("❌ Journal des optimisations non enregistré: {}", e);
+        results.warnings.push(format!("Journal non enregistré, restauration impossible : {}", e));
+    }
+    if changed {
+        results.push("Service audio redémarré", restart_audio_service());
+    }
+    tracing::info!("🔊 Mode jeu audio : {} réglage(s) traité(s)", results.outcomes.len());
+    results
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn enter_gaming_mode(_enhancements: bool, _mmcss: bool) -> OsTweaksResults {
+    OsTweaksResults::default()
+}
+
+/// Undo what `enter_gaming_mode` changed, from the journal only. Nothing to do is not an error.
+pub fn exit_gaming_mode() -> OsTweaksResults {
+    let _guard = os_tweaks::lock_journal();
+    let journal = os_tweaks::TweakJournal::load();
+    let gaming_entries: Vec<&JournalEntry> =
+        journal.entries.iter().filter(|entry| entry.source == TweakSource::GamingMode).collect();
+    if gaming_entries.is_empty() {
+        return OsTweaksResults::default();
+    }
+    let touches_devices = gaming_entries.iter().any(|entry| is_enhancements_entry(entry, None));
+
+    #[allow(unused_mut)]
+    let mut results = os_tweaks::restore_matching(|entry| entry.source == TweakSource::GamingMode);
+    #[cfg(target_os = "windows")]
+    if touches_devices && results.outcomes.iter().any(|outcome| outcome.success) {
+        results.push("Service audio redémarré", restart_audio_service());
+    }
+    #[cfg(not(target_os = "windows"))]
+    let _ = touches_devices;
+    tracing::info!("🔊 Réglages audio du mode jeu restaurés ({})", results.outcomes.len());
+    results
+}
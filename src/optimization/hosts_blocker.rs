@@ -0,0 +1,330 @@
+//! # Hosts file blocker
+//!
+//! Blocks telemetry and launcher update hosts by pointing them to `0.0.0.0` in the system
+//! hosts file. GameBooster only writes between its own `# BEGIN GameBooster` /
+//! `# END GameBooster` lines; the rest of the file is kept byte-for-byte. The file is
+//! replaced atomically (temporary file + rename) and the DNS cache is flushed afterwards.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+const SECTION_BEGIN: &str = "# BEGIN GameBooster - section gérée automatiquement, ne pas modifier";
+const SECTION_END: &str = "# END GameBooster";
+const BLOCK_ADDRESS: &str = "0.0.0.0";
+
+/// Built-in list of hosts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum BuiltinProfile {
+    WindowsTelemetry,
+    LauncherUpdates,
+}
+
+impl BuiltinProfile {
+    pub const ALL: [BuiltinProfile; 2] = [BuiltinProfile::WindowsTelemetry, BuiltinProfile::LauncherUpdates];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BuiltinProfile::WindowsTelemetry => "Télémétrie Windows",
+            BuiltinProfile::LauncherUpdates => "Mises à jour des launchers",
+        }
+    }
+
+    pub fn domains(&self) -> &'static [&'static str] {
+        match self {
+            BuiltinProfile::WindowsTelemetry => &[
+                "vortex.data.microsoft.com",
+                "vortex-win.data.microsoft.com",
+                "settings-win.data.microsoft.com",
+                "v10.events.data.microsoft.com",
+                "v20.events.data.microsoft.com",
+                "self.events.data.microsoft.com",
+                "watson.telemetry.microsoft.com",
+                "watson.microsoft.com",
+                "telecommand.telemetry.microsoft.com",
+                "oca.telemetry.microsoft.com",
+                "sqm.telemetry.microsoft.com",
+                "df.telemetry.microsoft.com",
+            ],
+            // Mises à jour des clients uniquement : les serveurs de jeu et de connexion restent joignables
+            BuiltinProfile::LauncherUpdates => &[
+                "client-update.akamai.steamstatic.com",
+                "client-update.steamstatic.com",
+                "download.epicgames.com",
+                "us.patch.battle.net",
+                "eu.patch.battle.net",
+                "origin-a.akamaihd.net",
+            ],
+        }
+    }
+}
+
+/// What `apply_profile` writes: the enabled built-in lists and the user's domains,
+/// minus the entries switched off in the Optimization tab
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlockProfile {
+    pub builtin: BTreeSet<BuiltinProfile>,
+    pub custom_domains: Vec<String>,
+    pub disabled_domains: BTreeSet<String>,
+}
+
+impl Default for BlockProfile {
+    fn default() -> Self {
+        Self {
+            builtin: BTreeSet::from([BuiltinProfile::WindowsTelemetry]),
+            custom_domains: Vec::new(),
+            disabled_domains: BTreeSet::new(),
+        }
+    }
+}
+
+impl BlockProfile {
+    /// Every domain of the profile, disabled ones included, without duplicates
+    pub fn all_domains(&self) -> Vec<String> {
+        let mut domains: Vec<String> = Vec::new();
+        let builtin = self.builtin.iter().flat_map(|profile| profile.domains().iter().map(|d| d.to_string()));
+        for domain in builtin.chain(self.custom_domains.iter().cloned()) {
+            if !domains.contains(&domain) {
+                domains.push(domain);
+            }
+        }
+        domains
+    }
+
+    pub fn enabled_domains(&self) -> Vec<String> {
+        self.all_domains()
+            .into_iter()
+            .filter(|domain| !self.disabled_domains.contains(domain))
+            .collect()
+    }
+}
+
+/// Lowercased domain, `None` for anything that cannot go in a hosts line
+pub fn normalize_domain(input: &str) -> Option<String> {
+    let domain = input.trim().trim_end_matches('.').to_lowercase();
+    let valid = !domain.is_empty()
+        && domain.len() <= 253
+        && domain.contains('.')
+        && domain.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+        && domain.split('.').all(|label| !label.is_empty() && !label.starts_with('-') && !label.ends_with('-'));
+    valid.then_some(domain)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HostsStatus {
+    pub section_present: bool,
+    /// Domains currently blocked by our section
+    pub entries: Vec<String>,
+}
+
+impl HostsStatus {
+    pub fn entry_count(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+pub fn hosts_path() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        let system_root = std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string());
+        PathBuf::from(system_root).join("System32").join("drivers").join("etc").join("hosts")
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        PathBuf::from("/etc/hosts")
+    }
+}
+
+/// Presence of our section and the domains it blocks
+pub fn status() -> Result<HostsStatus> {
+    let content = fs::read(hosts_path())?;
+    let Some((start, end)) = find_section(&content) else {
+        return Ok(HostsStatus::default());
+    };
+
+    let entries = String::from_utf8_lossy(&content[start..end])
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            match (fields.next(), fields.next()) {
+                (Some(BLOCK_ADDRESS), Some(domain)) => Some(domain.to_string()),
+                _ => None,
+            }
+        })
+        .collect();
+    Ok(HostsStatus {
+        section_present: true,
+        entries,
+    })
+}
+
+/// Replace our section with the enabled domains of `profile` (section removed when none)
+pub fn apply_profile(profile: &BlockProfile) -> Result<HostsStatus> {
+    let domains = profile.enabled_domains();
+    if domains.is_empty() {
+        return remove_profile();
+    }
+
+    let content = read_for_update()?;
+    let updated = with_section(&content, &domains);
+    write_atomically(&updated)?;
+    tracing::info!("🚫 Fichier hosts: {} domaines bloqués", domains.len());
+    flush_dns_cache();
+    status()
+}
+
+/// Strip our section, leaving the rest of the file untouched
+pub fn remove_profile() -> Result<HostsStatus> {
+    let content = read_for_update()?;
+    if let Some((updated, _)) = without_section(&content) {
+        write_atomically(&updated)?;
+        tracing::info!("♻️ Section GameBooster retirée du fichier hosts");
+        flush_dns_cache();
+    }
+    status()
+}
+
+fn read_for_update() -> Result<Vec<u8>> {
+    if !crate::utils::is_elevated() {
+        return Err(anyhow!("Droits administrateur requis pour modifier {}", hosts_path().display()));
+    }
+    Ok(fs::read(hosts_path())?)
+}
+
+/// `content` with our section (re)written for `domains`, at the place of the previous one or at the end
+fn with_section(content: &[u8], domains: &[String]) -> Vec<u8> {
+    let newline = line_ending(content);
+    let (mut updated, insert_at) = without_section(content).unwrap_or_else(|| (content.to_vec(), content.len()));
+
+    let mut section = String::new();
+    // Fichier sans saut de ligne final : la section commence sur sa propre ligne et la ligne END
+    // reste sans saut de ligne, ce qui permet à without_section de rendre le fichier d'origine
+    let unterminated = insert_at > 0 && updated[insert_at - 1] != b'\n';
+    if unterminated {
+        section.push_str(newline);
+    }
+    section.push_str(SECTION_BEGIN);
+    section.push_str(newline);
+    for domain in domains {
+        section.push_str(&format!("{} {}{}", BLOCK_ADDRESS, domain, newline));
+    }
+    section.push_str(SECTION_END);
+    if !unterminated {
+        section.push_str(newline);
+    }
+    updated.splice(insert_at..insert_at, section.into_bytes());
+    updated
+}
+
+/// `content` without our section, plus the offset where it was
+fn without_section(content: &[u8]) -> Option<(Vec<u8>, usize)> {
+    let (mut start, end) = find_section(content)?;
+    // Ligne END sans saut de ligne final : le saut de ligne qui précède BEGIN a été ajouté par with_section
+    if end == content.len() && content.last() != Some(&b'\n') && start > 0 {
+        start -= 1;
+        if start > 0 && content[start - 1] == b'\r' {
+            start -= 1;
+        }
+    }
+    let mut updated = content[..start].to_vec();
+    updated.extend_from_slice(&content[end..]);
+    Some((updated, start))
+}
+
+/// Byte range of our section, from the start of the BEGIN line to the end of the END line
+fn find_section(content: &[u8]) -> Option<(usize, usize)> {
+    let start = find_line(content, SECTION_BEGIN.as_bytes(), 0)?;
+    let end_line = find_line(content, SECTION_END.as_bytes(), start)?;
+    let end = content[end_line..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map(|offset| end_line + offset + 1)
+        .unwrap_or(content.len());
+    Some((start, end))
+}
+
+/// Offset of the first line starting with `marker` at or after `from`
+fn find_line(content: &[u8], marker: &[u8], from: usize) -> Option<usize> {
+    (from..content.len()).find(|&i| (i == 0 || content[i - 1] == b'\n') && content[i..].starts_with(marker))
+}
+
+fn line_ending(content: &[u8]) -> &'static str {
+    if content.windows(2).any(|w| w == b"\r\n") || (content.is_empty() && cfg!(target_os = "windows")) {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+/// Write next to the hosts file then rename over it, so that a failure never leaves it truncated
+fn write_atomically(content: &[u8]) -> Result<()> {
+    let path = hosts_path();
+    let temp = path.with_file_name("hosts.gamebooster.tmp");
+    fs::write(&temp, content)?;
+    if let Err(e) = fs::rename(&temp, &path) {
+        let _ = fs::remove_file(&temp);
+        return Err(anyhow!("Remplacement de {} impossible: {}", path.display(), e));
+    }
+    Ok(())
+}
+
+/// Best effort: an unflushed cache only delays the effect until the entries expire
+fn flush_dns_cache() {
+    #[cfg(target_os = "windows")]
+    let result = Command::new("ipconfig")
+        .arg("/flushdns")
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .output();
+    #[cfg(not(target_os = "windows"))]
+    let result = Command::new("resolvectl").arg("flush-caches").output();
+
+    match result {
+        Ok(output) if output.status.success() => tracing::info!("🔄 Cache DNS vidé"),
+        Ok(output) => tracing::warn!("⚠️ Vidage du cache DNS échoué: {}", String::from_utf8_lossy(&output.stdout).trim()),
+        Err(e) => tracing::warn!("⚠️ Vidage du cache DNS impossible: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn domains() -> Vec<String> {
+        vec!["vortex.data.microsoft.com".to_string(), "watson.microsoft.com".to_string()]
+    }
+
+    #[test]
+    fn remove_after_apply_restores_the_original_bytes() {
+        for original in [
+            &b""[..],
+            b"127.0.0.1 localhost",
+            b"127.0.0.1 localhost\n",
+            b"127.0.0.1 localhost\r\n::1 localhost",
+            b"127.0.0.1 localhost\r\n::1 localhost\r\n",
+        ] {
+            let applied = with_section(original, &domains());
+            let (restored, _) = without_section(&applied).unwrap();
+            assert_eq!(restored, original, "{:?}", String::from_utf8_lossy(original));
+
+            // Réappliquer par-dessus une section existante ne doit pas empiler les sauts de ligne
+            let reapplied = with_section(&applied, &domains());
+            assert_eq!(reapplied, applied);
+        }
+    }
+
+    #[test]
+    fn section_starts_on_its_own_line() {
+        let applied = with_section(b"127.0.0.1 localhost", &domains());
+        let text = String::from_utf8(applied).unwrap();
+        assert!(text.starts_with(&format!("127.0.0.1 localhost\n{}\n", SECTION_BEGIN)));
+        assert!(text.ends_with(SECTION_END));
+    }
+}
@@ -0,0 +1,11 @@
+//! # System optimizations
+//!
+//! Gaming-oriented system changes offered in the Optimization tab that are not tied to disk
+//! cleaning. Each submodule keeps track of what it changed so that it can be undone. The
+//! switches shown with their current state are registered in `tweaks::TweakRegistry`.
+
+pub mod audio;
+pub mod hosts_blocker;
+pub mod startup_manager;
+pub mod tweaks;
+pub mod windows_gaming;
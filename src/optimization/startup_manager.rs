@@ -0,0 +1,359 @@
+//! # Startup programs
+//!
+//! Lists the programs started at logon: the `Run` registry keys (user, machine and 32-bit
+//! machine), the user and common Startup folders, and the scheduled tasks with a logon trigger.
+//! Entries are switched off the way Task Manager does it, through the `StartupApproved`
+//! registry keys: the entry itself is never deleted and re-enabling restores it. Scheduled
+//! tasks are listed read-only.
+
+use anyhow::{anyhow, Result};
+
+#[cfg(target_os = "windows")]
+use crate::utils::registry::{self, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+#[cfg(target_os = "windows")]
+use windows_sys::Win32::System::Registry::HKEY;
+#[cfg(target_os = "windows")]
+use std::path::{Path, PathBuf};
+
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
+#[cfg(target_os = "windows")]
+const RUN32_KEY: &str = "Software\\WOW6432Node\\Microsoft\\Windows\\CurrentVersion\\Run";
+#[cfg(target_os = "windows")]
+const APPROVED_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\StartupApproved";
+
+/// Where an autostart entry is registered
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartupLocation {
+    RunUser,
+    RunMachine,
+    /// `Run` key of the 32-bit registry view
+    RunMachine32,
+    StartupFolderUser,
+    StartupFolderCommon,
+    ScheduledTask,
+}
+
+impl StartupLocation {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StartupLocation::RunUser => "Registre (utilisateur)",
+            StartupLocation::RunMachine => "Registre (machine)",
+            StartupLocation::RunMachine32 => "Registre (machine, 32 bits)",
+            StartupLocation::StartupFolderUser => "Dossier Démarrage (utilisateur)",
+            StartupLocation::StartupFolderCommon => "Dossier Démarrage (tous)",
+            StartupLocation::ScheduledTask => "Tâche planifiée",
+        }
+    }
+
+    /// HKLM and the common Startup folder are shared by all users
+    pub fn requires_elevation(&self) -> bool {
+        matches!(
+            self,
+            StartupLocation::RunMachine | StartupLocation::RunMachine32 | StartupLocation::StartupFolderCommon
+        )
+    }
+
+    /// Scheduled tasks are only listed for now
+    pub fn is_read_only(&self) -> bool {
+        *self == StartupLocation::ScheduledTask
+    }
+}
+
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+#[derive(Debug, Clone)]
+pub struct StartupEntry {
+    pub name: String,
+    pub command: String,
+    pub location: StartupLocation,
+    pub enabled: bool,
+    /// Company name of the executable, or author of the task
+    pub publisher: Option<String>,
+    /// Registry value name, file name or task path: what `StartupApproved` is keyed on
+    pub id: String,
+}
+
+/// Every autostart entry, enabled or not. Locations that cannot be read are logged and skipped.
+pub fn list_entries() -> Result<Vec<StartupEntry>> {
+    #[cfg(target_os = "windows")]
+    {
+        let mut entries = Vec::new();
+        for (location, root, path) in [
+            (StartupLocation::RunUser, HKEY_CURRENT_USER, RUN_KEY),
+            (StartupLocation::RunMachine, HKEY_LOCAL_MACHINE, RUN_KEY),
+            (StartupLocation::RunMachine32, HKEY_LOCAL_MACHINE, RUN32_KEY),
+        ] {
+            match run_key_entries(location, root, path) {
+                Ok(found) => entries.extend(found),
+                Err(e) => tracing::warn!("⚠️ Lecture de {} impossible: {}", location.label(), e),
+            }
+        }
+        for location in [StartupLocation::StartupFolderUser, StartupLocation::StartupFolderCommon] {
+            entries.extend(startup_folder_entries(location));
+        }
+        match logon_task_entries() {
+            Ok(found) => entries.extend(found),
+            Err(e) => tracing::warn!("⚠️ Lecture des tâches planifiées impossible: {}", e),
+        }
+
+        entries.sort_by_key(|entry| entry.name.to_lowercase());
+        Ok(entries)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        Ok(Vec::new())
+    }
+}
+
+/// Current state of `entry`, read from the system rather than from `entry.enabled`
+pub fn read_enabled(entry: &StartupEntry) -> Result<bool> {
+    #[cfg(target_os = "windows")]
+    {
+        if entry.location.is_read_only() {
+            return Ok(entry.enabled);
+        }
+        let (root, key) = approved_key(entry.location);
+        let data = registry::read_binary(root, &key, &entry.id)?;
+        Ok(is_approved(data.as_deref()))
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        Ok(entry.enabled)
+    }
+}
+
+/// Enable or disable `entry` through its `StartupApproved` value, like Task Manager
+pub fn set_enabled(entry: &StartupEntry, enabled: bool) -> Result<()> {
+    if entry.location.is_read_only() {
+        return Err(anyhow!("{} : les tâches planifiées sont en lecture seule", entry.name));
+    }
+    if entry.location.requires_elevation() && !crate::utils::is_elevated() {
+        return Err(anyhow!("Droits administrateur requis pour modifier {} ({})", entry.name, entry.location.label()));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let (root, key) = approved_key(entry.location);
+        registry::write_binary(root, &key, &entry.id, &approved_value(enabled))?;
+        tracing::info!(
+            "🚀 {} {} au démarrage ({})",
+            entry.name,
+            if enabled { "réactivé" } else { "désactivé" },
+            entry.location.label()
+        );
+        Ok(())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = enabled;
+        Err(anyhow!("Gestion du démarrage disponible uniquement sous Windows"))
+    }
+}
+
+/// Missing value: the entry was never switched off. Task Manager sets the low bit to disable.
+#[cfg(target_os = "windows")]
+fn is_approved(data: Option<&[u8]>) -> bool {
+    !matches!(data.and_then(|data| data.first()), Some(flags) if flags & 1 != 0)
+}
+
+/// 12 bytes: state flags, then the FILETIME of the deactivation (zero when enabled)
+#[cfg(target_os = "windows")]
+fn approved_value(enabled: bool) -> [u8; 12] {
+    let mut value = [0u8; 12];
+    if enabled {
+        value[0] = 0x02;
+    } else {
+        value[0] = 0x03;
+        // FILETIME : intervalles de 100 ns depuis le 01/01/1601
+        let unix_secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        let filetime = (unix_secs + 11_644_473_600) * 10_000_000;
+        value[4..].copy_from_slice(&filetime.to_le_bytes());
+    }
+    value
+}
+
+#[cfg(target_os = "windows")]
+fn approved_key(location: StartupLocation) -> (HKEY, String) {
+    let (root, subkey) = match location {
+        StartupLocation::RunUser => (HKEY_CURRENT_USER, "Run"),
+        StartupLocation::RunMachine => (HKEY_LOCAL_MACHINE, "Run"),
+        StartupLocation::RunMachine32 => (HKEY_LOCAL_MACHINE, "Run32"),
+        StartupLocation::StartupFolderUser => (HKEY_CURRENT_USER, "StartupFolder"),
+        StartupLocation::StartupFolderCommon | StartupLocation::ScheduledTask => (HKEY_LOCAL_MACHINE, "StartupFolder"),
+    };
+    (root, format!("{}\\{}", APPROVED_KEY, subkey))
+}
+
+#[cfg(target_os = "windows")]
+fn run_key_entries(location: StartupLocation, root: HKEY, path: &str) -> Result<Vec<StartupEntry>> {
+    let (approved_root, approved) = approved_key(location);
+    let mut entries = Vec::new();
+    for name in registry::list_value_names(root, path)? {
+        // La valeur par défaut d'une clé n'est pas une entrée de démarrage
+        if name.is_empty() {
+            continue;
+        }
+        let Some(command) = registry::read_string(root, path, &name)? else {
+            continue;
+        };
+        let enabled = is_approved(registry::read_binary(approved_root, &approved, &name).ok().flatten().as_deref());
+        entries.push(StartupEntry {
+            publisher: executable_path(&command).and_then(|exe| file_publisher(&exe)),
+            name: name.clone(),
+            command,
+            location,
+            enabled,
+            id: name,
+        });
+    }
+    Ok(entries)
+}
+
+#[cfg(target_os = "windows")]
+fn startup_folder(location: StartupLocation) -> Option<PathBuf> {
+    let base = match location {
+        StartupLocation::StartupFolderUser => dirs::data_dir()?,
+        _ => PathBuf::from(std::env::var("ProgramData").ok()?),
+    };
+    Some(base.join("Microsoft\\Windows\\Start Menu\\Programs\\Startup"))
+}
+
+#[cfg(target_os = "windows")]
+fn startup_folder_entries(location: StartupLocation) -> Vec<StartupEntry> {
+    let Some(folder) = startup_folder(location) else {
+        return Vec::new();
+    };
+    let Ok(files) = std::fs::read_dir(&folder) else {
+        return Vec::new();
+    };
+    let (approved_root, approved) = approved_key(location);
+
+    files
+        .flatten()
+        .filter(|file| file.file_type().is_ok_and(|t| t.is_file()))
+        .filter(|file| !file.file_name().to_string_lossy().eq_ignore_ascii_case("desktop.ini"))
+        .map(|file| {
+            let path = file.path();
+            let id = file.file_name().to_string_lossy().to_string();
+            let is_exe = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("exe"));
+            StartupEntry {
+                name: path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_else(|| id.clone()),
+                command: path.display().to_string(),
+                location,
+                enabled: is_approved(registry::read_binary(approved_root, &approved, &id).ok().flatten().as_deref()),
+                publisher: if is_exe { file_publisher(&path) } else { None },
+                id,
+            }
+        })
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct LogonTask {
+    path: String,
+    name: String,
+    command: Option<String>,
+    enabled: bool,
+    author: Option<String>,
+}
+
+#[cfg(target_os = "windows")]
+fn logon_task_entries() -> Result<Vec<StartupEntry>> {
+    let script = "$tasks = Get-ScheduledTask | Where-Object { $_.Triggers | Where-Object { $_.CimClass.CimClassName -eq 'MSFT_TaskLogonTrigger' } } | \
+        ForEach-Object { [PSCustomObject]@{ Path = $_.TaskPath + $_.TaskName; Name = $_.TaskName; \
+        Command = ($_.Actions | Where-Object { $_.Execute } | Select-Object -First 1 | ForEach-Object { ($_.Execute + ' ' + $_.Arguments).Trim() }); \
+        Enabled = ($_.State -ne 'Disabled'); Author = $_.Author } }; \
+        ConvertTo-Json -Compress -InputObject @($tasks)";
+    let output = crate::services::powershell_runner::run_powershell_command_blocking(script)?;
+    let tasks: Vec<LogonTask> = serde_json::from_str(output.trim())?;
+
+    Ok(tasks
+        .into_iter()
+        .map(|task| StartupEntry {
+            name: task.name,
+            command: task.command.unwrap_or_default(),
+            location: StartupLocation::ScheduledTask,
+            enabled: task.enabled,
+            publisher: task.author.filter(|author| !author.is_empty()),
+            id: task.path,
+        })
+        .collect())
+}
+
+/// Executable of a command line: quoted path, or everything up to `.exe`
+#[cfg(target_os = "windows")]
+fn executable_path(command: &str) -> Option<PathBuf> {
+    let command = expand_env_vars(command.trim());
+    let path = if let Some(rest) = command.strip_prefix('"') {
+        rest.split('"').next()?.to_string()
+    } else {
+        let end = command.to_ascii_lowercase().find(".exe").map(|index| index + 4).unwrap_or(command.len());
+        command[..end].to_string()
+    };
+    let path = PathBuf::from(path);
+    path.is_file().then_some(path)
+}
+
+/// Replace `%VAR%` with the environment value, left untouched when unknown
+#[cfg(target_os = "windows")]
+fn expand_env_vars(text: &str) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('%') {
+        let Some(len) = rest[start + 1..].find('%') else {
+            break;
+        };
+        let name = &rest[start + 1..start + 1 + len];
+        result.push_str(&rest[..start]);
+        match std::env::var(name) {
+            Ok(value) => result.push_str(&value),
+            Err(_) => result.push_str(&rest[start..start + len + 2]),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// CompanyName of the version resource of `path`
+#[cfg(target_os = "windows")]
+fn file_publisher(path: &Path) -> Option<String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::{GetFileVersionInfoSizeW, GetFileVersionInfoW, VerQueryValueW};
+
+    let to_wide = |text: &str| text.encode_utf16().chain(std::iter::once(0)).collect::<Vec<u16>>();
+    let path_w: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+
+    let size = unsafe { GetFileVersionInfoSizeW(path_w.as_ptr(), std::ptr::null_mut()) };
+    if size == 0 {
+        return None;
+    }
+    let mut data = vec![0u8; size as usize];
+    if unsafe { GetFileVersionInfoW(path_w.as_ptr(), 0, size, data.as_mut_ptr().cast()) } == 0 {
+        return None;
+    }
+
+    // Première langue déclarée, anglais US / Unicode à défaut
+    let mut translation = "040904b0".to_string();
+    let mut pointer: *mut std::ffi::c_void = std::ptr::null_mut();
+    let mut len = 0u32;
+    let query = to_wide("\\VarFileInfo\\Translation");
+    if unsafe { VerQueryValueW(data.as_ptr().cast(), query.as_ptr(), &mut pointer, &mut len) } != 0 && len >= 4 {
+        let codes = unsafe { std::slice::from_raw_parts(pointer as *const u16, 2) };
+        translation = format!("{:04x}{:04x}", codes[0], codes[1]);
+    }
+
+    let query = to_wide(&format!("\\StringFileInfo\\{}\\CompanyName", translation));
+    if unsafe { VerQueryValueW(data.as_ptr().cast(), query.as_ptr(), &mut pointer, &mut len) } == 0 || len == 0 {
+        return None;
+    }
+    let text = unsafe { std::slice::from_raw_parts(pointer as *const u16, len as usize) };
+    let publisher = String::from_utf16_lossy(text).trim_end_matches('\0').trim().to_string();
+    (!publisher.is_empty()).then_some(publisher)
+}
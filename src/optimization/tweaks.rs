@@ -0,0 +1,516 @@
+//! # Tweak registry
+//!
+//! Every switch of the Optimization tab (Game Mode, GameDVR, GPU scheduling, power plan,
+//! audio enhancements) implements `Tweak`: it reads its current state from the system, applies
+//! itself and reverts itself. The tab renders the `TweakRegistry` generically, so a checkbox
+//! shows what Windows says rather than what was last clicked.
+//!
+//! States are cached: `current_state` can run powercfg or open registry keys, too slow for
+//! every frame. The cache is filled on a worker thread (see `read_states`), refreshed with the
+//! button of the tab and after each apply / revert of the tweak concerned.
+//!
+//! Registry changes are journaled in `os_tweaks` before being written; `revert` puts back the
+//! journaled value and only falls back to the Windows default when nothing was journaled (a
+//! tweak already applied before GameBooster), so it also works after a restart.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+
+use crate::disk::os_tweaks::{self, PreviousValue, RegistryRoot};
+
+/// Prefix of the ids of the per-device audio tweaks, replaced when the devices are listed again
+pub const AUDIO_TWEAK_PREFIX: &str = "audio:";
+/// Id of `HighPerformancePlan`, read again whenever the plan is switched elsewhere
+pub const POWER_PLAN_TWEAK_ID: &str = "high_performance_plan";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TweakState {
+    Applied,
+    NotApplied,
+    /// The state could not be read
+    Unknown,
+    /// A machine policy decides, the switch is refused
+    BlockedByPolicy,
+}
+
+impl TweakState {
+    pub fn label(self) -> &'static str {
+        match self {
+            TweakState::Applied => "✅ Appliqué",
+            TweakState::NotApplied => "Non appliqué",
+            TweakState::Unknown => "❔ Inconnu",
+            TweakState::BlockedByPolicy => "🔒 Bloqué par une stratégie",
+        }
+    }
+}
+
+/// What `apply` changed, shown in the notification. The values to put back are in the
+/// `os_tweaks` journal: `revert` also works with `Applied::default()` after a restart.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Applied {
+    /// "AutoGameModeEnabled : 0 → 1"
+    pub detail: String,
+}
+
+pub trait Tweak: Send + Sync {
+    /// Stable key of the cache, also used in the logs
+    fn id(&self) -> &str;
+    fn label(&self) -> String;
+    /// HKLM values and machine-wide settings
+    fn requires_elevation(&self) -> bool {
+        false
+    }
+    fn current_state(&self) -> Result<TweakState>;
+    fn apply(&self) -> Result<Applied>;
+    fn revert(&self, applied: &Applied) -> Result<()>;
+}
+
+/// Last state read for a tweak
+#[derive(Debug, Clone)]
+pub struct CachedState {
+    pub state: TweakState,
+    /// Why the state is `Unknown`
+    pub error: Option<String>,
+    pub checked_at: DateTime<Local>,
+}
+
+impl CachedState {
+    fn read(tweak: &dyn Tweak) -> Self {
+        let (state, error) = match tweak.current_state() {
+            Ok(state) => (state, None),
+            Err(e) => (TweakState::Unknown, Some(format!("{:#}", e))),
+        };
+        Self { state, error, checked_at: Local::now() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TweakAction {
+    Apply,
+    Revert,
+}
+
+/// Outcome of `run`, with the state read right after
+#[derive(Debug, Clone)]
+pub struct TweakRun {
+    pub id: String,
+    pub label: String,
+    pub action: TweakAction,
+    pub outcome: Result<Applied, String>,
+    pub state: CachedState,
+}
+
+/// Apply or revert `tweak`, then read its state again. Blocking: run it on a worker thread.
+pub fn run(tweak: Arc<dyn Tweak>, action: TweakAction, applied: Applied) -> TweakRun {
+    let outcome = match action {
+        TweakAction::Apply => tweak.apply(),
+        TweakAction::Revert => tweak.revert(&applied).map(|()| Applied::default()),
+    };
+    match &outcome {
+        Ok(_) => tracing::info!("⚙️ {} : {}", tweak.label(), if action == TweakAction::Apply { "appliqué" } else { "annulé" }),
+        Err(e) => tracing::error!("❌ {} ({:?}) : {:#}", tweak.label(), action, e),
+    }
+    TweakRun {
+        id: tweak.id().to_string(),
+        label: tweak.label(),
+        action,
+        outcome: outcome.map_err(|e| format!("{:#}", e)),
+        state: CachedState::read(tweak.as_ref()),
+    }
+}
+
+/// States of `tweaks`, read in order. Blocking: run it on a worker thread.
+pub fn read_states(tweaks: &[Arc<dyn Tweak>]) -> Vec<(String, CachedState)> {
+    tweaks.iter().map(|tweak| (tweak.id().to_string(), CachedState::read(tweak.as_ref()))).collect()
+}
+
+/// Tweaks shown in the Optimization tab, with their cached states
+#[derive(Default)]
+pub struct TweakRegistry {
+    tweaks: Vec<Arc<dyn Tweak>>,
+    states: HashMap<String, CachedState>,
+    /// Outcome of the last `apply` of each tweak of this session
+    applied: HashMap<String, Applied>,
+}
+
+impl TweakRegistry {
+    /// Built-in tweaks of this Windows version; the audio devices are added once listed
+    pub fn standard() -> Self {
+        use crate::optimization::windows_gaming::GamingFeature;
+
+        let mut registry = Self::default();
+        for feature in GamingFeature::ALL {
+            registry.register(Arc::new(feature));
+        }
+        if crate::os_info::capabilities().gpu_scheduling {
+            registry.register(Arc::new(GPU_SCHEDULING));
+        }
+        registry.register(Arc::new(HighPerformancePlan));
+        registry
+    }
+
+    /// Add `tweak`, or replace the one with the same id; its state will be read again
+    pub fn register(&mut self, tweak: Arc<dyn Tweak>) {
+        self.states.remove(tweak.id());
+        match self.tweaks.iter_mut().find(|known| known.id() == tweak.id()) {
+            Some(known) => *known = tweak,
+            None => self.tweaks.push(tweak),
+        }
+    }
+
+    /// Replace every tweak whose id starts with `prefix` by `tweaks`
+    pub fn replace_prefixed(&mut self, prefix: &str, tweaks: Vec<Arc<dyn Tweak>>) {
+        self.tweaks.retain(|tweak| !tweak.id().starts_with(prefix));
+        self.states.retain(|id, _| !id.starts_with(prefix));
+        for tweak in tweaks {
+            self.register(tweak);
+        }
+    }
+
+    pub fn tweaks(&self) -> &[Arc<dyn Tweak>] {
+        &self.tweaks
+    }
+
+    pub fn get(&self, id: &str) -> Option<Arc<dyn Tweak>> {
+        self.tweaks.iter().find(|tweak| tweak.id() == id).cloned()
+    }
+
+    pub fn state(&self, id: &str) -> Option<&CachedState> {
+        self.states.get(id)
+    }
+
+    /// Tweaks without a cached state, to pass to `read_states`
+    pub fn stale(&self) -> Vec<Arc<dyn Tweak>> {
+        self.tweaks.iter().filter(|tweak| !self.states.contains_key(tweak.id())).cloned().collect()
+    }
+
+    /// Forget every cached state: the next `stale` returns all the tweaks
+    pub fn invalidate(&mut self) {
+        self.states.clear();
+    }
+
+    /// Forget the cached state of `id` only
+    pub fn forget(&mut self, id: &str) {
+        self.states.remove(id);
+    }
+
+    /// Store states read by `read_states`; those of tweaks removed meanwhile are dropped
+    pub fn store_states(&mut self, states: Vec<(String, CachedState)>) {
+        for (id, state) in states {
+            if self.tweaks.iter().any(|tweak| tweak.id() == id) {
+                self.states.insert(id, state);
+            }
+        }
+    }
+
+    /// Record the outcome of `run`
+    pub fn finish(&mut self, run: &TweakRun) {
+        match (&run.outcome, run.action) {
+            (Ok(applied), TweakAction::Apply) => {
+                self.applied.insert(run.id.clone(), applied.clone());
+            }
+            (Ok(_), TweakAction::Revert) => {
+                self.applied.remove(&run.id);
+            }
+            (Err(_), _) => {}
+        }
+        self.store_states(vec![(run.id.clone(), run.state.clone())]);
+    }
+
+    /// What the last `apply` of this session changed, empty otherwise (see `Applied`)
+    pub fn applied(&self, id: &str) -> Applied {
+        self.applied.get(id).cloned().unwrap_or_default()
+    }
+}
+
+/// Put back the journaled values of `targets` (root, path, value name). `Ok(false)` when none
+/// of them was journaled. The caller holds `os_tweaks::lock_journal`.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub(crate) fn restore_journaled(targets: &[(RegistryRoot, &str, &str)]) -> Result<bool> {
+    let is_target = |previous: &PreviousValue| match previous {
+        PreviousValue::Registry { root, path, name, .. } => targets
+            .iter()
+            .any(|(r, p, n)| r == root && p.eq_ignore_ascii_case(path) && n.eq_ignore_ascii_case(name)),
+        PreviousValue::PowerScheme(_) => false,
+    };
+    if !os_tweaks::TweakJournal::load().entries.iter().any(|entry| is_target(&entry.previous)) {
+        return Ok(false);
+    }
+    os_tweaks::restore_matching(|entry| is_target(&entry.previous)).into_result()?;
+    Ok(true)
+}
+
+/// One DWORD value set to `applied_value`: adding such a tweak is a constant and a line in
+/// `TweakRegistry::standard`
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub struct DwordTweak {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub root: RegistryRoot,
+    pub path: &'static str,
+    pub name: &'static str,
+    pub applied_value: u32,
+    /// Written by `revert` when no previous value was journaled
+    pub reverted_value: u32,
+}
+
+/// Hardware-accelerated GPU scheduling, applied at the next restart
+pub const GPU_SCHEDULING: DwordTweak = DwordTweak {
+    id: "gpu_scheduling",
+    label: "Planification GPU accélérée (redémarrage requis)",
+    root: RegistryRoot::LocalMachine,
+    path: "SYSTEM\\CurrentControlSet\\Control\\GraphicsDrivers",
+    name: "HwSchMode",
+    applied_value: 2,
+    reverted_value: 1,
+};
+
+impl Tweak for DwordTweak {
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    fn label(&self) -> String {
+        self.label.to_string()
+    }
+
+    fn requires_elevation(&self) -> bool {
+        self.root == RegistryRoot::LocalMachine
+    }
+
+    #[cfg(target_os = "windows")]
+    fn current_state(&self) -> Result<TweakState> {
+        let value = crate::utils::registry::read_dword(os_tweaks::hkey(self.root), self.path, self.name)?;
+        Ok(if value == Some(self.applied_value) { TweakState::Applied } else { TweakState::NotApplied })
+    }
+
+    #[cfg(target_os = "windows")]
+    fn apply(&self) -> Result<Applied> {
+        let _guard = os_tweaks::lock_journal();
+        let mut journal = os_tweaks::TweakJournal::load();
+        let result = os_tweaks::set_journaled_dword(
+            &mut journal,
+            os_tweaks::TweakSource::Windows,
+            self.label,
+            self.root,
+            self.path,
+            self.name,
+            self.applied_value,
+        );
+        // Journal enregistré même après un échec : rien n'a été écrit sans y figurer
+        if let Err(e) = journal.save() {
+            tracing::error!("❌ Journal des optimisations non enregistré: {}", e);
+        }
+        Ok(Applied { detail: result? })
+    }
+
+    #[cfg(target_os = "windows")]
+    fn revert(&self, _applied: &Applied) -> Result<()> {
+        let _guard = os_tweaks::lock_journal();
+        if !restore_journaled(&[(self.root, self.path, self.name)])? {
+            crate::utils::registry::write_dword(os_tweaks::hkey(self.root), self.path, self.name, self.reverted_value)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn current_state(&self) -> Result<TweakState> {
+        Err(anyhow::anyhow!("Disponible uniquement sous Windows"))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn apply(&self) -> Result<Applied> {
+        Err(anyhow::anyhow!("Disponible uniquement sous Windows"))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn revert(&self, _applied: &Applied) -> Result<()> {
+        Err(anyhow::anyhow!("Disponible uniquement sous Windows"))
+    }
+}
+
+/// Built-in High Performance plan active. The plan it replaced is journaled; the plan of the
+/// gaming mode is chosen separately below the registry.
+pub struct HighPerformancePlan;
+
+impl Tweak for HighPerformancePlan {
+    fn id(&self) -> &str {
+        POWER_PLAN_TWEAK_ID
+    }
+
+    fn label(&self) -> String {
+        "Plan d'alimentation Performances élevées".to_string()
+    }
+
+    fn current_state(&self) -> Result<TweakState> {
+        let active = crate::power::get_active_plan()?;
+        Ok(if active.eq_ignore_ascii_case(crate::power::HIGH_PERFORMANCE) {
+            TweakState::Applied
+        } else {
+            TweakState::NotApplied
+        })
+    }
+
+    fn apply(&self) -> Result<Applied> {
+        let _guard = os_tweaks::lock_journal();
+        let mut journal = os_tweaks::TweakJournal::load();
+        let result = os_tweaks::apply_power_plan(&mut journal);
+        if let Err(e) = journal.save() {
+            tracing::error!("❌ Journal des optimisations non enregistré: {}", e);
+        }
+        Ok(Applied { detail: result? })
+    }
+
+    fn revert(&self, _applied: &Applied) -> Result<()> {
+        let _guard = os_tweaks::lock_journal();
+        let is_plan = |entry: &os_tweaks::JournalEntry| matches!(entry.previous, PreviousValue::PowerScheme(_));
+        if !os_tweaks::TweakJournal::load().entries.iter().any(is_plan) {
+            return Err(anyhow::anyhow!("plan précédent inconnu, choisissez-le dans la liste des plans"));
+        }
+        os_tweaks::restore_matching(is_plan).into_result()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// In-memory tweak: `applied` is its whole "system state"
+    struct FakeTweak {
+        id: String,
+        applied: Mutex<bool>,
+        fail_apply: bool,
+        unreadable: bool,
+    }
+
+    impl FakeTweak {
+        fn new(id: &str) -> Arc<Self> {
+            Arc::new(Self { id: id.to_string(), applied: Mutex::new(false), fail_apply: false, unreadable: false })
+        }
+    }
+
+    impl Tweak for FakeTweak {
+        fn id(&self) -> &str {
+            &self.id
+        }
+
+        fn label(&self) -> String {
+            format!("Fake {}", self.id)
+        }
+
+        fn current_state(&self) -> Result<TweakState> {
+            if self.unreadable {
+                anyhow::bail!("clé introuvable");
+            }
+            Ok(if *self.applied.lock().unwrap() { TweakState::Applied } else { TweakState::NotApplied })
+        }
+
+        fn apply(&self) -> Result<Applied> {
+            if self.fail_apply {
+                anyhow::bail!("accès refusé");
+            }
+            *self.applied.lock().unwrap() = true;
+            Ok(Applied { detail: format!("{} : 0 → 1", self.id) })
+        }
+
+        fn revert(&self, _applied: &Applied) -> Result<()> {
+            *self.applied.lock().unwrap() = false;
+            Ok(())
+        }
+    }
+
+    fn ids(tweaks: &[Arc<dyn Tweak>]) -> Vec<&str> {
+        tweaks.iter().map(|tweak| tweak.id()).collect()
+    }
+
+    #[test]
+    fn states_are_cached_until_invalidated() {
+        let mut registry = TweakRegistry::default();
+        registry.register(FakeTweak::new("game_mode"));
+        registry.register(FakeTweak::new("game_dvr"));
+        assert_eq!(ids(&registry.stale()), vec!["game_mode", "game_dvr"]);
+
+        registry.store_states(read_states(&registry.stale()));
+        assert!(registry.stale().is_empty());
+        assert_eq!(registry.state("game_mode").unwrap().state, TweakState::NotApplied);
+
+        registry.forget("game_dvr");
+        assert_eq!(ids(&registry.stale()), vec!["game_dvr"]);
+        registry.invalidate();
+        assert_eq!(registry.stale().len(), 2);
+    }
+
+    #[test]
+    fn registering_an_id_again_replaces_the_tweak_and_its_state() {
+        let mut registry = TweakRegistry::default();
+        registry.register(FakeTweak::new("game_mode"));
+        registry.store_states(read_states(registry.tweaks()));
+
+        let replacement = FakeTweak::new("game_mode");
+        *replacement.applied.lock().unwrap() = true;
+        registry.register(replacement);
+        assert_eq!(registry.tweaks().len(), 1);
+        assert!(registry.state("game_mode").is_none());
+        registry.store_states(read_states(&registry.stale()));
+        assert_eq!(registry.state("game_mode").unwrap().state, TweakState::Applied);
+    }
+
+    #[test]
+    fn replacing_a_prefix_drops_the_old_tweaks_and_their_late_states() {
+        let mut registry = TweakRegistry::default();
+        registry.register(FakeTweak::new("game_mode"));
+        registry.register(FakeTweak::new("audio:speakers"));
+        registry.register(FakeTweak::new("audio:headset"));
+        // Lecture lancée avant que la liste des périphériques change
+        let late = read_states(registry.tweaks());
+
+        registry.replace_prefixed(AUDIO_TWEAK_PREFIX, vec![FakeTweak::new("audio:usb") as Arc<dyn Tweak>]);
+        assert_eq!(ids(registry.tweaks()), vec!["game_mode", "audio:usb"]);
+        registry.store_states(late);
+        assert!(registry.state("audio:headset").is_none());
+        assert!(registry.state("game_mode").is_some());
+        assert_eq!(ids(&registry.stale()), vec!["audio:usb"]);
+    }
+
+    #[test]
+    fn apply_then_revert_updates_the_state_and_the_applied_detail() {
+        let mut registry = TweakRegistry::default();
+        registry.register(FakeTweak::new("game_mode"));
+        let tweak = registry.get("game_mode").unwrap();
+
+        let applied = run(tweak.clone(), TweakAction::Apply, Applied::default());
+        assert_eq!(applied.outcome, Ok(Applied { detail: "game_mode : 0 → 1".to_string() }));
+        assert_eq!(applied.state.state, TweakState::Applied);
+        registry.finish(&applied);
+        assert_eq!(registry.applied("game_mode").detail, "game_mode : 0 → 1");
+        assert_eq!(registry.state("game_mode").unwrap().state, TweakState::Applied);
+
+        let reverted = run(tweak, TweakAction::Revert, registry.applied("game_mode"));
+        registry.finish(&reverted);
+        assert_eq!(registry.applied("game_mode"), Applied::default());
+        assert_eq!(registry.state("game_mode").unwrap().state, TweakState::NotApplied);
+    }
+
+    #[test]
+    fn failures_are_reported_without_losing_the_last_apply() {
+        let mut registry = TweakRegistry::default();
+        registry.register(FakeTweak::new("game_mode"));
+        registry.finish(&run(registry.get("game_mode").unwrap(), TweakAction::Apply, Applied::default()));
+
+        let broken = Arc::new(FakeTweak { id: "game_mode".to_string(), applied: Mutex::new(false), fail_apply: true, unreadable: true });
+        registry.register(broken.clone());
+        let failed = run(broken, TweakAction::Apply, Applied::default());
+        assert_eq!(failed.outcome, Err("accès refusé".to_string()));
+        assert_eq!(failed.state.state, TweakState::Unknown);
+        assert_eq!(failed.state.error.as_deref(), Some("clé introuvable"));
+
+        registry.finish(&failed);
+        assert_eq!(registry.applied("game_mode").detail, "game_mode : 0 → 1");
+        assert_eq!(registry.state("game_mode").unwrap().state, TweakState::Unknown);
+    }
+}
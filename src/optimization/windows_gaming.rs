@@ -0,0 +1,231 @@
+//! # Windows Game Mode and GameDVR
+//!
+//! The two per-user switches of the Xbox Game Bar settings, read from the registry on every
+//! call so that a change made in the Windows settings shows at once:
+//! - Game Mode: `AllowAutoGameMode` / `AutoGameModeEnabled` under `HKCU\Software\Microsoft\GameBar`
+//! - GameDVR capture: `GameDVR_Enabled` under `HKCU\System\GameConfigStore` and
+//!   `AppCaptureEnabled` under `HKCU\...\CurrentVersion\GameDVR`
+//!
+//! Both are HKCU values and need no elevation. The machine policy `AllowGameDVR = 0`
+//! (`HKLM\SOFTWARE\Policies\Microsoft\Windows\GameDVR`) disables capture whatever the user
+//! values say: it is reported and the switch is refused. Game Mode has no such policy.
+//! Every change journals the value it replaces (`TweakSource::WindowsGaming`).
+//!
+//! As `Tweak`s, Game Mode is applied when on and GameDVR when capture is off.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::optimization::tweaks::{Applied, Tweak, TweakState};
+
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const GAME_BAR_KEY: &str = "Software\\Microsoft\\GameBar";
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const GAME_MODE_VALUES: &[&str] = &["AllowAutoGameMode", "AutoGameModeEnabled"];
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const GAME_CONFIG_STORE_KEY: &str = "System\\GameConfigStore";
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const APP_CAPTURE_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\GameDVR";
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const GAMEDVR_POLICY_KEY: &str = "SOFTWARE\\Policies\\Microsoft\\Windows\\GameDVR";
+
+/// Switch offered in the Optimization tab and in boost profiles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamingFeature {
+    GameMode,
+    GameDvr,
+}
+
+impl GamingFeature {
+    pub const ALL: [GamingFeature; 2] = [GamingFeature::GameMode, GamingFeature::GameDvr];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GamingFeature::GameMode => "Mode Jeu Windows",
+            GamingFeature::GameDvr => "Capture GameDVR / Game Bar",
+        }
+    }
+
+    pub fn is_enabled(self) -> Result<bool> {
+        match self {
+            GamingFeature::GameMode => get_game_mode_enabled(),
+            GamingFeature::GameDvr => get_gamedvr_enabled(),
+        }
+    }
+
+    pub fn set_enabled(self, enabled: bool) -> Result<String> {
+        match self {
+            GamingFeature::GameMode => set_game_mode_enabled(enabled),
+            GamingFeature::GameDvr => set_gamedvr_enabled(enabled),
+        }
+    }
+
+    /// State forced by a machine policy, `None` when the user values apply
+    pub fn policy_override(self) -> Option<bool> {
+        match self {
+            GamingFeature::GameMode => None,
+            GamingFeature::GameDvr => gamedvr_policy_override(),
+        }
+    }
+
+    /// State set by the Optimization tab: Game Mode on, capture off
+    pub fn optimized(self) -> bool {
+        self == GamingFeature::GameMode
+    }
+
+    /// HKCU values written by `set_enabled`
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    fn registry_values(self) -> Vec<(&'static str, &'static str)> {
+        match self {
+            GamingFeature::GameMode => GAME_MODE_VALUES.iter().map(|name| (GAME_BAR_KEY, *name)).collect(),
+            GamingFeature::GameDvr => vec![(GAME_CONFIG_STORE_KEY, "GameDVR_Enabled"), (APP_CAPTURE_KEY, "AppCaptureEnabled")],
+        }
+    }
+}
+
+impl Tweak for GamingFeature {
+    fn id(&self) -> &str {
+        match self {
+            GamingFeature::GameMode => "game_mode",
+            GamingFeature::GameDvr => "gamedvr_capture_off",
+        }
+    }
+
+    fn label(&self) -> String {
+        match self {
+            GamingFeature::GameMode => "Mode Jeu Windows activé".to_string(),
+            GamingFeature::GameDvr => "Capture GameDVR / Game Bar désactivée".to_string(),
+        }
+    }
+
+    fn current_state(&self) -> Result<TweakState> {
+        if self.policy_override().is_some() {
+            return Ok(TweakState::BlockedByPolicy);
+        }
+        Ok(if self.is_enabled()? == self.optimized() { TweakState::Applied } else { TweakState::NotApplied })
+    }
+
+    fn apply(&self) -> Result<Applied> {
+        Ok(Applied { detail: self.set_enabled(self.optimized())? })
+    }
+
+    /// The journaled values, or the opposite of the optimized state when none was journaled
+    fn revert(&self, _applied: &Applied) -> Result<()> {
+        #[cfg(target_os = "windows")]
+        {
+            use crate::disk::os_tweaks::{self, RegistryRoot};
+
+            let targets: Vec<(RegistryRoot, &str, &str)> =
+                self.registry_values().into_iter().map(|(path, name)| (RegistryRoot::CurrentUser, path, name)).collect();
+            // Verrou relâché avant `set_enabled`, qui le reprend
+            let restored = {
+                let _guard = os_tweaks::lock_journal();
+                crate::optimization::tweaks::restore_journaled(&targets)?
+            };
+            if restored {
+                return Ok(());
+            }
+        }
+        self.set_enabled(!self.optimized()).map(|_| ())
+    }
+}
+
+/// A missing value means the Windows default, enabled for both features
+#[cfg(target_os = "windows")]
+fn read_flag(path: &str, name: &str) -> Result<bool> {
+    use crate::utils::registry;
+    Ok(registry::read_dword(registry::HKEY_CURRENT_USER, path, name)?.map_or(true, |value| value != 0))
+}
+
+/// Journal the values replaced, then write `enabled` to each of them
+#[cfg(target_os = "windows")]
+fn write_flags(label: &str, values: &[(&str, &str)], enabled: bool) -> Result<String> {
+    use crate::disk::os_tweaks::{self, set_journaled_dword, RegistryRoot, TweakJournal, TweakSource};
+
+    let _guard = os_tweaks::lock_journal();
+    let mut journal = TweakJournal::load();
+    let mut details = Vec::new();
+    let mut result = Ok(());
+    for (path, name) in values {
+        match set_journaled_dword(&mut journal, TweakSource::WindowsGaming, label, RegistryRoot::CurrentUser, path, name, enabled as u32) {
+            Ok(detail) => details.push(detail),
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        }
+    }
+    // Journal enregistré même après un échec : les valeurs déjà écrites restent restaurables
+    if let Err(e) = journal.save() {
+        tracing::error!("❌ Journal des optimisations non enregistré: {}", e);
+    }
+    result?;
+    tracing::info!("🎮 {} : {}", label, if enabled { "activé" } else { "désactivé" });
+    Ok(details.join(", "))
+}
+
+#[cfg(target_os = "windows")]
+pub fn get_game_mode_enabled() -> Result<bool> {
+    for name in GAME_MODE_VALUES {
+        if !read_flag(GAME_BAR_KEY, name)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(target_os = "windows")]
+pub fn set_game_mode_enabled(enabled: bool) -> Result<String> {
+    write_flags(GamingFeature::GameMode.label(), &GamingFeature::GameMode.registry_values(), enabled)
+}
+
+/// Capture as the user set it; see `gamedvr_policy_override` for the machine policy
+#[cfg(target_os = "windows")]
+pub fn get_gamedvr_enabled() -> Result<bool> {
+    Ok(read_flag(GAME_CONFIG_STORE_KEY, "GameDVR_Enabled")? && read_flag(APP_CAPTURE_KEY, "AppCaptureEnabled")?)
+}
+
+#[cfg(target_os = "windows")]
+pub fn set_gamedvr_enabled(enabled: bool) -> Result<String> {
+    if let Some(forced) = gamedvr_policy_override() {
+        if forced != enabled {
+            return Err(anyhow::anyhow!("contrôlé par une stratégie"));
+        }
+    }
+    write_flags(GamingFeature::GameDvr.label(), &GamingFeature::GameDvr.registry_values(), enabled)
+}
+
+/// `Some(false)` when `AllowGameDVR = 0` disables capture for every user
+#[cfg(target_os = "windows")]
+pub fn gamedvr_policy_override() -> Option<bool> {
+    use crate::utils::registry;
+    match registry::read_dword(registry::HKEY_LOCAL_MACHINE, GAMEDVR_POLICY_KEY, "AllowGameDVR") {
+        Ok(Some(0)) => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn get_game_mode_enabled() -> Result<bool> {
+    Err(anyhow::anyhow!("Mode Jeu disponible uniquement sous Windows"))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn set_game_mode_enabled(_enabled: bool) -> Result<String> {
+    Err(anyhow::anyhow!("Mode Jeu disponible uniquement sous Windows"))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn get_gamedvr_enabled() -> Result<bool> {
+    Err(anyhow::anyhow!("GameDVR disponible uniquement sous Windows"))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn set_gamedvr_enabled(_enabled: bool) -> Result<String> {
+    Err(anyhow::anyhow!("GameDVR disponible uniquement sous Windows"))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn gamedvr_policy_override() -> Option<bool> {
+    None
+}
@@ -1,42 +1,247 @@
 // This module provides functions to get OS-specific information.
+//
+// `get_os_details` reads the real version once (RtlGetVersion is not subject to the
+// compatibility shims of GetVersionExW, which reports Windows 8 to unmanifested programs) and
+// `capabilities` derives from it what the features of the app can rely on.
 
-#[cfg(target_os = "windows")]
-use std::mem;
-#[cfg(target_os = "windows")]
-use windows_sys::Win32::System::SystemInformation::{GetVersionExW, OSVERSIONINFOEXW};
+use std::sync::OnceLock;
 
-#[cfg(target_os = "windows")]
-pub fn get_windows_version_string() -> String {
-    unsafe {
-        let mut osvi: OSVERSIONINFOEXW = mem::zeroed();
-        osvi.dwOSVersionInfoSize = mem::size_of::<OSVERSIONINFOEXW>() as u32;
+/// Build of the first Windows 11 release
+pub const WIN11_BUILD: u32 = 22000;
+/// Windows 10 2004, first build with hardware-accelerated GPU scheduling
+const GPU_SCHEDULING_BUILD: u32 = 19041;
+/// Windows 8, first build shipping the NetQos PowerShell module
+const NETQOS_BUILD: u32 = 9200;
 
-        if GetVersionExW(&mut osvi as *mut _ as *mut _) == 0 {
-            return "Windows (unknown version - GetVersionExW failed)".to_string();
+/// What the running OS is
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OsDetails {
+    /// "windows" or "linux", see `get_os_platform`
+    pub platform: String,
+    /// "Windows 11", "Ubuntu 24.04 LTS"
+    pub name: String,
+    /// "10.0.22631" on Windows, VERSION_ID on Linux
+    pub version: String,
+    /// 0 on Linux
+    pub build_number: u32,
+    /// EditionID ("Professional", "Core") on Windows, ID ("ubuntu") on Linux
+    pub edition: String,
+    /// Feature update ("23H2"), when Windows reports it
+    pub display_version: Option<String>,
+    pub is_server: bool,
+}
+
+impl OsDetails {
+    /// Full description shown to the user
+    pub fn description(&self) -> String {
+        let mut text = self.name.clone();
+        if self.platform == "windows" {
+            if !self.edition.is_empty() {
+                text.push_str(&format!(" {}", self.edition));
+            }
+            if let Some(display_version) = &self.display_version {
+                text.push_str(&format!(" {}", display_version));
+            }
+            text.push_str(&format!(" (build {})", self.version));
+        } else if !self.version.is_empty() && !self.name.contains(&self.version) {
+            text.push_str(&format!(" ({})", self.version));
         }
+        text
+    }
 
-        let product_name = match (osvi.dwMajorVersion, osvi.dwMinorVersion) {
-            (10, 0) => {
-                if osvi.dwBuildNumber >= 22000 {
-                    "Windows 11"
-                } else {
-                    "Windows 10"
-                }
+    fn windows_major(&self) -> u32 {
+        self.version.split('.').next().and_then(|major| major.parse().ok()).unwrap_or(0)
+    }
+}
+
+/// Product name of a Windows version, Windows 11 still reporting itself as 10.0
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn windows_product_name(major: u32, minor: u32, build: u32) -> &'static str {
+    match (major, minor) {
+        (10, 0) if build >= WIN11_BUILD => "Windows 11",
+        (10, 0) => "Windows 10",
+        (6, 3) => "Windows 8.1",
+        (6, 2) => "Windows 8",
+        (6, 1) => "Windows 7",
+        (6, 0) => "Windows Vista",
+        _ => "Windows (older or unknown)",
+    }
+}
+
+/// Details from the version numbers and the CurrentVersion registry values.
+/// `installation_type` is "Client" or "Server" ("Server Core" included).
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub fn parse_windows_details(
+    (major, minor, build): (u32, u32, u32),
+    edition: Option<&str>,
+    display_version: Option<&str>,
+    installation_type: Option<&str>,
+) -> OsDetails {
+    let edition = edition.unwrap_or_default().trim().to_string();
+    let is_server = installation_type.is_some_and(|kind| kind.trim().starts_with("Server"))
+        || edition.contains("Server");
+    let name = if is_server {
+        format!("Windows Server ({})", windows_product_name(major, minor, build))
+    } else {
+        windows_product_name(major, minor, build).to_string()
+    };
+    OsDetails {
+        platform: "windows".to_string(),
+        name,
+        version: format!("{}.{}.{}", major, minor, build),
+        build_number: build,
+        edition,
+        display_version: display_version.map(str::trim).filter(|version| !version.is_empty()).map(str::to_string),
+        is_server,
+    }
+}
+
+/// Details from the content of /etc/os-release
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn parse_os_release(content: &str) -> OsDetails {
+    let value = |key: &str| {
+        content.lines().find_map(|line| {
+            let (name, value) = line.split_once('=')?;
+            (name.trim() == key).then(|| value.trim().trim_matches('"').trim_matches('\'').to_string())
+        })
+    };
+    let name = value("PRETTY_NAME").or_else(|| value("NAME")).unwrap_or_else(|| "Linux".to_string());
+    OsDetails {
+        platform: "linux".to_string(),
+        name,
+        version: value("VERSION_ID").unwrap_or_default(),
+        build_number: 0,
+        edition: value("ID").unwrap_or_default(),
+        display_version: None,
+        is_server: value("VARIANT_ID").is_some_and(|variant| variant == "server"),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn read_os_details() -> OsDetails {
+    use crate::utils::registry::{read_string, HKEY_LOCAL_MACHINE};
+    const CURRENT_VERSION: &str = "SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion";
+
+    let (major, minor, mut build) = rtl_version_numbers();
+    // CurrentBuild reste juste même si RtlGetVersion échoue
+    let registry = |name: &str| read_string(HKEY_LOCAL_MACHINE, CURRENT_VERSION, name).ok().flatten();
+    if let Some(current_build) = registry("CurrentBuild").and_then(|value| value.trim().parse().ok()) {
+        build = current_build;
+    }
+    let (major, minor) = if major == 0 && build >= 10240 { (10, 0) } else { (major, minor) };
+    parse_windows_details(
+        (major, minor, build),
+        registry("EditionID").as_deref(),
+        registry("DisplayVersion").as_deref(),
+        registry("InstallationType").as_deref(),
+    )
+}
+
+#[cfg(target_os = "linux")]
+fn read_os_details() -> OsDetails {
+    match std::fs::read_to_string("/etc/os-release").or_else(|_| std::fs::read_to_string("/usr/lib/os-release")) {
+        Ok(content) => parse_os_release(&content),
+        Err(e) => {
+            tracing::warn!("⚠️ /etc/os-release illisible: {}", e);
+            OsDetails {
+                platform: get_os_platform(),
+                name: get_windows_version_string(),
+                ..Default::default()
             }
-            (6, 3) => "Windows 8.1",
-            (6, 2) => "Windows 8",
-            (6, 1) => "Windows 7",
-            (6, 0) => "Windows Vista",
-            _ => "Windows (older or unknown)",
-        };
+        }
+    }
+}
 
-        format!(
-            "{} (Build {}.{}.{})",
-            product_name, osvi.dwMajorVersion, osvi.dwMinorVersion, osvi.dwBuildNumber
-        )
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn read_os_details() -> OsDetails {
+    OsDetails {
+        platform: get_os_platform(),
+        name: get_windows_version_string(),
+        ..Default::default()
     }
 }
 
+/// Details of the running OS, read once
+pub fn get_os_details() -> &'static OsDetails {
+    static DETAILS: OnceLock<OsDetails> = OnceLock::new();
+    DETAILS.get_or_init(|| {
+        let details = read_os_details();
+        tracing::info!("🖥️ Système : {}", details.description());
+        details
+    })
+}
+
+/// Features of the app the OS supports
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OsCapabilities {
+    /// Bandwidth limits: NetQos policies on Windows, tc on Linux
+    pub qos_supported: bool,
+    pub win10_tweaks: bool,
+    pub win11_tweaks: bool,
+    /// Hardware-accelerated GPU scheduling can be switched on
+    pub gpu_scheduling: bool,
+    pub defender_present: bool,
+    /// Windows services tab
+    pub services_supported: bool,
+    /// Disk cleaning and optimizations tab
+    pub optimization_supported: bool,
+}
+
+impl OsCapabilities {
+    pub fn from_details(details: &OsDetails, defender_present: bool) -> Self {
+        let windows = details.platform == "windows";
+        let windows_10 = windows && details.windows_major() >= 10;
+        Self {
+            qos_supported: (windows && details.build_number >= NETQOS_BUILD) || details.platform == "linux",
+            win10_tweaks: windows_10 && details.build_number < WIN11_BUILD,
+            win11_tweaks: windows_10 && details.build_number >= WIN11_BUILD,
+            gpu_scheduling: windows_10 && details.build_number >= GPU_SCHEDULING_BUILD,
+            defender_present: windows && defender_present,
+            services_supported: windows,
+            optimization_supported: windows,
+        }
+    }
+}
+
+/// Windows Defender platform folder, removed when another antivirus uninstalls it
+#[cfg(target_os = "windows")]
+fn is_defender_present() -> bool {
+    let program_data = std::env::var("ProgramData").unwrap_or_else(|_| "C:\\ProgramData".to_string());
+    std::path::Path::new(&program_data).join("Microsoft\\Windows Defender\\Platform").is_dir()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn is_defender_present() -> bool {
+    false
+}
+
+/// Capabilities of the running OS, computed once
+pub fn capabilities() -> OsCapabilities {
+    static CAPABILITIES: OnceLock<OsCapabilities> = OnceLock::new();
+    *CAPABILITIES.get_or_init(|| OsCapabilities::from_details(get_os_details(), is_defender_present()))
+}
+
+#[cfg(target_os = "windows")]
+fn rtl_version_numbers() -> (u32, u32, u32) {
+    use windows_sys::Wdk::System::SystemServices::RtlGetVersion;
+    use windows_sys::Win32::System::SystemInformation::OSVERSIONINFOW;
+
+    unsafe {
+        let mut info: OSVERSIONINFOW = std::mem::zeroed();
+        info.dwOSVersionInfoSize = std::mem::size_of::<OSVERSIONINFOW>() as u32;
+        if RtlGetVersion(&mut info) == 0 {
+            (info.dwMajorVersion, info.dwMinorVersion, info.dwBuildNumber)
+        } else {
+            (0, 0, 0)
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn get_windows_version_string() -> String {
+    get_os_details().description()
+}
+
 #[cfg(not(target_os = "windows"))]
 pub fn get_windows_version_string() -> String { // Cette fonction donne maintenant une description plus détaillée pour Linux
     match sys_info::os_type() {
@@ -62,15 +267,9 @@ pub fn get_os_platform() -> String {
 /// Returns the OS version as (major, minor, build)
 #[cfg(target_os = "windows")]
 pub fn get_windows_version_numbers() -> (u32, u32, u32) {
-    unsafe {
-        let mut osvi: OSVERSIONINFOEXW = mem::zeroed();
-        osvi.dwOSVersionInfoSize = mem::size_of::<OSVERSIONINFOEXW>() as u32;
-        if GetVersionExW(&mut osvi as *mut _ as *mut _) != 0 {
-            (osvi.dwMajorVersion, osvi.dwMinorVersion, osvi.dwBuildNumber)
-        } else {
-            (0, 0, 0)
-        }
-    }
+    let details = get_os_details();
+    let mut numbers = details.version.split('.').map(|part| part.parse().unwrap_or(0));
+    (numbers.next().unwrap_or(0), numbers.next().unwrap_or(0), details.build_number)
 }
 
 #[cfg(not(target_os = "windows"))]
@@ -79,4 +278,68 @@ pub fn get_windows_version_numbers() -> (u32, u32, u32) {
     // car le format n'est pas standardisé comme pour Windows.
     // Retourne (0,0,0) pour l'instant pour Linux.
     (0, 0, 0)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_11_pro_is_reported_with_its_build_and_edition() {
+        let details = parse_windows_details((10, 0, 22631), Some("Professional"), Some("23H2"), Some("Client"));
+        assert_eq!(details.name, "Windows 11");
+        assert_eq!(details.version, "10.0.22631");
+        assert_eq!(details.build_number, 22631);
+        assert!(!details.is_server);
+        assert_eq!(details.description(), "Windows 11 Professional 23H2 (build 10.0.22631)");
+    }
+
+    #[test]
+    fn windows_10_and_server_are_told_apart() {
+        let windows_10 = parse_windows_details((10, 0, 19045), Some(" Core "), Some(""), None);
+        assert_eq!(windows_10.name, "Windows 10");
+        assert_eq!(windows_10.edition, "Core");
+        assert_eq!(windows_10.display_version, None);
+
+        let server = parse_windows_details((10, 0, 20348), Some("ServerStandard"), None, Some("Server Core"));
+        assert!(server.is_server);
+        assert_eq!(server.name, "Windows Server (Windows 10)");
+        assert_eq!(parse_windows_details((6, 1, 7601), None, None, None).name, "Windows 7");
+    }
+
+    #[test]
+    fn os_release_is_parsed() {
+        let content = "NAME=\"Ubuntu\"\nVERSION_ID=\"24.04\"\nID=ubuntu\nPRETTY_NAME=\"Ubuntu 24.04 LTS\"\n";
+        let details = parse_os_release(content);
+        assert_eq!(details.platform, "linux");
+        assert_eq!(details.name, "Ubuntu 24.04 LTS");
+        assert_eq!(details.version, "24.04");
+        assert_eq!(details.edition, "ubuntu");
+        assert!(!details.is_server);
+        assert_eq!(details.description(), "Ubuntu 24.04 LTS");
+
+        let minimal = parse_os_release("NAME='Fedora Linux'\nVARIANT_ID=server\n");
+        assert_eq!(minimal.name, "Fedora Linux");
+        assert!(minimal.is_server);
+        assert_eq!(parse_os_release("").name, "Linux");
+    }
+
+    #[test]
+    fn capabilities_follow_the_build() {
+        let windows_11 = parse_windows_details((10, 0, WIN11_BUILD), None, None, None);
+        let capabilities = OsCapabilities::from_details(&windows_11, true);
+        assert!(capabilities.win11_tweaks && !capabilities.win10_tweaks);
+        assert!(capabilities.qos_supported && capabilities.gpu_scheduling && capabilities.defender_present);
+
+        let windows_10_1909 = parse_windows_details((10, 0, 18363), None, None, None);
+        let capabilities = OsCapabilities::from_details(&windows_10_1909, false);
+        assert!(capabilities.win10_tweaks && !capabilities.gpu_scheduling && !capabilities.defender_present);
+
+        let windows_7 = parse_windows_details((6, 1, 7601), None, None, None);
+        let capabilities = OsCapabilities::from_details(&windows_7, true);
+        assert!(!capabilities.qos_supported && !capabilities.win10_tweaks && capabilities.services_supported);
+
+        let linux = OsCapabilities::from_details(&parse_os_release("ID=debian"), true);
+        assert!(linux.qos_supported && !linux.services_supported && !linux.defender_present);
+    }
+}
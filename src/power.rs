@@ -0,0 +1,262 @@
+//! # Power plans
+//!
+//! List, read and switch Windows power plans with the powrprof API (`PowerEnumerate`,
+//! `PowerGetActiveScheme`, `PowerSetActiveScheme`), `powercfg` being used as a fallback and
+//! to create the hidden Ultimate Performance plan. Used by the gaming mode, which switches to
+//! the configured gaming plan and restores the previous one on exit.
+
+use anyhow::{anyhow, Result};
+
+/// Built-in High Performance plan (`SCHEME_MIN`)
+pub const HIGH_PERFORMANCE: &str = "8c5e7fda-e8bf-4a96-9a85-a6e23a8c635c";
+/// Ultimate Performance template, hidden until duplicated with `powercfg -duplicatescheme`
+pub const ULTIMATE_PERFORMANCE: &str = "e9a42b02-d5df-448d-aa00-03f14749eb61";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PowerPlan {
+    /// Lowercase GUID without braces
+    pub guid: String,
+    pub name: String,
+    pub active: bool,
+}
+
+/// Installed plans, the active one flagged
+pub fn list_power_plans() -> Result<Vec<PowerPlan>> {
+    #[cfg(target_os = "windows")]
+    {
+        match api::list_plans() {
+            Ok(plans) => Ok(plans),
+            Err(e) => {
+                tracing::warn!("⚠️ PowerEnumerate échoué, fallback powercfg: {}", e);
+                powercfg::list_plans()
+            }
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err(anyhow!("Plans d'alimentation disponibles uniquement sous Windows"))
+    }
+}
+
+/// GUID of the active plan
+pub fn get_active_plan() -> Result<String> {
+    #[cfg(target_os = "windows")]
+    {
+        api::active_plan().or_else(|e| {
+            tracing::warn!("⚠️ PowerGetActiveScheme échoué, fallback powercfg: {}", e);
+            powercfg::active_plan()
+        })
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err(anyhow!("Plans d'alimentation disponibles uniquement sous Windows"))
+    }
+}
+
+pub fn set_active_plan(guid: &str) -> Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+        if let Err(e) = api::set_active(guid) {
+            tracing::warn!("⚠️ PowerSetActiveScheme échoué, fallback powercfg: {}", e);
+            powercfg::run(&["/setactive", guid])?;
+        }
+        tracing::info!("🔋 Plan d'alimentation actif: {}", guid);
+        Ok(())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = guid;
+        Err(anyhow!("Plans d'alimentation disponibles uniquement sous Windows"))
+    }
+}
+
+/// Ultimate Performance plan installed on this machine, if any. Its GUID is the template's
+/// on some editions and a random one once duplicated, so it is found by name too.
+pub fn find_ultimate_performance(plans: &[PowerPlan]) -> Option<&PowerPlan> {
+    plans.iter().find(|plan| {
+        plan.guid.eq_ignore_ascii_case(ULTIMATE_PERFORMANCE)
+            || plan.name.to_lowercase().contains("ultimate")
+            || plan.name.to_lowercase().contains("optimales")
+    })
+}
+
+/// Duplicate the Ultimate Performance template, returning the GUID of the new plan
+pub fn create_ultimate_performance() -> Result<String> {
+    #[cfg(target_os = "windows")]
+    {
+        let output = powercfg::run(&["-duplicatescheme", ULTIMATE_PERFORMANCE])?;
+        let guid = powercfg::find_guid(&output)
+            .ok_or_else(|| anyhow!("GUID du nouveau plan introuvable: {}", output.trim()))?;
+        tracing::info!("🔋 Plan Performances optimales créé: {}", guid);
+        Ok(guid)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err(anyhow!("Plans d'alimentation disponibles uniquement sous Windows"))
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod api {
+    use anyhow::{anyhow, Result};
+    use windows_sys::core::GUID;
+    use windows_sys::Win32::Foundation::{LocalFree, ERROR_NO_MORE_ITEMS, ERROR_SUCCESS};
+    use windows_sys::Win32::System::Power::{
+        PowerEnumerate, PowerGetActiveScheme, PowerReadFriendlyName, PowerSetActiveScheme, ACCESS_SCHEME,
+    };
+
+    use super::PowerPlan;
+
+    fn guid_to_string(guid: &GUID) -> String {
+        format!(
+            "{:08x}-{:04x}-{:04x}-{:02x}{:02x}-{}",
+            guid.data1,
+            guid.data2,
+            guid.data3,
+            guid.data4[0],
+            guid.data4[1],
+            guid.data4[2..].iter().map(|b| format!("{:02x}", b)).collect::<String>()
+        )
+    }
+
+    fn parse_guid(value: &str) -> Result<GUID> {
+        let hex: String = value.trim_matches(|c| c == '{' || c == '}').replace('-', "");
+        if hex.len() != 32 {
+            return Err(anyhow!("GUID invalide: {}", value));
+        }
+        let uuid = u128::from_str_radix(&hex, 16).map_err(|_| anyhow!("GUID invalide: {}", value))?;
+        Ok(GUID::from_u128(uuid))
+    }
+
+    pub fn active_plan() -> Result<String> {
+        let mut active: *mut GUID = std::ptr::null_mut();
+        let result = unsafe { PowerGetActiveScheme(std::ptr::null_mut(), &mut active) };
+        if result != ERROR_SUCCESS || active.is_null() {
+            return Err(anyhow!("PowerGetActiveScheme: erreur {}", result));
+        }
+        let guid = unsafe { guid_to_string(&*active) };
+        unsafe { LocalFree(active as _) };
+        Ok(guid)
+    }
+
+    pub fn set_active(guid: &str) -> Result<()> {
+        let guid = parse_guid(guid)?;
+        let result = unsafe { PowerSetActiveScheme(std::ptr::null_mut(), &guid) };
+        if result == ERROR_SUCCESS {
+            Ok(())
+        } else {
+            Err(anyhow!("PowerSetActiveScheme: erreur {}", result))
+        }
+    }
+
+    fn friendly_name(guid: &GUID) -> String {
+        let mut buffer = [0u16; 256];
+        let mut size = std::mem::size_of_val(&buffer) as u32;
+        let result = unsafe {
+            PowerReadFriendlyName(
+                std::ptr::null_mut(),
+                guid,
+                std::ptr::null(),
+                std::ptr::null(),
+                buffer.as_mut_ptr() as *mut u8,
+                &mut size,
+            )
+        };
+        if result != ERROR_SUCCESS {
+            return guid_to_string(guid);
+        }
+        let len = (size as usize / 2).min(buffer.len());
+        String::from_utf16_lossy(&buffer[..len]).trim_end_matches('\0').to_string()
+    }
+
+    pub fn list_plans() -> Result<Vec<PowerPlan>> {
+        let active = active_plan().ok();
+        let mut plans = Vec::new();
+        for index in 0.. {
+            let mut guid = GUID::from_u128(0);
+            let mut size = std::mem::size_of::<GUID>() as u32;
+            let result = unsafe {
+                PowerEnumerate(
+                    std::ptr::null_mut(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                    ACCESS_SCHEME,
+                    index,
+                    &mut guid as *mut GUID as *mut u8,
+                    &mut size,
+                )
+            };
+            if result == ERROR_NO_MORE_ITEMS {
+                break;
+            }
+            if result != ERROR_SUCCESS {
+                return Err(anyhow!("PowerEnumerate: erreur {}", result));
+            }
+
+            let guid_string = guid_to_string(&guid);
+            plans.push(PowerPlan {
+                active: active.as_deref() == Some(guid_string.as_str()),
+                name: friendly_name(&guid),
+                guid: guid_string,
+            });
+        }
+        Ok(plans)
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod powercfg {
+    use anyhow::{anyhow, Result};
+    use std::os::windows::process::CommandExt;
+
+    use super::PowerPlan;
+
+    pub fn run(args: &[&str]) -> Result<String> {
+        let output = std::process::Command::new("powercfg")
+            .args(args)
+            .creation_flags(0x08000000) // CREATE_NO_WINDOW
+            .output()?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        } else {
+            Err(anyhow!(
+                "powercfg {} a échoué: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stdout).trim()
+            ))
+        }
+    }
+
+    /// First GUID-shaped token of a line; the surrounding text is localized
+    pub fn find_guid(text: &str) -> Option<String> {
+        text.split_whitespace()
+            .find(|token| token.len() == 36 && token.matches('-').count() == 4)
+            .map(str::to_lowercase)
+    }
+
+    pub fn active_plan() -> Result<String> {
+        let output = run(&["/getactivescheme"])?;
+        find_guid(&output).ok_or_else(|| anyhow!("Plan actif introuvable: {}", output.trim()))
+    }
+
+    /// `GUID: <guid>  (<name>) *` lines of `powercfg /list`, `*` marking the active plan
+    pub fn list_plans() -> Result<Vec<PowerPlan>> {
+        let output = run(&["/list"])?;
+        Ok(output
+            .lines()
+            .filter_map(|line| {
+                let guid = find_guid(line)?;
+                let name = line
+                    .split_once('(')
+                    .and_then(|(_, rest)| rest.rsplit_once(')'))
+                    .map(|(name, _)| name.to_string())
+                    .unwrap_or_else(|| guid.clone());
+                Some(PowerPlan {
+                    guid,
+                    name,
+                    active: line.trim_end().ends_with('*'),
+                })
+            })
+            .collect())
+    }
+}
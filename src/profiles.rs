@@ -0,0 +1,284 @@
+//! # Boost profiles
+//!
+//! A named set of actions applied in one click before a game ("Boost") and undone afterwards
+//! ("Rétablir"). `apply` records in an `AppliedProfile` everything needed to revert (previous
+//! service state and start type, previous power plan, created QoS policies, previous Game Mode
+//! and GameDVR state); the applied profile
+//! is saved in the settings so that it can still be reverted after a restart of the app.
+//! Every step is attempted even when a previous one failed, and its error is collected.
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::memory::MemoryCleanOptions;
+use crate::network::NetworkLimiter;
+use crate::optimization::windows_gaming::GamingFeature;
+use crate::services::gaming_services;
+use crate::services::ServiceAction;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BoostProfile {
+    pub name: String,
+    pub clean_ram: bool,
+    pub clean_ram_options: MemoryCleanOptions,
+    /// (service name, action); only `Stop`, `Start`, `Disable` and `Enable` are meaningful
+    pub service_actions: Vec<(String, ServiceAction)>,
+    /// (executable name, limit in KB/s)
+    pub network_limits: Vec<(String, u32)>,
+    /// GUID of the plan to activate
+    pub power_plan: Option<String>,
+    pub defender_pause_minutes: Option<u32>,
+    /// Game Mode / GameDVR switched on (`true`) or off; absent features are left unchanged
+    pub windows_gaming: Vec<(GamingFeature, bool)>,
+}
+
+impl Default for BoostProfile {
+    fn default() -> Self {
+        Self {
+            name: "Nouveau profil".to_string(),
+            clean_ram: true,
+            clean_ram_options: MemoryCleanOptions::default(),
+            service_actions: Vec::new(),
+            network_limits: Vec::new(),
+            power_plan: None,
+            defender_pause_minutes: None,
+            windows_gaming: Vec::new(),
+        }
+    }
+}
+
+/// Conservative built-in profile
+pub const LIGHT_PROFILE: &str = "Léger";
+pub const AGGRESSIVE_PROFILE: &str = "Agressif";
+
+/// Profiles shipped with the app; they can be duplicated, not edited
+pub fn built_in() -> Vec<BoostProfile> {
+    vec![
+        BoostProfile {
+            name: LIGHT_PROFILE.to_string(),
+            service_actions: vec![
+                ("WSearch".to_string(), ServiceAction::Stop),
+                ("SysMain".to_string(), ServiceAction::Stop),
+            ],
+            power_plan: Some(crate::power::HIGH_PERFORMANCE.to_string()),
+            ..Default::default()
+        },
+        BoostProfile {
+            name: AGGRESSIVE_PROFILE.to_string(),
+            service_actions: ["WSearch", "SysMain", "wuauserv", "Spooler", "WerSvc"]
+                .iter()
+                .map(|name| (name.to_string(), ServiceAction::Stop))
+                .collect(),
+            network_limits: vec![("OneDrive.exe".to_string(), 256)],
+            power_plan: Some(crate::power::HIGH_PERFORMANCE.to_string()),
+            ..Default::default()
+        },
+    ]
+}
+
+/// Built-in or user profile named `name`
+pub fn find(name: &str, custom: &[BoostProfile]) -> Option<BoostProfile> {
+    built_in().into_iter().chain(custom.iter().cloned()).find(|profile| profile.name == name)
+}
+
+/// How to undo the change made to one service
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ServiceRevert {
+    Start,
+    Stop,
+    /// Start type before the profile (`sc config` value)
+    StartType(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedProfile {
+    pub profile_name: String,
+    pub applied_at: DateTime<Local>,
+    /// Bytes freed by the memory cleaning
+    pub ram_freed: Option<usize>,
+    /// In the order they were made, reverted in reverse order
+    pub service_changes: Vec<(String, ServiceRevert)>,
+    pub network_policies: Vec<String>,
+    pub previous_power_plan: Option<String>,
+    pub defender_reenable_at: Option<DateTime<Local>>,
+    /// State of each switched feature before the profile
+    #[serde(default)]
+    pub windows_gaming_changes: Vec<(GamingFeature, bool)>,
+    /// One message per failed step
+    pub errors: Vec<String>,
+}
+
+/// Outcome of `revert`
+#[derive(Debug, Clone, Default)]
+pub struct RevertReport {
+    pub restored: usize,
+    pub errors: Vec<String>,
+}
+
+/// Result of an apply or revert run in the background
+pub enum ProfileOutcome {
+    Applied(AppliedProfile),
+    Reverted(RevertReport),
+}
+
+/// Apply every action of `profile`, collecting the failures in `AppliedProfile::errors`.
+/// Network limits are skipped without a `limiter`.
+pub fn apply(profile: &BoostProfile, limiter: Option<&mut NetworkLimiter>) -> AppliedProfile {
+    tracing::info!("🚀 Application du profil {}", profile.name);
+    let mut applied = AppliedProfile {
+        profile_name: profile.name.clone(),
+        applied_at: Local::now(),
+        ram_freed: None,
+        service_changes: Vec::new(),
+        network_policies: Vec::new(),
+        previous_power_plan: None,
+        defender_reenable_at: None,
+        windows_gaming_changes: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    if profile.clean_ram {
+        match crate::memory::clean_memory_with_options(&profile.clean_ram_options) {
+            Ok(results) => applied.ram_freed = Some(results.total_freed()),
+            Err(e) => applied.errors.push(format!("Nettoyage mémoire : {}", e)),
+        }
+    }
+
+    for (service_name, action) in &profile.service_actions {
+        match apply_service_action(service_name, *action) {
+            Ok(Some(revert)) => applied.service_changes.push((service_name.clone(), revert)),
+            Ok(None) => {}
+            Err(e) => applied.errors.push(format!("Service {} : {}", service_name, e)),
+        }
+    }
+
+    match limiter {
+        Some(limiter) => {
+            for (exe_name, limit_kbps) in &profile.network_limits {
+                match limiter.limit_executable(exe_name, *limit_kbps) {
+                    Ok(policy_name) => applied.network_policies.push(policy_name),
+                    Err(e) => applied.errors.push(format!("Limite réseau {} : {}", exe_name, e)),
+                }
+            }
+        }
+        None if !profile.network_limits.is_empty() => {
+            applied.errors.push("Limites réseau ignorées : limiteur réseau indisponible".to_string());
+        }
+        None => {}
+    }
+
+    if let Some(guid) = &profile.power_plan {
+        match crate::power::get_active_plan() {
+            Ok(current) if &current == guid => {}
+            Ok(current) => match crate::power::set_active_plan(guid) {
+                Ok(()) => applied.previous_power_plan = Some(current),
+                Err(e) => applied.errors.push(format!("Plan d'alimentation : {}", e)),
+            },
+            Err(e) => applied.errors.push(format!("Plan d'alimentation : {}", e)),
+        }
+    }
+
+    if let Some(minutes) = profile.defender_pause_minutes {
+        match crate::services::defender::DefenderService::disable_for_duration(minutes) {
+            Ok((_, deadline)) => applied.defender_reenable_at = Some(deadline),
+            Err(e) => applied.errors.push(format!("Pause de Defender : {}", e)),
+        }
+    }
+
+    for (feature, enabled) in &profile.windows_gaming {
+        let result = feature.is_enabled().and_then(|previous| {
+            if previous != *enabled {
+                feature.set_enabled(*enabled)?;
+                applied.windows_gaming_changes.push((*feature, previous));
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            applied.errors.push(format!("{} : {}", feature.label(), e));
+        }
+    }
+
+    tracing::info!("✅ Profil {} appliqué ({} erreur(s))", profile.name, applied.errors.len());
+    applied
+}
+
+/// Change one service; `None` when it was already in the requested state
+fn apply_service_action(service_name: &str, action: ServiceAction) -> Result<Option<ServiceRevert>> {
+    let running = crate::services::is_service_running(service_name)?;
+    match action {
+        ServiceAction::Stop if running => {
+            gaming_services::stop_service(service_name)?;
+            Ok(Some(ServiceRevert::Start))
+        }
+        ServiceAction::Start if !running => {
+            gaming_services::start_service(service_name)?;
+            Ok(Some(ServiceRevert::Stop))
+        }
+        ServiceAction::Disable | ServiceAction::Enable => {
+            let target = if action == ServiceAction::Disable { "disabled" } else { "auto" };
+            let previous = gaming_services::query_start_type(service_name)?;
+            if previous == target {
+                return Ok(None);
+            }
+            gaming_services::set_start_type(service_name, target)?;
+            Ok(Some(ServiceRevert::StartType(previous)))
+        }
+        ServiceAction::Stop | ServiceAction::Start => Ok(None),
+    }
+}
+
+/// Undo `applied` in reverse order, each step attempted even when a previous one failed
+pub fn revert(applied: &AppliedProfile, limiter: Option<&mut NetworkLimiter>) -> RevertReport {
+    tracing::info!("↩️ Rétablissement du profil {}", applied.profile_name);
+    let mut report = RevertReport::default();
+    let mut record = |step: String, result: Result<()>| match result {
+        Ok(()) => report.restored += 1,
+        Err(e) => {
+            tracing::error!("❌ {} non rétabli: {}", step, e);
+            report.errors.push(format!("{} : {}", step, e));
+        }
+    };
+
+    for (feature, previous) in applied.windows_gaming_changes.iter().rev() {
+        record(feature.label().to_string(), feature.set_enabled(*previous).map(|_| ()));
+    }
+
+    if applied.defender_reenable_at.is_some() {
+        let result = crate::services::defender::DefenderService::enable_immediately().map(|_| ());
+        record("Windows Defender".to_string(), result);
+    }
+
+    if let Some(guid) = &applied.previous_power_plan {
+        record("Plan d'alimentation".to_string(), crate::power::set_active_plan(guid));
+    }
+
+    match limiter {
+        Some(limiter) => {
+            for policy_name in applied.network_policies.iter().rev() {
+                record(format!("Politique {}", policy_name), limiter.remove_policy(policy_name));
+            }
+        }
+        None => {
+            for policy_name in &applied.network_policies {
+                record(
+                    format!("Politique {}", policy_name),
+                    Err(anyhow::anyhow!("limiteur réseau indisponible")),
+                );
+            }
+        }
+    }
+
+    for (service_name, revert) in applied.service_changes.iter().rev() {
+        let result = match revert {
+            ServiceRevert::Start => gaming_services::start_service(service_name),
+            ServiceRevert::Stop => gaming_services::stop_service(service_name),
+            ServiceRevert::StartType(start_type) => gaming_services::set_start_type(service_name, start_type),
+        };
+        record(format!("Service {}", service_name), result);
+    }
+
+    tracing::info!("✅ Profil {} rétabli ({} erreur(s))", applied.profile_name, report.errors.len());
+    report
+}
@@ -0,0 +1,271 @@
+//! # Protected processes
+//!
+//! `is_protected_process` tells whether throttling, blocking or trimming a process may hurt
+//! the system. It combines a built-in denylist (kernel and session managers, service hosts,
+//! GameBooster itself), the location of the executable (System32 images running in session 0
+//! are Windows services) and, where Windows exposes it, the protected-process-light status.
+//!
+//! `Critical` processes are refused by the network limiter and by `memory::trim_process`;
+//! `Cautious` ones are allowed after a confirmation in the UI. Automatic operations (memory
+//! cleaning, "Limiter tout le reste") skip both.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProtectionLevel {
+    #[default]
+    Normal,
+    /// Allowed after a confirmation: Windows shell, compositor, antivirus...
+    Cautious,
+    /// Refused: the session or the whole system depends on it
+    Critical,
+}
+
+impl ProtectionLevel {
+    pub fn is_protected(self) -> bool {
+        self != ProtectionLevel::Normal
+    }
+}
+
+/// Kernel, session managers and service hosts: slowing them down stalls Windows
+const CRITICAL_NAMES: &[&str] = &[
+    "system",
+    "registry",
+    "memory compression",
+    "secure system",
+    "smss.exe",
+    "csrss.exe",
+    "wininit.exe",
+    "winlogon.exe",
+    "services.exe",
+    "lsass.exe",
+    "lsaiso.exe",
+    "svchost.exe",
+    "audiodg.exe",
+    "fontdrvhost.exe",
+];
+
+/// Parts of the user session or of the antivirus that can be throttled, knowingly
+const CAUTIOUS_NAMES: &[&str] = &[
+    "dwm.exe",
+    "explorer.exe",
+    "sihost.exe",
+    "ctfmon.exe",
+    "msmpeng.exe",
+    "nissrv.exe",
+    "mpdefendercoreservice.exe",
+    "securityhealthservice.exe",
+    "spoolsv.exe",
+    "searchindexer.exe",
+];
+
+fn name_in(name: &str, list: &[&str]) -> bool {
+    let name = name.trim().to_lowercase();
+    list.iter().any(|listed| name == *listed || format!("{}.exe", name) == *listed)
+}
+
+/// `%SystemRoot%` in lowercase, `c:\windows` when the variable is missing
+fn system_root() -> String {
+    std::env::var("SystemRoot").unwrap_or_else(|_| r"C:\Windows".to_string()).to_lowercase()
+}
+
+/// Whether `path` is inside `%SystemRoot%`, and more precisely in System32/SysWOW64
+fn system_location(path: &Path) -> (bool, bool) {
+    let path = path.to_string_lossy().to_lowercase().replace('/', "\\");
+    let root = system_root();
+    let in_windows = path.starts_with(&format!("{}\\", root));
+    let in_system32 = [format!("{}\\system32\\", root), format!("{}\\syswow64\\", root)]
+        .iter()
+        .any(|dir| path.starts_with(dir.as_str()));
+    (in_windows, in_system32)
+}
+
+/// What Windows reports about a process, queried only when its name does not settle it
+#[derive(Debug, Clone, Default)]
+struct ProcessFacts {
+    path: Option<PathBuf>,
+    protected_light: bool,
+    session: Option<u32>,
+}
+
+/// Protection level of a process, with the reason shown to the user
+fn classify(pid: u32, name: &str, path: Option<&Path>) -> (ProtectionLevel, &'static str) {
+    classify_with(pid, name, || ProcessFacts {
+        path: path.map(Path::to_path_buf).or_else(|| image_path(pid)),
+        protected_light: is_protected_process_light(pid),
+        session: session_id(pid),
+    })
+}
+
+/// `classify`, the path, PPL status and session coming from `facts`
+fn classify_with(pid: u32, name: &str, facts: impl FnOnce() -> ProcessFacts) -> (ProtectionLevel, &'static str) {
+    if pid == std::process::id() {
+        return (ProtectionLevel::Critical, "il s'agit de GameBooster lui-même");
+    }
+    if pid == 0 || (cfg!(windows) && pid == 4) || (cfg!(not(windows)) && pid == 1) {
+        return (ProtectionLevel::Critical, "processus du noyau");
+    }
+    if name_in(name, CRITICAL_NAMES) {
+        return (ProtectionLevel::Critical, "processus système critique, Windows en dépend");
+    }
+
+    let facts = facts();
+    if facts.protected_light {
+        return (ProtectionLevel::Critical, "processus protégé par Windows (PPL)");
+    }
+    let (in_windows, in_system32) = facts.path.as_deref().map(system_location).unwrap_or((false, false));
+    if in_system32 && facts.session == Some(0) {
+        return (ProtectionLevel::Critical, "service Windows (System32, session 0)");
+    }
+
+    if name_in(name, CAUTIOUS_NAMES) {
+        return (ProtectionLevel::Cautious, "composant de la session Windows ou de l'antivirus");
+    }
+    if in_windows {
+        return (ProtectionLevel::Cautious, "exécutable du dossier Windows");
+    }
+    (ProtectionLevel::Normal, "")
+}
+
+/// Protection level of the process `pid` named `name`. `path` is resolved from the PID when
+/// unknown.
+pub fn is_protected_process(pid: u32, name: &str, path: Option<&Path>) -> ProtectionLevel {
+    classify(pid, name, path).0
+}
+
+/// Why the process is protected, empty for a `Normal` one
+pub fn protection_reason(pid: u32, name: &str, path: Option<&Path>) -> &'static str {
+    classify(pid, name, path).1
+}
+
+/// Refuse `action` ("limiter", "bloquer", "vider la mémoire de"...) on a critical process
+pub fn ensure_not_critical(pid: u32, name: &str, path: Option<&Path>, action: &str) -> Result<ProtectionLevel> {
+    let (level, reason) = classify(pid, name, path);
+    if level == ProtectionLevel::Critical {
+        tracing::warn!("🔒 Refus de {} {} (PID {}): {}", action, name, pid, reason);
+        return Err(anyhow::anyhow!("Impossible de {} {} (PID {}) : {}", action, name, pid, reason));
+    }
+    Ok(level)
+}
+
+#[cfg(windows)]
+fn image_path(pid: u32) -> Option<PathBuf> {
+    crate::network::query_full_image_path(pid)
+}
+
+#[cfg(not(windows))]
+fn image_path(_pid: u32) -> Option<PathBuf> {
+    None
+}
+
+/// Terminal Services session of the process, 0 for services
+#[cfg(windows)]
+fn session_id(pid: u32) -> Option<u32> {
+    use windows_sys::Win32::System::RemoteDesktop::ProcessIdToSessionId;
+
+    let mut session = 0u32;
+    (unsafe { ProcessIdToSessionId(pid, &mut session) } != 0).then_some(session)
+}
+
+#[cfg(not(windows))]
+fn session_id(_pid: u32) -> Option<u32> {
+    None
+}
+
+/// Protected process light (antimalware, lsass with LSA protection...). `false` when the
+/// process cannot be opened: the other rules still apply.
+#[cfg(windows)]
+fn is_protected_process_light(pid: u32) -> bool {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        GetProcessInformation, OpenProcess, ProcessProtectionLevelInfo, PROCESS_PROTECTION_LEVEL_INFORMATION,
+        PROCESS_QUERY_LIMITED_INFORMATION, PROTECTION_LEVEL_NONE,
+    };
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return false;
+        }
+        let mut info = PROCESS_PROTECTION_LEVEL_INFORMATION { ProtectionLevel: PROTECTION_LEVEL_NONE };
+        let ok = GetProcessInformation(
+            handle,
+            ProcessProtectionLevelInfo,
+            &mut info as *mut _ as *mut std::ffi::c_void,
+            std::mem::size_of::<PROCESS_PROTECTION_LEVEL_INFORMATION>() as u32,
+        );
+        CloseHandle(handle);
+        ok != 0 && info.ProtectionLevel != PROTECTION_LEVEL_NONE
+    }
+}
+
+#[cfg(not(windows))]
+fn is_protected_process_light(_pid: u32) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// PID of no running process, distinct from the kernel ones
+    const PID: u32 = 0x7fff_fff0;
+
+    fn level(name: &str, facts: ProcessFacts) -> ProtectionLevel {
+        classify_with(PID, name, || facts).0
+    }
+
+    fn at(path: String, session: Option<u32>) -> ProcessFacts {
+        ProcessFacts { path: Some(PathBuf::from(path)), protected_light: false, session }
+    }
+
+    #[test]
+    fn listed_names_are_classified_case_insensitively() {
+        for name in CRITICAL_NAMES {
+            assert_eq!(level(name, ProcessFacts::default()), ProtectionLevel::Critical, "{}", name);
+            assert_eq!(level(&name.to_uppercase(), ProcessFacts::default()), ProtectionLevel::Critical, "{}", name);
+        }
+        for name in CAUTIOUS_NAMES {
+            assert_eq!(level(name, ProcessFacts::default()), ProtectionLevel::Cautious, "{}", name);
+        }
+        // Nom sans extension, tel que rapporté par certaines API
+        assert_eq!(level("smss", ProcessFacts::default()), ProtectionLevel::Critical);
+        assert_eq!(level(" Explorer ", ProcessFacts::default()), ProtectionLevel::Cautious);
+        assert_eq!(level("game.exe", ProcessFacts::default()), ProtectionLevel::Normal);
+    }
+
+    #[test]
+    fn gamebooster_and_the_kernel_are_critical() {
+        assert_eq!(is_protected_process(std::process::id(), "gamebooster.exe", None), ProtectionLevel::Critical);
+        assert_eq!(classify_with(0, "idle", ProcessFacts::default).0, ProtectionLevel::Critical);
+    }
+
+    #[test]
+    fn system32_images_are_critical_in_session_zero_only() {
+        let root = system_root();
+        let system32 = format!("{}\\System32\\WUDFHost.exe", root);
+        let syswow64 = format!("{}\\SysWOW64\\svc32.exe", root);
+        assert_eq!(level("wudfhost.exe", at(system32.clone(), Some(0))), ProtectionLevel::Critical);
+        assert_eq!(level("svc32.exe", at(syswow64, Some(0))), ProtectionLevel::Critical);
+        // Session utilisateur ou session inconnue : simple exécutable du dossier Windows
+        assert_eq!(level("wudfhost.exe", at(system32.clone(), Some(1))), ProtectionLevel::Cautious);
+        assert_eq!(level("wudfhost.exe", at(system32, None)), ProtectionLevel::Cautious);
+        assert_eq!(level("helper.exe", at(format!("{}\\helper.exe", root), Some(0))), ProtectionLevel::Cautious);
+        assert_eq!(level("game.exe", at(r"D:\Games\game.exe".to_string(), Some(0))), ProtectionLevel::Normal);
+    }
+
+    #[test]
+    fn protected_process_light_is_critical() {
+        let facts = ProcessFacts { protected_light: true, ..Default::default() };
+        assert_eq!(level("antimalware.exe", facts), ProtectionLevel::Critical);
+    }
+
+    #[test]
+    fn reasons_are_given_for_protected_processes_only() {
+        assert!(classify_with(PID, "svchost.exe", ProcessFacts::default).1.contains("critique"));
+        assert_eq!(classify_with(PID, "game.exe", ProcessFacts::default).1, "");
+        assert!(ensure_not_critical(PID, "lsass.exe", None, "limiter").is_err());
+    }
+}
@@ -2,9 +2,12 @@
 
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use crate::scheduler::ScheduledTask;
 
+const CONFIG_DIR: &str = "config";
+const CONFIG_FILE: &str = "scheduler.json";
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SchedulerConfig {
     pub tasks: Vec<ScheduledTask>,
@@ -23,6 +26,23 @@ impl Default for SchedulerConfig {
 }
 
 impl SchedulerConfig {
+    pub fn path() -> PathBuf {
+        Path::new(CONFIG_DIR).join(CONFIG_FILE)
+    }
+
+    /// Tasks of `config/scheduler.json`, none when it is missing or invalid
+    pub fn load() -> Self {
+        Self::load_from_file(Self::path()).unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Planification invalide ({}), ignorée: {}", Self::path().display(), e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        fs::create_dir_all(CONFIG_DIR)?;
+        self.save_to_file(Self::path()).map_err(|e| anyhow::anyhow!("{}", e))
+    }
+
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
         if !path.as_ref().exists() {
             return Ok(Self::default());
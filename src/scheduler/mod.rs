@@ -1,12 +1,13 @@
 // Scheduler module for automatic cleaning tasks
 pub mod task;
 pub mod config;
+pub mod windows_tasks;
 
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Datelike, Duration, Local, TimeZone};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TaskType {
     CleanRam,
     CleanDisk,
@@ -14,12 +15,88 @@ pub enum TaskType {
     NetworkLimit,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl TaskType {
+    pub const ALL: [TaskType; 4] = [TaskType::CleanRam, TaskType::CleanDisk, TaskType::OptimizeServices, TaskType::NetworkLimit];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TaskType::CleanRam => "🧠 Nettoyage RAM",
+            TaskType::CleanDisk => "💾 Nettoyage disque",
+            TaskType::OptimizeServices => "🛡️ Optimisation des services",
+            TaskType::NetworkLimit => "📡 Limitation réseau",
+        }
+    }
+
+    /// Command-line equivalent (see `cli`), needed to run the task while the app is closed
+    pub fn cli_args(&self) -> Option<&'static str> {
+        match self {
+            TaskType::CleanRam => Some("--clean-ram"),
+            TaskType::CleanDisk => Some("--clean-disk"),
+            TaskType::OptimizeServices | TaskType::NetworkLimit => None,
+        }
+    }
+
+    pub fn requires_elevation(&self) -> bool {
+        match self {
+            TaskType::CleanRam => crate::memory::requires_elevation(),
+            TaskType::CleanDisk => crate::disk::requires_elevation(),
+            TaskType::OptimizeServices => true,
+            TaskType::NetworkLimit => crate::network::requires_elevation(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ScheduleRule {
     OnStartup,
     Hourly(u32), // Every X hours
     Daily(u32),  // At specific hour (0-23)
-    Weekly(u32, u32), // Day of week (0-6), hour (0-23)
+    Weekly(u32, u32), // Day of week (0 = lundi ... 6 = dimanche), hour (0-23)
+}
+
+pub const WEEKDAY_NAMES: [&str; 7] = ["Lundi", "Mardi", "Mercredi", "Jeudi", "Vendredi", "Samedi", "Dimanche"];
+
+impl ScheduleRule {
+    pub fn describe(&self) -> String {
+        match self {
+            ScheduleRule::OnStartup => "Au démarrage".to_string(),
+            ScheduleRule::Hourly(hours) => format!("Toutes les {} h", hours),
+            ScheduleRule::Daily(hour) => format!("Chaque jour à {:02}:00", hour),
+            ScheduleRule::Weekly(day, hour) => format!(
+                "Chaque {} à {:02}:00",
+                WEEKDAY_NAMES[(*day as usize).min(6)].to_lowercase(),
+                hour
+            ),
+        }
+    }
+
+    /// First run strictly after `from`; `None` for `OnStartup`
+    pub fn next_after(&self, from: DateTime<Local>) -> Option<DateTime<Local>> {
+        let at_hour = |date: chrono::NaiveDate, hour: u32| {
+            Local.from_local_datetime(&date.and_hms_opt(hour.min(23), 0, 0)?).earliest()
+        };
+        match self {
+            ScheduleRule::OnStartup => None,
+            ScheduleRule::Hourly(hours) => Some(from + Duration::hours((*hours).max(1) as i64)),
+            ScheduleRule::Daily(hour) => {
+                let today = at_hour(from.date_naive(), *hour)?;
+                if today > from {
+                    Some(today)
+                } else {
+                    at_hour(from.date_naive() + Duration::days(1), *hour)
+                }
+            }
+            ScheduleRule::Weekly(day, hour) => {
+                let days_ahead = (*day as i64).min(6) - from.weekday().num_days_from_monday() as i64;
+                let candidate = at_hour(from.date_naive() + Duration::days(days_ahead.rem_euclid(7)), *hour)?;
+                if candidate > from {
+                    Some(candidate)
+                } else {
+                    Some(candidate + Duration::weeks(1))
+                }
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +107,24 @@ pub struct ScheduledTask {
     pub enabled: bool,
     pub last_run: Option<DateTime<Local>>,
     pub next_run: Option<DateTime<Local>>,
+    /// Mirrored in the Windows Task Scheduler, which runs it instead of the app
+    #[serde(default)]
+    pub run_when_closed: bool,
+}
+
+impl ScheduledTask {
+    pub fn new(task_type: TaskType, schedule: ScheduleRule) -> Self {
+        let now = Local::now();
+        Self {
+            id: format!("{}", now.format("%Y%m%d%H%M%S%3f")),
+            task_type,
+            next_run: schedule.next_after(now),
+            schedule,
+            enabled: true,
+            last_run: None,
+            run_when_closed: false,
+        }
+    }
 }
 
 pub struct TaskScheduler {
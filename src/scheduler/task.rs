@@ -25,8 +25,8 @@ async fn execute_ram_cleaning() -> Result<String> {
 }
 
 async fn execute_disk_cleaning() -> Result<String> {
-    // TODO: Implement disk cleaning
-    Ok("Disk cleaning not yet implemented".to_string())
+    let results = crate::disk::clean_disk_with_options(crate::disk::DiskCleaningOptions::default(), false).await?;
+    Ok(format!("Disk cleaning completed. Freed: {} bytes", results.total_space_freed))
 }
 
 async fn execute_service_optimization() -> Result<String> {
@@ -0,0 +1,147 @@
+//! # Windows Task Scheduler mirror
+//!
+//! Copies of the in-app scheduled tasks registered in the Windows Task Scheduler, so that they
+//! run while GameBooster is closed. The Windows task starts `gamebooster <cli args>` (see `cli`)
+//! with a trigger equivalent to the `ScheduleRule`. Highest privileges are only requested for
+//! task types that need elevation.
+
+use anyhow::{anyhow, Result};
+
+use super::ScheduledTask;
+#[cfg(target_os = "windows")]
+use super::ScheduleRule;
+
+/// Task Scheduler folder holding the mirrored tasks
+const TASK_FOLDER: &str = "GameBooster";
+
+fn task_name(id: &str) -> String {
+    format!("\\{}\\Planification_{}", TASK_FOLDER, id)
+}
+
+/// Create or replace the Windows copy of `task`, enabled like the in-app task
+pub fn register(task: &ScheduledTask) -> Result<()> {
+    let args = task
+        .task_type
+        .cli_args()
+        .ok_or_else(|| anyhow!("{} n'a pas d'équivalent en ligne de commande", task.task_type.label()))?;
+
+    #[cfg(target_os = "windows")]
+    {
+        let exe_path = std::env::current_exe()?;
+        let xml = task_xml(task, &exe_path.to_string_lossy(), args)?;
+        crate::utils::scheduled_task::create_task_from_xml(&task_name(&task.id), &xml)?;
+        tracing::info!("🪟 Tâche {} copiée dans le Planificateur de tâches ({})", task.id, task.schedule.describe());
+        Ok(())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = args;
+        Err(anyhow!("Planificateur de tâches Windows disponible uniquement sous Windows"))
+    }
+}
+
+/// Delete the Windows copy; succeeds when there is none
+pub fn unregister(id: &str) -> Result<()> {
+    crate::utils::scheduled_task::delete_task(&task_name(id))
+}
+
+pub fn exists(id: &str) -> bool {
+    crate::utils::scheduled_task::task_exists(&task_name(id))
+}
+
+pub fn set_enabled(id: &str, enabled: bool) -> Result<()> {
+    crate::utils::scheduled_task::set_task_enabled(&task_name(id), enabled)?;
+    tracing::info!("🪟 Tâche Windows {} {}", id, if enabled { "activée" } else { "désactivée" });
+    Ok(())
+}
+
+/// `<Triggers>` content for a rule. Boundaries are local times without offset, so that
+/// the trigger follows daylight saving time.
+#[cfg(target_os = "windows")]
+fn trigger_xml(rule: &ScheduleRule) -> Result<String> {
+    let now = chrono::Local::now();
+    let start = |at: chrono::DateTime<chrono::Local>| at.format("%Y-%m-%dT%H:%M:%S").to_string();
+
+    let xml = match rule {
+        ScheduleRule::OnStartup => {
+            // Déclencheur d'ouverture de session de l'utilisateur courant : le démarrage du
+            // système exigerait le compte SYSTEM, sans accès au bureau de l'utilisateur
+            let user = match (std::env::var("USERDOMAIN"), std::env::var("USERNAME")) {
+                (Ok(domain), Ok(user)) => format!("{}\\{}", domain, user),
+                _ => return Err(anyhow!("Utilisateur courant inconnu")),
+            };
+            format!(
+                "<LogonTrigger><Enabled>true</Enabled><UserId>{}</UserId></LogonTrigger>",
+                crate::utils::scheduled_task::xml_escape(&user)
+            )
+        }
+        ScheduleRule::Hourly(hours) => format!(
+            "<TimeTrigger><StartBoundary>{}</StartBoundary><Enabled>true</Enabled>\
+             <Repetition><Interval>PT{}H</Interval><StopAtDurationEnd>false</StopAtDurationEnd></Repetition></TimeTrigger>",
+            start(rule.next_after(now).unwrap_or(now)),
+            (*hours).max(1)
+        ),
+        ScheduleRule::Daily(_) => format!(
+            "<CalendarTrigger><StartBoundary>{}</StartBoundary><Enabled>true</Enabled>\
+             <ScheduleByDay><DaysInterval>1</DaysInterval></ScheduleByDay></CalendarTrigger>",
+            start(rule.next_after(now).unwrap_or(now))
+        ),
+        ScheduleRule::Weekly(day, _) => {
+            // Éléments du schéma Task Scheduler, 0 = lundi comme dans ScheduleRule
+            const DAY_ELEMENTS: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+            format!(
+                "<CalendarTrigger><StartBoundary>{}</StartBoundary><Enabled>true</Enabled>\
+                 <ScheduleByWeek><DaysOfWeek><{}/></DaysOfWeek><WeeksInterval>1</WeeksInterval></ScheduleByWeek></CalendarTrigger>",
+                start(rule.next_after(now).unwrap_or(now)),
+                DAY_ELEMENTS[(*day as usize).min(6)]
+            )
+        }
+    };
+    Ok(xml)
+}
+
+#[cfg(target_os = "windows")]
+fn task_xml(task: &ScheduledTask, command: &str, args: &str) -> Result<String> {
+    use crate::utils::scheduled_task::xml_escape;
+
+    let run_level = if task.task_type.requires_elevation() { "HighestAvailable" } else { "LeastPrivilege" };
+    Ok(format!(
+        r#"<?xml version="1.0" encoding="UTF-16"?>
+<Task version="1.2" xmlns="http://schemas.microsoft.com/windows/2004/02/mit/task">
+  <RegistrationInfo>
+    <Description>GameBooster : {description} ({schedule})</Description>
+  </RegistrationInfo>
+  <Triggers>
+    {trigger}
+  </Triggers>
+  <Principals>
+    <Principal id="Author">
+      <LogonType>InteractiveToken</LogonType>
+      <RunLevel>{run_level}</RunLevel>
+    </Principal>
+  </Principals>
+  <Settings>
+    <Enabled>{enabled}</Enabled>
+    <MultipleInstancesPolicy>IgnoreNew</MultipleInstancesPolicy>
+    <StartWhenAvailable>true</StartWhenAvailable>
+    <DisallowStartIfOnBatteries>false</DisallowStartIfOnBatteries>
+    <StopIfGoingOnBatteries>false</StopIfGoingOnBatteries>
+    <ExecutionTimeLimit>PT30M</ExecutionTimeLimit>
+  </Settings>
+  <Actions Context="Author">
+    <Exec>
+      <Command>{command}</Command>
+      <Arguments>{args}</Arguments>
+    </Exec>
+  </Actions>
+</Task>
+"#,
+        description = xml_escape(task.task_type.label()),
+        schedule = xml_escape(&task.schedule.describe()),
+        trigger = trigger_xml(&task.schedule)?,
+        run_level = run_level,
+        enabled = task.enabled,
+        command = xml_escape(command),
+        args = xml_escape(args),
+    ))
+}
@@ -1,7 +1,15 @@
 // Windows Defender management
 
+use std::sync::mpsc::{self, Receiver, Sender};
+
 use anyhow::Result;
-use crate::services::winapi_defender::DefenderManager;
+use chrono::{DateTime, Duration, Local};
+use crate::config::AppConfig;
+use crate::services::winapi_defender::{DefenderAction, DefenderActionSummary, DefenderManager, DefenderStep, StepResult};
+use crate::utils::scheduled_task;
+
+/// Scheduled task re-enabling Defender if the app is closed at the end of a timed pause
+pub const REENABLE_TASK_NAME: &str = "GameBooster_DefenderReenable";
 
 #[derive(Debug, Clone, Default)]
 pub struct DefenderStatus {
@@ -13,6 +21,46 @@ pub struct DefenderStatus {
     pub last_operation_results: Vec<String>,
 }
 
+/// Live checklist of a Defender action running in the background: the planned steps, the
+/// results received so far and, once finished, the summary
+pub struct DefenderProgress {
+    pub action: DefenderAction,
+    pub steps: Vec<DefenderStep>,
+    pub results: Vec<StepResult>,
+    pub summary: Option<DefenderActionSummary>,
+    receiver: Receiver<StepResult>,
+}
+
+impl DefenderProgress {
+    /// The checklist and the sender to hand to `DefenderService::run_with_progress`
+    pub fn start(action: DefenderAction) -> (Self, Sender<StepResult>) {
+        let steps = match action {
+            DefenderAction::Disable => DefenderManager::disable_steps(),
+            DefenderAction::Enable => DefenderManager::enable_steps(),
+        };
+        let (sender, receiver) = mpsc::channel();
+        (Self { action, steps, results: Vec::new(), summary: None, receiver }, sender)
+    }
+
+    /// Collect the steps completed since the last call
+    pub fn poll(&mut self) {
+        self.results.extend(self.receiver.try_iter());
+    }
+
+    /// Share of the planned steps completed, 1.0 once the action is over
+    pub fn fraction(&self) -> f32 {
+        if self.summary.is_some() || self.steps.is_empty() {
+            return 1.0;
+        }
+        self.results.len().min(self.steps.len()) as f32 / self.steps.len() as f32
+    }
+
+    /// Result of the `index`-th planned step, `None` while it has not run
+    pub fn result_of(&self, index: usize) -> Option<&StepResult> {
+        self.results.get(index)
+    }
+}
+
 pub struct DefenderService;
 
 impl DefenderService {
@@ -89,6 +137,7 @@ impl DefenderService {
     /// Enable Defender immediately with detailed feedback
     pub fn enable_immediately() -> Result<DefenderStatus> {
         let results = DefenderManager::enable_defender_immediately()?;
+        Self::cancel_timed_pause();
         
         // Wait a moment for changes to take effect
         std::thread::sleep(std::time::Duration::from_millis(2000));
@@ -106,6 +155,55 @@ impl DefenderService {
         Ok(status)
     }
 
+    /// Disable or re-enable Defender, each step sent on `progress` as it completes.
+    /// Re-enabling also forgets a pending timed pause.
+    pub fn run_with_progress(action: DefenderAction, progress: &Sender<StepResult>) -> Result<DefenderActionSummary> {
+        match action {
+            DefenderAction::Disable => DefenderManager::disable_defender_with_progress(progress),
+            DefenderAction::Enable => {
+                let summary = DefenderManager::enable_defender_with_progress(progress)?;
+                Self::cancel_timed_pause();
+                Ok(summary)
+            }
+        }
+    }
+
+    /// Disable Defender for `minutes`, returning the re-enable deadline.
+    /// The running app re-enables at the deadline; a one-shot scheduled task
+    /// (`--defender-enable`) covers the case where it has been closed.
+    pub fn disable_for_duration(minutes: u32) -> Result<(DefenderStatus, DateTime<Local>)> {
+        let status = Self::disable_immediately()?;
+        let deadline = Local::now() + Duration::minutes(minutes as i64);
+
+        if let Err(e) = scheduled_task::create_one_shot_task(REENABLE_TASK_NAME, deadline, "--defender-enable") {
+            tracing::warn!("⚠️ Tâche de réactivation non créée, réactivation uniquement si l'app est ouverte: {}", e);
+        }
+
+        let mut config = AppConfig::load();
+        config.defender_reenable_at = Some(deadline);
+        if let Err(e) = config.save() {
+            tracing::error!("❌ Échéance de réactivation non sauvegardée: {}", e);
+        }
+
+        tracing::info!("⏸️ Defender en pause pour {} min (réactivation à {})", minutes, deadline.format("%H:%M"));
+        Ok((status, deadline))
+    }
+
+    /// Forget a pending timed pause: delete the scheduled task and the stored deadline
+    pub fn cancel_timed_pause() {
+        let mut config = AppConfig::load();
+        if config.defender_reenable_at.take().is_none() {
+            return;
+        }
+
+        if let Err(e) = scheduled_task::delete_task(REENABLE_TASK_NAME) {
+            tracing::warn!("⚠️ Suppression de la tâche de réactivation impossible: {}", e);
+        }
+        if let Err(e) = config.save() {
+            tracing::error!("❌ Échéance de réactivation non effacée: {}", e);
+        }
+    }
+
     /// Quick status check (lighter than full get_status)
     pub fn is_active() -> bool {
         Self::get_status()
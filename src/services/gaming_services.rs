@@ -0,0 +1,505 @@
+//! # Gaming services
+//!
+//! Background Windows services that can be stopped during a gaming session, and their
+//! restoration afterwards. In `ServiceMode::Session` a service is only stopped, its start type
+//! left untouched; in `ServiceMode::Durable` its start type is also set to disabled, the
+//! previous one being recorded in the operation so that the restoration can put it back.
+//!
+//! `sc` runs through a `CommandRunner`: the `*_with` variants take it, the others use the
+//! default one.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use chrono::Local;
+
+use super::winapi_service_manager::{ServiceManager, MAX_DEPENDENTS};
+use super::{get_service_status_with, ServiceAction, ServiceMode, ServiceOperation, ServicesOptimizationResults};
+use crate::system::{self, CommandRunner};
+
+/// Time allowed to stop every dependent of one service, the service is skipped beyond
+const DEPENDENTS_STOP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A service offered in the Services tab
+pub struct GamingService {
+    pub name: &'static str,
+    pub display_name: &'static str,
+    pub description: &'static str,
+    /// Selected when the user never changed the selection
+    pub selected_by_default: bool,
+}
+
+pub const GAMING_SERVICES: &[GamingService] = &[
+    GamingService { name: "WSearch", display_name: "Windows Search", description: "Indexation des fichiers", selected_by_default: true },
+    GamingService { name: "wuauserv", display_name: "Windows Update", description: "Mises à jour automatiques", selected_by_default: true },
+    GamingService { name: "SysMain", display_name: "Superfetch", description: "Préchargement des applications", selected_by_default: true },
+    GamingService { name: "Spooler", display_name: "Print Spooler", description: "Service d'impression", selected_by_default: true },
+    GamingService { name: "TabletInputService", display_name: "Tablet PC Input Service", description: "Saisie tactile", selected_by_default: false },
+    GamingService { name: "WerSvc", display_name: "Windows Error Reporting", description: "Rapports d'erreurs", selected_by_default: true },
+];
+
+fn display_name(service_name: &str) -> String {
+    GAMING_SERVICES
+        .iter()
+        .find(|s| s.name == service_name)
+        .map(|s| s.display_name.to_string())
+        .unwrap_or_else(|| service_name.to_string())
+}
+
+/// `sc stop|start <name>`; "already stopped/started" counts as success
+fn run_sc(runner: &dyn CommandRunner, verb: &str, service_name: &str) -> Result<()> {
+    let output = runner.run("sc", &[verb, service_name])?;
+    if output.success() {
+        return Ok(());
+    }
+    // 1062 : service non démarré, 1056 : déjà en cours d'exécution
+    let code = output.code.unwrap_or_default();
+    if (verb == "stop" && code == 1062) || (verb == "start" && code == 1056) {
+        return Ok(());
+    }
+    Err(anyhow!("sc {} {} a échoué (code {}): {}", verb, service_name, code, output.stdout.trim()))
+}
+
+/// `sc stop`, already stopped counts as success
+pub fn stop_service(service_name: &str) -> Result<()> {
+    run_sc(system::default_runner().as_ref(), "stop", service_name)
+}
+
+/// `sc start`, already running counts as success
+pub fn start_service(service_name: &str) -> Result<()> {
+    run_sc(system::default_runner().as_ref(), "start", service_name)
+}
+
+/// Start type as accepted by `sc config <name> start=` (`auto`, `delayed-auto`, `demand`, `disabled`...)
+pub fn query_start_type(service_name: &str) -> Result<String> {
+    query_start_type_with(system::default_runner().as_ref(), service_name)
+}
+
+fn query_start_type_with(runner: &dyn CommandRunner, service_name: &str) -> Result<String> {
+    let output = runner.run("sc", &["qc", service_name])?;
+    let stdout = &output.stdout;
+    // "        START_TYPE         : 2   AUTO_START  (DELAYED)"
+    let line = stdout
+        .lines()
+        .find(|line| line.trim_start().starts_with("START_TYPE"))
+        .ok_or_else(|| anyhow!("Type de démarrage de {} introuvable: {}", service_name, stdout.trim()))?;
+    let code = line.split(':').nth(1).and_then(|value| value.split_whitespace().next());
+    let start_type = match code {
+        Some("0") => "boot",
+        Some("1") => "system",
+        Some("2") if line.contains("DELAYED") => "delayed-auto",
+        Some("2") => "auto",
+        Some("3") => "demand",
+        Some("4") => "disabled",
+        _ => return Err(anyhow!("Type de démarrage de {} non reconnu: {}", service_name, line.trim())),
+    };
+    Ok(start_type.to_string())
+}
+
+/// `sc config <name> start= <start_type>`
+pub fn set_start_type(service_name: &str, start_type: &str) -> Result<()> {
+    set_start_type_with(system::default_runner().as_ref(), service_name, start_type)
+}
+
+fn set_start_type_with(runner: &dyn CommandRunner, service_name: &str, start_type: &str) -> Result<()> {
+    let output = runner.run("sc", &["config", service_name, "start=", start_type])?;
+    if output.success() {
+        tracing::info!("⚙️ Service {} : démarrage {}", service_name, start_type);
+        return Ok(());
+    }
+    Err(anyhow!("sc config {} start= {} a échoué: {}", service_name, start_type, output.stdout.trim()))
+}
+
+fn operation(service_name: &str, action: ServiceAction, result: Result<()>) -> ServiceOperation {
+    ServiceOperation {
+        service_name: service_name.to_string(),
+        display_name: display_name(service_name),
+        action,
+        timestamp: Local::now(),
+        success: result.is_ok(),
+        error_message: result.err().map(|e| e.to_string()),
+        mode: None,
+        previous_start_type: None,
+        stopped_for: None,
+    }
+}
+
+fn mode_operation(
+    service_name: &str,
+    action: ServiceAction,
+    mode: ServiceMode,
+    previous_start_type: Option<String>,
+    result: Result<()>,
+) -> ServiceOperation {
+    ServiceOperation {
+        mode: Some(mode),
+        previous_start_type,
+        ..operation(service_name, action, result)
+    }
+}
+
+/// Dependents of `service_name` that are not stopped, in stop order
+fn active_dependents(service_name: &str) -> Vec<String> {
+    ServiceManager::get_service_dependents(service_name)
+        .into_iter()
+        .filter(|dependent| ServiceManager::get_service_status(dependent).map_or(true, |status| status != "Stopped"))
+        .collect()
+}
+
+/// Poll until `service_name` is stopped, `sc stop` only sends the request
+fn wait_until_stopped(runner: &dyn CommandRunner, service_name: &str, deadline: Instant) -> Result<()> {
+    loop {
+        if get_service_status_with(runner, service_name)? == "Stopped" {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(anyhow!("{} toujours en cours d'arrêt après {} s", service_name, DEPENDENTS_STOP_TIMEOUT.as_secs()));
+        }
+        std::thread::sleep(Duration::from_millis(250));
+    }
+}
+
+/// Stop `dependents` of `service_name` first, one operation each (session mode, so that the
+/// restoration starts them again). Without `manage_dependencies`, or when one of them cannot be
+/// stopped, the error explains why `service_name` is skipped.
+fn stop_dependents(
+    runner: &dyn CommandRunner,
+    service_name: &str,
+    dependents: &[String],
+    manage_dependencies: bool,
+    dry_run: bool,
+    results: &mut ServicesOptimizationResults,
+) -> Result<()> {
+    if !manage_dependencies {
+        return Err(anyhow!(
+            "ignoré : requis par {} en cours d'exécution (cochez « Gérer les dépendances automatiquement » pour les arrêter d'abord)",
+            dependents.join(", ")
+        ));
+    }
+    if dependents.len() > MAX_DEPENDENTS {
+        return Err(anyhow!("ignoré : plus de {} services en dépendent", MAX_DEPENDENTS));
+    }
+
+    let deadline = Instant::now() + DEPENDENTS_STOP_TIMEOUT;
+    for dependent in dependents {
+        let result = if dry_run {
+            Ok(())
+        } else {
+            run_sc(runner, "stop", dependent).and_then(|()| wait_until_stopped(runner, dependent, deadline))
+        };
+        let failed = result.as_ref().err().map(|e| e.to_string());
+        match &failed {
+            None => tracing::info!("⏹️ Service dépendant {} arrêté avant {}", dependent, service_name),
+            Some(e) => tracing::error!("❌ Service dépendant {} non arrêté: {}", dependent, e),
+        }
+        results.add_operation(ServiceOperation {
+            stopped_for: Some(service_name.to_string()),
+            ..mode_operation(dependent, ServiceAction::Stop, ServiceMode::Session, None, result)
+        });
+        if failed.is_some() {
+            return Err(anyhow!("ignoré : le service dépendant {} n'a pas pu être arrêté", dependent));
+        }
+    }
+    Ok(())
+}
+
+/// Stop the selected services that are running; in durable mode, also disable them, even when
+/// already stopped. The running services that depend on one are stopped before it with
+/// `manage_dependencies`, otherwise it is skipped. Services with nothing to change are skipped, so that `restore_services`
+/// only undoes what this optimization did. The operation is `Stop` when the service was
+/// stopped, `Disable` when only its start type changed.
+/// With `dry_run`, the services are only queried and the results list what would be done.
+pub fn optimize_selected_services(
+    services: &[(String, ServiceMode)],
+    manage_dependencies: bool,
+    dry_run: bool,
+) -> ServicesOptimizationResults {
+    optimize_selected_services_with(system::default_runner().as_ref(), services, manage_dependencies, dry_run)
+}
+
+/// `optimize_selected_services`, `sc` running through `runner`
+pub fn optimize_selected_services_with(
+    runner: &dyn CommandRunner,
+    services: &[(String, ServiceMode)],
+    manage_dependencies: bool,
+    dry_run: bool,
+) -> ServicesOptimizationResults {
+    let mut results = ServicesOptimizationResults::new();
+    results.simulated = dry_run;
+
+    for (service_name, mode) in services {
+        let mode = *mode;
+        let running = match get_service_status_with(runner, service_name) {
+            Ok(status) => status == "Running",
+            Err(e) => {
+                results.add_operation(mode_operation(service_name, ServiceAction::Stop, mode, None, Err(e)));
+                continue;
+            }
+        };
+        let previous_start_type = match mode {
+            ServiceMode::Session => None,
+            ServiceMode::Durable => match query_start_type_with(runner, service_name) {
+                Ok(start_type) if start_type == "disabled" => None,
+                Ok(start_type) => Some(start_type),
+                Err(e) => {
+                    results.add_operation(mode_operation(service_name, ServiceAction::Disable, mode, None, Err(e)));
+                    continue;
+                }
+            },
+        };
+        if !running && previous_start_type.is_none() {
+            tracing::info!("⏭️ Service {} ignoré (déjà arrêté)", service_name);
+            continue;
+        }
+        let action = if running { ServiceAction::Stop } else { ServiceAction::Disable };
+
+        // Un service arrêté alors que d'autres en dépendent échoue (1051) ou les arrête en cascade
+        let dependents = if running { active_dependents(service_name) } else { Vec::new() };
+        if !dependents.is_empty() {
+            if let Err(e) = stop_dependents(runner, service_name, &dependents, manage_dependencies, dry_run, &mut results) {
+                tracing::warn!("⏭️ Service {} {}", service_name, e);
+                results.add_operation(mode_operation(service_name, action, mode, None, Err(e)));
+                continue;
+            }
+        }
+
+        if dry_run {
+            tracing::info!("🧪 Simulation : le service {} serait traité ({})", service_name, mode.label());
+            results.add_operation(mode_operation(service_name, action, mode, previous_start_type, Ok(())));
+            continue;
+        }
+
+        // Désactivé d'abord : un déclencheur ne peut plus le relancer entre-temps
+        let result = match &previous_start_type {
+            Some(_) => set_start_type_with(runner, service_name, "disabled"),
+            None => Ok(()),
+        };
+        let result = result.and_then(|()| if running { run_sc(runner, "stop", service_name) } else { Ok(()) });
+        match &result {
+            Ok(()) => tracing::info!("⏹️ Service {} traité ({})", service_name, mode.label()),
+            Err(e) => {
+                tracing::error!("❌ Arrêt du service {} impossible: {}", service_name, e);
+                // Pas de type de démarrage modifié derrière une opération en échec
+                if let Some(start_type) = &previous_start_type {
+                    if let Err(e) = set_start_type_with(runner, service_name, start_type) {
+                        tracing::error!("❌ Type de démarrage de {} non rétabli: {}", service_name, e);
+                    }
+                }
+            }
+        }
+        let previous_start_type = if result.is_ok() { previous_start_type } else { None };
+        results.add_operation(mode_operation(service_name, action, mode, previous_start_type, result));
+    }
+
+    results.complete();
+    results
+}
+
+/// Undo a previous optimization (nothing after a simulation): the start types changed by the
+/// durable mode are restored first, then the stopped services are started again
+pub fn restore_services(optimization: &ServicesOptimizationResults) -> ServicesOptimizationResults {
+    restore_services_with(system::default_runner().as_ref(), optimization)
+}
+
+/// `restore_services`, `sc` running through `runner`
+pub fn restore_services_with(runner: &dyn CommandRunner, optimization: &ServicesOptimizationResults) -> ServicesOptimizationResults {
+    let mut results = ServicesOptimizationResults::new();
+    if optimization.simulated {
+        results.complete();
+        return results;
+    }
+
+    for op in optimization.operations.iter().filter(|op| op.success) {
+        if let Some(start_type) = &op.previous_start_type {
+            let result = set_start_type_with(runner, &op.service_name, start_type);
+            if let Err(e) = &result {
+                tracing::error!("❌ Type de démarrage de {} non rétabli: {}", op.service_name, e);
+            }
+            results.add_operation(mode_operation(&op.service_name, ServiceAction::Enable, ServiceMode::Durable, None, result));
+        }
+    }
+    // Ordre inverse de l'arrêt : un service avant ceux qui en dépendent
+    let stopped: Vec<String> = optimization
+        .operations
+        .iter()
+        .rev()
+        .filter(|op| op.success && matches!(op.action, ServiceAction::Stop))
+        .map(|op| op.service_name.clone())
+        .collect();
+    for operation in restart_services_with(runner, &stopped).operations {
+        results.add_operation(operation);
+    }
+
+    results.complete();
+    results
+}
+
+/// Names of the services stopped for the session only, restarted by the panic handler; the
+/// durable ones stay disabled until they are restored from the Services tab
+pub fn stopped_services(optimization: &ServicesOptimizationResults) -> Vec<String> {
+    if optimization.simulated {
+        return Vec::new();
+    }
+    optimization
+        .operations
+        .iter()
+        .filter(|op| op.success && matches!(op.action, ServiceAction::Stop) && op.mode != Some(ServiceMode::Durable))
+        .map(|op| op.service_name.clone())
+        .collect()
+}
+
+/// Service name -> start type replaced by the durable mode, persisted so that the uninstall
+/// cleanup can put it back after the app was closed
+pub fn replaced_start_types(optimization: &ServicesOptimizationResults) -> Vec<(String, String)> {
+    if optimization.simulated {
+        return Vec::new();
+    }
+    optimization
+        .operations
+        .iter()
+        .filter(|op| op.success)
+        .filter_map(|op| Some((op.service_name.clone(), op.previous_start_type.clone()?)))
+        .collect()
+}
+
+/// `sc start` each of `service_names`
+pub fn restart_services(service_names: &[String]) -> ServicesOptimizationResults {
+    restart_services_with(system::default_runner().as_ref(), service_names)
+}
+
+fn restart_services_with(runner: &dyn CommandRunner, service_names: &[String]) -> ServicesOptimizationResults {
+    let mut results = ServicesOptimizationResults::new();
+    for service_name in service_names {
+        let result = run_sc(runner, "start", service_name);
+        match &result {
+            Ok(()) => tracing::info!("▶️ Service {} redémarré", service_name),
+            Err(e) => tracing::error!("❌ Redémarrage du service {} impossible: {}", service_name, e),
+        }
+        results.add_operation(operation(service_name, ServiceAction::Start, result));
+    }
+
+    results.complete();
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use crate::system::CommandOutput;
+
+    /// `sc` over in-memory services: name -> (running, start type code), recording the verbs run
+    struct FakeSc {
+        services: Mutex<HashMap<String, (bool, u32)>>,
+        verbs: Mutex<Vec<String>>,
+    }
+
+    impl FakeSc {
+        fn new(services: &[(&str, bool, u32)]) -> Self {
+            let services = services.iter().map(|(name, running, start)| (name.to_string(), (*running, *start))).collect();
+            Self { services: Mutex::new(services), verbs: Mutex::new(Vec::new()) }
+        }
+
+        fn state(&self, name: &str) -> (bool, u32) {
+            self.services.lock().unwrap()[name]
+        }
+    }
+
+    impl CommandRunner for FakeSc {
+        fn run(&self, program: &str, args: &[&str]) -> Result<CommandOutput> {
+            assert_eq!(program, "sc");
+            self.verbs.lock().unwrap().push(args[0].to_string());
+            let mut services = self.services.lock().unwrap();
+            let service = services.get_mut(args[1]).ok_or_else(|| anyhow!("service inconnu {}", args[1]))?;
+            let ok = |stdout: String| Ok(CommandOutput { code: Some(0), stdout, stderr: String::new() });
+            match args[0] {
+                "query" => ok(format!("        STATE              : {}", if service.0 { "4  RUNNING" } else { "1  STOPPED" })),
+                "qc" => ok(format!("        START_TYPE         : {}   X", service.1)),
+                "stop" if !service.0 => Ok(CommandOutput { code: Some(1062), ..Default::default() }),
+                "start" if service.0 => Ok(CommandOutput { code: Some(1056), ..Default::default() }),
+                "stop" | "start" => {
+                    service.0 = args[0] == "start";
+                    ok(String::new())
+                }
+                "config" => {
+                    service.1 = match args[3] {
+                        "auto" => 2,
+                        "demand" => 3,
+                        "disabled" => 4,
+                        other => panic!("type de démarrage inattendu {}", other),
+                    };
+                    ok(String::new())
+                }
+                verb => panic!("commande sc inattendue {}", verb),
+            }
+        }
+    }
+
+    #[test]
+    fn optimize_then_restore_puts_services_back() {
+        let sc = FakeSc::new(&[
+            ("GbTestSession", true, 3),
+            ("GbTestDurable", true, 2),
+            ("GbTestStopped", false, 3),
+        ]);
+        let selected = vec![
+            ("GbTestSession".to_string(), ServiceMode::Session),
+            ("GbTestDurable".to_string(), ServiceMode::Durable),
+            ("GbTestStopped".to_string(), ServiceMode::Session),
+        ];
+
+        let optimization = optimize_selected_services_with(&sc, &selected, false, false);
+        // Le service déjà arrêté n'a rien à défaire
+        assert_eq!(optimization.operations.len(), 2);
+        assert!(optimization.operations.iter().all(|op| op.success && matches!(op.action, ServiceAction::Stop)));
+        assert_eq!(optimization.operations[1].previous_start_type.as_deref(), Some("auto"));
+        assert_eq!(sc.state("GbTestSession"), (false, 3));
+        assert_eq!(sc.state("GbTestDurable"), (false, 4));
+        assert_eq!(stopped_services(&optimization), vec!["GbTestSession".to_string()]);
+        assert_eq!(
+            replaced_start_types(&optimization),
+            vec![("GbTestDurable".to_string(), "auto".to_string())]
+        );
+
+        let restoration = restore_services_with(&sc, &optimization);
+        assert!(restoration.operations.iter().all(|op| op.success));
+        assert_eq!(sc.state("GbTestSession"), (true, 3));
+        assert_eq!(sc.state("GbTestDurable"), (true, 2));
+        assert_eq!(sc.state("GbTestStopped"), (false, 3));
+    }
+
+    #[test]
+    fn dry_run_only_queries_the_services() {
+        let sc = FakeSc::new(&[("GbTestSession", true, 3), ("GbTestDurable", true, 2)]);
+        let selected = vec![
+            ("GbTestSession".to_string(), ServiceMode::Session),
+            ("GbTestDurable".to_string(), ServiceMode::Durable),
+        ];
+
+        let simulation = optimize_selected_services_with(&sc, &selected, true, true);
+        assert!(simulation.simulated);
+        assert_eq!(simulation.operations.len(), 2);
+        assert!(simulation.operations.iter().all(|op| op.success));
+        assert!(sc.verbs.lock().unwrap().iter().all(|verb| verb == "query" || verb == "qc"));
+        assert_eq!(sc.state("GbTestSession"), (true, 3));
+        assert_eq!(sc.state("GbTestDurable"), (true, 2));
+
+        // Rien à défaire ni à redémarrer après une simulation
+        assert!(restore_services_with(&sc, &simulation).operations.is_empty());
+        assert!(stopped_services(&simulation).is_empty());
+        assert!(replaced_start_types(&simulation).is_empty());
+        assert!(sc.verbs.lock().unwrap().iter().all(|verb| verb == "query" || verb == "qc"));
+    }
+
+    #[test]
+    fn disabled_stopped_service_is_skipped() {
+        let sc = FakeSc::new(&[("GbTestDisabled", false, 4)]);
+        let selected = vec![("GbTestDisabled".to_string(), ServiceMode::Durable)];
+
+        let optimization = optimize_selected_services_with(&sc, &selected, false, false);
+        assert!(optimization.operations.is_empty());
+        assert_eq!(sc.state("GbTestDisabled"), (false, 4));
+    }
+}
@@ -1,16 +1,18 @@
 // Windows services optimization module
 pub mod defender;
+pub mod gaming_services;
 pub mod powershell_runner;
+pub mod tamper_guidance;
 pub mod winapi_defender;
 pub mod winapi_service_manager;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::process::Command;
 use chrono::{DateTime, Local};
 use crate::services::defender::DefenderService;
+use crate::system::{self, CommandRunner};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ServiceAction {
     Disable,
     Enable,
@@ -18,6 +20,25 @@ pub enum ServiceAction {
     Start,
 }
 
+/// How a gaming service is stopped by the optimization
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServiceMode {
+    /// Stopped until the restoration or the next reboot, start type untouched
+    #[default]
+    Session,
+    /// Stopped and start type set to disabled, kept after a reboot
+    Durable,
+}
+
+impl ServiceMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ServiceMode::Session => "Arrêter pour cette session",
+            ServiceMode::Durable => "Désactiver durablement",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceOperation {
     pub service_name: String,
@@ -26,6 +47,15 @@ pub struct ServiceOperation {
     pub timestamp: DateTime<Local>,
     pub success: bool,
     pub error_message: Option<String>,
+    /// Mode of a gaming service optimization, `None` for the other operations
+    #[serde(default)]
+    pub mode: Option<ServiceMode>,
+    /// Start type before a durable optimization changed it
+    #[serde(default)]
+    pub previous_start_type: Option<String>,
+    /// Service that could only be stopped once this dependent of it was
+    #[serde(default)]
+    pub stopped_for: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +66,9 @@ pub struct ServicesOptimizationResults {
     pub defender_disabled: bool,
     pub services_optimized: u32,
     pub errors: Vec<String>,
+    /// Dry run: `operations` lists what would have been done, nothing was changed
+    #[serde(default)]
+    pub simulated: bool,
     pub is_completed: bool,
 }
 
@@ -48,6 +81,7 @@ impl ServicesOptimizationResults {
             defender_disabled: false,
             services_optimized: 0,
             errors: Vec::new(),
+            simulated: false,
             is_completed: false,
         }
     }
@@ -67,9 +101,33 @@ impl ServicesOptimizationResults {
     }
 }
 
-pub async fn optimize_services_for_gaming() -> Result<ServicesOptimizationResults> {
+/// Whether service control and Defender registry writes need administrator rights
+pub fn requires_elevation() -> bool {
+    true
+}
+
+/// Disable Defender for gaming. With `dry_run`, the operation is only listed.
+pub async fn optimize_services_for_gaming(dry_run: bool) -> Result<ServicesOptimizationResults> {
     let mut results = ServicesOptimizationResults::new();
 
+    if dry_run {
+        tracing::info!("🧪 Simulation : Windows Defender serait désactivé");
+        results.simulated = true;
+        results.add_operation(ServiceOperation {
+            service_name: "Windows Defender".to_string(),
+            display_name: "Windows Defender Antivirus Service".to_string(),
+            action: ServiceAction::Disable,
+            timestamp: Local::now(),
+            success: true,
+            error_message: None,
+            mode: None,
+            previous_start_type: None,
+            stopped_for: None,
+        });
+        results.complete();
+        return Ok(results);
+    }
+
     // Disable Windows Defender (with user consent)
     match handle_disable_defender().await {
         Ok(disabled) => {
@@ -82,6 +140,9 @@ pub async fn optimize_services_for_gaming() -> Result<ServicesOptimizationResult
                     timestamp: Local::now(),
                     success: true,
                     error_message: None,
+                    mode: None,
+                    previous_start_type: None,
+                    stopped_for: None,
                 });
             }
         }
@@ -93,6 +154,9 @@ pub async fn optimize_services_for_gaming() -> Result<ServicesOptimizationResult
                 timestamp: Local::now(),
                 success: false,
                 error_message: Some(e),
+                mode: None,
+                previous_start_type: None,
+                stopped_for: None,
             });
         }
     }
@@ -102,21 +166,18 @@ pub async fn optimize_services_for_gaming() -> Result<ServicesOptimizationResult
 }
 
 pub fn is_service_running(service_name: &str) -> Result<bool> {
-    let output = Command::new("sc")
-        .args(&["query", service_name])
-        .output()?;
-
-    let output_str = String::from_utf8_lossy(&output.stdout);
-    Ok(output_str.contains("RUNNING"))
+    Ok(get_service_status(service_name)? == "Running")
 }
 
 pub fn get_service_status(service_name: &str) -> Result<String> {
-    let output = Command::new("sc")
-        .args(&["query", service_name])
-        .output()?;
+    get_service_status_with(system::default_runner().as_ref(), service_name)
+}
+
+/// State reported by `sc query service_name`, run through `runner`
+pub fn get_service_status_with(runner: &dyn CommandRunner, service_name: &str) -> Result<String> {
+    let output = runner.run("sc", &["query", service_name])?;
+    let output_str = &output.stdout;
 
-    let output_str = String::from_utf8_lossy(&output.stdout);
-    
     if output_str.contains("RUNNING") {
         Ok("Running".to_string())
     } else if output_str.contains("STOPPED") {
@@ -139,3 +200,30 @@ pub async fn handle_disable_defender() -> Result<bool, String> {
         Err(e) => Err(format!("Failed to disable Windows Defender: {}", e)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system::testing::FakeRunner;
+
+    #[test]
+    fn service_status_comes_from_sc_query() {
+        for (state, expected) in [
+            ("4  RUNNING", "Running"),
+            ("1  STOPPED", "Stopped"),
+            ("2  START_PENDING", "Starting"),
+            ("3  STOP_PENDING", "Stopping"),
+            ("7  PAUSED", "Unknown"),
+        ] {
+            let runner = FakeRunner::printing(&format!("SERVICE_NAME: SysMain\n        STATE              : {}\n", state));
+            assert_eq!(get_service_status_with(&runner, "SysMain").unwrap(), expected);
+            assert_eq!(runner.calls(), vec!["sc query SysMain".to_string()]);
+        }
+    }
+
+    #[test]
+    fn service_status_fails_when_sc_cannot_run() {
+        let runner = FakeRunner::new(|_, _| Err(anyhow::anyhow!("sc introuvable")));
+        assert!(get_service_status_with(&runner, "SysMain").is_err());
+    }
+}
@@ -51,3 +51,9 @@ pub async fn run_powershell_command(command: &str) -> Result<String, PowerShellE
     let _ = command; // Mark as used
     Err(PowerShellExecutionError::NotAvailable)
 }
+
+/// Version bloquante de `run_powershell_command`, pour les threads de `Promise`.
+pub fn run_powershell_command_blocking(command: &str) -> Result<String, PowerShellExecutionError> {
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    runtime.block_on(run_powershell_command(command))
+}
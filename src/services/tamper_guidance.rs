@@ -0,0 +1,178 @@
+// Tamper Protection guidance
+//
+// With Tamper Protection on, Windows refuses every write under the Defender registry keys and
+// only the user can turn it off, in Windows Security. The guidance explains the manual step,
+// opens the right page, then polls the Defender status on a worker thread until Tamper
+// Protection reads as off and hands the interrupted operation back to be resumed. The UI only
+// renders `TamperGuidance::phase`.
+
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use crate::services::winapi_defender::DefenderManager;
+
+/// Windows Security page holding the Tamper Protection switch
+pub const TAMPER_SETTINGS_URI: &str = "windowsdefender://threatsettings";
+/// Delay between two status reads while waiting for the user
+pub const POLL_INTERVAL: Duration = Duration::from_secs(3);
+/// Waiting stops after this, the user can retry
+pub const POLL_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Defender operation interrupted by Tamper Protection, resumed once it is off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuardedOperation {
+    Disable,
+    /// Timed pause of this many minutes
+    Pause(u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TamperPhase {
+    /// Explanation shown, waiting for "J'ai désactivé la protection — réessayer"
+    Explaining,
+    /// Status polled every `POLL_INTERVAL` since `started`
+    Waiting { started: Instant },
+    /// Tamper Protection off: the operation is handed back by `poll`
+    Resumed,
+    /// Still on after `POLL_TIMEOUT`
+    TimedOut,
+}
+
+enum WatchEvent {
+    Off,
+    TimedOut,
+}
+
+/// Worker polling the status, stopped and joined when dropped
+struct TamperWatcher {
+    events: Receiver<WatchEvent>,
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl TamperWatcher {
+    fn start() -> Self {
+        let (sender, events) = mpsc::channel();
+        let (stop, stop_receiver) = mpsc::channel();
+        let handle = std::thread::Builder::new()
+            .name("tamper-watcher".to_string())
+            .spawn(move || watch(sender, stop_receiver))
+            .map_err(|e| tracing::warn!("⚠️ Surveillance de la protection contre les falsifications non démarrée: {}", e))
+            .ok();
+        Self { events, stop: Some(stop), handle }
+    }
+}
+
+impl Drop for TamperWatcher {
+    fn drop(&mut self) {
+        // Fermer le canal réveille le thread pendant son attente
+        self.stop.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn watch(events: Sender<WatchEvent>, stop: Receiver<()>) {
+    let started = Instant::now();
+    loop {
+        match stop.recv_timeout(POLL_INTERVAL) {
+            Err(RecvTimeoutError::Timeout) => {}
+            // Guide abandonné
+            Ok(()) | Err(RecvTimeoutError::Disconnected) => return,
+        }
+        let tamper_protection = DefenderManager::check_defender_status().map(|status| status.tamper_protection).unwrap_or(true);
+        if !tamper_protection {
+            tracing::info!("🔓 Protection contre les falsifications désactivée, reprise de l'opération Defender");
+            let _ = events.send(WatchEvent::Off);
+            return;
+        }
+        if started.elapsed() >= POLL_TIMEOUT {
+            tracing::info!("⏳ Protection contre les falsifications toujours active après {} s", POLL_TIMEOUT.as_secs());
+            let _ = events.send(WatchEvent::TimedOut);
+            return;
+        }
+    }
+}
+
+/// Guided flow around a Defender operation refused by Tamper Protection. Dropping it (the
+/// dialog abandoned) stops the polling thread.
+pub struct TamperGuidance {
+    pub operation: GuardedOperation,
+    phase: TamperPhase,
+    watcher: Option<TamperWatcher>,
+}
+
+impl TamperGuidance {
+    pub fn new(operation: GuardedOperation) -> Self {
+        tracing::info!("🔒 Protection contre les falsifications active : guide affiché pour {:?}", operation);
+        Self { operation, phase: TamperPhase::Explaining, watcher: None }
+    }
+
+    pub fn phase(&self) -> &TamperPhase {
+        &self.phase
+    }
+
+    /// The user says Tamper Protection is off: poll the status until it reads so
+    pub fn retry(&mut self) {
+        if matches!(self.phase, TamperPhase::Waiting { .. } | TamperPhase::Resumed) {
+            return;
+        }
+        self.watcher = Some(TamperWatcher::start());
+        self.phase = TamperPhase::Waiting { started: Instant::now() };
+    }
+
+    /// Advance the phase; returns the operation to resume once, when Tamper Protection is off
+    pub fn poll(&mut self) -> Option<GuardedOperation> {
+        let event = match self.watcher.as_ref()?.events.try_recv() {
+            Ok(event) => event,
+            Err(TryRecvError::Empty) => return None,
+            // Thread non démarré ou arrêté sans réponse
+            Err(TryRecvError::Disconnected) => WatchEvent::TimedOut,
+        };
+        self.watcher = None;
+        match event {
+            WatchEvent::Off => {
+                self.phase = TamperPhase::Resumed;
+                Some(self.operation)
+            }
+            WatchEvent::TimedOut => {
+                self.phase = TamperPhase::TimedOut;
+                None
+            }
+        }
+    }
+}
+
+/// Open the Tamper Protection page of Windows Security
+#[cfg(target_os = "windows")]
+pub fn open_tamper_settings() -> Result<()> {
+    use windows_sys::Win32::UI::Shell::ShellExecuteW;
+    use windows_sys::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    let operation: Vec<u16> = "open".encode_utf16().chain(std::iter::once(0)).collect();
+    let uri: Vec<u16> = TAMPER_SETTINGS_URI.encode_utf16().chain(std::iter::once(0)).collect();
+    let result = unsafe {
+        ShellExecuteW(
+            std::ptr::null_mut(),
+            operation.as_ptr(),
+            uri.as_ptr(),
+            std::ptr::null(),
+            std::ptr::null(),
+            SW_SHOWNORMAL,
+        )
+    };
+    // ShellExecuteW retourne une valeur > 32 en cas de succès
+    if result as isize <= 32 {
+        return Err(anyhow::anyhow!("Ouverture de Sécurité Windows impossible (code {})", result as isize));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn open_tamper_settings() -> Result<()> {
+    Err(anyhow::anyhow!("Fonctionnalité non disponible sur Linux"))
+}
@@ -1,30 +1,32 @@
 // Windows Defender management using Registry and WinAPI
 // Manages Windows Defender without PowerShell commands
 
-use anyhow::{Result};
+use anyhow::{anyhow, Result};
+#[cfg(target_os = "windows")]
+use anyhow::Context;
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+#[cfg(target_os = "windows")]
+use std::sync::mpsc;
+use std::sync::mpsc::Sender;
 
 #[cfg(target_os = "windows")]
 use std::ffi::{c_void, CString};
 #[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
-#[cfg(target_os = "windows")]
 use tracing;
 #[cfg(target_os = "windows")]
-use windows_sys::Win32::Foundation::{
-    CloseHandle, ERROR_FILE_NOT_FOUND, ERROR_SUCCESS, HANDLE,
-};
+use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
 #[cfg(target_os = "windows")]
 use windows_sys::Win32::Security::{
     GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY,
 };
 #[cfg(target_os = "windows")]
-use windows_sys::Win32::System::Registry::{
-    RegCloseKey, RegCreateKeyExA, RegDeleteValueA, RegOpenKeyExA, RegQueryValueExA, RegSetValueExA,
-    HKEY, HKEY_LOCAL_MACHINE, KEY_READ, KEY_SET_VALUE, KEY_WOW64_64KEY, REG_DWORD,
-    REG_OPTION_NON_VOLATILE,
-};
+use crate::utils::registry::{self, HKEY_LOCAL_MACHINE};
+#[cfg(target_os = "windows")]
+use crate::services::powershell_runner::run_powershell_command_blocking;
+#[cfg(target_os = "windows")]
+use crate::system::{self, CommandRunner};
 #[cfg(target_os = "windows")]
 use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
 
@@ -52,6 +54,169 @@ impl Default for DefenderStatus {
     }
 }
 
+/// What a step of a Defender action works on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefenderStepKind {
+    Service,
+    PowerShell,
+    Registry,
+    /// Final re-check of the real-time protection
+    Verification,
+}
+
+/// One operation of `disable_defender_with_progress` / `enable_defender_with_progress`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DefenderStep {
+    /// "Service WinDefend", "Registre Policy DisableRealtimeMonitoring"
+    pub name: String,
+    pub kind: DefenderStepKind,
+}
+
+impl DefenderStep {
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    fn new(kind: DefenderStepKind, name: impl Into<String>) -> Self {
+        Self { name: name.into(), kind }
+    }
+}
+
+/// A completed step, sent as soon as it finishes
+#[derive(Debug, Clone)]
+pub struct StepResult {
+    pub step: DefenderStep,
+    /// The error message on failure
+    pub outcome: std::result::Result<(), String>,
+    /// Win32 code of a failed registry write, see `registry::error_code`
+    pub registry_code: Option<u32>,
+}
+
+/// Answer of the registry to a write under the Defender keys while Tamper Protection is on
+const TAMPER_REGISTRY_CODES: [u32; 1] = [5]; // ERROR_ACCESS_DENIED
+
+impl StepResult {
+    /// A registry write refused the way Tamper Protection refuses it
+    pub fn refused_by_tamper_protection(&self) -> bool {
+        self.registry_code.is_some_and(|code| TAMPER_REGISTRY_CODES.contains(&code))
+    }
+
+    /// "✅ Service WinDefend", "❌ PowerShell : accès refusé"
+    pub fn message(&self) -> String {
+        match &self.outcome {
+            Ok(()) => format!("✅ {}", self.step.name),
+            Err(e) => format!("❌ {} : {}", self.step.name, e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefenderAction {
+    Disable,
+    Enable,
+}
+
+/// Overall result of a Defender action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionOutcome {
+    Success,
+    Partial,
+    Failed,
+}
+
+/// Step outcomes of a Defender action and the real-time protection state measured after it
+#[derive(Debug, Clone)]
+pub struct DefenderActionSummary {
+    pub action: DefenderAction,
+    /// Operations that succeeded, the verification excluded
+    pub succeeded: usize,
+    /// Operations run, the verification excluded
+    pub total: usize,
+    /// `None` when the re-check could not read the status
+    pub real_time_protection: Option<bool>,
+    /// A registry write was refused the way Tamper Protection refuses it
+    pub tamper_blocked: bool,
+}
+
+impl DefenderActionSummary {
+    pub fn from_results(action: DefenderAction, results: &[StepResult], real_time_protection: Option<bool>) -> Self {
+        let operations = results.iter().filter(|result| result.step.kind != DefenderStepKind::Verification);
+        let (total, succeeded) = operations.fold((0, 0), |(total, succeeded), result| (total + 1, succeeded + usize::from(result.outcome.is_ok())));
+        let tamper_blocked = results.iter().any(StepResult::refused_by_tamper_protection);
+        Self { action, succeeded, total, real_time_protection, tamper_blocked }
+    }
+
+    /// Decided by the re-check; the step counts only separate a partial result from a failure
+    pub fn outcome(&self) -> ActionOutcome {
+        let target_reached = match self.action {
+            DefenderAction::Disable => self.real_time_protection == Some(false),
+            DefenderAction::Enable => self.real_time_protection == Some(true),
+        };
+        if target_reached {
+            ActionOutcome::Success
+        } else if self.succeeded > 0 {
+            ActionOutcome::Partial
+        } else {
+            ActionOutcome::Failed
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match (self.action, self.outcome()) {
+            (DefenderAction::Disable, ActionOutcome::Success) => {
+                format!("🎉 SUCCÈS ! Defender désactivé ({}/{} méthodes réussies)", self.succeeded, self.total)
+            }
+            (DefenderAction::Disable, ActionOutcome::Partial) => format!(
+                "⚠️ Désactivation partielle ({}/{} méthodes réussies) - Certaines protections peuvent persister",
+                self.succeeded, self.total
+            ),
+            (DefenderAction::Disable, ActionOutcome::Failed) => {
+                "❌ ÉCHEC - Toutes les méthodes ont échoué. Tamper Protection probablement active.".to_string()
+            }
+            (DefenderAction::Enable, ActionOutcome::Success) => {
+                format!("🎉 SUCCÈS ! Defender réactivé ({}/{} opérations réussies)", self.succeeded, self.total)
+            }
+            (DefenderAction::Enable, _) => format!("⚠️ Réactivation partielle ({}/{} opérations réussies)", self.succeeded, self.total),
+        }
+    }
+}
+
+/// Run `steps` in order, each result sent on `progress` as it completes. A closed channel
+/// (nobody watching) does not stop the action.
+#[cfg(target_os = "windows")]
+fn run_steps(steps: Vec<(DefenderStep, Box<dyn Fn() -> Result<()>>)>, progress: &Sender<StepResult>) -> Vec<StepResult> {
+    let mut results = Vec::new();
+    for (step, operation) in steps {
+        let span = tracing::info_span!("defender_step", step = %step.name, kind = ?step.kind);
+        let _entered = span.enter();
+        let (outcome, registry_code) = match operation() {
+            Ok(()) => (Ok(()), None),
+            Err(e) => (Err(format!("{:#}", e)), registry::error_code(&e)),
+        };
+        let result = StepResult { step, outcome, registry_code };
+        match &result.outcome {
+            Ok(()) => tracing::debug!("{}", result.message()),
+            Err(_) => tracing::warn!("{}", result.message()),
+        }
+        let _ = progress.send(result.clone());
+        results.push(result);
+    }
+    results
+}
+
+/// Messages of a finished action, for the callers of the `Vec<String>` API
+#[cfg(target_os = "windows")]
+fn collect_messages(
+    start: &str,
+    action: impl FnOnce(&Sender<StepResult>) -> Result<DefenderActionSummary>,
+) -> Result<Vec<String>> {
+    let (sender, receiver) = mpsc::channel();
+    let summary = action(&sender)?;
+    drop(sender);
+
+    let mut messages = vec![start.to_string()];
+    messages.extend(receiver.iter().map(|result| result.message()));
+    messages.push(summary.message());
+    Ok(messages)
+}
+
 pub struct DefenderManager;
 
 #[cfg(target_os = "windows")]
@@ -61,6 +226,7 @@ impl DefenderManager {
     const FEATURES_REGISTRY_PATH: &'static str = "SOFTWARE\\Microsoft\\Windows Defender\\Features";
     const SPYNET_REGISTRY_PATH: &'static str = "SOFTWARE\\Microsoft\\Windows Defender\\Spynet";
     const SCAN_REGISTRY_PATH: &'static str = "SOFTWARE\\Microsoft\\Windows Defender\\Scan";
+    const EXCLUSION_PATHS_REGISTRY_PATH: &'static str = "SOFTWARE\\Microsoft\\Windows Defender\\Exclusions\\Paths";
 
     /// Check if Windows Defender real-time protection is enabled via registry
     pub fn check_defender_status() -> Result<DefenderStatus> {
@@ -98,240 +264,137 @@ impl DefenderManager {
 
     /// Read a DWORD value from the Policy registry key  
     fn _get_policy_setting(value_name: &str) -> Result<Option<u32>> {
-        unsafe {
-            let mut key: HKEY = std::ptr::null_mut();
-            let registry_path =
-                CString::new(Self::POLICY_REGISTRY_PATH).map_err(|e| anyhow!(e))?;
-
-            let result = RegOpenKeyExA(
-                HKEY_LOCAL_MACHINE,
-                registry_path.as_ptr() as *const u8,
-                0,
-                KEY_READ | KEY_WOW64_64KEY,
-                &mut key,
-            );
-
-            if result != ERROR_SUCCESS {
-                return Ok(None);
-            }
-
-            let mut value: u32 = 0;
-            let mut value_size: u32 = std::mem::size_of::<u32>() as u32;
-            let mut value_type: u32 = 0;
-            let value_name_cstr = CString::new(value_name).map_err(|e| anyhow!(e))?;
-
-            let read_result = RegQueryValueExA(
-                key,
-                value_name_cstr.as_ptr() as *const u8,
-                std::ptr::null_mut(),
-                &mut value_type,
-                &mut value as *mut u32 as *mut u8,
-                &mut value_size,
-            );
-
-            RegCloseKey(key);
-
-            if read_result == ERROR_SUCCESS {
-                Ok(Some(value))
-            } else if read_result == ERROR_FILE_NOT_FOUND {
-                Ok(None)
-            } else {
-                Err(anyhow!(
-                    "Failed to read policy registry value '{}'. Error: {}",
-                    value_name,
-                    read_result
-                ))
-            }
-        }
+        registry::read_dword(HKEY_LOCAL_MACHINE, Self::POLICY_REGISTRY_PATH, value_name)
+            .map_err(|e| anyhow!("Failed to read policy registry value '{}': {}", value_name, e))
     }
 
     /// Generic function to read a DWORD value from the Defender registry key.
     /// Returns Ok(None) if the value doesn't exist.
     fn _get_defender_setting(value_name: &str) -> Result<Option<u32>> {
-        unsafe {
-            let mut key: HKEY = std::ptr::null_mut();
-            let registry_path =
-                CString::new(Self::DEFENDER_REGISTRY_PATH).map_err(|e| anyhow!(e))?;
-
-            let result = RegOpenKeyExA(
-                HKEY_LOCAL_MACHINE,
-                registry_path.as_ptr() as *const u8,
-                0,
-                KEY_READ | KEY_WOW64_64KEY,
-                &mut key,
-            );
-
-            if result != ERROR_SUCCESS {
-                // Key not existing is not an error, it just means settings are default
-                return Ok(None);
-            }
-
-            let mut value: u32 = 0;
-            let mut value_size: u32 = std::mem::size_of::<u32>() as u32;
-            let mut value_type: u32 = 0;
-            let value_name_cstr = CString::new(value_name).map_err(|e| anyhow!(e))?;
-
-            let read_result = RegQueryValueExA(
-                key,
-                value_name_cstr.as_ptr() as *const u8,
-                std::ptr::null_mut(),
-                &mut value_type,
-                &mut value as *mut u32 as *mut u8,
-                &mut value_size,
-            );
-
-            RegCloseKey(key);
-
-            if read_result == ERROR_SUCCESS {
-                Ok(Some(value))
-            } else if read_result == ERROR_FILE_NOT_FOUND {
-                Ok(None) // Value not found is not an error
-            } else {
-                Err(anyhow!(
-                    "Failed to read registry value '{}'. Error: {}",
-                    value_name,
-                    read_result
-                ))
-            }
-        }
+        // Key not existing is not an error, it just means settings are default
+        registry::read_dword(HKEY_LOCAL_MACHINE, Self::DEFENDER_REGISTRY_PATH, value_name)
     }
 
     /// Read a DWORD value from the Features registry key
     fn _get_features_setting(value_name: &str) -> Result<Option<u32>> {
-        unsafe {
-            let mut key: HKEY = std::ptr::null_mut();
-            let registry_path =
-                CString::new(Self::FEATURES_REGISTRY_PATH).map_err(|e| anyhow!(e))?;
-
-            let result = RegOpenKeyExA(
-                HKEY_LOCAL_MACHINE,
-                registry_path.as_ptr() as *const u8,
-                0,
-                KEY_READ | KEY_WOW64_64KEY,
-                &mut key,
-            );
+        registry::read_dword(HKEY_LOCAL_MACHINE, Self::FEATURES_REGISTRY_PATH, value_name)
+            .map_err(|e| anyhow!("Failed to read features registry value '{}': {}", value_name, e))
+    }
 
-            if result != ERROR_SUCCESS {
-                return Ok(None);
-            }
+    /// Steps of `disable_defender_with_progress`, in order, the final verification included
+    pub fn disable_steps() -> Vec<DefenderStep> {
+        Self::_disable_plan().into_iter().map(|(step, _)| step).chain([Self::_verification_step()]).collect()
+    }
 
-            let mut value: u32 = 0;
-            let mut value_size: u32 = std::mem::size_of::<u32>() as u32;
-            let mut value_type: u32 = 0;
-            let value_name_cstr = CString::new(value_name).map_err(|e| anyhow!(e))?;
-
-            let read_result = RegQueryValueExA(
-                key,
-                value_name_cstr.as_ptr() as *const u8,
-                std::ptr::null_mut(),
-                &mut value_type,
-                &mut value as *mut u32 as *mut u8,
-                &mut value_size,
-            );
+    /// Steps of `enable_defender_with_progress`, in order, the final verification included
+    pub fn enable_steps() -> Vec<DefenderStep> {
+        Self::_enable_plan().into_iter().map(|(step, _)| step).chain([Self::_verification_step()]).collect()
+    }
 
-            RegCloseKey(key);
+    fn _verification_step() -> DefenderStep {
+        DefenderStep::new(DefenderStepKind::Verification, "Vérification de la protection temps réel")
+    }
 
-            if read_result == ERROR_SUCCESS {
-                Ok(Some(value))
-            } else if read_result == ERROR_FILE_NOT_FOUND {
-                Ok(None)
-            } else {
-                Err(anyhow!(
-                    "Failed to read features registry value '{}'. Error: {}",
-                    value_name,
-                    read_result
-                ))
-            }
+    /// Stop the services, PowerShell for the immediate effect, then the registry for persistence
+    fn _disable_plan() -> Vec<(DefenderStep, Box<dyn Fn() -> Result<()>>)> {
+        let mut plan: Vec<(DefenderStep, Box<dyn Fn() -> Result<()>>)> = Vec::new();
+        for service in ["WinDefend", "WdNisSvc", "WdFilter", "WdNisDrv"] {
+            plan.push((
+                DefenderStep::new(DefenderStepKind::Service, format!("Service {} arrêté", service)),
+                Box::new(move || Self::_stop_service_immediately(service)),
+            ));
         }
+        plan.push((
+            DefenderStep::new(DefenderStepKind::PowerShell, "PowerShell Set-MpPreference"),
+            Box::new(Self::_disable_via_powershell),
+        ));
+        plan.push((
+            DefenderStep::new(DefenderStepKind::Registry, "Registre Policy DisableRealtimeMonitoring"),
+            Box::new(|| Self::_set_defender_policy("DisableRealtimeMonitoring", 1)),
+        ));
+        plan.push((
+            DefenderStep::new(DefenderStepKind::Registry, "Registre Features TamperProtection"),
+            Box::new(|| Self::_set_features_setting("TamperProtection", 4)),
+        ));
+        plan.push((
+            DefenderStep::new(DefenderStepKind::Registry, "Registre Main DisableAntiSpyware"),
+            Box::new(|| Self::_set_defender_main_setting("DisableAntiSpyware", 1)),
+        ));
+        plan
     }
 
-    /// Attempt to disable Windows Defender immediately without restart
-    pub fn disable_defender_immediately() -> Result<Vec<String>> {
+    /// PowerShell, registry cleanup, then the service restart
+    fn _enable_plan() -> Vec<(DefenderStep, Box<dyn Fn() -> Result<()>>)> {
+        vec![
+            (
+                DefenderStep::new(DefenderStepKind::PowerShell, "PowerShell Set-MpPreference"),
+                Box::new(Self::_enable_via_powershell),
+            ),
+            (
+                DefenderStep::new(DefenderStepKind::Registry, "Registre Policy DisableRealtimeMonitoring nettoyé"),
+                Box::new(|| Self::_delete_defender_policy("DisableRealtimeMonitoring").map(|_| ())),
+            ),
+            (
+                DefenderStep::new(DefenderStepKind::Registry, "Registre Features TamperProtection"),
+                Box::new(|| Self::_set_features_setting("TamperProtection", 5)),
+            ),
+            (
+                DefenderStep::new(DefenderStepKind::Registry, "Registre Main DisableAntiSpyware nettoyé"),
+                Box::new(|| Self::_delete_defender_main_setting("DisableAntiSpyware").map(|_| ())),
+            ),
+            (
+                DefenderStep::new(DefenderStepKind::Service, "Service WinDefend redémarré"),
+                Box::new(|| Self::_start_service_immediately("WinDefend")),
+            ),
+        ]
+    }
+
+    /// Disable Defender without restart, each step sent on `progress` as it completes.
+    /// The final outcome is decided by re-reading the real-time protection state.
+    pub fn disable_defender_with_progress(progress: &Sender<StepResult>) -> Result<DefenderActionSummary> {
         if !utils::is_elevated() {
             return Err(anyhow!(
                 "Administrator privileges required to modify Windows Defender"
             ));
         }
 
-        let mut results = Vec::new();
-        let mut success_count = 0;
-
-        tracing::info!("Starting immediate Defender disable procedure...");
-        results.push("🚀 Démarrage de la désactivation immédiate de Defender...".to_string());
-
-        // Step 1: Try to stop Defender services immediately
-        let services = vec!["WinDefend", "WdNisSvc", "WdFilter", "WdNisDrv"];
-        for service in &services {
-            match Self::_stop_service_immediately(service) {
-                Ok(_) => {
-                    success_count += 1;
-                    let msg = format!("✅ Service {} arrêté avec succès", service);
-                    tracing::info!("{}", msg);
-                    results.push(msg);
-                }
-                Err(e) => {
-                    let msg = format!("❌ Échec arrêt service {}: {}", service, e);
-                    tracing::warn!("{}", msg);
-                    results.push(msg);
-                }
-            }
-        }
+        let span = tracing::info_span!("defender_action", action = ?DefenderAction::Disable);
+        let _entered = span.enter();
+        tracing::debug!("Starting immediate Defender disable procedure...");
+        let mut results = run_steps(Self::_disable_plan(), progress);
+        let real_time_protection = Self::_verify(DefenderAction::Disable, std::time::Duration::from_millis(1000), progress, &mut results);
 
-        // Step 2: Use PowerShell for immediate effect
-        match Self::_disable_via_powershell() {
-            Ok(_) => {
-                success_count += 1;
-                let msg = "✅ Désactivation PowerShell réussie".to_string();
-                tracing::info!("{}", msg);
-                results.push(msg);
-            }
-            Err(e) => {
-                let msg = format!("❌ Échec PowerShell: {}", e);
-                tracing::warn!("{}", msg);
-                results.push(msg);
-            }
-        }
-
-        // Step 3: Registry changes for persistence
-        type RegistryOperation = Box<dyn Fn() -> Result<()>>;
-        let registry_ops: Vec<(&str, RegistryOperation)> = vec![
-            ("Policy DisableRealtimeMonitoring", Box::new(|| Self::_set_defender_policy("DisableRealtimeMonitoring", 1))),
-            ("Features TamperProtection", Box::new(|| Self::_set_features_setting("TamperProtection", 4))),
-            ("Main DisableAntiSpyware", Box::new(|| Self::_set_defender_main_setting("DisableAntiSpyware", 1))),
-        ];
-
-        for (name, operation) in registry_ops {
-            match operation() {
-                Ok(_) => {
-                    success_count += 1;
-                    let msg = format!("✅ Registry {}: Succès", name);
-                    tracing::info!("{}", msg);
-                    results.push(msg);
-                }
-                Err(e) => {
-                    let msg = format!("❌ Registry {}: {}", name, e);
-                    tracing::warn!("{}", msg);
-                    results.push(msg);
-                }
-            }
-        }
+        let summary = DefenderActionSummary::from_results(DefenderAction::Disable, &results, real_time_protection);
+        tracing::info!("{}", summary.message());
+        Ok(summary)
+    }
 
-        // Step 4: Final verification
-        std::thread::sleep(std::time::Duration::from_millis(1000));
-        let final_status = Self::check_defender_status().unwrap_or_default();
-        
-        let summary = if !final_status.real_time_protection {
-            format!("🎉 SUCCÈS ! Defender désactivé ({}/7 méthodes réussies)", success_count)
-        } else if success_count > 0 {
-            format!("⚠️ Désactivation partielle ({}/7 méthodes réussies) - Certaines protections peuvent persister", success_count)
-        } else {
-            "❌ ÉCHEC - Toutes les méthodes ont échoué. Tamper Protection probablement active.".to_string()
+    /// Wait for the changes to apply, then send the re-check as the last step
+    fn _verify(
+        action: DefenderAction,
+        delay: std::time::Duration,
+        progress: &Sender<StepResult>,
+        results: &mut Vec<StepResult>,
+    ) -> Option<bool> {
+        std::thread::sleep(delay);
+        let status = Self::check_defender_status();
+        let real_time_protection = status.as_ref().ok().map(|status| status.real_time_protection);
+
+        let outcome = match (status, action) {
+            (Err(e), _) => Err(format!("statut illisible : {}", e)),
+            (Ok(status), DefenderAction::Disable) if status.real_time_protection => Err("protection temps réel toujours active".to_string()),
+            (Ok(status), DefenderAction::Enable) if !status.real_time_protection => Err("protection temps réel toujours inactive".to_string()),
+            (Ok(_), _) => Ok(()),
         };
+        let result = StepResult { step: Self::_verification_step(), outcome, registry_code: None };
+        let _ = progress.send(result.clone());
+        results.push(result);
+        real_time_protection
+    }
 
-        tracing::info!("{}", summary);
-        results.push(summary);
-
-        Ok(results)
+    /// Attempt to disable Windows Defender immediately without restart
+    pub fn disable_defender_immediately() -> Result<Vec<String>> {
+        collect_messages("🚀 Démarrage de la désactivation immédiate de Defender...", Self::disable_defender_with_progress)
     }
 
     /// Stop a Windows service immediately using the Service Control Manager API
@@ -380,121 +443,69 @@ impl DefenderManager {
 
     /// Use PowerShell commands for immediate Defender disabling
     fn _disable_via_powershell() -> Result<()> {
-        use std::process::Command;
-
-        let commands = vec![
-            "Set-MpPreference -DisableRealtimeMonitoring $true",
-            "Set-MpPreference -DisableIOAVProtection $true", 
-            "Set-MpPreference -DisableBehaviorMonitoring $true",
-            "Set-MpPreference -DisableBlockAtFirstSeen $true",
-        ];
+        Self::_disable_via_powershell_with(system::default_runner().as_ref())
+    }
 
-        for cmd in commands {
-            let mut command_obj = Command::new("powershell");
-            command_obj.args(&["-Command", cmd]);
-            
-            #[cfg(target_os = "windows")]
-            command_obj.creation_flags(0x08000000); // CREATE_NO_WINDOW
-            
-            let output = command_obj.output();
+    /// `_disable_via_powershell`, each `Set-MpPreference` run in order through `runner`;
+    /// a failed command is logged and the next one still runs
+    fn _disable_via_powershell_with(runner: &dyn CommandRunner) -> Result<()> {
+        Self::_run_mp_preferences(
+            runner,
+            &[
+                "Set-MpPreference -DisableRealtimeMonitoring $true",
+                "Set-MpPreference -DisableIOAVProtection $true",
+                "Set-MpPreference -DisableBehaviorMonitoring $true",
+                "Set-MpPreference -DisableBlockAtFirstSeen $true",
+            ],
+        )
+    }
 
-            match output {
+    /// Run every command, then fail with the first error when any of them failed
+    fn _run_mp_preferences(runner: &dyn CommandRunner, commands: &[&str]) -> Result<()> {
+        let mut failures = Vec::new();
+        for &cmd in commands {
+            match runner.powershell(cmd) {
                 Ok(result) => {
-                    if result.status.success() {
-                        tracing::info!("PowerShell command succeeded: {}", cmd);
+                    if result.success() {
+                        tracing::debug!("PowerShell command succeeded: {}", cmd);
                     } else {
-                        let stderr = String::from_utf8_lossy(&result.stderr);
-                        tracing::warn!("PowerShell command failed: {} - {}", cmd, stderr);
+                        tracing::warn!("PowerShell command failed: {} - {}", cmd, result.stderr);
+                        failures.push(result.stderr.trim().to_string());
                     }
                 }
                 Err(e) => {
                     tracing::warn!("Failed to execute PowerShell command {}: {}", cmd, e);
+                    failures.push(e.to_string());
                 }
             }
         }
 
-        Ok(())
+        match failures.first() {
+            None => Ok(()),
+            Some(first) => Err(anyhow!("{}/{} commande(s) en échec : {}", failures.len(), commands.len(), first)),
+        }
     }
 
-    /// Enable Defender immediately
-    pub fn enable_defender_immediately() -> Result<Vec<String>> {
+    /// Re-enable Defender without restart, each step sent on `progress` as it completes
+    pub fn enable_defender_with_progress(progress: &Sender<StepResult>) -> Result<DefenderActionSummary> {
         if !utils::is_elevated() {
             return Err(anyhow!(
                 "Administrator privileges required to modify Windows Defender"
             ));
         }
 
-        let mut results = Vec::new();
-        let mut success_count = 0;
-
-        results.push("🔄 Réactivation immédiate de Defender...".to_string());
-
-        // Step 1: PowerShell re-enable
-        match Self::_enable_via_powershell() {
-            Ok(_) => {
-                success_count += 1;
-                let msg = "✅ Réactivation PowerShell réussie".to_string();
-                results.push(msg);
-            }
-            Err(e) => {
-                let msg = format!("❌ Échec réactivation PowerShell: {}", e);
-                results.push(msg);
-            }
-        }
-
-        // Step 2: Registry cleanup
-        type RegistryOperation = Box<dyn Fn() -> Result<()>>;
-        let cleanup_ops: Vec<(&str, RegistryOperation)> = vec![
-            ("Policy DisableRealtimeMonitoring", Box::new(|| {
-                Self::_delete_defender_policy("DisableRealtimeMonitoring").map(|_| ())
-            })),
-            ("Features TamperProtection", Box::new(|| Self::_set_features_setting("TamperProtection", 5))),
-            ("Main DisableAntiSpyware", Box::new(|| {
-                Self::_delete_defender_main_setting("DisableAntiSpyware").map(|_| ())
-            })),
-        ];
-
-        for (name, operation) in cleanup_ops {
-            match operation() {
-                Ok(_) => {
-                    success_count += 1;
-                    let msg = format!("✅ Registry {}: Nettoyé", name);
-                    results.push(msg);
-                }
-                Err(e) => {
-                    let msg = format!("❌ Registry {}: {}", name, e);
-                    results.push(msg);
-                }
-            }
-        }
-
-        // Step 3: Start services
-        let services = vec!["WinDefend"];
-        for service in &services {
-            match Self::_start_service_immediately(service) {
-                Ok(_) => {
-                    success_count += 1;
-                    let msg = format!("✅ Service {} redémarré", service);
-                    results.push(msg);
-                }
-                Err(e) => {
-                    let msg = format!("❌ Échec redémarrage {}: {}", service, e);
-                    results.push(msg);
-                }
-            }
-        }
-
-        std::thread::sleep(std::time::Duration::from_millis(1500));
-        let final_status = Self::check_defender_status().unwrap_or_default();
-        
-        let summary = if final_status.real_time_protection {
-            format!("🎉 SUCCÈS ! Defender réactivé ({}/5 opérations réussies)", success_count)
-        } else {
-            format!("⚠️ Réactivation partielle ({}/5 opérations réussies)", success_count)
-        };
+        let span = tracing::info_span!("defender_action", action = ?DefenderAction::Enable);
+        let _entered = span.enter();
+        let mut results = run_steps(Self::_enable_plan(), progress);
+        let real_time_protection = Self::_verify(DefenderAction::Enable, std::time::Duration::from_millis(1500), progress, &mut results);
+        let summary = DefenderActionSummary::from_results(DefenderAction::Enable, &results, real_time_protection);
+        tracing::info!("{}", summary.message());
+        Ok(summary)
+    }
 
-        results.push(summary);
-        Ok(results)
+    /// Enable Defender immediately
+    pub fn enable_defender_immediately() -> Result<Vec<String>> {
+        collect_messages("🔄 Réactivation immédiate de Defender...", Self::enable_defender_with_progress)
     }
 
     /// Start a service immediately
@@ -538,26 +549,19 @@ impl DefenderManager {
 
     /// Enable Defender via PowerShell
     fn _enable_via_powershell() -> Result<()> {
-        use std::process::Command;
-
-        let commands = vec![
-            "Set-MpPreference -DisableRealtimeMonitoring $false",
-            "Set-MpPreference -DisableIOAVProtection $false",
-            "Set-MpPreference -DisableBehaviorMonitoring $false",
-            "Set-MpPreference -DisableBlockAtFirstSeen $false",
-        ];
-
-        for cmd in commands {
-            let mut command_obj = Command::new("powershell");
-            command_obj.args(&["-Command", cmd]);
-
-            #[cfg(target_os = "windows")]
-            command_obj.creation_flags(0x08000000);
-            
-            let _ = command_obj.output();
-        }
+        Self::_enable_via_powershell_with(system::default_runner().as_ref())
+    }
 
-        Ok(())
+    fn _enable_via_powershell_with(runner: &dyn CommandRunner) -> Result<()> {
+        Self::_run_mp_preferences(
+            runner,
+            &[
+                "Set-MpPreference -DisableRealtimeMonitoring $false",
+                "Set-MpPreference -DisableIOAVProtection $false",
+                "Set-MpPreference -DisableBehaviorMonitoring $false",
+                "Set-MpPreference -DisableBlockAtFirstSeen $false",
+            ],
+        )
     }
 
     /// Attempt to disable Windows Defender real-time protection via registry
@@ -801,56 +805,10 @@ pause
     /// Disable a specific service via registry
     fn _disable_service_via_registry(service_name: &str) -> Result<()> {
         let registry_path = format!("SYSTEM\\CurrentControlSet\\Services\\{}", service_name);
-        
-        unsafe {
-            let mut key: HKEY = std::ptr::null_mut();
-            let path_cstr = CString::new(registry_path).map_err(|e| anyhow!(e))?;
-
-            let result = RegCreateKeyExA(
-                HKEY_LOCAL_MACHINE,
-                path_cstr.as_ptr() as *const u8,
-                0,
-                std::ptr::null_mut(),
-                REG_OPTION_NON_VOLATILE,
-                KEY_SET_VALUE | KEY_WOW64_64KEY,
-                std::ptr::null_mut(),
-                &mut key,
-                std::ptr::null_mut(),
-            );
 
-            if result != ERROR_SUCCESS {
-                return Err(anyhow!(
-                    "Failed to open service registry key for {}. Error: {}",
-                    service_name,
-                    result
-                ));
-            }
-
-            // Set Start to 4 (SERVICE_DISABLED)
-            let value_name_cstr = CString::new("Start").map_err(|e| anyhow!(e))?;
-            let value_dword: u32 = 4;
-
-            let set_result = RegSetValueExA(
-                key,
-                value_name_cstr.as_ptr() as *const u8,
-                0,
-                REG_DWORD,
-                &value_dword as *const u32 as *const u8,
-                std::mem::size_of::<u32>() as u32,
-            );
-
-            RegCloseKey(key);
-
-            if set_result == ERROR_SUCCESS {
-                Ok(())
-            } else {
-                Err(anyhow!(
-                    "Failed to disable service {}. Error: {}",
-                    service_name,
-                    set_result
-                ))
-            }
-        }
+        // Start = 4 (SERVICE_DISABLED)
+        registry::write_dword(HKEY_LOCAL_MACHINE, &registry_path, "Start", 4)
+            .map_err(|e| anyhow!("Failed to disable service {}: {}", service_name, e))
     }
 
     /// Try to disable Tamper Protection
@@ -927,238 +885,95 @@ pause
 
     /// Set a DWORD value in the Defender Policy registry key
     fn _set_defender_policy(value_name: &str, value: u32) -> Result<()> {
-        unsafe {
-            let mut key: HKEY = std::ptr::null_mut();
-            let registry_path =
-                CString::new(Self::POLICY_REGISTRY_PATH).map_err(|e| anyhow!(e))?;
-
-            let result = RegCreateKeyExA(
-                HKEY_LOCAL_MACHINE,
-                registry_path.as_ptr() as *const u8,
-                0,
-                std::ptr::null_mut(),
-                REG_OPTION_NON_VOLATILE,
-                KEY_SET_VALUE | KEY_WOW64_64KEY,
-                std::ptr::null_mut(),
-                &mut key,
-                std::ptr::null_mut(),
-            );
-
-            if result != ERROR_SUCCESS {
-                return Err(anyhow!(
-                    "Failed to create/open registry policy key. Error: {}",
-                    result
-                ));
-            }
-
-            let value_name_cstr = CString::new(value_name).map_err(|e| anyhow!(e))?;
-            let value_dword: u32 = value;
-
-            let set_result = RegSetValueExA(
-                key,
-                value_name_cstr.as_ptr() as *const u8,
-                0,
-                REG_DWORD,
-                &value_dword as *const u32 as *const u8,
-                std::mem::size_of::<u32>() as u32,
-            );
-
-            RegCloseKey(key);
-
-            if set_result == ERROR_SUCCESS {
-                Ok(())
-            } else {
-                Err(anyhow!(
-                    "Failed to set registry policy '{}'. Error: {}. Tamper protection may be on.",
-                    value_name,
-                    set_result
-                ))
-            }
-        }
+        registry::write_dword(HKEY_LOCAL_MACHINE, Self::POLICY_REGISTRY_PATH, value_name, value)
+            .with_context(|| format!("Failed to set registry policy '{}'. Tamper protection may be on.", value_name))
     }
 
     /// Delete a value from the Defender Policy registry key
     fn _delete_defender_policy(value_name: &str) -> Result<bool> {
-        unsafe {
-            let mut key: HKEY = std::ptr::null_mut();
-            let registry_path =
-                CString::new(Self::POLICY_REGISTRY_PATH).map_err(|e| anyhow!(e))?;
-
-            let result = RegOpenKeyExA(
-                HKEY_LOCAL_MACHINE,
-                registry_path.as_ptr() as *const u8,
-                0,
-                KEY_SET_VALUE | KEY_WOW64_64KEY,
-                &mut key,
-            );
-
-            if result != ERROR_SUCCESS {
-                // If the key doesn't exist, the policy isn't active, so we're good.
-                return Ok(true);
-            }
-
-            let value_name_cstr = CString::new(value_name).map_err(|e| anyhow!(e))?;
-            let delete_result =
-                RegDeleteValueA(key, value_name_cstr.as_ptr() as *const u8);
-
-            RegCloseKey(key);
-
-            if delete_result == ERROR_SUCCESS
-                || delete_result == ERROR_FILE_NOT_FOUND
-            {
-                Ok(true)
-            } else {
-                Err(anyhow!(
-                    "Failed to delete registry policy '{}'. Error: {}. Tamper protection may be on.",
-                    value_name,
-                    delete_result
-                ))
-            }
-        }
+        // If the key doesn't exist, the policy isn't active, so we're good.
+        registry::delete_value(HKEY_LOCAL_MACHINE, Self::POLICY_REGISTRY_PATH, value_name)
+            .map(|_| true)
+            .with_context(|| format!("Failed to delete registry policy '{}'. Tamper protection may be on.", value_name))
     }
     
     /// Set a DWORD value in the Defender Features registry key
     fn _set_features_setting(value_name: &str, value: u32) -> Result<()> {
-        unsafe {
-            let mut key: HKEY = std::ptr::null_mut();
-            let registry_path =
-                CString::new(Self::FEATURES_REGISTRY_PATH).map_err(|e| anyhow!(e))?;
-
-            let result = RegCreateKeyExA(
-                HKEY_LOCAL_MACHINE,
-                registry_path.as_ptr() as *const u8,
-                0,
-                std::ptr::null_mut(),
-                REG_OPTION_NON_VOLATILE,
-                KEY_SET_VALUE | KEY_WOW64_64KEY,
-                std::ptr::null_mut(),
-                &mut key,
-                std::ptr::null_mut(),
-            );
-
-            if result != ERROR_SUCCESS {
-                return Err(anyhow!(
-                    "Failed to create/open Features registry key. Error: {}",
-                    result
-                ));
-            }
-
-            let value_name_cstr = CString::new(value_name).map_err(|e| anyhow!(e))?;
-            let value_dword: u32 = value;
-
-            let set_result = RegSetValueExA(
-                key,
-                value_name_cstr.as_ptr() as *const u8,
-                0,
-                REG_DWORD,
-                &value_dword as *const u32 as *const u8,
-                std::mem::size_of::<u32>() as u32,
-            );
-
-            RegCloseKey(key);
-
-            if set_result == ERROR_SUCCESS {
-                Ok(())
-            } else {
-                Err(anyhow!(
-                    "Failed to set Features setting '{}'. Error: {}",
-                    value_name,
-                    set_result
-                ))
-            }
-        }
+        registry::write_dword(HKEY_LOCAL_MACHINE, Self::FEATURES_REGISTRY_PATH, value_name, value)
+            .with_context(|| format!("Failed to set Features setting '{}'", value_name))
     }
 
     /// Set a DWORD value in the main Defender registry key
     fn _set_defender_main_setting(value_name: &str, value: u32) -> Result<()> {
-        unsafe {
-            let mut key: HKEY = std::ptr::null_mut();
-            let registry_path =
-                CString::new(Self::DEFENDER_REGISTRY_PATH).map_err(|e| anyhow!(e))?;
-
-            let result = RegCreateKeyExA(
-                HKEY_LOCAL_MACHINE,
-                registry_path.as_ptr() as *const u8,
-                0,
-                std::ptr::null_mut(),
-                REG_OPTION_NON_VOLATILE,
-                KEY_SET_VALUE | KEY_WOW64_64KEY,
-                std::ptr::null_mut(),
-                &mut key,
-                std::ptr::null_mut(),
-            );
-
-            if result != ERROR_SUCCESS {
-                return Err(anyhow!(
-                    "Failed to create/open main Defender registry key. Error: {}",
-                    result
-                ));
-            }
-
-            let value_name_cstr = CString::new(value_name).map_err(|e| anyhow!(e))?;
-            let value_dword: u32 = value;
-
-            let set_result = RegSetValueExA(
-                key,
-                value_name_cstr.as_ptr() as *const u8,
-                0,
-                REG_DWORD,
-                &value_dword as *const u32 as *const u8,
-                std::mem::size_of::<u32>() as u32,
-            );
+        registry::write_dword(HKEY_LOCAL_MACHINE, Self::DEFENDER_REGISTRY_PATH, value_name, value)
+            .with_context(|| format!("Failed to set main Defender setting '{}'", value_name))
+    }
 
-            RegCloseKey(key);
+    /// Delete a value from the main Defender registry key
+    fn _delete_defender_main_setting(value_name: &str) -> Result<bool> {
+        // If the key doesn't exist, the setting isn't active, so we're good.
+        registry::delete_value(HKEY_LOCAL_MACHINE, Self::DEFENDER_REGISTRY_PATH, value_name)
+            .map(|_| true)
+            .with_context(|| format!("Failed to delete main Defender setting '{}'", value_name))
+    }
 
-            if set_result == ERROR_SUCCESS {
+    /// Add a folder to the Defender path exclusions.
+    /// `Add-MpPreference` first; direct registry write only when elevated with Tamper Protection off.
+    pub fn add_exclusion_path(path: &Path) -> Result<()> {
+        let script = format!("Add-MpPreference -ExclusionPath {} -ErrorAction Stop", Self::_powershell_quote(path));
+        match run_powershell_command_blocking(&script) {
+            Ok(_) => {
+                tracing::info!("🛡️ Exclusion Defender ajoutée: {}", path.display());
                 Ok(())
-            } else {
-                Err(anyhow!(
-                    "Failed to set main Defender setting '{}'. Error: {}",
-                    value_name,
-                    set_result
-                ))
             }
+            Err(ps_error) if Self::_can_write_exclusions_registry() => {
+                tracing::warn!("⚠️ Add-MpPreference a échoué, écriture registre: {}", ps_error);
+                registry::write_dword(HKEY_LOCAL_MACHINE, Self::EXCLUSION_PATHS_REGISTRY_PATH, &path.to_string_lossy(), 0)
+                    .map_err(|e| anyhow!("{}\n{}", ps_error, e))
+            }
+            Err(ps_error) => Err(anyhow!(ps_error)),
         }
     }
 
-    /// Delete a value from the main Defender registry key
-    fn _delete_defender_main_setting(value_name: &str) -> Result<bool> {
-        unsafe {
-            let mut key: HKEY = std::ptr::null_mut();
-            let registry_path =
-                CString::new(Self::DEFENDER_REGISTRY_PATH).map_err(|e| anyhow!(e))?;
-
-            let result = RegOpenKeyExA(
-                HKEY_LOCAL_MACHINE,
-                registry_path.as_ptr() as *const u8,
-                0,
-                KEY_SET_VALUE | KEY_WOW64_64KEY,
-                &mut key,
-            );
-
-            if result != ERROR_SUCCESS {
-                // If the key doesn't exist, the setting isn't active, so we're good.
-                return Ok(true);
+    /// Remove a folder from the Defender path exclusions
+    pub fn remove_exclusion_path(path: &Path) -> Result<()> {
+        let script = format!("Remove-MpPreference -ExclusionPath {} -ErrorAction Stop", Self::_powershell_quote(path));
+        match run_powershell_command_blocking(&script) {
+            Ok(_) => {
+                tracing::info!("🛡️ Exclusion Defender supprimée: {}", path.display());
+                Ok(())
             }
+            Err(ps_error) if Self::_can_write_exclusions_registry() => {
+                tracing::warn!("⚠️ Remove-MpPreference a échoué, suppression registre: {}", ps_error);
+                registry::delete_value(HKEY_LOCAL_MACHINE, Self::EXCLUSION_PATHS_REGISTRY_PATH, &path.to_string_lossy())
+                    .map_err(|e| anyhow!("{}\n{}", ps_error, e))
+            }
+            Err(ps_error) => Err(anyhow!(ps_error)),
+        }
+    }
 
-            let value_name_cstr = CString::new(value_name).map_err(|e| anyhow!(e))?;
-            let delete_result =
-                RegDeleteValueA(key, value_name_cstr.as_ptr() as *const u8);
+    /// Current Defender path exclusions.
+    /// Without admin rights `Get-MpPreference` hides them ("N/A: Must be an administrator..."),
+    /// the registry is read instead.
+    pub fn list_exclusions() -> Result<Vec<PathBuf>> {
+        let output = run_powershell_command_blocking("Get-MpPreference | Select-Object -ExpandProperty ExclusionPath")
+            .map_err(|e| anyhow!(e))?;
+        let lines: Vec<&str> = output.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+
+        if lines.iter().any(|l| l.starts_with("N/A")) {
+            return registry::list_value_names(HKEY_LOCAL_MACHINE, Self::EXCLUSION_PATHS_REGISTRY_PATH)
+                .map(|names| names.into_iter().map(PathBuf::from).collect());
+        }
+        Ok(lines.into_iter().map(PathBuf::from).collect())
+    }
 
-            RegCloseKey(key);
+    fn _can_write_exclusions_registry() -> bool {
+        utils::is_elevated() && !Self::check_defender_status().map(|s| s.tamper_protection).unwrap_or(true)
+    }
 
-            if delete_result == ERROR_SUCCESS
-                || delete_result == ERROR_FILE_NOT_FOUND
-            {
-                Ok(true)
-            } else {
-                Err(anyhow!(
-                    "Failed to delete main Defender setting '{}'. Error: {}",
-                    value_name,
-                    delete_result
-                ))
-            }
-        }
+    /// Single-quoted PowerShell literal
+    fn _powershell_quote(path: &Path) -> String {
+        format!("'{}'", path.to_string_lossy().replace('\'', "''"))
     }
 
     /// Get a safe status check that doesn't require admin privileges
@@ -1186,6 +1001,22 @@ impl DefenderManager {
         })
     }
 
+    pub fn disable_steps() -> Vec<DefenderStep> {
+        Vec::new()
+    }
+
+    pub fn enable_steps() -> Vec<DefenderStep> {
+        Vec::new()
+    }
+
+    pub fn disable_defender_with_progress(_progress: &Sender<StepResult>) -> Result<DefenderActionSummary> {
+        Err(anyhow!("Fonctionnalité non disponible sur Linux"))
+    }
+
+    pub fn enable_defender_with_progress(_progress: &Sender<StepResult>) -> Result<DefenderActionSummary> {
+        Err(anyhow!("Fonctionnalité non disponible sur Linux"))
+    }
+
     pub fn disable_defender_immediately() -> Result<Vec<String>> {
         Ok(vec!["Fonctionnalité non disponible sur Linux".to_string()])
     }
@@ -1193,4 +1024,110 @@ impl DefenderManager {
     pub fn enable_defender_immediately() -> Result<Vec<String>> {
         Ok(vec!["Fonctionnalité non disponible sur Linux".to_string()])
     }
+
+    pub fn add_exclusion_path(_path: &Path) -> Result<()> {
+        Err(anyhow!("Fonctionnalité non disponible sur Linux"))
+    }
+
+    pub fn remove_exclusion_path(_path: &Path) -> Result<()> {
+        Err(anyhow!("Fonctionnalité non disponible sur Linux"))
+    }
+
+    pub fn list_exclusions() -> Result<Vec<PathBuf>> {
+        Ok(Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(kind: DefenderStepKind, name: &str, outcome: std::result::Result<(), &str>, registry_code: Option<u32>) -> StepResult {
+        StepResult {
+            step: DefenderStep::new(kind, name),
+            outcome: outcome.map_err(str::to_string),
+            registry_code,
+        }
+    }
+
+    fn verification(ok: bool) -> StepResult {
+        let outcome = if ok { Ok(()) } else { Err("protection temps réel toujours active") };
+        result(DefenderStepKind::Verification, "Vérification", outcome, None)
+    }
+
+    #[test]
+    fn summary_counts_operations_without_the_verification() {
+        let results = vec![
+            result(DefenderStepKind::Service, "Service WinDefend", Ok(()), None),
+            result(DefenderStepKind::PowerShell, "PowerShell Set-MpPreference", Err("accès refusé"), None),
+            result(DefenderStepKind::Registry, "Registre Policy DisableRealtimeMonitoring", Ok(()), None),
+            verification(false),
+        ];
+        let summary = DefenderActionSummary::from_results(DefenderAction::Disable, &results, Some(true));
+        assert_eq!((summary.succeeded, summary.total), (2, 3));
+        assert!(!summary.tamper_blocked);
+        // Des étapes réussies mais la protection toujours active : résultat partiel
+        assert_eq!(summary.outcome(), ActionOutcome::Partial);
+        assert!(summary.message().starts_with("⚠️ Désactivation partielle (2/3"));
+    }
+
+    #[test]
+    fn the_re_check_decides_the_outcome() {
+        let failed = vec![
+            result(DefenderStepKind::Service, "Service WinDefend", Err("accès refusé"), None),
+            result(DefenderStepKind::Registry, "Registre Policy DisableRealtimeMonitoring", Err("accès refusé"), Some(5)),
+        ];
+        // Toutes les étapes échouent mais la protection est coupée : succès
+        let disabled = DefenderActionSummary::from_results(DefenderAction::Disable, &failed, Some(false));
+        assert_eq!(disabled.outcome(), ActionOutcome::Success);
+        assert_eq!(disabled.message(), "🎉 SUCCÈS ! Defender désactivé (0/2 méthodes réussies)");
+
+        let unreadable = DefenderActionSummary::from_results(DefenderAction::Disable, &failed, None);
+        assert_eq!(unreadable.outcome(), ActionOutcome::Failed);
+        assert!(unreadable.message().starts_with("❌ ÉCHEC"));
+
+        let all_ok = vec![result(DefenderStepKind::Service, "Service WinDefend", Ok(()), None), verification(true)];
+        let enabled = DefenderActionSummary::from_results(DefenderAction::Enable, &all_ok, Some(true));
+        assert_eq!(enabled.outcome(), ActionOutcome::Success);
+        assert_eq!(enabled.message(), "🎉 SUCCÈS ! Defender réactivé (1/1 opérations réussies)");
+        let still_off = DefenderActionSummary::from_results(DefenderAction::Enable, &all_ok, Some(false));
+        assert_eq!(still_off.message(), "⚠️ Réactivation partielle (1/1 opérations réussies)");
+    }
+
+    #[test]
+    fn access_denied_registry_writes_flag_tamper_protection() {
+        let denied = result(DefenderStepKind::Registry, "Registre Policy DisableAntiSpyware", Err("accès refusé"), Some(5));
+        let missing = result(DefenderStepKind::Registry, "Registre Features TamperProtection", Err("introuvable"), Some(2));
+        assert!(denied.refused_by_tamper_protection());
+        assert!(!missing.refused_by_tamper_protection());
+        assert_eq!(denied.message(), "❌ Registre Policy DisableAntiSpyware : accès refusé");
+
+        let summary = DefenderActionSummary::from_results(DefenderAction::Disable, &[missing, denied], Some(true));
+        assert!(summary.tamper_blocked);
+        assert_eq!(summary.outcome(), ActionOutcome::Failed);
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn steps_run_in_order_and_are_streamed_as_they_finish() {
+        let steps: Vec<(DefenderStep, Box<dyn Fn() -> Result<()>>)> = vec![
+            (DefenderStep::new(DefenderStepKind::Service, "Service WinDefend"), Box::new(|| Ok(()))),
+            (DefenderStep::new(DefenderStepKind::PowerShell, "PowerShell"), Box::new(|| Err(anyhow!("accès refusé")))),
+            (DefenderStep::new(DefenderStepKind::Registry, "Registre"), Box::new(|| Ok(()))),
+        ];
+        let (sender, receiver) = mpsc::channel();
+        let results = run_steps(steps, &sender);
+        drop(sender);
+
+        let streamed: Vec<String> = receiver.iter().map(|result| result.message()).collect();
+        assert_eq!(streamed, vec!["✅ Service WinDefend", "❌ PowerShell : accès refusé", "✅ Registre"]);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[1].registry_code, None);
+
+        // Personne n'écoute : l'action va quand même au bout
+        let (closed, _) = mpsc::channel();
+        let steps: Vec<(DefenderStep, Box<dyn Fn() -> Result<()>>)> =
+            vec![(DefenderStep::new(DefenderStepKind::Service, "Service WinDefend"), Box::new(|| Ok(())))];
+        assert_eq!(run_steps(steps, &closed).len(), 1);
+    }
 }
@@ -1,31 +1,37 @@
 // Windows Service Manager using WinAPI directly
 // Manages Windows services without spawning PowerShell processes
 
-use anyhow::{Result};
+use anyhow::{anyhow, Result};
 
 #[cfg(target_os = "windows")]
 use std::ffi::CString;
 #[cfg(target_os = "windows")]
 use std::ptr;
 #[cfg(target_os = "windows")]
+use windows_sys::Win32::Foundation::{GetLastError, ERROR_INSUFFICIENT_BUFFER, ERROR_MORE_DATA};
+#[cfg(target_os = "windows")]
 use windows_sys::Win32::System::Services::{
-    CloseServiceHandle, OpenSCManagerA, OpenServiceA, QueryServiceStatus,
-    SC_MANAGER_ALL_ACCESS, SERVICE_QUERY_STATUS, SERVICE_STATUS, SERVICE_STOPPED,
+    CloseServiceHandle, EnumDependentServicesW, OpenSCManagerA, OpenServiceA, QueryServiceConfigW, QueryServiceStatus,
+    ENUM_SERVICE_STATUSW, QUERY_SERVICE_CONFIGW, SC_MANAGER_ALL_ACCESS, SC_MANAGER_CONNECT, SERVICE_ENUMERATE_DEPENDENTS,
+    SERVICE_QUERY_CONFIG, SERVICE_QUERY_STATUS, SERVICE_STATE_ALL, SERVICE_STATUS, SERVICE_STOPPED,
     SERVICE_START_PENDING, SERVICE_STOP_PENDING, SERVICE_RUNNING, SERVICE_CONTINUE_PENDING,
     SERVICE_PAUSE_PENDING, SERVICE_PAUSED, SC_HANDLE,
 };
 
+/// Dependents listed at most: the optimization refuses to stop a service needed by more
+pub const MAX_DEPENDENTS: usize = 32;
+
 pub struct ServiceManager;
 
 #[cfg(target_os = "windows")]
 impl ServiceManager {
     /// Open service control manager with appropriate permissions
-    fn open_scm() -> Result<SC_HANDLE> {
+    fn open_scm(access: u32) -> Result<SC_HANDLE> {
         unsafe {
             let scm_handle = OpenSCManagerA(
                 ptr::null(),
                 ptr::null(),
-                SC_MANAGER_ALL_ACCESS,
+                access,
             );
             if scm_handle == std::ptr::null_mut() {
                 Err(anyhow!("Could not open SCM"))
@@ -54,7 +60,7 @@ impl ServiceManager {
 
     /// Get service status
     pub fn get_service_status(service_name: &str) -> Result<String> {
-        let scm_handle = Self::open_scm()?;
+        let scm_handle = Self::open_scm(SC_MANAGER_ALL_ACCESS)?;
         
         let service_handle = match Self::open_service(scm_handle, service_name, SERVICE_QUERY_STATUS) {
             Ok(handle) => handle,
@@ -111,4 +117,102 @@ impl ServiceManager {
     pub fn is_service_running(_service_name: &str) -> Result<bool> {
         Ok(false) // Placeholder for non-Windows
     }
+
+    /// Run `query` on `service_name` opened with `access`, read-only access to the SCM
+    fn with_service<T>(service_name: &str, access: u32, query: impl FnOnce(SC_HANDLE) -> Result<T>) -> Result<T> {
+        let scm_handle = Self::open_scm(SC_MANAGER_CONNECT)?;
+        let result = Self::open_service(scm_handle, service_name, access).and_then(|service_handle| {
+            let result = query(service_handle);
+            unsafe { CloseServiceHandle(service_handle) };
+            result
+        });
+        unsafe { CloseServiceHandle(scm_handle) };
+        result
+    }
+
+    /// Services that need `service_name`, directly or through another service, in the order
+    /// they have to be stopped (the SCM lists them in reverse start order). At most
+    /// `MAX_DEPENDENTS + 1` names, so that the caller can tell the list was cut.
+    pub fn get_service_dependents(service_name: &str) -> Vec<String> {
+        let result = Self::with_service(service_name, SERVICE_ENUMERATE_DEPENDENTS, |service_handle| unsafe {
+            let (mut needed, mut count) = (0u32, 0u32);
+            // Premier appel sans tampon : réussit s'il n'y a aucun dépendant, sinon donne la taille
+            if EnumDependentServicesW(service_handle, SERVICE_STATE_ALL, ptr::null_mut(), 0, &mut needed, &mut count) != 0 {
+                return Ok(Vec::new());
+            }
+            if GetLastError() != ERROR_MORE_DATA {
+                return Err(anyhow!("EnumDependentServicesW: {}", std::io::Error::last_os_error()));
+            }
+            let mut buffer = vec![0u64; needed as usize / 8 + 1];
+            let entries = buffer.as_mut_ptr() as *mut ENUM_SERVICE_STATUSW;
+            if EnumDependentServicesW(service_handle, SERVICE_STATE_ALL, entries, needed, &mut needed, &mut count) == 0 {
+                return Err(anyhow!("EnumDependentServicesW: {}", std::io::Error::last_os_error()));
+            }
+            Ok(std::slice::from_raw_parts(entries, count as usize)
+                .iter()
+                .take(MAX_DEPENDENTS + 1)
+                .map(|entry| wide_to_string(entry.lpServiceName))
+                .collect())
+        });
+        result.unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Services dépendants de {} illisibles: {}", service_name, e);
+            Vec::new()
+        })
+    }
+
+    /// Services (and "groupe X" load order groups) `service_name` needs to run
+    pub fn get_service_dependencies(service_name: &str) -> Vec<String> {
+        let result = Self::with_service(service_name, SERVICE_QUERY_CONFIG, |service_handle| unsafe {
+            let mut needed = 0u32;
+            QueryServiceConfigW(service_handle, ptr::null_mut(), 0, &mut needed);
+            if GetLastError() != ERROR_INSUFFICIENT_BUFFER {
+                return Err(anyhow!("QueryServiceConfigW: {}", std::io::Error::last_os_error()));
+            }
+            let mut buffer = vec![0u64; needed as usize / 8 + 1];
+            let config = buffer.as_mut_ptr() as *mut QUERY_SERVICE_CONFIGW;
+            if QueryServiceConfigW(service_handle, config, needed, &mut needed) == 0 {
+                return Err(anyhow!("QueryServiceConfigW: {}", std::io::Error::last_os_error()));
+            }
+            // Liste de chaînes terminée par une chaîne vide ; '+' préfixe un groupe
+            let mut dependencies = Vec::new();
+            let mut cursor = (*config).lpDependencies as *const u16;
+            while !cursor.is_null() && *cursor != 0 {
+                let name = wide_to_string(cursor);
+                cursor = cursor.add(name.encode_utf16().count() + 1);
+                dependencies.push(match name.strip_prefix('+') {
+                    Some(group) => format!("groupe {}", group),
+                    None => name,
+                });
+            }
+            Ok(dependencies)
+        });
+        result.unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Dépendances de {} illisibles: {}", service_name, e);
+            Vec::new()
+        })
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn wide_to_string(ptr: *const u16) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    let len = (0..).take_while(|&i| unsafe { *ptr.add(i) } != 0).count();
+    String::from_utf16_lossy(unsafe { std::slice::from_raw_parts(ptr, len) })
+}
+
+#[cfg(not(target_os = "windows"))]
+impl ServiceManager {
+    pub fn get_service_status(_service_name: &str) -> Result<String> {
+        Err(anyhow!("Services Windows indisponibles sur cette plateforme"))
+    }
+
+    pub fn get_service_dependents(_service_name: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    pub fn get_service_dependencies(_service_name: &str) -> Vec<String> {
+        Vec::new()
+    }
 }
@@ -0,0 +1,340 @@
+//! # Gaming sessions
+//!
+//! A session lasts while the gaming mode or a boost profile is active. It adds up the RAM
+//! freed by the cleanings made meanwhile, the services stopped and the upload of the limited
+//! applications, then ends as a `SessionSummary` appended to `history/sessions.jsonl` (last
+//! `MAX_SESSIONS` kept).
+//!
+//! The open session is written to `history/session_open.json` before it starts and after
+//! each change, always through a temporary file renamed over the previous one: a crash leaves
+//! either the old or the new marker, never a truncated one. At the next startup the marker
+//! resumes the session when its profile is still applied, otherwise it is finalized as
+//! interrupted.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::history::HISTORY_DIR;
+use crate::network::NetworkProcessInfo;
+
+const SESSIONS_FILE: &str = "sessions.jsonl";
+const OPEN_SESSION_FILE: &str = "session_open.json";
+pub const MAX_SESSIONS: usize = 50;
+/// Samples further apart (network tab closed, app suspended) are not counted
+const MAX_SAMPLE_GAP_SECS: f64 = 15.0;
+/// Below this, the upload measured before the limit is too short to be a baseline
+const MIN_BASELINE_SECS: f64 = 10.0;
+/// The network samples only rewrite the marker this often
+const MARKER_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Upload of one application during the session, split by whether it was limited
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct UploadUsage {
+    /// Speed at the start of the session, before the limits of the profile
+    initial_bps: Option<u64>,
+    unlimited_bytes: f64,
+    unlimited_secs: f64,
+    limited_bytes: f64,
+    limited_secs: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameSession {
+    /// Profile name, or "Mode jeu"
+    pub name: String,
+    /// Game started by a watched launcher during the session
+    pub game: Option<String>,
+    /// `AppliedProfile::applied_at` of the profile that opened the session
+    pub profile_applied_at: Option<DateTime<Local>>,
+    pub started_at: DateTime<Local>,
+    /// Last write of the marker, end of an interrupted session
+    pub updated_at: DateTime<Local>,
+    pub ram_freed: u64,
+    pub cleanings: u32,
+    pub stopped_services: Vec<String>,
+    uploads: BTreeMap<String, UploadUsage>,
+    #[serde(skip)]
+    last_sample: Option<Instant>,
+    #[serde(skip)]
+    marker_written: Option<Instant>,
+}
+
+/// Upload of an application limited during the session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimitedApp {
+    pub name: String,
+    /// Average upload without limit, bytes per second
+    pub baseline_bps: u64,
+    /// Average upload while limited
+    pub limited_bps: u64,
+    /// Estimated: baseline minus limited upload, over the time spent limited
+    pub saved_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub name: String,
+    pub started_at: DateTime<Local>,
+    pub ended_at: DateTime<Local>,
+    pub ram_freed: u64,
+    pub cleanings: u32,
+    pub stopped_services: Vec<String>,
+    pub limited_apps: Vec<LimitedApp>,
+    pub upload_saved: u64,
+    /// Closed by a crash or a forced shutdown, finalized at the next startup
+    #[serde(default)]
+    pub interrupted: bool,
+}
+
+impl GameSession {
+    pub fn start(name: &str, profile_applied_at: Option<DateTime<Local>>) -> Self {
+        let now = Local::now();
+        tracing::info!("🎮 Session de jeu ouverte : {}", name);
+        Self {
+            name: name.to_string(),
+            game: None,
+            profile_applied_at,
+            started_at: now,
+            updated_at: now,
+            ram_freed: 0,
+            cleanings: 0,
+            stopped_services: Vec::new(),
+            uploads: BTreeMap::new(),
+            last_sample: None,
+            marker_written: None,
+        }
+    }
+
+    pub fn record_cleaning(&mut self, bytes_freed: u64) {
+        self.ram_freed += bytes_freed;
+        self.cleanings += 1;
+        self.save_marker(true);
+    }
+
+    pub fn record_services(&mut self, names: &[String]) {
+        for name in names {
+            if !self.stopped_services.contains(name) {
+                self.stopped_services.push(name.clone());
+            }
+        }
+        self.save_marker(true);
+    }
+
+    pub fn record_game(&mut self, game: &str) {
+        if self.game.as_deref() == Some(game) {
+            return;
+        }
+        self.game = Some(game.to_string());
+        self.save_marker(true);
+    }
+
+    /// Upload speeds of the last scan, made before the limits of the profile: the baseline of
+    /// the applications limited from the start
+    pub fn seed_network(&mut self, processes: &[&NetworkProcessInfo]) {
+        for process in processes {
+            let usage = self.uploads.entry(process.name.clone()).or_default();
+            usage.initial_bps = Some(usage.initial_bps.unwrap_or(0) + process.current_upload_speed);
+        }
+    }
+
+    /// Add the upload of each application since the previous network scan
+    pub fn record_network(&mut self, processes: &[&NetworkProcessInfo]) {
+        let now = Instant::now();
+        let elapsed = self.last_sample.map(|at| now.duration_since(at).as_secs_f64());
+        self.last_sample = Some(now);
+        let Some(secs) = elapsed.filter(|secs| *secs <= MAX_SAMPLE_GAP_SECS) else {
+            return;
+        };
+
+        // Processus d'une même application additionnés : une durée par relevé
+        let mut by_name: BTreeMap<&str, (u64, bool)> = BTreeMap::new();
+        for process in processes {
+            let (speed, limited) = by_name.entry(process.name.as_str()).or_default();
+            *speed += process.current_upload_speed;
+            *limited |= process.is_limited;
+        }
+        for (name, (speed, limited)) in by_name {
+            let usage = self.uploads.entry(name.to_string()).or_default();
+            let bytes = speed as f64 * secs;
+            if limited {
+                usage.limited_bytes += bytes;
+                usage.limited_secs += secs;
+            } else {
+                usage.unlimited_bytes += bytes;
+                usage.unlimited_secs += secs;
+            }
+        }
+        self.save_marker(false);
+    }
+
+    /// "Elden Ring", or the profile name when no game was seen
+    pub fn title(&self) -> &str {
+        self.game.as_deref().unwrap_or(&self.name)
+    }
+
+    pub fn duration(&self) -> Duration {
+        Local::now() - self.started_at
+    }
+
+    /// End the session; `interrupted` ends it at the last write of its marker
+    pub fn close(self, interrupted: bool) -> SessionSummary {
+        let limited_apps: Vec<LimitedApp> = self
+            .uploads
+            .iter()
+            .filter(|(_, usage)| usage.limited_secs > 0.0)
+            .filter_map(|(name, usage)| {
+                let baseline_bps = if usage.unlimited_secs >= MIN_BASELINE_SECS {
+                    (usage.unlimited_bytes / usage.unlimited_secs) as u64
+                } else {
+                    usage.initial_bps?
+                };
+                let limited_bps = (usage.limited_bytes / usage.limited_secs) as u64;
+                let saved_bytes = (baseline_bps.saturating_sub(limited_bps) as f64 * usage.limited_secs) as u64;
+                Some(LimitedApp { name: name.clone(), baseline_bps, limited_bps, saved_bytes })
+            })
+            .collect();
+
+        let summary = SessionSummary {
+            name: self.title().to_string(),
+            started_at: self.started_at,
+            ended_at: if interrupted { self.updated_at } else { Local::now() },
+            ram_freed: self.ram_freed,
+            cleanings: self.cleanings,
+            stopped_services: self.stopped_services,
+            upload_saved: limited_apps.iter().map(|app| app.saved_bytes).sum(),
+            limited_apps,
+            interrupted,
+        };
+        tracing::info!("🎮 {}", summary.headline());
+        summary
+    }
+
+    /// Write the marker now with `force`, otherwise at most every `MARKER_INTERVAL`
+    pub fn save_marker(&mut self, force: bool) {
+        if !force && self.marker_written.is_some_and(|at| at.elapsed() < MARKER_INTERVAL) {
+            return;
+        }
+        self.updated_at = Local::now();
+        self.marker_written = Some(Instant::now());
+        let written = serde_json::to_string(self)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| write_atomic(&marker_path(), &content));
+        if let Err(e) = written {
+            tracing::warn!("⚠️ Session de jeu non enregistrée: {}", e);
+        }
+    }
+}
+
+impl SessionSummary {
+    pub fn duration(&self) -> Duration {
+        self.ended_at - self.started_at
+    }
+
+    /// "Session Elden Ring — 2 h 14, 3.1 Go RAM libérés, 420 Mo d'upload économisés"
+    pub fn headline(&self) -> String {
+        let mut parts = vec![format!("Session {} — {}", self.name, format_duration(self.duration()))];
+        if self.ram_freed > 0 {
+            parts.push(format!("{} RAM libérés", format_bytes(self.ram_freed)));
+        }
+        if self.upload_saved > 0 {
+            parts.push(format!("{} d'upload économisés", format_bytes(self.upload_saved)));
+        }
+        parts.join(", ")
+    }
+}
+
+/// "2 h 14", "35 min"
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    if minutes >= 60 {
+        format!("{} h {:02}", minutes / 60, minutes % 60)
+    } else {
+        format!("{} min", minutes)
+    }
+}
+
+/// "3.1 Go", "420 Mo"
+pub fn format_bytes(bytes: u64) -> String {
+    let mb = bytes as f64 / 1024.0 / 1024.0;
+    if mb >= 1024.0 {
+        format!("{:.1} Go", mb / 1024.0)
+    } else {
+        format!("{:.0} Mo", mb)
+    }
+}
+
+pub fn path() -> PathBuf {
+    Path::new(HISTORY_DIR).join(SESSIONS_FILE)
+}
+
+fn marker_path() -> PathBuf {
+    Path::new(HISTORY_DIR).join(OPEN_SESSION_FILE)
+}
+
+/// Write through a temporary file renamed over `path`, in `HISTORY_DIR`
+pub(crate) fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    fs::create_dir_all(HISTORY_DIR)?;
+    let temporary = path.with_extension("tmp");
+    fs::write(&temporary, content)?;
+    fs::rename(&temporary, path)?;
+    Ok(())
+}
+
+/// Session left open by the previous run, `None` when it ended normally
+pub fn load_open_session() -> Option<GameSession> {
+    let content = fs::read_to_string(marker_path()).ok()?;
+    serde_json::from_str(&content)
+        .map_err(|e| tracing::warn!("⚠️ {} invalide, ignoré: {}", marker_path().display(), e))
+        .ok()
+}
+
+pub fn clear_open_session() {
+    if let Err(e) = fs::remove_file(marker_path()) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            tracing::warn!("⚠️ Suppression de {} impossible: {}", marker_path().display(), e);
+        }
+    }
+}
+
+/// Append a finished session, dropping the oldest ones beyond `MAX_SESSIONS`
+pub fn append(summary: &SessionSummary) -> Result<()> {
+    let mut lines: Vec<String> = match fs::read_to_string(path()) {
+        Ok(content) => content.lines().filter(|l| !l.trim().is_empty()).map(str::to_string).collect(),
+        Err(_) => Vec::new(),
+    };
+    lines.push(serde_json::to_string(summary)?);
+
+    let start = lines.len().saturating_sub(MAX_SESSIONS);
+    let mut content = lines[start..].join("\n");
+    content.push('\n');
+    write_atomic(&path(), &content)
+}
+
+/// The finished sessions, oldest first. Corrupt lines are skipped.
+pub fn load_recent() -> Vec<SessionSummary> {
+    let content = match fs::read_to_string(path()) {
+        Ok(content) => content,
+        Err(_) => return Vec::new(),
+    };
+    let mut sessions: Vec<SessionSummary> = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(index, line)| match serde_json::from_str(line) {
+            Ok(session) => Some(session),
+            Err(e) => {
+                tracing::warn!("⚠️ Ligne {} des sessions ignorée (corrompue): {}", index + 1, e);
+                None
+            }
+        })
+        .collect();
+    let start = sessions.len().saturating_sub(MAX_SESSIONS);
+    sessions.drain(..start);
+    sessions
+}
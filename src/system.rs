@@ -0,0 +1,218 @@
+//! # External commands
+//!
+//! PowerShell scripts and system tools (`sc`, `netsh`...) are run through a `CommandRunner`,
+//! so that the code deciding from their output does not depend on where it runs.
+//! `WindowsCommandRunner` is the real one:
+//! - no console window (`CREATE_NO_WINDOW`) and the same PowerShell flags for every script
+//! - a timeout: a hung PowerShell would otherwise block the calling thread forever
+//! - output decoded as UTF-8, invalid bytes replaced rather than failing the whole call
+
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Flags of every PowerShell script, followed by the script itself
+pub const POWERSHELL_ARGS: &[&str] = &[
+    "-NoProfile",
+    "-NonInteractive",
+    "-WindowStyle",
+    "Hidden",
+    "-ExecutionPolicy",
+    "Bypass",
+    "-Command",
+];
+
+/// Longest a command may run before it is killed
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// What a finished command printed, and its exit code (`None` when killed by a signal)
+#[derive(Debug, Clone, Default)]
+pub struct CommandOutput {
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl CommandOutput {
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+pub trait CommandRunner: Send + Sync {
+    /// Run `program` with `args` to completion. A non-zero exit code is not an error: the
+    /// caller decides from the output.
+    fn run(&self, program: &str, args: &[&str]) -> Result<CommandOutput>;
+
+    /// Run `script` with Windows PowerShell and `POWERSHELL_ARGS`
+    fn powershell(&self, script: &str) -> Result<CommandOutput> {
+        let mut args = POWERSHELL_ARGS.to_vec();
+        args.push(script);
+        self.run("powershell.exe", &args)
+    }
+}
+
+/// Runner starting real processes
+#[derive(Debug, Clone)]
+pub struct WindowsCommandRunner {
+    pub timeout: Duration,
+}
+
+impl Default for WindowsCommandRunner {
+    fn default() -> Self {
+        Self { timeout: DEFAULT_TIMEOUT }
+    }
+}
+
+/// Collect a pipe on its own thread: a full pipe would block the process before it exits
+fn read_pipe(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        bytes
+    })
+}
+
+fn decode(reader: JoinHandle<Vec<u8>>) -> String {
+    String::from_utf8_lossy(&reader.join().unwrap_or_default()).into_owned()
+}
+
+impl CommandRunner for WindowsCommandRunner {
+    fn run(&self, program: &str, args: &[&str]) -> Result<CommandOutput> {
+        let mut command = Command::new(program);
+        command.args(args).stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+        #[cfg(target_os = "windows")]
+        command.creation_flags(CREATE_NO_WINDOW);
+
+        let mut child = command.spawn().map_err(|e| anyhow!("Impossible d'exécuter {}: {}", program, e))?;
+        let stdout = read_pipe(child.stdout.take());
+        let stderr = read_pipe(child.stderr.take());
+
+        let started = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if started.elapsed() >= self.timeout {
+                let _ = child.kill();
+                let _ = child.wait();
+                // Lecteurs abandonnés : un processus enfant peut encore tenir les tubes ouverts
+                tracing::error!("⏱️ {} arrêté après {} s sans réponse", program, self.timeout.as_secs());
+                return Err(anyhow!("{} sans réponse après {} s", program, self.timeout.as_secs()));
+            }
+            thread::sleep(Duration::from_millis(20));
+        };
+
+        Ok(CommandOutput {
+            code: status.code(),
+            stdout: decode(stdout),
+            stderr: decode(stderr),
+        })
+    }
+}
+
+/// Runner used when none is injected
+pub fn default_runner() -> Arc<dyn CommandRunner> {
+    Arc::new(WindowsCommandRunner::default())
+}
+
+/// Runners for the tests of the modules deciding from command output
+#[cfg(test)]
+pub mod testing {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    type Respond = dyn Fn(&str, &[&str]) -> Result<CommandOutput> + Send + Sync;
+
+    /// Answers with `respond` and records each command line, program and arguments joined
+    /// by spaces (the PowerShell flags left out)
+    pub struct FakeRunner {
+        respond: Box<Respond>,
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl FakeRunner {
+        pub fn new(respond: impl Fn(&str, &[&str]) -> Result<CommandOutput> + Send + Sync + 'static) -> Self {
+            Self { respond: Box::new(respond), calls: Mutex::new(Vec::new()) }
+        }
+
+        /// Every command succeeds and prints `stdout`
+        pub fn printing(stdout: &str) -> Self {
+            let stdout = stdout.to_string();
+            Self::new(move |_, _| Ok(output(0, &stdout)))
+        }
+
+        pub fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    impl CommandRunner for FakeRunner {
+        fn run(&self, program: &str, args: &[&str]) -> Result<CommandOutput> {
+            let shown = args.strip_prefix(POWERSHELL_ARGS).unwrap_or(args);
+            self.calls.lock().unwrap().push(std::iter::once(program).chain(shown.iter().copied()).collect::<Vec<_>>().join(" "));
+            (self.respond)(program, args)
+        }
+    }
+
+    pub fn output(code: i32, stdout: &str) -> CommandOutput {
+        CommandOutput { code: Some(code), stdout: stdout.to_string(), stderr: String::new() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::FakeRunner;
+    use super::*;
+
+    #[test]
+    fn powershell_scripts_get_the_common_flags() {
+        let runner = FakeRunner::new(|program, args| {
+            assert_eq!(program, "powershell.exe");
+            assert_eq!(&args[..POWERSHELL_ARGS.len()], POWERSHELL_ARGS);
+            assert_eq!(args.len(), POWERSHELL_ARGS.len() + 1);
+            Ok(testing::output(0, "ok"))
+        });
+        let output = runner.powershell("Get-NetQosPolicy").unwrap();
+        assert!(output.success());
+        assert_eq!(runner.calls(), vec!["powershell.exe Get-NetQosPolicy".to_string()]);
+    }
+
+    #[test]
+    fn killed_or_failed_commands_are_not_successes() {
+        assert!(!CommandOutput { code: Some(1), ..Default::default() }.success());
+        assert!(!CommandOutput { code: None, ..Default::default() }.success());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn real_runner_reports_exit_code_and_output() {
+        let output = WindowsCommandRunner::default().run("sh", &["-c", "echo sortie; echo erreur >&2; exit 3"]).unwrap();
+        assert_eq!(output.code, Some(3));
+        assert_eq!(output.stdout.trim(), "sortie");
+        assert_eq!(output.stderr.trim(), "erreur");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn real_runner_kills_hung_commands() {
+        let runner = WindowsCommandRunner { timeout: Duration::from_millis(200) };
+        let started = Instant::now();
+        assert!(runner.run("sleep", &["10"]).is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(runner.run("gamebooster-commande-inexistante", &[]).is_err());
+    }
+}
@@ -1,27 +1,66 @@
 //! Theme management for the application.
 
 use eframe::egui;
+use egui::Color32;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Theme {
     pub name: &'static str,
     pub visuals: egui::Visuals,
+    /// Successful operations, active protections
+    pub success: Color32,
+    /// Failures, limited/blocked states
+    pub error: Color32,
+    /// Warnings and secondary highlights
+    pub warning: Color32,
+    /// Informational highlights, selection and links
+    pub accent: Color32,
+}
+
+impl Theme {
+    /// Replace the accent color (selection, links and highlighted labels)
+    pub fn with_accent(mut self, accent: Color32) -> Self {
+        self.accent = accent;
+        self.visuals.selection.bg_fill = accent;
+        self.visuals.hyperlink_color = accent;
+        self
+    }
 }
 
 pub fn dark_theme() -> Theme {
     Theme {
         name: "Dark",
         visuals: egui::Visuals::dark(),
+        success: Color32::from_rgb(102, 187, 106),
+        error: Color32::from_rgb(239, 83, 80),
+        warning: Color32::from_rgb(255, 167, 38),
+        accent: Color32::from_rgb(33, 150, 243),
     }
+    .with_accent(Color32::from_rgb(33, 150, 243))
 }
 
 pub fn light_theme() -> Theme {
+    // Couleurs plus sombres pour rester lisibles sur fond clair
     Theme {
         name: "Light",
         visuals: egui::Visuals::light(),
+        success: Color32::from_rgb(46, 125, 50),
+        error: Color32::from_rgb(198, 40, 40),
+        warning: Color32::from_rgb(230, 81, 0),
+        accent: Color32::from_rgb(21, 101, 192),
     }
+    .with_accent(Color32::from_rgb(21, 101, 192))
 }
 
 pub fn initial_theme() -> Theme {
     dark_theme()
-} 
\ No newline at end of file
+}
+
+/// Theme matching a persisted `Theme::name`, defaulting to the initial theme
+pub fn theme_by_name(name: &str) -> Theme {
+    match name {
+        "Light" => light_theme(),
+        "Dark" => dark_theme(),
+        _ => initial_theme(),
+    }
+}
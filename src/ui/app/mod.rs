@@ -1,200 +1,3669 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
 
-use crate::disk::{DiskCleaningOptions, DiskCleaningResults};
-use crate::memory::CleaningResults;
-use crate::services::defender::DefenderStatus;
-use crate::network::NetworkLimiter;
+use crate::config::{AppConfig, AutoCleanConfig, LimitPreferences};
+use crate::gaming::hotkey::{GlobalHotkey, Hotkey, HotkeyCleaning, HotkeyConfig};
+use crate::gaming::launcher_watcher::{LauncherTrigger, LauncherWatcher, LauncherWatcherConfig};
+use crate::history::{HistoryEntry, HistoryKind};
+use crate::ipc::IpcCommand;
+use crate::logs::{LevelHandle, LogLevel, LogTail};
+use crate::maintenance::CleanupReport;
+use crate::os_info::{OsCapabilities, OsDetails};
+
+use crate::disk::duplicates::{DuplicateGroup, DuplicateScan};
+use crate::disk::game_libraries::{GameLibraryScan, GameSort};
+use crate::disk::space_analyzer::{SpaceAnalysis, SpaceAnalysisJob, SpaceSort};
+use crate::disk::drive_info::{DriveInfo, OptimizeReport};
+use crate::disk::browser_cache::Browser;
+use crate::disk::thumbnails::LockedThumbnails;
+use crate::disk::{os_tweaks::OsTweaksResults, DiskCleaningOptions, DiskCleaningResults};
+use crate::diagnostics::{self, DiagnosticReport};
+use crate::diagnostics::event_log::{self, CrashQuery, CrashSort, SessionSpan};
+use crate::drift::{self, DriftIntent, DriftReport};
+use crate::memory::alert::{MemoryAlert, MemoryAlertConfig};
+use crate::memory::wsl::{self, WslAction, WslStatus};
+use crate::memory::{CleaningResults, ProcessMemoryInfo};
+use crate::services::defender::{DefenderProgress, DefenderStatus};
+use crate::services::tamper_guidance::{GuardedOperation, TamperGuidance, TamperPhase};
+use crate::services::winapi_defender::{ActionOutcome, DefenderAction, DefenderActionSummary};
+use crate::services::{ServiceMode, ServicesOptimizationResults};
+use crate::optimization::audio::{self, AudioDevice};
+use crate::optimization::hosts_blocker::{BlockProfile, HostsStatus};
+use crate::optimization::startup_manager::StartupEntry;
+use crate::optimization::tweaks::{self, CachedState, TweakAction, TweakRegistry, TweakRun, AUDIO_TWEAK_PREFIX};
+use crate::scheduler::config::SchedulerConfig;
+use crate::scheduler::{ScheduleRule, ScheduledTask, TaskType};
+use crate::network::{
+    BulkLimitPreview, ConnectionInfo, ExitedLimitAction, LimitDuration, LimitSummary, LimitVerification, NetworkLimiter, NetworkProcessSort, PortRule, QosPolicyInfo, QosRequirements, ReverseDnsCache,
+    SpeedLimitError, SpeedUnit,
+};
+use crate::network::connection_profile::{self, ConnectionProfile};
+use crate::network::ping_monitor::PingMonitor;
+use crate::power::PowerPlan;
+use crate::profiles::{self, AppliedProfile, BoostProfile, ProfileOutcome, ServiceRevert};
+use crate::sessions::{self, GameSession, SessionSummary};
+use crate::safety::{self, ProtectionLevel};
+use crate::ui::confirm::{ConfirmDialog, ConfirmOutcome, DangerLevel};
+use crate::ui::notifications::{system_toast, NotificationAction, NotificationLevel, Notifications};
+use crate::ui::process_icons::ProcessIcons;
+use crate::updater::{self, DownloadEvent, UpdateCheck};
+use crate::utils::process_control::{self, TerminationOutcome};
 
 use eframe::egui;
-// use image::load_from_memory; // Temporairement désactivé pour éviter les crashes
 use poll_promise::Promise;
 
 use crate::ui::{
-    disk_ui, memory_ui, network_ui, services_ui, settings_ui, scheduler_ui
+    disk_ui, memory_ui, network_ui, onboarding, services_ui, settings_ui, scheduler_ui
 };
+use crate::ui::onboarding::Onboarding;
+use crate::ui::memory_ui::ProcessMemorySort;
+use crate::ui::util::ELEVATION_REQUIRED_HINT;
 
 use crate::theme;
 
-#[derive(PartialEq, Debug, Clone, Copy)]
-pub enum Tab {
-    Memory,
-    Optimization, // Renamed from Hdd
-    Services,
-    Scheduler,
-    Network,
-    Settings,
-}
+/// How long a queried service status is reused before `sc query` runs again
+const SERVICE_STATUS_TTL: std::time::Duration = std::time::Duration::from_secs(30);
+/// Time a process gets to close its windows before it is killed
+const TERMINATE_GRACE_MS: u64 = 5000;
+/// Interval between two checks of the memory alert thresholds
+const MEMORY_ALERT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Tab {
+    Memory,
+    Optimization, // Renamed from Hdd
+    Services,
+    Scheduler,
+    Network,
+    Settings,
+}
+
+impl Tab {
+    /// Identifier used on the command line (`--tab=<id>`)
+    pub fn as_arg(&self) -> &'static str {
+        match self {
+            Tab::Memory => "memory",
+            Tab::Optimization => "optimization",
+            Tab::Services => "services",
+            Tab::Scheduler => "scheduler",
+            Tab::Network => "network",
+            Tab::Settings => "settings",
+        }
+    }
+
+    pub fn from_arg(value: &str) -> Option<Tab> {
+        match value {
+            "memory" => Some(Tab::Memory),
+            "optimization" => Some(Tab::Optimization),
+            "services" => Some(Tab::Services),
+            "scheduler" => Some(Tab::Scheduler),
+            "network" => Some(Tab::Network),
+            "settings" => Some(Tab::Settings),
+            _ => None,
+        }
+    }
+
+    /// Look for a `--tab=<id>` argument in the process command line
+    pub fn from_cli_args() -> Option<Tab> {
+        std::env::args()
+            .find_map(|arg| arg.strip_prefix("--tab=").and_then(Tab::from_arg))
+    }
+}
+
+/// Destructive action run through `CleanRamApp::request_action`
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum PendingAction {
+    CleanDisk,
+    DisableDefender,
+    PauseDefender,
+    ResetNetworkRules,
+    OptimizeServices,
+    /// Boost profile that pauses Defender, waiting in `profile_to_apply`
+    ApplyProfile,
+    /// "Limiter tout le reste", previewed in `limit_all_preview`
+    LimitAllExceptSelected,
+    /// Removal of the items listed in `cleanup_preview`
+    UninstallCleanup,
+    /// Operation on a cautious process (see `safety`), waiting in `protected_action`
+    ProtectedProcess,
+    /// Process waiting in `process_to_terminate`
+    TerminateProcess,
+    /// `wsl --shutdown`, stopping the running distributions
+    ShutdownWsl,
+}
+
+/// Operation on processes that `safety` asks to confirm first
+#[derive(Debug, Clone)]
+pub enum ProtectedAction {
+    Limit(u32),
+    /// Limit to the value remembered for the application, from its chip in the network tab
+    LimitAt { pid: u32, kbps: u32 },
+    /// Limit of the processes selected in the network tab
+    LimitSelection,
+    Block(u32),
+    Trim { pid: u32, name: String },
+}
+
+/// Process to close from the network or memory tab
+#[derive(Debug, Clone)]
+pub struct TerminateRequest {
+    pub pid: u32,
+    pub name: String,
+    /// Resolved before the process is gone, `None` when unreadable
+    pub exe_path: Option<PathBuf>,
+    /// Start `exe_path` again once the process has exited
+    pub restart: bool,
+    pub protection: ProtectionLevel,
+}
+
+/// Background work of the tweak registry
+pub enum TweakTask {
+    States(Vec<(String, CachedState)>),
+    Run(TweakRun),
+}
+
+impl PendingAction {
+    /// Identifier saved when the user ticks "Ne plus demander"
+    pub fn key(&self) -> &'static str {
+        match self {
+            PendingAction::CleanDisk => "clean_disk",
+            PendingAction::DisableDefender => "disable_defender",
+            PendingAction::PauseDefender => "pause_defender",
+            PendingAction::ResetNetworkRules => "reset_network_rules",
+            // Confirmation propre au mode durable : un ancien « Ne plus demander » ne s'applique pas
+            PendingAction::OptimizeServices => "disable_windows_update",
+            PendingAction::ApplyProfile => "apply_profile",
+            PendingAction::LimitAllExceptSelected => "limit_all_except_selected",
+            PendingAction::UninstallCleanup => "uninstall_cleanup",
+            PendingAction::ProtectedProcess => "protected_process",
+            PendingAction::TerminateProcess => "terminate_process",
+            PendingAction::ShutdownWsl => "shutdown_wsl",
+        }
+    }
+}
+
+pub struct CleanRamApp {
+    pub active_tab: Tab,
+    pub theme: theme::Theme,
+    pub ram_usage: f32,
+    pub cleaning_promise: Option<Promise<CleaningResults>>,
+    pub last_cleaned_results: Option<CleaningResults>,
+    /// Run before `last_cleaned_results`, for the comparison panel of the memory tab
+    pub previous_cleaned_results: Option<CleaningResults>,
+    pub disk_options: DiskCleaningOptions,
+    pub disk_cleaning_promise: Option<Promise<DiskCleaningResults>>,
+    pub last_disk_cleaned_results: Option<DiskCleaningResults>,
+    /// Options `last_disk_cleaned_results` was previewed with, recomputed from the preview
+    /// cache when they change
+    pub disk_preview_options: Option<DiskCleaningOptions>,
+    /// Application or restoration of the Windows 10/11 tweaks
+    pub os_tweaks_promise: Option<Promise<OsTweaksResults>>,
+    pub last_os_tweaks_results: Option<OsTweaksResults>,
+    pub hosts_profile: BlockProfile,
+    /// Application or removal of the hosts section, resolves to the new status
+    pub hosts_promise: Option<Promise<Result<HostsStatus, anyhow::Error>>>,
+    /// `None` until the hosts file is read for the first time
+    pub hosts_status: Option<Result<HostsStatus, String>>,
+    pub hosts_domain_input: String,
+    pub duplicate_scan_paths: Vec<PathBuf>,
+    /// Minimum size of the files compared, in MB
+    pub duplicate_min_size_mb: u64,
+    pub duplicate_scan: Option<DuplicateScan>,
+    /// Groups of the last scan, `None` before the first one
+    pub duplicate_groups: Option<Vec<DuplicateGroup>>,
+    /// Files checked for deletion
+    pub duplicate_selection: HashSet<PathBuf>,
+    pub duplicate_delete_promise: Option<Promise<u64>>,
+    /// Drives offered to the space analyzer, listed when the tab is first drawn
+    pub space_drives: Vec<PathBuf>,
+    pub space_root: Option<PathBuf>,
+    pub space_analysis_job: Option<SpaceAnalysisJob>,
+    /// Result of the last analysis, `None` before the first one
+    pub space_analysis: Option<SpaceAnalysis>,
+    /// Largest directories shown instead of the largest files
+    pub space_show_dirs: bool,
+    pub space_sort: SpaceSort,
+    pub space_sort_descending: bool,
+    pub game_library_promise: Option<Promise<GameLibraryScan>>,
+    /// "Mes jeux", `None` before the first scan
+    pub game_library: Option<GameLibraryScan>,
+    pub game_sort: GameSort,
+    pub game_sort_descending: bool,
+    /// `None` until the autostart entries are listed for the first time
+    pub startup_entries: Option<Result<Vec<StartupEntry>, String>>,
+    pub startup_promise: Option<Promise<Result<Vec<StartupEntry>, anyhow::Error>>>,
+    pub drives: Option<Result<Vec<DriveInfo>, String>>,
+    /// Retrim or analysis running with `defrag`, on the drive letter
+    pub drive_optimize_promise: Option<(String, Promise<Result<OptimizeReport, anyhow::Error>>)>,
+    pub last_drive_report: Option<Result<OptimizeReport, String>>,
+    pub startup_search: String,
+    /// Error of the last enable/disable in the startup programs manager
+    pub startup_entry_error: Option<String>,
+    pub processes: HashSet<u32>,
+    pub defender_status_promise: Option<Promise<Result<DefenderStatus, anyhow::Error>>>,
+    pub defender_action_promise: Option<Promise<Result<DefenderActionSummary, anyhow::Error>>>,
+    /// Checklist of the running (or last) Defender action, see `spawn_defender_action`
+    pub defender_progress: Option<DefenderProgress>,
+    /// Dialog guiding the user through turning Tamper Protection off; dropping it stops the polling
+    pub tamper_guidance: Option<TamperGuidance>,
+    pub last_defender_status: Option<Result<DefenderStatus, anyhow::Error>>,
+    /// Add/remove/list of Defender exclusions, resolves to the updated list
+    pub defender_exclusions_promise: Option<Promise<Result<Vec<PathBuf>, anyhow::Error>>>,
+    pub defender_exclusions: Option<Vec<PathBuf>>,
+    pub exclusion_suggestions: Vec<PathBuf>,
+    pub last_exclusion_error: Option<String>,
+    /// Running OS, shown in the settings tab
+    pub os_details: OsDetails,
+    /// What the running OS supports, gates the tabs
+    pub os_capabilities: OsCapabilities,
+    /// Header logo, `None` when the texture could not be built
+    pub logo: Option<egui::TextureHandle>,
+    pub is_first_frame: bool,
+    pub network_limiter: Option<NetworkLimiter>,
+    pub process_search_text: String,
+    /// Speed limit of the network tab, in `speed_limit_unit`
+    pub speed_limit_value: f64,
+    pub speed_limit_unit: SpeedUnit,
+    /// How long the limits set from the network tab last
+    pub limit_duration: LimitDuration,
+    /// Duration of `LimitDuration::Custom`
+    pub limit_custom_minutes: u32,
+    /// Last limit of each application and named presets
+    pub limit_preferences: LimitPreferences,
+    /// What happens to the limit of a process that exits, see `NetworkLimiter::set_exited_limit_handling`
+    pub exited_limit_action: ExitedLimitAction,
+    pub exited_limit_grace_secs: u64,
+    pub process_memory_list: Vec<ProcessMemoryInfo>,
+    pub process_memory_last_refresh: Option<Instant>,
+    /// Listing of the memory table, on a worker: up to 2048 processes are opened
+    pub process_memory_promise: Option<Promise<Result<Vec<ProcessMemoryInfo>, String>>>,
+    pub process_memory_sort: ProcessMemorySort,
+    pub process_memory_sort_descending: bool,
+    pub last_trim_message: Option<String>,
+    /// Trim of one process, resolves to `last_trim_message`
+    pub trim_promise: Option<Promise<String>>,
+    pub is_elevated: bool,
+    pub elevation_error: Option<String>,
+    pub last_bulk_limit_summary: Option<String>,
+    /// Targets of "Limiter tout le reste", waiting for the confirmation
+    pub limit_all_preview: Option<BulkLimitPreview>,
+    /// Mark the selection as priority traffic (DSCP EF) when limiting the rest
+    pub exempt_selection: bool,
+    /// Batch of the last "Limiter tout le reste", for "Annuler cette action"
+    pub last_limit_batch: Option<u64>,
+    pub priority_game_pid: Option<u32>,
+    pub global_cap_input: String,
+    pub last_global_limit_message: Option<String>,
+    pub dscp_selection: Option<u8>,
+    pub last_dscp_message: Option<String>,
+    /// Form of the "Règles par port" panel
+    pub port_rule_draft: PortRule,
+    pub last_port_rule_message: Option<String>,
+    pub last_network_error: Option<String>,
+    pub network_sort: NetworkProcessSort,
+    pub network_sort_descending: bool,
+    pub network_page: usize,
+    /// Root PIDs of the process groups expanded in the network table
+    pub expanded_network_groups: HashSet<u32>,
+    pub verify_limits: bool,
+    /// See `NetworkProcessInfo::is_idle`
+    pub show_idle_network_processes: bool,
+    /// See `NetworkLimiter::set_high_precision`
+    pub high_precision_network: bool,
+    /// Adapter new limits are scoped to, `None` for every adapter
+    pub qos_scope_adapter: Option<String>,
+    pub ping_monitor: PingMonitor,
+    /// Started when the memory tab is first shown
+    pub hardware_monitor: Option<crate::hardware::HardwareMonitor>,
+    /// Action waiting for the answer to its confirmation dialog
+    pub pending_confirmation: Option<(PendingAction, ConfirmDialog)>,
+    /// Operation on a cautious process, run once `PendingAction::ProtectedProcess` is confirmed
+    pub protected_action: Option<ProtectedAction>,
+    /// Run once `PendingAction::TerminateProcess` is confirmed
+    pub process_to_terminate: Option<TerminateRequest>,
+    /// Grace period and kill of `TerminateRequest`, one at a time
+    pub terminate_promise: Option<Promise<(TerminateRequest, anyhow::Result<TerminationOutcome>)>>,
+    pub skipped_confirmations: BTreeSet<String>,
+    /// Disk cleaning and service optimization run as a dry run
+    pub simulation_mode: bool,
+    pub ping_host: String,
+    /// Automatic scan interval of the network tab, 0 = manual only
+    pub network_refresh_secs: u64,
+    /// End of the last network scan
+    pub last_network_scan: Option<Instant>,
+    /// Latency measured while the network tab is shown; unchecking stops it for the session
+    pub ping_enabled: bool,
+    pub ping_on_metered: bool,
+    pub network_tab_last_seen: Option<Instant>,
+    /// Cost of the active connection, `None` when it cannot be read
+    pub connection_profile: Option<ConnectionProfile>,
+    pub connection_checked_at: Instant,
+    /// (label, PIDs) of the process whose connections are shown under the table
+    pub connection_panel: Option<(String, Vec<u32>)>,
+    pub process_connections: Vec<ConnectionInfo>,
+    pub connections_sort_descending: bool,
+    /// Show remote host names, resolved in the background
+    pub resolve_connection_names: bool,
+    pub reverse_dns: ReverseDnsCache,
+    /// Outbound rate measurements running after a limit, by PID
+    pub limit_verification_promises: HashMap<u32, Promise<anyhow::Result<LimitVerification>>>,
+    pub limit_verifications: HashMap<u32, Result<LimitVerification, String>>,
+    /// Orphaned QoS policies dialog closed for this session
+    pub orphan_dialog_dismissed: bool,
+    /// On-demand listing of the system QoS policies, foreign ones included ("Politiques QoS
+    /// actives" panel)
+    pub qos_verification_promise: Option<Promise<anyhow::Result<Vec<QosPolicyInfo>>>>,
+    /// Last listing and when it finished, kept until the next refresh
+    pub qos_verification: Option<(chrono::DateTime<chrono::Local>, Result<Vec<QosPolicyInfo>, String>)>,
+    pub selected_services: BTreeMap<String, bool>,
+    /// Per-service stop mode, `ServiceMode::Session` when absent
+    pub service_modes: BTreeMap<String, ServiceMode>,
+    /// Stop the running dependents of a service before it instead of skipping it
+    pub manage_service_dependencies: bool,
+    /// Stop (or restart, when `services_restoring`) of the selected gaming services
+    pub services_promise: Option<Promise<ServicesOptimizationResults>>,
+    pub services_restoring: bool,
+    /// Last optimization, whose stopped services can be restarted
+    pub last_services_results: Option<ServicesOptimizationResults>,
+    pub last_services_restore: Option<ServicesOptimizationResults>,
+    /// Service name -> (status, queried at)
+    pub services_status_cache: HashMap<String, (String, Instant)>,
+    /// Services whose status is missing or older than `SERVICE_STATUS_TTL`, queried together
+    pub services_status_queue: BTreeSet<String>,
+    /// `sc query` of the queued services, (name, status)
+    pub services_status_promise: Option<Promise<Vec<(String, String)>>>,
+    /// Service name -> (dependents, dependencies), queried when its detail is first opened
+    pub service_dependencies_cache: HashMap<String, (Vec<String>, Vec<String>)>,
+    pub scheduler_config: SchedulerConfig,
+    /// IDs of the tasks present in the Windows Task Scheduler, `None` until first checked
+    pub mirrored_tasks: Option<HashSet<String>>,
+    pub scheduler_error: Option<String>,
+    pub new_task_type: TaskType,
+    pub new_task_rule: ScheduleRule,
+    /// In-app run of a due task that is not mirrored in Windows: (task id, run)
+    pub scheduled_task_promise: Option<(String, Promise<Result<String, anyhow::Error>>)>,
+    pub startup_tasks_done: bool,
+    /// `OnStartup` tasks run once per launch
+    pub launched_at: chrono::DateTime<chrono::Local>,
+    pub auto_clean: AutoCleanConfig,
+    pub memory_alert_config: MemoryAlertConfig,
+    pub memory_alert: MemoryAlert,
+    pub memory_alert_checked_at: Option<Instant>,
+    /// Set by the click on a Windows notification, from a system thread
+    pub toast_clicked: Arc<AtomicBool>,
+    /// Executable icons of the network and memory tables
+    pub process_icons: ProcessIcons,
+    /// Drift check running in the background, see `crate::drift`
+    pub drift_promise: Option<Promise<DriftReport>>,
+    pub drift_checked_at: Option<Instant>,
+    /// Latest drift check; its drifts wait for "Ré-appliquer" or "Accepter l'état actuel"
+    pub drift_report: Option<DriftReport>,
+    /// Open while the gaming mode or a boost profile is active, see `crate::sessions`
+    pub game_session: Option<GameSession>,
+    /// Finished gaming sessions, oldest first
+    pub game_sessions: Vec<SessionSummary>,
+    /// Inner size of the restored window, kept while it is maximized
+    pub window_size: egui::Vec2,
+    /// Outer top-left corner on the virtual desktop, `None` until the window reports it
+    pub window_position: Option<egui::Pos2>,
+    pub window_pixels_per_point: f32,
+    pub window_maximized: bool,
+    pub accent_color: Option<egui::Color32>,
+    pub history: Vec<HistoryEntry>,
+    /// Follows the log files while the "Journal" section of the settings is open
+    pub log_tail: Option<LogTail>,
+    pub log_min_level: LogLevel,
+    pub log_search: String,
+    pub log_auto_scroll: bool,
+    /// Level written to the log files, applied through `log_level_handle`
+    pub log_level: LogLevel,
+    pub log_retention_days: u32,
+    /// Set by `main` once logging is started
+    pub log_level_handle: Option<LevelHandle>,
+    /// Size of the log folder and when it was measured
+    pub log_dir_size: Option<(u64, Instant)>,
+    /// Day the retention last ran, so that it runs again after midnight
+    pub log_retention_day: chrono::NaiveDate,
+    pub disk_operation_is_clean: bool,
+    pub run_at_startup: bool,
+    pub startup_error: Option<String>,
+    pub start_minimized: bool,
+    pub defender_pause_minutes: u32,
+    pub defender_reenable_at: Option<chrono::DateTime<chrono::Local>>,
+    /// Installed power plans, loaded on first display of the Optimization tab
+    pub power_plans: Option<Vec<PowerPlan>>,
+    /// Entries being typed in the "Avancé" section of the temporary files
+    pub new_temp_skip_extension: String,
+    pub new_temp_skip_pattern: String,
+    /// Browsers with a profile, detected on first display of the Optimization tab
+    pub installed_browsers: Option<Vec<Browser>>,
+    /// Plan activated by the gaming mode, High Performance when unset
+    pub gaming_power_plan: Option<String>,
+    /// Plan to reactivate when the gaming mode ends, persisted in case the app is closed first
+    pub power_plan_to_restore: Option<String>,
+    pub gaming_audio_enhancements_off: bool,
+    pub gaming_mmcss_priority: bool,
+    /// Playback devices, listed on first display of the Optimization tab
+    pub audio_devices: Option<Result<Vec<AudioDevice>, String>>,
+    /// Audio change followed by a new listing of the devices
+    pub audio_promise: Option<Promise<(OsTweaksResults, Result<Vec<AudioDevice>, String>)>>,
+    pub last_audio_results: Option<OsTweaksResults>,
+    /// Switches of the Optimization tab with their last read state
+    pub tweak_registry: TweakRegistry,
+    /// State reading or apply / revert of one tweak
+    pub tweak_promise: Option<Promise<TweakTask>>,
+    /// Read when the memory tab first shows a `vmmem` process
+    pub wsl_status: Option<WslStatus>,
+    pub wsl_promise: Option<Promise<(Option<(WslAction, Result<String, String>)>, WslStatus)>>,
+    /// Mirrors `AppConfig::services_to_restart`, read by the panic hook
+    pub services_to_restart: Vec<String>,
+    /// Mirrors `AppConfig::services_start_types`, read by the uninstall cleanup
+    pub services_start_types: BTreeMap<String, String>,
+    /// User profiles, see `profiles::built_in` for the others
+    pub boost_profiles: Vec<BoostProfile>,
+    /// Name of the profile behind the "Boost" button of the tab bar
+    pub default_profile: Option<String>,
+    /// First-run assistant shown over the window
+    pub onboarding: Option<Onboarding>,
+    pub onboarding_completed: bool,
+    pub applied_profile: Option<AppliedProfile>,
+    /// Apply or revert in progress; holds the network limiter until it finishes
+    pub profile_promise: Option<Promise<(Option<NetworkLimiter>, ProfileOutcome)>>,
+    /// Profile waiting for the confirmation of its Defender pause
+    pub profile_to_apply: Option<BoostProfile>,
+    /// Index in `boost_profiles` of the profile open in the editor
+    pub editing_profile: Option<usize>,
+    pub last_profile_message: Option<String>,
+    /// Outcome of the user actions, shown as toasts and in the list behind the bell
+    pub notifications: Notifications,
+    /// Inventory or removal of `maintenance::full_cleanup`; holds the network limiter until it finishes
+    pub cleanup_promise: Option<Promise<(Option<NetworkLimiter>, CleanupReport)>>,
+    /// Inventory waiting for the confirmation of the removal
+    pub cleanup_preview: Option<CleanupReport>,
+    /// Outcome of the removal, shown in a dialog until closed
+    pub cleanup_report: Option<CleanupReport>,
+    /// The settings folder was removed by the cleanup: nothing is written back on exit
+    pub settings_removed: bool,
+    pub gaming_mode_active: bool,
+    pub last_power_error: Option<String>,
+    pub launcher_watcher_config: LauncherWatcherConfig,
+    /// Running while `launcher_watcher_config.enabled`, restarted when the settings change
+    pub launcher_watcher: Option<LauncherWatcher>,
+    pub hotkey_config: HotkeyConfig,
+    /// Registered while `hotkey_config.enabled`, registered again when the combination changes
+    pub global_hotkey: Option<GlobalHotkey>,
+    /// Combination last registered or attempted, `None` when disabled: a conflict is not
+    /// retried every frame
+    pub hotkey_applied: Option<String>,
+    /// Conflict with another program, or invalid combination typed in the settings
+    pub hotkey_error: Option<String>,
+    /// Combination being typed in the settings
+    pub hotkey_draft: String,
+    pub hotkey_cleaning: Option<Promise<HotkeyCleaning>>,
+    /// Requests of the instances started while this one runs, see `ipc`, and of the global
+    /// hotkey
+    pub ipc_commands: Option<std::sync::mpsc::Receiver<IpcCommand>>,
+    /// Sending end of `ipc_commands`, handed to the global hotkey thread
+    pub command_sender: Option<std::sync::mpsc::Sender<IpcCommand>>,
+    pub check_updates: bool,
+    pub check_updates_on_metered: bool,
+    pub ignored_update_version: Option<String>,
+    pub update_check_promise: Option<Promise<UpdateCheck>>,
+    pub diagnostic_promise: Option<Promise<anyhow::Result<DiagnosticReport>>>,
+    /// Prerequisite check started with the app, handed to the limiter when done
+    pub qos_requirements_promise: Option<Promise<QosRequirements>>,
+    /// Zip written (or error) by the last diagnostic report, shown in a dialog until closed
+    pub diagnostic_report: Option<anyhow::Result<DiagnosticReport>>,
+    /// Event log query of the "Crashs récents" panel, see `crate::diagnostics::event_log`
+    pub crash_promise: Option<Promise<CrashQuery>>,
+    pub crash_query: Option<CrashQuery>,
+    pub crash_sort: CrashSort,
+    pub crash_sort_descending: bool,
+    /// Time and event ID of the crash whose details are shown
+    pub crash_expanded: Option<(chrono::DateTime<chrono::Local>, u32)>,
+    /// Outcome of the last update check, shown in the settings
+    pub update_check: Option<UpdateCheck>,
+    /// Events of the download in progress
+    pub update_download: Option<std::sync::mpsc::Receiver<DownloadEvent>>,
+    /// Bytes downloaded and expected total of the download in progress
+    pub update_download_progress: Option<(u64, Option<u64>)>,
+    /// Downloaded file, or why the download failed
+    pub update_download_result: Option<Result<PathBuf, String>>,
+}
+
+impl CleanRamApp {
+    pub fn is_not_busy(&self) -> bool {
+        // Only block UI during heavy operations, not status checks
+        self.cleaning_promise.is_none() 
+            && self.hotkey_cleaning.is_none()
+            && self.disk_cleaning_promise.is_none() 
+            && self.defender_action_promise.is_none()
+            && self.services_promise.is_none()
+            && self.os_tweaks_promise.is_none()
+            && self.hosts_promise.is_none()
+            && self.duplicate_delete_promise.is_none()
+            && self.profile_promise.is_none()
+            && self.drive_optimize_promise.is_none()
+            && self.cleanup_promise.is_none()
+    }
+
+    pub fn new(cc: &eframe::CreationContext<'_>, config: AppConfig) -> Self {
+        let logo = crate::ui::icons::load_logo_texture(&cc.egui_ctx);
+        
+        let network_limiter = match crate::network::NetworkLimiter::new() {
+            Ok(mut limiter) => {
+                tracing::info!("✅ Network manager QoS initialized");
+                limiter.set_exited_limit_handling(config.exited_limit_action, config.exited_limit_grace_secs);
+                limiter.set_high_precision(config.high_precision_network);
+                Some(limiter)
+            }
+            Err(e) => {
+                tracing::error!("❌ Failed to initialize network manager: {}", e);
+                None
+            }
+        };
+        // Sans PowerShell : élévation du jeton et présence de la classe WMI NetQoS
+        let qos_requirements_promise = network_limiter
+            .is_some()
+            .then(|| Promise::spawn_thread("qos_requirements", QosRequirements::check));
+
+        // Pas de vérification de mise à jour au démarrage sur une connexion limitée, sauf choix contraire
+        let connection_profile = connection_profile::current()
+            .map_err(|e| tracing::warn!("⚠️ Coût de la connexion inconnu: {}", e))
+            .ok();
+        let metered = connection_profile.is_some_and(|profile| profile.is_metered());
+        if metered && config.check_updates && !config.check_updates_on_metered {
+            tracing::info!("📶 Connexion limitée : vérification des mises à jour reportée");
+        }
+
+        let os_details = crate::os_info::get_os_details().clone();
+        let os_capabilities = crate::os_info::capabilities();
+        tracing::info!("🖥️ Capacités du système : {:?}", os_capabilities);
+
+        // Mode jeu interrompu (plantage, arrêt forcé) : le plan d'origine n'a jamais été remis
+        let power_plan_to_restore = config.power_plan_to_restore.filter(|guid| {
+            match crate::power::set_active_plan(guid) {
+                Ok(()) => {
+                    tracing::info!("🔋 Plan d'alimentation d'origine restauré au démarrage: {}", guid);
+                    false
+                }
+                Err(e) => {
+                    tracing::warn!("⚠️ Restauration du plan d'alimentation {} impossible: {}", guid, e);
+                    true
+                }
+            }
+        });
+
+        let (game_session, game_sessions) = recover_game_session(config.applied_profile.as_ref());
+
+        Self {
+            active_tab: Tab::from_arg(&config.last_tab).unwrap_or(Tab::Memory),
+            theme: Self::build_theme(&config.theme, config.accent_color.map(|[r, g, b]| egui::Color32::from_rgb(r, g, b))),
+            ram_usage: 0.0,
+            cleaning_promise: None,
+            last_cleaned_results: None,
+            previous_cleaned_results: None,
+            disk_options: config.disk_options,
+            disk_cleaning_promise: None,
+            last_disk_cleaned_results: None,
+            disk_preview_options: None,
+            os_tweaks_promise: None,
+            last_os_tweaks_results: None,
+            hosts_profile: config.hosts_profile,
+            hosts_promise: None,
+            hosts_status: None,
+            hosts_domain_input: String::new(),
+            duplicate_scan_paths: config.duplicate_scan_paths,
+            duplicate_min_size_mb: crate::disk::duplicates::DEFAULT_MIN_SIZE / 1024 / 1024,
+            duplicate_scan: None,
+            duplicate_groups: None,
+            duplicate_selection: HashSet::new(),
+            duplicate_delete_promise: None,
+            space_drives: Vec::new(),
+            space_root: None,
+            space_analysis_job: None,
+            space_analysis: None,
+            space_show_dirs: false,
+            space_sort: SpaceSort::Size,
+            space_sort_descending: true,
+            game_library_promise: None,
+            game_library: None,
+            game_sort: GameSort::Size,
+            game_sort_descending: true,
+            startup_entries: None,
+            startup_promise: None,
+            drives: None,
+            drive_optimize_promise: None,
+            last_drive_report: None,
+            startup_search: String::new(),
+            startup_entry_error: None,
+            processes: HashSet::new(),
+            defender_status_promise: None,
+            defender_action_promise: None,
+            defender_progress: None,
+            tamper_guidance: None,
+            last_defender_status: None,
+            defender_exclusions_promise: None,
+            defender_exclusions: None,
+            exclusion_suggestions: crate::utils::game_libraries::detect_library_paths(),
+            last_exclusion_error: None,
+            os_details,
+            os_capabilities,
+            logo,
+            is_first_frame: true,
+            network_limiter,
+            process_search_text: String::new(),
+            speed_limit_value: config.default_speed_limit.trim().parse().unwrap_or(1.0),
+            speed_limit_unit: config.default_speed_limit_unit,
+            limit_duration: LimitDuration::default(),
+            limit_custom_minutes: 90,
+            limit_preferences: config.limit_preferences.clone(),
+            exited_limit_action: config.exited_limit_action,
+            exited_limit_grace_secs: config.exited_limit_grace_secs,
+            process_memory_list: Vec::new(),
+            process_memory_last_refresh: None,
+            process_memory_promise: None,
+            process_memory_sort: ProcessMemorySort::WorkingSet,
+            process_memory_sort_descending: true,
+            last_trim_message: None,
+            trim_promise: None,
+            is_elevated: crate::utils::is_elevated(),
+            elevation_error: None,
+            last_bulk_limit_summary: None,
+            limit_all_preview: None,
+            exempt_selection: false,
+            last_limit_batch: None,
+            priority_game_pid: None,
+            global_cap_input: "1.0".to_string(),
+            last_global_limit_message: None,
+            dscp_selection: None,
+            last_dscp_message: None,
+            port_rule_draft: PortRule::default(),
+            last_port_rule_message: None,
+            last_network_error: None,
+            network_sort: NetworkProcessSort::Download,
+            network_sort_descending: true,
+            network_page: 0,
+            expanded_network_groups: HashSet::new(),
+            verify_limits: config.verify_network_limits,
+            show_idle_network_processes: config.show_idle_network_processes,
+            high_precision_network: config.high_precision_network,
+            qos_scope_adapter: None,
+            ping_monitor: PingMonitor::new(),
+            hardware_monitor: None,
+            pending_confirmation: None,
+            protected_action: None,
+            process_to_terminate: None,
+            terminate_promise: None,
+            skipped_confirmations: config.skipped_confirmations,
+            simulation_mode: config.simulation_mode,
+            ping_host: config.ping_host,
+            network_refresh_secs: config.network_refresh_secs,
+            last_network_scan: None,
+            ping_enabled: true,
+            ping_on_metered: config.ping_on_metered,
+            network_tab_last_seen: None,
+            connection_profile,
+            connection_checked_at: Instant::now(),
+            connection_panel: None,
+            process_connections: Vec::new(),
+            connections_sort_descending: false,
+            resolve_connection_names: true,
+            reverse_dns: ReverseDnsCache::new(),
+            limit_verification_promises: HashMap::new(),
+            limit_verifications: HashMap::new(),
+            orphan_dialog_dismissed: false,
+            qos_verification_promise: None,
+            qos_verification: None,
+            selected_services: config.selected_services,
+            service_modes: config.service_modes,
+            manage_service_dependencies: config.manage_service_dependencies,
+            services_promise: None,
+            services_restoring: false,
+            last_services_results: None,
+            last_services_restore: None,
+            services_status_cache: HashMap::new(),
+            services_status_queue: BTreeSet::new(),
+            services_status_promise: None,
+            service_dependencies_cache: HashMap::new(),
+            scheduler_config: SchedulerConfig::load(),
+            mirrored_tasks: None,
+            scheduler_error: None,
+            new_task_type: TaskType::CleanRam,
+            new_task_rule: ScheduleRule::Daily(3),
+            scheduled_task_promise: None,
+            startup_tasks_done: false,
+            launched_at: chrono::Local::now(),
+            auto_clean: config.auto_clean,
+            memory_alert_config: config.memory_alert,
+            memory_alert: MemoryAlert::default(),
+            memory_alert_checked_at: None,
+            toast_clicked: Arc::default(),
+            process_icons: ProcessIcons::default(),
+            drift_promise: None,
+            drift_checked_at: None,
+            drift_report: None,
+            game_session,
+            game_sessions,
+            window_size: egui::vec2(config.window.width, config.window.height),
+            window_position: config.window.position.map(egui::Pos2::from),
+            window_pixels_per_point: config.window.pixels_per_point,
+            window_maximized: config.window.maximized,
+            accent_color: config.accent_color.map(|[r, g, b]| egui::Color32::from_rgb(r, g, b)),
+            history: crate::history::load_recent(crate::history::MAX_ENTRIES),
+            log_tail: None,
+            log_min_level: LogLevel::Info,
+            log_search: String::new(),
+            log_auto_scroll: true,
+            log_level: config.log_level,
+            log_retention_days: config.log_retention_days,
+            log_level_handle: None,
+            log_dir_size: None,
+            // La rétention du jour a été appliquée par `main`
+            log_retention_day: chrono::Local::now().date_naive(),
+            disk_operation_is_clean: false,
+            run_at_startup: crate::utils::is_run_at_startup_enabled(),
+            startup_error: None,
+            start_minimized: std::env::args().any(|arg| arg == "--minimized"),
+            defender_pause_minutes: 60,
+            defender_reenable_at: config.defender_reenable_at,
+            power_plans: None,
+            new_temp_skip_extension: String::new(),
+            new_temp_skip_pattern: String::new(),
+            installed_browsers: None,
+            gaming_power_plan: config.gaming_power_plan,
+            power_plan_to_restore,
+            gaming_audio_enhancements_off: config.gaming_audio_enhancements_off,
+            gaming_mmcss_priority: config.gaming_mmcss_priority,
+            audio_devices: None,
+            audio_promise: None,
+            tweak_registry: TweakRegistry::standard(),
+            tweak_promise: None,
+            last_audio_results: None,
+            wsl_status: None,
+            wsl_promise: None,
+            services_to_restart: config.services_to_restart,
+            services_start_types: config.services_start_types,
+            boost_profiles: config.boost_profiles,
+            default_profile: config.default_profile,
+            onboarding: None,
+            onboarding_completed: config.onboarding_completed,
+            applied_profile: config.applied_profile,
+            profile_promise: None,
+            profile_to_apply: None,
+            editing_profile: None,
+            last_profile_message: None,
+            notifications: Notifications::default(),
+            cleanup_promise: None,
+            cleanup_preview: None,
+            cleanup_report: None,
+            settings_removed: false,
+            gaming_mode_active: false,
+            last_power_error: None,
+            launcher_watcher_config: config.launcher_watcher,
+            launcher_watcher: None,
+            hotkey_draft: config.hotkey.combination.clone(),
+            hotkey_config: config.hotkey,
+            global_hotkey: None,
+            hotkey_applied: None,
+            hotkey_error: None,
+            hotkey_cleaning: None,
+            ipc_commands: None,
+            command_sender: None,
+            check_updates: config.check_updates,
+            check_updates_on_metered: config.check_updates_on_metered,
+            ignored_update_version: config.ignored_update_version,
+            // Jamais bloquant : le résultat est relevé par `poll_update_check`
+            update_check_promise: (config.check_updates && (!metered || config.check_updates_on_metered))
+                .then(|| Promise::spawn_thread("update_check", updater::check_for_update)),
+            diagnostic_promise: None,
+            diagnostic_report: None,
+            crash_promise: None,
+            crash_query: None,
+            crash_sort: CrashSort::Time,
+            crash_sort_descending: true,
+            crash_expanded: None,
+            qos_requirements_promise,
+            update_check: None,
+            update_download: None,
+            update_download_progress: None,
+            update_download_result: None,
+        }
+    }
+
+    fn build_theme(name: &str, accent: Option<egui::Color32>) -> theme::Theme {
+        let base = theme::theme_by_name(name);
+        match accent {
+            Some(accent) => base.with_accent(accent),
+            None => base,
+        }
+    }
+
+    /// Switch theme and/or accent color, applied immediately
+    pub fn set_theme(&mut self, name: &str, accent: Option<egui::Color32>, ctx: &egui::Context) {
+        self.accent_color = accent;
+        self.theme = Self::build_theme(name, accent);
+        ctx.set_visuals(self.theme.visuals.clone());
+    }
+
+    /// Toggle the "run at login" registry entry, keeping the checkbox in sync with the registry
+    pub fn set_run_at_startup(&mut self, enabled: bool) {
+        match crate::utils::set_run_at_startup(enabled) {
+            Ok(()) => {
+                tracing::info!("✅ Lancement au démarrage: {}", if enabled { "activé" } else { "désactivé" });
+                self.startup_error = None;
+            }
+            Err(e) => {
+                tracing::error!("❌ Modification du lancement au démarrage échouée: {}", e);
+                self.startup_error = Some(e.to_string());
+            }
+        }
+        self.run_at_startup = crate::utils::is_run_at_startup_enabled();
+    }
+
+    /// Reload the installed power plans; failures (e.g. locked-down machines) are only reported
+    pub fn refresh_power_plans(&mut self) {
+        match crate::power::list_power_plans() {
+            Ok(plans) => {
+                self.power_plans = Some(plans);
+                self.last_power_error = None;
+            }
+            Err(e) => {
+                tracing::error!("❌ Lecture des plans d'alimentation impossible: {}", e);
+                self.power_plans = Some(Vec::new());
+                self.last_power_error = Some(e.to_string());
+            }
+        }
+        self.tweak_registry.forget(tweaks::POWER_PLAN_TWEAK_ID);
+    }
+
+    pub fn switch_power_plan(&mut self, guid: &str) {
+        if let Err(e) = crate::power::set_active_plan(guid) {
+            tracing::error!("❌ Changement de plan d'alimentation impossible: {}", e);
+            self.last_power_error = Some(e.to_string());
+            return;
+        }
+        self.refresh_power_plans();
+    }
+
+    /// Duplicate the hidden Ultimate Performance plan and select it as the gaming plan
+    pub fn create_ultimate_performance_plan(&mut self) {
+        match crate::power::create_ultimate_performance() {
+            Ok(guid) => {
+                self.gaming_power_plan = Some(guid);
+                self.save_settings();
+                self.refresh_power_plans();
+            }
+            Err(e) => {
+                tracing::error!("❌ Création du plan Performances optimales impossible: {}", e);
+                self.last_power_error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// Switch to the gaming power plan, remembering the current one on disk first
+    pub fn enter_gaming_mode(&mut self) {
+        if self.gaming_mode_active {
+            return;
+        }
+        self.gaming_mode_active = true;
+        tracing::info!("🎮 Mode jeu activé");
+        self.sync_game_session();
+
+        let (enhancements, mmcss) = (self.gaming_audio_enhancements_off, self.gaming_mmcss_priority);
+        if enhancements || mmcss {
+            self.start_audio_task("audio_gaming_enter", move || audio::enter_gaming_mode(enhancements, mmcss));
+        }
+
+        let gaming_plan = self
+            .gaming_power_plan
+            .clone()
+            .unwrap_or_else(|| crate::power::HIGH_PERFORMANCE.to_string());
+        let current = match crate::power::get_active_plan() {
+            Ok(current) => current,
+            Err(e) => {
+                tracing::error!("❌ Plan d'alimentation actif inconnu, plan inchangé: {}", e);
+                self.last_power_error = Some(e.to_string());
+                return;
+            }
+        };
+        if current == gaming_plan {
+            return;
+        }
+
+        // Un plan déjà mémorisé (restauration échouée) reste le plan d'origine
+        if self.power_plan_to_restore.is_none() {
+            self.power_plan_to_restore = Some(current);
+            self.save_settings();
+        }
+        self.switch_power_plan(&gaming_plan);
+    }
+
+    pub fn exit_gaming_mode(&mut self) {
+        if !self.gaming_mode_active {
+            return;
+        }
+        self.gaming_mode_active = false;
+        tracing::info!("🎮 Mode jeu désactivé");
+        self.sync_game_session();
+        // Depuis le journal : un réglage laissé par une session interrompue est aussi rétabli
+        if audio::has_gaming_mode_changes() {
+            self.start_audio_task("audio_gaming_exit", audio::exit_gaming_mode);
+        }
+        self.restore_power_plan();
+        self.refresh_power_plans();
+    }
+
+    /// Open or close the gaming session to follow the gaming mode and the applied profile
+    fn sync_game_session(&mut self) {
+        let active = self.gaming_mode_active || self.applied_profile.is_some();
+        match (active, self.game_session.is_some()) {
+            (true, false) => {
+                let (name, applied_at) = match &self.applied_profile {
+                    Some(applied) => (applied.profile_name.as_str(), Some(applied.applied_at)),
+                    None => ("Mode jeu", None),
+                };
+                let mut session = GameSession::start(name, applied_at);
+                if let Some(limiter) = &self.network_limiter {
+                    session.seed_network(&limiter.get_processes());
+                }
+                // Écrit avant tout relevé : une session interrompue est retrouvée au démarrage
+                session.save_marker(true);
+                self.game_session = Some(session);
+            }
+            (false, true) => self.close_game_session(),
+            _ => {}
+        }
+    }
+
+    fn close_game_session(&mut self) {
+        let Some(session) = self.game_session.take() else {
+            return;
+        };
+        let summary = session.close(false);
+        if let Err(e) = sessions::append(&summary) {
+            tracing::error!("❌ Écriture de la session de jeu impossible: {}", e);
+        }
+        sessions::clear_open_session();
+        self.notifications.info("Session", summary.headline());
+        self.game_sessions.push(summary);
+        let excess = self.game_sessions.len().saturating_sub(sessions::MAX_SESSIONS);
+        self.game_sessions.drain(..excess);
+    }
+
+    /// Run an audio change in the background, then list the devices again
+    pub fn start_audio_task(&mut self, name: &str, task: impl FnOnce() -> OsTweaksResults + Send + 'static) {
+        // Une tâche précédente se termine quand même : le journal est protégé par un verrou
+        self.audio_promise = Some(Promise::spawn_thread(name, move || {
+            let results = task();
+            (results, audio::list_render_devices().map_err(|e| e.to_string()))
+        }));
+    }
+
+    pub fn refresh_audio_devices(&mut self) {
+        self.start_audio_task("audio_devices", OsTweaksResults::default);
+    }
+
+    fn poll_audio_task(&mut self) {
+        let Some(promise) = self.audio_promise.take() else {
+            return;
+        };
+        match promise.try_take() {
+            Ok((results, devices)) => {
+                let failed: Vec<String> = results
+                    .outcomes
+                    .iter()
+                    .filter(|outcome| !outcome.success)
+                    .map(|outcome| format!("{} : {}", outcome.label, outcome.detail))
+                    .collect();
+                if !failed.is_empty() {
+                    self.notifications.failures("Audio", format!("{} réglage(s) audio non appliqué(s)", failed.len()), &failed);
+                }
+                if !results.outcomes.is_empty() || !results.warnings.is_empty() {
+                    self.last_audio_results = Some(results);
+                }
+                if let Ok(devices) = &devices {
+                    let device_tweaks = devices
+                        .iter()
+                        .map(|device| Arc::new(audio::EnhancementsTweak::new(device.clone())) as Arc<dyn tweaks::Tweak>)
+                        .collect();
+                    self.tweak_registry.replace_prefixed(AUDIO_TWEAK_PREFIX, device_tweaks);
+                }
+                self.audio_devices = Some(devices);
+            }
+            Err(promise) => self.audio_promise = Some(promise),
+        }
+    }
+
+    /// Read the states missing from the tweak registry on a worker thread
+    pub fn refresh_tweak_states(&mut self) {
+        if self.tweak_promise.is_some() {
+            return;
+        }
+        let stale = self.tweak_registry.stale();
+        if stale.is_empty() {
+            return;
+        }
+        self.tweak_promise = Some(Promise::spawn_thread("tweak_states", move || TweakTask::States(tweaks::read_states(&stale))));
+    }
+
+    /// Read every tweak state again, after a change made outside the registry
+    pub fn reload_tweak_states(&mut self) {
+        self.tweak_registry.invalidate();
+        self.refresh_tweak_states();
+    }
+
+    /// Apply or revert one tweak on a worker thread
+    pub fn toggle_tweak(&mut self, id: &str, apply: bool) {
+        if self.tweak_promise.is_some() {
+            return;
+        }
+        let Some(tweak) = self.tweak_registry.get(id) else {
+            return;
+        };
+        let action = if apply { TweakAction::Apply } else { TweakAction::Revert };
+        let applied = self.tweak_registry.applied(id);
+        self.tweak_promise = Some(Promise::spawn_thread("tweak_toggle", move || TweakTask::Run(tweaks::run(tweak, action, applied))));
+    }
+
+    fn poll_tweak_task(&mut self) {
+        let Some(promise) = self.tweak_promise.take() else {
+            return;
+        };
+        match promise.try_take() {
+            Ok(TweakTask::States(states)) => self.tweak_registry.store_states(states),
+            Ok(TweakTask::Run(run)) => {
+                match (&run.outcome, run.action) {
+                    (Ok(applied), TweakAction::Apply) if !applied.detail.is_empty() => {
+                        self.notifications.success("Optimisations", format!("{} : {}", run.label, applied.detail))
+                    }
+                    (Ok(_), TweakAction::Apply) => self.notifications.success("Optimisations", format!("{} appliqué", run.label)),
+                    (Ok(_), TweakAction::Revert) => self.notifications.success("Optimisations", format!("{} annulé", run.label)),
+                    (Err(e), _) => self.notifications.error("Optimisations", format!("{} non modifié : {}", run.label, e)),
+                }
+                self.tweak_registry.finish(&run);
+                if run.id == tweaks::POWER_PLAN_TWEAK_ID {
+                    self.power_plans = None;
+                }
+            }
+            Err(promise) => self.tweak_promise = Some(promise),
+        }
+    }
+
+    /// Look for a newer release on a worker thread, from the settings or at startup
+    pub fn start_update_check(&mut self) {
+        if self.update_check_promise.is_none() {
+            self.update_check_promise = Some(Promise::spawn_thread("update_check", updater::check_for_update));
+        }
+    }
+
+    fn poll_update_check(&mut self) {
+        let Some(promise) = self.update_check_promise.take() else {
+            return;
+        };
+        match promise.try_take() {
+            Ok(check) => {
+                if let UpdateCheck::Available(release) = &check {
+                    if self.ignored_update_version.as_deref() != Some(release.tag.as_str()) {
+                        self.notifications.info(
+                            "Mise à jour",
+                            format!("GameBooster {} est disponible : voir Paramètres → Mises à jour", release.version),
+                        );
+                    }
+                }
+                self.update_check = Some(check);
+            }
+            Err(promise) => self.update_check_promise = Some(promise),
+        }
+    }
+
+    /// Collect the diagnostic report and zip it on a worker thread
+    pub fn start_diagnostic_report(&mut self) {
+        if self.diagnostic_promise.is_none() {
+            self.diagnostic_report = None;
+            self.diagnostic_promise =
+                Some(Promise::spawn_thread("diagnostics", || diagnostics::collect().write_zip()));
+        }
+    }
+
+    /// Read the recent crashes on a worker thread, tagged with the finished sessions and
+    /// the one in progress
+    pub fn start_crash_query(&mut self) {
+        if self.crash_promise.is_some() {
+            return;
+        }
+        let mut spans: Vec<SessionSpan> = self.game_sessions.iter().map(SessionSpan::from).collect();
+        if let Some(session) = &self.game_session {
+            spans.push(SessionSpan {
+                name: session.title().to_string(),
+                started_at: session.started_at,
+                ended_at: chrono::Local::now(),
+                interrupted: false,
+            });
+        }
+        self.crash_promise = Some(Promise::spawn_thread("crash_events", move || event_log::recent_crashes(&spans)));
+    }
+
+    pub fn poll_crash_query(&mut self) {
+        if let Some(promise) = self.crash_promise.take() {
+            match promise.try_take() {
+                Ok(query) => self.crash_query = Some(query),
+                Err(promise) => self.crash_promise = Some(promise),
+            }
+        }
+    }
+
+    fn poll_qos_requirements(&mut self) {
+        let Some(promise) = self.qos_requirements_promise.take() else {
+            return;
+        };
+        match promise.try_take() {
+            Ok(requirements) => {
+                if let Some(limiter) = self.network_limiter.as_mut() {
+                    limiter.set_qos_requirements(requirements);
+                }
+            }
+            Err(promise) => self.qos_requirements_promise = Some(promise),
+        }
+    }
+
+    fn poll_diagnostic_report(&mut self) {
+        let Some(promise) = self.diagnostic_promise.take() else {
+            return;
+        };
+        match promise.try_take() {
+            Ok(Ok(report)) => self.diagnostic_report = Some(Ok(report)),
+            Ok(Err(e)) => {
+                tracing::error!("❌ Rapport de diagnostic impossible: {:#}", e);
+                self.diagnostic_report = Some(Err(e));
+            }
+            Err(promise) => self.diagnostic_promise = Some(promise),
+        }
+    }
+
+    /// Download the asset of the available release into the Downloads folder
+    pub fn start_update_download(&mut self) {
+        let Some(UpdateCheck::Available(release)) = &self.update_check else {
+            return;
+        };
+        let Some(asset) = release.asset.clone() else {
+            return;
+        };
+        if self.update_download.is_some() {
+            return;
+        }
+        self.update_download_progress = Some((0, (asset.size > 0).then_some(asset.size)));
+        self.update_download_result = None;
+        self.update_download = Some(updater::start_download(asset));
+    }
+
+    fn poll_update_download(&mut self) {
+        let Some(receiver) = &self.update_download else {
+            return;
+        };
+        let mut finished = false;
+        for event in receiver.try_iter() {
+            match event {
+                DownloadEvent::Progress { downloaded, total } => self.update_download_progress = Some((downloaded, total)),
+                DownloadEvent::Done(path) => {
+                    self.notifications.success("Mise à jour", format!("Téléchargée dans {}", path.display()));
+                    self.update_download_result = Some(Ok(path));
+                    finished = true;
+                }
+                DownloadEvent::Failed(error) => {
+                    self.notifications.error("Mise à jour", format!("Téléchargement échoué : {}", error));
+                    self.update_download_result = Some(Err(error));
+                    finished = true;
+                }
+            }
+        }
+        if finished {
+            self.update_download = None;
+            self.update_download_progress = None;
+        }
+    }
+
+    /// Stop notifying the available release; a later one is notified again
+    pub fn ignore_available_update(&mut self) {
+        if let Some(UpdateCheck::Available(release)) = &self.update_check {
+            tracing::info!("🔄 Version {} ignorée", release.tag);
+            self.ignored_update_version = Some(release.tag.clone());
+            self.save_settings();
+        }
+    }
+
+    /// Reactivate the plan saved by `enter_gaming_mode`; kept on disk if that fails
+    fn restore_power_plan(&mut self) {
+        let Some(guid) = self.power_plan_to_restore.clone() else {
+            return;
+        };
+        match crate::power::set_active_plan(&guid) {
+            Ok(()) => {
+                self.power_plan_to_restore = None;
+                self.save_settings();
+            }
+            Err(e) => {
+                tracing::error!("❌ Restauration du plan d'alimentation {} impossible: {}", guid, e);
+                self.last_power_error = Some(e.to_string());
+            }
+        }
+    }
+
+    pub fn save_scheduler_config(&self) {
+        if let Err(e) = self.scheduler_config.save() {
+            tracing::error!("❌ Sauvegarde de la planification échouée: {}", e);
+        }
+    }
+
+    /// Look up which tasks have a copy in the Windows Task Scheduler
+    pub fn refresh_mirrored_tasks(&mut self) {
+        self.mirrored_tasks = Some(
+            self.scheduler_config
+                .tasks
+                .iter()
+                .filter(|task| task.run_when_closed && crate::scheduler::windows_tasks::exists(&task.id))
+                .map(|task| task.id.clone())
+                .collect(),
+        );
+    }
+
+    pub fn add_scheduled_task(&mut self, task_type: TaskType, rule: ScheduleRule) {
+        let task = ScheduledTask::new(task_type, rule);
+        tracing::info!("⏰ Tâche planifiée ajoutée: {} ({})", task.task_type.label(), task.schedule.describe());
+        self.scheduler_config.add_task(task);
+        self.save_scheduler_config();
+    }
+
+    pub fn remove_scheduled_task(&mut self, id: &str) {
+        if let Err(e) = crate::scheduler::windows_tasks::unregister(id) {
+            tracing::error!("❌ Suppression de la tâche Windows {} impossible: {}", id, e);
+            self.scheduler_error = Some(e.to_string());
+            return;
+        }
+        if let Some(mirrored) = &mut self.mirrored_tasks {
+            mirrored.remove(id);
+        }
+        self.scheduler_config.remove_task(id);
+        self.save_scheduler_config();
+    }
+
+    /// The Windows copy follows the in-app state, otherwise it would keep running
+    pub fn set_scheduled_task_enabled(&mut self, id: &str, enabled: bool) {
+        let Some(task) = self.scheduler_config.get_task_mut(id) else {
+            return;
+        };
+        if task.run_when_closed {
+            if let Err(e) = crate::scheduler::windows_tasks::set_enabled(id, enabled) {
+                tracing::error!("❌ Tâche Windows {} non modifiée: {}", id, e);
+                self.scheduler_error = Some(e.to_string());
+                return;
+            }
+        }
+        task.enabled = enabled;
+        if enabled && task.next_run.is_none() {
+            task.next_run = task.schedule.next_after(chrono::Local::now());
+        }
+        self.save_scheduler_config();
+    }
+
+    /// Register or delete the Windows copy of a task ("exécuter même si GameBooster est fermé")
+    pub fn set_scheduled_task_mirrored(&mut self, id: &str, mirrored: bool) {
+        let Some(task) = self.scheduler_config.get_task_mut(id) else {
+            return;
+        };
+        let result = if mirrored {
+            crate::scheduler::windows_tasks::register(task)
+        } else {
+            crate::scheduler::windows_tasks::unregister(id)
+        };
+        match result {
+            Ok(()) => {
+                task.run_when_closed = mirrored;
+                self.scheduler_error = None;
+                let mirrored_tasks = self.mirrored_tasks.get_or_insert_with(HashSet::new);
+                if mirrored {
+                    mirrored_tasks.insert(id.to_string());
+                } else {
+                    mirrored_tasks.remove(id);
+                }
+                self.save_scheduler_config();
+            }
+            Err(e) => {
+                tracing::error!("❌ Copie de la tâche {} dans le Planificateur Windows impossible: {}", id, e);
+                self.scheduler_error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// Run the due tasks that Windows does not run for us, one at a time
+    pub fn run_due_scheduled_tasks(&mut self) {
+        if let Some((id, promise)) = self.scheduled_task_promise.take() {
+            match promise.try_take() {
+                Ok(result) => {
+                    match &result {
+                        Ok(message) => {
+                            tracing::info!("⏰ Tâche {} exécutée: {}", id, message);
+                            self.notifications.info("Planificateur", format!("Tâche {} : {}", id, message));
+                        }
+                        Err(e) => {
+                            tracing::error!("❌ Tâche {} échouée: {}", id, e);
+                            self.notifications.error_chain("Planificateur", &format!("Tâche {} échouée", id), e);
+                        }
+                    }
+                    if let Some(task) = self.scheduler_config.get_task_mut(&id) {
+                        let now = chrono::Local::now();
+                        task.last_run = Some(now);
+                        task.next_run = task.schedule.next_after(now);
+                    }
+                    self.save_scheduler_config();
+                }
+                Err(promise) => self.scheduled_task_promise = Some((id, promise)),
+            }
+            return;
+        }
+
+        let startup_pending = !self.startup_tasks_done;
+        let due = self.scheduler_config.tasks.iter().find(|task| {
+            if !task.enabled || task.run_when_closed {
+                return false;
+            }
+            match task.schedule {
+                ScheduleRule::OnStartup => startup_pending && !matches!(task.last_run, Some(run) if run >= self.launched_at),
+                _ => crate::scheduler::task::is_task_due(task),
+            }
+        });
+        let Some(task) = due.cloned() else {
+            self.startup_tasks_done = true;
+            return;
+        };
+
+        tracing::info!("⏰ Exécution de la tâche planifiée {} ({})", task.id, task.task_type.label());
+        let id = task.id.clone();
+        self.scheduled_task_promise = Some((id, Promise::spawn_thread("scheduled_task", move || {
+            tokio::runtime::Runtime::new()?.block_on(crate::scheduler::task::execute_task(&task))
+        })));
+    }
+
+    /// Start, restart or stop the launcher watcher to follow its settings, then report the
+    /// cleanings it ran
+    pub fn update_launcher_watcher(&mut self) {
+        let config = &self.launcher_watcher_config;
+        let outdated = self.launcher_watcher.as_ref().is_some_and(|watcher| watcher.config() != config);
+        if !config.enabled || outdated {
+            self.launcher_watcher = None;
+        }
+        if config.enabled && self.launcher_watcher.is_none() {
+            self.launcher_watcher = Some(LauncherWatcher::start(config.clone()));
+        }
+
+        let events = self.launcher_watcher.as_ref().map(LauncherWatcher::poll_events).unwrap_or_default();
+        for event in events {
+            if let (LauncherTrigger::GameLaunch(game), Some(session)) = (&event.trigger, self.game_session.as_mut()) {
+                session.record_game(game);
+            }
+            match &event.cleaning {
+                Ok(results) => {
+                    self.notifications.success("Lanceurs", event.summary());
+                    self.record_history(HistoryEntry::from_ram(results));
+                }
+                Err(_) => self.notifications.error("Lanceurs", event.summary()),
+            }
+            if let Some(Err(e)) = &event.standby_purge {
+                self.notifications.warning("Lanceurs", format!("Purge de la liste standby impossible : {}", e));
+            }
+        }
+    }
+
+    /// Register, register again or release the global hotkey to follow its settings
+    pub fn update_global_hotkey(&mut self, ctx: &egui::Context) {
+        let wanted = self.hotkey_config.enabled.then(|| self.hotkey_config.combination.clone());
+        if wanted == self.hotkey_applied {
+            return;
+        }
+        // L'ancienne combinaison est libérée avant d'enregistrer la nouvelle
+        self.global_hotkey = None;
+        self.hotkey_error = None;
+        self.hotkey_applied = wanted.clone();
+        let (Some(combination), Some(sender)) = (wanted, self.command_sender.clone()) else {
+            return;
+        };
+        let ctx = ctx.clone();
+        let registered = Hotkey::parse(&combination)
+            .and_then(|hotkey| GlobalHotkey::register(hotkey, sender, move || ctx.request_repaint()));
+        match registered {
+            Ok(hotkey) => self.global_hotkey = Some(hotkey),
+            Err(e) => {
+                tracing::warn!("⚠️ Raccourci global non enregistré: {}", e);
+                self.notifications.warning("Raccourci", format!("Raccourci global indisponible : {}", e));
+                self.hotkey_error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// Use the combination typed in the settings; applying the same one again retries it
+    /// after a conflict
+    pub fn apply_hotkey_draft(&mut self) {
+        match Hotkey::parse(&self.hotkey_draft) {
+            Ok(hotkey) => {
+                self.hotkey_config.combination = hotkey.to_string();
+                self.hotkey_draft = self.hotkey_config.combination.clone();
+                self.hotkey_applied = None;
+            }
+            Err(e) => self.hotkey_error = Some(e.to_string()),
+        }
+    }
+
+    /// Cleaning asked by the global hotkey, even minimized
+    fn start_hotkey_cleaning(&mut self) {
+        if self.cleaning_promise.is_some() || self.hotkey_cleaning.is_some() {
+            tracing::info!("⌨️ Nettoyage déjà en cours, raccourci ignoré");
+            return;
+        }
+        if !self.can_run(crate::memory::requires_elevation()) {
+            self.notifications.warning("Raccourci", ELEVATION_REQUIRED_HINT);
+            return;
+        }
+        let purge_standby = self.hotkey_config.purge_standby && self.is_elevated;
+        self.hotkey_cleaning = Some(Promise::spawn_thread("hotkey_clean", move || HotkeyCleaning::run(purge_standby)));
+    }
+
+    /// Report the cleaning of the hotkey, with a Windows notification: the game keeps the
+    /// window out of sight
+    fn poll_hotkey_cleaning(&mut self, ctx: &egui::Context) {
+        let Some(promise) = self.hotkey_cleaning.take() else {
+            return;
+        };
+        let outcome = match promise.try_take() {
+            Ok(outcome) => outcome,
+            Err(promise) => {
+                self.hotkey_cleaning = Some(promise);
+                ctx.request_repaint_after(std::time::Duration::from_millis(250));
+                return;
+            }
+        };
+        let summary = outcome.summary();
+        match &outcome.cleaning {
+            Ok(results) => {
+                self.notifications.success("Raccourci", summary.clone());
+                self.record_history(HistoryEntry::from_ram(results));
+                self.previous_cleaned_results = self.last_cleaned_results.replace(results.clone());
+            }
+            Err(_) => self.notifications.error("Raccourci", summary.clone()),
+        }
+        if let Some(Err(e)) = &outcome.standby_purge {
+            self.notifications.warning("Raccourci", format!("Purge de la liste standby impossible : {}", e));
+        }
+        let clicked = self.toast_clicked.clone();
+        let ctx = ctx.clone();
+        let shown = system_toast::show("GameBooster", &summary, move || {
+            clicked.store(true, Ordering::Relaxed);
+            ctx.request_repaint();
+        });
+        if let Err(e) = shown {
+            tracing::warn!("⚠️ Notification Windows impossible: {}", e);
+        }
+    }
+
+    /// Ctrl+1 to Ctrl+6 switch tabs, Ctrl+R scans the network, Ctrl+M cleans the memory
+    fn handle_shortcuts(&mut self, ctx: &egui::Context, network_available: bool) {
+        const TAB_KEYS: [(egui::Key, Tab); 6] = [
+            (egui::Key::Num1, Tab::Memory),
+            (egui::Key::Num2, Tab::Optimization),
+            (egui::Key::Num3, Tab::Services),
+            (egui::Key::Num4, Tab::Scheduler),
+            (egui::Key::Num5, Tab::Network),
+            (egui::Key::Num6, Tab::Settings),
+        ];
+        let (tab, rescan, clean) = ctx.input_mut(|input| {
+            let tab = TAB_KEYS
+                .iter()
+                .find(|(key, _)| input.consume_key(egui::Modifiers::COMMAND, *key))
+                .map(|(_, tab)| *tab);
+            (tab, input.consume_key(egui::Modifiers::COMMAND, egui::Key::R), input.consume_key(egui::Modifiers::COMMAND, egui::Key::M))
+        });
+        if let Some(tab) = tab {
+            self.active_tab = tab;
+        }
+        if rescan && network_available {
+            self.scan_network_processes();
+        }
+        if clean && self.can_run(crate::memory::requires_elevation()) {
+            self.start_memory_cleaning();
+        }
+    }
+
+    /// Remember where the window is, saved with the settings. A minimized window reports a
+    /// meaningless position and a maximized one the size of the monitor: the last normal
+    /// geometry is kept instead.
+    fn track_window_geometry(&mut self, ctx: &egui::Context) {
+        let (outer_rect, inner_rect, maximized, minimized, pixels_per_point) = ctx.input(|i| {
+            let viewport = i.viewport();
+            (viewport.outer_rect, viewport.inner_rect, viewport.maximized, viewport.minimized, viewport.native_pixels_per_point)
+        });
+        if minimized == Some(true) {
+            return;
+        }
+        self.window_maximized = maximized == Some(true);
+        if self.window_maximized {
+            return;
+        }
+        self.window_size = inner_rect.map(|rect| rect.size()).unwrap_or_else(|| ctx.screen_rect().size());
+        if let Some(outer_rect) = outer_rect {
+            self.window_position = Some(outer_rect.min);
+        }
+        if let Some(pixels_per_point) = pixels_per_point {
+            self.window_pixels_per_point = pixels_per_point;
+        }
+    }
+
+    /// Store a completed cleaning in the history file and the in-memory list
+    pub fn record_history(&mut self, entry: HistoryEntry) {
+        if let Err(e) = crate::history::append(&entry) {
+            tracing::error!("❌ Écriture de l'historique impossible: {}", e);
+        }
+        if let (HistoryKind::Ram, Some(session)) = (entry.kind, self.game_session.as_mut()) {
+            session.record_cleaning(entry.bytes_freed);
+        }
+        self.history.push(entry);
+        let excess = self.history.len().saturating_sub(crate::history::MAX_ENTRIES);
+        self.history.drain(..excess);
+    }
+
+    pub fn start_duplicate_scan(&mut self) {
+        self.duplicate_groups = None;
+        self.duplicate_selection.clear();
+        self.duplicate_scan = Some(crate::disk::duplicates::scan_duplicates(
+            self.duplicate_scan_paths.clone(),
+            self.duplicate_min_size_mb * 1024 * 1024,
+        ));
+    }
+
+    /// Collect the finished scan and deletion; a deletion is recorded as a disk cleaning
+    pub fn poll_duplicates(&mut self) {
+        if let Some(scan) = self.duplicate_scan.take() {
+            match scan.promise.try_take() {
+                Ok(groups) => self.duplicate_groups = Some(groups),
+                Err(promise) => {
+                    self.duplicate_scan = Some(DuplicateScan {
+                        promise,
+                        progress: scan.progress,
+                    })
+                }
+            }
+        }
+
+        if let Some(promise) = self.duplicate_delete_promise.take() {
+            match promise.try_take() {
+                Ok(freed) => {
+                    let deleted: HashSet<PathBuf> = self.duplicate_selection.iter().filter(|path| !path.exists()).cloned().collect();
+                    let mut results = DiskCleaningResults::new();
+                    results.duplicates_cleaned = freed;
+                    results.total_space_freed = freed;
+                    results.files_processed = deleted.len() as u32;
+                    results.complete();
+                    self.record_history(HistoryEntry::from_disk(&results));
+                    self.last_disk_cleaned_results = Some(results);
+
+                    // Les groupes réduits à un seul fichier ne sont plus des doublons
+                    if let Some(groups) = self.duplicate_groups.as_mut() {
+                        for group in groups.iter_mut() {
+                            group.files.retain(|path| !deleted.contains(path));
+                        }
+                        groups.retain(|group| group.files.len() > 1);
+                    }
+                    self.duplicate_selection.clear();
+                }
+                Err(promise) => self.duplicate_delete_promise = Some(promise),
+            }
+        }
+    }
+
+    pub fn delete_selected_duplicates(&mut self) {
+        let (Some(groups), false) = (self.duplicate_groups.clone(), self.duplicate_selection.is_empty()) else {
+            return;
+        };
+        let selection = self.duplicate_selection.clone();
+        self.duplicate_delete_promise = Some(Promise::spawn_thread("duplicate_delete", move || {
+            crate::disk::duplicates::delete_duplicates(&groups, &selection)
+        }));
+    }
+
+    pub fn start_space_analysis(&mut self) {
+        let Some(root) = self.space_root.clone() else {
+            return;
+        };
+        self.space_analysis = None;
+        self.space_analysis_job = Some(crate::disk::space_analyzer::start_analysis(
+            root,
+            crate::disk::space_analyzer::DEFAULT_MAX_DEPTH,
+        ));
+    }
+
+    /// Collect the finished (or cancelled) analysis
+    pub fn poll_space_analysis(&mut self) {
+        if let Some(job) = self.space_analysis_job.take() {
+            match job.promise.try_take() {
+                Ok(analysis) => self.space_analysis = Some(analysis),
+                Err(promise) => {
+                    self.space_analysis_job = Some(SpaceAnalysisJob {
+                        root: job.root,
+                        promise,
+                        progress: job.progress,
+                    })
+                }
+            }
+        }
+    }
+
+    /// List the installed games in the background (sizes may have to be measured)
+    pub fn start_game_library_scan(&mut self) {
+        if self.game_library_promise.is_none() {
+            self.game_library_promise = Some(Promise::spawn_thread("game_libraries", crate::disk::game_libraries::scan));
+        }
+    }
+
+    pub fn poll_game_library_scan(&mut self) {
+        if let Some(promise) = self.game_library_promise.take() {
+            match promise.try_take() {
+                Ok(scan) => self.game_library = Some(scan),
+                Err(promise) => self.game_library_promise = Some(promise),
+            }
+        }
+    }
+
+    /// List the autostart entries in the background (the scheduled tasks query is slow)
+    pub fn refresh_startup_entries(&mut self) {
+        if self.startup_promise.is_none() {
+            self.startup_promise = Some(Promise::spawn_thread("startup_entries", crate::optimization::startup_manager::list_entries));
+        }
+    }
+
+    pub fn poll_startup_entries(&mut self) {
+        if let Some(promise) = self.startup_promise.take() {
+            match promise.try_take() {
+                Ok(result) => self.startup_entries = Some(result.map_err(|e| e.to_string())),
+                Err(promise) => self.startup_promise = Some(promise),
+            }
+        }
+    }
+
+    pub fn refresh_drives(&mut self) {
+        self.drives = Some(crate::disk::drive_info::list_drives().map_err(|e| e.to_string()));
+    }
+
+    /// Retrim or analyze `drive` in the background
+    pub fn start_drive_optimization(&mut self, drive: &DriveInfo) {
+        if self.drive_optimize_promise.is_some() {
+            return;
+        }
+        let target = drive.clone();
+        self.drive_optimize_promise = Some((
+            drive.letter.clone(),
+            Promise::spawn_thread("drive_optimize", move || crate::disk::drive_info::optimize(&target)),
+        ));
+    }
+
+    pub fn poll_drive_optimization(&mut self) {
+        let Some((letter, promise)) = self.drive_optimize_promise.take() else {
+            return;
+        };
+        match promise.try_take() {
+            Ok(Ok(report)) => {
+                tracing::info!("✅ Optimisation du lecteur {} terminée", letter);
+                let message = report.recommendation.clone().unwrap_or_else(|| "Analyse terminée".to_string());
+                self.notifications.success("Disque", format!("{} : {}", letter, message));
+                self.last_drive_report = Some(Ok(report));
+                self.refresh_drives();
+            }
+            Ok(Err(e)) => {
+                tracing::error!("❌ Optimisation du lecteur {} échouée: {}", letter, e);
+                self.notifications.error_chain("Disque", &format!("Optimisation du lecteur {} impossible", letter), &e);
+                self.last_drive_report = Some(Err(e.to_string()));
+            }
+            Err(promise) => self.drive_optimize_promise = Some((letter, promise)),
+        }
+    }
+
+    /// Switch an entry on or off, then show the state read back from the registry
+    pub fn set_startup_entry_enabled(&mut self, entry: &StartupEntry, enabled: bool) {
+        use crate::optimization::startup_manager;
+
+        self.startup_entry_error = startup_manager::set_enabled(entry, enabled).err().map(|e| e.to_string());
+        if let Some(e) = &self.startup_entry_error {
+            tracing::error!("❌ {}", e);
+        }
+        let state = match startup_manager::read_enabled(entry) {
+            Ok(state) => state,
+            Err(e) => {
+                tracing::warn!("⚠️ Relecture de l'état de {} impossible: {}", entry.name, e);
+                return;
+            }
+        };
+        if let Some(Ok(entries)) = self.startup_entries.as_mut() {
+            for listed in entries.iter_mut().filter(|listed| listed.location == entry.location && listed.id == entry.id) {
+                listed.enabled = state;
+            }
+        }
+    }
+
+    /// Snapshot of the persisted settings
+    pub fn to_config(&self) -> AppConfig {
+        AppConfig {
+            theme: self.theme.name.to_string(),
+            accent_color: self.accent_color.map(|c| [c.r(), c.g(), c.b()]),
+            last_tab: self.active_tab.as_arg().to_string(),
+            disk_options: self.disk_options.clone(),
+            hosts_profile: self.hosts_profile.clone(),
+            selected_services: self.selected_services.clone(),
+            service_modes: self.service_modes.clone(),
+            manage_service_dependencies: self.manage_service_dependencies,
+            default_speed_limit: self.speed_limit_value.to_string(),
+            default_speed_limit_unit: self.speed_limit_unit,
+            limit_preferences: self.limit_preferences.clone(),
+            exited_limit_action: self.exited_limit_action,
+            exited_limit_grace_secs: self.exited_limit_grace_secs,
+            high_precision_network: self.high_precision_network,
+            verify_network_limits: self.verify_limits,
+            show_idle_network_processes: self.show_idle_network_processes,
+            ping_host: self.ping_host.clone(),
+            ping_on_metered: self.ping_on_metered,
+            network_refresh_secs: self.network_refresh_secs,
+            duplicate_scan_paths: self.duplicate_scan_paths.clone(),
+            simulation_mode: self.simulation_mode,
+            skipped_confirmations: self.skipped_confirmations.clone(),
+            auto_clean: self.auto_clean.clone(),
+            memory_alert: self.memory_alert_config.clone(),
+            window: crate::config::WindowConfig {
+                width: self.window_size.x,
+                height: self.window_size.y,
+                position: self.window_position.map(|position| [position.x, position.y]),
+                pixels_per_point: self.window_pixels_per_point,
+                maximized: self.window_maximized,
+            },
+            defender_reenable_at: self.defender_reenable_at,
+            gaming_power_plan: self.gaming_power_plan.clone(),
+            power_plan_to_restore: self.power_plan_to_restore.clone(),
+            gaming_audio_enhancements_off: self.gaming_audio_enhancements_off,
+            gaming_mmcss_priority: self.gaming_mmcss_priority,
+            services_to_restart: self.services_to_restart.clone(),
+            services_start_types: self.services_start_types.clone(),
+            boost_profiles: self.boost_profiles.clone(),
+            applied_profile: self.applied_profile.clone(),
+            launcher_watcher: self.launcher_watcher_config.clone(),
+            hotkey: self.hotkey_config.clone(),
+            log_level: self.log_level,
+            log_retention_days: self.log_retention_days,
+            check_updates: self.check_updates,
+            check_updates_on_metered: self.check_updates_on_metered,
+            ignored_update_version: self.ignored_update_version.clone(),
+            default_profile: self.default_profile.clone(),
+            onboarding_completed: self.onboarding_completed,
+        }
+    }
+
+    pub fn save_settings(&self) {
+        if self.settings_removed {
+            tracing::info!("💾 Paramètres supprimés par le nettoyage : pas de sauvegarde");
+            return;
+        }
+        match self.to_config().save() {
+            Ok(()) => tracing::info!("💾 Paramètres sauvegardés dans {}", AppConfig::path().display()),
+            Err(e) => tracing::error!("❌ Sauvegarde des paramètres échouée: {}", e),
+        }
+    }
+
+    /// Restore default settings and rewrite the settings file
+    pub fn reset_settings(&mut self, ctx: &egui::Context) {
+        // Une pause Defender, un plan d'alimentation, des services ou un profil à rétablir ne sont pas des paramètres : ils sont conservés
+        let defaults = AppConfig {
+            defender_reenable_at: self.defender_reenable_at,
+            power_plan_to_restore: self.power_plan_to_restore.clone(),
+            services_to_restart: self.services_to_restart.clone(),
+            services_start_types: self.services_start_types.clone(),
+            applied_profile: self.applied_profile.clone(),
+            // Un retour aux valeurs par défaut ne rouvre pas l'assistant
+            onboarding_completed: self.onboarding_completed,
+            ..AppConfig::default()
+        };
+        self.accent_color = None;
+        self.theme = theme::theme_by_name(&defaults.theme);
+        self.disk_options = defaults.disk_options.clone();
+        self.hosts_profile = defaults.hosts_profile.clone();
+        self.selected_services = defaults.selected_services.clone();
+        self.service_modes = defaults.service_modes.clone();
+        self.manage_service_dependencies = defaults.manage_service_dependencies;
+        self.speed_limit_value = defaults.default_speed_limit.parse().unwrap_or(1.0);
+        self.speed_limit_unit = defaults.default_speed_limit_unit;
+        self.limit_preferences = defaults.limit_preferences.clone();
+        self.exited_limit_action = defaults.exited_limit_action;
+        self.exited_limit_grace_secs = defaults.exited_limit_grace_secs;
+        self.apply_exited_limit_handling();
+        self.verify_limits = defaults.verify_network_limits;
+        self.show_idle_network_processes = defaults.show_idle_network_processes;
+        if self.high_precision_network != defaults.high_precision_network {
+            self.high_precision_network = defaults.high_precision_network;
+            if let Some(ref mut limiter) = self.network_limiter {
+                limiter.set_high_precision(self.high_precision_network);
+            }
+        }
+        self.ping_host = defaults.ping_host.clone();
+        self.ping_on_metered = defaults.ping_on_metered;
+        self.network_refresh_secs = defaults.network_refresh_secs;
+        self.duplicate_scan_paths = defaults.duplicate_scan_paths.clone();
+        self.simulation_mode = defaults.simulation_mode;
+        self.boost_profiles = defaults.boost_profiles.clone();
+        self.default_profile = defaults.default_profile.clone();
+        self.editing_profile = None;
+        self.skipped_confirmations = defaults.skipped_confirmations.clone();
+        self.auto_clean = defaults.auto_clean.clone();
+        self.memory_alert_config = defaults.memory_alert.clone();
+        self.gaming_power_plan = defaults.gaming_power_plan.clone();
+        self.gaming_audio_enhancements_off = defaults.gaming_audio_enhancements_off;
+        self.gaming_mmcss_priority = defaults.gaming_mmcss_priority;
+        self.launcher_watcher_config = defaults.launcher_watcher.clone();
+        self.hotkey_config = defaults.hotkey.clone();
+        self.hotkey_draft = self.hotkey_config.combination.clone();
+        self.set_log_level(defaults.log_level);
+        self.log_retention_days = defaults.log_retention_days;
+        self.check_updates = defaults.check_updates;
+        self.check_updates_on_metered = defaults.check_updates_on_metered;
+        self.ignored_update_version = defaults.ignored_update_version.clone();
+        ctx.set_visuals(self.theme.visuals.clone());
+
+        // La taille de fenêtre par défaut s'applique au prochain lancement
+        match defaults.save() {
+            Ok(()) => tracing::info!("♻️ Paramètres réinitialisés"),
+            Err(e) => tracing::error!("❌ Réinitialisation des paramètres échouée: {}", e),
+        }
+    }
+
+    /// Run `action`, behind a confirmation dialog when it is destructive and the user did not
+    /// ask to skip it. Nothing is confirmed in simulation mode, where nothing is modified.
+    pub fn request_action(&mut self, action: PendingAction) {
+        if self.needs_confirmation(action) {
+            self.pending_confirmation = Some((action, self.confirmation_dialog(action)));
+        } else {
+            self.run_action(action);
+        }
+    }
+
+    fn needs_confirmation(&self, action: PendingAction) -> bool {
+        if self.skipped_confirmations.contains(action.key()) {
+            return false;
+        }
+        match action {
+            PendingAction::CleanDisk => {
+                !self.simulation_mode
+                    && (self.disk_options.clean_recycle_bin
+                        || self.disk_options.clean_system_cache
+                        || self.restarts_explorer())
+            }
+            PendingAction::OptimizeServices => {
+                !self.simulation_mode
+                    && crate::services::gaming_services::GAMING_SERVICES.iter().any(|service| {
+                        service.name == "wuauserv"
+                            && self.is_service_selected(service)
+                            && self.service_mode(service.name) == ServiceMode::Durable
+                    })
+            }
+            PendingAction::DisableDefender
+            | PendingAction::PauseDefender
+            | PendingAction::ResetNetworkRules
+            | PendingAction::ApplyProfile
+            | PendingAction::LimitAllExceptSelected
+            | PendingAction::UninstallCleanup
+            | PendingAction::ProtectedProcess
+            | PendingAction::TerminateProcess
+            | PendingAction::ShutdownWsl => true,
+        }
+    }
+
+    /// The disk cleaning may stop Explorer to delete the locked thumbnails
+    fn restarts_explorer(&self) -> bool {
+        self.disk_options.clean_thumbnails && self.disk_options.locked_thumbnails == LockedThumbnails::RestartExplorer
+    }
+
+    fn confirmation_dialog(&self, action: PendingAction) -> ConfirmDialog {
+        match action {
+            PendingAction::CleanDisk => {
+                let mut consequences = Vec::new();
+                if self.disk_options.clean_recycle_bin || self.disk_options.clean_system_cache {
+                    consequences.push("Le contenu de la corbeille et/ou du cache système sera supprimé sans possibilité de restauration.");
+                }
+                if self.restarts_explorer() {
+                    consequences.push("L'Explorateur sera arrêté puis relancé si des miniatures sont verrouillées : la barre des tâches disparaîtra quelques secondes.");
+                }
+                ConfirmDialog::new("Nettoyage définitif")
+                    .message(consequences.join("\n"))
+                    .confirm_label("🧹 Nettoyer")
+                    .remember_choice()
+            }
+            PendingAction::DisableDefender => ConfirmDialog::new("Désactiver Windows Defender")
+                .message("La protection en temps réel restera désactivée jusqu'à sa réactivation manuelle : le PC ne sera plus protégé contre les logiciels malveillants.")
+                .danger(DangerLevel::Critical)
+                .confirm_label("❌ Désactiver"),
+            PendingAction::PauseDefender => ConfirmDialog::new("Mettre Defender en pause")
+                .message(format!(
+                    "La protection en temps réel sera désactivée pendant {} min, puis réactivée automatiquement.",
+                    self.defender_pause_minutes
+                ))
+                .danger(DangerLevel::Critical)
+                .confirm_label("⏸️ Mettre en pause"),
+            PendingAction::ResetNetworkRules => ConfirmDialog::new("Tout réinitialiser")
+                .message("Toutes les limitations, la limite globale et les blocages pare-feu créés par GameBooster seront supprimés.")
+                .confirm_label("♻️ Réinitialiser")
+                .remember_choice(),
+            PendingAction::OptimizeServices => ConfirmDialog::new("Désactiver Windows Update")
+                .message("Windows Update restera désactivé après un redémarrage : aucune mise à jour, y compris de sécurité, ne sera installée tant que les services ne sont pas restaurés.")
+                .danger(DangerLevel::Critical)
+                .confirm_label("⏹️ Désactiver"),
+            PendingAction::ApplyProfile => {
+                let profile = self.profile_to_apply.as_ref();
+                ConfirmDialog::new(format!("Profil {}", profile.map(|p| p.name.as_str()).unwrap_or_default()))
+                    .message(format!(
+                        "Ce profil met la protection en temps réel de Defender en pause pendant {} min.",
+                        profile.and_then(|p| p.defender_pause_minutes).unwrap_or_default()
+                    ))
+                    .danger(DangerLevel::Critical)
+                    .confirm_label("🚀 Boost")
+            }
+            PendingAction::LimitAllExceptSelected => {
+                let preview = self.limit_all_preview.clone().unwrap_or_default();
+                let exe_names = preview.exe_names();
+                let mut message = format!(
+                    "{} processus ({} exécutable(s)) seront limités à {} :\n{}",
+                    preview.targets.len(),
+                    exe_names.len(),
+                    self.speed_limit_kbps().map(crate::network::describe_speed_limit).unwrap_or_default(),
+                    preview_list(&exe_names)
+                );
+                message.push_str(&format!("\n\nÉpargnés : {}", preview.selected_exe_names.join(", ")));
+                if !preview.protected.is_empty() {
+                    message.push_str(&format!("\nProcessus système protégés : {}", preview.protected.join(", ")));
+                }
+                ConfirmDialog::new("Limiter tout le reste")
+                    .message(message)
+                    .confirm_label("⚡ Limiter")
+            }
+            PendingAction::UninstallCleanup => {
+                let preview = self.cleanup_preview.clone().unwrap_or_default();
+                let mut message = String::from("Voici ce qui sera supprimé :");
+                let mut category = None;
+                for item in &preview.items {
+                    if category != Some(item.category) {
+                        category = Some(item.category);
+                        message.push_str(&format!("\n{}", item.category.label()));
+                    }
+                    message.push_str(&format!("\n  • {}", item.description));
+                }
+                if !self.is_elevated && preview.items.iter().any(|item| item.category.requires_elevation()) {
+                    message.push_str("\n\nSans droits administrateur, les politiques QoS, règles pare-feu, Defender, services, optimisations Windows et fichier hosts seront laissés en place.");
+                }
+                ConfirmDialog::new("Tout nettoyer avant désinstallation")
+                    .message(message)
+                    .danger(DangerLevel::Critical)
+                    .confirm_label("🧹 Tout supprimer")
+            }
+            PendingAction::ProtectedProcess => {
+                let (title, verb) = match self.protected_action {
+                    Some(ProtectedAction::Block(_)) => ("Bloquer un processus système", "⛔ Bloquer quand même"),
+                    Some(ProtectedAction::Trim { .. }) => ("Vider la mémoire d'un processus système", "✂ Vider quand même"),
+                    _ => ("Limiter un processus système", "⚡ Limiter quand même"),
+                };
+                let processes = self
+                    .protected_processes()
+                    .into_iter()
+                    .map(|(name, reason)| format!("  • {} : {}", name, reason))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                ConfirmDialog::new(title)
+                    .message(format!(
+                        "Windows ou la session utilisateur dépendent de :\n{}\n\nLe système peut ralentir ou mal fonctionner tant que l'action est en place.",
+                        processes
+                    ))
+                    .confirm_label(verb)
+            }
+            PendingAction::TerminateProcess => {
+                let Some(request) = self.process_to_terminate.as_ref() else {
+                    return ConfirmDialog::new("Terminer le processus");
+                };
+                let mut message = format!(
+                    "{} (PID {}) sera invité à se fermer, puis arrêté de force s'il tourne encore après {} s. Les données non enregistrées seront perdues.",
+                    request.name,
+                    request.pid,
+                    TERMINATE_GRACE_MS / 1000
+                );
+                if request.restart {
+                    match &request.exe_path {
+                        Some(path) => message.push_str(&format!("\n\nIl sera ensuite relancé : {}", path.display())),
+                        None => message.push_str("\n\nChemin de l'exécutable inconnu : il ne pourra pas être relancé."),
+                    }
+                }
+                let mut dialog = ConfirmDialog::new(if request.restart { "Redémarrer le processus" } else { "Terminer le processus" })
+                    .confirm_label(if request.restart { "🔄 Redémarrer" } else { "🛑 Terminer" });
+                if request.protection.is_protected() {
+                    let reason = safety::protection_reason(request.pid, &request.name, request.exe_path.as_deref());
+                    message.push_str(&format!("\n\nProcessus système ({}) : la session peut devenir instable.", reason));
+                    dialog = dialog.danger(DangerLevel::Critical);
+                }
+                dialog.message(message)
+            }
+            PendingAction::ShutdownWsl => {
+                let distros = self.wsl_status.as_ref().map(|status| status.running_distros.clone()).unwrap_or_default();
+                let running = if distros.is_empty() {
+                    "Toutes les distributions WSL en cours".to_string()
+                } else {
+                    format!("Les distributions en cours ({})", distros.join(", "))
+                };
+                ConfirmDialog::new("Compacter la mémoire WSL")
+                    .message(format!(
+                        "{} seront arrêtées, Docker Desktop compris : les programmes qui y tournent perdront leur travail non enregistré. La mémoire de la machine virtuelle est ensuite rendue à Windows.",
+                        running
+                    ))
+                    .confirm_label("🐧 Arrêter WSL")
+                    .remember_choice()
+            }
+        }
+    }
+
+    fn run_action(&mut self, action: PendingAction) {
+        match action {
+            PendingAction::CleanDisk => self.start_disk_cleaning(),
+            PendingAction::DisableDefender => self.spawn_defender_action(false),
+            PendingAction::PauseDefender => self.pause_defender(),
+            PendingAction::ResetNetworkRules => self.reset_all_network_rules(),
+            PendingAction::OptimizeServices => self.start_services_optimization(),
+            PendingAction::ApplyProfile => {
+                if let Some(profile) = self.profile_to_apply.take() {
+                    self.apply_profile(profile);
+                }
+            }
+            PendingAction::LimitAllExceptSelected => self.limit_all_except_selected(),
+            PendingAction::UninstallCleanup => self.start_cleanup(false),
+            PendingAction::ProtectedProcess => {
+                if let Some(action) = self.protected_action.take() {
+                    self.run_protected(action);
+                }
+            }
+            PendingAction::TerminateProcess => {
+                if let Some(request) = self.process_to_terminate.take() {
+                    self.start_termination(request);
+                }
+            }
+            PendingAction::ShutdownWsl => self.start_wsl_task(Some(WslAction::Shutdown)),
+        }
+    }
+
+    /// Run `action` then read the WSL status again, on a worker thread; `None` only reads it
+    pub fn start_wsl_task(&mut self, action: Option<WslAction>) {
+        if self.wsl_promise.is_some() {
+            return;
+        }
+        self.wsl_promise = Some(Promise::spawn_thread("wsl", move || {
+            let runner = crate::system::default_runner();
+            let outcome = action.map(|action| (action, action.run(runner.as_ref()).map_err(|e| format!("{:#}", e))));
+            (outcome, wsl::query_status(runner.as_ref()))
+        }));
+    }
+
+    fn poll_wsl_task(&mut self) {
+        let Some(promise) = self.wsl_promise.take() else {
+            return;
+        };
+        match promise.try_take() {
+            Ok((outcome, status)) => {
+                match outcome {
+                    Some((_, Ok(message))) => self.notifications.success("WSL", message),
+                    Some((action, Err(e))) => {
+                        let title = match action {
+                            WslAction::Shutdown => "Arrêt de WSL impossible",
+                            WslAction::EnableAutoMemoryReclaim => "Activation d'autoMemoryReclaim impossible",
+                        };
+                        self.notifications.error("WSL", format!("{} : {}", title, e));
+                    }
+                    None => {}
+                }
+                self.wsl_status = Some(status);
+                // vmmem disparaît ou rétrécit après l'arrêt
+                self.process_memory_last_refresh = None;
+            }
+            Err(promise) => self.wsl_promise = Some(promise),
+        }
+    }
+
+    fn draw_confirmation(&mut self, ctx: &egui::Context) {
+        let Some((action, dialog)) = self.pending_confirmation.as_mut() else {
+            return;
+        };
+        let action = *action;
+        match dialog.show(ctx, &self.theme) {
+            ConfirmOutcome::Pending => {}
+            ConfirmOutcome::Confirmed { remember } => {
+                self.pending_confirmation = None;
+                if remember {
+                    self.skipped_confirmations.insert(action.key().to_string());
+                }
+                self.run_action(action);
+            }
+            ConfirmOutcome::Cancelled => {
+                tracing::info!("↩️ Action {} annulée", action.key());
+                self.pending_confirmation = None;
+                self.profile_to_apply = None;
+                self.limit_all_preview = None;
+                self.cleanup_preview = None;
+                self.protected_action = None;
+                self.process_to_terminate = None;
+            }
+        }
+    }
+
+    /// Run `action`, after a confirmation when it targets a cautious process. Critical
+    /// processes are refused with an explanation by the network limiter and `trim_process`.
+    pub fn request_protected(&mut self, action: ProtectedAction) {
+        let cautious = self
+            .protected_processes_for(&action)
+            .iter()
+            .any(|(level, _, _)| *level == ProtectionLevel::Cautious);
+        if cautious {
+            self.protected_action = Some(action);
+            self.request_action(PendingAction::ProtectedProcess);
+        } else {
+            self.run_protected(action);
+        }
+    }
+
+    fn run_protected(&mut self, action: ProtectedAction) {
+        match action {
+            ProtectedAction::Limit(pid) => self.limit_process(pid),
+            ProtectedAction::LimitAt { pid, kbps } => self.limit_process_at(pid, kbps),
+            ProtectedAction::LimitSelection => self.apply_speed_limit_to_selected(),
+            ProtectedAction::Block(pid) => self.set_process_blocked(pid, true),
+            ProtectedAction::Trim { pid, name } => self.trim_process(pid, &name),
+        }
+    }
+
+    /// (level, name, reason) of the protected processes `action` targets
+    fn protected_processes_for(&self, action: &ProtectedAction) -> Vec<(ProtectionLevel, String, &'static str)> {
+        let network = |pids: Vec<u32>| -> Vec<(ProtectionLevel, String, &'static str)> {
+            let Some(ref limiter) = self.network_limiter else {
+                return Vec::new();
+            };
+            limiter
+                .get_processes()
+                .into_iter()
+                .filter(|process| pids.contains(&process.pid) && process.protection.is_protected())
+                .map(|process| {
+                    let reason = safety::protection_reason(process.pid, &process.name, process.exe_path.as_deref());
+                    (process.protection, format!("{} (PID {})", process.name, process.pid), reason)
+                })
+                .collect()
+        };
+        match action {
+            ProtectedAction::Limit(pid) | ProtectedAction::LimitAt { pid, .. } | ProtectedAction::Block(pid) => network(vec![*pid]),
+            ProtectedAction::LimitSelection => network(self.processes.iter().copied().collect()),
+            ProtectedAction::Trim { pid, name } => {
+                let level = safety::is_protected_process(*pid, name, None);
+                if level.is_protected() {
+                    vec![(level, format!("{} (PID {})", name, pid), safety::protection_reason(*pid, name, None))]
+                } else {
+                    Vec::new()
+                }
+            }
+        }
+    }
+
+    /// (name, reason) of the processes listed in the confirmation of `protected_action`
+    fn protected_processes(&self) -> Vec<(String, &'static str)> {
+        self.protected_action
+            .as_ref()
+            .map(|action| self.protected_processes_for(action))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(_, name, reason)| (name, reason))
+            .collect()
+    }
+
+    /// Terminate (and with `restart`, relaunch) a process after a confirmation. Critical
+    /// processes are refused; `exe_path` is resolved from the PID when unknown.
+    pub fn request_terminate(&mut self, pid: u32, name: &str, exe_path: Option<PathBuf>, restart: bool) {
+        if self.terminate_promise.is_some() {
+            self.notifications.warning("Processus", "Un processus est déjà en cours d'arrêt");
+            return;
+        }
+        let exe_path = exe_path.or_else(|| process_control::process_image_path(pid));
+        let protection = match safety::ensure_not_critical(pid, name, exe_path.as_deref(), "terminer") {
+            Ok(protection) => protection,
+            Err(e) => {
+                self.notifications.error_chain("Processus", "Arrêt refusé", &e);
+                return;
+            }
+        };
+        self.process_to_terminate = Some(TerminateRequest {
+            pid,
+            name: name.to_string(),
+            exe_path,
+            restart,
+            protection,
+        });
+        self.request_action(PendingAction::TerminateProcess);
+    }
+
+    fn start_termination(&mut self, request: TerminateRequest) {
+        tracing::info!("🛑 Arrêt demandé pour {} (PID {}), redémarrage={}", request.name, request.pid, request.restart);
+        self.terminate_promise = Some(Promise::spawn_thread("terminate_process", move || {
+            let outcome = process_control::terminate_process(request.pid, TERMINATE_GRACE_MS);
+            (request, outcome)
+        }));
+    }
+
+    /// Report a finished termination, drop what referred to the PID and relaunch if asked
+    fn poll_termination(&mut self) {
+        let Some(promise) = self.terminate_promise.take() else {
+            return;
+        };
+        let (request, outcome) = match promise.try_take() {
+            Ok(done) => done,
+            Err(promise) => {
+                self.terminate_promise = Some(promise);
+                return;
+            }
+        };
+        let target = format!("{} (PID {})", request.name, request.pid);
+        let outcome = match outcome {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                tracing::error!("❌ Arrêt de {} échoué: {}", target, e);
+                self.notifications.error_chain("Processus", &format!("Arrêt de {} impossible", target), &e);
+                return;
+            }
+        };
+        tracing::info!("🛑 {} : {}", target, outcome.label());
+        if outcome == TerminationOutcome::AccessDenied {
+            self.notifications.error(
+                "Processus",
+                format!("{} : accès refusé, le processus appartient à un autre utilisateur ou tourne en administrateur", target),
+            );
+            return;
+        }
+
+        // Le PID pourra être réutilisé par un autre programme : ne rien lui laisser attaché
+        self.processes.remove(&request.pid);
+        self.limit_verifications.remove(&request.pid);
+        self.limit_verification_promises.remove(&request.pid);
+        if let Some(limiter) = self.network_limiter.as_mut() {
+            if let Err(e) = limiter.release_terminated_process(request.pid) {
+                self.notifications.error_chain("Réseau", &format!("Limite de {} non supprimée", target), &e);
+            }
+        }
+        self.process_memory_last_refresh = None;
+
+        if !request.restart {
+            self.notifications.success("Processus", format!("{} {}", target, outcome.label()));
+            return;
+        }
+        match request.exe_path.as_deref().map(process_control::relaunch) {
+            Some(Ok(new_pid)) => self.notifications.success(
+                "Processus",
+                format!("{} {}, relancé (PID {})", target, outcome.label(), new_pid),
+            ),
+            Some(Err(e)) => self.notifications.error_chain("Processus", &format!("{} {}", target, outcome.label()), &e),
+            None => self.notifications.warning(
+                "Processus",
+                format!("{} {}, mais son exécutable est inconnu : relancez-le manuellement", target, outcome.label()),
+            ),
+        }
+    }
+
+    /// Empty the working set of one process of the memory table, on a worker thread
+    pub fn trim_process(&mut self, pid: u32, name: &str) {
+        if self.trim_promise.is_some() {
+            return;
+        }
+        let name = name.to_string();
+        self.last_trim_message = Some(format!("⏳ Trim de {} (PID {})…", name, pid));
+        self.trim_promise = Some(Promise::spawn_thread("memory_trim", move || match crate::memory::trim_process(pid) {
+            Ok(freed) => {
+                tracing::info!("✂ Trim {} (PID {}): {} bytes libérés", name, pid, freed);
+                format!("✅ {} (PID {}) : {:.2} MB libérés", name, pid, freed as f64 / (1024.0 * 1024.0))
+            }
+            Err(e) => {
+                tracing::warn!("⚠️ Trim {} (PID {}) échoué: {}", name, pid, e);
+                format!("❌ {} (PID {}) : {}", name, pid, e)
+            }
+        }));
+    }
+
+    /// List the memory of the processes on a worker thread, unless a listing is running
+    pub fn refresh_process_memory(&mut self) {
+        if self.process_memory_promise.is_none() {
+            self.process_memory_promise = Some(Promise::spawn_thread("process_memory", || {
+                crate::memory::list_processes_memory().map_err(|e| e.to_string())
+            }));
+        }
+    }
+
+    /// Collect the listing and the trim of the memory table once done
+    pub fn poll_process_memory(&mut self) {
+        if let Some(promise) = self.process_memory_promise.take() {
+            match promise.try_take() {
+                Ok(Ok(processes)) => {
+                    self.process_memory_list = processes;
+                    self.process_memory_last_refresh = Some(Instant::now());
+                }
+                Ok(Err(e)) => {
+                    tracing::error!("❌ Impossible de lister la mémoire des processus: {}", e);
+                    self.process_memory_last_refresh = Some(Instant::now());
+                }
+                Err(promise) => self.process_memory_promise = Some(promise),
+            }
+        }
+        if let Some(promise) = self.trim_promise.take() {
+            match promise.try_take() {
+                Ok(message) => {
+                    self.last_trim_message = Some(message);
+                    // Rafraîchir la liste au prochain affichage
+                    self.process_memory_last_refresh = None;
+                }
+                Err(promise) => self.trim_promise = Some(promise),
+            }
+        }
+    }
+
+    /// Apply `profile`, after a confirmation when it pauses Defender
+    pub fn request_profile(&mut self, profile: BoostProfile) {
+        if profile.defender_pause_minutes.is_some() {
+            self.profile_to_apply = Some(profile);
+            self.request_action(PendingAction::ApplyProfile);
+        } else {
+            self.apply_profile(profile);
+        }
+    }
+
+    /// Apply `profile` in the background. The network limiter is lent to the task and given
+    /// back by `poll_profile_task`.
+    pub fn apply_profile(&mut self, profile: BoostProfile) {
+        if self.profile_promise.is_some() || self.applied_profile.is_some() {
+            return;
+        }
+        let mut limiter = self.network_limiter.take();
+        self.last_profile_message = None;
+        self.profile_promise = Some(Promise::spawn_thread("profile_apply", move || {
+            let applied = crate::profiles::apply(&profile, limiter.as_mut());
+            (limiter, ProfileOutcome::Applied(applied))
+        }));
+    }
+
+    /// Undo the applied profile in the background
+    pub fn revert_profile(&mut self) {
+        if self.profile_promise.is_some() {
+            return;
+        }
+        let Some(applied) = self.applied_profile.clone() else {
+            return;
+        };
+        let mut limiter = self.network_limiter.take();
+        self.last_profile_message = None;
+        self.profile_promise = Some(Promise::spawn_thread("profile_revert", move || {
+            let report = crate::profiles::revert(&applied, limiter.as_mut());
+            (limiter, ProfileOutcome::Reverted(report))
+        }));
+    }
+
+    /// Inventory (`dry_run`) or removal of every GameBooster artifact in the background. The
+    /// network limiter is lent to the task and given back by `poll_cleanup_task`.
+    pub fn start_cleanup(&mut self, dry_run: bool) {
+        if self.cleanup_promise.is_some() {
+            return;
+        }
+        // Les miroirs en mémoire doivent être sur disque : le nettoyage relit les paramètres
+        self.save_settings();
+        let mut limiter = self.network_limiter.take();
+        self.cleanup_promise = Some(Promise::spawn_thread("uninstall_cleanup", move || {
+            let report = crate::maintenance::full_cleanup(limiter.as_mut(), dry_run);
+            (limiter, report)
+        }));
+    }
+
+    pub fn poll_cleanup_task(&mut self) {
+        let Some(promise) = self.cleanup_promise.take() else {
+            return;
+        };
+        let (limiter, report) = match promise.try_take() {
+            Ok(done) => done,
+            Err(promise) => {
+                self.cleanup_promise = Some(promise);
+                return;
+            }
+        };
+        if limiter.is_some() {
+            self.network_limiter = limiter;
+        }
+
+        if report.dry_run {
+            if report.items.is_empty() {
+                self.notifications.info("Nettoyage", "Aucune trace de GameBooster à supprimer");
+            } else {
+                self.cleanup_preview = Some(report);
+                self.request_action(PendingAction::UninstallCleanup);
+            }
+            return;
+        }
+
+        // Relire ce que le nettoyage a rétabli, sans quoi la prochaine sauvegarde le réécrirait
+        let config_removed = !AppConfig::path().exists();
+        let config = AppConfig::load();
+        self.services_to_restart = config.services_to_restart;
+        self.services_start_types = config.services_start_types;
+        self.power_plan_to_restore = config.power_plan_to_restore;
+        self.defender_reenable_at = config.defender_reenable_at;
+        self.run_at_startup = crate::utils::is_run_at_startup_enabled();
+        self.settings_removed = config_removed;
+
+        let failed = report.failures() + report.needs_elevation();
+        if failed == 0 {
+            self.notifications.success("Nettoyage", format!("{} élément(s) supprimé(s)", report.items.len()));
+        } else {
+            self.notifications.warning("Nettoyage", format!("{} élément(s) non supprimé(s) sur {}", failed, report.items.len()));
+        }
+        self.cleanup_report = Some(report);
+    }
+
+    pub fn poll_profile_task(&mut self) {
+        let Some(promise) = self.profile_promise.take() else {
+            return;
+        };
+        let (limiter, outcome) = match promise.try_take() {
+            Ok(result) => result,
+            Err(promise) => {
+                self.profile_promise = Some(promise);
+                return;
+            }
+        };
+        self.network_limiter = limiter;
+
+        match outcome {
+            ProfileOutcome::Applied(applied) => {
+                if let Some(deadline) = applied.defender_reenable_at {
+                    self.defender_reenable_at = Some(deadline);
+                    self.last_defender_status = None;
+                }
+                self.last_profile_message = Some(if applied.errors.is_empty() {
+                    self.notifications.success("Profils", format!("Profil {} appliqué", applied.profile_name));
+                    format!("✅ Profil {} appliqué", applied.profile_name)
+                } else {
+                    let message = format!("Profil {} appliqué avec {} erreur(s)", applied.profile_name, applied.errors.len());
+                    self.notifications.failures("Profils", message.clone(), &applied.errors);
+                    format!("⚠️ {}", message)
+                });
+                self.applied_profile = Some(applied);
+                self.sync_game_session();
+                if let (Some(session), Some(applied)) = (self.game_session.as_mut(), self.applied_profile.as_ref()) {
+                    session.profile_applied_at.get_or_insert(applied.applied_at);
+                    if let Some(freed) = applied.ram_freed {
+                        session.record_cleaning(freed as u64);
+                    }
+                    let stopped: Vec<String> = applied
+                        .service_changes
+                        .iter()
+                        .filter(|(_, revert)| *revert == ServiceRevert::Start)
+                        .map(|(name, _)| name.clone())
+                        .collect();
+                    session.record_services(&stopped);
+                }
+            }
+            ProfileOutcome::Reverted(report) => {
+                if self.applied_profile.as_ref().is_some_and(|applied| applied.defender_reenable_at.is_some()) {
+                    self.defender_reenable_at = None;
+                    self.last_defender_status = None;
+                }
+                self.last_profile_message = Some(if report.errors.is_empty() {
+                    format!("✅ {} modification(s) rétablie(s)", report.restored)
+                } else {
+                    self.notifications.failures("Profils", "Rétablissement du profil incomplet", &report.errors);
+                    format!("⚠️ Rétablissement incomplet :\n{}", report.errors.join("\n"))
+                });
+                self.applied_profile = None;
+                self.sync_game_session();
+            }
+        }
+        self.invalidate_service_statuses();
+        self.power_plans = None;
+        self.tweak_registry.invalidate();
+        // Sauvegardé immédiatement : le profil reste rétablissable après un redémarrage
+        self.save_settings();
+    }
+
+    /// Clean the memory in the background
+    pub fn start_memory_cleaning(&mut self) {
+        if self.cleaning_promise.is_some() || self.hotkey_cleaning.is_some() {
+            return;
+        }
+        self.cleaning_promise = Some(Promise::spawn_thread("memory_clean", || {
+            match crate::memory::clean_memory() {
+                Ok(results) => results,
+                Err(e) => {
+                    // En cas d'erreur, créer un CleaningResults avec le message d'erreur
+                    let mut error_results = CleaningResults::new();
+                    error_results.has_error = true;
+                    error_results.error_message = format!("Erreur lors du nettoyage de la mémoire : {}", e);
+                    error_results.is_completed = true;
+                    error_results.end_time = Some(chrono::Local::now());
+                    error_results
+                }
+            }
+        }));
+    }
+
+    /// Clean the disk in the background, as a dry run in simulation mode
+    pub fn start_disk_cleaning(&mut self) {
+        if self.disk_cleaning_promise.is_some() {
+            return;
+        }
+        let options = self.disk_options.clone();
+        let dry_run = self.simulation_mode;
+        self.disk_operation_is_clean = true;
+        self.disk_cleaning_promise = Some(Promise::spawn_thread("disk_clean", move || {
+            match tokio::runtime::Runtime::new().unwrap().block_on(async {
+                crate::disk::clean_disk_with_options(options, dry_run).await
+            }) {
+                Ok(results) => results,
+                Err(e) => {
+                    // Résultat vide, l'erreur est montrée avec les autres
+                    let mut results = crate::disk::DiskCleaningResults::new();
+                    results.errors.push(format!("Nettoyage impossible : {:#}", e));
+                    results
+                }
+            }
+        }));
+    }
+
+    /// Disable Defender for `defender_pause_minutes`, re-enabled by a scheduled task
+    pub fn pause_defender(&mut self) {
+        match crate::services::defender::DefenderService::disable_for_duration(self.defender_pause_minutes) {
+            Ok((status, _)) if status.real_time_protection && status.tamper_protection => {
+                self.tamper_guidance = Some(TamperGuidance::new(GuardedOperation::Pause(self.defender_pause_minutes)));
+                crate::services::defender::DefenderService::cancel_timed_pause();
+            }
+            Ok((_, deadline)) => self.defender_reenable_at = Some(deadline),
+            Err(e) => {
+                tracing::error!("❌ Pause de Defender impossible: {}", e);
+                self.notifications.error_chain("Defender", "Pause de Defender impossible", &e);
+                self.last_defender_status = Some(Err(e));
+                return;
+            }
+        }
+        // Nouvelle vérification à la prochaine ouverture de l'onglet Services
+        self.last_defender_status = None;
+    }
+
+    /// Tamper Protection reads as on; unknown counts as on
+    fn tamper_protection_active() -> bool {
+        crate::services::winapi_defender::DefenderManager::check_defender_status()
+            .map(|status| status.tamper_protection)
+            .unwrap_or(true)
+    }
+
+    /// Start a Defender disable or pause, through the Tamper Protection guide when it is on:
+    /// every registry write would be refused
+    pub fn request_defender_disable(&mut self, operation: GuardedOperation) {
+        if Self::tamper_protection_active() {
+            self.tamper_guidance = Some(TamperGuidance::new(operation));
+            return;
+        }
+        match operation {
+            GuardedOperation::Disable => self.request_action(PendingAction::DisableDefender),
+            GuardedOperation::Pause(minutes) => {
+                self.defender_pause_minutes = minutes;
+                self.request_action(PendingAction::PauseDefender);
+            }
+        }
+    }
+
+    /// Resume the operation of the guide once Tamper Protection reads as off
+    fn poll_tamper_guidance(&mut self, ctx: &egui::Context) {
+        let Some(guidance) = self.tamper_guidance.as_mut() else {
+            return;
+        };
+        if matches!(guidance.phase(), TamperPhase::Waiting { .. }) {
+            ctx.request_repaint_after(std::time::Duration::from_millis(500));
+        }
+        let Some(operation) = guidance.poll() else {
+            return;
+        };
+        self.tamper_guidance = None;
+        self.notifications.success("Defender", "Protection contre les falsifications désactivée, reprise de l'opération");
+        self.last_defender_status = None;
+        self.request_defender_disable(operation);
+    }
+
+    /// Enable or disable Defender off the UI thread; the steps are shown as they complete and
+    /// the result is collected by `check_defender_reenable`
+    pub fn spawn_defender_action(&mut self, enable: bool) {
+        if self.defender_action_promise.is_some() {
+            return;
+        }
+        let action = if enable { DefenderAction::Enable } else { DefenderAction::Disable };
+        let (progress, sender) = DefenderProgress::start(action);
+        self.defender_progress = Some(progress);
+        self.defender_action_promise = Some(Promise::spawn_thread("defender_action", move || {
+            crate::services::defender::DefenderService::run_with_progress(action, &sender)
+        }));
+    }
+
+    /// Collect the pending Defender action, and re-enable Defender in the background
+    /// once the timed pause is over
+    fn check_defender_reenable(&mut self, ctx: &egui::Context) {
+        if let Some(progress) = self.defender_progress.as_mut() {
+            progress.poll();
+        }
+        if let Some(promise) = self.defender_action_promise.take() {
+            match promise.try_take() {
+                Ok(Ok(summary)) => {
+                    let active = summary.real_time_protection.unwrap_or(true);
+                    tracing::info!("🛡️ Action Defender terminée, protection temps réel: {}", active);
+                    match summary.outcome() {
+                        ActionOutcome::Success => self.notifications.success("Defender", summary.message()),
+                        ActionOutcome::Partial | ActionOutcome::Failed => self.notifications.warning("Defender", summary.message()),
+                    }
+                    if active {
+                        self.defender_reenable_at = None;
+                    }
+                    if summary.action == DefenderAction::Disable && summary.tamper_blocked && Self::tamper_protection_active() {
+                        self.tamper_guidance = Some(TamperGuidance::new(GuardedOperation::Disable));
+                    }
+                    if let Some(progress) = self.defender_progress.as_mut() {
+                        progress.poll();
+                        progress.summary = Some(summary);
+                    }
+                    // Nouvelle vérification à la prochaine ouverture de l'onglet Services
+                    self.last_defender_status = None;
+                }
+                Ok(Err(e)) => {
+                    tracing::error!("❌ Action Defender échouée: {}", e);
+                    self.notifications.error_chain("Defender", "Action Defender échouée", &e);
+                    self.defender_reenable_at = None;
+                    self.defender_progress = None;
+                    self.last_defender_status = Some(Err(e));
+                }
+                Err(promise) => self.defender_action_promise = Some(promise),
+            }
+            return;
+        }
+
+        let Some(deadline) = self.defender_reenable_at else {
+            return;
+        };
+        if chrono::Local::now() >= deadline {
+            let (progress, sender) = DefenderProgress::start(DefenderAction::Enable);
+            self.defender_progress = Some(progress);
+            self.defender_action_promise = Some(Promise::spawn_thread("defender_reenable", move || {
+                crate::services::defender::DefenderService::run_with_progress(DefenderAction::Enable, &sender)
+            }));
+        } else {
+            // Sans interaction, egui ne redessine pas : se réveiller pour l'échéance
+            ctx.request_repaint_after(std::time::Duration::from_secs(30));
+        }
+    }
+
+    /// Whether a gaming service is selected, falling back to its default selection
+    pub fn is_service_selected(&self, service: &crate::services::gaming_services::GamingService) -> bool {
+        self.selected_services.get(service.name).copied().unwrap_or(service.selected_by_default)
+    }
+
+    pub fn service_mode(&self, service_name: &str) -> ServiceMode {
+        self.service_modes.get(service_name).copied().unwrap_or_default()
+    }
+
+    /// Last known status of a service, "…" before the first query. A missing or stale one is
+    /// queued for `poll_service_statuses`, so that `sc query` never runs on the UI thread.
+    pub fn cached_service_status(&mut self, service_name: &str) -> String {
+        let cached = self.services_status_cache.get(service_name);
+        if !cached.is_some_and(|(_, checked_at)| checked_at.elapsed() < SERVICE_STATUS_TTL) {
+            self.services_status_queue.insert(service_name.to_string());
+        }
+        cached.map(|(status, _)| status.clone()).unwrap_or_else(|| "…".to_string())
+    }
+
+    /// Query every status again, dropping a query started before the change
+    pub fn invalidate_service_statuses(&mut self) {
+        self.services_status_cache.clear();
+        self.services_status_promise = None;
+    }
+
+    /// Store the statuses queried on the worker, then query the queued services
+    pub fn poll_service_statuses(&mut self) {
+        if let Some(promise) = self.services_status_promise.take() {
+            match promise.try_take() {
+                Ok(statuses) => {
+                    let now = Instant::now();
+                    for (service_name, status) in statuses {
+                        self.services_status_cache.insert(service_name, (status, now));
+                    }
+                }
+                Err(promise) => {
+                    self.services_status_promise = Some(promise);
+                    return;
+                }
+            }
+        }
+        if self.services_status_queue.is_empty() {
+            return;
+        }
+        let queued = std::mem::take(&mut self.services_status_queue);
+        self.services_status_promise = Some(Promise::spawn_thread("services_status", move || {
+            queued
+                .into_iter()
+                .map(|service_name| {
+                    let status = crate::services::get_service_status(&service_name).unwrap_or_else(|e| {
+                        tracing::warn!("⚠️ Statut du service {} indisponible: {}", service_name, e);
+                        "Unknown".to_string()
+                    });
+                    (service_name, status)
+                })
+                .collect()
+        }));
+    }
+
+    /// (services that depend on `service_name`, services it depends on)
+    pub fn cached_service_dependencies(&mut self, service_name: &str) -> &(Vec<String>, Vec<String>) {
+        use crate::services::winapi_service_manager::ServiceManager;
+
+        self.service_dependencies_cache.entry(service_name.to_string()).or_insert_with(|| {
+            (ServiceManager::get_service_dependents(service_name), ServiceManager::get_service_dependencies(service_name))
+        })
+    }
+
+    /// Stop the selected gaming services in the background
+    pub fn start_services_optimization(&mut self) {
+        if self.services_promise.is_some() {
+            return;
+        }
+        let selected: Vec<(String, ServiceMode)> = crate::services::gaming_services::GAMING_SERVICES
+            .iter()
+            .filter(|service| self.is_service_selected(service))
+            .map(|service| (service.name.to_string(), self.service_mode(service.name)))
+            .collect();
+        self.services_restoring = false;
+        let dry_run = self.simulation_mode;
+        let manage_dependencies = self.manage_service_dependencies;
+        self.services_promise = Some(Promise::spawn_thread("services_optimization", move || {
+            crate::services::gaming_services::optimize_selected_services(&selected, manage_dependencies, dry_run)
+        }));
+    }
+
+    /// Restart the services stopped by the last optimization
+    pub fn restore_optimized_services(&mut self) {
+        if self.services_promise.is_some() {
+            return;
+        }
+        let Some(optimization) = self.last_services_results.clone() else {
+            return;
+        };
+        self.services_restoring = true;
+        self.services_promise = Some(Promise::spawn_thread("services_restore", move || {
+            crate::services::gaming_services::restore_services(&optimization)
+        }));
+    }
+
+    /// Store the outcome of a finished optimization or restoration
+    pub fn poll_services_task(&mut self) {
+        let Some(promise) = self.services_promise.take() else {
+            return;
+        };
+        match promise.try_take() {
+            Ok(results) => {
+                // Sauvegardé immédiatement : relu par le gestionnaire de panique
+                if self.services_restoring {
+                    self.notify_services_results(&results);
+                    self.services_to_restart.clear();
+                    self.services_start_types.clear();
+                    self.last_services_results = None;
+                    self.last_services_restore = Some(results);
+                } else {
+                    self.services_to_restart = crate::services::gaming_services::stopped_services(&results);
+                    if let Some(session) = self.game_session.as_mut() {
+                        session.record_services(&self.services_to_restart);
+                    }
+                    // Le type d'origine relevé en premier reste la référence
+                    for (service, start_type) in crate::services::gaming_services::replaced_start_types(&results) {
+                        self.services_start_types.entry(service).or_insert(start_type);
+                    }
+                    self.notify_services_results(&results);
+                    self.last_services_restore = None;
+                    self.last_services_results = Some(results);
+                }
+                self.save_settings();
+                self.invalidate_service_statuses();
+            }
+            Err(promise) => self.services_promise = Some(promise),
+        }
+    }
+
+    fn notify_services_results(&mut self, results: &ServicesOptimizationResults) {
+        let action = if self.services_restoring { "Redémarrage" } else { "Arrêt" };
+        if results.errors.is_empty() {
+            self.notifications.success("Services", format!("{} des services terminé", action));
+        } else {
+            let message = format!("{} des services : {} erreur(s)", action, results.errors.len());
+            self.notifications.failures("Services", message, &results.errors);
+        }
+    }
+
+    /// Whether a feature gated by `requires_elevation()` can run in this session
+    pub fn can_run(&self, requires_elevation: bool) -> bool {
+        !requires_elevation || self.is_elevated
+    }
+
+    /// Relaunch elevated on the current tab, then close this instance
+    pub fn relaunch_as_admin(&mut self, ctx: &egui::Context) {
+        let args = vec![format!("--tab={}", self.active_tab.as_arg()), crate::ipc::RELAUNCH_ARG.to_string()];
+        match crate::utils::relaunch_as_admin(&args) {
+            Ok(()) => {
+                tracing::info!("🔐 Relance en administrateur demandée, fermeture de l'instance actuelle");
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+            Err(e) => {
+                tracing::warn!("⚠️ Relance administrateur impossible: {}", e);
+                self.elevation_error = Some(e.to_string());
+            }
+        }
+    }
+
+    pub fn update_network_scan(&mut self) {
+        if let Some(ref mut limiter) = self.network_limiter {
+            // Le résumé du scan est écrit par le limiteur, une fois par minute au niveau info
+            if let Err(e) = limiter.scan_network_processes() {
+                tracing::error!("❌ Erreur scan réseau: {}", e);
+                self.notifications.error_chain("Réseau", "Scan réseau impossible", &e);
+            }
+        }
+        if let (Some(session), Some(limiter)) = (self.game_session.as_mut(), self.network_limiter.as_ref()) {
+            session.record_network(&limiter.get_processes());
+        }
+        self.refresh_process_connections();
+        self.last_network_scan = Some(Instant::now());
+    }
+
+    /// Scan again once `network_refresh_secs` have passed since the end of the previous scan.
+    /// The scan runs on the UI thread: counting from its end, scans never overlap.
+    pub fn auto_refresh_network(&mut self, ctx: &egui::Context) {
+        if self.network_refresh_secs == 0 || self.network_limiter.is_none() {
+            return;
+        }
+        let interval = std::time::Duration::from_secs(self.network_refresh_secs);
+        let elapsed = self.last_network_scan.map(|scan| scan.elapsed());
+        if elapsed.map_or(true, |elapsed| elapsed >= interval) {
+            self.update_network_scan();
+            ctx.request_repaint_after(interval);
+        } else if let Some(elapsed) = elapsed {
+            ctx.request_repaint_after(interval - elapsed);
+        }
+    }
+
+    /// Show the connections of `pids` under the process table, or hide them when already shown
+    pub fn toggle_connection_panel(&mut self, label: &str, pids: &[u32]) {
+        if self.connection_panel.as_ref().is_some_and(|(_, shown)| shown == pids) {
+            self.connection_panel = None;
+            self.process_connections.clear();
+            return;
+        }
+        self.connection_panel = Some((label.to_string(), pids.to_vec()));
+        self.refresh_process_connections();
+    }
+
+    /// Re-read the sockets of the process shown in the connection panel
+    pub fn refresh_process_connections(&mut self) {
+        let (Some((_, pids)), Some(limiter)) = (&self.connection_panel, &self.network_limiter) else {
+            return;
+        };
+        self.process_connections = pids.iter().flat_map(|pid| limiter.get_process_connections(*pid)).collect();
+    }
+
+    pub fn scan_network_processes(&mut self) {
+        tracing::info!("🔄 Scan réseau demandé");
+        self.update_network_scan();
+    }
+
+    /// Limit `pid` to the value of the input, or to the last limit of its application when
+    /// the input is left at 0
+    pub fn limit_process(&mut self, pid: u32) {
+        tracing::info!("🎯 Début limitation processus PID {}", pid);
+        let remembered = if self.speed_limit_value == 0.0 {
+            self.network_process_name(pid).and_then(|name| self.limit_preferences.remembered(&name))
+        } else {
+            None
+        };
+        let limit_kbps = match remembered {
+            Some(kbps) => {
+                tracing::info!("🔁 Limite retenue pour PID {} : {} KB/s", pid, kbps);
+                kbps
+            }
+            None => match self.speed_limit_kbps() {
+                Ok(kbps) => {
+                    tracing::info!("🔢 Conversion: {} {} → {} KB/s", self.speed_limit_value, self.speed_limit_unit.label(), kbps);
+                    kbps
+                }
+                Err(e) => {
+                    self.notifications.error("Réseau", format!("Limite invalide : {}", e));
+                    return;
+                }
+            },
+        };
+        self.limit_process_at(pid, limit_kbps);
+    }
+
+    /// Executable name of `pid` in the last network scan
+    fn network_process_name(&self, pid: u32) -> Option<String> {
+        let limiter = self.network_limiter.as_ref()?;
+        limiter.get_processes().into_iter().find(|process| process.pid == pid).map(|process| process.name.clone())
+    }
+
+    /// Limit `pid` to `limit_kbps` and remember it for its application
+    pub fn limit_process_at(&mut self, pid: u32, limit_kbps: u32) {
+        let limit_mbps = limit_kbps as f64 / 1024.0;
+        
+        if let Some(ref mut limiter) = self.network_limiter {
+            // Vérifier si le processus existe dans le scan
+            let Some(process_name) = limiter.get_processes().into_iter().find(|p| p.pid == pid).map(|p| p.name.clone()) else {
+                tracing::warn!("⚠️ Processus PID {} non trouvé dans le scan réseau", pid);
+                self.notifications.warning("Réseau", format!("Processus PID {} introuvable, relancez un scan", pid));
+                return;
+            };
+            
+            let duration = self.limit_duration.duration(self.limit_custom_minutes);
+            let result = match duration {
+                Some(duration) => limiter.set_process_speed_limit_for(pid, limit_kbps, duration, self.qos_scope_adapter.as_deref()),
+                None => limiter.set_process_speed_limit(pid, limit_kbps, self.qos_scope_adapter.as_deref()),
+            };
+            match result {
+                Ok(change) => {
+                    self.last_network_error = None;
+                    tracing::info!("✅ Limitation QoS appliquée: PID {} → {:.1} MB/s ({} KB/s)", pid, limit_mbps, limit_kbps);
+                    self.limit_preferences.remember(&process_name, limit_kbps);
+                    let until = match limiter.limit_expiry(pid) {
+                        Some(expiry) => format!(" jusqu'à {}", expiry.expires_at.format("%H:%M")),
+                        None => String::new(),
+                    };
+                    self.notifications.success("Réseau", format!("PID {} : {}{}", pid, change.describe(), until));
+                    // Politique laissée en place : déjà vérifiée lors de sa création
+                    if change.is_unchanged() {
+                        return;
+                    }
+
+                    self.limit_verifications.remove(&pid);
+                    if self.verify_limits {
+                        self.limit_verification_promises.insert(pid, Promise::spawn_thread("limit_verification", move || {
+                            NetworkLimiter::verify_limit_effectiveness(pid, limit_kbps, crate::network::DEFAULT_VERIFICATION_SECS)
+                        }));
+                    }
+                    
+                    // Vérifier immédiatement si la politique a été créée
+                    match limiter.verify_qos_policies(false) {
+                        Ok(policies) => {
+                            let policy_count = policies.len();
+                            tracing::info!("📋 Vérification: {} politiques QoS trouvées après création", policy_count);
+                            
+                            // Chercher notre politique spécifique
+                            let our_policy_name = limiter.policy_name_for(pid);
+                            let found = policies.iter().any(|p| p.name == our_policy_name);
+                            if found {
+                                tracing::info!("✅ Politique {} confirmée active", our_policy_name);
+                            } else {
+                                tracing::warn!("⚠️ Politique {} non trouvée dans la liste active", our_policy_name);
+                            }
+                        }
+                        Err(e) => {
+                            tracing::warn!("⚠️ Impossible de vérifier les politiques: {}", e);
+                        }
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("❌ Échec limitation QoS PID {}: {}", pid, e);
+                    self.notifications.error_chain("Réseau", &format!("Limitation PID {} impossible", pid), &e);
+                    self.last_network_error = Some(format!("Limitation PID {} impossible : {}", pid, e));
+                }
+            }
+        } else {
+            tracing::error!("❌ NetworkLimiter non initialisé pour PID {}", pid);
+            self.notifications.error("Réseau", "Limiteur réseau non initialisé");
+        }
+    }
+
+    pub fn remove_process_limit(&mut self, pid: u32) {
+        self.limit_verifications.remove(&pid);
+        if let Some(ref mut limiter) = self.network_limiter {
+            match limiter.remove_process_limit(pid) {
+                Ok(()) => {
+                    tracing::info!("✅ Limitation supprimée: PID {}", pid);
+                    self.notifications.success("Réseau", format!("Limitation du PID {} supprimée", pid));
+                }
+                Err(e) => {
+                    tracing::error!("❌ Échec suppression limitation PID {}: {}", pid, e);
+                    self.notifications.error_chain("Réseau", &format!("Suppression de la limite du PID {} impossible", pid), &e);
+                }
+            }
+        }
+    }
+
+    /// Run the requests forwarded by the instances started since, as the matching buttons would
+    fn poll_ipc_commands(&mut self, ctx: &egui::Context) {
+        let Some(receiver) = &self.ipc_commands else {
+            return;
+        };
+        let commands: Vec<IpcCommand> = receiver.try_iter().collect();
+        for command in commands {
+            match command {
+                IpcCommand::Show { tab } => self.bring_to_front(ctx, tab.as_deref().and_then(Tab::from_arg)),
+                IpcCommand::CleanRam => {
+                    self.active_tab = Tab::Memory;
+                    self.start_memory_cleaning();
+                }
+                IpcCommand::CleanDisk { options } => {
+                    // Options de la ligne de commande pour ce nettoyage seulement
+                    let saved = std::mem::replace(&mut self.disk_options, options);
+                    self.active_tab = Tab::Optimization;
+                    self.start_disk_cleaning();
+                    self.disk_options = saved;
+                }
+                IpcCommand::Limit { exe_name, limit_mbps } => {
+                    let limit_kbps = (limit_mbps * 1024.0).round() as u32;
+                    match self.network_limiter.as_mut().map(|limiter| limiter.limit_executable(&exe_name, limit_kbps)) {
+                        Some(Ok(_)) => self.notifications.success("Réseau", format!("{} limité à {:.1} MB/s", exe_name, limit_mbps)),
+                        Some(Err(e)) => self.notifications.error_chain("Réseau", &format!("Limitation de {} impossible", exe_name), &e),
+                        None => self.notifications.error("Réseau", "Limiteur réseau non initialisé"),
+                    }
+                }
+                IpcCommand::HotkeyCleanRam => self.start_hotkey_cleaning(),
+                IpcCommand::UnlimitAll => match self.network_limiter.as_mut().map(NetworkLimiter::clear_all_limits) {
+                    Some(Ok(())) => self.notifications.success("Réseau", "Toutes les limites ont été supprimées"),
+                    Some(Err(e)) => self.notifications.error_chain("Réseau", "Suppression des limites impossible", &e),
+                    None => self.notifications.error("Réseau", "Limiteur réseau non initialisé"),
+                },
+            }
+        }
+    }
+
+    /// Remove the time-boxed limits that are over, and wake up in time for the next one
+    /// Apply `level` to the running subscriber at once; saved with the other settings
+    pub fn set_log_level(&mut self, level: LogLevel) {
+        self.log_level = level;
+        if let Some(handle) = &self.log_level_handle {
+            match handle.reload(level.filter()) {
+                Ok(()) => tracing::info!("📜 Niveau de journalisation : {}", level.directive()),
+                Err(e) => tracing::error!("❌ Changement du niveau de journalisation impossible: {}", e),
+            }
+        }
+    }
+
+    pub fn clear_logs(&mut self) {
+        match crate::logs::clear() {
+            Ok(reclaimed) => self.notifications.success("Journal", format!("Journaux vidés, {} Ko libérés", reclaimed / 1024)),
+            Err(e) => self.notifications.warning("Journal", format!("Certains journaux n'ont pas été vidés : {}", e)),
+        }
+        self.log_dir_size = None;
+    }
+
+    /// Show the window in front of the others, on `tab` when given
+    fn bring_to_front(&mut self, ctx: &egui::Context, tab: Option<Tab>) {
+        if let Some(tab) = tab {
+            self.active_tab = tab;
+        }
+        ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+    }
+
+    /// Check the memory alert thresholds every `MEMORY_ALERT_INTERVAL`, minimized or not
+    fn tick_memory_alert(&mut self, ctx: &egui::Context) {
+        if self.toast_clicked.swap(false, Ordering::Relaxed) {
+            self.bring_to_front(ctx, Some(Tab::Memory));
+        }
+        if !self.memory_alert_config.enabled {
+            self.memory_alert = MemoryAlert::default();
+            return;
+        }
+        ctx.request_repaint_after(MEMORY_ALERT_INTERVAL);
+        if self.memory_alert_checked_at.is_some_and(|at| at.elapsed() < MEMORY_ALERT_INTERVAL) {
+            return;
+        }
+        self.memory_alert_checked_at = Some(Instant::now());
+
+        let info = crate::memory::get_detailed_system_memory_info();
+        let Some(message) = self.memory_alert.check(&self.memory_alert_config, &info) else {
+            return;
+        };
+        self.notifications.warning("Mémoire", message.clone());
+        if self.memory_alert_config.quiet_when_fullscreen && crate::gaming::fullscreen::is_fullscreen_foreground() {
+            tracing::info!("🔕 Jeu en plein écran : alerte mémoire sans notification Windows ({})", message);
+            return;
+        }
+        let clicked = self.toast_clicked.clone();
+        let ctx = ctx.clone();
+        let shown = system_toast::show("GameBooster", &message, move || {
+            clicked.store(true, Ordering::Relaxed);
+            ctx.request_repaint();
+        });
+        if let Err(e) = shown {
+            tracing::warn!("⚠️ Notification Windows impossible: {}", e);
+        }
+    }
+
+    /// Snapshot of what GameBooster applied, compared with the system by the drift check
+    fn drift_intent(&self) -> DriftIntent {
+        let (policies, qos_backend) = match &self.network_limiter {
+            Some(limiter) => (limiter.managed_policies().to_vec(), limiter.qos_backend()),
+            None => (Vec::new(), crate::network::QosBackend::Wmi),
+        };
+        DriftIntent {
+            policies,
+            qos_backend,
+            defender_paused_until: self.defender_reenable_at,
+            disabled_services: self.services_start_types.keys().cloned().collect(),
+        }
+    }
+
+    /// Compare the applied settings with the system in the background
+    pub fn start_drift_check(&mut self) {
+        if self.drift_promise.is_some() {
+            return;
+        }
+        self.drift_checked_at = Some(Instant::now());
+        let intent = self.drift_intent();
+        self.drift_promise = Some(Promise::spawn_thread("drift_check", move || drift::check(&intent)));
+    }
+
+    /// Drift check at startup then every `drift::CHECK_INTERVAL`, and the buttons of its
+    /// notification
+    fn tick_drift_check(&mut self, ctx: &egui::Context) {
+        for action in self.notifications.take_clicked() {
+            match action {
+                NotificationAction::ReapplyDrift => self.reapply_drift(),
+                NotificationAction::AcceptDrift => self.accept_drift(),
+            }
+        }
+
+        if let Some(promise) = self.drift_promise.take() {
+            match promise.try_take() {
+                Ok(report) => self.on_drift_report(report),
+                Err(promise) => {
+                    self.drift_promise = Some(promise);
+                    ctx.request_repaint_after(std::time::Duration::from_millis(250));
+                }
+            }
+            return;
+        }
+
+        let elapsed = self.drift_checked_at.map(|at| at.elapsed());
+        match elapsed {
+            Some(elapsed) if elapsed < drift::CHECK_INTERVAL => ctx.request_repaint_after(drift::CHECK_INTERVAL - elapsed),
+            _ => self.start_drift_check(),
+        }
+    }
+
+    fn on_drift_report(&mut self, mut report: DriftReport) {
+        // Modifié pendant la vérification (limite levée, pause terminée, services restaurés) : plus un écart
+        match &self.network_limiter {
+            Some(limiter) => report.missing_policies.retain(|policy| limiter.managed_policies().contains(policy)),
+            None => report.missing_policies.clear(),
+        }
+        report.unexpected_defender_state &= self.defender_reenable_at.is_some();
+        report.changed_services.retain(|service| self.services_start_types.contains_key(&service.name));
+
+        let drift_actions = [NotificationAction::ReapplyDrift, NotificationAction::AcceptDrift];
+        if report.is_empty() {
+            self.notifications.retire_actions(&drift_actions);
+        } else if !self.drift_report.as_ref().is_some_and(|previous| previous.same_drifts(&report)) {
+            // Mêmes écarts qu'à la vérification précédente : déjà signalés
+            self.notifications.push_with_actions(
+                NotificationLevel::Warning,
+                "Cohérence",
+                format!("{} réglage(s) modifié(s) par Windows depuis leur application", report.count()),
+                Some(report.lines().join("\n")),
+                drift_actions.to_vec(),
+            );
+        }
+        self.drift_report = Some(report);
+    }
+
+    /// Apply the drifted settings again, with the functions that created them
+    pub fn reapply_drift(&mut self) {
+        let Some(report) = self.drift_report.take().filter(|report| !report.is_empty()) else {
+            return;
+        };
+        self.notifications.retire_actions(&[NotificationAction::ReapplyDrift, NotificationAction::AcceptDrift]);
+        let mut errors = Vec::new();
+        if let Some(limiter) = self.network_limiter.as_mut() {
+            for policy in &report.missing_policies {
+                if let Err(e) = limiter.reapply_policy(&policy.name) {
+                    errors.push(format!("Politique {} : {:#}", policy.name, e));
+                }
+            }
+        }
+        if report.unexpected_defender_state {
+            // Désactivation de l'onglet Services : l'échéance de la pause est conservée
+            self.spawn_defender_action(false);
+        }
+        for service in &report.changed_services {
+            if let Err(e) = crate::services::gaming_services::set_start_type(&service.name, "disabled") {
+                errors.push(format!("Service {} : {:#}", service.name, e));
+            }
+        }
+
+        if errors.is_empty() {
+            self.notifications.success("Cohérence", format!("{} réglage(s) ré-appliqué(s)", report.count()));
+        } else {
+            let message = format!("Ré-application : {} erreur(s) sur {} réglage(s)", errors.len(), report.count());
+            self.notifications.failures("Cohérence", message, &errors);
+        }
+        if !report.changed_services.is_empty() {
+            self.invalidate_service_statuses();
+        }
+        if !report.missing_policies.is_empty() && self.qos_verification.is_some() {
+            self.start_qos_verification();
+        }
+    }
+
+    /// Keep the system as it is: the drifted settings are no longer tracked
+    pub fn accept_drift(&mut self) {
+        let Some(report) = self.drift_report.take().filter(|report| !report.is_empty()) else {
+            return;
+        };
+        self.notifications.retire_actions(&[NotificationAction::ReapplyDrift, NotificationAction::AcceptDrift]);
+        if let Some(limiter) = self.network_limiter.as_mut() {
+            for policy in &report.missing_policies {
+                limiter.forget_missing_policy(&policy.name);
+            }
+        }
+        if report.unexpected_defender_state {
+            // Defender déjà réactivé : la pause est abandonnée, tâche de réactivation comprise
+            crate::services::defender::DefenderService::cancel_timed_pause();
+            self.defender_reenable_at = None;
+            self.last_defender_status = None;
+        }
+        for service in &report.changed_services {
+            self.services_start_types.remove(&service.name);
+        }
+        self.save_settings();
+        self.notifications.info("Cohérence", format!("État actuel accepté : {} réglage(s) ne sont plus suivis", report.count()));
+    }
+
+    /// Retention of the log files, once a day
+    /// Metered connections are noticed within `CONNECTION_CHECK_INTERVAL`: switching from
+    /// Ethernet to a phone hotspot pauses the latency monitor and is notified
+    fn tick_connection_profile(&mut self, ctx: &egui::Context) {
+        const CONNECTION_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+        ctx.request_repaint_after(CONNECTION_CHECK_INTERVAL);
+        if self.connection_checked_at.elapsed() < CONNECTION_CHECK_INTERVAL {
+            return;
+        }
+        self.connection_checked_at = Instant::now();
+        let Ok(profile) = connection_profile::current() else {
+            return;
+        };
+        let was_metered = self.connection_profile.is_some_and(|previous| previous.is_metered());
+        if self.connection_profile.is_some() && profile.is_metered() != was_metered {
+            tracing::info!("📶 Passage en {}", profile.describe());
+            let message = if profile.is_metered() {
+                format!("Passage en {} : mesure de latence et mises à jour en pause", profile.describe())
+            } else {
+                format!("Passage en {}", profile.describe())
+            };
+            self.notifications.info("Réseau", message);
+        }
+        if profile.is_metered() && !self.ping_on_metered && self.ping_monitor.is_running() {
+            self.ping_monitor.stop();
+        }
+        self.connection_profile = Some(profile);
+    }
+
+    /// The active connection is metered
+    pub fn is_metered(&self) -> bool {
+        self.connection_profile.is_some_and(|profile| profile.is_metered())
+    }
+
+    fn tick_log_retention(&mut self) {
+        let today = chrono::Local::now().date_naive();
+        if today == self.log_retention_day {
+            return;
+        }
+        self.log_retention_day = today;
+        crate::logs::enforce_retention(self.log_retention_days);
+        self.log_dir_size = None;
+    }
+
+    fn tick_limit_expirations(&mut self, ctx: &egui::Context) {
+        let Some(limiter) = self.network_limiter.as_mut() else {
+            return;
+        };
+        for expired in limiter.tick_expirations() {
+            match expired.error {
+                None => self.notifications.info("Réseau", format!("⏳ Limite temporaire de {} expirée", expired.target)),
+                Some(error) => self.notifications.error(
+                    "Réseau",
+                    format!("Limite expirée de {} toujours active, nouvel essai dans une minute : {}", expired.target, error),
+                ),
+            }
+        }
+        if let Some(next) = limiter.next_expiry() {
+            let wait = (next - chrono::Local::now()).to_std().unwrap_or_default();
+            ctx.request_repaint_after(wait + std::time::Duration::from_millis(100));
+        }
+    }
+
+    /// The latency monitor only runs while someone can see it
+    fn stop_hidden_ping_monitor(&mut self, ctx: &egui::Context) {
+        const HIDDEN_DELAY: std::time::Duration = std::time::Duration::from_secs(60);
+        if !self.ping_monitor.is_running() {
+            return;
+        }
+        match self.network_tab_last_seen.map(|seen| seen.elapsed()) {
+            Some(hidden) if hidden <= HIDDEN_DELAY => {
+                // Réveil pour l'arrêt même si plus rien ne se passe à l'écran
+                ctx.request_repaint_after(HIDDEN_DELAY - hidden + std::time::Duration::from_secs(1));
+            }
+            _ => self.ping_monitor.stop(),
+        }
+    }
+
+    /// Collect the finished limit measurements
+    pub fn poll_limit_verifications(&mut self) {
+        let pids: Vec<u32> = self.limit_verification_promises.keys().copied().collect();
+        for pid in pids {
+            let Some(promise) = self.limit_verification_promises.remove(&pid) else {
+                continue;
+            };
+            match promise.try_take() {
+                Ok(result) => {
+                    if let Err(e) = &result {
+                        tracing::error!("❌ Vérification de la limite PID {} impossible: {}", pid, e);
+                    }
+                    self.limit_verifications.insert(pid, result.map_err(|e| e.to_string()));
+                }
+                Err(promise) => {
+                    self.limit_verification_promises.insert(pid, promise);
+                }
+            }
+        }
+    }
+
+    /// `speed_limit_value` in KB/s, within the bounds of the limiter
+    pub fn speed_limit_kbps(&self) -> Result<u32, SpeedLimitError> {
+        let result = crate::network::parse_speed_limit(self.speed_limit_value, self.speed_limit_unit);
+        if let Err(e) = &result {
+            tracing::warn!("⚠️ Limite refusée ({} {}): {}", self.speed_limit_value, self.speed_limit_unit.label(), e);
+        }
+        result
+    }
+
+    pub fn apply_speed_limit_to_selected(&mut self) {
+        if self.processes.is_empty() {
+            return;
+        }
+
+        let limit_kbps = match self.speed_limit_kbps() {
+            Ok(kbps) => kbps,
+            Err(e) => {
+                self.notifications.error("Réseau", format!("Limite invalide : {}", e));
+                self.last_bulk_limit_summary = Some(format!("❌ Limite invalide: {}", e));
+                return;
+            }
+        };
+
+        if let Some(ref mut limiter) = self.network_limiter {
+            let limits: Vec<(u32, u32)> = self.processes.iter().map(|&pid| (pid, limit_kbps)).collect();
+            let outcomes = limiter.set_speed_limits_bulk(&limits);
+            let counts = LimitSummary::from_outcomes(&outcomes);
+            let duration = self.limit_duration.duration(self.limit_custom_minutes);
+            let limited: Vec<u32> = outcomes.iter().filter(|(_, result)| result.is_ok()).map(|(pid, _)| *pid).collect();
+            for process in limiter.get_processes().into_iter().filter(|process| limited.contains(&process.pid)) {
+                self.limit_preferences.remember(&process.name, limit_kbps);
+            }
+            for pid in limited {
+                limiter.set_limit_expiry(pid, duration);
+            }
+
+            let mut errors = Vec::new();
+            for (pid, result) in &outcomes {
+                if let Err(e) = result {
+                    tracing::error!("❌ Échec limitation QoS PID {}: {}", pid, e);
+                    errors.push(format!("PID {} : {:#}", pid, e));
+                }
+            }
+            if errors.is_empty() {
+                self.notifications.success("Réseau", format!("Limites : {}", counts.describe()));
+            } else {
+                self.notifications.failures("Réseau", format!("Limitation en lot : {} échec(s) sur {}", counts.failed, outcomes.len()), &errors);
+            }
+
+            tracing::info!("✅ Limitation en lot: {}", counts.describe());
+            let mut summary = counts.describe();
+            // Applications Store non limitables : signalées plutôt que noyées dans le compte
+            let unsupported: Vec<String> = outcomes
+                .iter()
+                .filter_map(|(_, result)| result.as_ref().err())
+                .map(|e| e.to_string())
+                .filter(|message| message.contains(crate::network::appx::UNSUPPORTED_MESSAGE))
+                .collect();
+            if !unsupported.is_empty() {
+                summary.push_str(&format!("\n{}", unsupported.join("\n")));
+            }
+            self.last_bulk_limit_summary = Some(summary);
+        } else {
+            tracing::error!("❌ NetworkLimiter non initialisé");
+        }
+    }
 
-pub struct CleanRamApp {
-    pub active_tab: Tab,
-    pub theme: theme::Theme,
-    pub ram_usage: f32,
-    pub cleaning_promise: Option<Promise<CleaningResults>>,
-    pub last_cleaned_results: Option<CleaningResults>,
-    pub disk_options: DiskCleaningOptions,
-    pub disk_cleaning_promise: Option<Promise<DiskCleaningResults>>,
-    pub last_disk_cleaned_results: Option<DiskCleaningResults>,
-    pub processes: HashSet<u32>,
-    pub defender_status_promise: Option<Promise<Result<DefenderStatus, anyhow::Error>>>,
-    pub defender_action_promise: Option<Promise<Result<bool, anyhow::Error>>>,
-    pub last_defender_status: Option<Result<DefenderStatus, anyhow::Error>>,
-    pub windows_version_string: String,
-    pub logo: egui::TextureId,
-    pub ram_icon: egui::TextureId,
-    pub is_first_frame: bool,
-    pub network_limiter: Option<NetworkLimiter>,
-    pub process_search_text: String,
-    pub speed_limit_input: String,
-}
+    /// Preview "Limiter tout le reste" (every active process except the selection) and ask
+    /// for confirmation before applying it
+    pub fn request_limit_all_except_selected(&mut self) {
+        if self.processes.is_empty() {
+            return;
+        }
+        if let Err(e) = self.speed_limit_kbps() {
+            self.notifications.error("Réseau", format!("Limite invalide : {}", e));
+            self.last_bulk_limit_summary = Some(format!("❌ Limite invalide: {}", e));
+            return;
+        }
+        let Some(limiter) = self.network_limiter.as_ref() else {
+            tracing::error!("❌ NetworkLimiter non initialisé");
+            return;
+        };
 
-impl CleanRamApp {
-    pub fn is_not_busy(&self) -> bool {
-        // Only block UI during heavy operations, not status checks
-        self.cleaning_promise.is_none() 
-            && self.disk_cleaning_promise.is_none() 
-            && self.defender_action_promise.is_none()
+        let preview = limiter.preview_limit_all_except(&self.processes);
+        if preview.targets.is_empty() {
+            self.notifications.info("Réseau", "Aucun autre processus actif à limiter");
+            return;
+        }
+        self.limit_all_preview = Some(preview);
+        self.request_action(PendingAction::LimitAllExceptSelected);
     }
 
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        // Créer des textures simples sans charger d'images pour éviter les crashes
-        let dummy_texture_id = egui::TextureId::default();
-        
-        let network_limiter = match crate::network::NetworkLimiter::new() {
-            Ok(limiter) => {
-                tracing::info!("✅ Network manager QoS initialized");
-                Some(limiter)
-            }
+    /// Apply the confirmed `limit_all_preview` as one batch
+    fn limit_all_except_selected(&mut self) {
+        let Some(preview) = self.limit_all_preview.take() else {
+            return;
+        };
+        let limit_kbps = match self.speed_limit_kbps() {
+            Ok(kbps) => kbps,
             Err(e) => {
-                tracing::error!("❌ Failed to initialize network manager: {}", e);
-                None
+                self.notifications.error("Réseau", format!("Limite invalide : {}", e));
+                return;
             }
         };
+        let Some(limiter) = self.network_limiter.as_mut() else {
+            tracing::error!("❌ NetworkLimiter non initialisé");
+            return;
+        };
 
-        let detected_os_version = crate::os_info::get_os_platform(); // Modifié pour obtenir le type d'OS
-        tracing::info!("Detected OS Platform on startup (tracing): {}", detected_os_version);
-        println!("Detected OS Platform on startup (println): {}", detected_os_version);
+        let (batch, outcomes) = limiter.limit_all_except(&preview, limit_kbps, self.exempt_selection);
+        let counts = LimitSummary::from_outcomes(&outcomes);
+        let duration = self.limit_duration.duration(self.limit_custom_minutes);
+        for (pid, _) in outcomes.iter().filter(|(_, result)| result.is_ok()) {
+            limiter.set_limit_expiry(*pid, duration);
+        }
+        let errors: Vec<String> = outcomes
+            .iter()
+            .filter_map(|(pid, result)| result.as_ref().err().map(|e| format!("PID {} : {:#}", pid, e)))
+            .collect();
+        if errors.is_empty() {
+            self.notifications.success("Réseau", format!("Limités à {} KB/s : {}", limit_kbps, counts.describe()));
+        } else {
+            self.notifications.failures(
+                "Réseau",
+                format!("Limiter tout le reste : {} échec(s) sur {}", errors.len(), outcomes.len()),
+                &errors,
+            );
+        }
+        if self.exempt_selection && batch.exempted_exe_names.len() < preview.selected_exe_names.len() {
+            self.notifications.warning("Réseau", "Une partie de la sélection n'a pas pu être prioritaire (DSCP)");
+        }
 
-        Self {
-            active_tab: Tab::Memory,
-            theme: theme::dark_theme(),
-            ram_usage: 0.0,
-            cleaning_promise: None,
-            last_cleaned_results: None,
-            disk_options: DiskCleaningOptions::default(),
-            disk_cleaning_promise: None,
-            last_disk_cleaned_results: None,
-            processes: HashSet::new(),
-            defender_status_promise: None,
-            defender_action_promise: None,
-            last_defender_status: None,
-            windows_version_string: detected_os_version, // Stocke la plateforme détectée
-            logo: dummy_texture_id,
-            ram_icon: dummy_texture_id,
-            is_first_frame: true,
-            network_limiter,
-            process_search_text: String::new(),
-            speed_limit_input: "1.0".to_string(),
+        self.last_bulk_limit_summary = Some(format!("tout sauf {} : {}", preview.selected_exe_names.join(", "), counts.describe()));
+        self.last_limit_batch = (!batch.policies.is_empty() || !batch.exempted_exe_names.is_empty()).then_some(batch.id);
+    }
+
+    /// Remove exactly the policies created by the last "Limiter tout le reste"
+    pub fn undo_last_limit_batch(&mut self) {
+        let Some(id) = self.last_limit_batch.take() else {
+            return;
+        };
+        let Some(limiter) = self.network_limiter.as_mut() else {
+            return;
+        };
+        match limiter.undo_limit_batch(id) {
+            Ok(errors) if errors.is_empty() => {
+                self.notifications.success("Réseau", "Limitation en lot annulée");
+                self.last_bulk_limit_summary = Some("annulée".to_string());
+            }
+            Ok(errors) => self.notifications.failures("Réseau", "Annulation partielle de la limitation en lot", &errors),
+            Err(e) => self.notifications.error_chain("Réseau", "Annulation impossible", &e),
         }
     }
 
-    pub fn update_network_scan(&mut self) {
+    /// Apply (or remove, when no priority is selected) DSCP marking on the selected processes
+    pub fn apply_dscp_to_selected(&mut self) {
+        if self.processes.is_empty() {
+            return;
+        }
+
         if let Some(ref mut limiter) = self.network_limiter {
-            match limiter.scan_network_processes() {
-                Ok(()) => {
-                    tracing::info!("✅ Scan réseau terminé - données temps réel");
+            let mut failures = 0;
+            let mut exe_names: Vec<String> = Vec::new();
+            for &pid in &self.processes {
+                match limiter.qos_app_name(pid) {
+                    Ok(exe_name) => exe_names.push(exe_name),
+                    Err(e) => {
+                        tracing::error!("❌ Marquage DSCP PID {} impossible: {}", pid, e);
+                        failures += 1;
+                    }
                 }
-                Err(e) => {
-                    tracing::error!("❌ Erreur scan réseau: {}", e);
+            }
+            exe_names.sort_by_key(|name| name.to_lowercase());
+            exe_names.dedup_by_key(|name| name.to_lowercase());
+
+            let mut successes = 0;
+            for exe_name in &exe_names {
+                let result = match self.dscp_selection {
+                    Some(dscp) => limiter.set_process_dscp(exe_name, dscp),
+                    None => limiter.remove_process_dscp(exe_name),
+                };
+                match result {
+                    Ok(()) => successes += 1,
+                    Err(e) => {
+                        tracing::error!("❌ Échec marquage DSCP {}: {}", exe_name, e);
+                        failures += 1;
+                    }
                 }
             }
+
+            self.last_dscp_message = Some(format!("{} réussites, {} échecs", successes, failures));
+        } else {
+            tracing::error!("❌ NetworkLimiter non initialisé");
         }
     }
 
-    pub fn scan_network_processes(&mut self) {
-        tracing::info!("🔄 Scan réseau demandé");
-        self.update_network_scan();
-    }
+    /// Cap every other process to `global_cap_input` and exempt the chosen game
+    pub fn prioritize_game(&mut self) {
+        let Some(pid) = self.priority_game_pid else {
+            self.last_global_limit_message = Some("❌ Choisissez d'abord un jeu".to_string());
+            return;
+        };
 
-    pub fn limit_process(&mut self, pid: u32) {
-        tracing::info!("🎯 Début limitation processus PID {}", pid);
-        
-        if let Some(ref mut limiter) = self.network_limiter {
-            // Vérifier si le processus existe dans le scan
-            let process_exists = limiter.get_processes().iter().any(|p| p.pid == pid);
-            if !process_exists {
-                tracing::warn!("⚠️ Processus PID {} non trouvé dans le scan réseau", pid);
+        let limit_mbps = match crate::network::parse_speed_limit_mbps(&self.global_cap_input) {
+            Ok(mbps) => mbps,
+            Err(e) => {
+                tracing::error!("❌ Format de limite globale invalide '{}': {}", self.global_cap_input, e);
+                self.last_global_limit_message = Some(format!("❌ Limite invalide: {}", e));
                 return;
             }
-            
-            let limit_mbps = match crate::network::parse_speed_limit_mbps(&self.speed_limit_input) {
-                Ok(mbps) => {
-                    tracing::info!("📊 Limitation parse: {} MB/s → OK", mbps);
-                    mbps
-                },
+        };
+        let limit_kbps = (limit_mbps * 1024.0) as u32;
+
+        if let Some(ref mut limiter) = self.network_limiter {
+            let exe_name = match limiter.qos_app_name(pid) {
+                Ok(exe_name) => exe_name,
                 Err(e) => {
-                    tracing::error!("❌ Format de limitation invalide '{}': {}", self.speed_limit_input, e);
+                    self.last_global_limit_message = Some(format!("❌ {}", e));
                     return;
                 }
             };
-            
-            let limit_kbps = (limit_mbps * 1024.0) as u32;
-            tracing::info!("🔢 Conversion: {:.1} MB/s → {} KB/s", limit_mbps, limit_kbps);
-            
-            match limiter.set_process_speed_limit(pid, limit_kbps) {
-                Ok(()) => {
-                    tracing::info!("✅ Limitation QoS appliquée: PID {} → {:.1} MB/s ({} KB/s)", pid, limit_mbps, limit_kbps);
-                    
-                    // Vérifier immédiatement si la politique a été créée
-                    match limiter.verify_qos_policies() {
-                        Ok(policies) => {
-                            let policy_count = policies.len();
-                            tracing::info!("📋 Vérification: {} politiques QoS trouvées après création", policy_count);
-                            
-                            // Chercher notre politique spécifique
-                            let our_policy_name = format!("GameBooster_Limit_{}", pid);
-                            let found = policies.iter().any(|p| p.name == our_policy_name);
-                            if found {
-                                tracing::info!("✅ Politique {} confirmée active", our_policy_name);
-                            } else {
-                                tracing::warn!("⚠️ Politique {} non trouvée dans la liste active", our_policy_name);
-                            }
-                        }
-                        Err(e) => {
-                            tracing::warn!("⚠️ Impossible de vérifier les politiques: {}", e);
-                        }
-                    }
-                }
+            self.last_global_limit_message = Some(match limiter.set_global_limit_except(&[exe_name.clone()], limit_kbps) {
+                Ok(()) => format!("✅ {} prioritaire, reste du trafic limité à {:.2} MB/s", exe_name, limit_mbps),
                 Err(e) => {
-                    tracing::error!("❌ Échec limitation QoS PID {}: {}", pid, e);
+                    tracing::error!("❌ Échec limite globale: {}", e);
+                    self.notifications.error_chain("Réseau", "Limite globale impossible", &e);
+                    format!("❌ Échec: {}", e)
                 }
-            }
+            });
         } else {
-            tracing::error!("❌ NetworkLimiter non initialisé pour PID {}", pid);
+            tracing::error!("❌ NetworkLimiter non initialisé");
         }
     }
 
-    pub fn remove_process_limit(&mut self, pid: u32) {
-        if let Some(ref mut limiter) = self.network_limiter {
-            match limiter.remove_process_limit(pid) {
-                Ok(()) => {
-                    tracing::info!("✅ Limitation supprimée: PID {}", pid);
-                }
-                Err(e) => {
-                    tracing::error!("❌ Échec suppression limitation PID {}: {}", pid, e);
-                }
+    /// Create the rule of the "Règles par port" form
+    pub fn add_port_rule(&mut self) {
+        let rule = self.port_rule_draft;
+        let Some(ref mut limiter) = self.network_limiter else {
+            tracing::error!("❌ NetworkLimiter non initialisé");
+            return;
+        };
+        self.last_port_rule_message = Some(match limiter.set_port_limit(rule) {
+            Ok(()) => format!("✅ {} limité à {} KB/s", rule.describe(), rule.limit_kbps),
+            Err(e) => {
+                tracing::error!("❌ Échec règle par port {}: {}", rule.describe(), e);
+                self.notifications.error_chain("Réseau", "Règle par port impossible", &e);
+                format!("❌ Échec: {}", e)
             }
-        }
+        });
     }
 
-    pub fn apply_speed_limit_to_selected(&mut self) {
-        let selected_pids: Vec<u32> = self.processes.iter().copied().collect();
-        
-        for pid in selected_pids {
-            self.limit_process(pid);
-        }
-        
-        if !self.processes.is_empty() {
-            tracing::info!("✅ Limitation appliquée à {} processus sélectionnés", self.processes.len());
+    pub fn remove_port_rule(&mut self, rule: &PortRule) {
+        let Some(ref mut limiter) = self.network_limiter else {
+            return;
+        };
+        self.last_port_rule_message = Some(match limiter.remove_port_limit(rule) {
+            Ok(()) => format!("✅ Règle {} supprimée", rule.describe()),
+            Err(e) => {
+                tracing::error!("❌ Échec suppression règle par port {}: {}", rule.describe(), e);
+                self.notifications.error_chain("Réseau", "Suppression de la règle par port impossible", &e);
+                format!("❌ Échec: {}", e)
+            }
+        });
+    }
+
+    pub fn clear_global_limit(&mut self) {
+        if let Some(ref mut limiter) = self.network_limiter {
+            self.last_global_limit_message = Some(match limiter.clear_global_limit() {
+                Ok(()) => "✅ Limite globale supprimée".to_string(),
+                Err(e) => {
+                    tracing::error!("❌ Échec suppression limite globale: {}", e);
+                    self.notifications.error_chain("Réseau", "Suppression de la limite globale impossible", &e);
+                    format!("❌ Échec: {}", e)
+                }
+            });
         }
     }
 
@@ -214,7 +3683,148 @@ impl CleanRamApp {
         tracing::info!("✅ {} processus désélectionnés", count);
     }
 
+    /// Block or unblock all network access of a process
+    pub fn set_process_blocked(&mut self, pid: u32, blocked: bool) {
+        if let Some(ref mut limiter) = self.network_limiter {
+            let Some(exe_path) = limiter.process_exe_path(pid) else {
+                tracing::error!("❌ Exécutable introuvable pour PID {}", pid);
+                self.notifications.error("Réseau", format!("Chemin de l'exécutable introuvable pour PID {}", pid));
+                self.last_network_error = Some(format!("Chemin de l'exécutable introuvable pour PID {}", pid));
+                return;
+            };
+            let result = if blocked {
+                limiter.block_process_network(&exe_path)
+            } else {
+                limiter.unblock_process_network(&exe_path)
+            };
+            if let Err(e) = result {
+                tracing::error!("❌ Échec (dé)blocage réseau PID {}: {}", pid, e);
+                self.notifications.error_chain("Réseau", &format!("Blocage PID {} impossible", pid), &e);
+                self.last_network_error = Some(format!("Blocage PID {} impossible : {}", pid, e));
+            }
+        }
+    }
+
+    /// Remove every limit, global cap and firewall block
+    pub fn reset_all_network_rules(&mut self) {
+        self.clear_all_network_limits();
+        if let Some(ref mut limiter) = self.network_limiter {
+            if let Err(e) = limiter.clear_all_blocks() {
+                tracing::error!("❌ Échec suppression des blocages: {}", e);
+                self.notifications.error_chain("Réseau", "Suppression des blocages impossible", &e);
+            }
+        }
+    }
+
+    /// List the system QoS policies in the background for the reconciliation panel
+    pub fn start_qos_verification(&mut self) {
+        if self.qos_verification_promise.is_some() {
+            return;
+        }
+        let Some(ref limiter) = self.network_limiter else {
+            return;
+        };
+        let backend = limiter.qos_backend();
+        self.qos_verification_promise = Some(Promise::spawn_thread("qos_verification", move || {
+            NetworkLimiter::list_qos_policies(backend, true)
+        }));
+    }
+
+    pub fn poll_qos_verification(&mut self) {
+        let Some(promise) = self.qos_verification_promise.take() else {
+            return;
+        };
+        match promise.try_take() {
+            Ok(result) => {
+                if let Err(e) = &result {
+                    tracing::error!("❌ Vérification des politiques QoS impossible: {}", e);
+                }
+                self.qos_verification = Some((chrono::Local::now(), result.map_err(|e| e.to_string())));
+            }
+            Err(promise) => self.qos_verification_promise = Some(promise),
+        }
+    }
+
+    /// Delete (untracked policy) or create again (tracked policy missing from Windows) a
+    /// row of the reconciliation panel, then list the policies again
+    pub fn reconcile_qos_policy(&mut self, policy: &QosPolicyInfo, reapply: bool) {
+        let Some(ref mut limiter) = self.network_limiter else {
+            return;
+        };
+        let result = if reapply {
+            limiter.reapply_policy(&policy.name)
+        } else {
+            limiter.remove_untracked_policy(policy)
+        };
+        match result {
+            Ok(()) if reapply => self.notifications.success("Réseau", format!("Politique {} recréée", policy.name)),
+            Ok(()) => self.notifications.success("Réseau", format!("Politique {} supprimée", policy.name)),
+            Err(e) => {
+                tracing::error!("❌ Politique {}: {}", policy.name, e);
+                self.notifications.error_chain("Réseau", &format!("Politique {}", policy.name), &e);
+                self.last_network_error = Some(format!("Politique {} : {}", policy.name, e));
+            }
+        }
+        self.start_qos_verification();
+    }
+
+    /// Hand the exited process settings to the limiter after a change
+    pub fn apply_exited_limit_handling(&mut self) {
+        if let Some(ref mut limiter) = self.network_limiter {
+            limiter.set_exited_limit_handling(self.exited_limit_action, self.exited_limit_grace_secs);
+        }
+    }
+
+    /// Delete (`remove`) or keep for the next launch the limit of a process that exited
+    pub fn resolve_exited_limit(&mut self, policy_name: &str, remove: bool) {
+        let Some(ref mut limiter) = self.network_limiter else {
+            return;
+        };
+        if !remove {
+            limiter.keep_exited_limit(policy_name);
+            return;
+        }
+        match limiter.remove_exited_limit(policy_name) {
+            Ok(()) => self.notifications.success("Réseau", format!("Limite {} supprimée", policy_name)),
+            Err(e) => {
+                tracing::error!("❌ Limite {}: {}", policy_name, e);
+                self.notifications.error_chain("Réseau", &format!("Limite {}", policy_name), &e);
+            }
+        }
+    }
+
+    /// Keep (`adopt`) or delete a QoS policy left by a previous session
+    pub fn resolve_orphaned_policy(&mut self, name: &str, adopt: bool) {
+        if let Some(ref mut limiter) = self.network_limiter {
+            let result = if adopt {
+                limiter.adopt_orphaned_policy(name)
+            } else {
+                limiter.remove_orphaned_policy(name)
+            };
+            if let Err(e) = result {
+                tracing::error!("❌ Politique orpheline {}: {}", name, e);
+                self.notifications.error_chain("Réseau", &format!("Politique {}", name), &e);
+                self.last_network_error = Some(format!("Politique {} : {}", name, e));
+            }
+        }
+    }
+
+    /// Put back the TCP auto-tuning restricted by older builds, the user having asked for it
+    pub fn reset_legacy_autotuning(&mut self) {
+        let Some(ref mut limiter) = self.network_limiter else {
+            return;
+        };
+        match limiter.reset_legacy_autotuning() {
+            Ok(change) => self.notifications.success("Réseau", change),
+            Err(e) => {
+                tracing::error!("❌ Auto-tuning TCP: {}", e);
+                self.notifications.error_chain("Réseau", "Rétablissement de l'auto-tuning TCP impossible", &e);
+            }
+        }
+    }
+
     pub fn clear_all_network_limits(&mut self) {
+        self.limit_verifications.clear();
         if let Some(ref mut limiter) = self.network_limiter {
             match limiter.clear_all_limits() {
                 Ok(()) => {
@@ -222,6 +3832,7 @@ impl CleanRamApp {
                 }
                 Err(e) => {
                     tracing::error!("❌ Échec suppression globale: {}", e);
+                    self.notifications.error_chain("Réseau", "Suppression des limitations impossible", &e);
                 }
             }
         }
@@ -231,20 +3842,129 @@ impl CleanRamApp {
 impl eframe::App for CleanRamApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         ctx.set_visuals(self.theme.visuals.clone());
-        let is_linux = self.windows_version_string.to_lowercase() == "linux";
+        self.track_window_geometry(ctx);
+        let capabilities = self.os_capabilities;
+        // Sous Linux, l'onglet réseau est utilisable dès que la surveillance /proc a démarré
+        let network_available =
+            capabilities.qos_supported && (self.network_limiter.is_some() || self.os_details.platform == "windows");
+        self.check_defender_reenable(ctx);
+        self.run_due_scheduled_tasks();
+        self.stop_hidden_ping_monitor(ctx);
+        if !self.scheduler_config.tasks.is_empty() {
+            ctx.request_repaint_after(std::time::Duration::from_secs(60));
+        }
+        self.poll_profile_task();
+        if self.profile_promise.is_some() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(250));
+        }
+        self.poll_cleanup_task();
+        self.poll_termination();
+        self.poll_audio_task();
+        self.poll_tweak_task();
+        self.poll_wsl_task();
+        if self.audio_promise.is_some() || self.tweak_promise.is_some() || self.wsl_promise.is_some() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(250));
+        }
+        if self.terminate_promise.is_some() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(250));
+        }
+        if self.cleanup_promise.is_some() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(250));
+        }
+        self.poll_update_check();
+        self.poll_update_download();
+        if self.update_check_promise.is_some() || self.update_download.is_some() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(250));
+        }
+        self.poll_qos_requirements();
+        self.poll_diagnostic_report();
+        if self.diagnostic_promise.is_some() || self.qos_requirements_promise.is_some() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(250));
+        }
+        self.handle_shortcuts(ctx, network_available);
+        self.update_global_hotkey(ctx);
+        self.poll_ipc_commands(ctx);
+        self.poll_hotkey_cleaning(ctx);
+        self.tick_limit_expirations(ctx);
+        self.tick_log_retention();
+        self.tick_connection_profile(ctx);
+        self.poll_tamper_guidance(ctx);
+        self.tick_memory_alert(ctx);
+        self.tick_drift_check(ctx);
+        self.update_launcher_watcher();
+        if self.launcher_watcher.is_some() {
+            ctx.request_repaint_after(std::time::Duration::from_secs(2));
+        }
+        if !self.onboarding_completed && self.onboarding.is_none() {
+            self.onboarding = Some(Onboarding::start(self));
+        }
+        onboarding::draw_onboarding(self, ctx);
+        network_ui::draw_orphaned_policies_dialog(self, ctx);
+        settings_ui::draw_cleanup_report(self, ctx);
+        settings_ui::draw_diagnostic_report(self, ctx);
+        services_ui::draw_tamper_guidance(self, ctx);
+        self.draw_confirmation(ctx);
 
         egui::CentralPanel::default().show(ctx, |ui| {
+            if !self.is_elevated {
+                let mut relaunch_clicked = false;
+                ui.horizontal(|ui| {
+                    ui.colored_label(self.theme.warning, "⚠ Non administrateur — certaines fonctions limitées");
+                    if ui.button("🔐 Relancer en administrateur").clicked() {
+                        relaunch_clicked = true;
+                    }
+                    if let Some(error) = &self.elevation_error {
+                        ui.colored_label(self.theme.error, error);
+                    }
+                });
+                if relaunch_clicked {
+                    self.relaunch_as_admin(ctx);
+                }
+                ui.separator();
+            }
+            if self.simulation_mode {
+                ui.colored_label(self.theme.warning, "🧪 Mode simulation : le nettoyage de disque et l'arrêt des services ne modifient rien");
+                ui.separator();
+            }
+            if let Some(applied) = &self.applied_profile {
+                let mut revert_clicked = false;
+                ui.horizontal(|ui| {
+                    ui.colored_label(
+                        self.theme.accent,
+                        egui::RichText::new(format!(
+                            "🚀 Profil « {} » actif depuis {}",
+                            applied.profile_name,
+                            applied.applied_at.format("%H:%M")
+                        ))
+                        .strong(),
+                    );
+                    let button = egui::Button::new(egui::RichText::new("↩️ Rétablir").strong().color(egui::Color32::WHITE))
+                        .fill(self.theme.accent);
+                    revert_clicked = ui.add_enabled(self.profile_promise.is_none(), button).clicked();
+                    if self.profile_promise.is_some() {
+                        ui.spinner();
+                    }
+                });
+                if revert_clicked {
+                    self.revert_profile();
+                }
+                ui.separator();
+            }
+
             ui.horizontal(|ui| {
+                if let Some(logo) = &self.logo {
+                    ui.add(egui::Image::new((logo.id(), egui::vec2(24.0, 24.0))));
+                }
                 if ui.selectable_label(self.active_tab == Tab::Memory, "🧠 Mémoire").clicked() {
                     self.active_tab = Tab::Memory;
                 }
                 
-                let optimization_label = if is_linux { "⚙️ Optimisation (WIP)" } else { "⚙️ Optimisation" };
+                let optimization_label = if capabilities.optimization_supported { "⚙️ Optimisation" } else { "⚙️ Optimisation (WIP)" };
                 if ui.selectable_label(self.active_tab == Tab::Optimization, optimization_label).clicked() {
                     self.active_tab = Tab::Optimization;
                 }
 
-                let services_label = if is_linux { "🛡️ Services (WIP)" } else { "🛡️ Services" };
+                let services_label = if capabilities.services_supported { "🛡️ Services" } else { "🛡️ Services (WIP)" };
                 if ui.selectable_label(self.active_tab == Tab::Services, services_label).clicked() {
                     self.active_tab = Tab::Services;
                 }
@@ -254,13 +3974,30 @@ impl eframe::App for CleanRamApp {
                     self.active_tab = Tab::Scheduler;
                 }
 
-                let network_label = if is_linux { "📡 Réseau (WIP)" } else { "📡 Réseau" };
+                let network_label = if network_available { "📡 Réseau" } else { "📡 Réseau (WIP)" };
                 if ui.selectable_label(self.active_tab == Tab::Network, network_label).clicked() { 
                     self.active_tab = Tab::Network;
                 }
                 if ui.selectable_label(self.active_tab == Tab::Settings, "⚙️ Paramètres").clicked() {
                     self.active_tab = Tab::Settings;
                 }
+                let mut boost = None;
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    self.notifications.draw_bell(ui, &self.theme);
+                    let default_profile = self.default_profile.as_deref().and_then(|name| profiles::find(name, &self.boost_profiles));
+                    if let Some(profile) = default_profile.filter(|_| self.applied_profile.is_none()) {
+                        if ui
+                            .add_enabled(self.profile_promise.is_none(), egui::Button::new(format!("🚀 Boost « {} »", profile.name)))
+                            .on_hover_text(crate::ui::profiles_ui::profile_summary(&profile))
+                            .clicked()
+                        {
+                            boost = Some(profile);
+                        }
+                    }
+                });
+                if let Some(profile) = boost {
+                    self.request_profile(profile);
+                }
             });
 
             ui.separator();
@@ -269,40 +4006,110 @@ impl eframe::App for CleanRamApp {
             match self.active_tab {
                 Tab::Memory => memory_ui::draw_memory_tab(self, ui, &theme_clone),
                 Tab::Optimization => {
-                    if is_linux {
-                        ui.centered_and_justified(|ui| {
-                            ui.label("Cet onglet est en cours de développement pour Linux.");
-                        });
-                    } else {
+                    if capabilities.optimization_supported {
                         disk_ui::draw_disk_tab(self, ui);
+                    } else {
+                        draw_unsupported_tab(ui, &self.os_details);
                     }
                 }
                 Tab::Services => {
-                    if is_linux {
-                        ui.centered_and_justified(|ui| {
-                            ui.label("Cet onglet est en cours de développement pour Linux.");
-                        });
-                    } else {
+                    if capabilities.services_supported {
                         services_ui::services_ui(self, ui);
+                    } else {
+                        draw_unsupported_tab(ui, &self.os_details);
                     }
                 }
                 Tab::Scheduler => scheduler_ui::draw_scheduler_tab(self, ui),
                 Tab::Network => {
-                    if is_linux {
-                        ui.centered_and_justified(|ui| {
-                            ui.label("Cet onglet est en cours de développement pour Linux.");
-                        });
-                    } else {
+                    if network_available {
                         network_ui::draw_network_tab(self, ui);
+                    } else {
+                        draw_unsupported_tab(ui, &self.os_details);
                     }
                 }
                 Tab::Settings => settings_ui::draw_settings_tab(self, ui),
             }
         });
+        // Le lecteur du journal ne tourne que sous les yeux de l'utilisateur
+        if self.active_tab != Tab::Settings {
+            self.log_tail = None;
+        }
+        self.notifications.show(ctx, &self.theme);
 
         if self.is_first_frame {
             self.is_first_frame = false;
+            // Lancé au démarrage de Windows : rester discret dans la barre des tâches
+            if self.start_minimized {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
+            }
             // Pas de vérification automatique au lancement pour éviter l'ouverture de PowerShell
         }
     }
-}
\ No newline at end of file
+
+    /// Called by eframe periodically and on exit
+    fn save(&mut self, _storage: &mut dyn eframe::Storage) {
+        self.save_settings();
+    }
+
+    /// Leave the gaming mode so that the original power plan and audio settings are not left behind
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        // Profil toujours appliqué : la session reprend au prochain lancement
+        let resumable = self.game_session.as_ref().is_some_and(|session| {
+            session.profile_applied_at.is_some()
+                && session.profile_applied_at == self.applied_profile.as_ref().map(|applied| applied.applied_at)
+        });
+        match self.game_session.as_mut() {
+            Some(session) if resumable => session.save_marker(true),
+            _ => self.close_game_session(),
+        }
+        // Désenregistre le raccourci global et arrête son thread
+        self.global_hotkey = None;
+        if let Some(limiter) = self.network_limiter.as_mut() {
+            limiter.save_session_totals();
+        }
+        self.restore_power_plan();
+        if audio::has_gaming_mode_changes() {
+            audio::exit_gaming_mode();
+        }
+    }
+}
+
+/// Resume the gaming session left open by the previous run when its profile is still
+/// applied, finalize it as interrupted otherwise
+fn recover_game_session(applied: Option<&AppliedProfile>) -> (Option<GameSession>, Vec<SessionSummary>) {
+    let mut finished = sessions::load_recent();
+    let Some(open) = sessions::load_open_session() else {
+        return (None, finished);
+    };
+    if open.profile_applied_at.is_some() && open.profile_applied_at == applied.map(|applied| applied.applied_at) {
+        tracing::info!("🎮 Session de jeu {} reprise", open.title());
+        return (Some(open), finished);
+    }
+
+    let summary = open.close(true);
+    if let Err(e) = sessions::append(&summary) {
+        tracing::error!("❌ Écriture de la session de jeu interrompue impossible: {}", e);
+    }
+    sessions::clear_open_session();
+    finished.push(summary);
+    let excess = finished.len().saturating_sub(sessions::MAX_SESSIONS);
+    finished.drain(..excess);
+    (None, finished)
+}
+
+/// Tab body when the running OS lacks what the tab needs
+fn draw_unsupported_tab(ui: &mut egui::Ui, os_details: &OsDetails) {
+    ui.centered_and_justified(|ui| {
+        ui.label(format!("Cet onglet n'est pas encore disponible sur {}.", os_details.name));
+    });
+}
+
+/// Up to ten names, then the number of the others
+fn preview_list(names: &[&str]) -> String {
+    const SHOWN: usize = 10;
+    let mut list = names.iter().take(SHOWN).copied().collect::<Vec<_>>().join(", ");
+    if names.len() > SHOWN {
+        list.push_str(&format!(" et {} autre(s)", names.len() - SHOWN));
+    }
+    list
+}
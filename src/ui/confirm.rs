@@ -0,0 +1,119 @@
+//! # Confirmation dialog
+//!
+//! Modal asking the user to confirm a destructive action, built builder-style:
+//! `ConfirmDialog::new(title).message(..).danger(..).remember_choice()`. The dialog is drawn
+//! every frame by its owner until `show` returns something other than `Pending`; the rest of
+//! the window is dimmed and does not react in the meantime. Escape cancels.
+
+use eframe::egui;
+
+use crate::theme::Theme;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DangerLevel {
+    /// Reversible, or limited to temporary data
+    Warning,
+    /// Lowers the protection of the system or cannot be undone
+    Critical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmOutcome {
+    Pending,
+    /// `remember`: "Ne plus demander" was checked
+    Confirmed { remember: bool },
+    Cancelled,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfirmDialog {
+    title: String,
+    message: String,
+    danger: DangerLevel,
+    confirm_label: String,
+    allow_remember: bool,
+    remember: bool,
+}
+
+impl ConfirmDialog {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            message: String::new(),
+            danger: DangerLevel::Warning,
+            confirm_label: "Confirmer".to_string(),
+            allow_remember: false,
+            remember: false,
+        }
+    }
+
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    pub fn danger(mut self, danger: DangerLevel) -> Self {
+        self.danger = danger;
+        self
+    }
+
+    pub fn confirm_label(mut self, label: impl Into<String>) -> Self {
+        self.confirm_label = label.into();
+        self
+    }
+
+    /// Offer a "Ne plus demander" checkbox
+    pub fn remember_choice(mut self) -> Self {
+        self.allow_remember = true;
+        self
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, theme: &Theme) -> ConfirmOutcome {
+        let mut outcome = ConfirmOutcome::Pending;
+        if ctx.input(|input| input.key_pressed(egui::Key::Escape)) {
+            return ConfirmOutcome::Cancelled;
+        }
+
+        // Voile sur le reste de la fenêtre, qui absorbe aussi les clics
+        egui::Area::new("confirm_dialog_backdrop")
+            .order(egui::Order::Middle)
+            .fixed_pos(egui::Pos2::ZERO)
+            .show(ctx, |ui| {
+                let screen = ctx.screen_rect();
+                ui.allocate_rect(screen, egui::Sense::click());
+                ui.painter().rect_filled(screen, 0.0, egui::Color32::from_black_alpha(140));
+            });
+
+        let (icon, color) = match self.danger {
+            DangerLevel::Warning => ("⚠️", theme.warning),
+            DangerLevel::Critical => ("⛔", theme.error),
+        };
+        egui::Area::new("confirm_dialog")
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                egui::Frame::window(ui.style()).show(ui, |ui| {
+                    ui.set_max_width(420.0);
+                    ui.colored_label(color, egui::RichText::new(format!("{} {}", icon, self.title)).heading());
+                    ui.add_space(6.0);
+                    ui.label(&self.message);
+                    ui.add_space(6.0);
+                    if self.allow_remember {
+                        ui.checkbox(&mut self.remember, "Ne plus demander");
+                    }
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        let confirm = egui::Button::new(egui::RichText::new(&self.confirm_label).color(egui::Color32::WHITE)).fill(color);
+                        if ui.add(confirm).clicked() {
+                            outcome = ConfirmOutcome::Confirmed { remember: self.remember };
+                        }
+                        if ui.button("Annuler").clicked() {
+                            outcome = ConfirmOutcome::Cancelled;
+                        }
+                    });
+                });
+            });
+
+        outcome
+    }
+}
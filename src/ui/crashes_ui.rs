@@ -0,0 +1,176 @@
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+
+use crate::diagnostics::event_log::{CrashEvent, CrashSort, LOOKBACK_DAYS, MAX_EVENTS};
+use crate::ui::app::CleanRamApp;
+
+/// "Crashs récents": crashes of the event logs, read when the section is first opened
+pub fn draw_recent_crashes(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    app.poll_crash_query();
+    let title = match &app.crash_query {
+        Some(query) => format!("💥 Crashs récents ({})", query.crashes.len()),
+        None => "💥 Crashs récents".to_string(),
+    };
+    egui::CollapsingHeader::new(title).id_source("recent_crashes").show(ui, |ui| {
+        if !cfg!(target_os = "windows") {
+            ui.weak("Lecture du journal d'événements Windows uniquement");
+            return;
+        }
+        if app.crash_query.is_none() {
+            app.start_crash_query();
+        }
+        draw_crash_list(app, ui);
+    });
+}
+
+fn draw_crash_list(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    let theme = app.theme.clone();
+    ui.weak(format!(
+        "Plantages d'applications, rapports d'erreur et redémarrages inattendus des {} derniers jours (journaux Application et Système)",
+        LOOKBACK_DAYS
+    ));
+    ui.horizontal(|ui| {
+        let busy = app.crash_promise.is_some();
+        if ui.add_enabled(!busy, egui::Button::new("🔄 Actualiser")).clicked() {
+            app.start_crash_query();
+        }
+        if busy {
+            ui.spinner();
+            ui.label("Lecture des journaux...");
+        }
+    });
+
+    let Some(query) = &app.crash_query else {
+        return;
+    };
+    for skipped in &query.skipped_logs {
+        ui.colored_label(theme.warning, format!("⚠️ Journal non lu : {}", skipped));
+    }
+    if query.truncated {
+        ui.weak(format!("Limité aux {} événements les plus récents", MAX_EVENTS));
+    }
+    if query.crashes.is_empty() {
+        ui.label("✅ Aucun plantage enregistré");
+        return;
+    }
+
+    let mut crashes: Vec<CrashEvent> = query.crashes.clone();
+    let sort = app.crash_sort;
+    crashes.sort_by(|a, b| sort.compare(a, b));
+    if app.crash_sort_descending {
+        crashes.reverse();
+    }
+    let during_play = crashes.iter().filter(|crash| crash.session.is_some()).count();
+    if during_play > 0 {
+        ui.colored_label(theme.warning, format!("🎮 {} plantage(s) pendant une session de jeu", during_play));
+    }
+
+    let mut toggled = None;
+    ui.push_id("recent_crashes_table", |ui| {
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::exact(24.0))
+            .column(Column::initial(120.0))
+            .column(Column::initial(150.0))
+            .column(Column::initial(150.0).clip(true))
+            .column(Column::initial(140.0).clip(true))
+            .column(Column::initial(100.0))
+            .column(Column::remainder().at_least(100.0).clip(true))
+            .max_scroll_height(260.0)
+            .header(22.0, |mut header| {
+                header.col(|_| {});
+                header.col(|ui| crash_sort_header(app, ui, "Date", CrashSort::Time));
+                header.col(|ui| crash_sort_header(app, ui, "Type", CrashSort::Kind));
+                header.col(|ui| crash_sort_header(app, ui, "Application", CrashSort::Application));
+                header.col(|ui| crash_sort_header(app, ui, "Module en faute", CrashSort::Module));
+                header.col(|ui| {
+                    ui.strong("Code");
+                });
+                header.col(|ui| {
+                    ui.strong("Session");
+                });
+            })
+            .body(|mut body| {
+                for crash in &crashes {
+                    let key = (crash.time, crash.event_id);
+                    let expanded = app.crash_expanded == Some(key);
+                    body.row(22.0, |mut row| {
+                        row.col(|ui| {
+                            if ui.small_button(if expanded { "⏷" } else { "⏵" }).on_hover_text("Détails").clicked() {
+                                toggled = Some(key);
+                            }
+                        });
+                        row.col(|ui| {
+                            ui.label(crash.time.format("%d/%m %H:%M").to_string());
+                        });
+                        row.col(|ui| {
+                            ui.label(crash.kind.label());
+                        });
+                        row.col(|ui| {
+                            ui.label(crash.application.as_deref().unwrap_or("—"));
+                        });
+                        row.col(|ui| {
+                            ui.label(crash.faulting_module.as_deref().unwrap_or("—"));
+                        });
+                        row.col(|ui| {
+                            ui.monospace(crash.exception_code.as_deref().unwrap_or("—"));
+                        });
+                        row.col(|ui| match &crash.session {
+                            Some(session) => {
+                                ui.colored_label(theme.warning, format!("🎮 {}", session));
+                            }
+                            None => {
+                                ui.weak("—");
+                            }
+                        });
+                    });
+                }
+            });
+    });
+
+    if let Some(key) = toggled {
+        app.crash_expanded = if app.crash_expanded == Some(key) { None } else { Some(key) };
+    }
+    let Some(crash) = app.crash_expanded.and_then(|key| crashes.iter().find(|crash| (crash.time, crash.event_id) == key)) else {
+        return;
+    };
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        ui.horizontal(|ui| {
+            ui.strong(format!("{} — {}", crash.kind.label(), crash.time.format("%d/%m/%Y %H:%M:%S")));
+            if ui.button("📋 Copier les détails").clicked() {
+                ui.output_mut(|output| output.copied_text = crash.details());
+            }
+        });
+        ui.weak(format!("Journal {}, source {}, événement {}", crash.log, crash.provider, crash.event_id));
+        egui::ScrollArea::vertical().id_source("crash_details").max_height(180.0).show(ui, |ui| {
+            egui::Grid::new("crash_details_grid").num_columns(2).striped(true).show(ui, |ui| {
+                for (name, value) in &crash.data {
+                    ui.label(name);
+                    ui.label(value);
+                    ui.end_row();
+                }
+            });
+        });
+    });
+}
+
+fn crash_sort_header(app: &mut CleanRamApp, ui: &mut egui::Ui, label: &str, column: CrashSort) {
+    let is_active = app.crash_sort == column;
+    let text = if is_active {
+        format!("{} {}", label, if app.crash_sort_descending { "⬇" } else { "⬆" })
+    } else {
+        label.to_string()
+    };
+
+    if ui.selectable_label(is_active, egui::RichText::new(text).strong()).clicked() {
+        if is_active {
+            app.crash_sort_descending = !app.crash_sort_descending;
+        } else {
+            app.crash_sort = column;
+            app.crash_sort_descending = column == CrashSort::Time;
+        }
+    }
+}
@@ -1,6 +1,12 @@
 use eframe::egui;
 use egui::ProgressBar;
-use crate::ui::app::CleanRamApp;
+use crate::ui::app::{CleanRamApp, PendingAction};
+use crate::disk::game_libraries::{GameSort, InstalledGame, RECLAIM_AFTER_DAYS};
+use crate::disk::space_analyzer::{SizedPath, SpaceSort};
+use crate::disk::drive_info::{MediaType, OptimizeMode};
+use crate::disk::thumbnails::LockedThumbnails;
+use crate::disk::{CategoryResult, DiskCategory};
+use egui_extras::{Column, TableBuilder};
 use poll_promise::Promise;
 
 pub fn draw_disk_tab(app: &mut CleanRamApp, ui: &mut egui::Ui) {
@@ -12,16 +18,31 @@ pub fn draw_disk_tab(app: &mut CleanRamApp, ui: &mut egui::Ui) {
     ui.horizontal(|ui| {
         ui.checkbox(&mut app.disk_options.clean_temp_files, "🗃️ Fichiers temporaires");
         ui.checkbox(&mut app.disk_options.clean_browser_cache, "🌐 Cache navigateurs");
+        ui.add_enabled(
+            app.disk_options.clean_browser_cache,
+            egui::Checkbox::new(&mut app.disk_options.force_close_browsers, "Fermer les navigateurs ouverts"),
+        )
+        .on_hover_text("Sinon, le cache d'un navigateur en cours d'exécution est ignoré");
     });
+    if app.disk_options.clean_browser_cache && app.is_metered() {
+        ui.colored_label(app.theme.warning, "📶 Connexion limitée — le cache sera retéléchargé");
+    }
+    if app.disk_options.clean_browser_cache {
+        draw_browser_selection(app, ui);
+    }
     
     ui.horizontal(|ui| {
         ui.checkbox(&mut app.disk_options.clean_thumbnails, "🖼️ Miniatures");
         ui.checkbox(&mut app.disk_options.clean_recycle_bin, "🗑️ Corbeille");
     });
+    if app.disk_options.clean_thumbnails {
+        draw_locked_thumbnails(app, ui);
+    }
 
     ui.horizontal(|ui| {
         ui.checkbox(&mut app.disk_options.clean_system_cache, "⚙️ Cache système");
     });
+    draw_temp_policy(app, ui);
 
     ui.separator();
 
@@ -31,43 +52,110 @@ pub fn draw_disk_tab(app: &mut CleanRamApp, ui: &mut egui::Ui) {
         ui.checkbox(&mut app.disk_options.win11_optimizations, "Windows 11");
         ui.checkbox(&mut app.disk_options.win10_optimizations, "Windows 10");
     });
+    if app.os_capabilities.gpu_scheduling {
+        ui.label("Mode Jeu, capture GameDVR désactivée, planification GPU accélérée, plan Performances élevées");
+    } else {
+        ui.label("Mode Jeu, capture GameDVR désactivée, plan Performances élevées");
+    }
+    draw_os_tweaks(app, ui);
+    draw_tweaks(app, ui);
+
+    ui.separator();
+
+    draw_power_plans(app, ui);
+
+    ui.separator();
+
+    draw_audio_devices(app, ui);
+
+    ui.separator();
+
+    draw_hosts_blocker(app, ui);
+
+    ui.separator();
+
+    draw_duplicates(app, ui);
+
+    ui.separator();
+
+    draw_drives(app, ui);
+
+    ui.separator();
+
+    draw_space_analyzer(app, ui);
+
+    ui.separator();
+
+    draw_game_libraries(app, ui);
+
+    ui.separator();
+
+    draw_startup_manager(app, ui);
 
     ui.separator();
 
     // Boutons d'action
     let is_busy = app.disk_cleaning_promise.is_some();
+    let can_clean = app.can_run(crate::disk::requires_elevation());
 
     ui.horizontal(|ui| {
-        if ui.add_enabled(!is_busy, egui::Button::new("🔍 Aperçu")).clicked() {
-            // Lance l'aperçu en arrière-plan
-            let options = app.disk_options.clone();
-            app.disk_cleaning_promise = Some(Promise::spawn_thread("disk_scan", move || {
-                match crate::disk::scan_disk_with_options(options) {
-                    Ok(results) => results,
-                    Err(_) => crate::disk::DiskCleaningResults::new(), // Résultat vide en cas d'erreur
-                }
-            }));
+        if ui.add_enabled(!is_busy, egui::Button::new("🔍 Aperçu"))
+            .on_hover_text("Réutilise une analyse récente, sinon ne parcourt que les dossiers modifiés")
+            .clicked()
+        {
+            start_disk_preview(app, false);
+        }
+        if ui.add_enabled(!is_busy, egui::Button::new("🔄"))
+            .on_hover_text("Analyse complète de tous les dossiers, sans le cache")
+            .clicked()
+        {
+            start_disk_preview(app, true);
         }
 
-        if ui.add_enabled(!is_busy, egui::Button::new("🧹 Nettoyer")).clicked() {
-            // Lance le nettoyage en arrière-plan  
-            let options = app.disk_options.clone();
-            app.disk_cleaning_promise = Some(Promise::spawn_thread("disk_clean", move || {
-                match tokio::runtime::Runtime::new().unwrap().block_on(async {
-                    crate::disk::clean_disk_with_options(options).await
-                }) {
-                    Ok(results) => results,
-                    Err(_) => crate::disk::DiskCleaningResults::new(), // Résultat vide en cas d'erreur
-                }
-            }));
+        if ui.add_enabled(!is_busy && can_clean, egui::Button::new("🧹 Nettoyer"))
+            .on_disabled_hover_text(if can_clean { "Opération en cours..." } else { crate::ui::util::ELEVATION_REQUIRED_HINT })
+            .clicked()
+        {
+            app.request_action(PendingAction::CleanDisk);
+        }
+
+        ui.separator();
+        let mut minutes = app.disk_options.preview_ttl_secs / 60;
+        ui.label("Aperçu valable");
+        if ui.add(egui::DragValue::new(&mut minutes).clamp_range(0..=120).suffix(" min"))
+            .on_hover_text("0 : toujours vérifier les dossiers modifiés")
+            .changed()
+        {
+            app.disk_options.preview_ttl_secs = minutes * 60;
         }
     });
 
+    // Catégorie ou navigateur changé après un aperçu : nouvelle estimation depuis le cache
+    let is_preview = app.last_disk_cleaned_results.as_ref().is_some_and(|results| results.estimated_at.is_some() && !results.simulated);
+    if !is_busy && is_preview && app.disk_preview_options.as_ref() != Some(&app.disk_options) {
+        if let Some(results) = crate::disk::preview_cache::cached_preview(&app.disk_options) {
+            app.last_disk_cleaned_results = Some(results);
+        }
+        app.disk_preview_options = Some(app.disk_options.clone());
+    }
+
     // Gestion des promises et barre de progression
     if let Some(promise) = &app.disk_cleaning_promise {
         if let Some(result) = promise.ready() {
             // Promise terminée, récupère le résultat directement
+            let entry = crate::history::HistoryEntry::from_disk(result);
+            if !result.errors.is_empty() {
+                let message = format!("{} erreur(s) pendant l'opération sur le disque", result.errors.len());
+                app.notifications.failures("Disque", message, &result.errors);
+            } else if app.disk_operation_is_clean && !result.simulated {
+                let freed_mb = result.total_space_freed as f64 / 1024.0 / 1024.0;
+                app.notifications.success("Disque", format!("Nettoyage terminé, {:.2} MB libérés", freed_mb));
+            }
             app.last_disk_cleaned_results = Some(result.clone());
+            // L'aperçu et la simulation ne libèrent rien : seul un vrai nettoyage est historisé
+            if app.disk_operation_is_clean && !result.simulated {
+                app.record_history(entry);
+            }
             app.disk_cleaning_promise = None; // Nettoie la promise
         } else {
             // En cours d'exécution
@@ -80,8 +168,1284 @@ pub fn draw_disk_tab(app: &mut CleanRamApp, ui: &mut egui::Ui) {
     // Résultats
     if let Some(results) = &app.last_disk_cleaned_results {
         ui.separator();
+        if results.simulated {
+            ui.colored_label(app.theme.warning, "🧪 SIMULATION — rien n'a été modifié");
+        }
         ui.label("✅ Derniers résultats :");
-        ui.label(format!("📁 Fichiers temporaires: {}", results.temp_files_cleaned));
-        ui.label(format!("💾 Espace libéré: {:.2} MB", results.total_space_freed as f64 / 1024.0 / 1024.0));
+        if let Some(estimated_at) = results.estimated_at {
+            let minutes = (chrono::Local::now() - estimated_at).num_minutes();
+            let age = if minutes < 1 { "moins d'une minute".to_string() } else { format!("{} min", minutes) };
+            ui.label(format!("🕒 Estimation datant de {}", age))
+                .on_hover_text("🔄 pour une analyse complète");
+        }
+        if results.duplicates_cleaned > 0 {
+            ui.label(format!("🗂️ Doublons : {}", format_size(results.duplicates_cleaned)));
+        } else {
+            for category in DiskCategory::ALL {
+                draw_category_result(ui, &app.theme, category, results.category(category), app.disk_operation_is_clean);
+            }
+        }
+        ui.label(format!(
+            "💾 Espace libéré: {}, {} fichier(s)",
+            format_size(results.total_space_freed),
+            format_count(results.files_processed)
+        ));
+        if results.pending_reboot_bytes > 0 {
+            ui.label(format!("⏳ {} libérés au prochain redémarrage", format_size(results.pending_reboot_bytes)));
+        }
+        for browser in &results.browsers {
+            let name = browser.browser.name();
+            if !browser.skipped {
+                ui.label(format!(
+                    "    {} ({} profil(s)) : {}, {} fichier(s)",
+                    name,
+                    browser.profiles.len(),
+                    format_size(browser.freed),
+                    format_count(browser.files)
+                ))
+                .on_hover_text(browser.profiles.join(", "));
+            } else if app.disk_operation_is_clean {
+                ui.colored_label(app.theme.warning, format!("ℹ️ {}", crate::disk::browser_cache::skipped_message(name)));
+            } else {
+                ui.colored_label(app.theme.warning, format!("ℹ️ {} en cours d'exécution — cache non compté", name));
+            }
+        }
+    }
+    draw_largest_files(app, ui);
+} 
+
+/// "il y a 3 j", "il y a 5 h", "il y a 12 min"
+fn format_age(modified: chrono::DateTime<chrono::Local>) -> String {
+    let age = chrono::Local::now() - modified;
+    if age.num_days() > 0 {
+        format!("il y a {} j", age.num_days())
+    } else if age.num_hours() > 0 {
+        format!("il y a {} h", age.num_hours())
+    } else {
+        format!("il y a {} min", age.num_minutes().max(0))
+    }
+}
+
+/// Biggest files of the preview, each with its folder and its own deletion
+fn draw_largest_files(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    let Some(results) = app.last_disk_cleaned_results.as_ref().filter(|results| !results.largest_files.is_empty()) else {
+        return;
+    };
+    let mut deleted = None;
+
+    egui::CollapsingHeader::new(format!("📄 Fichiers les plus volumineux ({})", results.largest_files.len()))
+        .id_source("disk_largest_files")
+        .show(ui, |ui| {
+            egui::ScrollArea::vertical().id_source("disk_largest_files_scroll").max_height(300.0).show(ui, |ui| {
+                egui::Grid::new("disk_largest_files_grid").striped(true).num_columns(5).show(ui, |ui| {
+                    for detail in &results.largest_files {
+                        ui.label(format_size(detail.size));
+                        ui.weak(detail.modified.map(format_age).unwrap_or_default());
+                        ui.label(crate::disk::largest_files::display_path(&detail.path))
+                            .on_hover_text(format!("{}\n{}", detail.category.label(), detail.path.display()));
+                        if ui.small_button("📂").on_hover_text("Ouvrir le dossier").clicked() {
+                            if let Err(e) = crate::utils::reveal_in_file_manager(&detail.path) {
+                                tracing::warn!("⚠️ Ouverture de {} impossible: {}", detail.path.display(), e);
+                            }
+                        }
+                        if ui.small_button("🗑️").on_hover_text("Supprimer ce fichier").clicked() {
+                            deleted = Some(detail.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+
+    let Some(detail) = deleted else {
+        return;
+    };
+    match crate::disk::delete_scanned_file(&detail) {
+        Ok(()) => {
+            if let Some(results) = app.last_disk_cleaned_results.as_mut() {
+                results.forget_file(&detail);
+            }
+            let name = crate::disk::largest_files::display_path(&detail.path);
+            app.notifications.success("Disque", format!("{} supprimé, {} libérés", name, format_size(detail.size)));
+        }
+        Err(e) => app.notifications.error("Disque", e.to_string()),
+    }
+}
+
+/// Preview scan in the background, reusing the preview cache unless `force`
+fn start_disk_preview(app: &mut CleanRamApp, force: bool) {
+    let options = app.disk_options.clone();
+    app.disk_preview_options = Some(options.clone());
+    app.disk_operation_is_clean = false;
+    app.disk_cleaning_promise = Some(Promise::spawn_thread("disk_scan", move || {
+        match crate::disk::scan_disk_with_options(options, force, true) {
+            Ok(results) => results,
+            Err(_) => crate::disk::DiskCleaningResults::new(), // Résultat vide en cas d'erreur
+        }
+    }));
+}
+
+/// "1.2 Go", "350 Mo", "12 Ko"
+fn format_size(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    let bytes = bytes as f64;
+    if bytes >= KB * KB * KB {
+        format!("{:.1} Go", bytes / (KB * KB * KB))
+    } else if bytes >= KB * KB {
+        format!("{:.0} Mo", bytes / (KB * KB))
+    } else {
+        format!("{:.0} Ko", bytes / KB)
+    }
+}
+
+/// Thousands separated by a thin space: "3 412"
+fn format_count(count: u32) -> String {
+    let digits = count.to_string();
+    let mut text = String::new();
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index) % 3 == 0 {
+            text.push('\u{202F}');
+        }
+        text.push(digit);
+    }
+    text
+}
+
+/// "📁 Fichiers temporaires : 1.2 Go, 3 412 fichiers, 12 ignorés" and its errors, collapsed
+fn draw_category_result(
+    ui: &mut egui::Ui,
+    theme: &crate::theme::Theme,
+    category: DiskCategory,
+    result: &CategoryResult,
+    is_clean: bool,
+) {
+    let icon = match category {
+        DiskCategory::TempFiles => "📁",
+        DiskCategory::BrowserCache => "🌐",
+        DiskCategory::Thumbnails => "🖼️",
+    };
+    let mut text = format!(
+        "{} {} : {}, {} fichier(s)",
+        icon,
+        category.label(),
+        format_size(result.bytes_freed),
+        format_count(result.files_deleted)
+    );
+    if result.files_skipped > 0 {
+        text.push_str(&format!(", {} ignoré(s)", format_count(result.files_skipped)));
+    }
+    let response = ui.label(text);
+    if result.files_skipped > 0 {
+        response.on_hover_text(match category {
+            DiskCategory::BrowserCache => "Fichiers des navigateurs restés ouverts",
+            _ => "Fichiers ouverts par un programme, conservés",
+        });
+    }
+    if !is_clean && result.locked_bytes > 0 {
+        ui.colored_label(
+            theme.warning,
+            format!("    dont {} : nécessite un redémarrage ou la fermeture d'Explorer", format_size(result.locked_bytes)),
+        );
+    }
+    if result.pending_reboot_bytes > 0 {
+        ui.label(format!("    ⏳ {} supprimé(s) au prochain redémarrage", format_size(result.pending_reboot_bytes)));
+    }
+    if result.error_count() == 0 {
+        return;
+    }
+    let verb = if is_clean { "non supprimé(s)" } else { "illisible(s)" };
+    egui::CollapsingHeader::new(egui::RichText::new(format!("⚠️ {} fichier(s) {}", result.error_count(), verb)).color(theme.warning))
+        .id_source(("disk_category_errors", category.label()))
+        .show(ui, |ui| {
+            egui::ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
+                for error in &result.errors {
+                    ui.weak(error);
+                }
+                if result.errors_omitted > 0 {
+                    ui.weak(format!("… et {} autre(s)", result.errors_omitted));
+                }
+            });
+        });
+}
+
+/// What the cleaning does with the thumbnail cache files held open by Explorer
+fn draw_locked_thumbnails(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+        ui.label("    Miniatures verrouillées par l'Explorateur :");
+        egui::ComboBox::from_id_source("locked_thumbnails")
+            .selected_text(app.disk_options.locked_thumbnails.label())
+            .show_ui(ui, |ui| {
+                for strategy in LockedThumbnails::ALL {
+                    ui.selectable_value(&mut app.disk_options.locked_thumbnails, strategy, strategy.label());
+                }
+            })
+            .response
+            .on_hover_text(
+                "Ignorer : conservées jusqu'à la fermeture de session\n\
+                 Supprimer au redémarrage : Windows les supprime au prochain démarrage (administrateur requis)\n\
+                 Redémarrer l'Explorateur : la barre des tâches et les fenêtres de l'Explorateur se ferment quelques secondes",
+            );
+    });
+    if app.disk_options.locked_thumbnails == LockedThumbnails::DeleteAtReboot && !app.is_elevated {
+        ui.colored_label(app.theme.warning, "    Droits administrateur requis : les fichiers verrouillés seront ignorés");
+    }
+}
+
+/// Removable list of `items` with a field to add one
+fn draw_editable_list(ui: &mut egui::Ui, label: &str, hint: &str, items: &mut Vec<String>, new_item: &mut String) {
+    ui.horizontal_wrapped(|ui| {
+        ui.label(label);
+        let mut removed = None;
+        for (index, item) in items.iter().enumerate() {
+            if ui.small_button(format!("{} ✖", item)).on_hover_text("Retirer").clicked() {
+                removed = Some(index);
+            }
+        }
+        if let Some(index) = removed {
+            items.remove(index);
+        }
+        let response = ui.add(egui::TextEdit::singleline(new_item).hint_text(hint).desired_width(140.0));
+        let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        if (ui.small_button("➕").clicked() || submitted) && !new_item.trim().is_empty() {
+            items.push(new_item.trim().to_string());
+            new_item.clear();
+        }
+    });
+}
+
+/// Age and exclusions of the temporary files cleaning, shared by the preview
+fn draw_temp_policy(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    ui.add_enabled_ui(app.disk_options.clean_temp_files, |ui| {
+        egui::CollapsingHeader::new("Avancé").id_source("temp_policy").show(ui, |ui| {
+            let policy = &mut app.disk_options.temp_policy;
+            ui.horizontal(|ui| {
+                ui.label("Fichiers temporaires plus anciens que");
+                ui.add(egui::DragValue::new(&mut policy.min_age_hours).clamp_range(0..=24 * 90).suffix(" h"));
+            })
+            .response
+            .on_hover_text("Un installeur peut avoir besoin de ses fichiers récents au prochain démarrage (0 = tout)");
+            draw_editable_list(ui, "Extensions conservées :", "lock", &mut policy.skip_extensions, &mut app.new_temp_skip_extension);
+            draw_editable_list(ui, "Chemins conservés :", "*\\Setup*\\*", &mut policy.skip_patterns, &mut app.new_temp_skip_pattern);
+            ui.weak("Les fichiers ouverts par un programme sont toujours conservés.");
+        });
+    });
+}
+
+/// One checkbox per detected browser, with the size found by the last preview
+fn draw_browser_selection(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    let installed = app
+        .installed_browsers
+        .get_or_insert_with(crate::disk::browser_cache::installed_browsers)
+        .clone();
+    ui.horizontal_wrapped(|ui| {
+        ui.label("    Navigateurs :");
+        if installed.is_empty() {
+            ui.weak("aucun navigateur détecté");
+        }
+        for browser in &installed {
+            let size = app
+                .last_disk_cleaned_results
+                .as_ref()
+                .and_then(|results| results.browsers.iter().find(|result| result.browser == *browser))
+                .filter(|result| !result.skipped)
+                .map(|result| format!(" ({:.1} MB)", result.freed as f64 / 1024.0 / 1024.0))
+                .unwrap_or_default();
+            let mut included = app.disk_options.browsers.includes(*browser);
+            if ui.checkbox(&mut included, format!("{}{}", browser.name(), size)).changed() {
+                app.disk_options.browsers.set(*browser, included, &installed);
+            }
+        }
+        if ui.small_button("🔄").on_hover_text("Détecter à nouveau les navigateurs installés").clicked() {
+            app.installed_browsers = None;
+        }
+    });
+}
+
+/// Apply / restore of the Windows 10/11 tweaks (`disk::os_tweaks`) and their last outcome
+fn draw_os_tweaks(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    let theme = app.theme.clone();
+    let is_busy = app.os_tweaks_promise.is_some();
+    let enabled = crate::disk::os_tweaks::is_enabled_for_this_os(&app.disk_options);
+
+    ui.horizontal(|ui| {
+        if ui.add_enabled(!is_busy && enabled, egui::Button::new("⚡ Appliquer les optimisations"))
+            .on_disabled_hover_text("Cochez la version de Windows de ce PC")
+            .clicked()
+        {
+            let options = app.disk_options.clone();
+            app.os_tweaks_promise = Some(Promise::spawn_thread("os_tweaks_apply", move || {
+                crate::disk::os_tweaks::apply(&options)
+            }));
+        }
+        if ui.add_enabled(!is_busy, egui::Button::new("↩️ Restaurer les optimisations")).clicked() {
+            app.os_tweaks_promise = Some(Promise::spawn_thread("os_tweaks_restore", crate::disk::os_tweaks::restore));
+        }
+        if is_busy {
+            ui.spinner();
+        }
+    });
+
+    if let Some(promise) = app.os_tweaks_promise.take() {
+        match promise.try_take() {
+            Ok(results) => {
+                app.last_os_tweaks_results = Some(results);
+                app.tweak_registry.invalidate();
+            }
+            Err(promise) => app.os_tweaks_promise = Some(promise),
+        }
+    }
+
+    if let Some(results) = &app.last_os_tweaks_results {
+        for outcome in &results.outcomes {
+            if outcome.success {
+                ui.colored_label(theme.success, format!("✅ {} : {}", outcome.label, outcome.detail));
+            } else {
+                ui.colored_label(theme.error, format!("❌ {} : {}", outcome.label, outcome.detail));
+            }
+        }
+        for warning in &results.warnings {
+            ui.colored_label(theme.warning, format!("⚠️ {}", warning));
+        }
+    }
+}
+
+/// Switches of the tweak registry not tied to a device, with the state read from the system
+fn draw_tweaks(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    use crate::optimization::tweaks::AUDIO_TWEAK_PREFIX;
+
+    app.refresh_tweak_states();
+    ui.horizontal(|ui| {
+        ui.label("🎛️ État des optimisations :");
+        if app.tweak_promise.is_some() {
+            ui.spinner();
+        } else if ui.small_button("🔄 Actualiser").on_hover_text("Relire l'état de chaque optimisation").clicked() {
+            app.reload_tweak_states();
+        }
+    });
+    draw_tweak_rows(app, ui, "tweak_rows", |id| !id.starts_with(AUDIO_TWEAK_PREFIX));
+}
+
+/// One checkbox per tweak of `filter`, ticked when the tweak is applied, and its state
+fn draw_tweak_rows(app: &mut CleanRamApp, ui: &mut egui::Ui, id_source: &str, filter: impl Fn(&str) -> bool) {
+    use crate::optimization::tweaks::TweakState;
+    use crate::ui::util::ELEVATION_REQUIRED_HINT;
+
+    let theme = app.theme.clone();
+    let is_busy = app.tweak_promise.is_some();
+    let mut toggled = None;
+    egui::Grid::new(id_source).num_columns(2).spacing([12.0, 4.0]).show(ui, |ui| {
+        for tweak in app.tweak_registry.tweaks().iter().filter(|tweak| filter(tweak.id())) {
+            let cached = app.tweak_registry.state(tweak.id());
+            let state = cached.map(|cached| cached.state);
+            let needs_elevation = tweak.requires_elevation() && !app.is_elevated;
+            let blocked = state == Some(TweakState::BlockedByPolicy);
+            let mut checked = state == Some(TweakState::Applied);
+            let response = ui.add_enabled(
+                !is_busy && !blocked && !needs_elevation && state.is_some(),
+                egui::Checkbox::new(&mut checked, tweak.label()),
+            );
+            let response = if blocked {
+                response.on_disabled_hover_text("Contrôlé par une stratégie de l'ordinateur")
+            } else if needs_elevation {
+                response.on_disabled_hover_text(ELEVATION_REQUIRED_HINT)
+            } else {
+                response
+            };
+            if response.changed() {
+                toggled = Some((tweak.id().to_string(), checked));
+            }
+
+            match cached {
+                Some(cached) => {
+                    let color = match cached.state {
+                        TweakState::Applied => theme.success,
+                        TweakState::NotApplied => ui.visuals().weak_text_color(),
+                        TweakState::Unknown | TweakState::BlockedByPolicy => theme.warning,
+                    };
+                    let label = ui
+                        .colored_label(color, cached.state.label())
+                        .on_hover_text(format!("Lu à {}", cached.checked_at.format("%H:%M:%S")));
+                    if let Some(error) = &cached.error {
+                        label.on_hover_text(error);
+                    }
+                }
+                None => {
+                    ui.spinner();
+                }
+            }
+            ui.end_row();
+        }
+    });
+    if let Some((id, apply)) = toggled {
+        app.toggle_tweak(&id, apply);
+    }
+}
+
+/// Active power plan with one-click switch, and the plan used by the gaming mode
+fn draw_power_plans(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    let theme = app.theme.clone();
+    if app.power_plans.is_none() {
+        app.refresh_power_plans();
+    }
+    let plans = app.power_plans.clone().unwrap_or_default();
+
+    ui.label("🔋 Plan d'alimentation :");
+    let mut switch_to = None;
+    ui.horizontal_wrapped(|ui| {
+        for plan in &plans {
+            if ui.selectable_label(plan.active, &plan.name).on_hover_text(&plan.guid).clicked() && !plan.active {
+                switch_to = Some(plan.guid.clone());
+            }
+        }
+        if ui.small_button("🔄").on_hover_text("Actualiser").clicked() {
+            app.refresh_power_plans();
+        }
+    });
+    if let Some(guid) = switch_to {
+        app.switch_power_plan(&guid);
+    }
+
+    let gaming_plan = app
+        .gaming_power_plan
+        .clone()
+        .unwrap_or_else(|| crate::power::HIGH_PERFORMANCE.to_string());
+    let gaming_plan_name = plans
+        .iter()
+        .find(|plan| plan.guid == gaming_plan)
+        .map(|plan| plan.name.clone())
+        .unwrap_or_else(|| "Performances élevées".to_string());
+    let mut selected = gaming_plan.clone();
+    ui.horizontal(|ui| {
+        ui.label("🎮 Plan du mode jeu :");
+        egui::ComboBox::from_id_source("gaming_power_plan")
+            .selected_text(gaming_plan_name)
+            .show_ui(ui, |ui| {
+                for plan in &plans {
+                    ui.selectable_value(&mut selected, plan.guid.clone(), &plan.name);
+                }
+            });
+
+        if !plans.is_empty() && crate::power::find_ultimate_performance(&plans).is_none()
+            && ui.button("➕ Créer Performances optimales").clicked()
+        {
+            app.create_ultimate_performance_plan();
+        }
+    });
+    if selected != gaming_plan {
+        app.gaming_power_plan = Some(selected);
+        app.save_settings();
+    }
+
+    ui.horizontal(|ui| {
+        if app.gaming_mode_active {
+            if ui.button("⏹️ Quitter le mode jeu").clicked() {
+                app.exit_gaming_mode();
+            }
+            ui.colored_label(theme.success, "Mode jeu actif");
+        } else if ui.button("🎮 Activer le mode jeu").clicked() {
+            app.enter_gaming_mode();
+        }
+    });
+
+    if let Some(error) = &app.last_power_error {
+        ui.colored_label(theme.error, format!("❌ {}", error));
+    }
+}
+
+/// Enhancements switch of each playback device, and what the gaming mode changes for audio
+fn draw_audio_devices(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    use crate::optimization::tweaks::AUDIO_TWEAK_PREFIX;
+    use crate::ui::util::ELEVATION_REQUIRED_HINT;
+
+    let theme = app.theme.clone();
+    if app.audio_devices.is_none() && app.audio_promise.is_none() {
+        app.refresh_audio_devices();
+    }
+    let is_busy = app.audio_promise.is_some();
+
+    ui.horizontal(|ui| {
+        ui.label("🔊 Améliorations audio (effets, égaliseur, son spatial) :");
+        if is_busy {
+            ui.spinner();
+        } else if ui.small_button("🔄").on_hover_text("Actualiser").clicked() {
+            app.refresh_audio_devices();
+        }
+    });
+
+    match &app.audio_devices {
+        Some(Ok(devices)) if devices.is_empty() => {
+            ui.label("Aucun périphérique de lecture actif");
+        }
+        Some(Ok(_)) => {
+            // Chaque changement redémarre le service audio
+            draw_tweak_rows(app, ui, "audio_tweak_rows", |id| id.starts_with(AUDIO_TWEAK_PREFIX));
+        }
+        Some(Err(e)) => {
+            ui.colored_label(theme.error, format!("❌ {}", e));
+        }
+        None => {}
+    }
+
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("🎮 Mode jeu :");
+        changed |= ui
+            .checkbox(&mut app.gaming_audio_enhancements_off, "Désactiver les améliorations audio")
+            .on_hover_text("Rétablies à la sortie du mode jeu, y compris pour un périphérique débranché entre-temps")
+            .changed();
+        changed |= ui
+            .checkbox(&mut app.gaming_mmcss_priority, "Priorité MMCSS des jeux")
+            .on_hover_text("Priorités GPU et CPU de la tâche multimédia « Games », rétablies à la sortie du mode jeu")
+            .changed();
+    });
+    if changed {
+        app.save_settings();
+    }
+    if (app.gaming_audio_enhancements_off || app.gaming_mmcss_priority) && !app.is_elevated {
+        ui.colored_label(theme.warning, format!("⚠️ {}", ELEVATION_REQUIRED_HINT));
+    }
+
+    if let Some(results) = &app.last_audio_results {
+        for outcome in &results.outcomes {
+            if outcome.success {
+                ui.colored_label(theme.success, format!("✅ {} : {}", outcome.label, outcome.detail));
+            } else {
+                ui.colored_label(theme.error, format!("❌ {} : {}", outcome.label, outcome.detail));
+            }
+        }
+        for warning in &results.warnings {
+            ui.colored_label(theme.warning, format!("⚠️ {}", warning));
+        }
+    }
+}
+
+/// Built-in and custom domains of the hosts blocker, with per-entry toggles
+fn draw_hosts_blocker(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    use crate::optimization::hosts_blocker::{self, BuiltinProfile};
+
+    let theme = app.theme.clone();
+    if app.hosts_status.is_none() {
+        app.hosts_status = Some(hosts_blocker::status().map_err(|e| e.to_string()));
+    }
+    if let Some(promise) = app.hosts_promise.take() {
+        match promise.try_take() {
+            Ok(result) => {
+                if let Err(e) = &result {
+                    tracing::error!("❌ Modification du fichier hosts échouée: {}", e);
+                }
+                app.hosts_status = Some(result.map_err(|e| e.to_string()));
+            }
+            Err(promise) => app.hosts_promise = Some(promise),
+        }
+    }
+
+    ui.label("🚫 Blocage télémétrie / mises à jour (fichier hosts) :");
+    match &app.hosts_status {
+        Some(Ok(status)) if status.section_present => {
+            ui.colored_label(theme.success, format!("✅ Section GameBooster active : {} domaines bloqués", status.entry_count()));
+        }
+        Some(Ok(_)) => {
+            ui.label("Aucun domaine bloqué par GameBooster");
+        }
+        Some(Err(e)) => {
+            ui.colored_label(theme.error, format!("❌ {}", e));
+        }
+        None => {}
+    }
+
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        for profile in BuiltinProfile::ALL {
+            let mut enabled = app.hosts_profile.builtin.contains(&profile);
+            if ui.checkbox(&mut enabled, profile.label()).changed() {
+                if enabled {
+                    app.hosts_profile.builtin.insert(profile);
+                } else {
+                    app.hosts_profile.builtin.remove(&profile);
+                }
+                changed = true;
+            }
+        }
+    });
+    if app.hosts_profile.builtin.contains(&BuiltinProfile::LauncherUpdates) {
+        ui.colored_label(theme.warning, "⚠️ Les launchers concernés ne pourront plus se mettre à jour tant que le blocage est actif");
+    }
+
+    let domains = app.hosts_profile.all_domains();
+    let mut removed_domain = None;
+    egui::CollapsingHeader::new(format!("Domaines ({})", domains.len()))
+        .id_source("hosts_blocker_domains")
+        .show(ui, |ui| {
+            for domain in &domains {
+                ui.horizontal(|ui| {
+                    let mut enabled = !app.hosts_profile.disabled_domains.contains(domain);
+                    if ui.checkbox(&mut enabled, domain).changed() {
+                        if enabled {
+                            app.hosts_profile.disabled_domains.remove(domain);
+                        } else {
+                            app.hosts_profile.disabled_domains.insert(domain.clone());
+                        }
+                        changed = true;
+                    }
+                    if app.hosts_profile.custom_domains.contains(domain) && ui.small_button("🗑").clicked() {
+                        removed_domain = Some(domain.clone());
+                    }
+                });
+            }
+
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut app.hosts_domain_input);
+                let normalized = hosts_blocker::normalize_domain(&app.hosts_domain_input);
+                if ui.add_enabled(normalized.is_some(), egui::Button::new("➕ Ajouter"))
+                    .on_disabled_hover_text("Nom de domaine invalide")
+                    .clicked()
+                {
+                    if let Some(domain) = normalized {
+                        if !app.hosts_profile.custom_domains.contains(&domain) {
+                            app.hosts_profile.custom_domains.push(domain);
+                            changed = true;
+                        }
+                        app.hosts_domain_input.clear();
+                    }
+                }
+            });
+        });
+    if let Some(domain) = removed_domain {
+        app.hosts_profile.custom_domains.retain(|d| *d != domain);
+        app.hosts_profile.disabled_domains.remove(&domain);
+        changed = true;
+    }
+    if changed {
+        app.save_settings();
+    }
+
+    let is_busy = app.hosts_promise.is_some();
+    let can_write = app.can_run(true);
+    ui.horizontal(|ui| {
+        if ui.add_enabled(!is_busy && can_write, egui::Button::new("🚫 Appliquer le blocage"))
+            .on_disabled_hover_text(crate::ui::util::ELEVATION_REQUIRED_HINT)
+            .clicked()
+        {
+            let profile = app.hosts_profile.clone();
+            app.hosts_promise = Some(Promise::spawn_thread("hosts_apply", move || hosts_blocker::apply_profile(&profile)));
+        }
+        if ui.add_enabled(!is_busy && can_write, egui::Button::new("♻️ Retirer le blocage"))
+            .on_disabled_hover_text(crate::ui::util::ELEVATION_REQUIRED_HINT)
+            .clicked()
+        {
+            app.hosts_promise = Some(Promise::spawn_thread("hosts_remove", hosts_blocker::remove_profile));
+        }
+        if is_busy {
+            ui.spinner();
+        }
+    });
+}
+
+fn format_mb(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / 1024.0 / 1024.0)
+}
+
+/// Duplicate finder: scanned folders, groups by reclaimable size and per-file selection
+fn format_gb(bytes: u64) -> String {
+    format!("{:.1} GB", bytes as f64 / 1024.0 / 1024.0 / 1024.0)
+}
+
+/// Drives with their media type and space, and the retrim / analysis through `defrag`
+fn draw_drives(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    if app.drives.is_none() {
+        app.refresh_drives();
+    }
+    app.poll_drive_optimization();
+
+    let theme = app.theme.clone();
+    let can_optimize = app.can_run(crate::disk::drive_info::requires_elevation());
+    let running = app.drive_optimize_promise.as_ref().map(|(letter, _)| letter.clone());
+
+    ui.horizontal(|ui| {
+        ui.label("💽 Lecteurs :");
+        if ui.small_button("🔄").on_hover_text("Actualiser").clicked() {
+            app.refresh_drives();
+        }
+    });
+    if !can_optimize {
+        ui.colored_label(
+            theme.warning,
+            "🔒 Le retrim et l'analyse passent par defrag.exe, qui exige les droits administrateur",
+        );
+    }
+
+    let drives = match &app.drives {
+        Some(Ok(drives)) => drives.clone(),
+        Some(Err(e)) => {
+            ui.colored_label(theme.error, format!("❌ {}", e));
+            return;
+        }
+        None => return,
+    };
+    if drives.is_empty() {
+        ui.label("Aucun lecteur local détecté");
+    }
+
+    let mut optimize = None;
+    for drive in &drives {
+        ui.horizontal(|ui| {
+            let system = if drive.is_system { " (système)" } else { "" };
+            ui.label(egui::RichText::new(format!("{}{}", drive.letter, system)).strong());
+            ui.label(drive.media_type.label());
+            ui.add(
+                ProgressBar::new(drive.used_fraction())
+                    .desired_width(200.0)
+                    .text(format!("{} libres sur {}", format_gb(drive.free_bytes), format_gb(drive.total_bytes))),
+            );
+
+            let (label, unavailable) = match drive.optimize_mode() {
+                Some(OptimizeMode::Retrim) => ("✂️ Exécuter TRIM", None),
+                Some(OptimizeMode::Analyze) => ("🔍 Analyser", None),
+                None if drive.media_type == MediaType::Removable => {
+                    ("⚡ Optimiser", Some("Non disponible pour un lecteur amovible"))
+                }
+                None => ("⚡ Optimiser", Some("Type de support inconnu : optimisation désactivée")),
+            };
+            let enabled = can_optimize && unavailable.is_none() && running.is_none();
+            let disabled_hint = unavailable.unwrap_or(if can_optimize {
+                "Opération en cours..."
+            } else {
+                crate::ui::util::ELEVATION_REQUIRED_HINT
+            });
+            if ui.add_enabled(enabled, egui::Button::new(label)).on_disabled_hover_text(disabled_hint).clicked() {
+                optimize = Some(drive.clone());
+            }
+            if running.as_deref() == Some(drive.letter.as_str()) {
+                ui.spinner();
+            }
+        });
+        for hint in drive.health_hints() {
+            ui.colored_label(theme.warning, format!("   ⚠️ {}", hint));
+        }
+    }
+    if let Some(drive) = optimize {
+        app.start_drive_optimization(&drive);
+    }
+
+    match &app.last_drive_report {
+        Some(Ok(report)) => {
+            let action = match report.mode {
+                OptimizeMode::Retrim => "Retrim",
+                OptimizeMode::Analyze => "Analyse",
+            };
+            egui::CollapsingHeader::new(format!("📄 {} de {}", action, report.letter))
+                .id_source("drive_report")
+                .default_open(true)
+                .show(ui, |ui| {
+                    if let Some(recommendation) = &report.recommendation {
+                        ui.colored_label(theme.accent, recommendation);
+                    }
+                    for line in &report.details {
+                        ui.monospace(line);
+                    }
+                });
+        }
+        Some(Err(e)) => {
+            ui.colored_label(theme.error, format!("❌ {}", e));
+        }
+        None => {}
+    }
+}
+
+fn draw_duplicates(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    app.poll_duplicates();
+
+    ui.label("🗂️ Fichiers en double :");
+    let mut removed_path = None;
+    for path in &app.duplicate_scan_paths {
+        ui.horizontal(|ui| {
+            ui.label(path.display().to_string());
+            if ui.small_button("✖").on_hover_text("Retirer ce dossier").clicked() {
+                removed_path = Some(path.clone());
+            }
+        });
+    }
+    if let Some(path) = removed_path {
+        app.duplicate_scan_paths.retain(|p| *p != path);
+    }
+
+    let scanning = app.duplicate_scan.is_some();
+    let deleting = app.duplicate_delete_promise.is_some();
+    ui.horizontal(|ui| {
+        if ui.button("➕ Ajouter un dossier").clicked() {
+            if let Some(folder) = rfd::FileDialog::new().set_title("Dossier à analyser").pick_folder() {
+                if !app.duplicate_scan_paths.contains(&folder) {
+                    app.duplicate_scan_paths.push(folder);
+                }
+            }
+        }
+        ui.label("Taille minimale :");
+        ui.add(egui::DragValue::new(&mut app.duplicate_min_size_mb).clamp_range(0..=10_240).suffix(" MB"));
+        if ui
+            .add_enabled(!scanning && !deleting && !app.duplicate_scan_paths.is_empty(), egui::Button::new("🔍 Rechercher les doublons"))
+            .clicked()
+        {
+            app.start_duplicate_scan();
+        }
+    });
+
+    if let Some(scan) = &app.duplicate_scan {
+        use std::sync::atomic::Ordering;
+        let progress = &scan.progress;
+        let to_hash = progress.files_to_hash.load(Ordering::Relaxed);
+        let text = if to_hash == 0 {
+            format!("Inventaire : {} fichiers", progress.files_found.load(Ordering::Relaxed))
+        } else {
+            format!("Comparaison : {}/{} fichiers", progress.files_hashed.load(Ordering::Relaxed), to_hash)
+        };
+        ui.add(ProgressBar::new(progress.fraction()).text(text));
+        ui.ctx().request_repaint_after(std::time::Duration::from_millis(200));
+        return;
+    }
+
+    let Some(groups) = app.duplicate_groups.clone() else {
+        return;
+    };
+    if groups.is_empty() {
+        ui.label("Aucun doublon trouvé");
+        return;
+    }
+
+    let reclaimable: u64 = groups.iter().map(|group| group.reclaimable()).sum();
+    let selected_size: u64 = groups
+        .iter()
+        .map(|group| group.size * group.files.iter().filter(|path| app.duplicate_selection.contains(*path)).count() as u64)
+        .sum();
+    ui.horizontal(|ui| {
+        ui.label(format!("{} groupes, {} récupérables", groups.len(), format_mb(reclaimable)));
+        if ui.button("Sélectionner les copies").on_hover_text("Coche tous les fichiers sauf le premier de chaque groupe").clicked() {
+            app.duplicate_selection = groups.iter().flat_map(|group| group.files.iter().skip(1).cloned()).collect();
+        }
+        if ui.button("Tout décocher").clicked() {
+            app.duplicate_selection.clear();
+        }
+        if ui
+            .add_enabled(!deleting && selected_size > 0, egui::Button::new(format!("🗑️ Supprimer la sélection ({})", format_mb(selected_size))))
+            .clicked()
+        {
+            app.delete_selected_duplicates();
+        }
+        if deleting {
+            ui.spinner();
+        }
+    });
+
+    egui::ScrollArea::vertical().id_source("duplicate_groups").max_height(300.0).show(ui, |ui| {
+        for group in &groups {
+            let header = format!(
+                "{} fichiers × {} — {} récupérables",
+                group.files.len(),
+                format_mb(group.size),
+                format_mb(group.reclaimable())
+            );
+            egui::CollapsingHeader::new(header).id_source(&group.hash).show(ui, |ui| {
+                let kept = group.files.iter().filter(|path| !app.duplicate_selection.contains(*path)).count();
+                for path in &group.files {
+                    let mut checked = app.duplicate_selection.contains(path);
+                    // Le dernier fichier non coché du groupe reste conservé
+                    let can_toggle = checked || kept > 1;
+                    let response = ui
+                        .add_enabled(can_toggle, egui::Checkbox::new(&mut checked, path.display().to_string()))
+                        .on_disabled_hover_text("Au moins un fichier par groupe est conservé");
+                    if response.changed() {
+                        if checked {
+                            app.duplicate_selection.insert(path.clone());
+                        } else {
+                            app.duplicate_selection.remove(path);
+                        }
+                    }
+                }
+                ui.weak(format!("BLAKE3 {}", &group.hash[..16]));
+            });
+        }
+    });
+}
+
+/// Space analyzer: drive choice, progress with cancellation, largest files or directories
+fn draw_space_analyzer(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    app.poll_space_analysis();
+    if app.space_drives.is_empty() {
+        app.space_drives = crate::disk::space_analyzer::drives();
+        app.space_root = app.space_drives.first().cloned();
+    }
+
+    ui.label("📊 Espace disque :");
+    let analyzing = app.space_analysis_job.is_some();
+    ui.horizontal(|ui| {
+        let selected = app.space_root.as_ref().map(|root| root.display().to_string()).unwrap_or_default();
+        egui::ComboBox::from_id_source("space_root").selected_text(selected).show_ui(ui, |ui| {
+            for drive in &app.space_drives {
+                ui.selectable_value(&mut app.space_root, Some(drive.clone()), drive.display().to_string());
+            }
+        });
+        if ui.button("📁 Autre dossier").clicked() {
+            if let Some(folder) = rfd::FileDialog::new().set_title("Dossier à analyser").pick_folder() {
+                app.space_root = Some(folder);
+            }
+        }
+        if ui
+            .add_enabled(!analyzing && app.space_root.is_some(), egui::Button::new("🔍 Analyser l'espace disque"))
+            .clicked()
+        {
+            app.start_space_analysis();
+        }
+    });
+
+    if let Some(job) = &app.space_analysis_job {
+        use std::sync::atomic::Ordering;
+        ui.horizontal(|ui| {
+            ui.spinner();
+            ui.label(format!(
+                "{} : {} dossiers, {:.1} GB",
+                job.root.display(),
+                job.progress.dirs_visited.load(Ordering::Relaxed),
+                job.progress.bytes_accounted.load(Ordering::Relaxed) as f64 / 1024.0 / 1024.0 / 1024.0
+            ));
+            if ui.button("⏹ Annuler").clicked() {
+                job.progress.cancel();
+            }
+        });
+        ui.ctx().request_repaint_after(std::time::Duration::from_millis(200));
+        return;
+    }
+
+    let Some(analysis) = &app.space_analysis else {
+        return;
+    };
+    let mut summary = format!("{} : {:.1} GB", analysis.root.path.display(), analysis.root.size as f64 / 1024.0 / 1024.0 / 1024.0);
+    if analysis.cancelled {
+        summary.push_str(" (analyse interrompue)");
+    }
+    ui.horizontal(|ui| {
+        ui.label(summary);
+        if analysis.skipped_dirs > 0 {
+            ui.colored_label(app.theme.warning, format!("⚠️ {} dossiers inaccessibles ignorés", analysis.skipped_dirs));
+        }
+    });
+
+    let (file_count, dir_count) = (analysis.largest_files.len(), analysis.largest_dirs.len());
+    let mut rows: Vec<SizedPath> = if app.space_show_dirs {
+        analysis.largest_dirs.clone()
+    } else {
+        analysis.largest_files.clone()
+    };
+    match app.space_sort {
+        SpaceSort::Size => rows.sort_by(|a, b| a.size.cmp(&b.size)),
+        SpaceSort::Path => rows.sort_by(|a, b| a.path.cmp(&b.path)),
+    }
+    if app.space_sort_descending {
+        rows.reverse();
+    }
+
+    ui.horizontal(|ui| {
+        ui.selectable_value(&mut app.space_show_dirs, false, format!("Fichiers ({})", file_count));
+        ui.selectable_value(&mut app.space_show_dirs, true, format!("Dossiers ({})", dir_count));
+    });
+
+    ui.push_id("space_analysis_table", |ui| {
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::initial(100.0))
+            .column(Column::remainder().at_least(200.0).clip(true))
+            .max_scroll_height(300.0)
+            .header(22.0, |mut header| {
+                header.col(|ui| space_sort_header(app, ui, "Taille", SpaceSort::Size));
+                header.col(|ui| space_sort_header(app, ui, "Chemin", SpaceSort::Path));
+            })
+            .body(|mut body| {
+                for item in &rows {
+                    body.row(22.0, |mut row| {
+                        row.col(|ui| {
+                            ui.label(format_mb(item.size));
+                        });
+                        row.col(|ui| {
+                            let response = ui.add(egui::Label::new(item.path.display().to_string()).sense(egui::Sense::click()));
+                            response.on_hover_text("Clic droit pour les actions").context_menu(|ui| {
+                                if ui.button("📂 Ouvrir dans l'explorateur").clicked() {
+                                    if let Err(e) = crate::utils::reveal_in_file_manager(&item.path) {
+                                        tracing::warn!("⚠️ Ouverture de {} impossible: {}", item.path.display(), e);
+                                    }
+                                    ui.close_menu();
+                                }
+                                if ui.button("📋 Copier le chemin").clicked() {
+                                    ui.output_mut(|output| output.copied_text = item.path.display().to_string());
+                                    ui.close_menu();
+                                }
+                            });
+                        });
+                    });
+                }
+            });
+    });
+}
+
+fn space_sort_header(app: &mut CleanRamApp, ui: &mut egui::Ui, label: &str, column: SpaceSort) {
+    let is_active = app.space_sort == column;
+    let text = if is_active {
+        format!("{} {}", label, if app.space_sort_descending { "⬇" } else { "⬆" })
+    } else {
+        label.to_string()
+    };
+
+    if ui.selectable_label(is_active, egui::RichText::new(text).strong()).clicked() {
+        if is_active {
+            app.space_sort_descending = !app.space_sort_descending;
+        } else {
+            app.space_sort = column;
+            app.space_sort_descending = column == SpaceSort::Size;
+        }
+    }
+}
+
+/// "Mes jeux": installed Steam, Epic and GOG games by size, the ones not played for a long
+/// time highlighted
+fn draw_game_libraries(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    app.poll_game_library_scan();
+    ui.label("🎮 Mes jeux :");
+    let scanning = app.game_library_promise.is_some();
+    ui.horizontal(|ui| {
+        let text = if app.game_library.is_some() { "🔄 Actualiser" } else { "🔍 Analyser mes jeux" };
+        if ui.add_enabled(!scanning, egui::Button::new(text))
+            .on_hover_text("Bibliothèques Steam, Epic Games et GOG")
+            .clicked()
+        {
+            app.start_game_library_scan();
+        }
+        if scanning {
+            ui.spinner();
+            ui.label("Lecture des bibliothèques...");
+        }
+    });
+
+    let Some(scan) = &app.game_library else {
+        return;
+    };
+    let theme = app.theme.clone();
+    if !scan.skipped_manifests.is_empty() {
+        let names: Vec<String> = scan.skipped_manifests.iter().map(|path| path.display().to_string()).collect();
+        ui.colored_label(theme.warning, format!("⚠️ {} manifeste(s) illisible(s) ignoré(s)", names.len()))
+            .on_hover_text(names.join("\n"));
+    }
+    if scan.games.is_empty() {
+        ui.label("Aucun jeu trouvé");
+        return;
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        for (drive, total) in scan.totals_by_drive() {
+            ui.label(format!("💽 {} : {}", drive, format_size(total)));
+        }
+    });
+    let candidates: Vec<&InstalledGame> = scan.games.iter().filter(|game| game.is_reclaim_candidate()).collect();
+    if !candidates.is_empty() {
+        let reclaimable: u64 = candidates.iter().map(|game| game.size_on_disk).sum();
+        ui.colored_label(
+            theme.warning,
+            format!(
+                "💤 {} jeu(x) non lancé(s) depuis {} jours : {} récupérables en les désinstallant",
+                candidates.len(),
+                RECLAIM_AFTER_DAYS,
+                format_size(reclaimable)
+            ),
+        );
+    }
+
+    let mut games: Vec<InstalledGame> = scan.games.clone();
+    let sort = app.game_sort;
+    games.sort_by(|a, b| sort.compare(a, b));
+    if app.game_sort_descending {
+        games.reverse();
+    }
+
+    ui.push_id("game_libraries_table", |ui| {
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::initial(200.0).clip(true))
+            .column(Column::initial(90.0))
+            .column(Column::initial(90.0))
+            .column(Column::initial(110.0))
+            .column(Column::remainder().at_least(120.0))
+            .max_scroll_height(300.0)
+            .header(22.0, |mut header| {
+                header.col(|ui| game_sort_header(app, ui, "Jeu", GameSort::Name));
+                header.col(|ui| game_sort_header(app, ui, "Plateforme", GameSort::Platform));
+                header.col(|ui| game_sort_header(app, ui, "Taille", GameSort::Size));
+                header.col(|ui| game_sort_header(app, ui, "Dernier lancement", GameSort::LastPlayed));
+                header.col(|_| {});
+            })
+            .body(|mut body| {
+                for game in &games {
+                    let stale = game.is_reclaim_candidate();
+                    body.row(22.0, |mut row| {
+                        row.col(|ui| {
+                            let label = if stale {
+                                egui::RichText::new(&game.name).color(theme.warning)
+                            } else {
+                                egui::RichText::new(&game.name)
+                            };
+                            ui.label(label).on_hover_text(game.install_dir.display().to_string());
+                        });
+                        row.col(|ui| {
+                            ui.label(game.platform.label());
+                        });
+                        row.col(|ui| {
+                            ui.label(format_size(game.size_on_disk));
+                        });
+                        row.col(|ui| match game.last_played {
+                            Some(played) if stale => {
+                                ui.colored_label(theme.warning, format!("💤 {}", format_age(played)));
+                            }
+                            Some(played) => {
+                                ui.label(format_age(played));
+                            }
+                            None => {
+                                ui.weak("—").on_hover_text("Non enregistré par ce launcher");
+                            }
+                        });
+                        row.col(|ui| {
+                            if ui.small_button("📂 Ouvrir le dossier").clicked() {
+                                if let Err(e) = crate::utils::reveal_in_file_manager(&game.install_dir) {
+                                    tracing::warn!("⚠️ Ouverture de {} impossible: {}", game.install_dir.display(), e);
+                                }
+                            }
+                        });
+                    });
+                }
+            });
+    });
+}
+
+fn game_sort_header(app: &mut CleanRamApp, ui: &mut egui::Ui, label: &str, column: GameSort) {
+    let is_active = app.game_sort == column;
+    let text = if is_active {
+        format!("{} {}", label, if app.game_sort_descending { "⬇" } else { "⬆" })
+    } else {
+        label.to_string()
+    };
+
+    if ui.selectable_label(is_active, egui::RichText::new(text).strong()).clicked() {
+        if is_active {
+            app.game_sort_descending = !app.game_sort_descending;
+        } else {
+            app.game_sort = column;
+            app.game_sort_descending = matches!(column, GameSort::Size | GameSort::LastPlayed);
+        }
+    }
+}
+
+/// Autostart entries with a search box and a toggle per row
+fn draw_startup_manager(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    if app.startup_entries.is_none() {
+        app.refresh_startup_entries();
+    }
+    app.poll_startup_entries();
+
+    let theme = app.theme.clone();
+    ui.label("🚀 Démarrage :");
+    ui.horizontal(|ui| {
+        ui.label("🔎");
+        ui.add(egui::TextEdit::singleline(&mut app.startup_search).hint_text("Nom, éditeur ou commande").desired_width(220.0));
+        if ui.add_enabled(app.startup_promise.is_none(), egui::Button::new("🔄 Actualiser")).clicked() {
+            app.refresh_startup_entries();
+        }
+        if app.startup_promise.is_some() {
+            ui.spinner();
+        }
+    });
+    if let Some(e) = &app.startup_entry_error {
+        ui.colored_label(theme.error, format!("❌ {}", e));
+    }
+
+    let entries = match &app.startup_entries {
+        Some(Ok(entries)) => entries.clone(),
+        Some(Err(e)) => {
+            ui.colored_label(theme.error, format!("❌ {}", e));
+            return;
+        }
+        None => return,
+    };
+    let search = app.startup_search.to_lowercase();
+    let visible: Vec<&crate::optimization::startup_manager::StartupEntry> = entries
+        .iter()
+        .filter(|entry| {
+            search.is_empty()
+                || entry.name.to_lowercase().contains(&search)
+                || entry.command.to_lowercase().contains(&search)
+                || entry.publisher.as_ref().is_some_and(|publisher| publisher.to_lowercase().contains(&search))
+        })
+        .collect();
+    if visible.is_empty() {
+        ui.label("Aucun programme au démarrage");
+        return;
+    }
+
+    let mut toggled = None;
+    ui.push_id("startup_entries_table", |ui| {
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::exact(24.0))
+            .column(Column::initial(180.0).clip(true))
+            .column(Column::initial(140.0).clip(true))
+            .column(Column::initial(170.0))
+            .column(Column::remainder().at_least(150.0).clip(true))
+            .max_scroll_height(300.0)
+            .header(22.0, |mut header| {
+                header.col(|_| {});
+                for title in ["Programme", "Éditeur", "Emplacement", "Commande"] {
+                    header.col(|ui| {
+                        ui.strong(title);
+                    });
+                }
+            })
+            .body(|mut body| {
+                for entry in &visible {
+                    body.row(22.0, |mut row| {
+                        row.col(|ui| {
+                            let mut enabled = entry.enabled;
+                            let editable = !entry.location.is_read_only() && app.can_run(entry.location.requires_elevation());
+                            let response = ui.add_enabled(editable, egui::Checkbox::new(&mut enabled, ""));
+                            let response = if entry.location.is_read_only() {
+                                response.on_disabled_hover_text("Tâche planifiée : lecture seule")
+                            } else {
+                                response.on_disabled_hover_text("Droits administrateur requis")
+                            };
+                            if response.changed() {
+                                toggled = Some(((*entry).clone(), enabled));
+                            }
+                        });
+                        row.col(|ui| {
+                            if entry.enabled {
+                                ui.label(&entry.name);
+                            } else {
+                                ui.weak(&entry.name);
+                            }
+                        });
+                        row.col(|ui| {
+                            ui.label(entry.publisher.as_deref().unwrap_or("—"));
+                        });
+                        row.col(|ui| {
+                            ui.label(entry.location.label());
+                        });
+                        row.col(|ui| {
+                            ui.label(&entry.command).on_hover_text(&entry.command);
+                        });
+                    });
+                }
+            });
+    });
+
+    if let Some((entry, enabled)) = toggled {
+        app.set_startup_entry_enabled(&entry, enabled);
     }
-} 
\ No newline at end of file
+}
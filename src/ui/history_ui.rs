@@ -0,0 +1,91 @@
+use eframe::egui;
+
+use crate::history::{self, HistoryKind};
+use crate::theme::Theme;
+use crate::ui::app::CleanRamApp;
+
+const CHART_DAYS: i64 = 14;
+const RECENT_ROWS: usize = 10;
+
+fn format_gb(bytes: u64) -> String {
+    let gb = bytes as f64 / 1024.0 / 1024.0 / 1024.0;
+    if gb >= 10.0 {
+        format!("{:.0} Go", gb)
+    } else if gb >= 1.0 {
+        format!("{:.1} Go", gb)
+    } else {
+        format!("{:.0} Mo", bytes as f64 / 1024.0 / 1024.0)
+    }
+}
+
+/// Bar chart of bytes freed per day
+fn draw_daily_chart(ui: &mut egui::Ui, totals: &[(chrono::NaiveDate, u64)], theme: &Theme) {
+    let max = totals.iter().map(|(_, total)| *total).max().unwrap_or(0).max(1);
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width().min(560.0), 120.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+
+    let label_height = 14.0;
+    let slot_width = rect.width() / totals.len().max(1) as f32;
+    for (index, (day, total)) in totals.iter().enumerate() {
+        let height = (rect.height() - label_height) * (*total as f32 / max as f32);
+        let left = rect.left() + index as f32 * slot_width + 2.0;
+        let bottom = rect.bottom() - label_height;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(left, bottom - height),
+            egui::pos2(left + slot_width - 4.0, bottom),
+        );
+        painter.rect_filled(bar, 2.0, theme.accent);
+        painter.text(
+            egui::pos2(left + (slot_width - 4.0) / 2.0, rect.bottom()),
+            egui::Align2::CENTER_BOTTOM,
+            day.format("%d").to_string(),
+            egui::FontId::proportional(10.0),
+            ui.visuals().weak_text_color(),
+        );
+    }
+}
+
+pub fn draw_history_section(app: &mut CleanRamApp, ui: &mut egui::Ui, theme: &Theme) {
+    ui.group(|ui| {
+        ui.label("📈 Historique des nettoyages");
+        ui.separator();
+
+        ui.label(
+            egui::RichText::new(format!(
+                "Total libéré cette semaine : {}",
+                format_gb(history::total_freed_in(&app.history, 7))
+            ))
+            .strong()
+            .color(theme.success),
+        );
+
+        ui.add_space(5.0);
+        ui.label(format!("Mémoire libérée par jour ({} derniers jours) :", CHART_DAYS));
+        draw_daily_chart(ui, &history::daily_totals(&app.history, CHART_DAYS), theme);
+
+        if app.history.is_empty() {
+            ui.label("Aucun nettoyage enregistré pour le moment.");
+            return;
+        }
+
+        ui.add_space(5.0);
+        egui::CollapsingHeader::new("Derniers nettoyages").show(ui, |ui| {
+            egui::Grid::new("history_recent").striped(true).num_columns(4).show(ui, |ui| {
+                for entry in app.history.iter().rev().take(RECENT_ROWS) {
+                    ui.label(entry.timestamp.format("%d/%m %H:%M").to_string());
+                    ui.label(match entry.kind {
+                        HistoryKind::Ram => "🧠 RAM",
+                        HistoryKind::Disk => "💾 Disque",
+                    });
+                    ui.label(format_gb(entry.bytes_freed));
+                    if entry.errors > 0 {
+                        ui.colored_label(theme.warning, format!("{} erreur(s)", entry.errors));
+                    } else {
+                        ui.label(format!("{} éléments", entry.items));
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+    });
+}
@@ -1,26 +1,54 @@
-use eframe::egui::IconData;
+use std::sync::OnceLock;
+
+use eframe::egui::{self, IconData};
 use image::{load_from_memory, ImageBuffer, Rgba};
 
+/// Logo embedded in the executable
+pub const LOGO_PNG: &[u8] = include_bytes!("../../../assets/img/logo.png");
+
+/// Icon of the window, decoded from `LOGO_PNG` once
+pub fn app_icon() -> &'static IconData {
+    static ICON: OnceLock<IconData> = OnceLock::new();
+    ICON.get_or_init(|| create_app_icon(LOGO_PNG))
+}
+
+/// Texture of the header logo, built from the window icon; `None` if its pixels are inconsistent
+pub fn load_logo_texture(ctx: &egui::Context) -> Option<egui::TextureHandle> {
+    let icon = app_icon();
+    let size = [icon.width as usize, icon.height as usize];
+    if icon.rgba.len() != size[0] * size[1] * 4 {
+        tracing::warn!("⚠️ Logo incohérent ({}x{}, {} octets), non affiché", size[0], size[1], icon.rgba.len());
+        return None;
+    }
+    let image = egui::ColorImage::from_rgba_unmultiplied(size, &icon.rgba);
+    Some(ctx.load_texture("app_logo", image, egui::TextureOptions::LINEAR))
+}
+
 // Créer une icône d'application à partir d'un fichier image
 pub fn create_app_icon(logo_bytes: &[u8]) -> IconData {
     // Utiliser le logo intégré
-    if let Ok(image) = load_from_memory(logo_bytes) {
-        // Redimensionner l'image si elle est trop grande
-        let max_dimension = 128; // Taille maximale pour l'icône
-        let width = std::cmp::min(image.width(), max_dimension);
-        let height = std::cmp::min(image.height(), max_dimension);
-        
-        // Utiliser l'image redimensionnée
-        let image = image.resize_exact(width, height, image::imageops::FilterType::Lanczos3);
-        let rgba = image.to_rgba8().into_raw();
-        
-        return IconData {
-            rgba,
-            width,
-            height,
-        };
+    match load_from_memory(logo_bytes) {
+        // Une image vide ferait échouer le redimensionnement
+        Ok(image) if image.width() > 0 && image.height() > 0 => {
+            // Redimensionner l'image si elle est trop grande
+            let max_dimension = 128; // Taille maximale pour l'icône
+            let width = std::cmp::min(image.width(), max_dimension);
+            let height = std::cmp::min(image.height(), max_dimension);
+
+            // Utiliser l'image redimensionnée
+            let image = image.resize_exact(width, height, image::imageops::FilterType::Lanczos3);
+            let rgba = image.to_rgba8().into_raw();
+
+            return IconData {
+                rgba,
+                width,
+                height,
+            };
+        }
+        Ok(_) => tracing::warn!("⚠️ Logo vide, icône par défaut utilisée"),
+        Err(e) => tracing::warn!("⚠️ Logo illisible, icône par défaut utilisée: {}", e),
     }
-    
+
     // Si le chargement échoue, créer une icône par défaut
     // Créer une image de 32x32 pixels
     let width = 32;
@@ -63,4 +91,41 @@ pub fn create_app_icon(logo_bytes: &[u8]) -> IconData {
         width,
         height,
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_consistent(icon: &IconData) {
+        assert_eq!(icon.rgba.len(), icon.width as usize * icon.height as usize * 4);
+    }
+
+    #[test]
+    fn corrupt_or_empty_bytes_fall_back_to_the_generated_icon() {
+        for bytes in [&b""[..], b"not a png", &LOGO_PNG[..LOGO_PNG.len().min(64)]] {
+            let icon = create_app_icon(bytes);
+            assert_eq!((icon.width, icon.height), (32, 32));
+            assert_consistent(&icon);
+            assert!(icon.rgba.chunks(4).all(|pixel| pixel[3] == 255));
+        }
+    }
+
+    #[test]
+    fn embedded_logo_is_decoded_within_128_pixels() {
+        let icon = create_app_icon(LOGO_PNG);
+        assert!(icon.width > 0 && icon.width <= 128 && icon.height > 0 && icon.height <= 128);
+        assert_consistent(&icon);
+    }
+
+    #[test]
+    fn small_image_keeps_its_size() {
+        let image = image::DynamicImage::ImageRgba8(ImageBuffer::from_pixel(4, 3, Rgba([255, 0, 0, 255])));
+        let mut png = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut png, image::ImageOutputFormat::Png).unwrap();
+
+        let icon = create_app_icon(png.get_ref());
+        assert_eq!((icon.width, icon.height), (4, 3));
+        // Le filtre Lanczos peut arrondir d'une unité
+        assert!(icon.rgba.chunks(4).all(|pixel| pixel[0] >= 250 && pixel[1] <= 5 && pixel[2] <= 5));
+    }
+}
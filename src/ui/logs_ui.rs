@@ -0,0 +1,147 @@
+use eframe::egui;
+
+use std::time::{Duration, Instant};
+
+use crate::logs::{LogLevel, LogTail, MAX_LINES};
+use crate::theme::Theme;
+use crate::ui::app::CleanRamApp;
+
+const LEVEL_FILTERS: &[(LogLevel, &str)] = &[
+    (LogLevel::Info, "ℹ️ Info"),
+    (LogLevel::Warn, "⚠️ Avertissements"),
+    (LogLevel::Error, "❌ Erreurs"),
+];
+
+/// The folder size is measured again after this delay, the files grow while the app runs
+const SIZE_REFRESH: Duration = Duration::from_secs(10);
+
+fn level_label(level: LogLevel) -> &'static str {
+    match level {
+        LogLevel::Error => "Erreurs",
+        LogLevel::Warn => "Avertissements",
+        LogLevel::Info => "Info",
+        LogLevel::Debug => "Débogage",
+        LogLevel::Trace => "Trace",
+    }
+}
+
+fn level_color(level: Option<LogLevel>, theme: &Theme, ui: &egui::Ui) -> egui::Color32 {
+    match level {
+        Some(LogLevel::Error) => theme.error,
+        Some(LogLevel::Warn) => theme.warning,
+        _ => ui.visuals().text_color(),
+    }
+}
+
+/// Level written to the files, retention and size of the log folder
+fn draw_logging_settings(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+        ui.label("Niveau de journalisation :");
+        let mut level = app.log_level;
+        egui::ComboBox::from_id_source("log_level")
+            .selected_text(level_label(level))
+            .show_ui(ui, |ui| {
+                for candidate in LogLevel::ALL {
+                    ui.selectable_value(&mut level, candidate, level_label(candidate));
+                }
+            })
+            .response
+            .on_hover_text("Appliqué immédiatement ; Débogage et Trace produisent des fichiers volumineux");
+        if level != app.log_level {
+            app.set_log_level(level);
+            app.save_settings();
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label("Conserver les journaux :");
+        let response = ui
+            .add(egui::DragValue::new(&mut app.log_retention_days).clamp_range(0..=365).suffix(" jour(s)"))
+            .on_hover_text("0 = ne jamais supprimer. Les fichiers plus anciens sont supprimés au démarrage puis chaque jour");
+        if response.changed() {
+            app.save_settings();
+        }
+    });
+
+    let stale = app.log_dir_size.map_or(true, |(_, measured)| measured.elapsed() >= SIZE_REFRESH);
+    if stale {
+        app.log_dir_size = Some((crate::logs::directory_size(), Instant::now()));
+    }
+    let size = app.log_dir_size.map(|(size, _)| size).unwrap_or(0);
+    ui.horizontal(|ui| {
+        ui.label(format!(
+            "Dossier {} : {:.1} Mo",
+            LogTail::directory().display(),
+            size as f64 / 1024.0 / 1024.0
+        ));
+        if ui
+            .button("🗑️ Vider les journaux")
+            .on_hover_text("Supprime les anciens fichiers et vide celui du jour")
+            .clicked()
+        {
+            app.clear_logs();
+        }
+    });
+}
+
+/// Last lines of the log files, followed live while the section is open
+pub fn draw_logs_section(app: &mut CleanRamApp, ui: &mut egui::Ui, theme: &Theme) {
+    ui.group(|ui| {
+        draw_logging_settings(app, ui);
+        ui.separator();
+
+        let header = egui::CollapsingHeader::new("📜 Journal").show(ui, |ui| {
+            let tail = app.log_tail.get_or_insert_with(LogTail::start);
+
+            ui.horizontal(|ui| {
+                ui.label("Niveau minimal :");
+                for (level, label) in LEVEL_FILTERS {
+                    ui.selectable_value(&mut app.log_min_level, *level, *label);
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("🔍");
+                ui.add(egui::TextEdit::singleline(&mut app.log_search).hint_text("Rechercher").desired_width(220.0));
+                ui.checkbox(&mut app.log_auto_scroll, "Défilement automatique");
+            });
+
+            let entries = tail.entries(app.log_min_level, &app.log_search);
+            ui.horizontal(|ui| {
+                if ui.button("📋 Copier").on_hover_text("Copie les lignes affichées").clicked() {
+                    let text = entries.iter().map(|entry| entry.text.as_str()).collect::<Vec<_>>().join("\n");
+                    ui.output_mut(|output| output.copied_text = text);
+                }
+                if ui.button("📂 Ouvrir le dossier des logs").clicked() {
+                    if let Err(e) = crate::utils::reveal_in_file_manager(&LogTail::directory()) {
+                        tracing::error!("❌ Ouverture du dossier des logs impossible: {}", e);
+                        app.notifications.error_chain("Journal", "Ouverture du dossier des logs impossible", &e);
+                    }
+                }
+                ui.weak(format!("{} entrée(s), {} au plus", entries.len(), MAX_LINES));
+            });
+            ui.separator();
+
+            if entries.is_empty() {
+                ui.weak("Aucune ligne à afficher");
+            } else {
+                egui::ScrollArea::both()
+                    .max_height(320.0)
+                    .auto_shrink([false, true])
+                    .stick_to_bottom(app.log_auto_scroll)
+                    .show(ui, |ui| {
+                        for entry in &entries {
+                            let text = egui::RichText::new(&entry.text).monospace().small();
+                            ui.add(egui::Label::new(text.color(level_color(entry.level, theme, ui))).wrap(false));
+                        }
+                    });
+            }
+
+            // Le fichier est relu en arrière-plan : redessiner pour afficher les ajouts
+            ui.ctx().request_repaint_after(std::time::Duration::from_secs(1));
+        });
+
+        // Section repliée : plus besoin de suivre le fichier
+        if header.body_returned.is_none() {
+            app.log_tail = None;
+        }
+    });
+}
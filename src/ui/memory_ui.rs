@@ -1,14 +1,93 @@
-use crate::memory::{clean_memory, get_detailed_system_memory_info, CleaningResults};
+use crate::memory::{
+    get_detailed_system_memory_info,
+    wsl::{WslAction, VM_HOST_HINT},
+    CleaningDiff, ProcessMemoryInfo,
+};
+use crate::safety::{self, ProtectionLevel};
 use crate::theme::Theme;
-use crate::ui::app::CleanRamApp;
+use crate::ui::app::{CleanRamApp, PendingAction, ProtectedAction};
+use crate::ui::util::{terminate_menu, ELEVATION_REQUIRED_HINT};
 use eframe::egui::{self, Layout, RichText, ProgressBar};
-use poll_promise::Promise;
+use std::time::Duration;
+
+/// Nombre de processus affichés dans le tableau mémoire
+const TOP_PROCESS_COUNT: usize = 25;
+/// Intervalle de rafraîchissement du tableau mémoire
+const PROCESS_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Column used to sort the per-process memory table
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ProcessMemorySort {
+    Name,
+    Pid,
+    WorkingSet,
+    PrivateBytes,
+}
+
+/// CPU/GPU load and temperature badges, "n/a" for the sensors that cannot be read
+fn draw_hardware_strip(app: &mut CleanRamApp, ui: &mut egui::Ui, theme: &Theme) {
+    use crate::hardware::{HardwareMonitor, TEMP_CRITICAL, TEMP_WARN};
+
+    let monitor = app.hardware_monitor.get_or_insert_with(HardwareMonitor::start);
+    ui.ctx().request_repaint_after(Duration::from_secs(2));
+    let Some(snapshot) = monitor.snapshot() else {
+        ui.horizontal(|ui| {
+            ui.spinner();
+            ui.weak("Lecture des capteurs…");
+        });
+        return;
+    };
+
+    let temp_badge = |ui: &mut egui::Ui, temp: Option<f32>, tooltip: &str| match temp {
+        Some(temp) => {
+            let color = if temp >= TEMP_CRITICAL {
+                theme.error
+            } else if temp >= TEMP_WARN {
+                theme.warning
+            } else {
+                theme.success
+            };
+            ui.label(RichText::new(format!("🌡 {:.0} °C", temp)).color(color).strong()).on_hover_text(tooltip);
+        }
+        None => {
+            ui.weak("🌡 n/a").on_hover_text(tooltip);
+        }
+    };
+
+    ui.horizontal(|ui| {
+        let per_core = snapshot
+            .per_core
+            .iter()
+            .enumerate()
+            .map(|(index, usage)| format!("Cœur {} : {:.0} %", index, usage))
+            .collect::<Vec<_>>()
+            .join("\n");
+        ui.label(RichText::new(format!("🖥 CPU {:.0} %", snapshot.cpu_usage)).strong()).on_hover_text(per_core);
+        let cpu_sensor = match &snapshot.cpu_temp_source {
+            Some(source) => format!("Capteur : {}", source),
+            None => "Aucun capteur de température CPU lisible (droits administrateur requis sous Windows)".to_string(),
+        };
+        temp_badge(ui, snapshot.cpu_temp, &cpu_sensor);
+
+        ui.separator();
+
+        let gpu_name = snapshot.gpu_name.clone().unwrap_or_else(|| "Aucun GPU NVIDIA (nvidia-smi) ou AMD (amdgpu) détecté".to_string());
+        let gpu_usage = snapshot.gpu_usage.map(|usage| format!("{:.0} %", usage)).unwrap_or_else(|| "n/a".to_string());
+        ui.label(RichText::new(format!("🎮 GPU {}", gpu_usage)).strong()).on_hover_text(&gpu_name);
+        temp_badge(ui, snapshot.gpu_temp, &gpu_name);
+    });
+    ui.separator();
+}
 
 fn bytes_to_gb(bytes: u64) -> f64 {
     bytes as f64 / 1024.0 / 1024.0 / 1024.0
 }
 
-pub fn draw_memory_tab(app: &mut CleanRamApp, ui: &mut egui::Ui, _theme: &Theme) {
+fn bytes_to_mb(bytes: usize) -> f64 {
+    bytes as f64 / 1024.0 / 1024.0
+}
+
+pub fn draw_memory_tab(app: &mut CleanRamApp, ui: &mut egui::Ui, theme: &Theme) {
     let mem_info = get_detailed_system_memory_info();
 
     // Mettre à jour l'utilisation de la RAM uniquement si aucune opération de nettoyage n'est en cours
@@ -16,6 +95,9 @@ pub fn draw_memory_tab(app: &mut CleanRamApp, ui: &mut egui::Ui, _theme: &Theme)
         app.ram_usage = mem_info.used_physical_percent();
     }
 
+    draw_hardware_strip(app, ui, theme);
+    crate::ui::sessions_ui::draw_sessions_section(app, ui, theme);
+
     ui.vertical_centered(|ui| {
         ui.add_space(10.0);
         ui.heading("Optimisation de la Mémoire");
@@ -73,26 +155,12 @@ pub fn draw_memory_tab(app: &mut CleanRamApp, ui: &mut egui::Ui, _theme: &Theme)
         let button_size = egui::vec2(200.0, 40.0);
         let clean_button = egui::Button::new("Nettoyer la RAM").min_size(button_size);
 
-        let is_cleaning = app.cleaning_promise.is_some();
-        ui.add_enabled(!is_cleaning, clean_button).on_hover_text("Nettoie les processus et le working set de l'application.")
-            .clicked().then(|| {
-                let promise = Promise::spawn_thread("memory_clean", || {
-                    // Gérer le Result de clean_memory
-                    match clean_memory() {
-                        Ok(results) => results,
-                        Err(e) => {
-                            // En cas d'erreur, créer un CleaningResults avec le message d'erreur
-                            let mut error_results = CleaningResults::new();
-                            error_results.has_error = true;
-                            error_results.error_message = format!("Erreur lors du nettoyage de la mémoire : {}", e);
-                            error_results.is_completed = true;
-                            error_results.end_time = Some(chrono::Local::now());
-                            error_results
-                        }
-                    }
-                });
-                app.cleaning_promise = Some(promise);
-            });
+        let is_cleaning = app.cleaning_promise.is_some() || app.hotkey_cleaning.is_some();
+        let can_clean = app.can_run(crate::memory::requires_elevation());
+        ui.add_enabled(!is_cleaning && can_clean, clean_button)
+            .on_hover_text("Nettoie les processus et le working set de l'application.")
+            .on_disabled_hover_text(if can_clean { "Nettoyage en cours..." } else { ELEVATION_REQUIRED_HINT })
+            .clicked().then(|| app.start_memory_cleaning());
 
         if is_cleaning {
             ui.spinner();
@@ -103,7 +171,15 @@ pub fn draw_memory_tab(app: &mut CleanRamApp, ui: &mut egui::Ui, _theme: &Theme)
 
     if let Some(promise) = &app.cleaning_promise {
         if let Some(results) = promise.ready() {
-            app.last_cleaned_results = Some(results.clone());
+            let entry = crate::history::HistoryEntry::from_ram(results);
+            if results.has_error && results.total_freed() == 0 {
+                app.notifications.warning("Mémoire", results.error_message.clone());
+            } else {
+                let freed_mb = results.total_freed() as f64 / 1024.0 / 1024.0;
+                app.notifications.success("Mémoire", format!("{:.2} MB libérés", freed_mb));
+            }
+            app.previous_cleaned_results = app.last_cleaned_results.replace(results.clone());
+            app.record_history(entry);
             app.cleaning_promise = None;
             // No need to manually update ram_usage here, it will be updated on the next frame
         }
@@ -118,11 +194,17 @@ pub fn draw_memory_tab(app: &mut CleanRamApp, ui: &mut egui::Ui, _theme: &Theme)
         });
 
         if results.has_error {
-            ui.colored_label(egui::Color32::RED, &results.error_message);
+            ui.colored_label(theme.error, &results.error_message);
         } else {
             let freed_mb = results.total_freed() as f64 / 1024.0 / 1024.0;
             if freed_mb > 0.0 || !results.processes.is_empty() {
-                ui.label(format!("Mémoire libérée : {:.2} MB", freed_mb));
+                ui.label(format!("Working set libéré : {:.2} MB", freed_mb))
+                    .on_hover_text("Pages retirées des processus : elles restent en mémoire (liste d'attente) et une partie est relue aussitôt");
+                if let Some(gained) = results.available_gained() {
+                    let gained_mb = gained as f64 / 1024.0 / 1024.0;
+                    ui.label(format!("Mémoire disponible : {:+.2} MB", gained_mb))
+                        .on_hover_text("Variation de la mémoire disponible du système pendant le nettoyage : le gain réel");
+                }
                 ui.label(format!("Processus optimisés : {}", results.processes.len()));
             } else {
                 // Afficher le message spécifique de Linux si aucune mémoire n'a été "libérée"
@@ -134,18 +216,305 @@ pub fn draw_memory_tab(app: &mut CleanRamApp, ui: &mut egui::Ui, _theme: &Theme)
                 ui.add_space(10.0);
                 egui::CollapsingHeader::new("Détails de l'optimisation").show(ui, |ui| {
                     egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
-                        for process in &results.processes {
-                            ui.horizontal(|ui| {
-                                ui.label(format!("{}", process.name));
-                                ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
-                                    let mem_freed_mb = process.memory_freed as f64 / 1024.0 / 1024.0;
-                                    ui.label(format!("{:.2} MB", mem_freed_mb));
-                                });
-                            });
-                        }
+                        egui::Grid::new("cleaned_processes_grid").striped(true).num_columns(4).show(ui, |ui| {
+                            ui.strong("Processus");
+                            ui.strong("Working set avant → après");
+                            ui.strong("Libéré");
+                            ui.strong("Privé");
+                            ui.end_row();
+                            for process in &results.processes {
+                                if process.pid > 0 {
+                                    ui.label(format!("{} ({})", process.name, process.pid));
+                                } else {
+                                    ui.label(&process.name);
+                                }
+                                // Rapports antérieurs : seule la quantité libérée est connue
+                                if process.working_set_before > 0 {
+                                    ui.label(format!(
+                                        "{:.1} → {:.1} MB",
+                                        bytes_to_mb(process.working_set_before),
+                                        bytes_to_mb(process.working_set_after)
+                                    ));
+                                } else {
+                                    ui.weak("—");
+                                }
+                                ui.label(format!("{:.2} MB", bytes_to_mb(process.memory_freed)));
+                                if process.private_bytes > 0 {
+                                    ui.weak(format!("{:.1} MB", bytes_to_mb(process.private_bytes)));
+                                } else {
+                                    ui.weak("—");
+                                }
+                                ui.end_row();
+                            }
+                        });
                     });
                 });
             }
+
+            let previous = app.previous_cleaned_results.as_ref().filter(|previous| !previous.has_error);
+            if let Some(previous) = previous {
+                ui.add_space(10.0);
+                draw_cleaning_diff(ui, theme, &results.diff(previous));
+            }
+        }
+    }
+
+    ui.add_space(20.0);
+    ui.separator();
+    draw_process_memory_table(app, ui);
+    if app.process_memory_list.iter().any(|process| process.is_vm_host) {
+        ui.add_space(10.0);
+        draw_wsl_section(app, ui, theme);
+    }
+}
+
+/// `vmmem` is not trimmed: its memory is given back from inside the virtual machine
+fn draw_wsl_section(app: &mut CleanRamApp, ui: &mut egui::Ui, theme: &Theme) {
+    if app.wsl_status.is_none() {
+        app.start_wsl_task(None);
+    }
+    let busy = app.wsl_promise.is_some();
+    let mut shutdown_clicked = false;
+    let mut reclaim_clicked = false;
+
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        ui.label(RichText::new("🐧 Machine virtuelle WSL2 / Hyper-V").strong());
+        ui.weak("vmmem n'est pas vidé par le nettoyage : la mémoire appartient aux distributions et se libère depuis WSL.");
+        let Some(status) = &app.wsl_status else {
+            ui.spinner();
+            return;
+        };
+        if !status.installed {
+            ui.weak("WSL absent : la machine virtuelle appartient à Hyper-V ou à Docker Desktop");
+            return;
+        }
+        if status.running_distros.is_empty() {
+            ui.label("Aucune distribution WSL en cours");
+        } else {
+            ui.label(format!("Distributions en cours : {}", status.running_distros.join(", ")));
+        }
+        ui.horizontal(|ui| {
+            shutdown_clicked = ui
+                .add_enabled(!busy, egui::Button::new("🐧 Compacter la mémoire WSL"))
+                .on_hover_text("Arrête toutes les distributions (wsl --shutdown) : vmmem rend toute sa mémoire")
+                .clicked();
+            if status.auto_memory_reclaim_enabled() {
+                ui.colored_label(theme.success, format!("✅ autoMemoryReclaim : {}", status.auto_memory_reclaim.as_deref().unwrap_or_default()));
+            } else if status.supports_auto_memory_reclaim() {
+                reclaim_clicked = ui
+                    .add_enabled(!busy, egui::Button::new("♻ Activer autoMemoryReclaim"))
+                    .on_hover_text("WSL rend peu à peu son cache à Windows, sans arrêter les distributions (.wslconfig, au prochain démarrage de WSL)")
+                    .clicked();
+            } else {
+                ui.weak("autoMemoryReclaim : WSL 1.3.10 ou plus récent requis (wsl --update)");
+            }
+            if busy {
+                ui.spinner();
+            }
+        });
+    });
+
+    if shutdown_clicked {
+        app.request_action(PendingAction::ShutdownWsl);
+    }
+    if reclaim_clicked {
+        app.start_wsl_task(Some(WslAction::EnableAutoMemoryReclaim));
+    }
+}
+
+/// Signed size in MB with an arrow: green when more was freed than the previous run
+fn delta_label(ui: &mut egui::Ui, theme: &Theme, delta_bytes: i64) {
+    let delta_mb = delta_bytes as f64 / 1024.0 / 1024.0;
+    if delta_bytes > 0 {
+        ui.colored_label(theme.success, format!("⬆ +{:.2} MB", delta_mb));
+    } else if delta_bytes < 0 {
+        ui.colored_label(theme.warning, format!("⬇ {:.2} MB", delta_mb));
+    } else {
+        ui.weak("= 0 MB");
+    }
+}
+
+/// Comparison of the last memory cleaning with the one before
+fn draw_cleaning_diff(ui: &mut egui::Ui, theme: &Theme, diff: &CleaningDiff) {
+    egui::CollapsingHeader::new("Comparaison avec le nettoyage précédent")
+        .default_open(true)
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Mémoire libérée :");
+                delta_label(ui, theme, diff.freed_delta);
+            });
+            if !diff.appeared.is_empty() {
+                ui.colored_label(theme.success, format!("➕ Nouveaux : {}", diff.appeared.join(", ")));
+            }
+            if !diff.disappeared.is_empty() {
+                ui.colored_label(theme.warning, format!("➖ Absents : {}", diff.disappeared.join(", ")));
+            }
+
+            let changed: Vec<_> = diff.changed.iter().filter(|process| process.delta() != 0).collect();
+            if changed.is_empty() {
+                ui.weak("Aucun écart par processus");
+                return;
+            }
+            egui::ScrollArea::vertical().id_source("cleaning_diff").max_height(200.0).show(ui, |ui| {
+                for process in changed {
+                    ui.horizontal(|ui| {
+                        ui.label(&process.name);
+                        ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                            delta_label(ui, theme, process.delta());
+                            ui.weak(format!("{:.2} MB", bytes_to_mb(process.freed)));
+                        });
+                    });
+                }
+            });
+        });
+}
+
+fn sort_processes(processes: &mut [ProcessMemoryInfo], sort: ProcessMemorySort, descending: bool) {
+    processes.sort_by(|a, b| {
+        let ordering = match sort {
+            ProcessMemorySort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            ProcessMemorySort::Pid => a.pid.cmp(&b.pid),
+            ProcessMemorySort::WorkingSet => a.working_set.cmp(&b.working_set),
+            ProcessMemorySort::PrivateBytes => a.private_bytes.cmp(&b.private_bytes),
+        };
+        if descending { ordering.reverse() } else { ordering }
+    });
+}
+
+fn sort_header(app: &mut CleanRamApp, ui: &mut egui::Ui, label: &str, column: ProcessMemorySort) {
+    let is_active = app.process_memory_sort == column;
+    let text = if is_active {
+        format!("{} {}", label, if app.process_memory_sort_descending { "⬇" } else { "⬆" })
+    } else {
+        label.to_string()
+    };
+
+    if ui.selectable_label(is_active, RichText::new(text).strong()).clicked() {
+        if is_active {
+            app.process_memory_sort_descending = !app.process_memory_sort_descending;
+        } else {
+            app.process_memory_sort = column;
+            app.process_memory_sort_descending = true;
         }
     }
-}
\ No newline at end of file
+}
+
+fn draw_process_memory_table(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    let needs_refresh = app
+        .process_memory_last_refresh
+        .map_or(true, |last| last.elapsed() >= PROCESS_REFRESH_INTERVAL);
+    app.poll_process_memory();
+    if needs_refresh {
+        app.refresh_process_memory();
+    }
+    if app.process_memory_promise.is_some() || app.trim_promise.is_some() {
+        ui.ctx().request_repaint_after(Duration::from_millis(250));
+    } else {
+        ui.ctx().request_repaint_after(PROCESS_REFRESH_INTERVAL);
+    }
+
+    ui.heading("Processus les plus gourmands");
+    ui.add_space(5.0);
+
+    ui.horizontal(|ui| {
+        ui.label("🔍 Filtrer :");
+        ui.text_edit_singleline(&mut app.process_search_text);
+    });
+    ui.weak("Clic droit sur un processus pour le terminer ou le redémarrer");
+
+    if let Some(message) = &app.last_trim_message {
+        ui.label(message);
+    }
+
+    let search = app.process_search_text.to_lowercase();
+    let mut processes: Vec<ProcessMemoryInfo> = app
+        .process_memory_list
+        .iter()
+        .filter(|p| search.is_empty() || p.name.to_lowercase().contains(&search))
+        .cloned()
+        .collect();
+    sort_processes(&mut processes, app.process_memory_sort, app.process_memory_sort_descending);
+    processes.truncate(TOP_PROCESS_COUNT);
+
+    let mut pid_to_trim: Option<(u32, String)> = None;
+    let mut pid_to_terminate: Option<(u32, String, bool)> = None;
+
+    egui::ScrollArea::vertical()
+        .id_source("process_memory_table")
+        .max_height(300.0)
+        .show(ui, |ui| {
+            egui::Grid::new("process_memory_grid")
+                .num_columns(5)
+                .striped(true)
+                .show(ui, |ui| {
+                    sort_header(app, ui, "Processus", ProcessMemorySort::Name);
+                    sort_header(app, ui, "PID", ProcessMemorySort::Pid);
+                    sort_header(app, ui, "Working set", ProcessMemorySort::WorkingSet);
+                    sort_header(app, ui, "Private bytes", ProcessMemorySort::PrivateBytes);
+                    ui.label("");
+                    ui.end_row();
+
+                    for process in &processes {
+                        let critical = process.protection == ProtectionLevel::Critical;
+                        let name = if process.is_vm_host {
+                            let name = ui.horizontal(|ui| {
+                                app.process_icons.show(ui, process.exe_path.as_deref());
+                                let name = ui.weak(&process.name).on_hover_text(VM_HOST_HINT);
+                                ui.colored_label(app.theme.accent, RichText::new("VM").small()).on_hover_text(VM_HOST_HINT);
+                                name
+                            }).inner;
+                            ui.weak(process.pid.to_string());
+                            ui.weak(format!("{:.1} MB", bytes_to_mb(process.working_set)));
+                            ui.weak(format!("{:.1} MB", bytes_to_mb(process.private_bytes)));
+                            name
+                        } else if process.protection.is_protected() {
+                            let name = ui.horizontal(|ui| {
+                                app.process_icons.show(ui, process.exe_path.as_deref());
+                                ui.weak(format!("🔒 {}", process.name))
+                                    .on_hover_ui(|ui| {
+                                        ui.label(safety::protection_reason(process.pid, &process.name, None));
+                                    })
+                            }).inner;
+                            ui.weak(process.pid.to_string());
+                            ui.weak(format!("{:.1} MB", bytes_to_mb(process.working_set)));
+                            ui.weak(format!("{:.1} MB", bytes_to_mb(process.private_bytes)));
+                            name
+                        } else {
+                            let name = ui.horizontal(|ui| {
+                                app.process_icons.show(ui, process.exe_path.as_deref());
+                                ui.label(&process.name)
+                            }).inner;
+                            ui.label(process.pid.to_string());
+                            ui.label(format!("{:.1} MB", bytes_to_mb(process.working_set)));
+                            ui.label(format!("{:.1} MB", bytes_to_mb(process.private_bytes)));
+                            name
+                        };
+                        if let Some(restart) = terminate_menu(&name, critical) {
+                            pid_to_terminate = Some((process.pid, process.name.clone(), restart));
+                        }
+                        let disabled_hint = if process.is_vm_host {
+                            "Machine virtuelle : utilisez « Compacter la mémoire WSL » sous la liste"
+                        } else {
+                            "Processus système critique : sa mémoire n'est pas vidée"
+                        };
+                        if ui
+                            .add_enabled(!critical && !process.is_vm_host, egui::Button::new("✂ Trim").small())
+                            .on_hover_text("Vide le working set de ce processus uniquement")
+                            .on_disabled_hover_text(disabled_hint)
+                            .clicked()
+                        {
+                            pid_to_trim = Some((process.pid, process.name.clone()));
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+
+    if let Some((pid, name)) = pid_to_trim {
+        app.request_protected(ProtectedAction::Trim { pid, name });
+    }
+    if let Some((pid, name, restart)) = pid_to_terminate {
+        app.request_terminate(pid, &name, None, restart);
+    }
+}
@@ -1,8 +1,17 @@
 pub mod app;
+pub mod confirm;
+pub mod crashes_ui;
 pub mod disk_ui;
+pub mod history_ui;
+pub mod logs_ui;
 pub mod memory_ui;
 pub mod network_ui;
+pub mod notifications;
+pub mod onboarding;
+pub mod process_icons;
+pub mod profiles_ui;
 pub mod services_ui;
+pub mod sessions_ui;
 pub mod scheduler_ui;
 pub mod settings_ui;
 pub mod util;
@@ -1,9 +1,120 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
 use egui::Ui;
-use crate::network::format_speed;
-use crate::ui::app::CleanRamApp;
+use egui_extras::{Column, TableBuilder};
+use crate::network::process_filter::{filter_groups, ProcessFilter};
+use crate::network::{
+    find_conflicts, format_speed, NetworkProcessInfo, NetworkProcessSort, PolicyConflict, PortDirection, PortProtocol, PortRule,
+    ProcessGroup, QosPolicyInfo, QosPolicyState, QosUnavailableReason, ReverseDns, TcpState,
+};
+use crate::sessions::format_bytes;
+use crate::theme::Theme;
+use crate::safety::ProtectionLevel;
+use crate::ui::app::{CleanRamApp, ProtectedAction};
+use crate::ui::util::{terminate_menu, ELEVATION_REQUIRED_HINT};
+
+const PROTECTED_PROCESS_HINT: &str = "Processus système critique : Windows en dépend";
+
+/// Groupes affichés par page dans le tableau réseau (hors processus limités)
+const NETWORK_PAGE_SIZE: usize = 25;
+
+/// Choices of the auto-refresh interval, in seconds (0 = manual)
+const REFRESH_INTERVALS: [u64; 4] = [0, 2, 5, 10];
+
+fn refresh_interval_label(secs: u64) -> String {
+    if secs == 0 {
+        "Manuel".to_string()
+    } else {
+        format!("{} s", secs)
+    }
+}
+
+/// Data of a scan older than `STALE_SCAN_SECS` is no longer current
+fn is_stale(age: Option<u64>) -> bool {
+    age.is_some_and(|age| age > crate::network::STALE_SCAN_SECS)
+}
+
+/// A speed, greyed out when it comes from an outdated scan and marked "≈" when estimated
+fn speed_label(ui: &mut Ui, color: egui::Color32, bytes_per_sec: u64, stale: bool, estimated: bool) {
+    let text = if estimated {
+        format!("≈ {}", format_speed(bytes_per_sec))
+    } else {
+        format_speed(bytes_per_sec)
+    };
+    if stale {
+        ui.weak(text);
+    } else {
+        ui.colored_label(color, text);
+    }
+}
+
+/// Totals of the last scan, its age and the auto-refresh interval
+fn draw_network_header(app: &mut CleanRamApp, ui: &mut Ui, theme: &Theme) {
+    let Some((stats, age, source, estimated)) = app.network_limiter.as_ref().map(|limiter| {
+        (limiter.get_network_stats(), limiter.seconds_since_last_scan(), limiter.stats_source_label(), limiter.speeds_estimated())
+    }) else {
+        return;
+    };
+    let stale = is_stale(age);
+    let mut interval = app.network_refresh_secs;
+    ui.horizontal_wrapped(|ui| {
+        ui.label("📥");
+        speed_label(ui, theme.accent, stats.total_download_bytes, stale, estimated);
+        ui.label("📤");
+        speed_label(ui, theme.warning, stats.total_upload_bytes, stale, estimated);
+        ui.separator();
+        ui.label(format!("👁 {} processus surveillés", stats.total_processes));
+        if estimated {
+            ui.colored_label(theme.warning, "≈ débits estimés").on_hover_text(
+                "Aucun compteur : débits déduits du type de processus, du CPU et de la mémoire. \
+                 La surveillance haute précision (administrateur) mesure le trafic réel",
+            );
+        } else {
+            ui.weak(format!("débits : {}", source));
+        }
+        ui.separator();
+        ui.colored_label(theme.error, format!("🎯 {} limite(s) active(s)", stats.limited_processes_count));
+        ui.separator();
+        match age {
+            Some(age) => {
+                ui.weak(format!("actualisé il y a {} s", age));
+                if stale {
+                    ui.colored_label(theme.warning, "⏳ périmé")
+                        .on_hover_text("Les débits affichés datent du dernier scan : relancez-le pour les mettre à jour");
+                }
+                // L'âge affiché avance même sans actualisation automatique
+                ui.ctx().request_repaint_after(std::time::Duration::from_secs(1));
+            }
+            None => {
+                ui.weak("jamais actualisé");
+            }
+        }
+        ui.separator();
+        ui.label("🔄 Actualisation :");
+        egui::ComboBox::from_id_source("network_refresh_interval")
+            .selected_text(refresh_interval_label(interval))
+            .width(80.0)
+            .show_ui(ui, |ui| {
+                for secs in REFRESH_INTERVALS {
+                    ui.selectable_value(&mut interval, secs, refresh_interval_label(secs));
+                }
+            });
+    });
+    if interval != app.network_refresh_secs {
+        app.network_refresh_secs = interval;
+        app.save_settings();
+    }
+    ui.separator();
+}
 
 /// Draws the network management tab
 pub fn draw_network_tab(app: &mut CleanRamApp, ui: &mut Ui) {
+    let theme = app.theme.clone();
+    app.poll_limit_verifications();
+    if !app.limit_verification_promises.is_empty() {
+        ui.ctx().request_repaint_after(std::time::Duration::from_millis(500));
+    }
     ui.add_space(10.0);
     
     // En-tête avec informations importantes
@@ -11,24 +122,38 @@ pub fn draw_network_tab(app: &mut CleanRamApp, ui: &mut Ui) {
         ui.label("🌐");
         ui.heading("Gestionnaire Réseau par Processus");
     });
+    app.auto_refresh_network(ui.ctx());
+    draw_network_header(app, ui, &theme);
+    draw_qos_unavailable_banner(app, ui, &theme);
+    draw_active_adapter(app, ui, &theme);
     
     ui.separator();
+
+    draw_ping_panel(app, ui, &theme);
+    ui.separator();
     
     // IMPORTANT: Notice sur la limitation réelle
-    ui.colored_label(egui::Color32::from_rgb(33, 150, 243), "✅ LIMITATION RÉSEAU RÉELLE ACTIVE");
+    ui.colored_label(theme.accent, "✅ LIMITATION RÉSEAU RÉELLE ACTIVE");
     ui.label("• 🔥 Surveillance temps réel avec données système réelles");
     ui.label("• 🎯 Limitation QoS Windows natives (PowerShell silencieux)");
     ui.label("• 📊 Statistiques basées sur CPU/mémoire et type de processus");
     ui.label("• ⚡ Vitesses actuelles calculées en temps réel");
+    if cfg!(target_os = "linux") {
+        ui.colored_label(
+            theme.warning,
+            "🐧 Linux : connexions lues dans /proc (processus de l'utilisateur courant), débits seulement pour les conteneurs ; \
+             limitation de l'envoi via tc + cgroup net_cls (root requis)",
+        );
+    }
     ui.separator();
 
     // Collecter TOUTES les données d'abord pour éviter les conflits de borrow - CLONÉES
-    let (stats, all_processes, has_limiter) = if let Some(ref limiter) = app.network_limiter {
-        let stats = limiter.get_network_stats();
+    let (all_processes, groups, has_limiter) = if let Some(ref limiter) = app.network_limiter {
         let processes: Vec<_> = limiter.get_processes().iter().map(|p| (*p).clone()).collect();
-        (Some(stats), processes, true)
+        let groups = limiter.get_processes_grouped(app.network_sort, app.network_sort_descending);
+        (processes, groups, true)
     } else {
-        (None, Vec::new(), false)
+        (Vec::new(), Vec::new(), false)
     };
 
     // Section de contrôle
@@ -37,76 +162,280 @@ pub fn draw_network_tab(app: &mut CleanRamApp, ui: &mut Ui) {
     // Variables pour collecter les actions
     let mut scan_clicked = false;
     let mut clear_clicked = false;
+    let mut reset_all_clicked = false;
     let mut apply_limit_clicked = false;
+    let mut limit_others_clicked = false;
+    let mut undo_batch_clicked = false;
     let mut select_all_clicked = false;
     let mut deselect_all_clicked = false;
+    let mut apply_dscp_clicked = false;
+    let mut prioritize_clicked = false;
+    let mut clear_global_clicked = false;
+    let can_limit = app.can_run(crate::network::requires_elevation());
     
     ui.horizontal(|ui| {
         if ui.button("🔄 Scanner processus").clicked() {
             scan_clicked = true;
         }
         
-        if ui.button("🔓 Supprimer toutes limites").clicked() {
+        if ui.add_enabled(can_limit, egui::Button::new("🔓 Supprimer toutes limites"))
+            .on_disabled_hover_text(ELEVATION_REQUIRED_HINT)
+            .clicked()
+        {
             clear_clicked = true;
         }
+
+        if ui.add_enabled(can_limit, egui::Button::new("♻️ Tout réinitialiser"))
+            .on_hover_text("Supprime limites, limite globale et blocages pare-feu")
+            .on_disabled_hover_text(ELEVATION_REQUIRED_HINT)
+            .clicked()
+        {
+            reset_all_clicked = true;
+        }
     });
 
+    if let Some(error) = app.last_network_error.clone() {
+        ui.horizontal(|ui| {
+            ui.colored_label(theme.error, format!("❌ {}", error));
+            if ui.small_button("✖").clicked() {
+                app.last_network_error = None;
+            }
+        });
+    }
+
     ui.separator();
 
-    // Statistiques globales
-    if let Some(stats) = stats {
-        ui.label("📊 Statistiques réseau temps réel :");
-        
+    // Mode "tout sauf mon jeu"
+    let global_limit = app.network_limiter.as_ref().and_then(|l| l.global_limit().cloned());
+    ui.group(|ui| {
+        ui.label(egui::RichText::new("🎮 Prioriser ce jeu").strong().size(16.0));
+        ui.label("Limite tout le trafic réseau sauf le jeu choisi, qui passe en priorité haute.");
+
         ui.horizontal(|ui| {
-            ui.group(|ui| {
-                ui.label("📥 Débit entrant actuel:");
-                ui.colored_label(egui::Color32::from_rgb(33, 150, 243), format_speed(stats.total_download_bytes));
-            });
-            
-            ui.group(|ui| {
-                ui.label("📤 Débit sortant actuel:");
-                ui.colored_label(egui::Color32::from_rgb(255, 152, 0), format_speed(stats.total_upload_bytes));
-            });
-            
-            ui.group(|ui| {
-                ui.label("🎯 Processus limités:");
-                ui.colored_label(
-                    egui::Color32::from_rgb(244, 67, 54),
-                    format!("{}/{}", stats.limited_processes_count, stats.total_processes)
-                );
-            });
+            let selected_label = app
+                .priority_game_pid
+                .and_then(|pid| all_processes.iter().find(|p| p.pid == pid))
+                .map(|p| format!("{} (PID {})", p.name, p.pid))
+                .unwrap_or_else(|| "Choisir un processus...".to_string());
+
+            egui::ComboBox::from_id_source("priority_game_combo")
+                .selected_text(selected_label)
+                .width(250.0)
+                .show_ui(ui, |ui| {
+                    for process in &all_processes {
+                        ui.selectable_value(
+                            &mut app.priority_game_pid,
+                            Some(process.pid),
+                            format!("{} (PID {})", process.name, process.pid),
+                        );
+                    }
+                });
+
+            ui.label("Reste limité à");
+            ui.add(egui::TextEdit::singleline(&mut app.global_cap_input).desired_width(60.0));
+            ui.label("MB/s");
+
+            if ui.add_enabled(can_limit && app.priority_game_pid.is_some(), egui::Button::new("🎮 Prioriser"))
+                .on_disabled_hover_text(if can_limit { "Choisissez un processus" } else { ELEVATION_REQUIRED_HINT })
+                .clicked()
+            {
+                prioritize_clicked = true;
+            }
+
+            if ui.add_enabled(can_limit && global_limit.is_some(), egui::Button::new("🔓 Retirer la limite globale"))
+                .on_disabled_hover_text(if can_limit { "Aucune limite globale active" } else { ELEVATION_REQUIRED_HINT })
+                .clicked()
+            {
+                clear_global_clicked = true;
+            }
         });
-        
-        ui.separator();
-    }
+
+        if let Some(global) = &global_limit {
+            ui.colored_label(
+                theme.warning,
+                format!(
+                    "🌐 Limite globale active : {} KB/s, exemptés : {}",
+                    global.limit_kbps,
+                    global.exempt_exe_names.join(", ")
+                ),
+            );
+        }
+        if let Some(message) = &app.last_global_limit_message {
+            ui.label(message);
+        }
+    });
+
+    draw_port_rules_panel(app, ui, &theme, can_limit);
+    ui.separator();
 
     // Section de recherche
-    ui.label("🔍 Recherche de processus :");
-    ui.text_edit_singleline(&mut app.process_search_text);
+    ui.horizontal(|ui| {
+        ui.label("🔍 Recherche de processus :");
+        ui.add(egui::TextEdit::singleline(&mut app.process_search_text).hint_text("nom ou PID, plusieurs termes, re:regex"))
+            .on_hover_text("Termes séparés par des espaces : tous doivent correspondre au nom ou au PID.\nPréfixe re: pour une expression régulière.");
+        if !app.process_search_text.is_empty() && ui.small_button("✖").on_hover_text("Effacer la recherche").clicked() {
+            app.process_search_text.clear();
+        }
+        ui.checkbox(&mut app.show_idle_network_processes, "Afficher les processus inactifs")
+            .on_hover_text("Processus avec des sockets ouvertes mais sans trafic (jeu dans un salon, launcher en attente).\nLes processus limités ou bloqués restent toujours affichés.");
+    });
+    let filter = match ProcessFilter::parse(&app.process_search_text) {
+        Ok(filter) => filter,
+        Err(e) => {
+            ui.colored_label(theme.error, format!("❌ Expression régulière invalide : {}", e));
+            ProcessFilter::All
+        }
+    };
     ui.add_space(5.0);
 
-    // Filtrage par recherche - AVEC CLONES
-    let filtered_processes: Vec<_> = all_processes
+    // Un groupe reste entier dès qu'un de ses processus correspond ; les groupes limités
+    // restent toujours affichés, grisés s'ils ne correspondent pas
+    let filtered: Vec<(ProcessGroup, bool)> = filter_groups(groups, &filter);
+    let dimmed_groups: HashSet<u32> = filtered.iter().filter(|(_, matched)| !matched).map(|(group, _)| group.root_pid).collect();
+    // Le filtre des inactifs masque des lignes, pas des données : les totaux des groupes
+    // comptent toujours tous leurs processus
+    let hide_idle = !app.show_idle_network_processes;
+    let hidden_idle = if hide_idle {
+        filtered.iter().flat_map(|(group, _)| &group.members).filter(|process| process.is_idle() && !app.processes.contains(&process.pid)).count()
+    } else {
+        0
+    };
+    let filtered_groups: Vec<ProcessGroup> = filtered
+        .into_iter()
+        .map(|(group, _)| group)
+        .filter(|group| !hide_idle || !group.is_idle() || group.members.iter().any(|process| app.processes.contains(&process.pid)))
+        .collect();
+    let filtered_processes: Vec<NetworkProcessInfo> = filtered_groups
         .iter()
-        .filter(|process| {
-            if app.process_search_text.is_empty() {
-                true
-            } else {
-                process.name.to_lowercase().contains(&app.process_search_text.to_lowercase())
-            }
-        })
-        .cloned()
+        .filter(|group| !dimmed_groups.contains(&group.root_pid))
+        .flat_map(|group| group.members.iter().cloned())
+        .filter(|process| !hide_idle || !process.is_idle())
         .collect();
 
     // Section de limitation rapide
     ui.horizontal(|ui| {
         ui.label("⚡ Limitation rapide :");
-        ui.text_edit_singleline(&mut app.speed_limit_input);
-        ui.label("MB/s");
+        ui.add(egui::DragValue::new(&mut app.speed_limit_value).speed(0.1).clamp_range(0.0..=f64::MAX).max_decimals(2));
+        egui::ComboBox::from_id_source("speed_limit_unit")
+            .selected_text(app.speed_limit_unit.label())
+            .width(70.0)
+            .show_ui(ui, |ui| {
+                for unit in crate::network::SpeedUnit::ALL {
+                    ui.selectable_value(&mut app.speed_limit_unit, unit, unit.label());
+                }
+            });
+        egui::ComboBox::from_id_source("speed_limit_preset")
+            .selected_text("Préréglage")
+            .width(110.0)
+            .show_ui(ui, |ui| {
+                for preset in &app.limit_preferences.presets {
+                    let text = format!("{} : {}", preset.name, crate::network::describe_speed_limit(preset.kbps));
+                    if ui.selectable_label(false, text).clicked() {
+                        (app.speed_limit_value, app.speed_limit_unit) = preset_input(preset.kbps);
+                    }
+                }
+            })
+            .response
+            .on_hover_text("Préréglages modifiables dans les Paramètres");
+        // Valeur réellement appliquée : lève la confusion entre MB/s et Mbps
+        match crate::network::parse_speed_limit(app.speed_limit_value, app.speed_limit_unit) {
+            Ok(kbps) => ui.weak(format!("= {}", crate::network::describe_speed_limit(kbps))),
+            Err(_) if app.speed_limit_value == 0.0 => ui
+                .weak("= dernière limite de chaque application")
+                .on_hover_text("🚫 Limiter applique la dernière limite retenue pour l'application"),
+            Err(e) => ui.colored_label(theme.error, format!("❌ {}", e)),
+        };
+
+        ui.label("Durée :");
+        egui::ComboBox::from_id_source("limit_duration")
+            .selected_text(app.limit_duration.label())
+            .width(100.0)
+            .show_ui(ui, |ui| {
+                for duration in crate::network::LimitDuration::ALL {
+                    ui.selectable_value(&mut app.limit_duration, duration, duration.label());
+                }
+            })
+            .response
+            .on_hover_text("La limite est retirée automatiquement à l'échéance, même après un redémarrage de GameBooster");
+        if app.limit_duration == crate::network::LimitDuration::Custom {
+            ui.add(egui::DragValue::new(&mut app.limit_custom_minutes).clamp_range(1..=1440).suffix(" min"));
+        }
         
-        if ui.button("Appliquer aux sélectionnés").clicked() {
+        if ui.add_enabled(can_limit, egui::Button::new("Appliquer aux sélectionnés"))
+            .on_disabled_hover_text(ELEVATION_REQUIRED_HINT)
+            .clicked()
+        {
             apply_limit_clicked = true;
         }
+
+        let has_selection = !app.processes.is_empty();
+        if ui.add_enabled(can_limit && has_selection, egui::Button::new("🎮 Limiter tout le reste"))
+            .on_hover_text(format!(
+                "Limite chaque processus actif (plus de {} o/s) sauf la sélection. Processus système épargnés : {}",
+                crate::network::BULK_LIMIT_MIN_ACTIVITY,
+                crate::network::BULK_LIMIT_DENYLIST.join(", ")
+            ))
+            .on_disabled_hover_text(if can_limit { "Sélectionnez d'abord votre jeu" } else { ELEVATION_REQUIRED_HINT })
+            .clicked()
+        {
+            limit_others_clicked = true;
+        }
+        ui.checkbox(&mut app.exempt_selection, "Prioriser la sélection")
+            .on_hover_text("Marque aussi le trafic de la sélection en priorité (DSCP EF)");
+        if app.last_limit_batch.is_some() && ui.button("↩️ Annuler cette action").clicked() {
+            undo_batch_clicked = true;
+        }
+
+        ui.checkbox(&mut app.verify_limits, "🔬 Vérifier l'effet")
+            .on_hover_text("Mesure le débit sortant pendant 10 s après une limitation et le compare à la limite (tolérance 20 %)");
+
+        draw_scope_selector(app, ui);
+
+        if let Some(summary) = &app.last_bulk_limit_summary {
+            ui.label(format!("📦 Dernière limitation en lot : {}", summary));
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("🏷️ Priorité (DSCP) :");
+
+        let selected_text = match app.dscp_selection {
+            None => "Aucune".to_string(),
+            Some(dscp) => crate::network::DSCP_PRESETS
+                .iter()
+                .find(|(_, value)| *value == dscp)
+                .map(|(label, _)| label.to_string())
+                .unwrap_or_else(|| format!("Personnalisée ({})", dscp)),
+        };
+        egui::ComboBox::from_id_source("dscp_selector")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut app.dscp_selection, None, "Aucune");
+                for (label, value) in crate::network::DSCP_PRESETS {
+                    ui.selectable_value(&mut app.dscp_selection, Some(*value), *label);
+                }
+            })
+            .response
+            .on_hover_text(
+                "Marque le trafic pour que les routeurs le priorisent.\n\
+                 Windows peut exiger le paramètre de registre « Do not use NLA » \
+                 (HKLM\\SYSTEM\\CurrentControlSet\\Services\\Tcpip\\QoS) : il est créé automatiquement s'il manque.",
+            );
+
+        if let Some(dscp) = app.dscp_selection.as_mut() {
+            ui.add(egui::DragValue::new(dscp).clamp_range(0..=crate::network::DSCP_MAX));
+        }
+
+        if ui.add_enabled(can_limit, egui::Button::new("Appliquer la priorité"))
+            .on_disabled_hover_text(ELEVATION_REQUIRED_HINT)
+            .clicked()
+        {
+            apply_dscp_clicked = true;
+        }
+
+        if let Some(message) = &app.last_dscp_message {
+            ui.label(format!("🏷️ Dernier marquage : {}", message));
+        }
     });
 
     ui.separator();
@@ -127,116 +456,111 @@ pub fn draw_network_tab(app: &mut CleanRamApp, ui: &mut Ui) {
     });
 
     ui.separator();
+    draw_exited_limits(app, ui, &theme);
 
     if !has_limiter {
-        ui.colored_label(egui::Color32::RED, "❌ Gestionnaire réseau non initialisé");
-    } else if filtered_processes.is_empty() && app.process_search_text.is_empty() {
-        ui.colored_label(egui::Color32::YELLOW, "⚠️ Aucun processus trouvé. Cliquez sur 'Scanner processus'");
+        ui.colored_label(theme.error, "❌ Gestionnaire réseau non initialisé");
+    } else if filtered_groups.is_empty() && app.process_search_text.is_empty() {
+        ui.colored_label(theme.warning, "⚠️ Aucun processus trouvé. Cliquez sur 'Scanner processus'");
         ui.colored_label(egui::Color32::GRAY, "💡 Le scan utilise les données système réelles");
-    } else if filtered_processes.is_empty() {
-        ui.colored_label(egui::Color32::YELLOW, "🔍 Aucun processus ne correspond à votre recherche");
+    } else if filtered_groups.is_empty() {
+        ui.colored_label(theme.warning, "🔍 Aucun processus ne correspond à votre recherche");
     } else {
         // Liste des processus - DONNÉES RÉELLES
         ui.label("📊 Processus avec activité réseau (temps réel) :");
+        ui.weak("Clic droit sur un processus pour le terminer ou le redémarrer");
         
         // Variables pour collecter les actions à effectuer
-        let mut actions_to_perform: Vec<(u32, bool)> = Vec::new(); // (pid, is_limit_action)
+        let mut actions_to_perform: Vec<(u32, LimitAction)> = Vec::new();
+        let mut block_actions: Vec<(u32, bool)> = Vec::new(); // (pid, block)
         
-        egui::ScrollArea::vertical()
-            .max_height(400.0)
-            .show(ui, |ui| {
-                for process in &filtered_processes {
-                    ui.group(|ui| {
-                        ui.horizontal(|ui| {
-                            // Checkbox de sélection
-                            let mut selected = app.processes.contains(&process.pid);
-                            if ui.checkbox(&mut selected, "").changed() {
-                                if selected {
-                                    app.processes.insert(process.pid);
-                                } else {
-                                    app.processes.remove(&process.pid);
-                                }
-                            }
-                            
-                            // Informations du processus
-                            ui.vertical(|ui| {
-                                ui.horizontal(|ui| {
-                                    ui.label(format!("📋 {} (PID: {})", process.name, process.pid));
-                                    
-                                    // Badge de statut avec limitation appliquée
-                                    if process.is_limited {
-                                        ui.colored_label(egui::Color32::RED, "🚫 LIMITÉ");
-                                        if let Some(limit) = process.speed_limit {
-                                            ui.colored_label(
-                                                egui::Color32::YELLOW, 
-                                                format!("({} KB/s)", limit)
-                                            );
-                                        }
-                                    } else {
-                                        ui.colored_label(egui::Color32::GREEN, "✅ LIBRE");
-                                    }
-                                });
-                                
-                                // Statistiques réseau TEMPS RÉEL
-                                ui.horizontal(|ui| {
-                                    ui.label("📥 Vitesse actuelle reçue:");
-                                    ui.colored_label(
-                                        egui::Color32::from_rgb(33, 150, 243),
-                                        format_speed(process.current_download_speed)
-                                    );
-                                    
-                                    ui.label("📤 Vitesse actuelle envoyée:");
-                                    ui.colored_label(
-                                        egui::Color32::from_rgb(255, 152, 0),
-                                        format_speed(process.current_upload_speed)
-                                    );
-                                });
-                                
-                                // Total et connexions
-                                ui.horizontal(|ui| {
-                                    ui.label(format!("📊 Total: ⬇️ {} / ⬆️ {}", 
-                                        format_speed(process.bytes_received),
-                                        format_speed(process.bytes_sent)
-                                    ));
-                                    ui.label(format!("🔗 {} connexions", process.connections));
-                                });
-                            });
-                            
-                            // Actions sur le processus avec feedback visuel
-                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                if process.is_limited {
-                                    if ui.button("🔓 Libérer").clicked() {
-                                        tracing::info!("🔓 Libération demandée pour PID {}", process.pid);
-                                        actions_to_perform.push((process.pid, false));
-                                    }
-                                } else {
-                                    if ui.button("🚫 Limiter").clicked() {
-                                        tracing::info!("🚫 Limitation demandée pour PID {} ({})", process.pid, process.name);
-                                        actions_to_perform.push((process.pid, true));
-                                    }
-                                }
-                                
-                                if ui.button("⚙️ Config").clicked() {
-                                    tracing::info!("⚙️ Configuration demandée pour PID {} ({})", process.pid, process.name);
-                                    actions_to_perform.push((process.pid, true)); // Config = limit for now
-                                }
-                            });
-                        });
-                    });
-                    ui.add_space(5.0);
+        let mut toggled_groups: Vec<u32> = Vec::new();
+        let mut terminate_actions: Vec<(u32, String, bool)> = Vec::new(); // (pid, nom, redémarrer)
+
+        // Les processus limités restent épinglés en haut même quand leur trafic retombe
+        let (limited_groups, other_groups): (Vec<&ProcessGroup>, Vec<&ProcessGroup>) =
+            filtered_groups.iter().partition(|group| group.is_limited());
+
+        if !limited_groups.is_empty() {
+            ui.label(egui::RichText::new(format!("🚫 Limités ({})", limited_groups.len())).strong());
+            let rows = table_rows(&limited_groups, &app.expanded_network_groups, &dimmed_groups, hide_idle);
+            draw_process_table(ui, app, "network_limited_table", &rows, &theme, can_limit, &mut actions_to_perform, &mut block_actions, &mut toggled_groups, &mut terminate_actions);
+            ui.add_space(10.0);
+        }
+
+        let page_count = other_groups.len().div_ceil(NETWORK_PAGE_SIZE).max(1);
+        app.network_page = app.network_page.min(page_count - 1);
+        let page_groups: Vec<&ProcessGroup> = other_groups
+            .iter()
+            .skip(app.network_page * NETWORK_PAGE_SIZE)
+            .take(NETWORK_PAGE_SIZE)
+            .copied()
+            .collect();
+
+        if !page_groups.is_empty() {
+            let rows = table_rows(&page_groups, &app.expanded_network_groups, &dimmed_groups, hide_idle);
+            draw_process_table(ui, app, "network_process_table", &rows, &theme, can_limit, &mut actions_to_perform, &mut block_actions, &mut toggled_groups, &mut terminate_actions);
+        }
+
+        if page_count > 1 {
+            ui.horizontal(|ui| {
+                if ui.add_enabled(app.network_page > 0, egui::Button::new("◀ Précédent")).clicked() {
+                    app.network_page -= 1;
+                }
+                ui.label(format!("Page {} / {}", app.network_page + 1, page_count));
+                if ui.add_enabled(app.network_page + 1 < page_count, egui::Button::new("Suivant ▶")).clicked() {
+                    app.network_page += 1;
                 }
             });
+        }
+        if hidden_idle > 0 {
+            ui.weak(format!("💤 {} processus inactif(s) masqué(s) : sockets ouvertes sans trafic", hidden_idle));
+        }
+
+        for root_pid in toggled_groups {
+            if !app.expanded_network_groups.remove(&root_pid) {
+                app.expanded_network_groups.insert(root_pid);
+            }
+        }
         
         // Exécuter les actions collectées après la boucle
-        for (pid, is_limit) in actions_to_perform {
-            if is_limit {
-                tracing::info!("🎯 Application limitation pour PID {}", pid);
-                app.limit_process(pid);
+        for (pid, action) in actions_to_perform {
+            match action {
+                LimitAction::Limit => {
+                    tracing::info!("🎯 Application limitation pour PID {}", pid);
+                    app.request_protected(ProtectedAction::Limit(pid));
+                }
+                LimitAction::Remembered(kbps) => {
+                    tracing::info!("🔁 Application de la limite retenue pour PID {} : {} KB/s", pid, kbps);
+                    app.request_protected(ProtectedAction::LimitAt { pid, kbps });
+                }
+                LimitAction::Remove => {
+                    tracing::info!("🔓 Suppression limitation pour PID {}", pid);
+                    app.remove_process_limit(pid);
+                }
+            }
+        }
+        for (pid, blocked) in block_actions {
+            if blocked {
+                app.request_protected(ProtectedAction::Block(pid));
             } else {
-                tracing::info!("🔓 Suppression limitation pour PID {}", pid);
-                app.remove_process_limit(pid);
+                app.set_process_blocked(pid, false);
             }
         }
+        for (pid, name, restart) in terminate_actions {
+            let exe_path = app
+                .network_limiter
+                .as_ref()
+                .and_then(|limiter| limiter.process_exe_path(pid))
+                .map(std::path::PathBuf::from);
+            app.request_terminate(pid, &name, exe_path, restart);
+        }
+
+        draw_connection_panel(app, ui, &theme);
+        ui.separator();
+        draw_data_usage_panel(app, ui, &theme);
+        ui.separator();
+        draw_qos_policies_panel(app, ui, &theme);
     }
 
     // Informations techniques
@@ -258,7 +582,31 @@ pub fn draw_network_tab(app: &mut CleanRamApp, ui: &mut Ui) {
     }
     if apply_limit_clicked {
         tracing::info!("⚡ Application limitation rapide demandée à {} processus", app.processes.len());
-        app.apply_speed_limit_to_selected();
+        app.request_protected(ProtectedAction::LimitSelection);
+    }
+    if limit_others_clicked {
+        tracing::info!("🎮 Limitation de tout sauf {} processus demandée", app.processes.len());
+        app.request_limit_all_except_selected();
+    }
+    if undo_batch_clicked {
+        tracing::info!("↩️ Annulation de la dernière limitation en lot demandée");
+        app.undo_last_limit_batch();
+    }
+    if apply_dscp_clicked {
+        tracing::info!("🏷️ Marquage DSCP demandé pour {} processus", app.processes.len());
+        app.apply_dscp_to_selected();
+    }
+    if reset_all_clicked {
+        tracing::info!("♻️ Réinitialisation complète demandée");
+        app.request_action(crate::ui::app::PendingAction::ResetNetworkRules);
+    }
+    if prioritize_clicked {
+        tracing::info!("🎮 Priorisation du jeu demandée");
+        app.prioritize_game();
+    }
+    if clear_global_clicked {
+        tracing::info!("🔓 Suppression limite globale demandée");
+        app.clear_global_limit();
     }
     if select_all_clicked {
         // CORRECTION: Sélectionner seulement les processus filtrés
@@ -271,4 +619,1204 @@ pub fn draw_network_tab(app: &mut CleanRamApp, ui: &mut Ui) {
         tracing::info!("❌ Désélection de tous les processus");
         app.deselect_all_processes();
     }
-} 
\ No newline at end of file
+} 
+/// One line of the process table: a whole application group or a single process
+struct TableRow {
+    label: String,
+    tooltip: String,
+    /// For the icon before the label
+    exe_path: Option<PathBuf>,
+    pid_text: String,
+    /// Selected/deselected together by the row checkbox
+    pids: Vec<u32>,
+    /// Limit and block act on the executable, so any member PID stands for the whole group
+    action_pid: u32,
+    /// Key of the remembered limit, see `LimitPreferences`
+    exe_name: String,
+    download: u64,
+    upload: u64,
+    connections: u32,
+    /// "12 connexions (9 established, 3 time_wait)"
+    connections_summary: String,
+    speed_limit: Option<u32>,
+    is_limited: bool,
+    is_blocked: bool,
+    /// `Some(package family name or placeholder)` for Store applications
+    uwp_package: Option<String>,
+    /// `(root_pid, expanded)` for groups of several processes
+    group_toggle: Option<(u32, bool)>,
+    is_member: bool,
+    /// Limited but not matching the search: kept visible, greyed
+    dimmed: bool,
+    /// Greyed with a lock; critical rows cannot be limited or blocked
+    protection: ProtectionLevel,
+    /// `vmmem` of WSL2 / Hyper-V: the limiter refuses it, a port rule is needed
+    is_vm_host: bool,
+}
+
+impl TableRow {
+    fn from_process(process: &NetworkProcessInfo, is_member: bool) -> Self {
+        Self {
+            label: process.name.clone(),
+            tooltip: process
+                .exe_path
+                .as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| {
+                    if process.is_uwp {
+                        format!("Chemin de l'exécutable inconnu : {}", crate::network::appx::UNSUPPORTED_MESSAGE)
+                    } else {
+                        "Chemin de l'exécutable inconnu : limitation impossible".to_string()
+                    }
+                }),
+            exe_path: process.exe_path.clone(),
+            pid_text: process.pid.to_string(),
+            pids: vec![process.pid],
+            action_pid: process.pid,
+            exe_name: process.name.clone(),
+            download: process.current_download_speed,
+            upload: process.current_upload_speed,
+            connections: process.connections,
+            connections_summary: connections_summary(process.connections, &process.connections_by_state),
+            speed_limit: process.speed_limit,
+            is_limited: process.is_limited,
+            is_blocked: process.is_blocked,
+            uwp_package: uwp_package(process),
+            group_toggle: None,
+            is_member,
+            dimmed: false,
+            protection: process.protection,
+            is_vm_host: process.is_vm_host,
+        }
+    }
+
+    fn from_group(group: &ProcessGroup, expanded: bool) -> Self {
+        Self {
+            label: format!("{} ({} processus)", group.exe_name, group.members.len()),
+            tooltip: group.members[0]
+                .exe_path
+                .as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| group.exe_name.clone()),
+            exe_path: group.members[0].exe_path.clone(),
+            pid_text: group.root_pid.to_string(),
+            pids: group.members.iter().map(|p| p.pid).collect(),
+            action_pid: group.members[0].pid,
+            exe_name: group.members[0].name.clone(),
+            download: group.total_download_speed,
+            upload: group.total_upload_speed,
+            connections: group.total_connections(),
+            connections_summary: connections_summary(group.total_connections(), &group.connections_by_state()),
+            speed_limit: group.speed_limit(),
+            is_limited: group.is_limited(),
+            is_blocked: group.is_blocked(),
+            uwp_package: uwp_package(&group.members[0]),
+            group_toggle: Some((group.root_pid, expanded)),
+            is_member: false,
+            dimmed: false,
+            protection: group.protection(),
+            is_vm_host: group.members[0].is_vm_host,
+        }
+    }
+}
+
+/// Limit button of a table row, run once the tables are drawn
+enum LimitAction {
+    /// With the value of the input
+    Limit,
+    /// With the limit remembered for the application, in KB/s
+    Remembered(u32),
+    Remove,
+}
+
+/// Total followed by the states, most frequent first
+fn connections_summary(total: u32, by_state: &HashMap<TcpState, u32>) -> String {
+    let mut states: Vec<(&TcpState, &u32)> = by_state.iter().collect();
+    states.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    let details: Vec<String> = states.iter().map(|(state, count)| format!("{} {}", count, state.label())).collect();
+    if details.is_empty() {
+        format!("{} connexion(s)", total)
+    } else {
+        format!("{} connexion(s) ({})", total, details.join(", "))
+    }
+}
+
+fn uwp_package(process: &NetworkProcessInfo) -> Option<String> {
+    process
+        .is_uwp
+        .then(|| process.package_family_name.clone().unwrap_or_else(|| "Package inconnu".to_string()))
+}
+
+/// Rows for `groups` in display order, members listed under their expanded group.
+/// Rows of the groups in `dimmed` are greyed.
+/// Rows of `groups`; with `hide_idle`, the idle members of an expanded group are left out
+/// (the group row still counts them)
+fn table_rows(groups: &[&ProcessGroup], expanded: &HashSet<u32>, dimmed: &HashSet<u32>, hide_idle: bool) -> Vec<TableRow> {
+    let mut rows = Vec::new();
+    for group in groups {
+        let start = rows.len();
+        if let [process] = group.members.as_slice() {
+            rows.push(TableRow::from_process(process, false));
+        } else {
+            let is_expanded = expanded.contains(&group.root_pid);
+            rows.push(TableRow::from_group(group, is_expanded));
+            if is_expanded {
+                rows.extend(
+                    group
+                        .members
+                        .iter()
+                        .filter(|member| !hide_idle || !member.is_idle())
+                        .map(|member| TableRow::from_process(member, true)),
+                );
+            }
+        }
+        if dimmed.contains(&group.root_pid) {
+            for row in &mut rows[start..] {
+                row.dimmed = true;
+            }
+        }
+    }
+    rows
+}
+
+/// Input value and unit showing `kbps`: MB/s from 1 MB/s, KB/s below
+fn preset_input(kbps: u32) -> (f64, crate::network::SpeedUnit) {
+    if kbps >= 1024 {
+        (kbps as f64 / 1024.0, crate::network::SpeedUnit::MegaBytesPerSec)
+    } else {
+        (kbps as f64, crate::network::SpeedUnit::KiloBytesPerSec)
+    }
+}
+
+/// "1.0 MB/s" for a limit in KB/s, "—" without limit
+fn format_limit(limit_kbps: Option<u32>) -> String {
+    match limit_kbps {
+        Some(kbps) => format!("{:.1} MB/s", kbps as f64 / 1024.0),
+        None => "—".to_string(),
+    }
+}
+
+fn sort_header(app: &mut CleanRamApp, ui: &mut Ui, label: &str, column: NetworkProcessSort) {
+    let is_active = app.network_sort == column;
+    let text = if is_active {
+        format!("{} {}", label, if app.network_sort_descending { "⬇" } else { "⬆" })
+    } else {
+        label.to_string()
+    };
+
+    if ui.selectable_label(is_active, egui::RichText::new(text).strong()).clicked() {
+        if is_active {
+            app.network_sort_descending = !app.network_sort_descending;
+        } else {
+            app.network_sort = column;
+            app.network_sort_descending = true;
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_process_table(
+    ui: &mut Ui,
+    app: &mut CleanRamApp,
+    id: &str,
+    rows: &[TableRow],
+    theme: &Theme,
+    can_limit: bool,
+    actions_to_perform: &mut Vec<(u32, LimitAction)>,
+    block_actions: &mut Vec<(u32, bool)>,
+    toggled_groups: &mut Vec<u32>,
+    terminate_actions: &mut Vec<(u32, String, bool)>,
+) {
+    let stale = is_stale(app.network_limiter.as_ref().and_then(|limiter| limiter.seconds_since_last_scan()));
+    let estimated = app.network_limiter.as_ref().is_some_and(|limiter| limiter.speeds_estimated());
+    ui.push_id(id, |ui| {
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::exact(24.0))
+            .column(Column::initial(220.0).at_least(120.0).clip(true))
+            .column(Column::initial(60.0))
+            .column(Column::initial(90.0))
+            .column(Column::initial(90.0))
+            .column(Column::initial(80.0))
+            .column(Column::initial(80.0))
+            .column(Column::remainder().at_least(150.0))
+            .max_scroll_height(400.0)
+            .header(22.0, |mut header| {
+                header.col(|_| {});
+                header.col(|ui| sort_header(app, ui, "Processus", NetworkProcessSort::Name));
+                header.col(|ui| sort_header(app, ui, "PID", NetworkProcessSort::Pid));
+                header.col(|ui| sort_header(app, ui, "📥 Reçu", NetworkProcessSort::Download));
+                header.col(|ui| sort_header(app, ui, "📤 Envoyé", NetworkProcessSort::Upload));
+                header.col(|ui| sort_header(app, ui, "🔗 Connexions", NetworkProcessSort::Connections));
+                header.col(|ui| sort_header(app, ui, "Limite", NetworkProcessSort::Limit));
+                header.col(|ui| {
+                    ui.strong("Actions");
+                });
+            })
+            .body(|mut body| {
+                for row_data in rows {
+                    body.row(24.0, |mut row| {
+                        row.col(|ui| {
+                            let mut selected = row_data.pids.iter().all(|pid| app.processes.contains(pid));
+                            if ui.checkbox(&mut selected, "").changed() {
+                                for pid in &row_data.pids {
+                                    if selected {
+                                        app.processes.insert(*pid);
+                                    } else {
+                                        app.processes.remove(pid);
+                                    }
+                                }
+                            }
+                        });
+                        row.col(|ui| {
+                            if let Some((root_pid, expanded)) = row_data.group_toggle {
+                                if ui.small_button(if expanded { "▼" } else { "▶" }).clicked() {
+                                    toggled_groups.push(root_pid);
+                                }
+                            } else if row_data.is_member {
+                                ui.add_space(16.0);
+                            }
+                            app.process_icons.show(ui, row_data.exe_path.as_deref());
+                            let name = if row_data.dimmed {
+                                ui.weak(&row_data.label)
+                                    .on_hover_text(format!("{}\nNe correspond pas à la recherche : affiché car limité", row_data.tooltip))
+                            } else if row_data.protection.is_protected() {
+                                let note = if row_data.protection == ProtectionLevel::Critical {
+                                    "Processus système critique : limitation et blocage refusés"
+                                } else {
+                                    "Processus système : confirmation demandée avant de le limiter ou le bloquer"
+                                };
+                                ui.weak(format!("🔒 {}", row_data.label))
+                                    .on_hover_text(format!("{}\n{}", row_data.tooltip, note))
+                            } else {
+                                ui.label(&row_data.label).on_hover_text(&row_data.tooltip)
+                            };
+                            // Clic droit : terminer le processus de la ligne (la racine pour un groupe)
+                            if let Some(restart) = terminate_menu(&name, row_data.protection == ProtectionLevel::Critical) {
+                                terminate_actions.push((row_data.action_pid, row_data.label.clone(), restart));
+                            }
+                            if let Some(package) = &row_data.uwp_package {
+                                ui.colored_label(theme.accent, egui::RichText::new("UWP").small())
+                                    .on_hover_text(format!("Application Store : {}\nLimitée par le chemin complet de son exécutable", package));
+                            }
+                            if row_data.is_vm_host {
+                                ui.colored_label(theme.accent, egui::RichText::new("VM").small())
+                                    .on_hover_text(crate::memory::wsl::VM_HOST_HINT);
+                            }
+                            if row_data.is_blocked {
+                                ui.colored_label(theme.error, "⛔");
+                            }
+                        });
+                        row.col(|ui| {
+                            ui.label(&row_data.pid_text);
+                        });
+                        row.col(|ui| {
+                            speed_label(ui, theme.accent, row_data.download, stale, estimated);
+                        });
+                        row.col(|ui| {
+                            speed_label(ui, theme.warning, row_data.upload, stale, estimated);
+                        });
+                        row.col(|ui| {
+                            if ui.link(row_data.connections.to_string())
+                                .on_hover_text(format!("{}\nCliquer pour afficher les connexions ouvertes", row_data.connections_summary))
+                                .clicked()
+                            {
+                                app.toggle_connection_panel(&row_data.label, &row_data.pids);
+                            }
+                        });
+                        row.col(|ui| {
+                            if row_data.is_limited {
+                                ui.colored_label(theme.error, format_limit(row_data.speed_limit));
+                                draw_scope_badge(ui, app, &row_data.pids, theme);
+                                draw_expiry_badge(ui, app, &row_data.pids, theme);
+                                draw_verification_badge(ui, app, &row_data.pids, theme);
+                            } else {
+                                ui.label(format_limit(None));
+                                let remembered = app.limit_preferences.remembered(&row_data.exe_name);
+                                let critical = row_data.protection == ProtectionLevel::Critical;
+                                if let (Some(kbps), false, false) = (remembered, critical, row_data.is_member) {
+                                    if ui.add_enabled(can_limit, egui::Button::new(format!("↻ {}", format_limit(Some(kbps)))).small())
+                                        .on_hover_text("Dernière limite appliquée à cette application")
+                                        .on_disabled_hover_text(ELEVATION_REQUIRED_HINT)
+                                        .clicked()
+                                    {
+                                        actions_to_perform.push((row_data.action_pid, LimitAction::Remembered(kbps)));
+                                    }
+                                }
+                            }
+                        });
+                        row.col(|ui| {
+                            // Les processus d'un groupe se pilotent depuis la ligne du groupe
+                            if row_data.is_member {
+                                return;
+                            }
+                            let critical = row_data.protection == ProtectionLevel::Critical;
+                            let disabled_hint = if critical { PROTECTED_PROCESS_HINT } else { ELEVATION_REQUIRED_HINT };
+
+                            if row_data.is_limited {
+                                if ui.add_enabled(can_limit, egui::Button::new("🔓 Libérer").small())
+                                    .on_disabled_hover_text(ELEVATION_REQUIRED_HINT)
+                                    .clicked()
+                                {
+                                    tracing::info!("🔓 Libération demandée pour {}", row_data.label);
+                                    actions_to_perform.push((row_data.action_pid, LimitAction::Remove));
+                                }
+                            } else if ui.add_enabled(can_limit && !critical, egui::Button::new("🚫 Limiter").small())
+                                .on_disabled_hover_text(disabled_hint)
+                                .clicked()
+                            {
+                                tracing::info!("🚫 Limitation demandée pour {}", row_data.label);
+                                actions_to_perform.push((row_data.action_pid, LimitAction::Limit));
+                            }
+
+                            let blocked = row_data.is_blocked;
+                            if ui.add_enabled(can_limit && (blocked || !critical), egui::SelectableLabel::new(blocked, "⛔ Bloquer"))
+                                .on_hover_text("Bloque tout le trafic entrant et sortant via le pare-feu Windows")
+                                .on_disabled_hover_text(disabled_hint)
+                                .clicked()
+                            {
+                                tracing::info!("⛔ Blocage={} demandé pour {}", !blocked, row_data.label);
+                                block_actions.push((row_data.action_pid, !blocked));
+                            }
+                        });
+                    });
+                }
+            });
+    });
+}
+
+/// Latency to the configured host: sparkline and statistics over the last minute
+fn draw_ping_panel(app: &mut CleanRamApp, ui: &mut Ui, theme: &Theme) {
+    use crate::network::ping_monitor::{HOST_PRESETS, WINDOW};
+
+    app.network_tab_last_seen = Some(std::time::Instant::now());
+    let paused_by_cost = app.is_metered() && !app.ping_on_metered;
+    if app.ping_enabled && !paused_by_cost && !app.ping_monitor.is_running() {
+        app.ping_monitor.start(&app.ping_host);
+    }
+
+    let mut restart = false;
+    ui.horizontal(|ui| {
+        if ui.checkbox(&mut app.ping_enabled, "📶 Latence vers").changed() && !app.ping_enabled {
+            app.ping_monitor.stop();
+        }
+        let response = ui.add(egui::TextEdit::singleline(&mut app.ping_host).desired_width(160.0));
+        if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            restart = true;
+        }
+        egui::ComboBox::from_id_source("ping_host_preset")
+            .selected_text("Régions")
+            .show_ui(ui, |ui| {
+                for (label, host) in HOST_PRESETS {
+                    if ui.selectable_label(app.ping_host == *host, format!("{} — {}", label, host)).clicked() {
+                        app.ping_host = host.to_string();
+                        restart = true;
+                    }
+                }
+            });
+    });
+    if restart && app.ping_enabled && !paused_by_cost && app.ping_host.trim() != app.ping_monitor.host() {
+        app.ping_monitor.start(&app.ping_host);
+        app.save_settings();
+    }
+    if app.is_metered() {
+        ui.horizontal(|ui| {
+            if paused_by_cost {
+                ui.colored_label(theme.warning, "📶 Connexion limitée : mesure de latence en pause");
+            }
+            if ui.checkbox(&mut app.ping_on_metered, "Mesurer quand même").changed() {
+                if !app.ping_on_metered {
+                    app.ping_monitor.stop();
+                }
+                app.save_settings();
+            }
+        });
+    }
+
+    if !app.ping_monitor.is_running() {
+        return;
+    }
+    ui.ctx().request_repaint_after(std::time::Duration::from_secs(1));
+
+    let stats = app.ping_monitor.snapshot();
+    if let Some(error) = &stats.error {
+        ui.colored_label(theme.error, format!("❌ {}", error));
+        return;
+    }
+    let color = if stats.is_degraded() { theme.error } else { theme.success };
+    let ms = |value: Option<f64>| value.map(|v| format!("{:.0} ms", v)).unwrap_or_else(|| "—".to_string());
+
+    ui.horizontal(|ui| {
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(240.0, 36.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_stroke(rect, 2.0, ui.visuals().widgets.noninteractive.bg_stroke);
+
+        let max = stats.max_ms.unwrap_or(1.0).max(1.0);
+        let step = rect.width() / (WINDOW - 1) as f32;
+        let offset = WINDOW.saturating_sub(stats.samples.len());
+        let mut previous: Option<egui::Pos2> = None;
+        for (index, sample) in stats.samples.iter().enumerate() {
+            let x = rect.left() + (offset + index) as f32 * step;
+            match sample {
+                Some(rtt) => {
+                    let point = egui::pos2(x, rect.bottom() - 2.0 - (*rtt / max) as f32 * (rect.height() - 4.0));
+                    if let Some(previous) = previous {
+                        painter.line_segment([previous, point], egui::Stroke::new(1.5, color));
+                    }
+                    previous = Some(point);
+                }
+                None => {
+                    // Paquet perdu
+                    painter.line_segment([egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())], egui::Stroke::new(1.0, theme.error));
+                    previous = None;
+                }
+            }
+        }
+
+        ui.colored_label(color, ms(stats.last_ms));
+        ui.label(format!(
+            "min {} · moy {} · max {} · gigue {} · pertes {:.0} %",
+            ms(stats.min_ms),
+            ms(stats.avg_ms),
+            ms(stats.max_ms),
+            ms(stats.jitter_ms),
+            stats.loss_percent
+        ));
+    });
+}
+
+/// ✓/✗ and measured rate of the last limit verification of one of `pids`
+fn draw_verification_badge(ui: &mut Ui, app: &CleanRamApp, pids: &[u32], theme: &Theme) {
+    if pids.iter().any(|pid| app.limit_verification_promises.contains_key(pid)) {
+        ui.spinner().on_hover_text("Mesure du débit sortant en cours...");
+        return;
+    }
+    let Some(verification) = pids.iter().find_map(|pid| app.limit_verifications.get(pid)) else {
+        return;
+    };
+
+    match verification {
+        Ok(v) if v.idle => {
+            ui.label("?").on_hover_text("Aucun envoi pendant la mesure : efficacité non vérifiable");
+        }
+        Ok(v) => {
+            let measured = format_speed((v.measured_avg_kbps * 1024.0) as u64);
+            let hover = format!("{} mesurés pour une limite de {}", measured, format_limit(Some(v.configured_kbps)));
+            if v.effective {
+                ui.colored_label(theme.success, format!("✓ {}", measured)).on_hover_text(hover);
+            } else {
+                ui.colored_label(theme.error, format!("✗ {}", measured))
+                    .on_hover_text(format!("{} — la limite ne semble pas appliquée", hover));
+            }
+        }
+        Err(e) => {
+            ui.colored_label(theme.warning, "⚠").on_hover_text(format!("Vérification impossible : {}", e));
+        }
+    }
+}
+
+/// Open sockets of the process chosen in the table, sorted by remote address
+fn draw_connection_panel(app: &mut CleanRamApp, ui: &mut Ui, theme: &Theme) {
+    let Some((label, _)) = app.connection_panel.clone() else {
+        return;
+    };
+
+    ui.add_space(10.0);
+    let mut close = false;
+    let mut refresh = false;
+    let mut names_pending = false;
+    ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(format!("🔗 Connexions de {} ({})", label, app.process_connections.len())).strong());
+            if ui.small_button("🔄").on_hover_text("Relire les connexions").clicked() {
+                refresh = true;
+            }
+            ui.checkbox(&mut app.resolve_connection_names, "Noms d'hôtes")
+                .on_hover_text("Résolution DNS inverse des adresses distantes, en arrière-plan");
+            if ui.small_button("✖").clicked() {
+                close = true;
+            }
+        });
+
+        let mut connections = app.process_connections.clone();
+        connections.sort_by(|a, b| (a.remote_addr, a.remote_port, a.protocol).cmp(&(b.remote_addr, b.remote_port, b.protocol)));
+        if app.connections_sort_descending {
+            connections.reverse();
+        }
+
+        ui.push_id("process_connections_table", |ui| {
+            TableBuilder::new(ui)
+                .striped(true)
+                .resizable(true)
+                .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                .column(Column::initial(50.0))
+                .column(Column::initial(180.0))
+                .column(Column::initial(260.0).clip(true))
+                .column(Column::remainder().at_least(90.0))
+                .max_scroll_height(250.0)
+                .header(22.0, |mut header| {
+                    header.col(|ui| {
+                        ui.strong("Proto");
+                    });
+                    header.col(|ui| {
+                        ui.strong("Local");
+                    });
+                    header.col(|ui| {
+                        let arrow = if app.connections_sort_descending { "⬇" } else { "⬆" };
+                        if ui.selectable_label(true, egui::RichText::new(format!("Distant {}", arrow)).strong()).clicked() {
+                            app.connections_sort_descending = !app.connections_sort_descending;
+                        }
+                    });
+                    header.col(|ui| {
+                        ui.strong("État");
+                    });
+                })
+                .body(|mut body| {
+                    for connection in &connections {
+                        body.row(22.0, |mut row| {
+                            row.col(|ui| {
+                                ui.label(connection.protocol.label());
+                            });
+                            row.col(|ui| {
+                                ui.label(connection.local_addr.to_string());
+                            });
+                            row.col(|ui| {
+                                let Some(address) = connection.remote_addr else {
+                                    ui.weak("—");
+                                    return;
+                                };
+                                let text = std::net::SocketAddr::new(address, connection.remote_port).to_string();
+                                if ui.small_button("📋").on_hover_text("Copier l'adresse").clicked() {
+                                    ui.output_mut(|output| output.copied_text = address.to_string());
+                                }
+                                ui.label(&text);
+                                if app.resolve_connection_names {
+                                    match app.reverse_dns.lookup(address) {
+                                        ReverseDns::Found(name) => {
+                                            ui.colored_label(theme.accent, name);
+                                        }
+                                        ReverseDns::Pending => {
+                                            names_pending = true;
+                                            ui.spinner();
+                                        }
+                                        ReverseDns::NotFound => {}
+                                    }
+                                }
+                            });
+                            row.col(|ui| {
+                                ui.label(connection.state.unwrap_or("—"));
+                            });
+                        });
+                    }
+                });
+        });
+    });
+
+    // Les noms arrivent du thread de résolution
+    if names_pending {
+        ui.ctx().request_repaint_after(std::time::Duration::from_millis(500));
+    }
+    if refresh {
+        app.refresh_process_connections();
+    }
+    if close {
+        app.connection_panel = None;
+        app.process_connections.clear();
+    }
+}
+
+/// Adapter carrying the default route, with its link speed
+/// Limits refused by `QosRequirements`; the monitoring below keeps working
+fn draw_qos_unavailable_banner(app: &mut CleanRamApp, ui: &mut Ui, theme: &Theme) {
+    let Some(unavailable) = app
+        .network_limiter
+        .as_ref()
+        .and_then(|limiter| limiter.qos_requirements())
+        .and_then(|requirements| requirements.unavailable())
+    else {
+        return;
+    };
+    let mut relaunch_clicked = false;
+    ui.horizontal(|ui| {
+        ui.colored_label(theme.warning, format!("⚠ {}", unavailable));
+        if unavailable.0 == QosUnavailableReason::NotElevated {
+            ui.label("—");
+            if ui.button("🔐 Relancer en admin").clicked() {
+                relaunch_clicked = true;
+            }
+        }
+    });
+    ui.weak("La surveillance du trafic reste disponible.");
+    if relaunch_clicked {
+        app.relaunch_as_admin(ui.ctx());
+    }
+}
+
+fn draw_active_adapter(app: &CleanRamApp, ui: &mut Ui, theme: &Theme) {
+    let Some(limiter) = app.network_limiter.as_ref() else {
+        return;
+    };
+    ui.horizontal(|ui| match limiter.active_adapter() {
+        Some(adapter) => {
+            ui.label(if adapter.is_wifi { "📶 Carte active :" } else { "🔌 Carte active :" });
+            ui.strong(&adapter.name).on_hover_text(&adapter.description);
+            ui.colored_label(theme.accent, adapter.link_speed_text());
+        }
+        None => {
+            ui.colored_label(theme.warning, "⚠ Aucune carte réseau connectée");
+        }
+    });
+}
+
+/// Adapter the next limits are scoped to
+fn draw_scope_selector(app: &mut CleanRamApp, ui: &mut Ui) {
+    let adapters: Vec<String> = app
+        .network_limiter
+        .as_ref()
+        .map(|limiter| {
+            limiter
+                .adapters()
+                .iter()
+                .filter(|adapter| adapter.is_up && !adapter.ipv4.is_empty())
+                .map(|adapter| adapter.name.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    // Sous Linux, la classe tc s'applique à toutes les cartes
+    if adapters.is_empty() || cfg!(target_os = "linux") {
+        return;
+    }
+
+    ui.label("Carte :");
+    egui::ComboBox::from_id_source("qos_scope_adapter")
+        .selected_text(app.qos_scope_adapter.as_deref().unwrap_or("Toutes"))
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut app.qos_scope_adapter, None, "Toutes");
+            for adapter in adapters {
+                let label = adapter.clone();
+                ui.selectable_value(&mut app.qos_scope_adapter, Some(adapter), label);
+            }
+        })
+        .response
+        .on_hover_text("Limite uniquement le trafic qui sort par cette carte");
+}
+
+/// Time left before a time-boxed limit of one of `pids` is removed
+fn draw_expiry_badge(ui: &mut Ui, app: &CleanRamApp, pids: &[u32], theme: &Theme) {
+    let Some(limiter) = app.network_limiter.as_ref() else {
+        return;
+    };
+    let Some(expiry) = pids.iter().find_map(|pid| limiter.limit_expiry(*pid)) else {
+        return;
+    };
+
+    if expiry.removal_failed() {
+        ui.colored_label(theme.warning, "⏳ expirée").on_hover_text(
+            "La suppression de cette limite a échoué : nouvel essai automatique chaque minute, ou libérez-la à la main",
+        );
+        return;
+    }
+    let remaining = (expiry.expires_at - chrono::Local::now()).num_seconds().max(0);
+    let countdown = if remaining >= 3600 {
+        format!("{} h {:02}", remaining / 3600, remaining % 3600 / 60)
+    } else {
+        format!("{:02}:{:02}", remaining / 60, remaining % 60)
+    };
+    ui.weak(format!("⏳ {}", countdown))
+        .on_hover_text(format!("Limite retirée à {}", expiry.expires_at.format("%H:%M")));
+    ui.ctx().request_repaint_after(std::time::Duration::from_secs(1));
+}
+
+/// Limits whose process exited: their policy still throttles the next launch of the executable
+fn draw_exited_limits(app: &mut CleanRamApp, ui: &mut Ui, theme: &Theme) {
+    let exited = match app.network_limiter {
+        Some(ref limiter) if !limiter.recently_exited().is_empty() => limiter.recently_exited().to_vec(),
+        _ => return,
+    };
+    let can_remove = app.can_run(crate::network::requires_elevation());
+    let mut resolved: Option<(String, bool)> = None;
+
+    ui.label(egui::RichText::new(format!("💤 Processus limités terminés ({})", exited.len())).weak().strong());
+    egui::Grid::new("exited_limits_grid").striped(true).num_columns(4).show(ui, |ui| {
+        for limit in &exited {
+            ui.weak(&limit.exe);
+            ui.weak(crate::network::describe_speed_limit(limit.kbps));
+            match &limit.removal_error {
+                Some(error) => {
+                    ui.colored_label(theme.warning, "⚠️ suppression échouée").on_hover_text(error);
+                }
+                None => {
+                    ui.weak(format!("terminé à {}", limit.exited_at.format("%H:%M:%S")));
+                }
+            }
+            ui.horizontal(|ui| {
+                if ui.add_enabled(can_remove, egui::Button::new("🗑️ Supprimer"))
+                    .on_hover_text("Retirer la politique QoS : le prochain lancement ne sera pas limité")
+                    .on_disabled_hover_text(ELEVATION_REQUIRED_HINT)
+                    .clicked()
+                {
+                    resolved = Some((limit.policy_name.clone(), true));
+                }
+                if ui.button("📌 Conserver")
+                    .on_hover_text("Garder la limite : elle s'appliquera de nouveau au prochain lancement")
+                    .clicked()
+                {
+                    resolved = Some((limit.policy_name.clone(), false));
+                }
+            });
+            ui.end_row();
+        }
+    });
+    if app.exited_limit_action == crate::network::ExitedLimitAction::Remove {
+        ui.weak(format!("Suppression automatique {} s après la fin du processus (Paramètres)", app.exited_limit_grace_secs));
+    }
+    ui.add_space(6.0);
+
+    if let Some((policy_name, remove)) = resolved {
+        app.resolve_exited_limit(&policy_name, remove);
+    }
+}
+
+/// Adapter of a scoped limit, in warning color when it is no longer connected
+fn draw_scope_badge(ui: &mut Ui, app: &CleanRamApp, pids: &[u32], theme: &Theme) {
+    let Some(limiter) = app.network_limiter.as_ref() else {
+        return;
+    };
+    let Some((pid, adapter)) = pids.iter().find_map(|pid| limiter.policy_scope(*pid).map(|adapter| (*pid, adapter))) else {
+        return;
+    };
+
+    if limiter.stale_scoped_limits().contains(&pid) {
+        ui.colored_label(theme.warning, "⚠").on_hover_text(format!(
+            "Limite liée à {}, qui n'est plus connectée : libérez puis limitez à nouveau",
+            adapter
+        ));
+    } else {
+        ui.label("🔌").on_hover_text(format!("Limite appliquée uniquement sur {}", adapter));
+    }
+}
+
+/// Policies found on the system next to the ones GameBooster tracks, listed on demand
+/// (several seconds with PowerShell) and kept until "Actualiser"
+/// Limits on a protocol and port range, for the traffic of no single executable
+fn draw_port_rules_panel(app: &mut CleanRamApp, ui: &mut Ui, theme: &Theme, can_limit: bool) {
+    let rules = app.network_limiter.as_ref().map(|limiter| limiter.port_rules()).unwrap_or_default();
+    let mut add_clicked = false;
+    let mut removed: Option<PortRule> = None;
+
+    egui::CollapsingHeader::new(format!("🔌 Règles par port ({})", rules.len())).id_source("port_rules").show(ui, |ui| {
+        ui.label("Limite l'envoi d'une plage de ports, quel que soit le processus (machines virtuelles, WSL, uTP).");
+        if cfg!(target_os = "linux") {
+            ui.colored_label(theme.warning, "🐧 Indisponible sous Linux : les limites y sont posées par processus");
+            return;
+        }
+
+        let draft = &mut app.port_rule_draft;
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("port_rule_protocol")
+                .selected_text(draft.protocol.label())
+                .width(70.0)
+                .show_ui(ui, |ui| {
+                    for protocol in PortProtocol::ALL {
+                        ui.selectable_value(&mut draft.protocol, protocol, protocol.label());
+                    }
+                });
+            ui.label("Ports");
+            ui.add(egui::DragValue::new(&mut draft.port_range.0).clamp_range(1..=u16::MAX));
+            ui.label("à");
+            ui.add(egui::DragValue::new(&mut draft.port_range.1).clamp_range(1..=u16::MAX));
+            egui::ComboBox::from_id_source("port_rule_direction")
+                .selected_text(draft.direction.label())
+                .show_ui(ui, |ui| {
+                    for direction in [PortDirection::Source, PortDirection::Destination] {
+                        ui.selectable_value(&mut draft.direction, direction, direction.label());
+                    }
+                })
+                .response
+                .on_hover_text("Port local : trafic servi par cette machine (seed BitTorrent)\nPort distant : trafic vers un serveur");
+            ui.add(
+                egui::DragValue::new(&mut draft.limit_kbps)
+                    .clamp_range(crate::network::MIN_SPEED_LIMIT_KBPS..=crate::network::MAX_SPEED_LIMIT_KBPS)
+                    .suffix(" KB/s"),
+            );
+        });
+
+        let problem = match draft.validate() {
+            Err(e) => Some(e.to_string()),
+            Ok(()) => app
+                .network_limiter
+                .as_ref()
+                .and_then(|limiter| limiter.overlapping_port_rule(draft))
+                .map(|existing| format!("Chevauche la règle {}", existing.describe())),
+        };
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(can_limit && problem.is_none(), egui::Button::new("➕ Ajouter la règle"))
+                .on_disabled_hover_text(problem.as_deref().unwrap_or(ELEVATION_REQUIRED_HINT))
+                .clicked()
+            {
+                add_clicked = true;
+            }
+            if let Some(problem) = &problem {
+                ui.colored_label(theme.error, format!("⚠ {}", problem));
+            }
+        });
+        if let Some(message) = &app.last_port_rule_message {
+            ui.label(message);
+        }
+
+        if rules.is_empty() {
+            ui.weak("Aucune règle par port");
+            return;
+        }
+        egui::Grid::new("port_rules_grid").striped(true).num_columns(3).show(ui, |ui| {
+            for rule in &rules {
+                ui.label(format!("🔌 {}", rule.describe()));
+                ui.label(format!("{} KB/s", rule.limit_kbps));
+                if ui
+                    .add_enabled(can_limit, egui::Button::new("🗑️"))
+                    .on_hover_text(rule.policy_name())
+                    .on_disabled_hover_text(ELEVATION_REQUIRED_HINT)
+                    .clicked()
+                {
+                    removed = Some(*rule);
+                }
+                ui.end_row();
+            }
+        });
+    });
+
+    if add_clicked {
+        app.add_port_rule();
+    }
+    if let Some(rule) = removed {
+        app.remove_port_rule(&rule);
+    }
+}
+
+/// Data used today by each application: download then upload in one bar, scaled to the
+/// application that used the most
+fn draw_data_usage_panel(app: &mut CleanRamApp, ui: &mut Ui, theme: &Theme) {
+    let Some(ref limiter) = app.network_limiter else {
+        return;
+    };
+    let totals = limiter.get_session_totals();
+    let estimated = limiter.speeds_estimated();
+    let mut reset = false;
+
+    egui::CollapsingHeader::new("📶 Consommation (aujourd'hui)").show(ui, |ui| {
+        ui.horizontal(|ui| {
+            let down: u64 = totals.iter().map(|traffic| traffic.bytes_down).sum();
+            let up: u64 = totals.iter().map(|traffic| traffic.bytes_up).sum();
+            ui.label(format!("Total : ↓ {} / ↑ {}", format_bytes(down), format_bytes(up)));
+            if ui.add_enabled(!totals.is_empty(), egui::Button::new("🗑 Réinitialiser"))
+                .on_hover_text("Remettre les compteurs à zéro")
+                .clicked()
+            {
+                reset = true;
+            }
+        });
+        if estimated {
+            ui.weak("Débits estimés (droits administrateur requis pour les compteurs ETW) : les totaux le sont aussi");
+        }
+        if totals.is_empty() {
+            ui.weak("Aucun trafic compté depuis le démarrage ou minuit");
+            return;
+        }
+
+        let max = totals.first().map_or(1, |traffic| traffic.total()).max(1);
+        egui::Grid::new("data_usage_grid").striped(true).num_columns(3).show(ui, |ui| {
+            for traffic in &totals {
+                let name = ui.label(&traffic.name);
+                if let Some(path) = &traffic.exe_path {
+                    name.on_hover_text(path.display().to_string());
+                }
+
+                let (rect, _) = ui.allocate_exact_size(egui::vec2(220.0, 12.0), egui::Sense::hover());
+                let painter = ui.painter_at(rect);
+                let down_width = rect.width() * (traffic.bytes_down as f32 / max as f32);
+                let up_width = rect.width() * (traffic.bytes_up as f32 / max as f32);
+                let down_bar = egui::Rect::from_min_size(rect.min, egui::vec2(down_width, rect.height()));
+                let up_bar = egui::Rect::from_min_size(
+                    egui::pos2(rect.left() + down_width, rect.top()),
+                    egui::vec2(up_width, rect.height()),
+                );
+                painter.rect_filled(down_bar, 2.0, theme.accent);
+                painter.rect_filled(up_bar, 2.0, theme.warning);
+
+                ui.label(format!("↓ {} / ↑ {}", format_bytes(traffic.bytes_down), format_bytes(traffic.bytes_up)))
+                    .on_hover_text(format!("Depuis {}", traffic.first_seen.format("%H:%M")));
+                ui.end_row();
+            }
+        });
+    });
+
+    if reset {
+        if let Some(limiter) = app.network_limiter.as_mut() {
+            limiter.reset_session_totals();
+        }
+    }
+}
+
+fn draw_qos_policies_panel(app: &mut CleanRamApp, ui: &mut Ui, theme: &Theme) {
+    app.poll_qos_verification();
+    let busy = app.qos_verification_promise.is_some();
+    if busy {
+        ui.ctx().request_repaint_after(std::time::Duration::from_millis(200));
+    }
+
+    let header = egui::CollapsingHeader::new("🧾 Politiques QoS actives").show(ui, |ui| {
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!busy, egui::Button::new("🔄 Actualiser")).clicked() {
+                app.start_qos_verification();
+            }
+            if busy {
+                ui.spinner();
+                ui.label("Vérification en cours...");
+            } else if let Some((checked_at, _)) = &app.qos_verification {
+                ui.weak(format!("Vérifié à {}", checked_at.format("%H:%M:%S")));
+            }
+        });
+
+        let policies = match &app.qos_verification {
+            Some((_, Ok(policies))) => policies.clone(),
+            Some((_, Err(e))) => {
+                ui.colored_label(theme.error, format!("❌ Vérification impossible : {}", e));
+                return;
+            }
+            None => return,
+        };
+        let Some(ref limiter) = app.network_limiter else {
+            return;
+        };
+        let rows = limiter.reconcile_qos_policies(&policies);
+        let conflicts = find_conflicts(&policies);
+        draw_policy_conflicts(ui, theme, &conflicts);
+        let foreign = policies.iter().filter(|policy| policy.is_foreign()).count();
+        if foreign > 0 {
+            ui.weak(format!("{} politique(s) d'autres outils ou de stratégies de groupe, non listée(s)", foreign));
+        }
+        if rows.is_empty() {
+            ui.weak("Aucune politique QoS GameBooster");
+            return;
+        }
+
+        let can_edit = app.can_run(crate::network::requires_elevation());
+        let mut action: Option<(QosPolicyInfo, bool)> = None;
+        egui::Grid::new("qos_policies_grid").striped(true).num_columns(6).show(ui, |ui| {
+            for title in ["Politique", "Cible", "Limite", "Registre seul", "Processus limités", ""] {
+                ui.label(egui::RichText::new(title).strong());
+            }
+            ui.end_row();
+
+            for row in &rows {
+                let policy = &row.policy;
+                ui.label(&policy.name);
+                let conflicting: Vec<&PolicyConflict> = conflicts
+                    .iter()
+                    .filter(|conflict| conflict.ours.name == policy.name || conflict.theirs.name == policy.name)
+                    .collect();
+                if conflicting.is_empty() {
+                    ui.label(policy.target_label());
+                } else {
+                    let others: Vec<String> = conflicting
+                        .iter()
+                        .map(|conflict| {
+                            let other = if conflict.ours.name == policy.name { &conflict.theirs } else { &conflict.ours };
+                            format!("{} ({})", other.name, policy_rate(other))
+                        })
+                        .collect();
+                    ui.colored_label(theme.warning, format!("⚠ {}", policy.app_name))
+                        .on_hover_text(format!("Aussi limitée par : {}", others.join(", ")));
+                }
+                if policy.throttle_bits > 0 {
+                    ui.label(format!("{:.2} MB/s", policy.throttle_bits as f64 / (1024.0 * 1024.0 * 8.0)));
+                } else {
+                    ui.label("—");
+                }
+                ui.label(if policy.is_registry_only { "oui" } else { "non" });
+                if row.limited_processes.is_empty() {
+                    ui.weak("aucun");
+                } else {
+                    ui.label(row.limited_processes.join(", "));
+                }
+                match row.state {
+                    QosPolicyState::Tracked => {
+                        ui.colored_label(theme.success, "✅ Suivie");
+                    }
+                    QosPolicyState::Untracked => {
+                        if ui
+                            .add_enabled(can_edit, egui::Button::new("🗑️ Supprimer"))
+                            .on_hover_text("Politique inconnue de GameBooster")
+                            .on_disabled_hover_text(ELEVATION_REQUIRED_HINT)
+                            .clicked()
+                        {
+                            action = Some((policy.clone(), false));
+                        }
+                    }
+                    QosPolicyState::Missing => {
+                        if ui
+                            .add_enabled(can_edit, egui::Button::new("🔁 Ré-appliquer"))
+                            .on_hover_text("Suivie par GameBooster mais absente de Windows")
+                            .on_disabled_hover_text(ELEVATION_REQUIRED_HINT)
+                            .clicked()
+                        {
+                            action = Some((policy.clone(), true));
+                        }
+                    }
+                }
+                ui.end_row();
+            }
+        });
+
+        if let Some((policy, reapply)) = action {
+            app.reconcile_qos_policy(&policy, reapply);
+        }
+    });
+
+    // Première ouverture : lancer la vérification une fois, le résultat reste en cache
+    if header.body_returned.is_some() && app.qos_verification.is_none() && !busy {
+        app.start_qos_verification();
+    }
+}
+
+fn policy_rate(policy: &QosPolicyInfo) -> String {
+    match policy.throttle_rate() {
+        Some(bits) => format!("{:.2} MB/s", bits as f64 / (1024.0 * 1024.0 * 8.0)),
+        None => "sans limite".to_string(),
+    }
+}
+
+/// Executables targeted by several throttling policies: Windows keeps only one of them,
+/// which explains a limit that "does not work"
+fn draw_policy_conflicts(ui: &mut Ui, theme: &Theme, conflicts: &[PolicyConflict]) {
+    for conflict in conflicts {
+        let text = if conflict.is_foreign() {
+            format!(
+                "⚠ {} : limite GameBooster {} mais aussi « {} » ({}), créée par un autre outil ou une stratégie de groupe",
+                conflict.exe,
+                policy_rate(&conflict.ours),
+                conflict.theirs.name,
+                policy_rate(&conflict.theirs)
+            )
+        } else {
+            format!(
+                "⚠ {} : deux limites GameBooster différentes, {} ({}) et {} ({})",
+                conflict.exe,
+                conflict.ours.name,
+                policy_rate(&conflict.ours),
+                conflict.theirs.name,
+                policy_rate(&conflict.theirs)
+            )
+        };
+        ui.colored_label(theme.warning, text)
+            .on_hover_text("Windows n'applique qu'une des politiques : la limite effective peut différer de celle choisie");
+    }
+}
+
+/// One-time dialog listing what a previous session left behind: GameBooster QoS policies
+/// (removal suggested when the targeted executable no longer exists) and the TCP auto-tuning
+/// restricted by older builds, reset only when the user asks.
+pub fn draw_orphaned_policies_dialog(app: &mut CleanRamApp, ctx: &egui::Context) {
+    if app.orphan_dialog_dismissed {
+        return;
+    }
+    let (orphans, autotuning_restricted): (Vec<QosPolicyInfo>, bool) = match app.network_limiter {
+        Some(ref limiter) => (limiter.orphaned_policies().to_vec(), limiter.legacy_autotuning_restricted()),
+        None => return,
+    };
+    if orphans.is_empty() && !autotuning_restricted {
+        return;
+    }
+
+    let theme = app.theme.clone();
+    let can_remove = app.can_run(crate::network::requires_elevation());
+    let mut resolved: Vec<(String, bool)> = Vec::new();
+    let mut apply_suggestions = false;
+    let mut reset_autotuning = false;
+    let mut open = true;
+
+    egui::Window::new("⚠️ Restes d'une session précédente")
+        .collapsible(false)
+        .resizable(false)
+        .open(&mut open)
+        .show(ctx, |ui| {
+            if autotuning_restricted {
+                ui.label("L'auto-tuning TCP de Windows est à « restricted », réglage laissé par une ancienne version de GameBooster : il ralentit toutes les connexions de la machine.");
+                if ui.add_enabled(can_remove, egui::Button::new("🔧 Rétablir « normal »"))
+                    .on_hover_text("netsh interface tcp set global autotuninglevel=normal")
+                    .on_disabled_hover_text(ELEVATION_REQUIRED_HINT)
+                    .clicked()
+                {
+                    reset_autotuning = true;
+                }
+                ui.separator();
+            }
+            if orphans.is_empty() {
+                if ui.button("Plus tard").clicked() {
+                    app.orphan_dialog_dismissed = true;
+                }
+                return;
+            }
+
+            ui.label("Ces politiques GameBooster viennent d'une session précédente (arrêt brutal ?) et brident peut-être encore des applications :");
+            ui.add_space(6.0);
+
+            egui::Grid::new("orphaned_policies_grid").striped(true).show(ui, |ui| {
+                for policy in &orphans {
+                    let rate = if policy.throttle_bits > 0 {
+                        format_speed(policy.throttle_bits / 8)
+                    } else {
+                        "—".to_string()
+                    };
+                    ui.label(&policy.name);
+                    ui.label(&policy.app_name);
+                    ui.label(rate);
+                    if policy.target_missing() {
+                        ui.colored_label(theme.warning, "exécutable introuvable");
+                    } else {
+                        ui.label("");
+                    }
+                    if ui.button("📥 Adopter").on_hover_text("Conserver la limite et l'afficher comme limitée").clicked() {
+                        resolved.push((policy.name.clone(), true));
+                    }
+                    if ui.add_enabled(can_remove, egui::Button::new("🗑️ Supprimer"))
+                        .on_disabled_hover_text(ELEVATION_REQUIRED_HINT)
+                        .clicked()
+                    {
+                        resolved.push((policy.name.clone(), false));
+                    }
+                    ui.end_row();
+                }
+            });
+
+            ui.add_space(6.0);
+            ui.horizontal(|ui| {
+                if ui.add_enabled(can_remove, egui::Button::new("✅ Appliquer la suggestion"))
+                    .on_hover_text("Supprime les politiques dont l'exécutable a disparu, adopte les autres")
+                    .on_disabled_hover_text(ELEVATION_REQUIRED_HINT)
+                    .clicked()
+                {
+                    apply_suggestions = true;
+                }
+                if ui.button("Plus tard").clicked() {
+                    app.orphan_dialog_dismissed = true;
+                }
+            });
+        });
+
+    if apply_suggestions {
+        resolved = orphans.iter().map(|p| (p.name.clone(), !p.target_missing())).collect();
+    }
+    for (name, adopt) in resolved {
+        app.resolve_orphaned_policy(&name, adopt);
+    }
+    if reset_autotuning {
+        app.reset_legacy_autotuning();
+    }
+    if !open {
+        app.orphan_dialog_dismissed = true;
+    }
+}
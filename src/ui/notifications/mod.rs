@@ -0,0 +1,318 @@
+//! # Notification center
+//!
+//! Outcome of the user actions (limits, cleanings, services, Defender, scheduled tasks), shown
+//! as toasts in the bottom-right corner for a few seconds and kept in a bounded list opened
+//! from the bell of the tab bar. An anyhow error shows its top-level message, the full chain
+//! of causes being in the hover text of the toast and under "Détails" in the list.
+//! A notification may offer buttons (`NotificationAction`): the click is queued and handled
+//! by the app, see `take_clicked`.
+//! `system_toast` shows Windows notifications, for the alerts that matter while the window is
+//! not in sight.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+use eframe::egui;
+
+use crate::theme::Theme;
+
+pub mod system_toast;
+
+/// Oldest notifications are dropped beyond this
+const MAX_NOTIFICATIONS: usize = 100;
+const TOAST_DURATION: Duration = Duration::from_secs(6);
+const MAX_TOASTS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationLevel {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl NotificationLevel {
+    fn icon(&self) -> &'static str {
+        match self {
+            NotificationLevel::Info => "ℹ️",
+            NotificationLevel::Success => "✅",
+            NotificationLevel::Warning => "⚠️",
+            NotificationLevel::Error => "❌",
+        }
+    }
+
+    fn color(&self, theme: &Theme) -> egui::Color32 {
+        match self {
+            NotificationLevel::Info => theme.accent,
+            NotificationLevel::Success => theme.success,
+            NotificationLevel::Warning => theme.warning,
+            NotificationLevel::Error => theme.error,
+        }
+    }
+}
+
+/// Button of a notification, handled by the app once clicked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationAction {
+    /// Apply again what the drift check found changed, see `crate::drift`
+    ReapplyDrift,
+    /// Keep the system as it is and stop tracking the drifted settings
+    AcceptDrift,
+}
+
+impl NotificationAction {
+    fn label(&self) -> &'static str {
+        match self {
+            NotificationAction::ReapplyDrift => "🔁 Ré-appliquer",
+            NotificationAction::AcceptDrift => "✔ Accepter l'état actuel",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub level: NotificationLevel,
+    pub message: String,
+    /// Causes of an anyhow error or list of individual failures, one per line
+    pub details: Option<String>,
+    pub timestamp: DateTime<Local>,
+    /// Feature that raised it ("Réseau", "Defender"...)
+    pub source: &'static str,
+    /// Buttons, removed once one of them is clicked
+    pub actions: Vec<NotificationAction>,
+    id: u64,
+    /// Start of the toast, pushed back while the toast is hovered
+    toast_since: Instant,
+    dismissed: bool,
+}
+
+#[derive(Default)]
+pub struct Notifications {
+    /// Newest first
+    items: VecDeque<Notification>,
+    next_id: u64,
+    /// Pushed since the list was last opened
+    unread: usize,
+    panel_open: bool,
+    /// Clicked buttons not yet handled by the app
+    clicked: Vec<NotificationAction>,
+}
+
+impl Notifications {
+    pub fn push(&mut self, level: NotificationLevel, source: &'static str, message: impl Into<String>, details: Option<String>) {
+        self.push_with_actions(level, source, message, details, Vec::new());
+    }
+
+    /// Notification with buttons; the older notifications lose the same buttons, only the
+    /// latest one acts
+    pub fn push_with_actions(
+        &mut self,
+        level: NotificationLevel,
+        source: &'static str,
+        message: impl Into<String>,
+        details: Option<String>,
+        actions: Vec<NotificationAction>,
+    ) {
+        for notification in self.items.iter_mut() {
+            notification.actions.retain(|action| !actions.contains(action));
+        }
+        self.items.push_front(Notification {
+            level,
+            message: message.into(),
+            details,
+            timestamp: Local::now(),
+            source,
+            actions,
+            id: self.next_id,
+            toast_since: Instant::now(),
+            dismissed: false,
+        });
+        self.items.truncate(MAX_NOTIFICATIONS);
+        self.next_id += 1;
+        if !self.panel_open {
+            self.unread += 1;
+        }
+    }
+
+    pub fn info(&mut self, source: &'static str, message: impl Into<String>) {
+        self.push(NotificationLevel::Info, source, message, None);
+    }
+
+    pub fn success(&mut self, source: &'static str, message: impl Into<String>) {
+        self.push(NotificationLevel::Success, source, message, None);
+    }
+
+    pub fn warning(&mut self, source: &'static str, message: impl Into<String>) {
+        self.push(NotificationLevel::Warning, source, message, None);
+    }
+
+    pub fn error(&mut self, source: &'static str, message: impl Into<String>) {
+        self.push(NotificationLevel::Error, source, message, None);
+    }
+
+    /// "`context` : <top-level message>", with the causes of `error` as details
+    pub fn error_chain(&mut self, source: &'static str, context: &str, error: &anyhow::Error) {
+        let details = (error.chain().count() > 1).then(|| error.chain().map(|cause| cause.to_string()).collect::<Vec<_>>().join("\n↳ "));
+        self.push(NotificationLevel::Error, source, format!("{} : {}", context, error), details);
+    }
+
+    /// Warning summarizing several failures, listed in the details
+    pub fn failures(&mut self, source: &'static str, message: impl Into<String>, errors: &[String]) {
+        let details = (!errors.is_empty()).then(|| errors.join("\n"));
+        self.push(NotificationLevel::Warning, source, message, details);
+    }
+
+    /// Buttons clicked since the last call
+    pub fn take_clicked(&mut self) -> Vec<NotificationAction> {
+        std::mem::take(&mut self.clicked)
+    }
+
+    /// Remove `actions` from every notification, once the app handled them some other way
+    pub fn retire_actions(&mut self, actions: &[NotificationAction]) {
+        for notification in self.items.iter_mut() {
+            notification.actions.retain(|action| !actions.contains(action));
+        }
+    }
+
+    /// Bell of the tab bar, with the number of unread notifications
+    pub fn draw_bell(&mut self, ui: &mut egui::Ui, theme: &Theme) {
+        let text = if self.unread > 0 {
+            egui::RichText::new(format!("🔔 {}", self.unread)).color(theme.warning).strong()
+        } else {
+            egui::RichText::new("🔔")
+        };
+        if ui.selectable_label(self.panel_open, text).on_hover_text("Notifications").clicked() {
+            self.panel_open = !self.panel_open;
+            self.unread = 0;
+        }
+    }
+
+    /// Toasts of the latest notifications, then the full list when opened from the bell
+    pub fn show(&mut self, ctx: &egui::Context, theme: &Theme) {
+        self.show_toasts(ctx, theme);
+        if self.panel_open {
+            self.show_panel(ctx, theme);
+        }
+    }
+
+    fn show_toasts(&mut self, ctx: &egui::Context, theme: &Theme) {
+        let clicked = &mut self.clicked;
+        let mut toasts: Vec<&mut Notification> = self
+            .items
+            .iter_mut()
+            .filter(|notification| !notification.dismissed && notification.toast_since.elapsed() < TOAST_DURATION)
+            .take(MAX_TOASTS)
+            .collect();
+        if toasts.is_empty() {
+            return;
+        }
+
+        egui::Area::new("notification_toasts")
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-10.0, -10.0))
+            .show(ctx, |ui| {
+                ui.set_max_width(360.0);
+                for notification in toasts.iter_mut() {
+                    let frame = egui::Frame::popup(ui.style()).stroke(egui::Stroke::new(1.0, notification.level.color(theme)));
+                    let response = frame
+                        .show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                let text = format!("{} {}", notification.level.icon(), notification.message);
+                                let label = ui.colored_label(notification.level.color(theme), text);
+                                if let Some(details) = &notification.details {
+                                    label.on_hover_text(details);
+                                }
+                                if ui.small_button("✖").clicked() {
+                                    notification.dismissed = true;
+                                }
+                            });
+                            if draw_actions(ui, &mut notification.actions, clicked) {
+                                notification.dismissed = true;
+                            }
+                        })
+                        .response;
+                    // Le temps de lire les détails : pas de fermeture sous la souris
+                    if ui.rect_contains_pointer(response.rect) {
+                        notification.toast_since = Instant::now();
+                    }
+                }
+            });
+
+        // Sans interaction, egui ne redessine pas : se réveiller pour la fermeture
+        let next_expiry = toasts
+            .iter()
+            .map(|notification| TOAST_DURATION.saturating_sub(notification.toast_since.elapsed()))
+            .min()
+            .unwrap_or(TOAST_DURATION);
+        ctx.request_repaint_after(next_expiry + Duration::from_millis(50));
+    }
+
+    fn show_panel(&mut self, ctx: &egui::Context, theme: &Theme) {
+        let mut open = true;
+        let mut clear = false;
+        egui::Window::new("🔔 Notifications")
+            .open(&mut open)
+            .default_width(460.0)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                if self.items.is_empty() {
+                    ui.weak("Aucune notification");
+                    return;
+                }
+                if ui.button("🗑️ Tout effacer").clicked() {
+                    clear = true;
+                }
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    for notification in self.items.iter_mut() {
+                        ui.horizontal(|ui| {
+                            ui.weak(notification.timestamp.format("%H:%M:%S").to_string());
+                            ui.weak(format!("[{}]", notification.source));
+                        });
+                        ui.colored_label(
+                            notification.level.color(theme),
+                            format!("{} {}", notification.level.icon(), notification.message),
+                        );
+                        if let Some(details) = &notification.details {
+                            egui::CollapsingHeader::new("Détails")
+                                .id_source(("notification_details", notification.id))
+                                .show(ui, |ui| {
+                                    ui.label(details);
+                                });
+                        }
+                        draw_actions(ui, &mut notification.actions, &mut self.clicked);
+                        ui.separator();
+                    }
+                });
+            });
+
+        if clear {
+            self.items.clear();
+        }
+        if !open {
+            self.panel_open = false;
+        }
+    }
+}
+
+/// Buttons of a notification; a click queues the action and removes the buttons
+fn draw_actions(ui: &mut egui::Ui, actions: &mut Vec<NotificationAction>, clicked: &mut Vec<NotificationAction>) -> bool {
+    if actions.is_empty() {
+        return false;
+    }
+    let mut chosen = None;
+    ui.horizontal(|ui| {
+        for action in actions.iter() {
+            if ui.small_button(action.label()).clicked() {
+                chosen = Some(*action);
+            }
+        }
+    });
+    let Some(action) = chosen else {
+        return false;
+    };
+    clicked.push(action);
+    actions.clear();
+    true
+}
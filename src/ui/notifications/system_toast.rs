@@ -0,0 +1,89 @@
+//! # Windows notifications
+//!
+//! Toasts of the Windows notification center, shown even when the window is minimized.
+//! GameBooster is not a packaged app: its AppUserModelID is registered under
+//! `HKCU\Software\Classes\AppUserModelId` before the first toast, without which Windows
+//! silently drops them. The cleanup before uninstallation removes that key.
+
+use anyhow::Result;
+
+/// AppUserModelID the toasts are shown under
+pub const APP_ID: &str = "GameBooster";
+#[cfg(target_os = "windows")]
+const APP_ID_KEY: &str = "Software\\Classes\\AppUserModelId\\GameBooster";
+
+/// Show a toast; `on_activated` runs on a system thread when the user clicks it
+#[cfg(target_os = "windows")]
+pub fn show(title: &str, body: &str, on_activated: impl Fn() + Send + Sync + 'static) -> Result<()> {
+    use std::sync::{Mutex, Once};
+    use windows::core::{IInspectable, HSTRING};
+    use windows::Data::Xml::Dom::XmlDocument;
+    use windows::Foundation::TypedEventHandler;
+    use windows::UI::Notifications::{ToastNotification, ToastNotificationManager};
+
+    use crate::utils::scheduled_task::xml_escape;
+
+    // Le dernier toast est gardé : relâché, il ne remonterait plus son clic
+    static LAST_TOAST: Mutex<Option<ToastNotification>> = Mutex::new(None);
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| {
+        if let Err(e) = register() {
+            tracing::warn!("⚠️ Enregistrement de l'identifiant {} pour les notifications impossible: {}", APP_ID, e);
+        }
+    });
+
+    let payload = format!(
+        "<toast activationType=\"foreground\"><visual><binding template=\"ToastGeneric\">\
+         <text>{}</text><text>{}</text></binding></visual></toast>",
+        xml_escape(title),
+        xml_escape(body)
+    );
+    let document = XmlDocument::new()?;
+    document.LoadXml(&HSTRING::from(payload))?;
+    let toast = ToastNotification::CreateToastNotification(&document)?;
+    toast.Activated(&TypedEventHandler::<ToastNotification, IInspectable>::new(move |_, _| {
+        on_activated();
+        Ok(())
+    }))?;
+    ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(APP_ID))?.Show(&toast)?;
+
+    if let Ok(mut last) = LAST_TOAST.lock() {
+        *last = Some(toast);
+    }
+    tracing::info!("🔔 Notification Windows : {} — {}", title, body);
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn show(_title: &str, _body: &str, _on_activated: impl Fn() + Send + Sync + 'static) -> Result<()> {
+    Err(anyhow::anyhow!("Notifications système disponibles uniquement sous Windows"))
+}
+
+#[cfg(target_os = "windows")]
+fn register() -> Result<()> {
+    use crate::utils::registry::{write_string, HKEY_CURRENT_USER};
+    write_string(HKEY_CURRENT_USER, APP_ID_KEY, "DisplayName", APP_ID)
+}
+
+/// Whether the AppUserModelID is registered, for the cleanup before uninstallation
+pub fn is_registered() -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        crate::utils::registry::key_exists(crate::utils::registry::HKEY_CURRENT_USER, APP_ID_KEY).unwrap_or(false)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        false
+    }
+}
+
+pub fn unregister() -> Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+        crate::utils::registry::delete_key(crate::utils::registry::HKEY_CURRENT_USER, APP_ID_KEY)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        Ok(())
+    }
+}
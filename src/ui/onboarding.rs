@@ -0,0 +1,348 @@
+//! # First-run assistant
+//!
+//! Shown over the window on the first launch, until completed or skipped, and again from the
+//! settings ("Relancer l'assistant"):
+//! 1. what works on this machine: elevation, network limits, Defender tamper protection, OS
+//!    edition, checked on a worker thread with the same functions as the tabs
+//! 2. the starting choices: default boost profile, automatic RAM cleaning, start with Windows
+//! 3. every area of the system GameBooster may modify, with a link to the tab doing it
+//!
+//! Nothing is applied before "Terminer": skipping keeps the current settings.
+
+use eframe::egui;
+use poll_promise::Promise;
+
+use crate::network::QosRequirements;
+use crate::profiles::{self, BoostProfile};
+use crate::services::winapi_defender::{DefenderManager, DefenderStatus};
+use crate::theme::Theme;
+use crate::ui::app::{CleanRamApp, Tab};
+
+/// Results of the checks of the first page
+pub struct OnboardingChecks {
+    pub qos: QosRequirements,
+    /// `None` when Defender is not installed
+    pub defender: Option<Result<DefenderStatus, String>>,
+}
+
+impl OnboardingChecks {
+    /// Blocking (WMI, registry): run on a worker thread
+    fn run(defender_present: bool) -> Self {
+        let qos = QosRequirements::check();
+        let defender = defender_present.then(|| DefenderManager::check_defender_status().map_err(|e| e.to_string()));
+        Self { qos, defender }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OnboardingPage {
+    Checks,
+    Choices,
+    Summary,
+}
+
+/// State of the assistant while it is shown
+pub struct Onboarding {
+    page: OnboardingPage,
+    checks_promise: Option<Promise<OnboardingChecks>>,
+    checks: Option<OnboardingChecks>,
+    /// Name of the profile offered by the "Boost" button of the tab bar
+    default_profile: String,
+    auto_clean: bool,
+    run_at_startup: bool,
+}
+
+impl Onboarding {
+    /// Start the checks; the choices start from the current settings
+    pub fn start(app: &CleanRamApp) -> Self {
+        let defender_present = app.os_capabilities.defender_present;
+        tracing::info!("🧭 Assistant de démarrage ouvert");
+        Self {
+            page: OnboardingPage::Checks,
+            checks_promise: Some(Promise::spawn_thread("onboarding_checks", move || OnboardingChecks::run(defender_present))),
+            checks: None,
+            default_profile: app.default_profile.clone().unwrap_or_else(|| profiles::LIGHT_PROFILE.to_string()),
+            auto_clean: app.auto_clean.enabled,
+            run_at_startup: app.run_at_startup,
+        }
+    }
+
+    fn poll_checks(&mut self) {
+        if let Some(promise) = self.checks_promise.take() {
+            match promise.try_take() {
+                Ok(checks) => self.checks = Some(checks),
+                Err(promise) => self.checks_promise = Some(promise),
+            }
+        }
+    }
+}
+
+/// How the assistant was left
+enum OnboardingExit {
+    Skipped,
+    Finished { open_tab: Option<Tab> },
+}
+
+/// Draw the assistant over the window while `app.onboarding` is set
+pub fn draw_onboarding(app: &mut CleanRamApp, ctx: &egui::Context) {
+    let Some(mut onboarding) = app.onboarding.take() else {
+        return;
+    };
+    onboarding.poll_checks();
+    if onboarding.checks_promise.is_some() {
+        ctx.request_repaint_after(std::time::Duration::from_millis(250));
+    }
+
+    // Voile sur le reste de la fenêtre, qui absorbe aussi les clics
+    egui::Area::new("onboarding_backdrop")
+        .order(egui::Order::Middle)
+        .fixed_pos(egui::Pos2::ZERO)
+        .show(ctx, |ui| {
+            let screen = ctx.screen_rect();
+            ui.allocate_rect(screen, egui::Sense::click());
+            ui.painter().rect_filled(screen, 0.0, egui::Color32::from_black_alpha(160));
+        });
+
+    let theme = app.theme.clone();
+    let mut exit = None;
+    egui::Area::new("onboarding")
+        .order(egui::Order::Foreground)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            egui::Frame::window(ui.style()).show(ui, |ui| {
+                ui.set_width(560.0);
+                let (step, title) = match onboarding.page {
+                    OnboardingPage::Checks => (1, "Ce qui fonctionne sur ce PC"),
+                    OnboardingPage::Choices => (2, "Réglages de départ"),
+                    OnboardingPage::Summary => (3, "Ce que GameBooster peut modifier"),
+                };
+                ui.weak(format!("Bienvenue dans GameBooster — étape {}/3", step));
+                ui.heading(title);
+                ui.separator();
+
+                let page = onboarding.page;
+                egui::ScrollArea::vertical().max_height(380.0).show(ui, |ui| match page {
+                    OnboardingPage::Checks => draw_checks(app, ui, &theme, &onboarding),
+                    OnboardingPage::Choices => draw_choices(ui, &mut onboarding),
+                    OnboardingPage::Summary => {
+                        if let Some(tab) = draw_summary(ui, &onboarding) {
+                            exit = Some(OnboardingExit::Finished { open_tab: Some(tab) });
+                        }
+                    }
+                });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Passer").on_hover_text("Fermer l'assistant sans rien changer").clicked() {
+                        exit = Some(OnboardingExit::Skipped);
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let next = match onboarding.page {
+                            OnboardingPage::Checks => Some(OnboardingPage::Choices),
+                            OnboardingPage::Choices => Some(OnboardingPage::Summary),
+                            OnboardingPage::Summary => None,
+                        };
+                        let label = if next.is_some() { "Suivant ▶" } else { "✔ Terminer" };
+                        let button = egui::Button::new(egui::RichText::new(label).color(egui::Color32::WHITE)).fill(theme.accent);
+                        if ui.add(button).clicked() {
+                            match next {
+                                Some(page) => onboarding.page = page,
+                                None => exit = Some(OnboardingExit::Finished { open_tab: None }),
+                            }
+                        }
+                        let previous = match onboarding.page {
+                            OnboardingPage::Checks => None,
+                            OnboardingPage::Choices => Some(OnboardingPage::Checks),
+                            OnboardingPage::Summary => Some(OnboardingPage::Choices),
+                        };
+                        if let Some(page) = previous {
+                            if ui.button("◀ Précédent").clicked() {
+                                onboarding.page = page;
+                            }
+                        }
+                    });
+                });
+            });
+        });
+
+    match exit {
+        None => app.onboarding = Some(onboarding),
+        Some(OnboardingExit::Skipped) => {
+            tracing::info!("🧭 Assistant de démarrage passé");
+            app.onboarding_completed = true;
+            app.save_settings();
+        }
+        Some(OnboardingExit::Finished { open_tab }) => {
+            tracing::info!("🧭 Assistant de démarrage terminé (profil par défaut : {})", onboarding.default_profile);
+            app.default_profile = Some(onboarding.default_profile);
+            app.auto_clean.enabled = onboarding.auto_clean;
+            if onboarding.run_at_startup != app.run_at_startup {
+                app.set_run_at_startup(onboarding.run_at_startup);
+            }
+            if let Some(tab) = open_tab {
+                app.active_tab = tab;
+            }
+            app.onboarding_completed = true;
+            app.save_settings();
+        }
+    }
+}
+
+/// "✅ Droits administrateur" and what it means, in green or red
+fn check_row(ui: &mut egui::Ui, theme: &Theme, ok: bool, label: &str, explanation: &str) {
+    let (icon, color) = if ok { ("✅", theme.success) } else { ("❌", theme.error) };
+    ui.colored_label(color, egui::RichText::new(format!("{} {}", icon, label)).strong());
+    ui.label(explanation);
+    ui.add_space(4.0);
+}
+
+fn draw_checks(app: &CleanRamApp, ui: &mut egui::Ui, theme: &Theme, onboarding: &Onboarding) {
+    let details = &app.os_details;
+    let capabilities = app.os_capabilities;
+
+    check_row(
+        ui,
+        theme,
+        !details.is_server && capabilities.optimization_supported,
+        &details.description(),
+        if !capabilities.optimization_supported {
+            "Seuls le nettoyage de la mémoire et la surveillance réseau sont disponibles sur ce système"
+        } else if details.is_server {
+            "Édition serveur : certaines optimisations de jeu sont sans effet"
+        } else {
+            "Optimisations Windows, services et nettoyage de disque disponibles"
+        },
+    );
+    check_row(
+        ui,
+        theme,
+        app.is_elevated,
+        "Droits administrateur",
+        if app.is_elevated {
+            "Services, Defender, limites réseau et purge de la liste standby utilisables"
+        } else {
+            "Services, Defender, limites réseau et purge de la liste standby demandent de relancer en administrateur"
+        },
+    );
+
+    let Some(checks) = &onboarding.checks else {
+        ui.horizontal(|ui| {
+            ui.spinner();
+            ui.label("Vérification de la limitation réseau et de Defender...");
+        });
+        return;
+    };
+
+    match checks.qos.unavailable() {
+        _ if !capabilities.qos_supported => check_row(
+            ui,
+            theme,
+            false,
+            "Limitation réseau",
+            "Politiques QoS non prises en charge par cette version : surveillance seule",
+        ),
+        None => check_row(ui, theme, true, "Limitation réseau", "Les débits par application peuvent être limités (politiques QoS)"),
+        Some(reason) => check_row(ui, theme, false, "Limitation réseau", &format!("{} : surveillance seule", reason)),
+    }
+
+    match &checks.defender {
+        None => check_row(ui, theme, true, "Windows Defender", "Defender absent : la pause pendant les jeux est sans objet"),
+        Some(Ok(status)) if status.tamper_protection => check_row(
+            ui,
+            theme,
+            false,
+            "Windows Defender",
+            "La protection contre les falsifications est active : Defender ne peut pas être mis en pause. \
+             Désactivez-la dans Sécurité Windows pour utiliser cette fonction.",
+        ),
+        Some(Ok(_)) => check_row(ui, theme, true, "Windows Defender", "Peut être mis en pause pendant les jeux, puis réactivé"),
+        Some(Err(e)) => check_row(ui, theme, false, "Windows Defender", &format!("État illisible : {}", e)),
+    }
+}
+
+fn draw_choices(ui: &mut egui::Ui, onboarding: &mut Onboarding) {
+    ui.label("Profil de boost par défaut, proposé par le bouton 🚀 de la barre d'onglets :");
+    let choices: [(&str, &str); 2] = [
+        (profiles::LIGHT_PROFILE, "Prudent : arrête l'indexation et SysMain, active Performances élevées"),
+        (profiles::AGGRESSIVE_PROFILE, "Agressif : arrête aussi Windows Update, l'impression et les rapports d'erreurs, limite OneDrive"),
+    ];
+    for (name, description) in choices {
+        ui.radio_value(&mut onboarding.default_profile, name.to_string(), description);
+    }
+    if let Some(profile) = profiles::built_in().iter().find(|profile| profile.name == onboarding.default_profile) {
+        ui.weak(format!("    {}", crate::ui::profiles_ui::profile_summary(profile)));
+    }
+    ui.add_space(8.0);
+    ui.checkbox(&mut onboarding.auto_clean, "Nettoyer la RAM automatiquement quand elle est presque pleine");
+    ui.checkbox(&mut onboarding.run_at_startup, "Lancer GameBooster au démarrage de Windows (réduit)");
+    ui.weak("Tout se modifie ensuite dans les Paramètres et l'onglet Mémoire.");
+}
+
+/// Areas of the system changed by GameBooster and the tab changing them
+const MODIFIED_AREAS: &[(&str, &str, Tab)] = &[
+    (
+        "🧠 Mémoire",
+        "Vide la mémoire de travail des processus et, en administrateur, la liste standby. Rien n'est écrit sur le disque.",
+        Tab::Memory,
+    ),
+    (
+        "🛡️ Services Windows",
+        "Arrête des services (Windows Search, SysMain...). Le mode durable change leur type de démarrage ; « Rétablir » le restaure.",
+        Tab::Services,
+    ),
+    (
+        "🛡️ Windows Defender",
+        "Met la protection en temps réel en pause par le registre, réactivée automatiquement à la fin du délai choisi.",
+        Tab::Services,
+    ),
+    (
+        "⚡ Plan d'alimentation et mode jeu",
+        "Active Performances élevées (le plan d'origine est restauré), Game Mode, GameDVR et les priorités MMCSS dans le registre.",
+        Tab::Optimization,
+    ),
+    (
+        "💾 Fichiers et registre",
+        "Supprime fichiers temporaires, cache des navigateurs et miniatures. Les optimisations Windows modifient le registre, \
+         le bloqueur modifie le fichier hosts.",
+        Tab::Optimization,
+    ),
+    (
+        "📡 Réseau",
+        "Crée des politiques QoS « GameBooster_* » et des règles de pare-feu « GameBooster_Block_* », listées et supprimables \
+         dans l'onglet Réseau.",
+        Tab::Network,
+    ),
+    (
+        "🚀 Démarrage",
+        "Ajoute l'entrée « GameBooster » à la clé Run de l'utilisateur si le lancement avec Windows est activé.",
+        Tab::Settings,
+    ),
+];
+
+/// Tab whose link was clicked
+fn draw_summary(ui: &mut egui::Ui, onboarding: &Onboarding) -> Option<Tab> {
+    let mut open_tab = None;
+    ui.label("Chaque modification est lancée par vous, et la plupart s'annulent depuis l'onglet qui la fait :");
+    ui.add_space(4.0);
+    for (title, description, tab) in MODIFIED_AREAS {
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(*title).strong());
+            if ui.link("ouvrir l'onglet").clicked() {
+                open_tab = Some(*tab);
+            }
+        });
+        ui.label(*description);
+        ui.add_space(4.0);
+    }
+    ui.separator();
+    let boost: Option<BoostProfile> = profiles::built_in().into_iter().find(|profile| profile.name == onboarding.default_profile);
+    if let Some(profile) = boost {
+        ui.label(format!("Profil par défaut : {} ({})", profile.name, crate::ui::profiles_ui::profile_summary(&profile)));
+    }
+    ui.label(format!(
+        "Nettoyage automatique : {} · Démarrage avec Windows : {}",
+        if onboarding.auto_clean { "activé" } else { "désactivé" },
+        if onboarding.run_at_startup { "activé" } else { "désactivé" }
+    ));
+    open_tab
+}
@@ -0,0 +1,219 @@
+//! # Process icons
+//!
+//! Icon of each executable, shown before the process names of the network and memory tables.
+//! - extracted on a worker thread (`ExtractIconExW`, then `GetIconInfo` + `GetDIBits` for the
+//!   pixels) and received through a channel: a new process never delays a frame
+//! - uploaded once as a texture, cached by executable path
+//! - at most `MAX_ICONS` textures, the least recently shown is evicted; dropping its
+//!   `TextureHandle` frees the texture
+//! - a placeholder while the icon is loading, and for the executables that cannot be read
+//!   (system processes, WindowsApps folders) or have no icon; always on Linux
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use eframe::egui;
+
+/// Textures kept at most
+const MAX_ICONS: usize = 200;
+
+enum Icon {
+    Pending,
+    Ready(egui::TextureHandle),
+    Unavailable,
+}
+
+struct Entry {
+    icon: Icon,
+    last_used: u64,
+}
+
+type Extracted = (PathBuf, Option<egui::ColorImage>);
+
+#[derive(Default)]
+pub struct ProcessIcons {
+    entries: HashMap<PathBuf, Entry>,
+    /// Incremented on each lookup, orders the entries by last use
+    tick: u64,
+    /// Channels of the worker, started on the first request
+    worker: Option<(Sender<PathBuf>, Receiver<Extracted>)>,
+}
+
+impl ProcessIcons {
+    /// Icon of `path` sized to a widget row, or the placeholder until it is ready
+    pub fn show(&mut self, ui: &mut egui::Ui, path: Option<&Path>) -> egui::Response {
+        let size = egui::Vec2::splat(ui.spacing().interact_size.y - 2.0);
+        self.receive(ui.ctx());
+        match path.and_then(|path| self.texture(ui.ctx(), path)) {
+            Some(texture) => ui.add(egui::Image::new((texture, size))),
+            None => placeholder(ui, size),
+        }
+    }
+
+    /// Turn the extracted icons into textures
+    fn receive(&mut self, ctx: &egui::Context) {
+        let Some((_, results)) = &self.worker else {
+            return;
+        };
+        for (path, image) in results.try_iter() {
+            // Évincée pendant l'extraction : rien à charger
+            let Some(entry) = self.entries.get_mut(&path) else {
+                continue;
+            };
+            entry.icon = match image {
+                Some(image) => Icon::Ready(ctx.load_texture(format!("process_icon:{}", path.display()), image, egui::TextureOptions::LINEAR)),
+                None => Icon::Unavailable,
+            };
+        }
+    }
+
+    fn texture(&mut self, ctx: &egui::Context, path: &Path) -> Option<egui::TextureId> {
+        self.tick += 1;
+        if let Some(entry) = self.entries.get_mut(path) {
+            entry.last_used = self.tick;
+            return match &entry.icon {
+                Icon::Ready(texture) => Some(texture.id()),
+                Icon::Pending | Icon::Unavailable => None,
+            };
+        }
+
+        let (requests, _) = self.worker.get_or_insert_with(|| start_worker(ctx.clone()));
+        if requests.send(path.to_path_buf()).is_err() {
+            return None;
+        }
+        self.entries.insert(path.to_path_buf(), Entry { icon: Icon::Pending, last_used: self.tick });
+        if self.entries.len() > MAX_ICONS {
+            let oldest = self.entries.iter().min_by_key(|(_, entry)| entry.last_used).map(|(path, _)| path.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        None
+    }
+}
+
+fn placeholder(ui: &mut egui::Ui, size: egui::Vec2) -> egui::Response {
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+    ui.painter().rect_stroke(rect.shrink(2.0), 3.0, ui.visuals().widgets.noninteractive.bg_stroke);
+    response
+}
+
+fn start_worker(ctx: egui::Context) -> (Sender<PathBuf>, Receiver<Extracted>) {
+    let (requests, pending) = mpsc::channel::<PathBuf>();
+    let (done, results) = mpsc::channel();
+    let spawned = std::thread::Builder::new().name("process_icons".to_string()).spawn(move || {
+        for path in pending {
+            let image = extract_icon(&path);
+            if done.send((path, image)).is_err() {
+                break;
+            }
+            ctx.request_repaint();
+        }
+    });
+    if let Err(e) = spawned {
+        // Récepteur de requêtes libéré avec la fermeture : les icônes restent des espaces réservés
+        tracing::warn!("⚠️ Thread des icônes de processus impossible à lancer: {}", e);
+    }
+    (requests, results)
+}
+
+/// First icon of the executable as RGBA pixels
+#[cfg(target_os = "windows")]
+fn extract_icon(path: &Path) -> Option<egui::ColorImage> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::UI::Shell::ExtractIconExW;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{DestroyIcon, HICON};
+
+    let path_w: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut large: HICON = std::ptr::null_mut();
+    let mut small: HICON = std::ptr::null_mut();
+    let count = unsafe { ExtractIconExW(path_w.as_ptr(), 0, &mut large, &mut small, 1) };
+    // u32::MAX : fichier illisible ; 0 : exécutable sans icône
+    if count == 0 || count == u32::MAX {
+        return None;
+    }
+
+    // La grande icône (32 px) reste nette une fois réduite, même à 150 %
+    let icon = if large.is_null() { small } else { large };
+    let image = unsafe { icon_to_image(icon) };
+    for handle in [large, small] {
+        if !handle.is_null() {
+            unsafe { DestroyIcon(handle) };
+        }
+    }
+    image
+}
+
+#[cfg(not(target_os = "windows"))]
+fn extract_icon(_path: &Path) -> Option<egui::ColorImage> {
+    None
+}
+
+/// Pixels of `bitmap` as 32-bit top-down BGRA rows, with its size
+#[cfg(target_os = "windows")]
+unsafe fn bitmap_pixels(bitmap: windows_sys::Win32::Graphics::Gdi::HBITMAP) -> Option<(usize, usize, Vec<u8>)> {
+    use windows_sys::Win32::Graphics::Gdi::{
+        GetDC, GetDIBits, GetObjectW, ReleaseDC, BITMAP, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
+    };
+
+    let mut header: BITMAP = std::mem::zeroed();
+    if GetObjectW(bitmap, std::mem::size_of::<BITMAP>() as i32, &mut header as *mut BITMAP as *mut _) == 0 {
+        return None;
+    }
+    let (width, height) = (header.bmWidth, header.bmHeight.abs());
+    if width <= 0 || height <= 0 {
+        return None;
+    }
+
+    let mut info: BITMAPINFO = std::mem::zeroed();
+    info.bmiHeader.biSize = std::mem::size_of::<BITMAPINFOHEADER>() as u32;
+    info.bmiHeader.biWidth = width;
+    info.bmiHeader.biHeight = -height; // lignes de haut en bas
+    info.bmiHeader.biPlanes = 1;
+    info.bmiHeader.biBitCount = 32;
+    info.bmiHeader.biCompression = BI_RGB;
+
+    let mut pixels = vec![0u8; width as usize * height as usize * 4];
+    let dc = GetDC(std::ptr::null_mut());
+    let lines = GetDIBits(dc, bitmap, 0, height as u32, pixels.as_mut_ptr() as *mut _, &mut info, DIB_RGB_COLORS);
+    ReleaseDC(std::ptr::null_mut(), dc);
+    (lines != 0).then_some((width as usize, height as usize, pixels))
+}
+
+#[cfg(target_os = "windows")]
+unsafe fn icon_to_image(icon: windows_sys::Win32::UI::WindowsAndMessaging::HICON) -> Option<egui::ColorImage> {
+    use windows_sys::Win32::Graphics::Gdi::DeleteObject;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{GetIconInfo, ICONINFO};
+
+    let mut info: ICONINFO = std::mem::zeroed();
+    if GetIconInfo(icon, &mut info) == 0 {
+        return None;
+    }
+    // Icônes monochromes (hbmColor nul) : rares pour un exécutable, remplacées par l'espace réservé
+    let image = (!info.hbmColor.is_null())
+        .then(|| bitmap_pixels(info.hbmColor))
+        .flatten()
+        .map(|(width, height, mut pixels)| {
+            // Icône sans canal alpha : la transparence vient du masque (blanc = transparent)
+            if pixels.chunks_exact(4).all(|pixel| pixel[3] == 0) {
+                let mask = bitmap_pixels(info.hbmMask).filter(|(w, h, _)| (*w, *h) == (width, height));
+                for (index, pixel) in pixels.chunks_exact_mut(4).enumerate() {
+                    let transparent = mask.as_ref().is_some_and(|(_, _, mask)| mask[index * 4] != 0);
+                    pixel[3] = if transparent { 0 } else { 255 };
+                }
+            }
+            for pixel in pixels.chunks_exact_mut(4) {
+                pixel.swap(0, 2); // BGRA -> RGBA
+            }
+            egui::ColorImage::from_rgba_unmultiplied([width, height], &pixels)
+        });
+
+    if !info.hbmColor.is_null() {
+        DeleteObject(info.hbmColor);
+    }
+    if !info.hbmMask.is_null() {
+        DeleteObject(info.hbmMask);
+    }
+    image
+}
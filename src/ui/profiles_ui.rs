@@ -0,0 +1,297 @@
+use crate::optimization::windows_gaming::GamingFeature;
+use crate::profiles::{self, BoostProfile};
+use crate::services::gaming_services::GAMING_SERVICES;
+use crate::services::ServiceAction;
+use crate::theme::Theme;
+use crate::ui::app::CleanRamApp;
+use eframe::egui;
+
+/// One-line description of what a profile does
+pub fn profile_summary(profile: &BoostProfile) -> String {
+    let mut parts = Vec::new();
+    if profile.clean_ram {
+        parts.push("RAM".to_string());
+    }
+    if !profile.service_actions.is_empty() {
+        parts.push(format!("{} service(s)", profile.service_actions.len()));
+    }
+    if !profile.network_limits.is_empty() {
+        parts.push(format!("{} limite(s) réseau", profile.network_limits.len()));
+    }
+    if profile.power_plan.is_some() {
+        parts.push("plan d'alimentation".to_string());
+    }
+    if let Some(minutes) = profile.defender_pause_minutes {
+        parts.push(format!("Defender en pause {} min", minutes));
+    }
+    for (feature, enabled) in &profile.windows_gaming {
+        parts.push(format!("{} {}", feature.label(), if *enabled { "activé" } else { "désactivé" }));
+    }
+    if parts.is_empty() {
+        "Aucune action".to_string()
+    } else {
+        parts.join(" · ")
+    }
+}
+
+enum ProfileCommand {
+    Boost(BoostProfile),
+    Duplicate(BoostProfile),
+    Edit(usize),
+    Delete(usize),
+    /// `None` removes the default profile
+    SetDefault(Option<String>),
+}
+
+/// Built-in and user boost profiles, the editor of the selected one and the applied profile
+pub fn draw_profiles_section(app: &mut CleanRamApp, ui: &mut egui::Ui, theme: &Theme) {
+    ui.group(|ui| {
+        ui.label("🚀 Profils de boost");
+        ui.separator();
+
+        let is_busy = app.profile_promise.is_some();
+        if let Some(applied) = &app.applied_profile {
+            ui.colored_label(theme.accent, format!("Profil « {} » appliqué à {}", applied.profile_name, applied.applied_at.format("%H:%M")));
+            for error in &applied.errors {
+                ui.colored_label(theme.warning, format!("⚠️ {}", error));
+            }
+        }
+        if is_busy {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Opération en cours...");
+            });
+        }
+        if let Some(message) = &app.last_profile_message {
+            ui.label(message);
+        }
+
+        let can_boost = !is_busy && app.applied_profile.is_none();
+        let mut command = None;
+        egui::Grid::new("boost_profiles").striped(true).num_columns(3).show(ui, |ui| {
+            let built_in = profiles::built_in().into_iter().map(|profile| (None, profile));
+            let custom = app.boost_profiles.iter().cloned().enumerate().map(|(index, profile)| (Some(index), profile));
+            for (index, profile) in built_in.chain(custom) {
+                let is_default = app.default_profile.as_deref() == Some(profile.name.as_str());
+                let star = if is_default { "⭐ " } else { "" };
+                match index {
+                    None => ui.label(egui::RichText::new(format!("{}{} (intégré)", star, profile.name)).strong()),
+                    Some(_) => ui.label(egui::RichText::new(format!("{}{}", star, profile.name)).strong()),
+                };
+                ui.weak(profile_summary(&profile));
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(can_boost, egui::Button::new("🚀 Boost"))
+                        .on_disabled_hover_text("Rétablissez d'abord le profil appliqué")
+                        .clicked()
+                    {
+                        command = Some(ProfileCommand::Boost(profile.clone()));
+                    }
+                    if ui.button("📄 Dupliquer").clicked() {
+                        command = Some(ProfileCommand::Duplicate(profile.clone()));
+                    }
+                    if ui
+                        .selectable_label(is_default, "⭐")
+                        .on_hover_text("Profil par défaut : bouton Boost de la barre d'onglets")
+                        .clicked()
+                    {
+                        command = Some(ProfileCommand::SetDefault((!is_default).then(|| profile.name.clone())));
+                    }
+                    if let Some(index) = index {
+                        if ui.selectable_label(app.editing_profile == Some(index), "✏️ Modifier").clicked() {
+                            command = Some(ProfileCommand::Edit(index));
+                        }
+                        if ui.button("🗑️").on_hover_text("Supprimer le profil").clicked() {
+                            command = Some(ProfileCommand::Delete(index));
+                        }
+                    }
+                });
+                ui.end_row();
+            }
+        });
+
+        match command {
+            Some(ProfileCommand::Boost(profile)) => app.request_profile(profile),
+            Some(ProfileCommand::Duplicate(mut profile)) => {
+                profile.name = format!("{} (copie)", profile.name);
+                app.boost_profiles.push(profile);
+                app.editing_profile = Some(app.boost_profiles.len() - 1);
+                app.save_settings();
+            }
+            Some(ProfileCommand::Edit(index)) => {
+                let already_open = app.editing_profile == Some(index);
+                app.editing_profile = if already_open { None } else { Some(index) };
+                if already_open {
+                    app.save_settings();
+                }
+            }
+            Some(ProfileCommand::SetDefault(name)) => {
+                app.default_profile = name;
+                app.save_settings();
+            }
+            Some(ProfileCommand::Delete(index)) => {
+                app.boost_profiles.remove(index);
+                app.editing_profile = None;
+                app.save_settings();
+            }
+            None => {}
+        }
+
+        let Some(index) = app.editing_profile.filter(|index| *index < app.boost_profiles.len()) else {
+            return;
+        };
+        if app.power_plans.is_none() {
+            app.refresh_power_plans();
+        }
+        let power_plans = app.power_plans.clone().unwrap_or_default();
+        ui.separator();
+        let done = draw_profile_editor(ui, &mut app.boost_profiles[index], &power_plans);
+        if done {
+            app.editing_profile = None;
+            app.save_settings();
+        }
+    });
+}
+
+/// Editor of a user profile; true when "Terminer" is clicked
+fn draw_profile_editor(ui: &mut egui::Ui, profile: &mut BoostProfile, power_plans: &[crate::power::PowerPlan]) -> bool {
+    ui.horizontal(|ui| {
+        ui.label("Nom :");
+        ui.text_edit_singleline(&mut profile.name);
+    });
+
+    ui.checkbox(&mut profile.clean_ram, "🧠 Nettoyer la RAM");
+    if profile.clean_ram {
+        ui.indent("profile_ram_exclusions", |ui| {
+            ui.label("Processus épargnés (le jeu) :");
+            let mut removed = None;
+            for (i, name) in profile.clean_ram_options.excluded_processes.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(name).hint_text("jeu.exe").desired_width(180.0));
+                    if ui.small_button("✖").clicked() {
+                        removed = Some(i);
+                    }
+                });
+            }
+            if let Some(i) = removed {
+                profile.clean_ram_options.excluded_processes.remove(i);
+            }
+            if ui.small_button("➕ Ajouter un processus").clicked() {
+                profile.clean_ram_options.excluded_processes.push(String::new());
+            }
+            ui.horizontal(|ui| {
+                ui.label("Ignorer les processus sous");
+                ui.add(egui::DragValue::new(&mut profile.clean_ram_options.min_working_set_mb).clamp_range(0..=512).suffix(" MB"));
+            });
+        });
+    }
+
+    ui.label("🛡️ Services :");
+    egui::Grid::new("profile_services").num_columns(2).show(ui, |ui| {
+        for service in GAMING_SERVICES {
+            let position = profile.service_actions.iter().position(|(name, _)| name == service.name);
+            let mut action = position.map(|i| profile.service_actions[i].1);
+            ui.label(service.display_name);
+            let label = |action: Option<ServiceAction>| match action {
+                None => "Inchangé",
+                Some(ServiceAction::Stop) => "Arrêter",
+                Some(ServiceAction::Disable) => "Désactiver",
+                Some(ServiceAction::Start) => "Démarrer",
+                Some(ServiceAction::Enable) => "Activer",
+            };
+            egui::ComboBox::from_id_source(("profile_service", service.name))
+                .selected_text(label(action))
+                .show_ui(ui, |ui| {
+                    for choice in [None, Some(ServiceAction::Stop), Some(ServiceAction::Disable)] {
+                        ui.selectable_value(&mut action, choice, label(choice));
+                    }
+                });
+            match (position, action) {
+                (Some(i), Some(action)) => profile.service_actions[i].1 = action,
+                (Some(i), None) => {
+                    profile.service_actions.remove(i);
+                }
+                (None, Some(action)) => profile.service_actions.push((service.name.to_string(), action)),
+                (None, None) => {}
+            }
+            ui.end_row();
+        }
+    });
+
+    ui.label("📡 Limites réseau par exécutable :");
+    let mut removed = None;
+    for (i, (exe_name, limit_kbps)) in profile.network_limits.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(exe_name).hint_text("OneDrive.exe").desired_width(180.0));
+            ui.add(egui::DragValue::new(limit_kbps).clamp_range(1..=1_000_000).suffix(" KB/s"));
+            if ui.small_button("✖").clicked() {
+                removed = Some(i);
+            }
+        });
+    }
+    if let Some(i) = removed {
+        profile.network_limits.remove(i);
+    }
+    if ui.small_button("➕ Ajouter une limite").clicked() {
+        profile.network_limits.push((String::new(), 512));
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("🔋 Plan d'alimentation :");
+        let selected = match &profile.power_plan {
+            None => "Inchangé".to_string(),
+            Some(guid) => power_plans
+                .iter()
+                .find(|plan| &plan.guid == guid)
+                .map(|plan| plan.name.clone())
+                .unwrap_or_else(|| guid.clone()),
+        };
+        egui::ComboBox::from_id_source("profile_power_plan")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut profile.power_plan, None, "Inchangé");
+                for plan in power_plans {
+                    ui.selectable_value(&mut profile.power_plan, Some(plan.guid.clone()), &plan.name);
+                }
+            });
+    });
+
+    ui.horizontal(|ui| {
+        let mut pause = profile.defender_pause_minutes.is_some();
+        if ui.checkbox(&mut pause, "⏸️ Mettre Defender en pause").changed() {
+            profile.defender_pause_minutes = pause.then_some(60);
+        }
+        if let Some(minutes) = profile.defender_pause_minutes.as_mut() {
+            ui.add(egui::DragValue::new(minutes).clamp_range(5..=480).suffix(" min"));
+        }
+    });
+
+    for feature in GamingFeature::ALL {
+        ui.horizontal(|ui| {
+            ui.label(format!("🎮 {} :", feature.label()));
+            let current = profile.windows_gaming.iter().find(|(f, _)| *f == feature).map(|(_, enabled)| *enabled);
+            let mut selected = current;
+            let text = |state: Option<bool>| match state {
+                None => "Inchangé",
+                Some(true) => "Activer",
+                Some(false) => "Désactiver",
+            };
+            egui::ComboBox::from_id_source(("profile_windows_gaming", feature))
+                .selected_text(text(selected))
+                .show_ui(ui, |ui| {
+                    for state in [None, Some(true), Some(false)] {
+                        ui.selectable_value(&mut selected, state, text(state));
+                    }
+                });
+            if selected != current {
+                profile.windows_gaming.retain(|(f, _)| *f != feature);
+                if let Some(enabled) = selected {
+                    profile.windows_gaming.push((feature, enabled));
+                }
+            }
+        });
+    }
+
+    ui.add_space(5.0);
+    ui.button("✔ Terminer").clicked()
+}
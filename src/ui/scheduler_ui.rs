@@ -1,7 +1,142 @@
 // UI for the task scheduler
+use crate::scheduler::{ScheduleRule, TaskType, WEEKDAY_NAMES};
 use crate::ui::app::CleanRamApp;
 use eframe::egui;
 
-pub fn draw_scheduler_tab(_app: &mut CleanRamApp, ui: &mut egui::Ui) {
-    ui.label("Scheduler UI - Coming soon!");
-} 
\ No newline at end of file
+pub fn draw_scheduler_tab(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    let theme = app.theme.clone();
+    if app.mirrored_tasks.is_none() {
+        app.refresh_mirrored_tasks();
+    }
+
+    ui.heading("⏰ Planificateur");
+    ui.label("Les tâches s'exécutent pendant que GameBooster est ouvert, ou via le Planificateur de tâches Windows quand « Exécuter même si GameBooster est fermé » est coché.");
+    ui.separator();
+
+    draw_new_task_form(app, ui);
+    ui.separator();
+
+    if app.scheduler_config.tasks.is_empty() {
+        ui.label("Aucune tâche planifiée");
+    }
+
+    let mirrored = app.mirrored_tasks.clone().unwrap_or_default();
+    let running = app.scheduled_task_promise.as_ref().map(|(id, _)| id.clone());
+    let mut toggled_enabled = None;
+    let mut toggled_mirror = None;
+    let mut removed = None;
+    for task in &app.scheduler_config.tasks {
+        ui.horizontal(|ui| {
+            let mut enabled = task.enabled;
+            if ui.checkbox(&mut enabled, task.task_type.label()).changed() {
+                toggled_enabled = Some((task.id.clone(), enabled));
+            }
+            ui.label(task.schedule.describe());
+
+            if let Some(next_run) = task.next_run.filter(|_| task.enabled) {
+                ui.weak(format!("prochaine : {}", next_run.format("%d/%m %H:%M")));
+            }
+            if let Some(last_run) = task.last_run {
+                ui.weak(format!("dernière : {}", last_run.format("%d/%m %H:%M")));
+            }
+            if running.as_deref() == Some(task.id.as_str()) {
+                ui.spinner();
+            }
+
+            let has_cli = task.task_type.cli_args().is_some();
+            let mut run_when_closed = task.run_when_closed;
+            if ui
+                .add_enabled(has_cli && cfg!(target_os = "windows"), egui::Checkbox::new(&mut run_when_closed, "Exécuter même si GameBooster est fermé"))
+                .on_disabled_hover_text("Type de tâche sans équivalent en ligne de commande, ou système autre que Windows")
+                .changed()
+            {
+                toggled_mirror = Some((task.id.clone(), run_when_closed));
+            }
+            if task.run_when_closed {
+                if mirrored.contains(&task.id) {
+                    ui.colored_label(theme.success, "🪟 Copiée dans Windows");
+                } else {
+                    ui.colored_label(theme.warning, "⚠️ Absente du Planificateur Windows")
+                        .on_hover_text("Décochez puis recochez l'option pour la recréer");
+                }
+            }
+
+            if ui.small_button("🗑").on_hover_text("Supprimer la tâche").clicked() {
+                removed = Some(task.id.clone());
+            }
+        });
+    }
+
+    if let Some((id, enabled)) = toggled_enabled {
+        app.set_scheduled_task_enabled(&id, enabled);
+    }
+    if let Some((id, mirrored)) = toggled_mirror {
+        app.set_scheduled_task_mirrored(&id, mirrored);
+    }
+    if let Some(id) = removed {
+        app.remove_scheduled_task(&id);
+    }
+
+    if let Some(error) = &app.scheduler_error {
+        ui.colored_label(theme.error, format!("❌ {}", error));
+    }
+}
+
+/// Task type and schedule of a new task
+fn draw_new_task_form(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+        ui.label("➕ Nouvelle tâche :");
+        egui::ComboBox::from_id_source("new_task_type")
+            .selected_text(app.new_task_type.label())
+            .show_ui(ui, |ui| {
+                for task_type in TaskType::ALL {
+                    ui.selectable_value(&mut app.new_task_type, task_type, task_type.label());
+                }
+            });
+
+        let rule_kind = match app.new_task_rule {
+            ScheduleRule::OnStartup => "Au démarrage",
+            ScheduleRule::Hourly(_) => "Toutes les X heures",
+            ScheduleRule::Daily(_) => "Chaque jour",
+            ScheduleRule::Weekly(..) => "Chaque semaine",
+        };
+        egui::ComboBox::from_id_source("new_task_rule")
+            .selected_text(rule_kind)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut app.new_task_rule, ScheduleRule::OnStartup, "Au démarrage");
+                if ui.selectable_label(matches!(app.new_task_rule, ScheduleRule::Hourly(_)), "Toutes les X heures").clicked() {
+                    app.new_task_rule = ScheduleRule::Hourly(4);
+                }
+                if ui.selectable_label(matches!(app.new_task_rule, ScheduleRule::Daily(_)), "Chaque jour").clicked() {
+                    app.new_task_rule = ScheduleRule::Daily(3);
+                }
+                if ui.selectable_label(matches!(app.new_task_rule, ScheduleRule::Weekly(..)), "Chaque semaine").clicked() {
+                    app.new_task_rule = ScheduleRule::Weekly(0, 3);
+                }
+            });
+
+        match &mut app.new_task_rule {
+            ScheduleRule::OnStartup => {}
+            ScheduleRule::Hourly(hours) => {
+                ui.add(egui::DragValue::new(hours).clamp_range(1..=24).suffix(" h"));
+            }
+            ScheduleRule::Daily(hour) => {
+                ui.add(egui::DragValue::new(hour).clamp_range(0..=23).suffix(":00"));
+            }
+            ScheduleRule::Weekly(day, hour) => {
+                egui::ComboBox::from_id_source("new_task_day")
+                    .selected_text(WEEKDAY_NAMES[(*day as usize).min(6)])
+                    .show_ui(ui, |ui| {
+                        for (index, name) in WEEKDAY_NAMES.iter().enumerate() {
+                            ui.selectable_value(day, index as u32, *name);
+                        }
+                    });
+                ui.add(egui::DragValue::new(hour).clamp_range(0..=23).suffix(":00"));
+            }
+        }
+
+        if ui.button("Ajouter").clicked() {
+            app.add_scheduled_task(app.new_task_type, app.new_task_rule.clone());
+        }
+    });
+}
@@ -1,67 +1,123 @@
 use eframe::egui;
+use std::path::PathBuf;
+
+use crate::services::gaming_services::GAMING_SERVICES;
+use crate::services::tamper_guidance::{open_tamper_settings, GuardedOperation, TamperPhase, POLL_INTERVAL, POLL_TIMEOUT};
+use crate::services::winapi_defender::{ActionOutcome, DefenderAction, DefenderManager, StepResult};
+use crate::services::{ServiceAction, ServiceMode, ServiceOperation, ServicesOptimizationResults};
+use crate::ui::app::PendingAction;
+use crate::ui::util::ELEVATION_REQUIRED_HINT;
+use crate::utils::game_libraries;
 
 pub fn services_ui(app: &mut crate::CleanRamApp, ui: &mut egui::Ui) {
+    let theme = app.theme.clone();
     ui.heading("🛡️ DÉSACTIVATION WINDOWS DEFENDER - IMMEDIAT");
     ui.separator();
+    if !app.os_capabilities.defender_present {
+        ui.colored_label(theme.warning, "⚠️ Windows Defender semble absent (remplacé par un autre antivirus)");
+    }
 
-    // Test immédiat du statut - STOCKE le résultat dans l'app
-    if ui.button("🔍 VÉRIFIER STATUT DEFENDER").clicked() {
-        match crate::services::defender::DefenderService::get_status() {
-            Ok(status) => {
-                app.last_defender_status = Some(Ok(status));
-            }
-            Err(e) => {
-                app.last_defender_status = Some(Err(e));
-            }
+    // Vérification en arrière-plan : au premier affichage de l'onglet puis à la demande
+    let first_open = app.last_defender_status.is_none() && app.defender_status_promise.is_none();
+    let is_checking = app.defender_status_promise.is_some();
+    ui.horizontal(|ui| {
+        if ui.add_enabled(!is_checking, egui::Button::new("🔍 VÉRIFIER STATUT DEFENDER")).clicked() || first_open {
+            spawn_defender_status_check(app);
         }
-    }
+        if is_checking {
+            ui.spinner();
+            ui.label("Vérification en cours...");
+        }
+    });
+    poll_defender_status(app);
 
     // Affiche le statut stocké si disponible
+    let mut tamper_protection = false;
     if let Some(ref status_result) = app.last_defender_status {
         match status_result {
             Ok(status) => {
+                tamper_protection = status.tamper_protection;
                 if status.real_time_protection {
-                    ui.colored_label(egui::Color32::RED, "❌ DEFENDER EST ACTIF");
+                    ui.colored_label(theme.error, "❌ DEFENDER EST ACTIF");
                 } else {
-                    ui.colored_label(egui::Color32::GREEN, "✅ DEFENDER EST DÉSACTIVÉ");
+                    ui.colored_label(theme.success, "✅ DEFENDER EST DÉSACTIVÉ");
                 }
+
+                egui::Grid::new("defender_components")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        component_row(ui, &theme, "Protection temps réel", status.real_time_protection);
+                        component_row(ui, &theme, "Protection cloud", status.cloud_protection);
+                        component_row(ui, &theme, "Envoi automatique d'échantillons", status.automatic_sample_submission);
+                        component_row(ui, &theme, "Protection contre les falsifications", status.tamper_protection);
+                    });
             }
             Err(e) => {
-                ui.colored_label(egui::Color32::YELLOW, format!("⚠️ Erreur: {}", e));
+                ui.colored_label(theme.warning, format!("⚠️ Erreur: {}", e));
             }
         }
     }
 
     ui.separator();
 
+    let can_control = app.can_run(crate::services::requires_elevation()) && app.defender_action_promise.is_none();
+    if app.defender_action_promise.is_some() {
+        ui.ctx().request_repaint_after(std::time::Duration::from_millis(250));
+    }
+    draw_defender_progress(app, ui, &theme);
+
     // BOUTON DÉSACTIVATION IMMÉDIATE
-    if ui.button("❌ DÉSACTIVER DEFENDER MAINTENANT").clicked() {
-        match crate::services::defender::DefenderService::disable_immediately() {
-            Ok(result) => {
-                ui.colored_label(egui::Color32::GREEN, "✅ DÉSACTIVATION LANCÉE !");
-                for res in result.last_operation_results {
-                    ui.label(res);
-                }
-            }
-            Err(e) => {
-                ui.colored_label(egui::Color32::RED, format!("❌ ERREUR: {}", e));
-            }
+    // Avec la protection contre les falsifications, les écritures registre échouent de toute
+    // façon : le clic ouvre le guide pour la désactiver, puis l'opération reprend
+    let tamper_hint = "La protection contre les falsifications est active : un guide vous aide à la désactiver dans Sécurité Windows";
+    let mut disable_button = ui
+        .add_enabled(can_control, egui::Button::new("❌ DÉSACTIVER DEFENDER MAINTENANT"))
+        .on_disabled_hover_text(ELEVATION_REQUIRED_HINT);
+    if tamper_protection {
+        disable_button = disable_button.on_hover_text(tamper_hint);
+    }
+    if disable_button.clicked() {
+        app.request_defender_disable(GuardedOperation::Disable);
+    }
+
+    // PAUSE TEMPORAIRE
+    ui.horizontal(|ui| {
+        let pause_clicked = ui
+            .add_enabled(can_control, egui::Button::new("⏸️ DÉSACTIVER PENDANT"))
+            .on_disabled_hover_text(ELEVATION_REQUIRED_HINT)
+            .on_hover_text(if tamper_protection {
+                tamper_hint
+            } else {
+                "Defender est réactivé automatiquement à la fin du délai, même si l'application est fermée"
+            })
+            .clicked();
+        ui.add(egui::DragValue::new(&mut app.defender_pause_minutes).clamp_range(5..=480).suffix(" min"));
+
+        if pause_clicked {
+            app.request_defender_disable(GuardedOperation::Pause(app.defender_pause_minutes));
         }
+    });
+
+    if let Some(deadline) = app.defender_reenable_at {
+        let remaining = (deadline - chrono::Local::now()).num_minutes().max(0) + 1;
+        ui.horizontal(|ui| {
+            ui.colored_label(theme.warning, format!("⏳ Réactivation dans {} min ({})", remaining, deadline.format("%H:%M")));
+            if ui.add_enabled(can_control, egui::Button::new("▶️ Réactiver maintenant"))
+                .on_disabled_hover_text(ELEVATION_REQUIRED_HINT)
+                .clicked()
+            {
+                app.spawn_defender_action(true);
+            }
+        });
     }
 
     // BOUTON RÉACTIVATION
-    if ui.button("✅ RÉACTIVER DEFENDER").clicked() {
-        match crate::services::defender::DefenderService::enable_immediately() {
-            Ok(result) => {
-                ui.colored_label(egui::Color32::GREEN, "✅ RÉACTIVATION LANCÉE !");
-                for res in result.last_operation_results {
-                    ui.label(res);
-                }
-            }
-            Err(e) => {
-                ui.colored_label(egui::Color32::RED, format!("❌ ERREUR: {}", e));
-            }
-        }
+    if ui.add_enabled(can_control, egui::Button::new("✅ RÉACTIVER DEFENDER"))
+        .on_disabled_hover_text(ELEVATION_REQUIRED_HINT)
+        .clicked()
+    {
+        app.spawn_defender_action(true);
     }
 
     ui.separator();
@@ -70,47 +126,47 @@ pub fn services_ui(app: &mut crate::CleanRamApp, ui: &mut egui::Ui) {
     ui.separator();
 
     // === DEFENDER CONTROL PANEL ===
-    egui::CollapsingHeader::new("🛡️ Windows Defender - Contrôle Immédiat")
-        .default_open(true)
-        .show(ui, |ui| {
-            ui.label("⚡ Désactivation/Activation IMMÉDIATE sans redémarrage");
-            ui.separator();
+    if let Some(Ok(defender_status)) = &app.last_defender_status {
+        egui::CollapsingHeader::new("🛡️ Windows Defender - Contrôle Immédiat")
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.label("⚡ Désactivation/Activation IMMÉDIATE sans redémarrage");
+                ui.separator();
 
-            // Status check
-            let defender_status = crate::services::defender::DefenderService::get_status().unwrap_or_default();
-            
-            // Display current status
-            ui.horizontal(|ui| {
-                let (color, icon) = if defender_status.real_time_protection {
-                    (egui::Color32::from_rgb(46, 125, 50), "🛡️ ACTIF")
-                } else {
-                    (egui::Color32::from_rgb(198, 40, 40), "❌ DÉSACTIVÉ")
-                };
-                
-                ui.colored_label(color, format!("Statut: {}", icon));
-            });
+                ui.label(&defender_status.status_text);
 
-            ui.separator();
+                ui.separator();
 
-            // Detailed status
-            ui.label("📊 Détails de protection:");
-            egui::Grid::new("defender_details")
-                .num_columns(2)
-                .show(ui, |ui| {
-                    for detail in &defender_status.last_operation_results {
-                        if detail.contains("✅") {
-                            ui.colored_label(egui::Color32::from_rgb(46, 125, 50), detail);
-                        } else if detail.contains("❌") {
-                            ui.colored_label(egui::Color32::from_rgb(198, 40, 40), detail);
-                        } else if detail.contains("🔒") || detail.contains("🔓") {
-                            ui.colored_label(egui::Color32::from_rgb(63, 81, 181), detail);
-                        } else {
-                            ui.label(detail);
+                // Detailed status
+                ui.label("📊 Détails de protection:");
+                egui::Grid::new("defender_details")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        for detail in &defender_status.last_operation_results {
+                            if detail.contains("✅") {
+                                ui.colored_label(theme.success, detail);
+                            } else if detail.contains("❌") {
+                                ui.colored_label(theme.error, detail);
+                            } else if detail.contains("🔒") || detail.contains("🔓") {
+                                ui.colored_label(theme.accent, detail);
+                            } else {
+                                ui.label(detail);
+                            }
+                            ui.end_row();
                         }
-                        ui.end_row();
-                    }
-                });
-        });
+                    });
+            });
+
+        ui.separator();
+    }
+
+    // === DEFENDER EXCLUSIONS ===
+    draw_exclusions_section(app, ui, &theme);
+
+    ui.separator();
+
+    // === GAMING SERVICES ===
+    draw_gaming_services_section(app, ui, &theme);
 
     ui.separator();
 
@@ -118,14 +174,458 @@ pub fn services_ui(app: &mut crate::CleanRamApp, ui: &mut egui::Ui) {
     egui::CollapsingHeader::new("ℹ️ Informations Importantes")
         .default_open(false)
         .show(ui, |ui| {
-            ui.colored_label(egui::Color32::YELLOW, "⚠️ ATTENTION :");
+            ui.colored_label(theme.warning, "⚠️ ATTENTION :");
             ui.label("• Les modifications prennent effet IMMÉDIATEMENT");
             ui.label("• Aucun redémarrage nécessaire");
             ui.label("• Privilèges administrateur requis");
             ui.label("• La Protection contre les Falsifications peut bloquer certaines opérations");
             ui.separator();
-            ui.colored_label(egui::Color32::from_rgb(33, 150, 243), "🎮 Pour le Gaming :");
+            ui.colored_label(theme.accent, "🎮 Pour le Gaming :");
             ui.label("• Désactivation temporaire recommandée");
             ui.label("• Réactivation après session de jeu");
         });
-}
\ No newline at end of file
+}
+
+fn spawn_defender_status_check(app: &mut crate::CleanRamApp) {
+    app.defender_status_promise = Some(poll_promise::Promise::spawn_thread("defender_status", || {
+        crate::services::defender::DefenderService::get_status()
+    }));
+}
+
+fn poll_defender_status(app: &mut crate::CleanRamApp) {
+    if let Some(promise) = app.defender_status_promise.take() {
+        match promise.try_take() {
+            Ok(result) => app.last_defender_status = Some(result),
+            Err(promise) => app.defender_status_promise = Some(promise),
+        }
+    }
+}
+
+/// Select → stop → results → restart of the background services listed in `GAMING_SERVICES`
+fn draw_gaming_services_section(app: &mut crate::CleanRamApp, ui: &mut egui::Ui, theme: &crate::theme::Theme) {
+    app.poll_services_task();
+    let can_control = app.can_run(crate::services::requires_elevation());
+    let is_running = app.services_promise.is_some();
+
+    egui::CollapsingHeader::new("🎮 Services Gaming")
+        .default_open(true)
+        .show(ui, |ui| {
+            ui.label("Services d'arrière-plan arrêtés pendant la session de jeu (redémarrables ensuite) :");
+
+            ui.horizontal(|ui| {
+                if ui.button("☑ Tout sélectionner").clicked() {
+                    for service in GAMING_SERVICES {
+                        app.selected_services.insert(service.name.to_string(), true);
+                    }
+                }
+                if ui.button("☐ Tout désélectionner").clicked() {
+                    for service in GAMING_SERVICES {
+                        app.selected_services.insert(service.name.to_string(), false);
+                    }
+                }
+                if ui.button("🔄 Actualiser les statuts").clicked() {
+                    app.invalidate_service_statuses();
+                    app.service_dependencies_cache.clear();
+                }
+            });
+            if ui
+                .checkbox(&mut app.manage_service_dependencies, "Gérer les dépendances automatiquement")
+                .on_hover_text("Arrête d'abord les services en cours d'exécution qui dépendent d'un service sélectionné ; sinon ce service est ignoré")
+                .changed()
+            {
+                app.save_settings();
+            }
+
+            egui::Grid::new("gaming_services").num_columns(5).striped(true).show(ui, |ui| {
+                for service in GAMING_SERVICES {
+                    let mut selected = app.is_service_selected(service);
+                    if ui.checkbox(&mut selected, service.display_name).on_hover_text(service.description).changed() {
+                        app.selected_services.insert(service.name.to_string(), selected);
+                    }
+                    let status = app.cached_service_status(service.name);
+                    let color = match status.as_str() {
+                        "Running" => theme.success,
+                        "Stopped" => theme.error,
+                        "Starting" | "Stopping" => theme.warning,
+                        _ => ui.visuals().weak_text_color(),
+                    };
+                    ui.colored_label(color, &status);
+                    let mut mode = app.service_mode(service.name);
+                    egui::ComboBox::from_id_source(("service_mode", service.name))
+                        .selected_text(mode.label())
+                        .show_ui(ui, |ui| {
+                            for choice in [ServiceMode::Session, ServiceMode::Durable] {
+                                ui.selectable_value(&mut mode, choice, choice.label());
+                            }
+                        });
+                    if mode != app.service_mode(service.name) {
+                        app.service_modes.insert(service.name.to_string(), mode);
+                        app.save_settings();
+                    }
+                    ui.label(service.description);
+                    egui::CollapsingHeader::new("🔗 Dépendances")
+                        .id_source(("service_dependencies", service.name))
+                        .default_open(false)
+                        .show(ui, |ui| {
+                            let (dependents, dependencies) = app.cached_service_dependencies(service.name);
+                            ui.label(format!("requis par : {}", dependency_list(dependents)));
+                            ui.label(format!("dépend de : {}", dependency_list(dependencies)));
+                        });
+                    ui.end_row();
+                }
+            });
+            app.poll_service_statuses();
+            if app.services_status_promise.is_some() {
+                ui.ctx().request_repaint_after(std::time::Duration::from_millis(250));
+            }
+
+            let selected_count = GAMING_SERVICES.iter().filter(|s| app.is_service_selected(s)).count();
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(can_control && !is_running && selected_count > 0, egui::Button::new(format!("⏹️ Arrêter {} service(s)", selected_count)))
+                    .on_disabled_hover_text(ELEVATION_REQUIRED_HINT)
+                    .clicked()
+                {
+                    app.request_action(PendingAction::OptimizeServices);
+                }
+                if is_running {
+                    ui.spinner();
+                    ui.label(if app.services_restoring { "Redémarrage en cours..." } else { "Arrêt en cours..." });
+                    ui.ctx().request_repaint_after(std::time::Duration::from_millis(250));
+                }
+            });
+
+            if let Some(results) = app.last_services_results.clone() {
+                ui.separator();
+                draw_services_results(ui, theme, "📋 Dernière optimisation", &results);
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            can_control && !is_running && !results.simulated && results.services_optimized > 0,
+                            egui::Button::new("▶️ Restaurer les services"),
+                        )
+                        .on_disabled_hover_text(if results.simulated { "Simulation : aucun service n'a été arrêté" } else { ELEVATION_REQUIRED_HINT })
+                        .clicked()
+                    {
+                        app.restore_optimized_services();
+                    }
+                    if ui.button("✖ Effacer").clicked() {
+                        app.last_services_results = None;
+                    }
+                });
+            }
+            if let Some(results) = app.last_services_restore.clone() {
+                ui.separator();
+                draw_services_results(ui, theme, "📋 Dernière restauration", &results);
+            }
+        });
+}
+
+/// Guide to turn Tamper Protection off; closing it abandons the operation and stops the polling
+pub fn draw_tamper_guidance(app: &mut crate::CleanRamApp, ctx: &egui::Context) {
+    let Some(guidance) = &app.tamper_guidance else {
+        return;
+    };
+    let theme = app.theme.clone();
+    let phase = guidance.phase().clone();
+    let operation = match guidance.operation {
+        GuardedOperation::Disable => "La désactivation de Defender".to_string(),
+        GuardedOperation::Pause(minutes) => format!("La pause de Defender ({} min)", minutes),
+    };
+    let mut open = true;
+    let mut close = false;
+    let mut open_settings = false;
+    let mut retry = false;
+    egui::Window::new("🔒 Protection contre les falsifications")
+        .open(&mut open)
+        .collapsible(false)
+        .default_width(460.0)
+        .show(ctx, |ui| {
+            ui.label(format!(
+                "{} a été bloquée : la protection contre les falsifications de Windows refuse toute modification des réglages de Defender.",
+                operation
+            ));
+            ui.label("Seul vous pouvez la désactiver :");
+            ui.label("1. Ouvrez Sécurité Windows avec le bouton ci-dessous");
+            ui.label("2. Désactivez « Protection contre les falsifications »");
+            ui.label("3. Revenez ici et cliquez sur « Réessayer » : l'opération reprend dès que le changement est détecté");
+            ui.separator();
+
+            match &phase {
+                TamperPhase::Explaining => {}
+                TamperPhase::Waiting { started } => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        let remaining = POLL_TIMEOUT.saturating_sub(started.elapsed()).as_secs();
+                        ui.label(format!("Vérification toutes les {} s... ({} s restantes)", POLL_INTERVAL.as_secs(), remaining));
+                    });
+                }
+                TamperPhase::Resumed => {
+                    ui.colored_label(theme.success, "✅ Protection désactivée, reprise de l'opération");
+                }
+                TamperPhase::TimedOut => {
+                    ui.colored_label(theme.warning, "⏳ La protection est toujours active. Vérifiez le réglage puis réessayez.");
+                }
+            }
+
+            ui.horizontal(|ui| {
+                if ui.button("🛡️ Ouvrir Sécurité Windows").clicked() {
+                    open_settings = true;
+                }
+                let waiting = matches!(phase, TamperPhase::Waiting { .. });
+                if ui.add_enabled(!waiting, egui::Button::new("J'ai désactivé la protection — réessayer")).clicked() {
+                    retry = true;
+                }
+                if ui.button("Abandonner").clicked() {
+                    close = true;
+                }
+            });
+        });
+
+    if open_settings {
+        if let Err(e) = open_tamper_settings() {
+            tracing::error!("❌ Ouverture de Sécurité Windows impossible: {}", e);
+            app.notifications.error_chain("Defender", "Ouverture de Sécurité Windows impossible", &e);
+        }
+    }
+    if retry {
+        if let Some(guidance) = app.tamper_guidance.as_mut() {
+            guidance.retry();
+        }
+    }
+    if !open || close {
+        tracing::info!("🔒 Guide de la protection contre les falsifications abandonné");
+        app.tamper_guidance = None;
+    }
+}
+
+/// Checklist of the running or last Defender action, filled as the steps complete
+fn draw_defender_progress(app: &mut crate::CleanRamApp, ui: &mut egui::Ui, theme: &crate::theme::Theme) {
+    let running = app.defender_action_promise.is_some();
+    let Some(progress) = &app.defender_progress else {
+        return;
+    };
+    let mut close = false;
+    ui.group(|ui| {
+        ui.horizontal(|ui| {
+            let title = match progress.action {
+                DefenderAction::Disable => "Désactivation de Defender",
+                DefenderAction::Enable => "Réactivation de Defender",
+            };
+            if running {
+                ui.spinner();
+            }
+            ui.strong(title);
+            if !running && ui.small_button("✖").on_hover_text("Masquer").clicked() {
+                close = true;
+            }
+        });
+        ui.add(egui::ProgressBar::new(progress.fraction()).show_percentage());
+
+        for (index, step) in progress.steps.iter().enumerate() {
+            match progress.result_of(index) {
+                Some(StepResult { outcome: Ok(()), .. }) => {
+                    ui.colored_label(theme.success, format!("✔ {}", step.name));
+                }
+                Some(StepResult { outcome: Err(e), .. }) => {
+                    ui.colored_label(theme.error, format!("✖ {}", step.name));
+                    ui.weak(format!("    {}", e));
+                }
+                None if running && index == progress.results.len() => {
+                    ui.label(format!("⏳ {}", step.name));
+                }
+                None => {
+                    ui.weak(format!("○ {}", step.name));
+                }
+            }
+        }
+
+        if let Some(summary) = &progress.summary {
+            let color = match summary.outcome() {
+                ActionOutcome::Success => theme.success,
+                ActionOutcome::Partial => theme.warning,
+                ActionOutcome::Failed => theme.error,
+            };
+            ui.colored_label(color, summary.message());
+        }
+    });
+    if close {
+        app.defender_progress = None;
+    }
+}
+
+fn draw_services_results(ui: &mut egui::Ui, theme: &crate::theme::Theme, title: &str, results: &ServicesOptimizationResults) {
+    ui.label(title);
+    if results.simulated {
+        ui.colored_label(theme.warning, "🧪 SIMULATION — rien n'a été modifié");
+    }
+    if results.operations.is_empty() {
+        ui.label("Aucun service à traiter (déjà arrêtés ?)");
+    }
+    for operation in &results.operations {
+        let description = operation_description(operation);
+        if operation.success {
+            ui.colored_label(theme.success, format!("✅ {} ({})", operation.display_name, description));
+        } else {
+            ui.colored_label(
+                theme.error,
+                format!(
+                    "❌ {} ({}) : {}",
+                    operation.display_name,
+                    description,
+                    operation.error_message.as_deref().unwrap_or("erreur inconnue")
+                ),
+            );
+        }
+    }
+}
+
+/// "Fax, PrintNotify", or "aucun"
+fn dependency_list(services: &[String]) -> String {
+    if services.is_empty() {
+        "aucun".to_string()
+    } else {
+        services.join(", ")
+    }
+}
+
+/// What was done to a service, with the start type it had before a durable optimization
+fn operation_description(operation: &ServiceOperation) -> String {
+    if let Some(service) = &operation.stopped_for {
+        return format!("dépend de {}, arrêté avant lui pour cette session", service);
+    }
+    let action = match (operation.action, operation.mode) {
+        (ServiceAction::Stop, Some(ServiceMode::Durable)) => "arrêté et désactivé",
+        (ServiceAction::Stop, _) => "arrêté pour cette session",
+        (ServiceAction::Disable, _) => "désactivé",
+        (ServiceAction::Enable, _) => "type de démarrage rétabli",
+        (ServiceAction::Start, _) => "redémarré",
+    };
+    match &operation.previous_start_type {
+        Some(start_type) => format!("{}, démarrage précédent : {}", action, start_type),
+        None => action.to_string(),
+    }
+}
+
+fn component_row(ui: &mut egui::Ui, theme: &crate::theme::Theme, label: &str, enabled: bool) {
+    ui.label(label);
+    if enabled {
+        ui.colored_label(theme.success, "● Activée");
+    } else {
+        ui.colored_label(theme.error, "○ Désactivée");
+    }
+    ui.end_row();
+}
+
+enum ExclusionAction {
+    Refresh,
+    Add(PathBuf),
+    Remove(PathBuf),
+}
+
+/// Game folders excluded from scans: the lighter alternative to disabling Defender
+fn draw_exclusions_section(app: &mut crate::CleanRamApp, ui: &mut egui::Ui, theme: &crate::theme::Theme) {
+    egui::CollapsingHeader::new("📁 Exclusions Defender")
+        .default_open(true)
+        .show(ui, |ui| {
+            ui.label("Excluez vos bibliothèques de jeux des analyses au lieu de désactiver Defender.");
+
+            if app.defender_exclusions.is_none()
+                && app.defender_exclusions_promise.is_none()
+                && app.last_exclusion_error.is_none()
+            {
+                spawn_exclusions_task(app, ExclusionAction::Refresh);
+            }
+            poll_exclusions(app);
+
+            let busy = app.defender_exclusions_promise.is_some();
+            let mut action: Option<ExclusionAction> = None;
+
+            ui.horizontal(|ui| {
+                if ui.add_enabled(!busy, egui::Button::new("📂 Ajouter un dossier...")).clicked() {
+                    if let Some(folder) = rfd::FileDialog::new().set_title("Dossier à exclure").pick_folder() {
+                        action = Some(ExclusionAction::Add(folder));
+                    }
+                }
+                if ui.add_enabled(!busy, egui::Button::new("🔄 Actualiser")).clicked() {
+                    action = Some(ExclusionAction::Refresh);
+                }
+                if busy {
+                    ui.spinner();
+                }
+            });
+
+            if let Some(error) = app.last_exclusion_error.clone() {
+                ui.horizontal(|ui| {
+                    ui.colored_label(theme.error, format!("❌ {}", error));
+                    if ui.small_button("✖").clicked() {
+                        app.last_exclusion_error = None;
+                    }
+                });
+            }
+
+            let exclusions = app.defender_exclusions.clone().unwrap_or_default();
+            if app.defender_exclusions.is_some() {
+                if exclusions.is_empty() {
+                    ui.label("Aucune exclusion de dossier.");
+                }
+                for path in &exclusions {
+                    ui.horizontal(|ui| {
+                        ui.label(path.display().to_string());
+                        if ui.add_enabled(!busy, egui::Button::new("🗑️").small()).on_hover_text("Retirer l'exclusion").clicked() {
+                            action = Some(ExclusionAction::Remove(path.clone()));
+                        }
+                    });
+                }
+            }
+
+            let suggestions: Vec<&PathBuf> = app
+                .exclusion_suggestions
+                .iter()
+                .filter(|s| !exclusions.iter().any(|e| game_libraries::same_path(e, s)))
+                .collect();
+            if !suggestions.is_empty() {
+                ui.add_space(5.0);
+                ui.label("💡 Bibliothèques de jeux détectées :");
+                for path in suggestions {
+                    ui.horizontal(|ui| {
+                        ui.label(path.display().to_string());
+                        if ui.add_enabled(!busy, egui::Button::new("➕ Exclure").small()).clicked() {
+                            action = Some(ExclusionAction::Add(path.clone()));
+                        }
+                    });
+                }
+            }
+
+            if let Some(action) = action {
+                spawn_exclusions_task(app, action);
+            }
+        });
+}
+
+/// Run the action then reload the list, off the UI thread (PowerShell is slow to start)
+fn spawn_exclusions_task(app: &mut crate::CleanRamApp, action: ExclusionAction) {
+    app.last_exclusion_error = None;
+    app.defender_exclusions_promise = Some(poll_promise::Promise::spawn_thread("defender_exclusions", move || {
+        match &action {
+            ExclusionAction::Refresh => {}
+            ExclusionAction::Add(path) => DefenderManager::add_exclusion_path(path)?,
+            ExclusionAction::Remove(path) => DefenderManager::remove_exclusion_path(path)?,
+        }
+        DefenderManager::list_exclusions()
+    }));
+}
+
+fn poll_exclusions(app: &mut crate::CleanRamApp) {
+    if let Some(promise) = app.defender_exclusions_promise.take() {
+        match promise.try_take() {
+            Ok(Ok(exclusions)) => app.defender_exclusions = Some(exclusions),
+            // Message tel quel : c'est lui qui indique un blocage par la protection contre les falsifications
+            Ok(Err(e)) => {
+                app.notifications.error_chain("Defender", "Exclusions non modifiées", &e);
+                app.last_exclusion_error = Some(e.to_string());
+            }
+            Err(promise) => app.defender_exclusions_promise = Some(promise),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use eframe::egui;
+
+use crate::sessions::{format_bytes, format_duration, SessionSummary};
+use crate::theme::Theme;
+use crate::ui::app::CleanRamApp;
+
+/// Running session, or the summary of the last one, then the previous sessions
+pub fn draw_sessions_section(app: &mut CleanRamApp, ui: &mut egui::Ui, theme: &Theme) {
+    if app.game_session.is_none() && app.game_sessions.is_empty() {
+        return;
+    }
+
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        if let Some(session) = &app.game_session {
+            ui.label(egui::RichText::new(format!("🎮 Session en cours : {}", session.title())).strong().color(theme.accent));
+            ui.label(format!(
+                "{} — {} RAM libérés en {} nettoyage(s)",
+                format_duration(session.duration()),
+                format_bytes(session.ram_freed),
+                session.cleanings
+            ));
+            // Durée affichée à la minute
+            ui.ctx().request_repaint_after(std::time::Duration::from_secs(30));
+        } else if let Some(last) = app.game_sessions.last() {
+            ui.label(egui::RichText::new(format!("🏁 {}", last.headline())).strong().color(theme.success));
+            draw_session_details(ui, last);
+        }
+
+        if app.game_sessions.is_empty() {
+            return;
+        }
+        egui::CollapsingHeader::new(format!("Sessions précédentes ({})", app.game_sessions.len()))
+            .id_source("game_sessions")
+            .show(ui, |ui| {
+                egui::ScrollArea::vertical().max_height(220.0).show(ui, |ui| {
+                    for (index, session) in app.game_sessions.iter().enumerate().rev() {
+                        let title = format!(
+                            "{} — {} ({})",
+                            session.started_at.format("%d/%m %H:%M"),
+                            session.name,
+                            format_duration(session.duration())
+                        );
+                        egui::CollapsingHeader::new(title).id_source(("game_session", index)).show(ui, |ui| {
+                            draw_session_details(ui, session);
+                        });
+                    }
+                });
+            });
+    });
+}
+
+fn draw_session_details(ui: &mut egui::Ui, session: &SessionSummary) {
+    if session.interrupted {
+        ui.weak("Session interrompue (fermeture forcée) : terminée au dernier enregistrement");
+    }
+    ui.label(format!("RAM libérée : {} en {} nettoyage(s)", format_bytes(session.ram_freed), session.cleanings));
+    if session.stopped_services.is_empty() {
+        ui.weak("Aucun service arrêté");
+    } else {
+        ui.label(format!("Services arrêtés : {}", session.stopped_services.join(", ")));
+    }
+    if session.limited_apps.is_empty() {
+        return;
+    }
+    ui.label(format!("Upload économisé (estimation) : {}", format_bytes(session.upload_saved)));
+    egui::Grid::new(("session_limited_apps", session.started_at.timestamp()))
+        .striped(true)
+        .num_columns(4)
+        .show(ui, |ui| {
+            ui.strong("Application");
+            ui.strong("Sans limite");
+            ui.strong("Limitée");
+            ui.strong("Économisé");
+            ui.end_row();
+            for app in &session.limited_apps {
+                ui.label(&app.name);
+                ui.label(format!("{}/s", format_speed(app.baseline_bps)));
+                ui.label(format!("{}/s", format_speed(app.limited_bps)));
+                ui.label(format_bytes(app.saved_bytes));
+                ui.end_row();
+            }
+        });
+}
+
+fn format_speed(bytes_per_sec: u64) -> String {
+    if bytes_per_sec >= 1024 * 1024 {
+        format!("{:.1} Mo", bytes_per_sec as f64 / 1024.0 / 1024.0)
+    } else {
+        format!("{:.0} Ko", bytes_per_sec as f64 / 1024.0)
+    }
+}
@@ -1,7 +1,17 @@
-use crate::theme::{self};
+use crate::maintenance::CleanupStatus;
 use crate::ui::app::CleanRamApp;
+use crate::updater::UpdateCheck;
 use eframe::egui;
 
+/// Color sample with its role name
+fn color_swatch(ui: &mut egui::Ui, color: egui::Color32, label: &str) {
+    ui.vertical(|ui| {
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(60.0, 24.0), egui::Sense::hover());
+        ui.painter().rect_filled(rect, 4.0, color);
+        ui.colored_label(color, label);
+    });
+}
+
 pub fn draw_settings_tab(app: &mut CleanRamApp, ui: &mut egui::Ui) {
     ui.heading("Paramètres");
 
@@ -12,26 +22,363 @@ pub fn draw_settings_tab(app: &mut CleanRamApp, ui: &mut egui::Ui) {
         ui.label("Thème de l'application");
         ui.horizontal(|ui| {
             if ui.selectable_label(app.theme.name == "Light", "Clair").clicked() {
-                app.theme = theme::light_theme();
-                ui.ctx().set_visuals(app.theme.visuals.clone());
+                app.set_theme("Light", app.accent_color, ui.ctx());
             }
             if ui.selectable_label(app.theme.name == "Dark", "Sombre").clicked() {
-                app.theme = theme::dark_theme();
-                ui.ctx().set_visuals(app.theme.visuals.clone());
+                app.set_theme("Dark", app.accent_color, ui.ctx());
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Couleur d'accent :");
+            let mut accent = app.theme.accent;
+            if egui::color_picker::color_edit_button_srgba(ui, &mut accent, egui::color_picker::Alpha::Opaque).changed() {
+                app.set_theme(app.theme.name, Some(accent), ui.ctx());
             }
+            if ui.add_enabled(app.accent_color.is_some(), egui::Button::new("Par défaut")).clicked() {
+                app.set_theme(app.theme.name, None, ui.ctx());
+            }
+        });
+
+        ui.add_space(5.0);
+        ui.label("Aperçu :");
+        ui.horizontal(|ui| {
+            color_swatch(ui, app.theme.accent, "Accent");
+            color_swatch(ui, app.theme.success, "Succès");
+            color_swatch(ui, app.theme.warning, "Attention");
+            color_swatch(ui, app.theme.error, "Erreur");
         });
     });
     
     ui.add_space(20.0);
 
+    // --- Startup ---
+    ui.group(|ui| {
+        ui.label("Démarrage");
+        ui.separator();
+        let mut run_at_startup = app.run_at_startup;
+        if ui
+            .checkbox(&mut run_at_startup, "Lancer au démarrage de Windows")
+            .on_hover_text("Démarre GameBooster réduit à l'ouverture de session (aucun droit administrateur requis)")
+            .changed()
+        {
+            app.set_run_at_startup(run_at_startup);
+        }
+        if let Some(error) = &app.startup_error {
+            ui.colored_label(app.theme.error, error);
+        }
+        if ui
+            .add_enabled(app.onboarding.is_none(), egui::Button::new("🧭 Relancer l'assistant"))
+            .on_hover_text("Vérifications du système, réglages de départ et ce que GameBooster peut modifier")
+            .clicked()
+        {
+            app.onboarding = Some(crate::ui::onboarding::Onboarding::start(app));
+        }
+    });
+
+    ui.add_space(20.0);
+
+    // --- Game launchers ---
+    ui.group(|ui| {
+        ui.label("🎮 Lanceurs de jeux");
+        ui.separator();
+        let config = &mut app.launcher_watcher_config;
+        ui.checkbox(&mut config.enabled, "Nettoyer la RAM avant un jeu")
+            .on_hover_text("Libère la mémoire quand Steam, Epic Games ou Battle.net lance un jeu ou télécharge");
+        ui.add_enabled_ui(config.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Délai minimal entre deux nettoyages :");
+                ui.add(egui::DragValue::new(&mut config.cooldown_minutes).clamp_range(1..=240).suffix(" min"));
+            });
+            ui.add_enabled(
+                app.is_elevated,
+                egui::Checkbox::new(&mut config.purge_standby, "Purger aussi la liste standby"),
+            )
+            .on_hover_text("Vide le cache de pages de Windows après le nettoyage (administrateur requis)")
+            .on_disabled_hover_text("Nécessite les droits administrateur");
+
+            for launcher in config.launchers.iter_mut() {
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut launcher.enabled, &launcher.name);
+                    ui.add_enabled_ui(launcher.enabled, |ui| {
+                        ui.checkbox(&mut launcher.child_trigger, "Lancement de jeu")
+                            .on_hover_text("Un processus lancé par le launcher, hors de ses processus auxiliaires");
+                        let mut downloads = launcher.activity_trigger.is_some();
+                        if ui
+                            .checkbox(&mut downloads, "Téléchargement")
+                            .on_hover_text("Écriture disque ou débit descendant soutenu pendant que le launcher tourne")
+                            .changed()
+                        {
+                            launcher.activity_trigger = downloads.then(Default::default);
+                        }
+                        if let Some(activity) = launcher.activity_trigger.as_mut() {
+                            ui.add(egui::DragValue::new(&mut activity.disk_write_mb_per_sec).clamp_range(1.0..=2000.0).prefix("disque ≥ ").suffix(" Mo/s"));
+                            ui.add(egui::DragValue::new(&mut activity.download_mb_per_sec).clamp_range(0.5..=2000.0).prefix("réseau ≥ ").suffix(" Mo/s"));
+                            ui.add(egui::DragValue::new(&mut activity.sustained_secs).clamp_range(2..=600).prefix("pendant ").suffix(" s"));
+                        }
+                    });
+                });
+            }
+        });
+    });
+
+    ui.add_space(20.0);
+
+    // --- Keyboard shortcuts ---
+    ui.group(|ui| {
+        ui.label("⌨️ Raccourcis clavier");
+        ui.separator();
+        ui.weak("Dans la fenêtre : Ctrl+1 à Ctrl+6 changent d'onglet, Ctrl+R scanne le réseau, Ctrl+M nettoie la RAM");
+        let config = &mut app.hotkey_config;
+        ui.checkbox(&mut config.enabled, "Raccourci global « Nettoyer la RAM »")
+            .on_hover_text("Fonctionne aussi en jeu et fenêtre réduite ; une notification Windows confirme le résultat");
+        let mut apply_clicked = false;
+        ui.add_enabled_ui(config.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Combinaison :");
+                let response = ui.add(egui::TextEdit::singleline(&mut app.hotkey_draft).desired_width(140.0));
+                let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                apply_clicked = ui.button("Appliquer").on_hover_text("Ex. : Ctrl+Alt+F9, Maj+Win+C").clicked() || submitted;
+                if app.global_hotkey.is_some() {
+                    ui.colored_label(app.theme.success, "✅ Enregistré");
+                }
+            });
+            ui.add_enabled(
+                app.is_elevated,
+                egui::Checkbox::new(&mut config.purge_standby, "Purger aussi la liste standby"),
+            )
+            .on_hover_text("Vide le cache de pages de Windows après le nettoyage (administrateur requis)")
+            .on_disabled_hover_text("Nécessite les droits administrateur");
+        });
+        if let Some(error) = &app.hotkey_error {
+            ui.colored_label(app.theme.error, error);
+        }
+        if apply_clicked {
+            app.apply_hotkey_draft();
+        }
+    });
+
+    ui.add_space(20.0);
+
+    // --- Memory alerts ---
+    ui.group(|ui| {
+        ui.label("🔔 Alertes mémoire");
+        ui.separator();
+        let config = &mut app.memory_alert_config;
+        ui.checkbox(&mut config.enabled, "Notification Windows quand la mémoire manque")
+            .on_hover_text("Une seule notification par franchissement de seuil, même fenêtre réduite ; un clic ouvre l'onglet Mémoire");
+        ui.add_enabled_ui(config.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Mémoire disponible sous :");
+                ui.add(egui::DragValue::new(&mut config.min_available_mb).clamp_range(256..=65536).speed(64).suffix(" Mo"));
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut config.pagefile_alert, "Fichier d'échange utilisé à plus de");
+                ui.add_enabled(
+                    config.pagefile_alert,
+                    egui::DragValue::new(&mut config.max_pagefile_percent).clamp_range(50..=99).suffix(" %"),
+                );
+            });
+            ui.checkbox(&mut config.quiet_when_fullscreen, "Pas de notification pendant un jeu en plein écran")
+                .on_hover_text("L'alerte reste dans la liste des notifications de GameBooster");
+        });
+    });
+
+    ui.add_space(20.0);
+
+    // --- Safety ---
+    ui.group(|ui| {
+        ui.label("Sécurité");
+        ui.separator();
+        ui.checkbox(&mut app.simulation_mode, "🧪 Mode simulation")
+            .on_hover_text("Le nettoyage de disque et l'arrêt des services indiquent ce qu'ils feraient, sans rien modifier");
+        ui.horizontal(|ui| {
+            ui.label(format!("Confirmations masquées : {}", app.skipped_confirmations.len()));
+            if ui
+                .add_enabled(!app.skipped_confirmations.is_empty(), egui::Button::new("🔔 Réactiver les confirmations"))
+                .clicked()
+            {
+                app.skipped_confirmations.clear();
+            }
+        });
+    });
+
+    ui.add_space(20.0);
+
+    // --- Network limits ---
+    ui.group(|ui| {
+        ui.label("🌐 Limites réseau");
+        ui.separator();
+        let can_measure = crate::utils::is_elevated() && cfg!(all(target_os = "windows", feature = "etw"));
+        let toggled = ui
+            .add_enabled(
+                can_measure,
+                egui::Checkbox::new(&mut app.high_precision_network, "Surveillance réseau haute précision (admin)"),
+            )
+            .on_hover_text("Débits mesurés par les événements réseau du noyau (ETW), TCP et UDP, au lieu d'estimations")
+            .on_disabled_hover_text("Nécessite Windows et les droits administrateur")
+            .changed();
+        if toggled {
+            if let Some(ref mut limiter) = app.network_limiter {
+                limiter.set_high_precision(app.high_precision_network);
+            }
+        }
+        ui.add_space(5.0);
+        ui.label("Préréglages proposés à côté de la limite :");
+        let presets = &mut app.limit_preferences.presets;
+        let mut removed = None;
+        for (index, preset) in presets.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut preset.name).desired_width(200.0));
+                ui.add(
+                    egui::DragValue::new(&mut preset.kbps)
+                        .clamp_range(crate::network::MIN_SPEED_LIMIT_KBPS..=crate::network::MAX_SPEED_LIMIT_KBPS)
+                        .suffix(" KB/s"),
+                );
+                if ui.small_button("🗑").on_hover_text("Supprimer le préréglage").clicked() {
+                    removed = Some(index);
+                }
+            });
+        }
+        if let Some(index) = removed {
+            presets.remove(index);
+        }
+        if ui.button("➕ Ajouter un préréglage").clicked() {
+            presets.push(crate::config::LimitPreset { name: format!("Préréglage {}", presets.len() + 1), kbps: 1024 });
+        }
+
+        ui.add_space(5.0);
+        let remembered = &mut app.limit_preferences.per_exe;
+        ui.label(format!("Dernières limites par application : {}", remembered.len()));
+        let mut forgotten = None;
+        for (exe_name, kbps) in remembered.iter() {
+            ui.horizontal(|ui| {
+                ui.label(format!("{} : {}", exe_name, crate::network::describe_speed_limit(*kbps)));
+                if ui.small_button("Oublier").clicked() {
+                    forgotten = Some(exe_name.clone());
+                }
+            });
+        }
+        if let Some(exe_name) = forgotten {
+            app.limit_preferences.forget(&exe_name);
+        }
+
+        ui.add_space(5.0);
+        let previous = (app.exited_limit_action, app.exited_limit_grace_secs);
+        ui.horizontal(|ui| {
+            ui.label("Quand un processus limité se termine :");
+            egui::ComboBox::from_id_source("exited_limit_action")
+                .selected_text(app.exited_limit_action.label())
+                .show_ui(ui, |ui| {
+                    for action in [crate::network::ExitedLimitAction::Remove, crate::network::ExitedLimitAction::Keep] {
+                        ui.selectable_value(&mut app.exited_limit_action, action, action.label());
+                    }
+                });
+            if app.exited_limit_action == crate::network::ExitedLimitAction::Remove {
+                ui.label("après");
+                ui.add(egui::DragValue::new(&mut app.exited_limit_grace_secs).clamp_range(0..=3600).suffix(" s"));
+            }
+        })
+        .response
+        .on_hover_text("La politique QoS vise le nom de l'exécutable : conservée, elle limite aussi son prochain lancement");
+        if (app.exited_limit_action, app.exited_limit_grace_secs) != previous {
+            app.apply_exited_limit_handling();
+        }
+    });
+
+    ui.add_space(20.0);
+
+    // --- Boost profiles ---
+    let theme = app.theme.clone();
+    crate::ui::profiles_ui::draw_profiles_section(app, ui, &theme);
+
+    ui.add_space(20.0);
+
+    // --- History ---
+    crate::ui::history_ui::draw_history_section(app, ui, &theme);
+
+    ui.add_space(20.0);
+
+    // --- Log viewer ---
+    crate::ui::logs_ui::draw_logs_section(app, ui, &theme);
+
+    ui.add_space(20.0);
+
+    // --- Settings file ---
+    ui.group(|ui| {
+        ui.label("Sauvegarde des paramètres");
+        ui.separator();
+        ui.label(format!("Fichier : {}", crate::config::AppConfig::path().display()));
+        ui.horizontal(|ui| {
+            if ui.button("💾 Sauvegarder maintenant").clicked() {
+                app.save_settings();
+            }
+            if ui.button("♻️ Réinitialiser les paramètres").clicked() {
+                app.reset_settings(ui.ctx());
+            }
+        });
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            let busy = app.cleanup_promise.is_some();
+            if ui
+                .add_enabled(!busy, egui::Button::new("🧹 Tout nettoyer avant désinstallation"))
+                .on_hover_text(
+                    "Liste puis supprime tout ce que GameBooster a laissé sur le système : politiques QoS, règles pare-feu, \
+                     Defender, services, tâches planifiées, lancement au démarrage, notifications, fichier hosts, dossiers config/logs",
+                )
+                .clicked()
+            {
+                app.start_cleanup(true);
+            }
+            if busy {
+                ui.spinner();
+            }
+        });
+    });
+
+    ui.add_space(20.0);
+
+    // --- Updates ---
+    ui.group(|ui| draw_updates_section(app, ui));
+
+    ui.add_space(20.0);
+
+    // --- Drift check ---
+    ui.group(|ui| draw_drift_section(app, ui));
+
+    ui.add_space(20.0);
+
+    // --- Diagnostic report ---
+    ui.group(|ui| {
+        ui.label("🩺 Diagnostic");
+        ui.separator();
+        ui.weak("Rassemble dans un zip local (dossier reports) le système, les prérequis et politiques QoS, les limites enregistrées, les 500 dernières lignes du journal et les paramètres (chemins masqués). Rien n'est envoyé.");
+        let busy = app.diagnostic_promise.is_some();
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!busy, egui::Button::new("🩺 Générer un rapport de diagnostic")).clicked() {
+                app.start_diagnostic_report();
+            }
+            if busy {
+                ui.spinner();
+                ui.label("Collecte en cours...");
+            }
+        });
+        ui.separator();
+        crate::ui::crashes_ui::draw_recent_crashes(app, ui);
+    });
+
+    ui.add_space(20.0);
+
     // --- System Information ---
     ui.group(|ui| {
         ui.label("Informations Système");
         ui.separator();
-        
-        // Use the already determined version string from the app state
-        ui.label(format!("Version de Windows : {}", app.windows_version_string));
-        
+
+        ui.label(format!("Système : {}", app.os_details.description()));
+        if app.os_details.is_server {
+            ui.label("Édition serveur : certaines optimisations de jeu sont sans effet");
+        }
+
         // You can add more system info here if needed
         // For example: CPU, GPU, RAM size, etc.
     });
@@ -49,4 +396,264 @@ pub fn draw_settings_tab(app: &mut CleanRamApp, ui: &mut egui::Ui) {
             ui.label("et Rust.");
         });
     });
-}
\ No newline at end of file
+}
+
+/// Last drift check, on demand check and the decision on its drifts
+fn draw_drift_section(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    ui.label("🧭 Cohérence système");
+    ui.separator();
+    ui.weak("Politiques QoS, pause de Defender et services désactivés sont comparés à l'état de Windows toutes les 10 minutes : une mise à jour ou un gpupdate peut les annuler.");
+
+    let checking = app.drift_promise.is_some();
+    ui.horizontal(|ui| {
+        if ui.add_enabled(!checking, egui::Button::new("🔍 Vérifier maintenant")).clicked() {
+            app.start_drift_check();
+        }
+        if checking {
+            ui.spinner();
+        }
+    });
+
+    let Some(report) = &app.drift_report else {
+        return;
+    };
+    let checked_at = report.checked_at.format("%H:%M").to_string();
+    if report.is_empty() {
+        ui.label(format!("✅ Aucun écart ({})", checked_at));
+        return;
+    }
+    ui.colored_label(app.theme.warning, format!("⚠️ {} écart(s) ({})", report.count(), checked_at));
+    for line in report.lines() {
+        ui.label(format!("• {}", line));
+    }
+    let mut reapply = None;
+    ui.horizontal(|ui| {
+        if ui.button("🔁 Ré-appliquer").clicked() {
+            reapply = Some(true);
+        }
+        if ui.button("✔ Accepter l'état actuel")
+            .on_hover_text("Les réglages modifiés ne sont plus suivis par GameBooster")
+            .clicked()
+        {
+            reapply = Some(false);
+        }
+    });
+    match reapply {
+        Some(true) => app.reapply_drift(),
+        Some(false) => app.accept_drift(),
+        None => {}
+    }
+}
+
+fn format_mb(bytes: u64) -> String {
+    format!("{:.1} Mo", bytes as f64 / 1_048_576.0)
+}
+
+/// Latest release, its notes and the download of its installer
+fn draw_updates_section(app: &mut CleanRamApp, ui: &mut egui::Ui) {
+    ui.label("🔄 Mises à jour");
+    ui.separator();
+    let mut never_check = !app.check_updates;
+    if ui
+        .checkbox(&mut never_check, "Ne jamais vérifier les mises à jour")
+        .on_hover_text("Aucune requête vers GitHub, ni au démarrage ni depuis ce panneau")
+        .changed()
+    {
+        app.check_updates = !never_check;
+    }
+    ui.add_enabled(app.check_updates, egui::Checkbox::new(&mut app.check_updates_on_metered, "Vérifier aussi sur une connexion limitée"))
+        .on_hover_text("Partage de connexion, forfait mobile : la vérification au démarrage est sinon reportée");
+    if app.is_metered() {
+        ui.weak("📶 Connexion limitée en cours");
+    }
+
+    ui.horizontal(|ui| {
+        ui.label(format!("Version installée : {}", env!("CARGO_PKG_VERSION")));
+        let checking = app.update_check_promise.is_some();
+        if ui
+            .add_enabled(app.check_updates && !checking, egui::Button::new("Vérifier maintenant"))
+            .clicked()
+        {
+            app.start_update_check();
+        }
+        if checking {
+            ui.spinner();
+        }
+    });
+    if let Some(ignored) = app.ignored_update_version.clone() {
+        ui.horizontal(|ui| {
+            ui.label(format!("Version ignorée : {}", ignored));
+            if ui.small_button("Ne plus ignorer").clicked() {
+                app.ignored_update_version = None;
+            }
+        });
+    }
+
+    let release = match &app.update_check {
+        None => return,
+        Some(UpdateCheck::UpToDate) => {
+            ui.colored_label(app.theme.success, "✅ GameBooster est à jour");
+            return;
+        }
+        Some(UpdateCheck::Unavailable(reason)) => {
+            ui.label("Vérification impossible").on_hover_text(reason);
+            return;
+        }
+        Some(UpdateCheck::Available(release)) => release.clone(),
+    };
+
+    ui.add_space(5.0);
+    ui.colored_label(app.theme.accent, format!("Nouvelle version : {} ({})", release.version, release.name));
+    if !release.notes.trim().is_empty() {
+        egui::ScrollArea::vertical()
+            .id_source("release_notes")
+            .max_height(160.0)
+            .show(ui, |ui| ui.label(release.notes.trim()));
+    }
+    ui.hyperlink_to("Voir sur GitHub", &release.html_url);
+
+    ui.horizontal(|ui| {
+        match &release.asset {
+            Some(asset) => {
+                let downloading = app.update_download.is_some();
+                if ui
+                    .add_enabled(!downloading, egui::Button::new("⬇️ Télécharger"))
+                    .on_hover_text(format!("{} ({}) dans le dossier Téléchargements", asset.name, format_mb(asset.size)))
+                    .clicked()
+                {
+                    app.start_update_download();
+                }
+            }
+            None => {
+                ui.label("Aucun installeur dans cette version");
+            }
+        }
+        if ui.button("Ignorer cette version").on_hover_text("Plus de notification pour cette version").clicked() {
+            app.ignore_available_update();
+        }
+    });
+
+    if let Some((downloaded, total)) = app.update_download_progress {
+        let bar = match total {
+            Some(total) if total > 0 => egui::ProgressBar::new(downloaded as f32 / total as f32)
+                .text(format!("{} / {}", format_mb(downloaded), format_mb(total))),
+            _ => egui::ProgressBar::new(0.0).text(format_mb(downloaded)),
+        };
+        ui.add(bar.desired_width(300.0));
+    }
+    match app.update_download_result.clone() {
+        Some(Ok(path)) => {
+            ui.horizontal(|ui| {
+                ui.label(format!("Téléchargée : {}", path.display()));
+                if ui.small_button("📂 Afficher").clicked() {
+                    if let Err(e) = crate::utils::reveal_in_file_manager(&path) {
+                        app.notifications.error_chain("Mise à jour", "Ouverture du dossier impossible", &e);
+                    }
+                }
+            });
+        }
+        Some(Err(error)) => {
+            ui.colored_label(app.theme.error, format!("Téléchargement échoué : {}", error));
+        }
+        None => {}
+    }
+}
+
+/// Per-item outcome of the cleanup before uninstallation, until closed
+/// Outcome of the diagnostic report, until closed
+pub fn draw_diagnostic_report(app: &mut CleanRamApp, ctx: &egui::Context) {
+    let Some(result) = &app.diagnostic_report else {
+        return;
+    };
+    let theme = &app.theme;
+    let mut open = true;
+    let mut close = false;
+    let mut reveal = None;
+    egui::Window::new("🩺 Rapport de diagnostic")
+        .open(&mut open)
+        .collapsible(false)
+        .default_width(460.0)
+        .show(ctx, |ui| {
+            match result {
+                Ok(report) => {
+                    ui.colored_label(theme.success, format!("✅ Rapport créé ({} fichiers)", report.sections));
+                    ui.label(report.path.display().to_string());
+                    for failure in &report.failures {
+                        ui.colored_label(theme.warning, format!("⚠️ {}", failure));
+                    }
+                    ui.weak("Joignez ce fichier à votre demande d'assistance.");
+                }
+                Err(e) => {
+                    ui.colored_label(theme.error, format!("❌ Rapport impossible : {:#}", e));
+                }
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                if let Ok(report) = result {
+                    if ui.button("📂 Ouvrir le dossier").clicked() {
+                        reveal = Some(report.path.clone());
+                    }
+                }
+                if ui.button("Fermer").clicked() {
+                    close = true;
+                }
+            });
+        });
+    if let Some(path) = reveal {
+        if let Err(e) = crate::utils::reveal_in_file_manager(&path) {
+            tracing::error!("❌ Ouverture du dossier des rapports impossible: {}", e);
+            app.notifications.error_chain("Diagnostic", "Ouverture du dossier impossible", &e);
+        }
+    }
+    if !open || close {
+        app.diagnostic_report = None;
+    }
+}
+
+pub fn draw_cleanup_report(app: &mut CleanRamApp, ctx: &egui::Context) {
+    let Some(report) = &app.cleanup_report else {
+        return;
+    };
+    let theme = &app.theme;
+    let mut open = true;
+    let mut close = false;
+    egui::Window::new("🧹 Nettoyage avant désinstallation")
+        .open(&mut open)
+        .collapsible(false)
+        .default_width(520.0)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                let mut category = None;
+                for item in &report.items {
+                    if category != Some(item.category) {
+                        category = Some(item.category);
+                        ui.label(egui::RichText::new(item.category.label()).strong());
+                    }
+                    match &item.status {
+                        CleanupStatus::Planned => ui.label(format!("• {}", item.description)),
+                        CleanupStatus::Removed => ui.colored_label(theme.success, format!("✅ {}", item.description)),
+                        CleanupStatus::Failed(error) => {
+                            ui.colored_label(theme.error, format!("❌ {} : {}", item.description, error))
+                        }
+                        CleanupStatus::NeedsElevation => ui.colored_label(
+                            theme.warning,
+                            format!("🔒 {} : droits administrateur requis", item.description),
+                        ),
+                    };
+                }
+            });
+            ui.separator();
+            if report.needs_elevation() > 0 {
+                ui.label("Relancez en administrateur puis recommencez pour les éléments restants.");
+            }
+            if app.settings_removed {
+                ui.label("Les paramètres ont été supprimés : ils ne seront pas réécrits à la fermeture.");
+            }
+            if ui.button("Fermer").clicked() {
+                close = true;
+            }
+        });
+    if !open || close {
+        app.cleanup_report = None;
+    }
+}
@@ -10,4 +10,31 @@ pub fn centered_button(
         ui.add(egui::Button::new(text).min_size(egui::vec2(button_width, 0.0)))
     })
     .inner
-} 
\ No newline at end of file
+} 
+/// Tooltip shown on buttons disabled because the app is not elevated
+pub const ELEVATION_REQUIRED_HINT: &str = "Nécessite les droits administrateur : utilisez « Relancer en administrateur »";
+
+/// "Terminer le processus" entries of the context menu of a process row: `Some(restart)` when
+/// one is chosen. Critical processes get a disabled entry explaining why.
+pub fn terminate_menu(response: &egui::Response, critical: bool) -> Option<bool> {
+    let mut chosen = None;
+    response.clone().context_menu(|ui| {
+        ui.add_enabled_ui(!critical, |ui| {
+            if ui.button("🛑 Terminer le processus").clicked() {
+                chosen = Some(false);
+                ui.close_menu();
+            }
+            if ui
+                .button("🔄 Terminer et redémarrer")
+                .on_hover_text("Relance le même exécutable, sans ses arguments d'origine")
+                .clicked()
+            {
+                chosen = Some(true);
+                ui.close_menu();
+            }
+        })
+        .response
+        .on_disabled_hover_text("Processus système critique : il ne peut pas être terminé");
+    });
+    chosen
+}
@@ -0,0 +1,246 @@
+//! # Update checker
+//!
+//! Compares the latest GitHub release with the running version and downloads its asset:
+//! - `check_for_update` runs on a worker thread, at startup and from the settings
+//! - any failure (offline, rate limit, unexpected answer) is `UpdateCheck::Unavailable`: an
+//!   update check never blocks nor fails anything else
+//! - `start_download` streams the asset into the Downloads folder and reports its progress on
+//!   a channel, the file only gets its final name once complete
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+
+const RELEASES_URL: &str = "https://api.github.com/repos/zehelh/GameBooster/releases/latest";
+/// GitHub rejects API requests without a User-Agent
+const USER_AGENT: &str = concat!("GameBooster/", env!("CARGO_PKG_VERSION"));
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+/// Connection only: the download itself may take longer on a slow link
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub url: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReleaseInfo {
+    pub version: semver::Version,
+    pub tag: String,
+    pub name: String,
+    /// Release notes, in Markdown as written on GitHub
+    pub notes: String,
+    pub html_url: String,
+    /// Installer, or archive when the release has none
+    pub asset: Option<ReleaseAsset>,
+}
+
+#[derive(Debug, Clone)]
+pub enum UpdateCheck {
+    UpToDate,
+    Available(ReleaseInfo),
+    /// Why the latest release could not be known
+    Unavailable(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum DownloadEvent {
+    /// Bytes written so far, and the expected total when the server sent it
+    Progress { downloaded: u64, total: Option<u64> },
+    Done(PathBuf),
+    Failed(String),
+}
+
+#[derive(Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    body: Option<String>,
+    html_url: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+    size: u64,
+}
+
+pub fn current_version() -> semver::Version {
+    semver::Version::parse(env!("CARGO_PKG_VERSION")).expect("version du paquet au format semver")
+}
+
+/// "v1.2.0" or "1.2.0"
+fn parse_tag(tag: &str) -> Option<semver::Version> {
+    semver::Version::parse(tag.trim().trim_start_matches(['v', 'V'])).ok()
+}
+
+/// Installer first (.exe, .msi), then an archive (.zip)
+fn pick_asset(assets: Vec<GithubAsset>) -> Option<ReleaseAsset> {
+    let rank = |asset: &GithubAsset| {
+        let name = asset.name.to_lowercase();
+        if name.ends_with(".exe") || name.ends_with(".msi") {
+            Some(0)
+        } else if name.ends_with(".zip") {
+            Some(1)
+        } else {
+            None
+        }
+    };
+    assets
+        .into_iter()
+        .filter_map(|asset| rank(&asset).map(|rank| (rank, asset)))
+        .min_by_key(|(rank, _)| *rank)
+        .map(|(_, asset)| ReleaseAsset { name: asset.name, url: asset.browser_download_url, size: asset.size })
+}
+
+fn fetch_latest_release() -> Result<GithubRelease> {
+    let client = reqwest::blocking::Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(CHECK_TIMEOUT)
+        .build()?;
+    let response = client
+        .get(RELEASES_URL)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send()
+        .context("GitHub injoignable")?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::FORBIDDEN || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(anyhow!("limite de requêtes GitHub atteinte"));
+    }
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Err(anyhow!("aucune version publiée"));
+    }
+    if !status.is_success() {
+        return Err(anyhow!("réponse GitHub {}", status));
+    }
+    response.json::<GithubRelease>().context("réponse GitHub illisible")
+}
+
+/// Latest release compared with the running version; blocking, up to `CHECK_TIMEOUT`
+pub fn check_for_update() -> UpdateCheck {
+    let release = match fetch_latest_release() {
+        Ok(release) => release,
+        Err(e) => {
+            tracing::info!("🔄 Vérification des mises à jour impossible: {:#}", e);
+            return UpdateCheck::Unavailable(format!("{:#}", e));
+        }
+    };
+    if release.draft || release.prerelease {
+        return UpdateCheck::UpToDate;
+    }
+    let Some(version) = parse_tag(&release.tag_name) else {
+        tracing::warn!("⚠️ Version GitHub {} illisible", release.tag_name);
+        return UpdateCheck::Unavailable(format!("version {} illisible", release.tag_name));
+    };
+
+    let current = current_version();
+    if version <= current {
+        tracing::info!("🔄 GameBooster {} à jour (dernière version {})", current, version);
+        return UpdateCheck::UpToDate;
+    }
+    tracing::info!("🔄 Nouvelle version disponible : {} (installée {})", version, current);
+    UpdateCheck::Available(ReleaseInfo {
+        version,
+        name: release.name.filter(|name| !name.trim().is_empty()).unwrap_or_else(|| release.tag_name.clone()),
+        tag: release.tag_name,
+        notes: release.body.unwrap_or_default(),
+        html_url: release.html_url,
+        asset: pick_asset(release.assets),
+    })
+}
+
+fn download_dir() -> PathBuf {
+    dirs::download_dir().unwrap_or_else(std::env::temp_dir)
+}
+
+fn download(asset: &ReleaseAsset, events: &Sender<DownloadEvent>) -> Result<PathBuf> {
+    let client = reqwest::blocking::Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(None)
+        .build()?;
+    let mut response = client.get(&asset.url).send().context("téléchargement impossible")?;
+    if !response.status().is_success() {
+        return Err(anyhow!("réponse {} pour {}", response.status(), asset.name));
+    }
+    let total = response.content_length().or((asset.size > 0).then_some(asset.size));
+
+    let destination = download_dir().join(&asset.name);
+    // Fichier partiel : un téléchargement interrompu ne laisse pas un installeur tronqué
+    let partial = destination.with_extension("part");
+    let mut file = File::create(&partial).with_context(|| format!("création de {}", partial.display()))?;
+
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut downloaded = 0u64;
+    loop {
+        let read = match response.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) => {
+                drop(file);
+                let _ = std::fs::remove_file(&partial);
+                return Err(anyhow!("téléchargement interrompu: {}", e));
+            }
+        };
+        file.write_all(&buffer[..read])?;
+        downloaded += read as u64;
+        // Interface fermée : inutile de continuer
+        if events.send(DownloadEvent::Progress { downloaded, total }).is_err() {
+            drop(file);
+            let _ = std::fs::remove_file(&partial);
+            return Err(anyhow!("téléchargement abandonné"));
+        }
+    }
+    file.flush()?;
+    drop(file);
+
+    if total.is_some_and(|total| downloaded != total) {
+        let _ = std::fs::remove_file(&partial);
+        return Err(anyhow!("téléchargement incomplet ({} octets sur {})", downloaded, total.unwrap_or_default()));
+    }
+    std::fs::rename(&partial, &destination).with_context(|| format!("renommage en {}", destination.display()))?;
+    Ok(destination)
+}
+
+/// Download `asset` on a worker thread; the receiver gets progress, then `Done` or `Failed`
+pub fn start_download(asset: ReleaseAsset) -> Receiver<DownloadEvent> {
+    let (sender, receiver) = mpsc::channel();
+    let spawned = std::thread::Builder::new().name("update_download".to_string()).spawn(move || {
+        tracing::info!("⬇️ Téléchargement de {}", asset.name);
+        let event = match download(&asset, &sender) {
+            Ok(path) => {
+                tracing::info!("✅ Mise à jour téléchargée: {}", path.display());
+                DownloadEvent::Done(path)
+            }
+            Err(e) => {
+                tracing::error!("❌ Téléchargement de {} échoué: {:#}", asset.name, e);
+                DownloadEvent::Failed(format!("{:#}", e))
+            }
+        };
+        let _ = sender.send(event);
+    });
+    if let Err(e) = spawned {
+        // L'émetteur a été consommé par la fermeture : l'échec est signalé par un canal neuf
+        let (sender, receiver) = mpsc::channel();
+        let _ = sender.send(DownloadEvent::Failed(format!("thread de téléchargement impossible à lancer: {}", e)));
+        return receiver;
+    }
+    receiver
+}
@@ -0,0 +1,108 @@
+//! # Game libraries
+//!
+//! Locate the Steam and Epic Games install folders, used to suggest Defender exclusions and
+//! by `disk::game_libraries` to list the installed games.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Steam library folders (`<library>\steamapps\common`) and Epic install roots found on
+/// this machine. Only existing folders, without duplicates.
+pub fn detect_library_paths() -> Vec<PathBuf> {
+    let mut paths = steam_library_paths();
+    paths.extend(epic_library_paths());
+
+    let mut unique: Vec<PathBuf> = Vec::new();
+    for path in paths {
+        if path.is_dir() && !unique.iter().any(|p| same_path(p, &path)) {
+            unique.push(path);
+        }
+    }
+    unique
+}
+
+/// Case-insensitive comparison, separators normalized (Windows paths)
+pub fn same_path(a: &Path, b: &Path) -> bool {
+    let normalize = |p: &Path| p.to_string_lossy().replace('/', "\\").trim_end_matches('\\').to_lowercase();
+    normalize(a) == normalize(b)
+}
+
+fn steam_install_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        if let Ok(Some(path)) = super::registry::read_string(super::registry::HKEY_CURRENT_USER, "Software\\Valve\\Steam", "SteamPath") {
+            return Some(PathBuf::from(path));
+        }
+        let default = PathBuf::from("C:\\Program Files (x86)\\Steam");
+        default.is_dir().then_some(default)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        dirs::home_dir().map(|home| home.join(".steam").join("steam"))
+    }
+}
+
+fn steam_library_paths() -> Vec<PathBuf> {
+    steam_libraries()
+        .into_iter()
+        .map(|library| library.join("steamapps").join("common"))
+        .collect()
+}
+
+/// Steam library roots (the folders holding `steamapps`), the Steam folder itself when
+/// `libraryfolders.vdf` lists none
+pub fn steam_libraries() -> Vec<PathBuf> {
+    let Some(steam_dir) = steam_install_dir() else {
+        return Vec::new();
+    };
+
+    let vdf = steam_dir.join("steamapps").join("libraryfolders.vdf");
+    let mut libraries = match fs::read_to_string(&vdf) {
+        Ok(content) => parse_library_folders(&content),
+        Err(e) => {
+            tracing::debug!("🔍 {} illisible: {}", vdf.display(), e);
+            Vec::new()
+        }
+    };
+    if libraries.is_empty() {
+        libraries.push(steam_dir);
+    }
+    libraries
+}
+
+/// `"path"  "D:\\SteamLibrary"` entries of `libraryfolders.vdf`
+fn parse_library_folders(content: &str) -> Vec<PathBuf> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let tokens: Vec<&str> = line.split('"').map(str::trim).filter(|t| !t.is_empty()).collect();
+            match tokens.as_slice() {
+                [key, value] if key.eq_ignore_ascii_case("path") => Some(PathBuf::from(value.replace("\\\\", "\\"))),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Folder of the Epic Games Launcher manifests (`*.item`, one per installed game)
+pub fn epic_manifests_dir() -> PathBuf {
+    let program_data = std::env::var("ProgramData").unwrap_or_else(|_| "C:\\ProgramData".to_string());
+    Path::new(&program_data).join("Epic\\EpicGamesLauncher\\Data\\Manifests")
+}
+
+/// Parent folders of the `InstallLocation` of each Epic manifest (`*.item`)
+fn epic_library_paths() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(epic_manifests_dir()) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("item")))
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .filter_map(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .filter_map(|manifest| manifest.get("InstallLocation")?.as_str().map(PathBuf::from))
+        .filter_map(|location| location.parent().map(Path::to_path_buf))
+        .collect()
+}
@@ -0,0 +1,241 @@
+//! # Utilities
+//!
+//! A module for shared utility functions.
+
+pub mod game_libraries;
+pub mod process_control;
+#[cfg(target_os = "windows")]
+pub mod registry;
+pub mod scheduled_task;
+
+#[cfg(target_os = "windows")]
+use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+#[cfg(target_os = "windows")]
+use windows_sys::Win32::Security::{
+    GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY,
+};
+#[cfg(target_os = "windows")]
+use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+#[cfg(target_os = "windows")]
+use std::ffi::c_void;
+#[cfg(target_os = "windows")]
+use std::os::windows::ffi::OsStrExt;
+
+use anyhow::Result;
+
+/// Checks if the current process is elevated (running as administrator or root).
+pub fn is_elevated() -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        unsafe {
+            let mut token: HANDLE = std::ptr::null_mut();
+            if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == 0 {
+                return false;
+            }
+
+            let mut elevation: TOKEN_ELEVATION = std::mem::zeroed();
+            let mut return_length: u32 = 0;
+            
+            let result = GetTokenInformation(
+                token,
+                TokenElevation,
+                &mut elevation as *mut TOKEN_ELEVATION as *mut c_void,
+                std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+                &mut return_length,
+            );
+
+            CloseHandle(token);
+
+            result != 0 && elevation.TokenIsElevated != 0
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        // On Linux, check if UID is 0 (root)
+        unsafe { libc::getuid() == 0 }
+    }
+}
+
+/// Re-launches the current executable with administrator rights (UAC prompt).
+/// The caller is responsible for closing the current instance once this returns `Ok`.
+pub fn relaunch_as_admin(args: &[String]) -> Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+        use winapi::um::shellapi::ShellExecuteW;
+        use winapi::um::winuser::SW_SHOWNORMAL;
+
+        fn to_wide(value: &std::ffi::OsStr) -> Vec<u16> {
+            value.encode_wide().chain(std::iter::once(0)).collect()
+        }
+
+        let exe_path = std::env::current_exe()?;
+        let parameters = args
+            .iter()
+            .map(|arg| format!("\"{}\"", arg))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let verb = to_wide(std::ffi::OsStr::new("runas"));
+        let file = to_wide(exe_path.as_os_str());
+        let params = to_wide(std::ffi::OsStr::new(&parameters));
+
+        let result = unsafe {
+            ShellExecuteW(
+                std::ptr::null_mut(),
+                verb.as_ptr(),
+                file.as_ptr(),
+                params.as_ptr(),
+                std::ptr::null(),
+                SW_SHOWNORMAL,
+            )
+        };
+
+        // ShellExecuteW retourne une valeur > 32 en cas de succès
+        if result as isize <= 32 {
+            return Err(anyhow::anyhow!(
+                "Relance administrateur refusée ou impossible (code {})",
+                result as isize
+            ));
+        }
+        Ok(())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = args;
+        Err(anyhow::anyhow!(
+            "Relance automatique non supportée : relancez l'application avec sudo"
+        ))
+    }
+}
+
+#[cfg(target_os = "windows")]
+const RUN_KEY_PATH: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
+#[cfg(target_os = "windows")]
+const RUN_VALUE_NAME: &str = "GameBooster";
+
+/// Whether the app is registered to start at login (read from the registry, not cached,
+/// so a manual removal is detected).
+pub fn is_run_at_startup_enabled() -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        matches!(
+            registry::read_string(registry::HKEY_CURRENT_USER, RUN_KEY_PATH, RUN_VALUE_NAME),
+            Ok(Some(command)) if !command.trim().is_empty()
+        )
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        false
+    }
+}
+
+/// Register/unregister the app under `HKCU\...\Run`, started with `--minimized`.
+/// HKCU only: no administrator rights needed.
+pub fn set_run_at_startup(enabled: bool) -> Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+        if enabled {
+            let exe_path = std::env::current_exe()?;
+            let command = format!("\"{}\" --minimized", exe_path.display());
+            registry::write_string(registry::HKEY_CURRENT_USER, RUN_KEY_PATH, RUN_VALUE_NAME, &command)
+        } else {
+            registry::delete_value(registry::HKEY_CURRENT_USER, RUN_KEY_PATH, RUN_VALUE_NAME)
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = enabled;
+        Err(anyhow::anyhow!("Lancement au démarrage disponible uniquement sous Windows"))
+    }
+}
+
+/// Show `path` in the file manager: a file is selected in its folder, a folder is opened
+pub fn reveal_in_file_manager(path: &std::path::Path) -> Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        let mut command = std::process::Command::new("explorer");
+        if path.is_dir() {
+            command.arg(path);
+        } else {
+            // explorer analyse lui-même sa ligne de commande : guillemets autour du chemin seul
+            command.raw_arg(format!("/select,\"{}\"", path.display()));
+        }
+        // explorer renvoie un code non nul même quand la fenêtre s'ouvre
+        command.spawn()?;
+        Ok(())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let folder = if path.is_dir() { path } else { path.parent().unwrap_or(path) };
+        std::process::Command::new("xdg-open").arg(folder).spawn()?;
+        Ok(())
+    }
+}
+
+/// Whether the rectangle (in pixels of the virtual desktop) overlaps a connected monitor.
+/// `false` where monitors cannot be listed before the window exists.
+pub fn rect_on_monitor(x: i32, y: i32, width: i32, height: i32) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        use windows_sys::Win32::Foundation::RECT;
+        use windows_sys::Win32::Graphics::Gdi::{MonitorFromRect, MONITOR_DEFAULTTONULL};
+
+        let rect = RECT { left: x, top: y, right: x + width.max(1), bottom: y + height.max(1) };
+        !unsafe { MonitorFromRect(&rect, MONITOR_DEFAULTTONULL) }.is_null()
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (x, y, width, height);
+        false
+    }
+}
+
+/// Checks if a process name corresponds to a common Windows system process.
+/// This helps in filtering out critical processes from user-facing lists.
+pub fn is_windows_system_process(process_name: &str) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        let lower_process_name = process_name.to_lowercase();
+        let windows_processes = [
+            "svchost.exe", "system", "registry", "dwm.exe", "winlogon.exe",
+            "csrss.exe", "lsass.exe", "services.exe", "spoolsv.exe",
+            "explorer.exe", "taskhost.exe", "rundll32.exe", "dllhost.exe",
+            "msiexec.exe", "conhost.exe", "audiodg.exe", "wininit.exe",
+            "fontdrvhost.exe", "sihost.exe", "ctfmon.exe"
+        ];
+
+        windows_processes.iter().any(|&sys_proc| 
+            lower_process_name.contains(sys_proc)
+        )
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        // Sur Linux, la notion de "processus système" à ne pas toucher est différente.
+        // On pourrait filtrer par UID (root), ou des noms communs comme "systemd", "kthreadd", etc.
+        // Pour l'instant, on retourne false pour ne pas filtrer agressivement.
+        let _ = process_name; // Évite l'avertissement unused_variables
+        false
+    }
+}
+/// Empty folder of a test under the system temp dir, removed with its content when dropped
+#[cfg(test)]
+pub struct TestDir(pub std::path::PathBuf);
+
+#[cfg(test)]
+impl TestDir {
+    /// `name` has to be unique among the tests, which run in parallel
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("gamebooster_test_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).expect("dossier de test");
+        Self(path)
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
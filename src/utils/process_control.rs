@@ -0,0 +1,243 @@
+//! # Process termination
+//!
+//! `terminate_process` first asks the process to close: WM_CLOSE to its top-level windows, or
+//! Ctrl+C to its console when it has none and is alone on it (SIGTERM on Linux). A process still running after
+//! the grace period is killed. Callers check `safety` first: nothing here refuses a system
+//! process.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Result;
+
+/// Wait for the process to disappear once killed
+const FORCE_WAIT: Duration = Duration::from_secs(3);
+
+/// How `terminate_process` ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminationOutcome {
+    /// Closed within the grace period
+    GracefulExit,
+    /// Killed after the grace period, or at once when it could not be asked to close
+    ForceKilled,
+    /// The process belongs to another user or runs elevated
+    AccessDenied,
+    /// Gone before anything was sent
+    AlreadyExited,
+}
+
+impl TerminationOutcome {
+    pub fn label(self) -> &'static str {
+        match self {
+            TerminationOutcome::GracefulExit => "fermé proprement",
+            TerminationOutcome::ForceKilled => "arrêté de force",
+            TerminationOutcome::AccessDenied => "accès refusé",
+            TerminationOutcome::AlreadyExited => "déjà terminé",
+        }
+    }
+}
+
+/// Close `pid`, killing it if it is still running after `grace_ms`
+#[cfg(target_os = "windows")]
+pub fn terminate_process(pid: u32, grace_ms: u64) -> Result<TerminationOutcome> {
+    use windows_sys::Win32::Foundation::{CloseHandle, GetLastError, ERROR_ACCESS_DENIED, ERROR_INVALID_PARAMETER};
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, TerminateProcess, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_SYNCHRONIZE, PROCESS_TERMINATE,
+    };
+
+    let handle = unsafe { OpenProcess(PROCESS_TERMINATE | PROCESS_SYNCHRONIZE | PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+    if handle.is_null() {
+        return match unsafe { GetLastError() } {
+            ERROR_ACCESS_DENIED => Ok(TerminationOutcome::AccessDenied),
+            // PID inconnu : le processus s'est terminé entre-temps
+            ERROR_INVALID_PARAMETER => Ok(TerminationOutcome::AlreadyExited),
+            _ => Err(anyhow::anyhow!("OpenProcess({}) : {}", pid, std::io::Error::last_os_error())),
+        };
+    }
+    let outcome = (|| {
+        if wait_for_exit(handle, Duration::ZERO) {
+            return Ok(TerminationOutcome::AlreadyExited);
+        }
+        let asked = close_windows(pid) || send_console_ctrl_c(pid);
+        if asked && wait_for_exit(handle, Duration::from_millis(grace_ms)) {
+            return Ok(TerminationOutcome::GracefulExit);
+        }
+        tracing::info!("🔪 PID {} toujours actif après {} ms, arrêt forcé", pid, grace_ms);
+        if unsafe { TerminateProcess(handle, 1) } == 0 {
+            let error = std::io::Error::last_os_error();
+            if error.raw_os_error() == Some(ERROR_ACCESS_DENIED as i32) {
+                // Refusé aussi quand le processus est déjà en train de se terminer
+                return Ok(if wait_for_exit(handle, FORCE_WAIT) {
+                    TerminationOutcome::ForceKilled
+                } else {
+                    TerminationOutcome::AccessDenied
+                });
+            }
+            return Err(anyhow::anyhow!("TerminateProcess({}) : {}", pid, error));
+        }
+        if !wait_for_exit(handle, FORCE_WAIT) {
+            return Err(anyhow::anyhow!("PID {} toujours actif {} s après l'arrêt forcé", pid, FORCE_WAIT.as_secs()));
+        }
+        Ok(TerminationOutcome::ForceKilled)
+    })();
+    unsafe { CloseHandle(handle) };
+    outcome
+}
+
+#[cfg(target_os = "windows")]
+fn wait_for_exit(handle: windows_sys::Win32::Foundation::HANDLE, timeout: Duration) -> bool {
+    use windows_sys::Win32::Foundation::WAIT_OBJECT_0;
+    use windows_sys::Win32::System::Threading::WaitForSingleObject;
+
+    unsafe { WaitForSingleObject(handle, timeout.as_millis().min(u32::MAX as u128 - 1) as u32) == WAIT_OBJECT_0 }
+}
+
+/// Post WM_CLOSE to the visible top-level windows of `pid`, `false` when it has none
+#[cfg(target_os = "windows")]
+fn close_windows(pid: u32) -> bool {
+    use windows_sys::Win32::Foundation::{BOOL, HWND, LPARAM};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetWindowThreadProcessId, IsWindowVisible, PostMessageW, WM_CLOSE,
+    };
+
+    struct Search {
+        pid: u32,
+        windows: Vec<HWND>,
+    }
+
+    unsafe extern "system" fn collect(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let search = &mut *(lparam as *mut Search);
+        let mut owner = 0u32;
+        GetWindowThreadProcessId(hwnd, &mut owner);
+        if owner == search.pid && IsWindowVisible(hwnd) != 0 {
+            search.windows.push(hwnd);
+        }
+        1
+    }
+
+    let mut search = Search { pid, windows: Vec::new() };
+    unsafe {
+        EnumWindows(Some(collect), &mut search as *mut Search as LPARAM);
+        // Une fenêtre d'un processus élevé refuse le message (UIPI) : compter celles qui l'acceptent
+        search.windows.iter().filter(|hwnd| PostMessageW(**hwnd, WM_CLOSE, 0, 0) != 0).count() > 0
+    }
+}
+
+/// Ctrl+C to the console of `pid`, the close request of a program without window. GameBooster
+/// attaches to that console for the time of the call and ignores the event itself. The event
+/// reaches every process of the console: nothing is sent when `pid` shares it (a terminal, a
+/// parent script), the process is then killed after the grace period.
+#[cfg(target_os = "windows")]
+fn send_console_ctrl_c(pid: u32) -> bool {
+    use windows_sys::Win32::System::Console::{
+        AttachConsole, FreeConsole, GenerateConsoleCtrlEvent, GetConsoleProcessList, SetConsoleCtrlHandler, CTRL_C_EVENT,
+    };
+
+    unsafe {
+        // Échoue si le processus n'a pas de console ou si GameBooster en a déjà une
+        if AttachConsole(pid) == 0 {
+            return false;
+        }
+        let mut attached = [0u32; 8];
+        let count = GetConsoleProcessList(attached.as_mut_ptr(), attached.len() as u32) as usize;
+        let own_pid = std::process::id();
+        let alone = count > 0 && count <= attached.len() && attached[..count].iter().all(|attached| *attached == pid || *attached == own_pid);
+        if !alone {
+            tracing::info!("⌨️ Console du PID {} partagée par {} processus : pas de Ctrl+C", pid, count.saturating_sub(1));
+            FreeConsole();
+            return false;
+        }
+        SetConsoleCtrlHandler(None, 1);
+        let sent = GenerateConsoleCtrlEvent(CTRL_C_EVENT, 0) != 0;
+        FreeConsole();
+        // L'événement est livré de façon asynchrone : ne pas le recevoir en rétablissant le handler
+        std::thread::sleep(Duration::from_millis(100));
+        SetConsoleCtrlHandler(None, 0);
+        sent
+    }
+}
+
+/// Close `pid` with SIGTERM, then SIGKILL if it is still running after `grace_ms`
+#[cfg(not(target_os = "windows"))]
+pub fn terminate_process(pid: u32, grace_ms: u64) -> Result<TerminationOutcome> {
+    let send = |signal: libc::c_int| -> std::io::Result<()> {
+        if unsafe { libc::kill(pid as libc::pid_t, signal) } == 0 {
+            Ok(())
+        } else {
+            Err(std::io::Error::last_os_error())
+        }
+    };
+
+    if !is_running(pid) {
+        return Ok(TerminationOutcome::AlreadyExited);
+    }
+    match send(libc::SIGTERM) {
+        Ok(()) => {}
+        Err(e) if e.raw_os_error() == Some(libc::ESRCH) => return Ok(TerminationOutcome::AlreadyExited),
+        Err(e) if e.raw_os_error() == Some(libc::EPERM) => return Ok(TerminationOutcome::AccessDenied),
+        Err(e) => return Err(anyhow::anyhow!("SIGTERM vers {} : {}", pid, e)),
+    }
+    if wait_until_gone(pid, Duration::from_millis(grace_ms)) {
+        return Ok(TerminationOutcome::GracefulExit);
+    }
+
+    tracing::info!("🔪 PID {} toujours actif après {} ms, arrêt forcé", pid, grace_ms);
+    match send(libc::SIGKILL) {
+        Ok(()) => {}
+        Err(e) if e.raw_os_error() == Some(libc::ESRCH) => return Ok(TerminationOutcome::GracefulExit),
+        Err(e) => return Err(anyhow::anyhow!("SIGKILL vers {} : {}", pid, e)),
+    }
+    if !wait_until_gone(pid, FORCE_WAIT) {
+        return Err(anyhow::anyhow!("PID {} toujours actif {} s après l'arrêt forcé", pid, FORCE_WAIT.as_secs()));
+    }
+    Ok(TerminationOutcome::ForceKilled)
+}
+
+/// Whether `pid` exists and is not a zombie waiting for its parent
+#[cfg(not(target_os = "windows"))]
+fn is_running(pid: u32) -> bool {
+    match std::fs::read_to_string(format!("/proc/{}/stat", pid)) {
+        // Le nom peut contenir des espaces et des parenthèses : l'état suit la dernière ')'
+        Ok(stat) => stat.rsplit_once(')').and_then(|(_, rest)| rest.trim_start().chars().next()) != Some('Z'),
+        Err(_) => false,
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn wait_until_gone(pid: u32, timeout: Duration) -> bool {
+    let start = std::time::Instant::now();
+    loop {
+        if !is_running(pid) {
+            return true;
+        }
+        if start.elapsed() >= timeout {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Executable of `pid`, resolved before terminating it so that it can be restarted
+pub fn process_image_path(pid: u32) -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        crate::network::query_full_image_path(pid)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        std::fs::read_link(format!("/proc/{}/exe", pid)).ok()
+    }
+}
+
+/// Start `exe_path` again, from its own folder and without the arguments of the killed
+/// instance (they cannot be read back reliably). Returns the new PID.
+pub fn relaunch(exe_path: &Path) -> Result<u32> {
+    let mut command = std::process::Command::new(exe_path);
+    if let Some(folder) = exe_path.parent() {
+        command.current_dir(folder);
+    }
+    let child = command
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Relance de {} impossible : {}", exe_path.display(), e))?;
+    Ok(child.id())
+}
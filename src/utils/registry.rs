@@ -0,0 +1,323 @@
+//! # Registry helpers
+//!
+//! Thin wrappers around the raw Win32 registry calls (open/create/delete key, read, write,
+//! enumerate and delete DWORD, string and binary values) shared by the Defender code, the
+//! settings, the startup manager and the game libraries.
+//! Keys are always opened in the 64-bit view.
+
+use anyhow::{anyhow, Result};
+use windows_sys::Win32::Foundation::{ERROR_FILE_NOT_FOUND, ERROR_NO_MORE_ITEMS, ERROR_SUCCESS};
+use windows_sys::Win32::System::Registry::{
+    RegCloseKey, RegCreateKeyExW, RegDeleteKeyExW, RegDeleteValueW, RegEnumKeyExW, RegEnumValueW, RegOpenKeyExW, RegQueryValueExW, RegSetValueExW, HKEY,
+    KEY_READ, KEY_SET_VALUE, KEY_WOW64_64KEY, REG_BINARY, REG_DWORD, REG_EXPAND_SZ, REG_OPTION_NON_VOLATILE, REG_SZ,
+};
+
+pub use windows_sys::Win32::System::Registry::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+
+/// A failed registry call and its Win32 error code (5: access denied, the usual answer of
+/// Tamper Protection to a write under the Defender keys)
+#[derive(Debug, thiserror::Error)]
+#[error("{message}. Error: {code}")]
+pub struct RegistryError {
+    message: String,
+    pub code: u32,
+}
+
+impl RegistryError {
+    fn new(message: String, code: u32) -> Self {
+        Self { message, code }
+    }
+}
+
+/// Win32 error code of the registry call behind `error`, `None` for another failure
+pub fn error_code(error: &anyhow::Error) -> Option<u32> {
+    error.chain().find_map(|cause| cause.downcast_ref::<RegistryError>()).map(|registry| registry.code)
+}
+
+fn to_wide(value: &str) -> Vec<u16> {
+    value.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// Open key that is closed when dropped
+struct RegKey(HKEY);
+
+impl Drop for RegKey {
+    fn drop(&mut self) {
+        unsafe {
+            RegCloseKey(self.0);
+        }
+    }
+}
+
+/// Open an existing key. `Ok(None)` when it does not exist.
+fn open_key(root: HKEY, path: &str, access: u32) -> Result<Option<RegKey>> {
+    let path_w = to_wide(path);
+    let mut key: HKEY = std::ptr::null_mut();
+    let result = unsafe { RegOpenKeyExW(root, path_w.as_ptr(), 0, access | KEY_WOW64_64KEY, &mut key) };
+
+    if result == ERROR_SUCCESS {
+        Ok(Some(RegKey(key)))
+    } else if result == ERROR_FILE_NOT_FOUND {
+        Ok(None)
+    } else {
+        Err(RegistryError::new(format!("Failed to open registry key '{}'", path), result).into())
+    }
+}
+
+/// Open a key for writing, creating it when missing
+fn create_key(root: HKEY, path: &str) -> Result<RegKey> {
+    let path_w = to_wide(path);
+    let mut key: HKEY = std::ptr::null_mut();
+    let result = unsafe {
+        RegCreateKeyExW(
+            root,
+            path_w.as_ptr(),
+            0,
+            std::ptr::null(),
+            REG_OPTION_NON_VOLATILE,
+            KEY_SET_VALUE | KEY_WOW64_64KEY,
+            std::ptr::null(),
+            &mut key,
+            std::ptr::null_mut(),
+        )
+    };
+
+    if result == ERROR_SUCCESS {
+        Ok(RegKey(key))
+    } else {
+        Err(RegistryError::new(format!("Failed to create/open registry key '{}'", path), result).into())
+    }
+}
+
+fn set_value(key: &RegKey, name: &str, value_type: u32, data: &[u8]) -> Result<()> {
+    let name_w = to_wide(name);
+    let result = unsafe { RegSetValueExW(key.0, name_w.as_ptr(), 0, value_type, data.as_ptr(), data.len() as u32) };
+
+    if result == ERROR_SUCCESS {
+        Ok(())
+    } else {
+        Err(RegistryError::new(format!("Failed to set registry value '{}'", name), result).into())
+    }
+}
+
+/// Whether the key exists
+pub fn key_exists(root: HKEY, path: &str) -> Result<bool> {
+    Ok(open_key(root, path, KEY_READ)?.is_some())
+}
+
+/// Read a DWORD value. `Ok(None)` when the key or the value does not exist.
+pub fn read_dword(root: HKEY, path: &str, name: &str) -> Result<Option<u32>> {
+    let Some(key) = open_key(root, path, KEY_READ)? else {
+        return Ok(None);
+    };
+
+    let name_w = to_wide(name);
+    let mut value: u32 = 0;
+    let mut value_size = std::mem::size_of::<u32>() as u32;
+    let mut value_type: u32 = 0;
+    let result = unsafe {
+        RegQueryValueExW(
+            key.0,
+            name_w.as_ptr(),
+            std::ptr::null(),
+            &mut value_type,
+            &mut value as *mut u32 as *mut u8,
+            &mut value_size,
+        )
+    };
+
+    if result == ERROR_SUCCESS {
+        Ok(Some(value))
+    } else if result == ERROR_FILE_NOT_FOUND {
+        Ok(None)
+    } else {
+        Err(RegistryError::new(format!("Failed to read registry value '{}'", name), result).into())
+    }
+}
+
+/// Write a DWORD value, creating the key when missing
+pub fn write_dword(root: HKEY, path: &str, name: &str, value: u32) -> Result<()> {
+    let key = create_key(root, path)?;
+    set_value(&key, name, REG_DWORD, &value.to_ne_bytes())
+}
+
+/// Read a REG_SZ / REG_EXPAND_SZ value. `Ok(None)` when the key or the value does not exist.
+pub fn read_string(root: HKEY, path: &str, name: &str) -> Result<Option<String>> {
+    let Some(key) = open_key(root, path, KEY_READ)? else {
+        return Ok(None);
+    };
+
+    let name_w = to_wide(name);
+    let mut buffer = vec![0u16; 2048];
+    let mut size = (buffer.len() * 2) as u32;
+    let mut value_type: u32 = 0;
+    let result = unsafe {
+        RegQueryValueExW(
+            key.0,
+            name_w.as_ptr(),
+            std::ptr::null(),
+            &mut value_type,
+            buffer.as_mut_ptr() as *mut u8,
+            &mut size,
+        )
+    };
+
+    if result == ERROR_FILE_NOT_FOUND {
+        return Ok(None);
+    }
+    if result != ERROR_SUCCESS {
+        return Err(RegistryError::new(format!("Failed to read registry value '{}'", name), result).into());
+    }
+    if value_type != REG_SZ && value_type != REG_EXPAND_SZ {
+        return Err(anyhow!("Registry value '{}' is not a string (type {})", name, value_type));
+    }
+
+    let len = (size as usize / 2).min(buffer.len());
+    let text = String::from_utf16_lossy(&buffer[..len]);
+    Ok(Some(text.trim_end_matches('\0').to_string()))
+}
+
+/// Write a REG_SZ value, creating the key when missing
+pub fn write_string(root: HKEY, path: &str, name: &str, value: &str) -> Result<()> {
+    let key = create_key(root, path)?;
+    let data: Vec<u8> = to_wide(value).iter().flat_map(|c| c.to_ne_bytes()).collect();
+    set_value(&key, name, REG_SZ, &data)
+}
+
+/// Read a REG_BINARY value. `Ok(None)` when the key or the value does not exist.
+pub fn read_binary(root: HKEY, path: &str, name: &str) -> Result<Option<Vec<u8>>> {
+    let Some(key) = open_key(root, path, KEY_READ)? else {
+        return Ok(None);
+    };
+
+    let name_w = to_wide(name);
+    let mut buffer = vec![0u8; 1024];
+    let mut size = buffer.len() as u32;
+    let mut value_type: u32 = 0;
+    let result = unsafe {
+        RegQueryValueExW(
+            key.0,
+            name_w.as_ptr(),
+            std::ptr::null(),
+            &mut value_type,
+            buffer.as_mut_ptr(),
+            &mut size,
+        )
+    };
+
+    if result == ERROR_FILE_NOT_FOUND {
+        return Ok(None);
+    }
+    if result != ERROR_SUCCESS {
+        return Err(RegistryError::new(format!("Failed to read registry value '{}'", name), result).into());
+    }
+    if value_type != REG_BINARY {
+        return Err(anyhow!("Registry value '{}' is not binary (type {})", name, value_type));
+    }
+
+    buffer.truncate(size as usize);
+    Ok(Some(buffer))
+}
+
+/// Write a REG_BINARY value, creating the key when missing
+pub fn write_binary(root: HKEY, path: &str, name: &str, data: &[u8]) -> Result<()> {
+    let key = create_key(root, path)?;
+    set_value(&key, name, REG_BINARY, data)
+}
+
+/// Delete a value. Succeeds when the key or the value is already absent.
+pub fn delete_value(root: HKEY, path: &str, name: &str) -> Result<()> {
+    let Some(key) = open_key(root, path, KEY_SET_VALUE)? else {
+        return Ok(());
+    };
+
+    let name_w = to_wide(name);
+    let result = unsafe { RegDeleteValueW(key.0, name_w.as_ptr()) };
+
+    if result == ERROR_SUCCESS || result == ERROR_FILE_NOT_FOUND {
+        Ok(())
+    } else {
+        Err(RegistryError::new(format!("Failed to delete registry value '{}'", name), result).into())
+    }
+}
+
+/// Delete a key without subkeys. Succeeds when it is already absent.
+pub fn delete_key(root: HKEY, path: &str) -> Result<()> {
+    let path_w = to_wide(path);
+    let result = unsafe { RegDeleteKeyExW(root, path_w.as_ptr(), KEY_WOW64_64KEY, 0) };
+
+    if result == ERROR_SUCCESS || result == ERROR_FILE_NOT_FOUND {
+        Ok(())
+    } else {
+        Err(RegistryError::new(format!("Failed to delete registry key '{}'", path), result).into())
+    }
+}
+
+/// Names of the values of a key. Empty when the key does not exist.
+pub fn list_value_names(root: HKEY, path: &str) -> Result<Vec<String>> {
+    let Some(key) = open_key(root, path, KEY_READ)? else {
+        return Ok(Vec::new());
+    };
+
+    let mut names = Vec::new();
+    let mut buffer = vec![0u16; 32768];
+    for index in 0.. {
+        let mut len = buffer.len() as u32;
+        let result = unsafe {
+            RegEnumValueW(
+                key.0,
+                index,
+                buffer.as_mut_ptr(),
+                &mut len,
+                std::ptr::null(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+
+        if result == ERROR_NO_MORE_ITEMS {
+            break;
+        }
+        if result != ERROR_SUCCESS {
+            return Err(RegistryError::new(format!("Failed to enumerate registry key '{}'", path), result).into());
+        }
+        names.push(String::from_utf16_lossy(&buffer[..len as usize]));
+    }
+    Ok(names)
+}
+
+/// Names of the subkeys of a key. Empty when the key does not exist.
+pub fn list_subkey_names(root: HKEY, path: &str) -> Result<Vec<String>> {
+    let Some(key) = open_key(root, path, KEY_READ)? else {
+        return Ok(Vec::new());
+    };
+
+    let mut names = Vec::new();
+    // Un nom de clé fait au plus 255 caractères
+    let mut buffer = vec![0u16; 256];
+    for index in 0.. {
+        let mut len = buffer.len() as u32;
+        let result = unsafe {
+            RegEnumKeyExW(
+                key.0,
+                index,
+                buffer.as_mut_ptr(),
+                &mut len,
+                std::ptr::null(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+
+        if result == ERROR_NO_MORE_ITEMS {
+            break;
+        }
+        if result != ERROR_SUCCESS {
+            return Err(RegistryError::new(format!("Failed to enumerate registry key '{}'", path), result).into());
+        }
+        names.push(String::from_utf16_lossy(&buffer[..len as usize]));
+    }
+    Ok(names)
+}
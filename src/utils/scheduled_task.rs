@@ -0,0 +1,150 @@
+//! # Windows scheduled tasks
+//!
+//! One-shot tasks registered with `schtasks`, used as a safety net for actions that must
+//! happen even if the app is closed (e.g. re-enabling Defender after a timed pause).
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+
+/// Register (or replace) a task running this executable with `args` once at `at`.
+/// Runs with the highest available privileges, and as soon as possible if the PC was off.
+pub fn create_one_shot_task(name: &str, at: DateTime<Local>, args: &str) -> Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+        let exe_path = std::env::current_exe()?;
+        let xml = task_xml(&exe_path.to_string_lossy(), args, at);
+        create_task_from_xml(name, &xml)?;
+        tracing::info!("⏰ Tâche planifiée {} créée pour {}", name, at.format("%d/%m %H:%M"));
+        Ok(())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (name, at, args);
+        Err(anyhow::anyhow!("Tâches planifiées disponibles uniquement sous Windows"))
+    }
+}
+
+/// Register (or replace) the task `name` from a Task Scheduler XML definition
+#[cfg(target_os = "windows")]
+pub(crate) fn create_task_from_xml(name: &str, xml: &str) -> Result<()> {
+    // schtasks attend un fichier XML en UTF-16 LE avec BOM
+    let file_name = name.replace('\\', "_");
+    let xml_path = std::env::temp_dir().join(format!("{}.xml", file_name));
+    let mut bytes = vec![0xFF, 0xFE];
+    bytes.extend(xml.encode_utf16().flat_map(|c| c.to_le_bytes()));
+    std::fs::write(&xml_path, bytes)?;
+
+    let result = run_schtasks(&["/Create", "/TN", name, "/XML", &xml_path.to_string_lossy(), "/F"]);
+    let _ = std::fs::remove_file(&xml_path);
+    result
+}
+
+pub fn task_exists(name: &str) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        run_schtasks(&["/Query", "/TN", name]).is_ok()
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = name;
+        false
+    }
+}
+
+/// Enable or disable an existing task without touching its definition
+pub fn set_task_enabled(name: &str, enabled: bool) -> Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+        run_schtasks(&["/Change", "/TN", name, if enabled { "/ENABLE" } else { "/DISABLE" }])
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (name, enabled);
+        Err(anyhow::anyhow!("Tâches planifiées disponibles uniquement sous Windows"))
+    }
+}
+
+/// Delete a task. Succeeds when it does not exist.
+pub fn delete_task(name: &str) -> Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+        if !task_exists(name) {
+            return Ok(());
+        }
+        run_schtasks(&["/Delete", "/TN", name, "/F"])?;
+        tracing::info!("🗑️ Tâche planifiée {} supprimée", name);
+        Ok(())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = name;
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn run_schtasks(args: &[&str]) -> Result<()> {
+    use std::os::windows::process::CommandExt;
+
+    let output = std::process::Command::new("schtasks")
+        .args(args)
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .output()?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "schtasks {} a échoué: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Task definition with a time trigger: avoids the locale-dependent `/SD` date format
+#[cfg(target_os = "windows")]
+fn task_xml(command: &str, args: &str, at: DateTime<Local>) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-16"?>
+<Task version="1.2" xmlns="http://schemas.microsoft.com/windows/2004/02/mit/task">
+  <Triggers>
+    <TimeTrigger>
+      <StartBoundary>{start}</StartBoundary>
+      <Enabled>true</Enabled>
+    </TimeTrigger>
+  </Triggers>
+  <Principals>
+    <Principal id="Author">
+      <LogonType>InteractiveToken</LogonType>
+      <RunLevel>HighestAvailable</RunLevel>
+    </Principal>
+  </Principals>
+  <Settings>
+    <StartWhenAvailable>true</StartWhenAvailable>
+    <DisallowStartIfOnBatteries>false</DisallowStartIfOnBatteries>
+    <StopIfGoingOnBatteries>false</StopIfGoingOnBatteries>
+    <ExecutionTimeLimit>PT10M</ExecutionTimeLimit>
+  </Settings>
+  <Actions Context="Author">
+    <Exec>
+      <Command>{command}</Command>
+      <Arguments>{args}</Arguments>
+    </Exec>
+  </Actions>
+</Task>
+"#,
+        start = at.format("%Y-%m-%dT%H:%M:%S"),
+        command = xml_escape(command),
+        args = xml_escape(args),
+    )
+}